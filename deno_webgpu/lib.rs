@@ -366,6 +366,9 @@ fn deserialize_features(features: &wgpu_types::Features) -> Vec<&'static str> {
     if features.contains(wgpu_types::Features::SHADER_UNUSED_VERTEX_OUTPUT) {
         return_features.push("shader-unused-vertex-output");
     }
+    if features.contains(wgpu_types::Features::SUBGROUP) {
+        return_features.push("subgroup");
+    }
 
     return_features
 }
@@ -410,6 +413,8 @@ pub async fn op_webgpu_request_adapter(
                 flags: wgpu_types::InstanceFlags::from_build_config(),
                 dx12_shader_compiler: wgpu_types::Dx12Compiler::Fxc,
                 gles_minor_version: wgpu_types::Gles3MinorVersion::default(),
+                empty_backend_options: Default::default(),
+                cpu_backend_options: Default::default(),
             },
         )));
         state.borrow::<Instance>()
@@ -636,6 +641,10 @@ impl From<GpuRequiredFeatures> for wgpu_types::Features {
             wgpu_types::Features::SHADER_UNUSED_VERTEX_OUTPUT,
             required_features.0.contains("shader-unused-vertex-output"),
         );
+        features.set(
+            wgpu_types::Features::SUBGROUP,
+            required_features.0.contains("subgroup"),
+        );
 
         features
     }
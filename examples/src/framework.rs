@@ -276,6 +276,8 @@ impl ExampleContext {
             flags: wgpu::InstanceFlags::from_build_config().with_env(),
             dx12_shader_compiler,
             gles_minor_version,
+            empty_backend_options: Default::default(),
+            cpu_backend_options: Default::default(),
         });
         surface.pre_adapter(&instance, window);
         let adapter = wgpu::util::initialize_adapter_from_env_or_default(&instance, surface.get())
@@ -184,6 +184,8 @@ async fn run() {
         flags: wgpu::InstanceFlags::from_build_config().with_env(),
         dx12_shader_compiler: wgpu::Dx12Compiler::default(),
         gles_minor_version: wgpu::Gles3MinorVersion::default(),
+        empty_backend_options: Default::default(),
+        cpu_backend_options: Default::default(),
     });
 
     // `request_adapter` instantiates the general connection to the GPU
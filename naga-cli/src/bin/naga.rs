@@ -75,7 +75,8 @@ struct Args {
     #[argh(option)]
     stdin_file_path: Option<String>,
 
-    /// generate debug symbols, only works for spv-out for now
+    /// generate debug symbols for spv-out, and `#line` directives for
+    /// glsl-out, hlsl-out and msl-out
     #[argh(switch, short = 'g')]
     generate_debug_symbols: bool,
 
@@ -93,6 +94,28 @@ struct Args {
     #[argh(option)]
     before_compaction: Option<String>,
 
+    /// optimize the module's IR and revalidate.
+    ///
+    /// This folds constant conditions, removes dead code and inlines small
+    /// functions. Optimization happens after compaction, if that was also
+    /// requested. Unlike compaction, this fails if the module doesn't
+    /// validate.
+    #[argh(switch)]
+    optimize: bool,
+
+    /// make WGSL output as small as possible, for shipping to browsers.
+    ///
+    /// Everything but entry points and overridable constants is renamed.
+    #[argh(switch)]
+    minify: bool,
+
+    /// write the names made up by `--minify` to the given file.
+    ///
+    /// Each line holds a made up name and the name it stands for. This
+    /// implies `--minify`.
+    #[argh(option)]
+    symbol_map: Option<String>,
+
     /// show version
     #[argh(switch)]
     version: bool,
@@ -180,6 +203,9 @@ struct Parameters<'a> {
     msl: naga::back::msl::Options,
     glsl: naga::back::glsl::Options,
     hlsl: naga::back::hlsl::Options,
+    wgsl_minify: bool,
+    wgsl_symbol_map: Option<String>,
+    source_files: Option<naga::back::SourceFiles>,
 }
 
 trait PrettyResult {
@@ -392,6 +418,8 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         params.hlsl.shader_model = model.0;
     }
     params.keep_coordinate_space = args.keep_coordinate_space;
+    params.wgsl_minify = args.minify || args.symbol_map.is_some();
+    params.wgsl_symbol_map = args.symbol_map;
 
     params.dot.cfg_only = args.dot_cfg_only;
 
@@ -403,6 +431,8 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
 
     let provider = FileProvider::new(); 
 
+    // The source files that the spans of SPIR-V and GLSL input refer to.
+    let mut source_table = None;
     let (mut module, input_text) = match Path::new(&input_path)
         .extension()
         .ok_or(CliError("Input filename has no extension"))?
@@ -410,7 +440,12 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         .ok_or(CliError("Input filename not valid unicode"))?
     {
         "bin" => (bincode::deserialize(&input)?, None),
-        "spv" => naga::front::spv::parse_u8_slice(&input, &params.spv_in).map(|m| (m, None))?,
+        "spv" => {
+            let (module, sources) =
+                naga::front::spv::parse_u8_slice_with_sources(&input, &params.spv_in)?;
+            source_table = Some(sources);
+            (module, None)
+        }
         // "wgsl" => {
         //     let input = String::from_utf8(input)?;
         //     let result = naga::front::wgsl::parse_str(&input);
@@ -439,9 +474,9 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
             let input = String::from_utf8(input)?;
             let mut parser = naga::front::glsl::Frontend::default();
 
-            (
+            let result = (
                 parser
-                    .parse(
+                    .parse_file(
                         &naga::front::glsl::Options {
                             stage: match ext {
                                 "vert" => naga::ShaderStage::Vertex,
@@ -465,6 +500,8 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
                             },
                             defines: Default::default(),
                         },
+                        0,
+                        &input_path.display().to_string(),
                         &input,
                     )
                     .unwrap_or_else(|errors| {
@@ -473,7 +510,9 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
                         std::process::exit(1);
                     }),
                 Some(input),
-            )
+            );
+            source_table = Some(parser.sources().clone());
+            result
         }
         _ => return Err(CliError("Unknown input file extension").into()),
     };
@@ -488,7 +527,25 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
             params.spv_out.debug_info = Some(naga::back::spv::DebugInfo {
                 source_code: input_text,
                 file_name: input_path,
-            })
+            });
+
+            let mut source_files = naga::back::SourceFiles::new();
+            if provider.get(1).is_some() {
+                // WGSL input may import other files, which the provider
+                // numbers from 1.
+                let mut id = 1;
+                while let Some(file) = provider.get(id) {
+                    source_files.add(Some(id), file.name(), file.source());
+                    id += 1;
+                }
+            } else if let Some(ref sources) = source_table {
+                for (id, file) in sources.iter() {
+                    source_files.add(Some(id), file.name(), file.source());
+                }
+            } else {
+                source_files.add(None, input_path.display().to_string(), input_text);
+            }
+            params.source_files = Some(source_files);
         } else {
             eprintln!(
                 "warning: `--generate-debug-symbols` was passed, \
@@ -519,7 +576,9 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         Ok(info) => Some(info),
         Err(error) => {
             // Validation failure is not fatal. Just report the error.
-            if let Some(input) = &input_text {
+            if let Some(sources) = &source_table {
+                error.emit_to_stderr_with_provider(sources);
+            } else if let Some(input) = &input_text {
                 let filename = input_path.file_name().and_then(std::ffi::OsStr::to_str);
                 emit_annotated_error(&error, filename.unwrap_or("input"), input);
             }
@@ -547,7 +606,9 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
                 Err(error) => {
                     // Validation failure is not fatal. Just report the error.
                     eprintln!("Error validating compacted module:");
-                    if let Some(input) = &input_text {
+                    if let Some(sources) = &source_table {
+                        error.emit_to_stderr_with_provider(sources);
+                    } else if let Some(input) = &input_text {
                         let filename = input_path.file_name().and_then(std::ffi::OsStr::to_str);
                         emit_annotated_error(&error, filename.unwrap_or("input"), input);
                     }
@@ -563,6 +624,37 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         info
     };
 
+    // Optimize the module, if requested.
+    let info = if args.optimize {
+        // Optimization may panic on invalid IR, and the point of optimizing is
+        // to write the result, so a validation failure is fatal here.
+        if info.is_none() {
+            return Err(CliError("Cannot optimize a module that failed validation").into());
+        }
+
+        naga::opt::optimize(&mut module, &naga::opt::Options::default());
+
+        // Re-validate the IR after optimization.
+        match naga::valid::Validator::new(params.validation_flags, validation_caps)
+            .validate(&module)
+        {
+            Ok(info) => Some(info),
+            Err(error) => {
+                eprintln!("Error validating optimized module:");
+                if let Some(sources) = &source_table {
+                    error.emit_to_stderr_with_provider(sources);
+                } else if let Some(input) = &input_text {
+                    let filename = input_path.file_name().and_then(std::ffi::OsStr::to_str);
+                    emit_annotated_error(&error, filename.unwrap_or("input"), input);
+                }
+                print_err(&error);
+                return Err(CliError("Optimization produced an invalid module").into());
+            }
+        }
+    } else {
+        info
+    };
+
     // If no output was requested, then report validation results and stop here.
     //
     // If the user asked for output, don't stop: some output formats (".txt",
@@ -616,17 +708,22 @@ fn write_output(
             options.bounds_check_policies = params.bounds_check_policies;
 
             let pipeline_options = msl::PipelineOptions::default();
-            let (msl, _) = msl::write_string(
-                module,
-                info.as_ref().ok_or(CliError(
-                    "Generating metal output requires validation to \
-                     succeed, and it failed in a previous step",
-                ))?,
-                &options,
-                &pipeline_options,
-            )
-            .unwrap_pretty();
-            fs::write(output_path, msl)?;
+            let mut writer = msl::Writer::new(String::new());
+            if let Some(ref source_files) = params.source_files {
+                writer.set_source_files(source_files.clone());
+            }
+            writer
+                .write(
+                    module,
+                    info.as_ref().ok_or(CliError(
+                        "Generating metal output requires validation to \
+                         succeed, and it failed in a previous step",
+                    ))?,
+                    &options,
+                    &pipeline_options,
+                )
+                .unwrap_pretty();
+            fs::write(output_path, writer.finish())?;
         }
         "spv" => {
             use naga::back::spv;
@@ -697,6 +794,9 @@ fn write_output(
                 params.bounds_check_policies,
             )
             .unwrap_pretty();
+            if let Some(ref source_files) = params.source_files {
+                writer.set_source_files(source_files.clone());
+            }
             writer.write()?;
             fs::write(output_path, buffer)?;
         }
@@ -710,6 +810,9 @@ fn write_output(
             use naga::back::hlsl;
             let mut buffer = String::new();
             let mut writer = hlsl::Writer::new(&mut buffer, &params.hlsl);
+            if let Some(ref source_files) = params.source_files {
+                writer.set_source_files(source_files.clone());
+            }
             writer
                 .write(
                     module,
@@ -724,16 +827,56 @@ fn write_output(
         "wgsl" => {
             use naga::back::wgsl;
 
-            let wgsl = wgsl::write_string(
+            let mut writer = wgsl::Writer::new(
+                String::new(),
+                if params.wgsl_minify {
+                    wgsl::WriterFlags::MINIFY
+                } else {
+                    wgsl::WriterFlags::empty()
+                },
+            );
+            writer
+                .write(
+                    module,
+                    info.as_ref().ok_or(CliError(
+                        "Generating wgsl output requires validation to \
+                         succeed, and it failed in a previous step",
+                    ))?,
+                )
+                .unwrap_pretty();
+            if let Some(ref symbol_map) = params.wgsl_symbol_map {
+                let symbols: String = writer
+                    .symbol_map()
+                    .iter()
+                    .map(|(name, original)| format!("{name} {original}\n"))
+                    .collect();
+                fs::write(symbol_map, symbols)?;
+            }
+            fs::write(output_path, writer.finish())?;
+        }
+        "rs" => {
+            use naga::back::rust;
+
+            let output = rust::write_string(
                 module,
                 info.as_ref().ok_or(CliError(
-                    "Generating wgsl output requires validation to \
+                    "Generating Rust output requires validation to \
                      succeed, and it failed in a previous step",
                 ))?,
-                wgsl::WriterFlags::empty(),
             )
             .unwrap_pretty();
-            fs::write(output_path, wgsl)?;
+            fs::write(output_path, output)?;
+        }
+        "json" if output_path.ends_with(".reflect.json") => {
+            let reflection = naga::reflect::reflect(
+                module,
+                info.as_ref().ok_or(CliError(
+                    "Generating reflection output requires validation to \
+                     succeed, and it failed in a previous step",
+                ))?,
+            )?;
+            let file = fs::File::create(output_path)?;
+            serde_json::to_writer_pretty(file, &reflection)?;
         }
         other => {
             println!("Unknown output extension: {other}");
@@ -279,6 +279,58 @@ impl StatementGraph {
                         crate::RayQueryFunction::Terminate => "RayQueryTerminate",
                     }
                 }
+                S::SubgroupElect { result } => {
+                    self.emits.push((id, result));
+                    "SubgroupElect"
+                }
+                S::SubgroupBallot { result, predicate } => {
+                    if let Some(predicate) = predicate {
+                        self.dependencies.push((id, predicate, "predicate"));
+                    }
+                    self.emits.push((id, result));
+                    "SubgroupBallot"
+                }
+                S::SubgroupCollectiveOperation {
+                    op: _,
+                    collective_op: _,
+                    argument,
+                    result,
+                } => {
+                    self.dependencies.push((id, argument, "arg"));
+                    self.emits.push((id, result));
+                    "SubgroupCollectiveOperation"
+                }
+                S::SubgroupGather {
+                    mode,
+                    argument,
+                    result,
+                } => {
+                    self.dependencies.push((id, argument, "arg"));
+                    self.emits.push((id, result));
+                    match mode {
+                        crate::GatherMode::BroadcastFirst => "SubgroupBroadcastFirst",
+                        crate::GatherMode::Broadcast(index) => {
+                            self.dependencies.push((id, index, "index"));
+                            "SubgroupBroadcast"
+                        }
+                        crate::GatherMode::Shuffle(index) => {
+                            self.dependencies.push((id, index, "index"));
+                            "SubgroupShuffle"
+                        }
+                        crate::GatherMode::ShuffleDown(index) => {
+                            self.dependencies.push((id, index, "index"));
+                            "SubgroupShuffleDown"
+                        }
+                        crate::GatherMode::ShuffleUp(index) => {
+                            self.dependencies.push((id, index, "index"));
+                            "SubgroupShuffleUp"
+                        }
+                        crate::GatherMode::ShuffleXor(index) => {
+                            self.dependencies.push((id, index, "index"));
+                            "SubgroupShuffleXor"
+                        }
+                    }
+                }
             };
             // Set the last node to the merge node
             last_node = merge_id;
@@ -581,6 +633,8 @@ fn write_function_expressions(
                 ("ArrayLength".into(), 7)
             }
             E::RayQueryProceedResult => ("rayQueryProceedResult".into(), 4),
+            E::SubgroupBallotResult => ("SubgroupBallotResult".into(), 4),
+            E::SubgroupOperationResult { .. } => ("SubgroupOperationResult".into(), 4),
             E::RayQueryGetIntersection { query, committed } => {
                 edges.insert("", query);
                 let ty = if committed { "Committed" } else { "Candidate" };
@@ -48,6 +48,8 @@ bitflags::bitflags! {
         ///
         /// We can always support this, either through the language or a polyfill
         const INSTANCE_INDEX = 1 << 22;
+        /// Subgroup operations, built-ins and barriers
+        const SUBGROUP_OPERATIONS = 1 << 23;
     }
 }
 
@@ -125,6 +127,7 @@ impl FeaturesManager {
         check_feature!(TEXTURE_SAMPLES, 150);
         check_feature!(TEXTURE_LEVELS, 130);
         check_feature!(IMAGE_SIZE, 430, 310);
+        check_feature!(SUBGROUP_OPERATIONS, 430, 310);
 
         // Return an error if there are missing features
         if missing.is_empty() {
@@ -251,6 +254,22 @@ impl FeaturesManager {
             }
         }
 
+        if self.0.contains(Features::SUBGROUP_OPERATIONS) {
+            // https://github.com/KhronosGroup/GLSL/blob/master/extensions/khr/GL_KHR_shader_subgroup.txt
+            writeln!(out, "#extension GL_KHR_shader_subgroup_basic : require")?;
+            writeln!(out, "#extension GL_KHR_shader_subgroup_vote : require")?;
+            writeln!(
+                out,
+                "#extension GL_KHR_shader_subgroup_arithmetic : require"
+            )?;
+            writeln!(out, "#extension GL_KHR_shader_subgroup_ballot : require")?;
+            writeln!(out, "#extension GL_KHR_shader_subgroup_shuffle : require")?;
+            writeln!(
+                out,
+                "#extension GL_KHR_shader_subgroup_shuffle_relative : require"
+            )?;
+        }
+
         Ok(())
     }
 }
@@ -469,11 +488,26 @@ impl<'a, W> Writer<'a, W> {
                         }
                     }
                 }
+                Expression::SubgroupBallotResult | Expression::SubgroupOperationResult { .. } => {
+                    features.request(Features::SUBGROUP_OPERATIONS)
+                }
                 _ => {}
             }
             }
         }
 
+        // Subgroup barriers have no result expression, so look for them in
+        // the statements instead.
+        if module
+            .functions
+            .iter()
+            .map(|(_, f)| &f.body)
+            .chain(std::iter::once(&entry_point.function.body))
+            .any(block_has_subgroup_barrier)
+        {
+            self.features.request(Features::SUBGROUP_OPERATIONS);
+        }
+
         self.features.check_availability(self.options.version)
     }
 
@@ -510,6 +544,12 @@ impl<'a, W> Writer<'a, W> {
                             crate::BuiltIn::InstanceIndex => {
                                 self.features.request(Features::INSTANCE_INDEX)
                             }
+                            crate::BuiltIn::NumSubgroups
+                            | crate::BuiltIn::SubgroupId
+                            | crate::BuiltIn::SubgroupSize
+                            | crate::BuiltIn::SubgroupInvocationId => {
+                                self.features.request(Features::SUBGROUP_OPERATIONS)
+                            }
                             _ => {}
                         },
                         Binding::Location {
@@ -534,3 +574,27 @@ impl<'a, W> Writer<'a, W> {
         }
     }
 }
+
+/// Returns true if `block` or any block nested in it contains a subgroup barrier.
+fn block_has_subgroup_barrier(block: &crate::Block) -> bool {
+    use crate::Statement as S;
+
+    block.iter().any(|statement| match *statement {
+        S::Barrier(flags) => flags.contains(crate::Barrier::SUB_GROUP),
+        S::Block(ref block) => block_has_subgroup_barrier(block),
+        S::If {
+            ref accept,
+            ref reject,
+            ..
+        } => block_has_subgroup_barrier(accept) || block_has_subgroup_barrier(reject),
+        S::Switch { ref cases, .. } => cases
+            .iter()
+            .any(|case| block_has_subgroup_barrier(&case.body)),
+        S::Loop {
+            ref body,
+            ref continuing,
+            ..
+        } => block_has_subgroup_barrier(body) || block_has_subgroup_barrier(continuing),
+        _ => false,
+    })
+}
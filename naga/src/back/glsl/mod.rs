@@ -317,6 +317,9 @@ pub struct ReflectionInfo {
     pub varying: crate::FastHashMap<String, VaryingLocation>,
     /// List of push constant items in the shader.
     pub push_constant_items: Vec<PushConstantItem>,
+    /// Where the lines of the output came from, if the writer was given
+    /// [source files](Writer::set_source_files).
+    pub line_map: back::LineMap,
 }
 
 /// Mapping between a texture and its sampler, if it exists.
@@ -519,7 +522,7 @@ pub struct Writer<'a, W> {
     /// The module analysis.
     info: &'a valid::ModuleInfo,
     /// The output writer.
-    out: W,
+    out: back::line_map::LineWriter<W>,
     /// User defined configuration to be used.
     options: &'a Options,
     /// The bound checking policies to be used
@@ -600,7 +603,7 @@ impl<'a, W: Write> Writer<'a, W> {
         let mut this = Self {
             module,
             info,
-            out,
+            out: back::line_map::LineWriter::new(out),
             options,
             policies,
 
@@ -623,6 +626,17 @@ impl<'a, W: Write> Writer<'a, W> {
         Ok(this)
     }
 
+    /// Write `#line` directives pointing statements back to `files`, and
+    /// report them in [`ReflectionInfo::line_map`].
+    ///
+    /// GLSL identifies files by number, and the generated code is string 0:
+    /// the directives use the files' [`FileId`](crate::FileId)s plus one, or
+    /// 1 for spans without one.
+    pub fn set_source_files(&mut self, files: back::SourceFiles) {
+        self.out
+            .set_source_files(files, back::line_map::FileSpelling::Number);
+    }
+
     /// Writes the [`Module`](crate::Module) as glsl to the output
     ///
     /// # Notes
@@ -1754,7 +1768,8 @@ impl<'a, W: Write> Writer<'a, W> {
         }
 
         // Write the function body (statement list)
-        for sta in func.body.iter() {
+        for (sta, span) in func.body.span_iter() {
+            self.out.mark(*span);
             // Write a statement, the indentation should always be 1 when writing the function body
             // `write_stmt` adds a newline
             self.write_stmt(sta, &ctx, back::Level(1))?;
@@ -1993,6 +2008,7 @@ impl<'a, W: Write> Writer<'a, W> {
                     }
 
                     if let Some(name) = expr_name {
+                        self.out.mark(ctx.expressions.get_span(handle));
                         write!(self.out, "{level}")?;
                         self.write_named_expr(handle, name, handle, ctx)?;
                     }
@@ -2004,7 +2020,8 @@ impl<'a, W: Write> Writer<'a, W> {
             Statement::Block(ref block) => {
                 write!(self.out, "{level}")?;
                 writeln!(self.out, "{{")?;
-                for sta in block.iter() {
+                for (sta, span) in block.span_iter() {
+                    self.out.mark(*span);
                     // Increase the indentation to help with readability
                     self.write_stmt(sta, ctx, level.next())?
                 }
@@ -2028,7 +2045,8 @@ impl<'a, W: Write> Writer<'a, W> {
                 self.write_expr(condition, ctx)?;
                 writeln!(self.out, ") {{")?;
 
-                for sta in accept {
+                for (sta, span) in accept.span_iter() {
+                    self.out.mark(*span);
                     // Increase indentation to help with readability
                     self.write_stmt(sta, ctx, level.next())?;
                 }
@@ -2038,7 +2056,8 @@ impl<'a, W: Write> Writer<'a, W> {
                 if !reject.is_empty() {
                     writeln!(self.out, "{level}}} else {{")?;
 
-                    for sta in reject {
+                    for (sta, span) in reject.span_iter() {
+                        self.out.mark(*span);
                         // Increase indentation to help with readability
                         self.write_stmt(sta, ctx, level.next())?;
                     }
@@ -2088,7 +2107,8 @@ impl<'a, W: Write> Writer<'a, W> {
                         writeln!(self.out)?;
                     }
 
-                    for sta in case.body.iter() {
+                    for (sta, span) in case.body.span_iter() {
+                        self.out.mark(*span);
                         self.write_stmt(sta, ctx, l2.next())?;
                     }
 
@@ -2125,7 +2145,8 @@ impl<'a, W: Write> Writer<'a, W> {
                     let l2 = level.next();
                     let l3 = l2.next();
                     writeln!(self.out, "{l2}if (!{gate_name}) {{")?;
-                    for sta in continuing {
+                    for (sta, span) in continuing.span_iter() {
+                        self.out.mark(*span);
                         self.write_stmt(sta, ctx, l3)?;
                     }
                     if let Some(condition) = break_if {
@@ -2140,7 +2161,8 @@ impl<'a, W: Write> Writer<'a, W> {
                 } else {
                     writeln!(self.out, "{level}while(true) {{")?;
                 }
-                for sta in body {
+                for (sta, span) in body.span_iter() {
+                    self.out.mark(*span);
                     self.write_stmt(sta, ctx, level.next())?;
                 }
                 writeln!(self.out, "{level}}}")?
@@ -2379,6 +2401,110 @@ impl<'a, W: Write> Writer<'a, W> {
                 writeln!(self.out, ");")?;
             }
             Statement::RayQuery { .. } => unreachable!(),
+            Statement::SubgroupElect { result } => {
+                write!(self.out, "{level}")?;
+                let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                let res_ty = ctx.resolve_type(result, &self.module.types);
+                self.write_value_type(res_ty)?;
+                write!(self.out, " {res_name} = ")?;
+                self.named_expressions
+                    .insert(result, NamedExpression::from_name(res_name));
+
+                writeln!(self.out, "subgroupElect();")?;
+            }
+            Statement::SubgroupBallot { result, predicate } => {
+                write!(self.out, "{level}")?;
+                let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                let res_ty = ctx.resolve_type(result, &self.module.types);
+                self.write_value_type(res_ty)?;
+                write!(self.out, " {res_name} = ")?;
+                self.named_expressions
+                    .insert(result, NamedExpression::from_name(res_name));
+
+                write!(self.out, "subgroupBallot(")?;
+                match predicate {
+                    Some(predicate) => self.write_expr(predicate, ctx)?,
+                    None => write!(self.out, "true")?,
+                }
+                writeln!(self.out, ");")?;
+            }
+            Statement::SubgroupCollectiveOperation {
+                op,
+                collective_op,
+                argument,
+                result,
+            } => {
+                write!(self.out, "{level}")?;
+                let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                let res_ty = ctx.resolve_type(result, &self.module.types);
+                self.write_value_type(res_ty)?;
+                write!(self.out, " {res_name} = ")?;
+                self.named_expressions
+                    .insert(result, NamedExpression::from_name(res_name));
+
+                let prefix = match collective_op {
+                    crate::CollectiveOperation::Reduce => "",
+                    crate::CollectiveOperation::InclusiveScan => "Inclusive",
+                    crate::CollectiveOperation::ExclusiveScan => "Exclusive",
+                };
+                let fun = match op {
+                    crate::SubgroupOperation::All => "All",
+                    crate::SubgroupOperation::Any => "Any",
+                    crate::SubgroupOperation::Add => "Add",
+                    crate::SubgroupOperation::Mul => "Mul",
+                    crate::SubgroupOperation::Max => "Max",
+                    crate::SubgroupOperation::Min => "Min",
+                    crate::SubgroupOperation::And => "And",
+                    crate::SubgroupOperation::Or => "Or",
+                    crate::SubgroupOperation::Xor => "Xor",
+                };
+                write!(self.out, "subgroup{prefix}{fun}(")?;
+                self.write_expr(argument, ctx)?;
+                writeln!(self.out, ");")?;
+            }
+            Statement::SubgroupGather {
+                mode,
+                argument,
+                result,
+            } => {
+                write!(self.out, "{level}")?;
+                let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                let res_ty = ctx.resolve_type(result, &self.module.types);
+                self.write_value_type(res_ty)?;
+                write!(self.out, " {res_name} = ")?;
+                self.named_expressions
+                    .insert(result, NamedExpression::from_name(res_name));
+
+                let (fun, index) = match mode {
+                    crate::GatherMode::BroadcastFirst => ("subgroupBroadcastFirst", None),
+                    crate::GatherMode::Broadcast(index) => ("subgroupBroadcast", Some(index)),
+                    crate::GatherMode::Shuffle(index) => ("subgroupShuffle", Some(index)),
+                    crate::GatherMode::ShuffleDown(index) => ("subgroupShuffleDown", Some(index)),
+                    crate::GatherMode::ShuffleUp(index) => ("subgroupShuffleUp", Some(index)),
+                    crate::GatherMode::ShuffleXor(index) => ("subgroupShuffleXor", Some(index)),
+                };
+                write!(self.out, "{fun}(")?;
+                self.write_expr(argument, ctx)?;
+                if let Some(index) = index {
+                    write!(self.out, ", ")?;
+                    // GLSL takes the invocation index as a `uint`.
+                    let is_signed = matches!(
+                        *ctx.resolve_type(index, &self.module.types),
+                        TypeInner::Scalar(crate::Scalar {
+                            kind: crate::ScalarKind::Sint,
+                            ..
+                        })
+                    );
+                    if is_signed {
+                        write!(self.out, "uint(")?;
+                        self.write_expr(index, ctx)?;
+                        write!(self.out, ")")?;
+                    } else {
+                        self.write_expr(index, ctx)?;
+                    }
+                }
+                writeln!(self.out, ");")?;
+            }
         }
 
         Ok(())
@@ -3575,7 +3701,9 @@ impl<'a, W: Write> Writer<'a, W> {
             Expression::CallResult(_)
             | Expression::AtomicResult { .. }
             | Expression::RayQueryProceedResult
-            | Expression::WorkGroupUniformLoadResult { .. } => unreachable!(),
+            | Expression::WorkGroupUniformLoadResult { .. }
+            | Expression::SubgroupBallotResult
+            | Expression::SubgroupOperationResult { .. } => unreachable!(),
             // `ArrayLength` is written as `expr.length()` and we convert it to a uint
             Expression::ArrayLength(expr) => {
                 write!(self.out, "uint(")?;
@@ -4144,7 +4272,14 @@ impl<'a, W: Write> Writer<'a, W> {
         if flags.contains(crate::Barrier::WORK_GROUP) {
             writeln!(self.out, "{level}memoryBarrierShared();")?;
         }
-        writeln!(self.out, "{level}barrier();")?;
+        if flags.contains(crate::Barrier::SUB_GROUP) {
+            writeln!(self.out, "{level}subgroupMemoryBarrier();")?;
+        }
+        if flags == crate::Barrier::SUB_GROUP {
+            writeln!(self.out, "{level}subgroupBarrier();")?;
+        } else {
+            writeln!(self.out, "{level}barrier();")?;
+        }
         Ok(())
     }
 
@@ -4252,6 +4387,7 @@ impl<'a, W: Write> Writer<'a, W> {
             uniforms,
             varying: mem::take(&mut self.varying),
             push_constant_items,
+            line_map: self.out.take_line_map(),
         })
     }
 
@@ -4413,6 +4549,11 @@ const fn glsl_built_in(built_in: crate::BuiltIn, options: VaryingOptions) -> &'s
         Bi::WorkGroupId => "gl_WorkGroupID",
         Bi::WorkGroupSize => "gl_WorkGroupSize",
         Bi::NumWorkGroups => "gl_NumWorkGroups",
+        // subgroup
+        Bi::NumSubgroups => "gl_NumSubgroups",
+        Bi::SubgroupId => "gl_SubgroupID",
+        Bi::SubgroupSize => "gl_SubgroupSize",
+        Bi::SubgroupInvocationId => "gl_SubgroupInvocationID",
     }
 }
 
@@ -174,6 +174,17 @@ impl crate::BuiltIn {
             Self::BaseInstance | Self::BaseVertex | Self::WorkGroupSize => {
                 return Err(Error::Unimplemented(format!("builtin {self:?}")))
             }
+            // See the module documentation.
+            Self::NumSubgroups | Self::SubgroupId => {
+                return Err(Error::Custom(format!(
+                    "Builtin {self:?} can't be read in HLSL"
+                )))
+            }
+            // These have no semantic, and are read with an intrinsic instead,
+            // see `to_hlsl_intrinsic`.
+            Self::SubgroupSize | Self::SubgroupInvocationId => {
+                return Err(Error::Custom(format!("Builtin {self:?} has no semantic")))
+            }
             Self::PointSize | Self::ViewIndex | Self::PointCoord => {
                 return Err(Error::Custom(format!("Unsupported builtin {self:?}")))
             }
@@ -181,6 +192,18 @@ impl crate::BuiltIn {
     }
 }
 
+impl crate::BuiltIn {
+    /// Return the HLSL intrinsic that reads this built-in, for built-ins that
+    /// have no corresponding semantic.
+    pub(super) const fn to_hlsl_intrinsic(self) -> Option<&'static str> {
+        match self {
+            Self::SubgroupSize => Some("WaveGetLaneCount()"),
+            Self::SubgroupInvocationId => Some("WaveGetLaneIndex()"),
+            _ => None,
+        }
+    }
+}
+
 impl crate::Interpolation {
     /// Return the string corresponding to the HLSL interpolation qualifier.
     pub(super) const fn to_hlsl_str(self) -> Option<&'static str> {
@@ -92,6 +92,19 @@ float3x2 GetMatmOnBaz(Baz obj) {
 We also emit an analogous `Set` function, as well as functions for
 accessing individual columns by dynamic index.
 
+# Subgroups
+
+Subgroup operations require shader model 6.0, and become wave intrinsics.
+`subgroup_size` and `subgroup_invocation_id` are read with
+`WaveGetLaneCount()` and `WaveGetLaneIndex()`, but HLSL has no way to read
+`num_subgroups` or `subgroup_id`: D3D doesn't say how the invocations of a
+workgroup are split into waves, so neither can be computed from the others.
+Entry points using them fail to translate.
+
+HLSL has no wave barrier, so a barrier with only the `SUB_GROUP` flag becomes
+`AllMemoryBarrier()`, which orders memory accesses without waiting for the
+rest of the workgroup.
+
 [hlsl]: https://docs.microsoft.com/en-us/windows/win32/direct3dhlsl/dx-graphics-hlsl
 [ilov]: https://gpuweb.github.io/gpuweb/wgsl/#internal-value-layout
 [16bb]: https://github.com/microsoft/DirectXShaderCompiler/wiki/Buffer-Packing#constant-buffer-packing
@@ -235,6 +248,9 @@ pub struct ReflectionInfo {
     ///
     /// Note: Some entry points may fail translation because of missing bindings.
     pub entry_point_names: Vec<Result<String, EntryPointError>>,
+    /// Where the lines of the output came from, if the writer was given
+    /// [source files](Writer::set_source_files).
+    pub line_map: back::LineMap,
 }
 
 #[derive(Error, Debug)]
@@ -269,7 +285,7 @@ impl Wrapped {
 }
 
 pub struct Writer<'a, W> {
-    out: W,
+    out: back::line_map::LineWriter<W>,
     names: crate::FastHashMap<proc::NameKey, String>,
     namer: proc::Namer,
     /// HLSL backend options
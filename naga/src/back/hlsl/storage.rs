@@ -50,7 +50,7 @@ use crate::{
     Handle,
 };
 
-use std::{fmt, mem};
+use std::{fmt, fmt::Write as _, mem};
 
 const STORE_TEMP_NAME: &str = "_value";
 
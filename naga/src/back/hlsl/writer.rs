@@ -8,7 +8,7 @@ use crate::{
     proc::{self, NameKey},
     valid, Handle, Module, ScalarKind, ShaderStage, TypeInner,
 };
-use std::{fmt, mem};
+use std::{fmt, fmt::Write as _, mem};
 
 const LOCATION_SEMANTIC: &str = "LOC";
 const SPECIAL_CBUF_TYPE: &str = "NagaConstants";
@@ -78,7 +78,7 @@ enum Io {
 impl<'a, W: fmt::Write> super::Writer<'a, W> {
     pub fn new(out: W, options: &'a Options) -> Self {
         Self {
-            out,
+            out: back::line_map::LineWriter::new(out),
             names: crate::FastHashMap::default(),
             namer: proc::Namer::default(),
             options,
@@ -90,6 +90,13 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         }
     }
 
+    /// Write `#line` directives pointing statements back to `files`, and
+    /// report them in [`ReflectionInfo::line_map`](super::ReflectionInfo::line_map).
+    pub fn set_source_files(&mut self, files: back::SourceFiles) {
+        self.out
+            .set_source_files(files, back::line_map::FileSpelling::Name);
+    }
+
     fn reset(&mut self, module: &Module) {
         self.names.clear();
         self.namer.reset(
@@ -104,6 +111,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         self.named_expressions.clear();
         self.wrapped.clear();
         self.need_bake_expressions.clear();
+        // Forget the directives written for any previous module.
+        self.out.take_line_map();
     }
 
     /// Helper method used to find which expressions of a given function require baking
@@ -378,7 +387,10 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             entry_point_names.push(Ok(name));
         }
 
-        Ok(super::ReflectionInfo { entry_point_names })
+        Ok(super::ReflectionInfo {
+            entry_point_names,
+            line_map: self.out.take_line_map(),
+        })
     }
 
     fn write_modifier(&mut self, binding: &crate::Binding) -> BackendResult {
@@ -458,6 +470,12 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         write!(self.out, "struct {struct_name}")?;
         writeln!(self.out, " {{")?;
         for m in members.iter() {
+            if let Some(crate::Binding::BuiltIn(built_in)) = m.binding {
+                if built_in.to_hlsl_intrinsic().is_some() {
+                    // Initialized from an intrinsic in `write_ep_arguments_initialization`.
+                    continue;
+                }
+            }
             write!(self.out, "{}", back::INDENT)?;
             if let Some(ref binding) = m.binding {
                 self.write_modifier(binding)?;
@@ -602,7 +620,24 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
     ) -> BackendResult {
         let ep_input = match self.entry_point_io[ep_index as usize].input.take() {
             Some(ep_input) => ep_input,
-            None => return Ok(()),
+            None => {
+                // The arguments are passed directly, except those built-ins
+                // that are read with an intrinsic.
+                for (arg_index, arg) in func.arguments.iter().enumerate() {
+                    let intrinsic = match arg.binding {
+                        Some(crate::Binding::BuiltIn(built_in)) => built_in.to_hlsl_intrinsic(),
+                        _ => None,
+                    };
+                    if let Some(intrinsic) = intrinsic {
+                        write!(self.out, "{}", back::INDENT)?;
+                        self.write_type(module, arg.ty)?;
+                        let arg_name =
+                            &self.names[&NameKey::EntryPointArgument(ep_index, arg_index as u32)];
+                        writeln!(self.out, " {arg_name} = {intrinsic};")?;
+                    }
+                }
+                return Ok(());
+            }
         };
         let mut fake_iter = ep_input.members.iter();
         for (arg_index, arg) in func.arguments.iter().enumerate() {
@@ -623,13 +658,15 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                             write!(self.out, ", ")?;
                         }
                         let fake_member = fake_iter.next().unwrap();
-                        write!(self.out, "{}.{}", ep_input.arg_name, fake_member.name)?;
+                        self.write_ep_input_member(&ep_input.arg_name, fake_member)?;
                     }
                     writeln!(self.out, " }};")?;
                 }
                 _ => {
                     let fake_member = fake_iter.next().unwrap();
-                    writeln!(self.out, " = {}.{};", ep_input.arg_name, fake_member.name)?;
+                    write!(self.out, " = ")?;
+                    self.write_ep_input_member(&ep_input.arg_name, fake_member)?;
+                    writeln!(self.out, ";")?;
                 }
             }
         }
@@ -637,6 +674,18 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         Ok(())
     }
 
+    /// Write a member of the flattened entry point input struct, or the
+    /// intrinsic that replaces it.
+    fn write_ep_input_member(&mut self, arg_name: &str, member: &EpStructMember) -> BackendResult {
+        match member.binding {
+            Some(crate::Binding::BuiltIn(built_in)) if built_in.to_hlsl_intrinsic().is_some() => {
+                write!(self.out, "{}", built_in.to_hlsl_intrinsic().unwrap())?;
+            }
+            _ => write!(self.out, "{}.{}", arg_name, member.name)?,
+        }
+        Ok(())
+    }
+
     /// Helper method used to write global variables
     /// # Notes
     /// Always adds a newline
@@ -1162,10 +1211,18 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     write!(self.out, "{} {}", ep_input.ty_name, ep_input.arg_name,)?;
                 } else {
                     let stage = module.entry_points[ep_index as usize].stage;
+                    let mut need_separator = false;
                     for (index, arg) in func.arguments.iter().enumerate() {
-                        if index != 0 {
+                        if let Some(crate::Binding::BuiltIn(built_in)) = arg.binding {
+                            if built_in.to_hlsl_intrinsic().is_some() {
+                                // Initialized from an intrinsic in `write_ep_arguments_initialization`.
+                                continue;
+                            }
+                        }
+                        if need_separator {
                             write!(self.out, ", ")?;
                         }
+                        need_separator = true;
                         self.write_type(module, arg.ty)?;
 
                         let argument_name =
@@ -1182,7 +1239,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     }
 
                     if need_workgroup_variables_initialization {
-                        if !func.arguments.is_empty() {
+                        if need_separator {
                             write!(self.out, ", ")?;
                         }
                         write!(self.out, "uint3 __local_invocation_id : SV_GroupThreadID")?;
@@ -1249,7 +1306,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         }
 
         // Write the function body (statement list)
-        for sta in func.body.iter() {
+        for (sta, span) in func.body.span_iter() {
+            self.out.mark(*span);
             // The indentation should always be 1 when writing the function body
             self.write_stmt(module, sta, func_ctx, back::Level(1))?;
         }
@@ -1335,6 +1393,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     };
 
                     if let Some(name) = expr_name {
+                        self.out.mark(func_ctx.expressions.get_span(handle));
                         write!(self.out, "{level}")?;
                         self.write_named_expr(module, handle, name, handle, func_ctx)?;
                     }
@@ -1344,7 +1403,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             Statement::Block(ref block) => {
                 write!(self.out, "{level}")?;
                 writeln!(self.out, "{{")?;
-                for sta in block.iter() {
+                for (sta, span) in block.span_iter() {
+                    self.out.mark(*span);
                     // Increase the indentation to help with readability
                     self.write_stmt(module, sta, func_ctx, level.next())?
                 }
@@ -1362,7 +1422,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 writeln!(self.out, ") {{")?;
 
                 let l2 = level.next();
-                for sta in accept {
+                for (sta, span) in accept.span_iter() {
+                    self.out.mark(*span);
                     // Increase indentation to help with readability
                     self.write_stmt(module, sta, func_ctx, l2)?;
                 }
@@ -1372,7 +1433,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 if !reject.is_empty() {
                     writeln!(self.out, "{level}}} else {{")?;
 
-                    for sta in reject {
+                    for (sta, span) in reject.span_iter() {
+                        self.out.mark(*span);
                         // Increase indentation to help with readability
                         self.write_stmt(module, sta, func_ctx, l2)?;
                     }
@@ -1750,7 +1812,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     writeln!(self.out, "{level}while(true) {{")?;
                     writeln!(self.out, "{l2}if (!{gate_name}) {{")?;
                     let l3 = l2.next();
-                    for sta in continuing.iter() {
+                    for (sta, span) in continuing.span_iter() {
+                        self.out.mark(*span);
                         self.write_stmt(module, sta, func_ctx, l3)?;
                     }
                     if let Some(condition) = break_if {
@@ -1766,7 +1829,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     writeln!(self.out, "{level}while(true) {{")?;
                 }
 
-                for sta in body.iter() {
+                for (sta, span) in body.span_iter() {
+                    self.out.mark(*span);
                     self.write_stmt(module, sta, func_ctx, l2)?;
                 }
                 writeln!(self.out, "{level}}}")?
@@ -1969,7 +2033,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                         for case in &cases[i..=end_case_idx] {
                             writeln!(self.out, "{indent_level_2}{{")?;
                             let prev_len = self.named_expressions.len();
-                            for sta in case.body.iter() {
+                            for (sta, span) in case.body.span_iter() {
+                                self.out.mark(*span);
                                 self.write_stmt(module, sta, func_ctx, indent_level_3)?;
                             }
                             // Clear all named expressions that were previously inserted by the statements in the block
@@ -1982,7 +2047,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                             writeln!(self.out, "{indent_level_2}break;")?;
                         }
                     } else {
-                        for sta in case.body.iter() {
+                        for (sta, span) in case.body.span_iter() {
+                            self.out.mark(*span);
                             self.write_stmt(module, sta, func_ctx, indent_level_2)?;
                         }
                         if !case.fall_through
@@ -2000,6 +2066,113 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 writeln!(self.out, "{level}}}")?
             }
             Statement::RayQuery { .. } => unreachable!(),
+            Statement::SubgroupElect { result } => {
+                let name = self.write_subgroup_result_start(module, result, func_ctx, level)?;
+                writeln!(self.out, "WaveIsFirstLane();")?;
+                self.named_expressions
+                    .insert(result, crate::NamedExpression::from_name(name));
+            }
+            Statement::SubgroupBallot { result, predicate } => {
+                let name = self.write_subgroup_result_start(module, result, func_ctx, level)?;
+                write!(self.out, "WaveActiveBallot(")?;
+                match predicate {
+                    Some(predicate) => self.write_expr(module, predicate, func_ctx)?,
+                    None => write!(self.out, "true")?,
+                }
+                writeln!(self.out, ");")?;
+                self.named_expressions
+                    .insert(result, crate::NamedExpression::from_name(name));
+            }
+            Statement::SubgroupCollectiveOperation {
+                op,
+                collective_op,
+                argument,
+                result,
+            } => {
+                let name = self.write_subgroup_result_start(module, result, func_ctx, level)?;
+                match (collective_op, op) {
+                    (crate::CollectiveOperation::Reduce, _) => {
+                        let fun = match op {
+                            crate::SubgroupOperation::All => "WaveActiveAllTrue",
+                            crate::SubgroupOperation::Any => "WaveActiveAnyTrue",
+                            crate::SubgroupOperation::Add => "WaveActiveSum",
+                            crate::SubgroupOperation::Mul => "WaveActiveProduct",
+                            crate::SubgroupOperation::Max => "WaveActiveMax",
+                            crate::SubgroupOperation::Min => "WaveActiveMin",
+                            crate::SubgroupOperation::And => "WaveActiveBitAnd",
+                            crate::SubgroupOperation::Or => "WaveActiveBitOr",
+                            crate::SubgroupOperation::Xor => "WaveActiveBitXor",
+                        };
+                        write!(self.out, "{fun}(")?;
+                        self.write_expr(module, argument, func_ctx)?;
+                        write!(self.out, ")")?;
+                    }
+                    (crate::CollectiveOperation::ExclusiveScan, crate::SubgroupOperation::Add) => {
+                        write!(self.out, "WavePrefixSum(")?;
+                        self.write_expr(module, argument, func_ctx)?;
+                        write!(self.out, ")")?;
+                    }
+                    (crate::CollectiveOperation::ExclusiveScan, crate::SubgroupOperation::Mul) => {
+                        write!(self.out, "WavePrefixProduct(")?;
+                        self.write_expr(module, argument, func_ctx)?;
+                        write!(self.out, ")")?;
+                    }
+                    // HLSL only has exclusive prefix operations, so fold the
+                    // invocation's own value back in.
+                    (crate::CollectiveOperation::InclusiveScan, crate::SubgroupOperation::Add) => {
+                        self.write_expr(module, argument, func_ctx)?;
+                        write!(self.out, " + WavePrefixSum(")?;
+                        self.write_expr(module, argument, func_ctx)?;
+                        write!(self.out, ")")?;
+                    }
+                    (crate::CollectiveOperation::InclusiveScan, crate::SubgroupOperation::Mul) => {
+                        self.write_expr(module, argument, func_ctx)?;
+                        write!(self.out, " * WavePrefixProduct(")?;
+                        self.write_expr(module, argument, func_ctx)?;
+                        write!(self.out, ")")?;
+                    }
+                    // The validator only allows scans of `Add` and `Mul`.
+                    _ => unreachable!(),
+                }
+                writeln!(self.out, ";")?;
+                self.named_expressions
+                    .insert(result, crate::NamedExpression::from_name(name));
+            }
+            Statement::SubgroupGather {
+                mode,
+                argument,
+                result,
+            } => {
+                let name = self.write_subgroup_result_start(module, result, func_ctx, level)?;
+                match mode {
+                    crate::GatherMode::BroadcastFirst => {
+                        write!(self.out, "WaveReadLaneFirst(")?;
+                        self.write_expr(module, argument, func_ctx)?;
+                    }
+                    crate::GatherMode::Broadcast(index) | crate::GatherMode::Shuffle(index) => {
+                        write!(self.out, "WaveReadLaneAt(")?;
+                        self.write_expr(module, argument, func_ctx)?;
+                        write!(self.out, ", ")?;
+                        self.write_expr(module, index, func_ctx)?;
+                    }
+                    crate::GatherMode::ShuffleDown(index)
+                    | crate::GatherMode::ShuffleUp(index)
+                    | crate::GatherMode::ShuffleXor(index) => {
+                        let op = match mode {
+                            crate::GatherMode::ShuffleDown(_) => "+",
+                            crate::GatherMode::ShuffleUp(_) => "-",
+                            _ => "^",
+                        };
+                        write!(self.out, "WaveReadLaneAt(")?;
+                        self.write_expr(module, argument, func_ctx)?;
+                        write!(self.out, ", WaveGetLaneIndex() {op} ")?;
+                        self.write_expr(module, index, func_ctx)?;
+                    }
+                }
+                writeln!(self.out, ");")?;
+                self.named_expressions
+                    .insert(result, crate::NamedExpression::from_name(name));
+            }
         }
 
         Ok(())
@@ -3158,7 +3331,9 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             Expression::CallResult(_)
             | Expression::AtomicResult { .. }
             | Expression::WorkGroupUniformLoadResult { .. }
-            | Expression::RayQueryProceedResult => {}
+            | Expression::RayQueryProceedResult
+            | Expression::SubgroupBallotResult
+            | Expression::SubgroupOperationResult { .. } => {}
         }
 
         if !closing_bracket.is_empty() {
@@ -3225,8 +3400,38 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         if barrier.contains(crate::Barrier::WORK_GROUP) {
             writeln!(self.out, "{level}GroupMemoryBarrierWithGroupSync();")?;
         }
+        // HLSL has no wave barrier. The lanes of a wave execute wave
+        // intrinsics together, so only their memory accesses need ordering,
+        // unless one of the barriers above did that already.
+        if barrier == crate::Barrier::SUB_GROUP {
+            writeln!(self.out, "{level}AllMemoryBarrier();")?;
+        }
         Ok(())
     }
+
+    /// Write the declaration of the named result of a subgroup statement, up
+    /// to and including the `=`, and return the name.
+    fn write_subgroup_result_start(
+        &mut self,
+        module: &Module,
+        result: Handle<crate::Expression>,
+        func_ctx: &back::FunctionCtx<'_>,
+        level: back::Level,
+    ) -> Result<String, Error> {
+        if self.options.shader_model < super::ShaderModel::V6_0 {
+            return Err(Error::Custom(
+                "Subgroup operations require shader model 6.0".to_string(),
+            ));
+        }
+        write!(self.out, "{level}")?;
+        let name = format!("{}{}", back::BAKE_PREFIX, result.index());
+        match func_ctx.info[result].ty {
+            proc::TypeResolution::Handle(handle) => self.write_type(module, handle)?,
+            proc::TypeResolution::Value(ref value) => self.write_value_type(module, value)?,
+        };
+        write!(self.out, " {name} = ")?;
+        Ok(name)
+    }
 }
 
 pub(super) struct MatrixType {
@@ -0,0 +1,261 @@
+/*!
+Mapping generated code back to the source it was translated from.
+
+Text backends whose output goes through a C-style preprocessor can write
+`#line` directives, so that the downstream compiler reports errors against
+the original source. The directives they wrote are also returned as a
+[`LineMap`], for messages that don't honor them.
+*/
+
+use crate::{span::FileId, Span};
+use std::fmt::{self, Write};
+
+/// The source files that a module's [`Span`]s refer to.
+///
+/// Spans whose [`file_id`](Span::file_id) was never [added](SourceFiles::add)
+/// are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct SourceFiles {
+    files: Vec<SourceFile>,
+}
+
+#[derive(Clone, Debug)]
+struct SourceFile {
+    file_id: Option<FileId>,
+    name: String,
+    /// The byte offset at which each line starts.
+    line_starts: Vec<u32>,
+}
+
+impl SourceFiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `source` as the file that spans with `file_id` refer to.
+    ///
+    /// Frontends that only ever read a single file produce spans without a
+    /// `file_id`; register their source with `None`.
+    pub fn add(&mut self, file_id: Option<FileId>, name: impl Into<String>, source: &str) {
+        let line_starts = std::iter::once(0)
+            .chain(
+                source
+                    .match_indices('\n')
+                    .map(|(index, _)| index as u32 + 1),
+            )
+            .collect();
+        self.files.retain(|file| file.file_id != file_id);
+        self.files.push(SourceFile {
+            file_id,
+            name: name.into(),
+            line_starts,
+        });
+    }
+
+    /// Return the name `file_id` was registered under.
+    pub fn name(&self, file_id: Option<FileId>) -> Option<&str> {
+        self.find(file_id).map(|file| file.name.as_str())
+    }
+
+    /// Return the file and the line, counting from 1, on which `span` starts.
+    pub fn locate(&self, span: Span) -> Option<(Option<FileId>, u32)> {
+        if !span.is_defined() {
+            return None;
+        }
+        let file = self.find(span.file_id)?;
+        let line = file
+            .line_starts
+            .partition_point(|&start| start <= span.start);
+        Some((span.file_id, line as u32))
+    }
+
+    fn find(&self, file_id: Option<FileId>) -> Option<&SourceFile> {
+        self.files.iter().find(|file| file.file_id == file_id)
+    }
+}
+
+/// Where the lines of a backend's output came from.
+///
+/// Each [`LineMapping`] corresponds to a `#line` directive: the output line
+/// following it came from the given source line, the next output line from
+/// the next source line, and so on until the next mapping.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LineMap {
+    /// The mappings, sorted by [`output_line`](LineMapping::output_line).
+    pub mappings: Vec<LineMapping>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineMapping {
+    /// The output line that `source_line` starts at, counting from 1.
+    pub output_line: u32,
+    /// The source file, as passed to [`SourceFiles::add`].
+    pub file_id: Option<FileId>,
+    /// The source line, counting from 1.
+    pub source_line: u32,
+}
+
+impl LineMap {
+    /// Return the source file and line that `output_line` came from, if
+    /// it follows a `#line` directive.
+    pub fn source_line(&self, output_line: u32) -> Option<(Option<FileId>, u32)> {
+        let index = self
+            .mappings
+            .partition_point(|mapping| mapping.output_line <= output_line);
+        let mapping = self.mappings.get(index.checked_sub(1)?)?;
+        Some((
+            mapping.file_id,
+            mapping.source_line + (output_line - mapping.output_line),
+        ))
+    }
+}
+
+/// How a `#line` directive names its file.
+#[derive(Clone, Copy)]
+pub(super) enum FileSpelling {
+    /// A quoted file name, as in C.
+    Name,
+    /// A source string number, as in GLSL. String 0 is the generated code
+    /// itself, so we use the file id plus one, or 1 for spans without one.
+    Number,
+}
+
+/// A [`Write`] adapter that writes `#line` directives.
+///
+/// Backends [`mark`](LineWriter::mark) the start of the code for each
+/// statement. The directive is only written once something else is, so
+/// that marks for code that turns out to be empty don't leave directives
+/// behind.
+pub(super) struct LineWriter<W> {
+    pub inner: W,
+    /// The current line, counting from 1.
+    line: u32,
+    directives: Option<Directives>,
+}
+
+struct Directives {
+    files: SourceFiles,
+    spelling: FileSpelling,
+    /// The location of the code about to be written, if it needs a directive.
+    pending: Option<(Option<FileId>, u32)>,
+    map: LineMap,
+}
+
+impl<W: Write> LineWriter<W> {
+    pub const fn new(inner: W) -> Self {
+        LineWriter {
+            inner,
+            line: 1,
+            directives: None,
+        }
+    }
+
+    /// Start writing directives for spans in `files`.
+    pub fn set_source_files(&mut self, files: SourceFiles, spelling: FileSpelling) {
+        self.directives = Some(Directives {
+            files,
+            spelling,
+            pending: None,
+            map: LineMap::default(),
+        });
+    }
+
+    /// Note that the code written next came from `span`.
+    ///
+    /// This must be called at the start of a line.
+    pub fn mark(&mut self, span: Span) {
+        if let Some(ref mut directives) = self.directives {
+            if let Some(location) = directives.files.locate(span) {
+                directives.pending = Some(location);
+            }
+        }
+    }
+
+    /// Return the directives written since the last call.
+    pub fn take_line_map(&mut self) -> LineMap {
+        match self.directives {
+            Some(ref mut directives) => {
+                directives.pending = None;
+                std::mem::take(&mut directives.map)
+            }
+            None => LineMap::default(),
+        }
+    }
+
+    fn write_pending_directive(&mut self) -> fmt::Result {
+        let directives = match self.directives {
+            Some(ref mut directives) => directives,
+            None => return Ok(()),
+        };
+        let (file_id, line) = match directives.pending.take() {
+            Some(location) => location,
+            None => return Ok(()),
+        };
+        // Don't bother if the compiler already knows where this line came from.
+        if directives.map.source_line(self.line) == Some((file_id, line)) {
+            return Ok(());
+        }
+
+        match directives.spelling {
+            FileSpelling::Name => {
+                let name = directives.files.name(file_id).unwrap_or_default();
+                let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+                writeln!(self.inner, "#line {line} \"{name}\"")?;
+            }
+            FileSpelling::Number => writeln!(
+                self.inner,
+                "#line {line} {}",
+                file_id.map_or(1, |id| id + 1)
+            )?,
+        }
+        self.line += 1;
+        directives.map.mappings.push(LineMapping {
+            output_line: self.line,
+            file_id,
+            source_line: line,
+        });
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for LineWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.is_empty() {
+            return Ok(());
+        }
+        self.write_pending_directive()?;
+        self.line += s.bytes().filter(|&byte| byte == b'\n').count() as u32;
+        self.inner.write_str(s)
+    }
+}
+
+#[test]
+fn locate_and_map() {
+    let mut files = SourceFiles::new();
+    files.add(Some(3), "main.wgsl", "fn a() {}\n\nfn b() {}\n");
+    assert_eq!(files.locate(Span::new(0, 2, Some(3))), Some((Some(3), 1)));
+    assert_eq!(files.locate(Span::new(11, 13, Some(3))), Some((Some(3), 3)));
+    assert_eq!(files.locate(Span::new(11, 13, None)), None);
+
+    let mut out = LineWriter::new(String::new());
+    out.set_source_files(files, FileSpelling::Name);
+    writeln!(out, "header").unwrap();
+    for start in [0, 11, 11, 0] {
+        out.mark(Span::new(start, start + 2, Some(3)));
+        if start == 0 {
+            // Code that turned out to be empty.
+            continue;
+        }
+        writeln!(out, "code").unwrap();
+    }
+    writeln!(out, "code").unwrap();
+    assert_eq!(
+        out.inner,
+        "header\n#line 3 \"main.wgsl\"\ncode\n#line 3 \"main.wgsl\"\ncode\n#line 1 \"main.wgsl\"\ncode\n"
+    );
+    let map = out.take_line_map();
+    assert_eq!(map.source_line(1), None);
+    assert_eq!(map.source_line(3), Some((Some(3), 3)));
+    assert_eq!(map.source_line(4), Some((Some(3), 4)));
+    assert_eq!(map.source_line(7), Some((Some(3), 1)));
+}
@@ -11,11 +11,18 @@ pub mod glsl;
 pub mod hlsl;
 #[cfg(feature = "msl-out")]
 pub mod msl;
+#[cfg(feature = "rust-out")]
+pub mod rust;
 #[cfg(feature = "spv-out")]
 pub mod spv;
 #[cfg(feature = "wgsl-out")]
 pub mod wgsl;
 
+#[cfg(any(feature = "glsl-out", feature = "hlsl-out", feature = "msl-out"))]
+mod line_map;
+#[cfg(any(feature = "glsl-out", feature = "hlsl-out", feature = "msl-out"))]
+pub use line_map::{LineMap, LineMapping, SourceFiles};
+
 const COMPONENTS: &[char] = &['x', 'y', 'z', 'w'];
 const INDENT: &str = "    ";
 const BAKE_PREFIX: &str = "_e";
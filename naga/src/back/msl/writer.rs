@@ -303,7 +303,7 @@ impl<'a> TypedGlobalVariable<'a> {
 }
 
 pub struct Writer<W> {
-    out: W,
+    out: back::line_map::LineWriter<W>,
     names: FastHashMap<NameKey, String>,
     named_expressions: crate::NamedExpressions,
     /// Set of expressions that need to be baked to avoid unnecessary repetition in output
@@ -587,7 +587,7 @@ impl<W: Write> Writer<W> {
     /// Creates a new `Writer` instance.
     pub fn new(out: W) -> Self {
         Writer {
-            out,
+            out: back::line_map::LineWriter::new(out),
             names: FastHashMap::default(),
             named_expressions: Default::default(),
             need_bake_expressions: Default::default(),
@@ -600,11 +600,18 @@ impl<W: Write> Writer<W> {
         }
     }
 
+    /// Write `#line` directives pointing statements back to `files`, and
+    /// report them in [`TranslationInfo::line_map`].
+    pub fn set_source_files(&mut self, files: back::SourceFiles) {
+        self.out
+            .set_source_files(files, back::line_map::FileSpelling::Name);
+    }
+
     /// Finishes writing and returns the output.
     // See https://github.com/rust-lang/rust-clippy/issues/4979.
     #[allow(clippy::missing_const_for_fn)]
     pub fn finish(self) -> W {
-        self.out
+        self.out.inner
     }
 
     fn put_call_parameters(
@@ -1961,7 +1968,9 @@ impl<W: Write> Writer<W> {
             crate::Expression::CallResult(_)
             | crate::Expression::AtomicResult { .. }
             | crate::Expression::WorkGroupUniformLoadResult { .. }
-            | crate::Expression::RayQueryProceedResult => {
+            | crate::Expression::RayQueryProceedResult
+            | crate::Expression::SubgroupBallotResult
+            | crate::Expression::SubgroupOperationResult { .. } => {
                 unreachable!()
             }
             crate::Expression::ArrayLength(expr) => {
@@ -2627,7 +2636,7 @@ impl<W: Write> Writer<W> {
     fn put_block(
         &mut self,
         level: back::Level,
-        statements: &[crate::Statement],
+        statements: &crate::Block,
         context: &StatementContext,
     ) -> BackendResult {
         // Add to the set in order to track the stack size.
@@ -2636,7 +2645,8 @@ impl<W: Write> Writer<W> {
         self.put_block_stack_pointers
             .insert(&level as *const _ as *const ());
 
-        for statement in statements {
+        for (statement, span) in statements.span_iter() {
+            self.out.mark(*span);
             log::trace!("statement[{}] {:?}", level.0, statement);
             match *statement {
                 crate::Statement::Emit(ref range) => {
@@ -2689,6 +2699,8 @@ impl<W: Write> Writer<W> {
                         };
 
                         if let Some(name) = expr_name {
+                            self.out
+                                .mark(context.expression.function.expressions.get_span(handle));
                             write!(self.out, "{level}")?;
                             self.start_baking_expression(handle, &context.expression, &name)?;
                             self.put_expression(handle, &context.expression, true)?;
@@ -2942,6 +2954,122 @@ impl<W: Write> Writer<W> {
                     writeln!(self.out, ";")?;
                     self.write_barrier(crate::Barrier::WORK_GROUP, level)?;
                 }
+                crate::Statement::SubgroupElect { result } => {
+                    write!(self.out, "{level}")?;
+                    let name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                    self.start_baking_expression(result, &context.expression, &name)?;
+                    self.named_expressions
+                        .insert(result, crate::NamedExpression::from_name(name));
+                    writeln!(self.out, "{NAMESPACE}::simd_is_first();")?;
+                }
+                crate::Statement::SubgroupBallot { result, predicate } => {
+                    let name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                    // `simd_ballot` returns a `simd_vote`, which converts to a
+                    // 64-bit mask; spread it over the first two components.
+                    let vote = format!("{name}_vote");
+                    write!(
+                        self.out,
+                        "{level}uint64_t {vote} = (uint64_t){NAMESPACE}::simd_ballot("
+                    )?;
+                    self.put_subgroup_predicate(predicate, &context.expression)?;
+                    writeln!(self.out, ");")?;
+                    write!(self.out, "{level}")?;
+                    self.start_baking_expression(result, &context.expression, &name)?;
+                    self.named_expressions
+                        .insert(result, crate::NamedExpression::from_name(name));
+                    writeln!(
+                        self.out,
+                        "{NAMESPACE}::uint4((uint){vote}, (uint)({vote} >> 32), 0, 0);"
+                    )?;
+                }
+                crate::Statement::SubgroupCollectiveOperation {
+                    op,
+                    collective_op,
+                    argument,
+                    result,
+                } => {
+                    write!(self.out, "{level}")?;
+                    let name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                    self.start_baking_expression(result, &context.expression, &name)?;
+                    self.named_expressions
+                        .insert(result, crate::NamedExpression::from_name(name));
+                    let fun = match (collective_op, op) {
+                        (crate::CollectiveOperation::Reduce, crate::SubgroupOperation::All) => {
+                            "simd_all"
+                        }
+                        (crate::CollectiveOperation::Reduce, crate::SubgroupOperation::Any) => {
+                            "simd_any"
+                        }
+                        (crate::CollectiveOperation::Reduce, crate::SubgroupOperation::Add) => {
+                            "simd_sum"
+                        }
+                        (crate::CollectiveOperation::Reduce, crate::SubgroupOperation::Mul) => {
+                            "simd_product"
+                        }
+                        (crate::CollectiveOperation::Reduce, crate::SubgroupOperation::Max) => {
+                            "simd_max"
+                        }
+                        (crate::CollectiveOperation::Reduce, crate::SubgroupOperation::Min) => {
+                            "simd_min"
+                        }
+                        (crate::CollectiveOperation::Reduce, crate::SubgroupOperation::And) => {
+                            "simd_and"
+                        }
+                        (crate::CollectiveOperation::Reduce, crate::SubgroupOperation::Or) => {
+                            "simd_or"
+                        }
+                        (crate::CollectiveOperation::Reduce, crate::SubgroupOperation::Xor) => {
+                            "simd_xor"
+                        }
+                        (
+                            crate::CollectiveOperation::ExclusiveScan,
+                            crate::SubgroupOperation::Add,
+                        ) => "simd_prefix_exclusive_sum",
+                        (
+                            crate::CollectiveOperation::ExclusiveScan,
+                            crate::SubgroupOperation::Mul,
+                        ) => "simd_prefix_exclusive_product",
+                        (
+                            crate::CollectiveOperation::InclusiveScan,
+                            crate::SubgroupOperation::Add,
+                        ) => "simd_prefix_inclusive_sum",
+                        (
+                            crate::CollectiveOperation::InclusiveScan,
+                            crate::SubgroupOperation::Mul,
+                        ) => "simd_prefix_inclusive_product",
+                        // The validator only allows scans of `Add` and `Mul`.
+                        _ => unreachable!(),
+                    };
+                    write!(self.out, "{NAMESPACE}::{fun}(")?;
+                    self.put_expression(argument, &context.expression, true)?;
+                    writeln!(self.out, ");")?;
+                }
+                crate::Statement::SubgroupGather {
+                    mode,
+                    argument,
+                    result,
+                } => {
+                    write!(self.out, "{level}")?;
+                    let name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                    self.start_baking_expression(result, &context.expression, &name)?;
+                    self.named_expressions
+                        .insert(result, crate::NamedExpression::from_name(name));
+                    let (fun, index) = match mode {
+                        crate::GatherMode::BroadcastFirst => ("simd_broadcast_first", None),
+                        crate::GatherMode::Broadcast(index) => ("simd_broadcast", Some(index)),
+                        crate::GatherMode::Shuffle(index) => ("simd_shuffle", Some(index)),
+                        crate::GatherMode::ShuffleDown(index) => ("simd_shuffle_down", Some(index)),
+                        crate::GatherMode::ShuffleUp(index) => ("simd_shuffle_up", Some(index)),
+                        crate::GatherMode::ShuffleXor(index) => ("simd_shuffle_xor", Some(index)),
+                    };
+                    write!(self.out, "{NAMESPACE}::{fun}(")?;
+                    self.put_expression(argument, &context.expression, true)?;
+                    if let Some(index) = index {
+                        write!(self.out, ", ")?;
+                        self.put_expression(index, &context.expression, true)?;
+                    }
+                    writeln!(self.out, ");")?;
+                }
                 crate::Statement::RayQuery { query, ref fun } => {
                     if context.expression.lang_version < (2, 4) {
                         return Err(Error::UnsupportedRayTracing);
@@ -3126,6 +3254,8 @@ impl<W: Write> Writer<W> {
             &mut self.names,
         );
         self.struct_member_pads.clear();
+        // Forget the directives written for any previous module.
+        self.out.take_line_map();
 
         writeln!(
             self.out,
@@ -3199,7 +3329,9 @@ impl<W: Write> Writer<W> {
 
         self.write_type_defs(module)?;
         self.write_global_constants(module, info)?;
-        self.write_functions(module, info, options, pipeline_options)
+        let mut translation_info = self.write_functions(module, info, options, pipeline_options)?;
+        translation_info.line_map = self.out.take_line_map();
+        Ok(translation_info)
     }
 
     /// Write the definition for the `DefaultConstructible` class.
@@ -3679,6 +3811,7 @@ impl<W: Write> Writer<W> {
 
         let mut info = TranslationInfo {
             entry_point_names: Vec::with_capacity(module.entry_points.len()),
+            line_map: back::LineMap::default(),
         };
         for (ep_index, ep) in module.entry_points.iter().enumerate() {
             let fun = &ep.function;
@@ -4360,6 +4493,20 @@ impl<W: Write> Writer<W> {
         Ok(info)
     }
 
+    fn put_subgroup_predicate(
+        &mut self,
+        predicate: Option<Handle<crate::Expression>>,
+        context: &ExpressionContext,
+    ) -> BackendResult {
+        match predicate {
+            Some(predicate) => self.put_expression(predicate, context, true),
+            None => {
+                write!(self.out, "true")?;
+                Ok(())
+            }
+        }
+    }
+
     fn write_barrier(&mut self, flags: crate::Barrier, level: back::Level) -> BackendResult {
         // Note: OR-ring bitflags requires `__HAVE_MEMFLAG_OPERATORS__`,
         // so we try to avoid it here.
@@ -4381,6 +4528,12 @@ impl<W: Write> Writer<W> {
                 "{level}{NAMESPACE}::threadgroup_barrier({NAMESPACE}::mem_flags::mem_threadgroup);",
             )?;
         }
+        if flags.contains(crate::Barrier::SUB_GROUP) {
+            writeln!(
+                self.out,
+                "{level}{NAMESPACE}::simdgroup_barrier({NAMESPACE}::mem_flags::mem_threadgroup);",
+            )?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,60 @@
+/*!
+Backend for Rust host code.
+
+This backend does not translate shader code. Instead, it writes Rust
+definitions that let host code share data with a shader without mirroring
+its types by hand:
+
+- Every host-shareable struct becomes a `#[repr(C)]` Rust struct that
+  derives [`bytemuck::Pod`], with explicit padding fields so that every
+  member sits at the offset the shader expects. Each struct is followed by
+  `const` assertions of its size and member offsets. Padding longer than
+  `bytemuck` supports for a single array is split into several fields.
+
+- Every global variable with a `@group` / `@binding` attribute gets a pair
+  of `_GROUP` and `_BINDING` constants. Names that collide once uppercased
+  get a numeric suffix.
+
+- Every `override` declaration with an `@id` attribute gets an
+  `_OVERRIDE_ID` constant.
+
+Vectors are written as arrays, like `[f32; 3]`, and matrices as arrays of
+columns. Columns with three rows are written with a fourth, padding row, as
+they are laid out in memory. Array elements whose stride exceeds their size
+are wrapped in a padded element struct.
+
+A runtime-sized array at the end of a struct cannot be part of a `Pod`
+type, so it is left out, and the struct gets associated constants giving
+the array's offset and stride instead.
+
+The generated code compiles with Rust 1.65 or later.
+
+[`bytemuck::Pod`]: https://docs.rs/bytemuck/latest/bytemuck/trait.Pod.html
+*/
+
+mod keywords;
+mod writer;
+
+use thiserror::Error;
+
+pub use writer::Writer;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    FmtError(#[from] std::fmt::Error),
+    #[error(transparent)]
+    Layouter(#[from] crate::proc::LayoutError),
+    #[error("{0}")]
+    Custom(String),
+}
+
+pub fn write_string(
+    module: &crate::Module,
+    info: &crate::valid::ModuleInfo,
+) -> Result<String, Error> {
+    let mut w = Writer::new(String::new());
+    w.write(module, info)?;
+    let output = w.finish();
+    Ok(output)
+}
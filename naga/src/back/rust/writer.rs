@@ -0,0 +1,402 @@
+use super::Error;
+use crate::{
+    back,
+    proc::{self, Layouter, NameKey},
+    valid, Handle, Module, TypeInner,
+};
+use std::fmt::Write;
+
+/// Shorthand result used internally by the backend
+type BackendResult = Result<(), Error>;
+
+/// Prefix of the padding fields inserted between struct members.
+const PADDING_PREFIX: &str = "_pad";
+
+/// The lengths of `[u8; N]` arrays that implement `bytemuck::Pod` without
+/// its `min_const_generics` feature, in decreasing order.
+const POD_ARRAY_LENGTHS: &[u32] = &[4096, 2048, 1024, 512, 256, 128, 96, 64, 48, 32];
+
+pub struct Writer<W> {
+    out: W,
+    names: crate::FastHashMap<NameKey, String>,
+    namer: proc::Namer,
+    layouter: Layouter,
+    /// Names of the padded element structs written so far, keyed by element
+    /// type and array stride.
+    padded_elements: crate::FastHashMap<(Handle<crate::Type>, u32), String>,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out: W) -> Self {
+        Writer {
+            out,
+            names: crate::FastHashMap::default(),
+            namer: proc::Namer::default(),
+            layouter: Layouter::default(),
+            padded_elements: crate::FastHashMap::default(),
+        }
+    }
+
+    fn reset(&mut self, module: &Module) -> BackendResult {
+        self.names.clear();
+        self.namer.reset(
+            module,
+            super::keywords::RESERVED,
+            &[],
+            &[],
+            &[PADDING_PREFIX],
+            &mut self.names,
+        );
+        self.layouter.clear();
+        self.layouter.update(module.to_ctx())?;
+        self.padded_elements.clear();
+        Ok(())
+    }
+
+    pub fn write(&mut self, module: &Module, info: &valid::ModuleInfo) -> BackendResult {
+        self.reset(module)?;
+
+        writeln!(
+            self.out,
+            "// Generated by naga from the shader's types. Do not edit."
+        )?;
+        writeln!(self.out)?;
+
+        // Write all host-shareable structs
+        for (handle, ty) in module.types.iter() {
+            if let TypeInner::Struct { ref members, span } = ty.inner {
+                let is_predeclared = module
+                    .special_types
+                    .predeclared_types
+                    .values()
+                    .any(|t| *t == handle);
+                if !is_predeclared && info[handle].contains(valid::TypeFlags::HOST_SHAREABLE) {
+                    self.write_struct(module, handle, members, span)?;
+                    writeln!(self.out)?;
+                }
+            }
+        }
+
+        // Write the group and binding of all resources
+        let mut wrote_bindings = false;
+        for (handle, var) in module.global_variables.iter() {
+            if let Some(ref binding) = var.binding {
+                // Uppercasing can make distinct names collide, so pass the
+                // constant names through the namer again.
+                let name = self.names[&NameKey::GlobalVariable(handle)].to_uppercase();
+                let group = self.namer.call(&format!("{name}_GROUP"));
+                let binding_name = self.namer.call(&format!("{name}_BINDING"));
+                writeln!(self.out, "pub const {group}: u32 = {};", binding.group)?;
+                writeln!(
+                    self.out,
+                    "pub const {binding_name}: u32 = {};",
+                    binding.binding
+                )?;
+                wrote_bindings = true;
+            }
+        }
+        if wrote_bindings {
+            writeln!(self.out)?;
+        }
+
+        // Write the ids of all pipeline-overridable constants
+        let mut wrote_overrides = false;
+        for (handle, constant) in module.constants.iter() {
+            if let crate::Override::ByNameOrId(id) = constant.r#override {
+                let name = self.names[&NameKey::Constant(handle)].to_uppercase();
+                let name = self.namer.call(&format!("{name}_OVERRIDE_ID"));
+                writeln!(self.out, "pub const {name}: u32 = {id};")?;
+                wrote_overrides = true;
+            }
+        }
+        if wrote_overrides {
+            writeln!(self.out)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(self) -> W {
+        self.out
+    }
+
+    fn write_struct(
+        &mut self,
+        module: &Module,
+        handle: Handle<crate::Type>,
+        members: &[crate::StructMember],
+        span: u32,
+    ) -> BackendResult {
+        // A runtime-sized array can only be the last member.
+        let (members, runtime_array) = match members.split_last() {
+            Some((last, rest))
+                if matches!(
+                    module.types[last.ty].inner,
+                    TypeInner::Array {
+                        size: crate::ArraySize::Dynamic,
+                        ..
+                    }
+                ) =>
+            {
+                (rest, Some((members.len() - 1, last)))
+            }
+            _ => (members, None),
+        };
+
+        // Resolve the member types first, since that may write padded
+        // element structs that this struct refers to.
+        let member_types = members
+            .iter()
+            .map(|member| self.type_name(module, member.ty))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let struct_name = self.names[&NameKey::Type(handle)].clone();
+        let size = match runtime_array {
+            Some((_, member)) => member.offset,
+            None => span,
+        };
+
+        if let Some((index, _)) = runtime_array {
+            let member_name = &self.names[&NameKey::StructMember(handle, index as u32)];
+            writeln!(
+                self.out,
+                "/// Followed by the runtime-sized array `{member_name}`, \
+                 which is not part of this type.",
+            )?;
+        }
+        writeln!(self.out, "#[repr(C)]")?;
+        writeln!(
+            self.out,
+            "#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]"
+        )?;
+        writeln!(self.out, "pub struct {struct_name} {{")?;
+
+        let mut offset = 0;
+        let mut padding_index = 0;
+        for (index, (member, member_type)) in members.iter().zip(member_types).enumerate() {
+            if member.offset > offset {
+                self.write_padding(&mut padding_index, member.offset - offset)?;
+            }
+            let member_name = &self.names[&NameKey::StructMember(handle, index as u32)];
+            writeln!(
+                self.out,
+                "{}pub {member_name}: {member_type},",
+                back::INDENT
+            )?;
+            offset = member.offset + self.layouter[member.ty].size;
+        }
+        if size > offset {
+            self.write_padding(&mut padding_index, size - offset)?;
+        }
+        writeln!(self.out, "}}")?;
+
+        if let Some((index, member)) = runtime_array {
+            let stride = match module.types[member.ty].inner {
+                TypeInner::Array { stride, .. } => stride,
+                _ => unreachable!(),
+            };
+            let member_name =
+                self.names[&NameKey::StructMember(handle, index as u32)].to_uppercase();
+            writeln!(self.out)?;
+            writeln!(self.out, "impl {struct_name} {{")?;
+            writeln!(
+                self.out,
+                "{}pub const {member_name}_OFFSET: usize = {};",
+                back::INDENT,
+                member.offset
+            )?;
+            writeln!(
+                self.out,
+                "{}pub const {member_name}_STRIDE: usize = {stride};",
+                back::INDENT
+            )?;
+            writeln!(self.out, "}}")?;
+        }
+
+        writeln!(self.out)?;
+        writeln!(
+            self.out,
+            "const _: () = assert!(std::mem::size_of::<{struct_name}>() == {size});"
+        )?;
+
+        // `std::mem::offset_of!` needs Rust 1.77, so compute member offsets
+        // from their addresses instead, like the `memoffset` crate does.
+        if !members.is_empty() {
+            writeln!(self.out, "const _: () = {{")?;
+            writeln!(
+                self.out,
+                "{}let value = std::mem::MaybeUninit::<{struct_name}>::uninit();",
+                back::INDENT
+            )?;
+            writeln!(self.out, "{}let base = value.as_ptr();", back::INDENT)?;
+            writeln!(
+                self.out,
+                "{}// SAFETY: This only computes the addresses of members, it never reads them.",
+                back::INDENT
+            )?;
+            writeln!(self.out, "{}unsafe {{", back::INDENT)?;
+            for (index, member) in members.iter().enumerate() {
+                let member_name = &self.names[&NameKey::StructMember(handle, index as u32)];
+                writeln!(
+                    self.out,
+                    "{}let member = std::ptr::addr_of!((*base).{member_name}).cast::<u8>();",
+                    back::Level(2)
+                )?;
+                writeln!(
+                    self.out,
+                    "{}assert!(member.offset_from(base.cast()) == {});",
+                    back::Level(2),
+                    member.offset
+                )?;
+            }
+            writeln!(self.out, "{}}}", back::INDENT)?;
+            writeln!(self.out, "}};")?;
+        }
+
+        Ok(())
+    }
+
+    /// Write padding fields covering `size` bytes.
+    ///
+    /// Large padding is split into several fields, since `bytemuck` only
+    /// implements `Pod` for some array lengths.
+    fn write_padding(&mut self, padding_index: &mut u32, mut size: u32) -> BackendResult {
+        while size > 0 {
+            let chunk = POD_ARRAY_LENGTHS
+                .iter()
+                .copied()
+                .find(|&length| length <= size)
+                .unwrap_or(size);
+            writeln!(
+                self.out,
+                "{}pub {PADDING_PREFIX}{padding_index}: [u8; {chunk}],",
+                back::INDENT
+            )?;
+            *padding_index += 1;
+            size -= chunk;
+        }
+        Ok(())
+    }
+
+    /// Return the Rust spelling of the type `handle`.
+    ///
+    /// Write a padded element struct first, if the type is an array whose
+    /// stride is larger than its element.
+    fn type_name(&mut self, module: &Module, handle: Handle<crate::Type>) -> Result<String, Error> {
+        let ty = &module.types[handle];
+        Ok(match ty.inner {
+            TypeInner::Scalar(scalar) | TypeInner::Atomic(scalar) => {
+                scalar_name(scalar)?.to_string()
+            }
+            TypeInner::Vector { size, scalar } => {
+                format!("[{}; {}]", scalar_name(scalar)?, size as u8)
+            }
+            TypeInner::Matrix {
+                columns,
+                rows,
+                scalar,
+            } => {
+                // Columns are laid out like vectors, so three-row columns
+                // are padded to four rows.
+                let rows = match rows {
+                    crate::VectorSize::Tri => 4,
+                    rows => rows as u8,
+                };
+                format!("[[{}; {rows}]; {}]", scalar_name(scalar)?, columns as u8)
+            }
+            TypeInner::Array {
+                base,
+                size: crate::ArraySize::Constant(count),
+                stride,
+            } => {
+                let element = if stride > self.layouter[base].size {
+                    self.padded_element(module, base, stride)?
+                } else {
+                    self.type_name(module, base)?
+                };
+                format!("[{element}; {count}]")
+            }
+            TypeInner::Struct { .. } => self.names[&NameKey::Type(handle)].clone(),
+            _ => {
+                return Err(Error::Custom(format!(
+                    "Type {:?} has no host-shareable Rust equivalent",
+                    ty.inner
+                )))
+            }
+        })
+    }
+
+    /// Return the name of a struct holding a `base` value padded to `stride`
+    /// bytes, writing it if this is its first use.
+    fn padded_element(
+        &mut self,
+        module: &Module,
+        base: Handle<crate::Type>,
+        stride: u32,
+    ) -> Result<String, Error> {
+        if let Some(name) = self.padded_elements.get(&(base, stride)) {
+            return Ok(name.clone());
+        }
+
+        let value_type = self.type_name(module, base)?;
+        let label = match module.types[base].inner {
+            TypeInner::Struct { .. } => self.names[&NameKey::Type(base)].clone(),
+            TypeInner::Scalar(scalar) | TypeInner::Atomic(scalar) => {
+                scalar_name(scalar)?.to_uppercase()
+            }
+            TypeInner::Vector { size, scalar } => {
+                format!("Vec{}{}", size as u8, scalar_name(scalar)?.to_uppercase())
+            }
+            TypeInner::Matrix {
+                columns,
+                rows,
+                scalar,
+            } => format!(
+                "Mat{}x{}{}",
+                columns as u8,
+                rows as u8,
+                scalar_name(scalar)?.to_uppercase()
+            ),
+            _ => "Array".to_string(),
+        };
+        let name = self.namer.call(&format!("{label}Element"));
+        let size = self.layouter[base].size;
+
+        writeln!(self.out, "#[repr(C)]")?;
+        writeln!(
+            self.out,
+            "#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]"
+        )?;
+        writeln!(self.out, "pub struct {name} {{")?;
+        writeln!(self.out, "{}pub value: {value_type},", back::INDENT)?;
+        self.write_padding(&mut 0, stride - size)?;
+        writeln!(self.out, "}}")?;
+        writeln!(self.out)?;
+        writeln!(
+            self.out,
+            "const _: () = assert!(std::mem::size_of::<{name}>() == {stride});"
+        )?;
+        writeln!(self.out)?;
+
+        self.padded_elements.insert((base, stride), name.clone());
+        Ok(name)
+    }
+}
+
+fn scalar_name(scalar: crate::Scalar) -> Result<&'static str, Error> {
+    use crate::ScalarKind as Sk;
+
+    Ok(match (scalar.kind, scalar.width) {
+        (Sk::Float, 4) => "f32",
+        (Sk::Float, 8) => "f64",
+        (Sk::Sint, 4) => "i32",
+        (Sk::Sint, 8) => "i64",
+        (Sk::Uint, 4) => "u32",
+        (Sk::Uint, 8) => "u64",
+        _ => {
+            return Err(Error::Custom(format!(
+                "Scalar {scalar:?} has no host-shareable Rust equivalent"
+            )))
+        }
+    })
+}
@@ -14,6 +14,7 @@ mod layout;
 mod ray;
 mod recyclable;
 mod selection;
+mod subgroup;
 mod writer;
 
 pub use spirv::Capability;
@@ -66,6 +67,8 @@ pub enum Error {
     EntryPointNotFound,
     #[error("target SPIRV-{0}.{1} is not supported")]
     UnsupportedVersion(u8, u8),
+    #[error("using {0} requires at least SPIRV-{1}.{2}")]
+    MissingVersion(&'static str, u8, u8),
     #[error("using {0} requires at least one of the capabilities {1:?}, but none are available")]
     MissingCapabilities(&'static str, Vec<Capability>),
     #[error("unimplemented {0}")]
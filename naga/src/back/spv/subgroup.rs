@@ -0,0 +1,240 @@
+/*!
+Generating SPIR-V for subgroup operations.
+*/
+
+use super::{Block, BlockContext, Error, Instruction};
+use crate::{arena::Handle, TypeInner};
+
+impl<'w> BlockContext<'w> {
+    fn require_subgroup_capability(
+        &mut self,
+        what: &'static str,
+        capability: spirv::Capability,
+    ) -> Result<(), Error> {
+        self.writer.require_version(what, 1, 3)?;
+        self.writer
+            .require_any(what, &[spirv::Capability::GroupNonUniform])?;
+        self.writer.require_any(what, &[capability])
+    }
+
+    pub(super) fn write_subgroup_elect(
+        &mut self,
+        result: Handle<crate::Expression>,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        self.require_subgroup_capability("subgroupElect", spirv::Capability::GroupNonUniform)?;
+
+        let id = self.gen_id();
+        let result_type_id = self.get_expression_type_id(&self.fun_info[result].ty);
+        let exec_scope_id = self.get_index_constant(spirv::Scope::Subgroup as u32);
+
+        self.cached[result] = id;
+        block.body.push(Instruction::group_non_uniform_elect(
+            result_type_id,
+            id,
+            exec_scope_id,
+        ));
+        Ok(())
+    }
+
+    pub(super) fn write_subgroup_ballot(
+        &mut self,
+        predicate: Option<Handle<crate::Expression>>,
+        result: Handle<crate::Expression>,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        self.require_subgroup_capability(
+            "subgroupBallot",
+            spirv::Capability::GroupNonUniformBallot,
+        )?;
+
+        let id = self.gen_id();
+        let result_type_id = self.get_expression_type_id(&self.fun_info[result].ty);
+        let exec_scope_id = self.get_index_constant(spirv::Scope::Subgroup as u32);
+        let predicate_id = match predicate {
+            Some(predicate) => self.cached[predicate],
+            None => self.writer.get_constant_scalar(crate::Literal::Bool(true)),
+        };
+
+        self.cached[result] = id;
+        block.body.push(Instruction::group_non_uniform_ballot(
+            result_type_id,
+            id,
+            exec_scope_id,
+            predicate_id,
+        ));
+        Ok(())
+    }
+
+    pub(super) fn write_subgroup_operation(
+        &mut self,
+        op: &crate::SubgroupOperation,
+        collective_op: &crate::CollectiveOperation,
+        argument: Handle<crate::Expression>,
+        result: Handle<crate::Expression>,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        use crate::SubgroupOperation as sg;
+        use spirv::Op;
+
+        let (capability, what) = match *op {
+            sg::All | sg::Any => (spirv::Capability::GroupNonUniformVote, "subgroupAll/Any"),
+            _ => (
+                spirv::Capability::GroupNonUniformArithmetic,
+                "subgroup arithmetic",
+            ),
+        };
+        self.require_subgroup_capability(what, capability)?;
+
+        let id = self.gen_id();
+        let result_type_id = self.get_expression_type_id(&self.fun_info[result].ty);
+
+        let kind = match *self.fun_info[argument].ty.inner_with(&self.ir_module.types) {
+            TypeInner::Scalar(scalar) | TypeInner::Vector { scalar, .. } => scalar.kind,
+            _ => return Err(Error::Validation("subgroup operation argument")),
+        };
+
+        use crate::ScalarKind as sk;
+        let spirv_op = match (kind, *op) {
+            (sk::Bool, sg::All) => Op::GroupNonUniformAll,
+            (sk::Bool, sg::Any) => Op::GroupNonUniformAny,
+            (_, sg::All | sg::Any) => return Err(Error::Validation("subgroup operation argument")),
+
+            (sk::Sint | sk::Uint, sg::Add) => Op::GroupNonUniformIAdd,
+            (sk::Float, sg::Add) => Op::GroupNonUniformFAdd,
+            (sk::Sint | sk::Uint, sg::Mul) => Op::GroupNonUniformIMul,
+            (sk::Float, sg::Mul) => Op::GroupNonUniformFMul,
+            (sk::Sint, sg::Max) => Op::GroupNonUniformSMax,
+            (sk::Uint, sg::Max) => Op::GroupNonUniformUMax,
+            (sk::Float, sg::Max) => Op::GroupNonUniformFMax,
+            (sk::Sint, sg::Min) => Op::GroupNonUniformSMin,
+            (sk::Uint, sg::Min) => Op::GroupNonUniformUMin,
+            (sk::Float, sg::Min) => Op::GroupNonUniformFMin,
+            (_, sg::Add | sg::Mul | sg::Min | sg::Max) => {
+                return Err(Error::Validation("subgroup operation argument"))
+            }
+
+            (sk::Sint | sk::Uint, sg::And) => Op::GroupNonUniformBitwiseAnd,
+            (sk::Sint | sk::Uint, sg::Or) => Op::GroupNonUniformBitwiseOr,
+            (sk::Sint | sk::Uint, sg::Xor) => Op::GroupNonUniformBitwiseXor,
+            (sk::Bool, sg::And) => Op::GroupNonUniformLogicalAnd,
+            (sk::Bool, sg::Or) => Op::GroupNonUniformLogicalOr,
+            (sk::Bool, sg::Xor) => Op::GroupNonUniformLogicalXor,
+            (_, sg::And | sg::Or | sg::Xor) => {
+                return Err(Error::Validation("subgroup operation argument"))
+            }
+        };
+
+        let exec_scope_id = self.get_index_constant(spirv::Scope::Subgroup as u32);
+
+        use crate::CollectiveOperation as c;
+        let group_op = match *op {
+            sg::All | sg::Any => None,
+            _ => Some(match *collective_op {
+                c::Reduce => spirv::GroupOperation::Reduce,
+                c::InclusiveScan => spirv::GroupOperation::InclusiveScan,
+                c::ExclusiveScan => spirv::GroupOperation::ExclusiveScan,
+            }),
+        };
+
+        let arg_id = self.cached[argument];
+        self.cached[result] = id;
+        block.body.push(Instruction::group_non_uniform_arithmetic(
+            spirv_op,
+            result_type_id,
+            id,
+            exec_scope_id,
+            group_op,
+            arg_id,
+        ));
+        Ok(())
+    }
+
+    pub(super) fn write_subgroup_gather(
+        &mut self,
+        mode: &crate::GatherMode,
+        argument: Handle<crate::Expression>,
+        result: Handle<crate::Expression>,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        // Before SPIR-V 1.5 the index of `OpGroupNonUniformBroadcast` must be
+        // a constant, so a dynamically uniform index needs a shuffle instead.
+        let broadcast_op = match *mode {
+            crate::GatherMode::Broadcast(index)
+                if self.writer.physical_layout.version < 0x10500
+                    && !matches!(
+                        self.ir_function.expressions[index],
+                        crate::Expression::Literal(_) | crate::Expression::Constant(_)
+                    ) =>
+            {
+                spirv::Op::GroupNonUniformShuffle
+            }
+            _ => spirv::Op::GroupNonUniformBroadcast,
+        };
+        let (capability, what) = match *mode {
+            crate::GatherMode::BroadcastFirst => (
+                spirv::Capability::GroupNonUniformBallot,
+                "subgroupBroadcastFirst",
+            ),
+            crate::GatherMode::Broadcast(_)
+                if broadcast_op == spirv::Op::GroupNonUniformBroadcast =>
+            {
+                (
+                    spirv::Capability::GroupNonUniformBallot,
+                    "subgroupBroadcast",
+                )
+            }
+            crate::GatherMode::Broadcast(_)
+            | crate::GatherMode::Shuffle(_)
+            | crate::GatherMode::ShuffleXor(_) => {
+                (spirv::Capability::GroupNonUniformShuffle, "subgroupShuffle")
+            }
+            crate::GatherMode::ShuffleDown(_) | crate::GatherMode::ShuffleUp(_) => (
+                spirv::Capability::GroupNonUniformShuffleRelative,
+                "subgroupShuffleUp/Down",
+            ),
+        };
+        self.require_subgroup_capability(what, capability)?;
+
+        let id = self.gen_id();
+        let result_type_id = self.get_expression_type_id(&self.fun_info[result].ty);
+        let exec_scope_id = self.get_index_constant(spirv::Scope::Subgroup as u32);
+        let arg_id = self.cached[argument];
+
+        let instruction = match *mode {
+            crate::GatherMode::BroadcastFirst => Instruction::group_non_uniform_broadcast_first(
+                result_type_id,
+                id,
+                exec_scope_id,
+                arg_id,
+            ),
+            crate::GatherMode::Broadcast(index)
+            | crate::GatherMode::Shuffle(index)
+            | crate::GatherMode::ShuffleDown(index)
+            | crate::GatherMode::ShuffleUp(index)
+            | crate::GatherMode::ShuffleXor(index) => {
+                let index_id = self.cached[index];
+                let op = match *mode {
+                    crate::GatherMode::BroadcastFirst => unreachable!(),
+                    crate::GatherMode::Broadcast(_) => broadcast_op,
+                    crate::GatherMode::Shuffle(_) => spirv::Op::GroupNonUniformShuffle,
+                    crate::GatherMode::ShuffleDown(_) => spirv::Op::GroupNonUniformShuffleDown,
+                    crate::GatherMode::ShuffleUp(_) => spirv::Op::GroupNonUniformShuffleUp,
+                    crate::GatherMode::ShuffleXor(_) => spirv::Op::GroupNonUniformShuffleXor,
+                };
+                Instruction::group_non_uniform_gather(
+                    op,
+                    result_type_id,
+                    id,
+                    exec_scope_id,
+                    arg_id,
+                    index_id,
+                )
+            }
+        };
+
+        self.cached[result] = id;
+        block.body.push(instruction);
+        Ok(())
+    }
+}
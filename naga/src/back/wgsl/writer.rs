@@ -58,43 +58,214 @@ bitflags::bitflags! {
     pub struct WriterFlags: u32 {
         /// Always annotate the type information instead of inferring.
         const EXPLICIT_TYPES = 0x1;
+        /// Make the output as small as possible.
+        ///
+        /// Give everything but entry points and overridable constants the
+        /// shortest names available, leave out whitespace that doesn't
+        /// separate tokens, and leave out parentheses that operator
+        /// precedence makes unnecessary. Attributes are all kept.
+        ///
+        /// [`Writer::symbol_map`] tells what the made up names stand for.
+        const MINIFY = 0x2;
     }
 }
 
 pub struct Writer<W> {
-    out: W,
+    out: Output<W>,
     flags: WriterFlags,
     names: crate::FastHashMap<NameKey, String>,
     namer: proc::Namer,
     named_expressions: crate::NamedExpressions,
     ep_results: Vec<(ShaderStage, Handle<crate::Type>)>,
+    symbol_map: Vec<(String, String)>,
+}
+
+/// Where a [`Writer`] writes to.
+///
+/// When minifying, this drops whitespace, except where it keeps two tokens
+/// apart, so the rest of the writer can lay out its output as usual.
+struct Output<W> {
+    inner: W,
+    minify: bool,
+    /// The last character written, if minifying.
+    last: Option<char>,
+    /// Whether whitespace has been dropped since `last`.
+    skipped_space: bool,
+}
+
+impl<W: Write> Write for Output<W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        if !self.minify {
+            return self.inner.write_str(s);
+        }
+        for c in s.chars() {
+            if c.is_whitespace() {
+                self.skipped_space = true;
+                continue;
+            }
+            if std::mem::take(&mut self.skipped_space) {
+                if let Some(last) = self.last {
+                    if needs_space(last, c) {
+                        self.inner.write_char(' ')?;
+                    }
+                }
+            }
+            self.inner.write_char(c)?;
+            self.last = Some(c);
+        }
+        Ok(())
+    }
+}
+
+/// Return true if `a` followed by `b` would be read as a single token, or
+/// as the start of a comment, unless they are separated by whitespace.
+fn needs_space(a: char, b: char) -> bool {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    if is_word(a) && is_word(b) {
+        return true;
+    }
+    matches!(
+        (a, b),
+        ('-', '-' | '=' | '>')
+            | ('+', '+' | '=')
+            | ('&', '&' | '=')
+            | ('|', '|' | '=')
+            | ('<', '<' | '=')
+            | ('>', '>' | '=')
+            | ('/', '/' | '*' | '=')
+            | ('*', '/' | '=')
+            | ('=' | '!' | '%' | '^', '=')
+    )
 }
 
 impl<W: Write> Writer<W> {
     pub fn new(out: W, flags: WriterFlags) -> Self {
         Writer {
-            out,
+            out: Output {
+                inner: out,
+                minify: flags.contains(WriterFlags::MINIFY),
+                last: None,
+                skipped_space: false,
+            },
             flags,
             names: crate::FastHashMap::default(),
             namer: proc::Namer::default(),
             named_expressions: crate::NamedExpressions::default(),
             ep_results: vec![],
+            symbol_map: Vec::new(),
         }
     }
 
     fn reset(&mut self, module: &Module) {
+        let minify = self.flags.contains(WriterFlags::MINIFY);
         self.names.clear();
+        self.namer.set_minify(minify);
         self.namer.reset(
             module,
             crate::keywords::wgsl::RESERVED,
-            // an identifier must not start with two underscore
-            &[],
+            // made up names must not shadow the builtins we call
+            if minify {
+                crate::keywords::wgsl::PREDECLARED
+            } else {
+                &[]
+            },
             &[],
+            // an identifier must not start with two underscore
             &["__"],
             &mut self.names,
         );
         self.named_expressions.clear();
         self.ep_results.clear();
+        self.symbol_map.clear();
+        if minify {
+            self.collect_symbols(module);
+        }
+    }
+
+    /// Pairs of names made up while minifying and the names they replace,
+    /// in the order they were declared.
+    ///
+    /// Struct members are given as `Struct.member`. Since each struct's
+    /// members are named separately, the same made up name may stand for
+    /// several of them. Unnamed things are left out.
+    ///
+    /// This is empty unless [`WriterFlags::MINIFY`] is set.
+    pub fn symbol_map(&self) -> &[(String, String)] {
+        &self.symbol_map
+    }
+
+    fn collect_symbols(&mut self, module: &Module) {
+        let mut symbols = Vec::new();
+        let mut add = |key: NameKey, original: &Option<String>| {
+            if let Some(ref original) = *original {
+                if self.names[&key] != *original {
+                    symbols.push((self.names[&key].clone(), original.clone()));
+                }
+            }
+        };
+
+        for (handle, ty) in module.types.iter() {
+            add(NameKey::Type(handle), &ty.name);
+            if let TypeInner::Struct { ref members, .. } = ty.inner {
+                for (index, member) in members.iter().enumerate() {
+                    let original = match (&ty.name, &member.name) {
+                        (&Some(ref ty), &Some(ref member)) => Some(format!("{ty}.{member}")),
+                        _ => None,
+                    };
+                    add(NameKey::StructMember(handle, index as u32), &original);
+                }
+            }
+        }
+        for (handle, constant) in module.constants.iter() {
+            add(NameKey::Constant(handle), &constant.name);
+        }
+        for (handle, global) in module.global_variables.iter() {
+            add(NameKey::GlobalVariable(handle), &global.name);
+        }
+        for (handle, function) in module.functions.iter() {
+            add(NameKey::Function(handle), &function.name);
+            for (index, argument) in function.arguments.iter().enumerate() {
+                add(
+                    NameKey::FunctionArgument(handle, index as u32),
+                    &argument.name,
+                );
+            }
+            for (local, variable) in function.local_variables.iter() {
+                add(NameKey::FunctionLocal(handle, local), &variable.name);
+            }
+        }
+        for (index, ep) in module.entry_points.iter().enumerate() {
+            let index = index as u16;
+            for (arg, argument) in ep.function.arguments.iter().enumerate() {
+                add(
+                    NameKey::EntryPointArgument(index, arg as u32),
+                    &argument.name,
+                );
+            }
+            for (local, variable) in ep.function.local_variables.iter() {
+                add(NameKey::EntryPointLocal(index, local), &variable.name);
+            }
+        }
+
+        self.symbol_map = symbols;
+    }
+
+    /// Return a name for an expression the front end named `original`.
+    fn named_expression_name(&mut self, original: &str) -> String {
+        let name = self.namer.call(original);
+        if self.flags.contains(WriterFlags::MINIFY) {
+            self.symbol_map.push((name.clone(), original.to_string()));
+        }
+        name
+    }
+
+    /// Return a name for the result of `handle`, which has none of its own.
+    fn bake_name(&mut self, handle: Handle<crate::Expression>) -> String {
+        if self.flags.contains(WriterFlags::MINIFY) {
+            self.namer.call("")
+        } else {
+            format!("{}{}", back::BAKE_PREFIX, handle.index())
+        }
     }
 
     fn is_builtin_wgsl_struct(&self, module: &Module, handle: Handle<crate::Type>) -> bool {
@@ -622,7 +793,7 @@ impl<W: Write> Writer<W> {
                         // But we write them to step by step. We need to recache them
                         // Otherwise, we could accidentally write variable name instead of full expression.
                         // Also, we use sanitized names! It defense backend from generating variable with name from reserved keywords.
-                        Some(self.namer.call(&expression.name))
+                        Some(self.named_expression_name(&expression.name))
                     } else {
                         let expr = &func_ctx.expressions[handle];
                         let min_ref_count = expr.bake_ref_count();
@@ -634,7 +805,7 @@ impl<W: Write> Writer<W> {
                             _ => false,
                         };
                         if min_ref_count <= info.ref_count || required_baking_expr {
-                            Some(format!("{}{}", back::BAKE_PREFIX, handle.index()))
+                            Some(self.bake_name(handle))
                         } else {
                             None
                         }
@@ -643,7 +814,7 @@ impl<W: Write> Writer<W> {
                     if let Some(name) = expr_name {
                         write!(self.out, "{level}")?;
                         self.start_named_expr(module, handle, func_ctx, &name)?;
-                        self.write_expr(module, handle, func_ctx)?;
+                        self.write_full_expr(module, handle, func_ctx)?;
                         self.named_expressions.insert(handle, NamedExpression::from_name(name));
                         writeln!(self.out, ";")?;
                     }
@@ -657,7 +828,7 @@ impl<W: Write> Writer<W> {
             } => {
                 write!(self.out, "{level}")?;
                 write!(self.out, "if ")?;
-                self.write_expr(module, condition, func_ctx)?;
+                self.write_full_expr(module, condition, func_ctx)?;
                 writeln!(self.out, " {{")?;
 
                 let l2 = level.next();
@@ -685,7 +856,7 @@ impl<W: Write> Writer<W> {
                 if let Some(return_value) = value {
                     // The leading space is important
                     write!(self.out, " ")?;
-                    self.write_expr(module, return_value, func_ctx)?;
+                    self.write_full_expr(module, return_value, func_ctx)?;
                 }
                 writeln!(self.out, ";")?;
             }
@@ -715,7 +886,7 @@ impl<W: Write> Writer<W> {
                         Indirection::Reference,
                     )?;
                     write!(self.out, " = ")?;
-                    self.write_expr(module, value, func_ctx)?;
+                    self.write_full_expr(module, value, func_ctx)?;
                 }
                 writeln!(self.out, ";")?
             }
@@ -726,7 +897,7 @@ impl<W: Write> Writer<W> {
             } => {
                 write!(self.out, "{level}")?;
                 if let Some(expr) = result {
-                    let name = format!("{}{}", back::BAKE_PREFIX, expr.index());
+                    let name = self.bake_name(expr);
                     self.start_named_expr(module, expr, func_ctx, &name)?;
                     self.named_expressions.insert(expr, NamedExpression::from_name(name));
                 }
@@ -736,7 +907,7 @@ impl<W: Write> Writer<W> {
                     if index != 0 {
                         write!(self.out, ", ")?;
                     }
-                    self.write_expr(module, argument, func_ctx)?;
+                    self.write_full_expr(module, argument, func_ctx)?;
                 }
                 writeln!(self.out, ");")?
             }
@@ -747,7 +918,7 @@ impl<W: Write> Writer<W> {
                 result,
             } => {
                 write!(self.out, "{level}")?;
-                let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                let res_name = self.bake_name(result);
                 self.start_named_expr(module, result, func_ctx, &res_name)?;
                 self.named_expressions.insert(result, NamedExpression::from_name(res_name));
 
@@ -765,7 +936,7 @@ impl<W: Write> Writer<W> {
             Statement::WorkGroupUniformLoad { pointer, result } => {
                 write!(self.out, "{level}")?;
                 // TODO: Obey named expressions here.
-                let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
+                let res_name = self.bake_name(result);
                 self.start_named_expr(module, result, func_ctx, &res_name)?;
                 self.named_expressions.insert(result, NamedExpression::from_name(res_name));
                 write!(self.out, "workgroupUniformLoad(")?;
@@ -917,8 +1088,74 @@ impl<W: Write> Writer<W> {
                 if barrier.contains(crate::Barrier::WORK_GROUP) {
                     writeln!(self.out, "{level}workgroupBarrier();")?;
                 }
+
+                if barrier.contains(crate::Barrier::SUB_GROUP) {
+                    writeln!(self.out, "{level}subgroupBarrier();")?;
+                }
             }
             Statement::RayQuery { .. } => unreachable!(),
+            Statement::SubgroupElect { result } => {
+                write!(self.out, "{level}")?;
+                let res_name = self.bake_name(result);
+                self.start_named_expr(module, result, func_ctx, &res_name)?;
+                self.named_expressions
+                    .insert(result, NamedExpression::from_name(res_name));
+                writeln!(self.out, "subgroupElect();")?;
+            }
+            Statement::SubgroupBallot { result, predicate } => {
+                write!(self.out, "{level}")?;
+                let res_name = self.bake_name(result);
+                self.start_named_expr(module, result, func_ctx, &res_name)?;
+                self.named_expressions
+                    .insert(result, NamedExpression::from_name(res_name));
+                write!(self.out, "subgroupBallot(")?;
+                if let Some(predicate) = predicate {
+                    self.write_expr(module, predicate, func_ctx)?;
+                }
+                writeln!(self.out, ");")?;
+            }
+            Statement::SubgroupCollectiveOperation {
+                op,
+                collective_op,
+                argument,
+                result,
+            } => {
+                write!(self.out, "{level}")?;
+                let res_name = self.bake_name(result);
+                self.start_named_expr(module, result, func_ctx, &res_name)?;
+                self.named_expressions
+                    .insert(result, NamedExpression::from_name(res_name));
+                let fun_str = subgroup_operation_str(op, collective_op)?;
+                write!(self.out, "{fun_str}(")?;
+                self.write_expr(module, argument, func_ctx)?;
+                writeln!(self.out, ");")?;
+            }
+            Statement::SubgroupGather {
+                mode,
+                argument,
+                result,
+            } => {
+                write!(self.out, "{level}")?;
+                let res_name = self.bake_name(result);
+                self.start_named_expr(module, result, func_ctx, &res_name)?;
+                self.named_expressions
+                    .insert(result, NamedExpression::from_name(res_name));
+                let (fun_str, index) = match mode {
+                    crate::GatherMode::BroadcastFirst => ("subgroupBroadcastFirst", None),
+                    crate::GatherMode::Broadcast(index) => ("subgroupBroadcast", Some(index)),
+                    crate::GatherMode::Shuffle(index) => ("subgroupShuffle", Some(index)),
+                    crate::GatherMode::ShuffleDown(index) => ("subgroupShuffleDown", Some(index)),
+                    crate::GatherMode::ShuffleUp(index) => ("subgroupShuffleUp", Some(index)),
+                    crate::GatherMode::ShuffleXor(index) => ("subgroupShuffleXor", Some(index)),
+                };
+                write!(self.out, "{fun_str}(")?;
+                self.write_expr(module, argument, func_ctx)?;
+                if let Some(index) = index {
+                    write!(self.out, ", ")?;
+                    self.write_expr(module, index, func_ctx)?;
+                }
+                writeln!(self.out, ");")?;
+            }
         }
 
         Ok(())
@@ -1061,6 +1298,84 @@ impl<W: Write> Writer<W> {
         Ok(())
     }
 
+    /// Write `expr` where any WGSL expression may appear, like the right
+    /// hand side of an assignment.
+    ///
+    /// When minifying, this leaves out the parentheses `write_expr` would put
+    /// around a binary operation, or around taking the address of a reference.
+    fn write_full_expr(
+        &mut self,
+        module: &Module,
+        expr: Handle<crate::Expression>,
+        func_ctx: &back::FunctionCtx<'_>,
+    ) -> BackendResult {
+        if let Some((op, left, right)) = self.unparenthesized_binary(expr, func_ctx) {
+            if !is_template_like(op) {
+                return self.write_binary_operands(module, op, left, right, func_ctx);
+            }
+        }
+        if self.flags.contains(WriterFlags::MINIFY) {
+            let plain = self.plain_form_indirection(expr, module, func_ctx);
+            if let Indirection::Reference = plain {
+                write!(self.out, "&")?;
+                return self.write_expr_plain_form(module, expr, func_ctx, plain);
+            }
+        }
+        self.write_expr(module, expr, func_ctx)
+    }
+
+    /// Write the operands of a binary operation, separated by the operator.
+    fn write_binary_operands(
+        &mut self,
+        module: &Module,
+        op: crate::BinaryOperator,
+        left: Handle<crate::Expression>,
+        right: Handle<crate::Expression>,
+        func_ctx: &back::FunctionCtx<'_>,
+    ) -> BackendResult {
+        self.write_binary_operand(module, op, left, false, func_ctx)?;
+        write!(self.out, " {} ", back::binary_operation_str(op))?;
+        self.write_binary_operand(module, op, right, true, func_ctx)
+    }
+
+    /// Write `operand` of a binary operation `parent`, parenthesized only if
+    /// necessary when minifying.
+    fn write_binary_operand(
+        &mut self,
+        module: &Module,
+        parent: crate::BinaryOperator,
+        operand: Handle<crate::Expression>,
+        is_right: bool,
+        func_ctx: &back::FunctionCtx<'_>,
+    ) -> BackendResult {
+        if let Some((op, left, right)) = self.unparenthesized_binary(operand, func_ctx) {
+            if binary_operand_fits(parent, op, is_right) {
+                return self.write_binary_operands(module, op, left, right, func_ctx);
+            }
+        }
+        self.write_expr(module, operand, func_ctx)
+    }
+
+    /// If minifying, and `expr` would be written as a binary operation,
+    /// return its operator and operands.
+    fn unparenthesized_binary(
+        &self,
+        expr: Handle<crate::Expression>,
+        func_ctx: &back::FunctionCtx<'_>,
+    ) -> Option<(
+        crate::BinaryOperator,
+        Handle<crate::Expression>,
+        Handle<crate::Expression>,
+    )> {
+        if !self.flags.contains(WriterFlags::MINIFY) || self.named_expressions.contains_key(&expr) {
+            return None;
+        }
+        match func_ctx.expressions[expr] {
+            crate::Expression::Binary { op, left, right } => Some((op, left, right)),
+            _ => None,
+        }
+    }
+
     fn write_const_expression(
         &mut self,
         module: &Module,
@@ -1182,7 +1497,8 @@ impl<W: Write> Writer<W> {
                     module,
                     expr,
                     func_ctx.expressions,
-                    |writer, expr| writer.write_expr(module, expr, func_ctx),
+                    // Components are always written between parentheses.
+                    |writer, expr| writer.write_full_expr(module, expr, func_ctx),
                 )?;
             }
             Expression::FunctionArgument(pos) => {
@@ -1192,15 +1508,13 @@ impl<W: Write> Writer<W> {
             }
             Expression::Binary { op, left, right } => {
                 write!(self.out, "(")?;
-                self.write_expr(module, left, func_ctx)?;
-                write!(self.out, " {} ", back::binary_operation_str(op))?;
-                self.write_expr(module, right, func_ctx)?;
+                self.write_binary_operands(module, op, left, right, func_ctx)?;
                 write!(self.out, ")")?;
             }
             Expression::Access { base, index } => {
                 self.write_expr_with_indirection(module, base, func_ctx, indirection)?;
                 write!(self.out, "[")?;
-                self.write_expr(module, index, func_ctx)?;
+                self.write_full_expr(module, index, func_ctx)?;
                 write!(self.out, "]")?
             }
             Expression::AccessIndex { base, index } => {
@@ -1465,7 +1779,7 @@ impl<W: Write> Writer<W> {
                     }
                 };
                 write!(self.out, "(")?;
-                self.write_expr(module, expr, func_ctx)?;
+                self.write_full_expr(module, expr, func_ctx)?;
                 write!(self.out, ")")?;
             }
             Expression::Load { pointer } => {
@@ -1491,7 +1805,7 @@ impl<W: Write> Writer<W> {
             }
             Expression::ArrayLength(expr) => {
                 write!(self.out, "arrayLength(")?;
-                self.write_expr(module, expr, func_ctx)?;
+                self.write_full_expr(module, expr, func_ctx)?;
                 write!(self.out, ")")?;
             }
 
@@ -1593,10 +1907,10 @@ impl<W: Write> Writer<W> {
                 match function {
                     Function::Regular(fun_name) => {
                         write!(self.out, "{fun_name}(")?;
-                        self.write_expr(module, arg, func_ctx)?;
+                        self.write_full_expr(module, arg, func_ctx)?;
                         for arg in IntoIterator::into_iter([arg1, arg2, arg3]).flatten() {
                             write!(self.out, ", ")?;
-                            self.write_expr(module, arg, func_ctx)?;
+                            self.write_full_expr(module, arg, func_ctx)?;
                         }
                         write!(self.out, ")")?
                     }
@@ -1621,10 +1935,21 @@ impl<W: Write> Writer<W> {
                     crate::UnaryOperator::BitwiseNot => "~",
                 };
 
-                write!(self.out, "{unary}(")?;
-                self.write_expr(module, expr, func_ctx)?;
-
-                write!(self.out, ")")?
+                // The operand's own parentheses are enough, when it has them.
+                let operand_is_singular = self.flags.contains(WriterFlags::MINIFY)
+                    && (self.named_expressions.contains_key(&expr)
+                        || matches!(
+                            func_ctx.expressions[expr],
+                            Expression::Binary { .. } | Expression::FunctionArgument(_)
+                        ));
+                if operand_is_singular {
+                    write!(self.out, "{unary}")?;
+                    self.write_expr(module, expr, func_ctx)?;
+                } else {
+                    write!(self.out, "{unary}(")?;
+                    self.write_expr(module, expr, func_ctx)?;
+                    write!(self.out, ")")?
+                }
             }
 
             Expression::Select {
@@ -1633,11 +1958,11 @@ impl<W: Write> Writer<W> {
                 reject,
             } => {
                 write!(self.out, "select(")?;
-                self.write_expr(module, reject, func_ctx)?;
+                self.write_full_expr(module, reject, func_ctx)?;
                 write!(self.out, ", ")?;
-                self.write_expr(module, accept, func_ctx)?;
+                self.write_full_expr(module, accept, func_ctx)?;
                 write!(self.out, ", ")?;
-                self.write_expr(module, condition, func_ctx)?;
+                self.write_full_expr(module, condition, func_ctx)?;
                 write!(self.out, ")")?
             }
             Expression::Derivative { axis, ctrl, expr } => {
@@ -1667,7 +1992,7 @@ impl<W: Write> Writer<W> {
                 };
                 write!(self.out, "{fun_name}(")?;
 
-                self.write_expr(module, argument, func_ctx)?;
+                self.write_full_expr(module, argument, func_ctx)?;
 
                 write!(self.out, ")")?
             }
@@ -1677,6 +2002,8 @@ impl<W: Write> Writer<W> {
             Expression::CallResult(_)
             | Expression::AtomicResult { .. }
             | Expression::RayQueryProceedResult
+            | Expression::SubgroupBallotResult
+            | Expression::SubgroupOperationResult { .. }
             | Expression::WorkGroupUniformLoadResult { .. } => {}
         }
 
@@ -1756,10 +2083,65 @@ impl<W: Write> Writer<W> {
     // See https://github.com/rust-lang/rust-clippy/issues/4979.
     #[allow(clippy::missing_const_for_fn)]
     pub fn finish(self) -> W {
-        self.out
+        self.out.inner
+    }
+}
+
+/// Return true if an operand of a binary operation `parent` that is itself
+/// a binary operation `child` needs no parentheses, according to WGSL's
+/// operator precedence.
+///
+/// WGSL doesn't let most operators mix without parentheses, so only
+/// arithmetic and left-associative chains of the same operator qualify.
+fn binary_operand_fits(
+    parent: crate::BinaryOperator,
+    child: crate::BinaryOperator,
+    is_right: bool,
+) -> bool {
+    use crate::BinaryOperator as Bo;
+
+    let multiplicative = matches!(child, Bo::Multiply | Bo::Divide | Bo::Modulo);
+    let additive = matches!(child, Bo::Add | Bo::Subtract);
+    match parent {
+        Bo::Multiply | Bo::Divide | Bo::Modulo => multiplicative && !is_right,
+        Bo::Add | Bo::Subtract => multiplicative || (additive && !is_right),
+        Bo::Equal | Bo::NotEqual | Bo::Less | Bo::LessEqual | Bo::Greater | Bo::GreaterEqual => {
+            multiplicative || additive
+        }
+        // `&&` and `||` end a template list, so the operands of `<` and `>`
+        // can't be mistaken for one.
+        Bo::LogicalAnd | Bo::LogicalOr => {
+            multiplicative
+                || additive
+                || matches!(
+                    child,
+                    Bo::Equal
+                        | Bo::NotEqual
+                        | Bo::Less
+                        | Bo::LessEqual
+                        | Bo::Greater
+                        | Bo::GreaterEqual
+                )
+                || (child == parent && !is_right)
+        }
+        Bo::And | Bo::InclusiveOr | Bo::ExclusiveOr => child == parent && !is_right,
+        Bo::ShiftLeft | Bo::ShiftRight => false,
     }
 }
 
+/// Return true if `op`'s operator contains `<` or `>`.
+///
+/// We always parenthesize these, so that they can't be mistaken for
+/// template lists, like `a<b, c>(d)` would be.
+const fn is_template_like(op: crate::BinaryOperator) -> bool {
+    use crate::BinaryOperator as Bo;
+
+    matches!(
+        op,
+        Bo::Less | Bo::LessEqual | Bo::Greater | Bo::GreaterEqual | Bo::ShiftLeft | Bo::ShiftRight
+    )
+}
+
 fn builtin_str(built_in: crate::BuiltIn) -> Result<&'static str, Error> {
     use crate::BuiltIn as Bi;
 
@@ -1778,6 +2160,10 @@ fn builtin_str(built_in: crate::BuiltIn) -> Result<&'static str, Error> {
         Bi::SampleMask => "sample_mask",
         Bi::PrimitiveIndex => "primitive_index",
         Bi::ViewIndex => "view_index",
+        Bi::NumSubgroups => "num_subgroups",
+        Bi::SubgroupId => "subgroup_id",
+        Bi::SubgroupSize => "subgroup_size",
+        Bi::SubgroupInvocationId => "subgroup_invocation_id",
         Bi::BaseInstance
         | Bi::BaseVertex
         | Bi::ClipDistance
@@ -1790,6 +2176,34 @@ fn builtin_str(built_in: crate::BuiltIn) -> Result<&'static str, Error> {
     })
 }
 
+fn subgroup_operation_str(
+    op: crate::SubgroupOperation,
+    collective_op: crate::CollectiveOperation,
+) -> Result<&'static str, Error> {
+    use crate::{CollectiveOperation as co, SubgroupOperation as sg};
+
+    Ok(match (op, collective_op) {
+        (sg::All, co::Reduce) => "subgroupAll",
+        (sg::Any, co::Reduce) => "subgroupAny",
+        (sg::Add, co::Reduce) => "subgroupAdd",
+        (sg::Mul, co::Reduce) => "subgroupMul",
+        (sg::Min, co::Reduce) => "subgroupMin",
+        (sg::Max, co::Reduce) => "subgroupMax",
+        (sg::And, co::Reduce) => "subgroupAnd",
+        (sg::Or, co::Reduce) => "subgroupOr",
+        (sg::Xor, co::Reduce) => "subgroupXor",
+        (sg::Add, co::ExclusiveScan) => "subgroupExclusiveAdd",
+        (sg::Mul, co::ExclusiveScan) => "subgroupExclusiveMul",
+        (sg::Add, co::InclusiveScan) => "subgroupInclusiveAdd",
+        (sg::Mul, co::InclusiveScan) => "subgroupInclusiveMul",
+        _ => {
+            return Err(Error::Custom(format!(
+                "Unsupported subgroup operation {op:?} {collective_op:?}"
+            )))
+        }
+    })
+}
+
 const fn image_dimension_str(dim: crate::ImageDimension) -> &'static str {
     use crate::ImageDimension as IDim;
 
@@ -46,9 +46,14 @@ pub fn compact(module: &mut crate::Module) {
     // We treat all special types as used by definition.
     module_tracer.trace_special_types(&module.special_types);
 
-    // We treat all named constants as used by definition.
+    // We treat all named or exported constants as used by definition.
+    let exported_constants = module.exports.values().filter_map(|export| match *export {
+        crate::Export::Const(constant) => Some(constant),
+        _ => None,
+    });
+    module_tracer.constants_used.insert_iter(exported_constants);
     for (handle, constant) in module.constants.iter() {
-        if constant.name.is_some() {
+        if constant.name.is_some() || module_tracer.constants_used.contains(handle) {
             module_tracer.constants_used.insert(handle);
             module_tracer.const_expressions_used.insert(constant.init);
         }
@@ -158,6 +163,14 @@ pub fn compact(module: &mut crate::Module) {
         }
     });
 
+    // Adjust exported constants. Nothing else that can be exported moves.
+    log::trace!("adjusting exports");
+    for export in module.exports.values_mut() {
+        if let crate::Export::Const(ref mut constant) = *export {
+            module_map.constants.adjust(constant);
+        }
+    }
+
     // Adjust global variables' types and initializers.
     log::trace!("adjusting global variables");
     for (_, global) in module.global_variables.iter_mut() {
@@ -3,7 +3,7 @@ use super::{
     token::{Directive, DirectiveKind, Token, TokenValue},
     types::parse_type,
 };
-use crate::{FastHashMap, Span, StorageAccess};
+use crate::{span::FileId, FastHashMap, Span, StorageAccess};
 use pp_rs::{
     pp::Preprocessor,
     token::{PreprocessorError, Punct, TokenValue as PPTokenValue},
@@ -26,15 +26,25 @@ pub enum LexerResultKind {
 
 pub struct Lexer<'a> {
     pp: Preprocessor<'a>,
+    /// The file the spans of lexed tokens refer to.
+    file_id: Option<FileId>,
 }
 
 impl<'a> Lexer<'a> {
-    pub fn new(input: &'a str, defines: &'a FastHashMap<String, String>) -> Self {
+    pub fn new(
+        input: &'a str,
+        defines: &'a FastHashMap<String, String>,
+        file_id: Option<FileId>,
+    ) -> Self {
         let mut pp = Preprocessor::new(input);
         for (define, value) in defines {
             pp.add_define(define, value).unwrap(); //TODO: handle error
         }
-        Lexer { pp }
+        Lexer { pp, file_id }
+    }
+
+    const fn span(&self, loc: pp_rs::token::Location) -> Span {
+        Span::new(loc.start, loc.end, self.file_id)
     }
 }
 
@@ -46,12 +56,12 @@ impl<'a> Iterator for Lexer<'a> {
             Err((err, loc)) => {
                 return Some(LexerResult {
                     kind: LexerResultKind::Error(err),
-                    meta: loc.into(),
+                    meta: self.span(loc),
                 });
             }
         };
 
-        let meta = pp_token.location.into();
+        let meta = self.span(pp_token.location);
         let value = match pp_token.value {
             PPTokenValue::Extension(extension) => {
                 return Some(LexerResult {
@@ -213,7 +223,7 @@ mod tests {
         let defines = crate::FastHashMap::default();
 
         // line comments
-        let mut lex = Lexer::new("#version 450\nvoid main () {}", &defines);
+        let mut lex = Lexer::new("#version 450\nvoid main () {}", &defines, None);
         let mut location = Location::default();
         location.start = 9;
         location.end = 12;
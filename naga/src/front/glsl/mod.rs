@@ -16,7 +16,10 @@ pub use ast::{Precision, Profile};
 pub use error::{Error, ErrorKind, ExpectedToken};
 pub use token::TokenValue;
 
-use crate::{proc::Layouter, FastHashMap, FastHashSet, Handle, Module, ShaderStage, Span, Type};
+use super::SourceTable;
+use crate::{
+    proc::Layouter, span::FileId, FastHashMap, FastHashSet, Handle, Module, ShaderStage, Span, Type,
+};
 use ast::{EntryArg, FunctionDeclaration, GlobalLookup};
 use parser::ParsingContext;
 
@@ -175,6 +178,8 @@ pub struct Frontend {
     layouter: Layouter,
 
     errors: Vec<Error>,
+
+    sources: SourceTable,
 }
 
 impl Frontend {
@@ -186,20 +191,41 @@ impl Frontend {
         self.global_variables.clear();
         self.entry_args.clear();
         self.layouter.clear();
+        self.sources.clear();
     }
 
     /// Parses a shader either outputting a shader [`Module`] or a list of
     /// [`Error`]s.
     ///
+    /// The shader's spans refer to file 0, registered in
+    /// [`sources`](Frontend::sources) under the name `glsl`. Use
+    /// [`parse_file`](Frontend::parse_file) to choose the id and name.
+    ///
     /// Multiple calls using the same `Frontend` and different shaders are supported.
     pub fn parse(
         &mut self,
         options: &Options,
         source: &str,
+    ) -> std::result::Result<Module, Vec<Error>> {
+        self.parse_file(options, 0, "glsl", source)
+    }
+
+    /// Parses a shader like [`parse`](Frontend::parse), registering `source`
+    /// as the file `name` with `file_id`.
+    ///
+    /// This lets the spans of modules parsed from different files be told
+    /// apart, for example when linking them.
+    pub fn parse_file(
+        &mut self,
+        options: &Options,
+        file_id: FileId,
+        name: &str,
+        source: &str,
     ) -> std::result::Result<Module, Vec<Error>> {
         self.reset(options.stage);
+        self.sources.insert(file_id, name, source);
 
-        let lexer = lex::Lexer::new(source, &options.defines);
+        let lexer = lex::Lexer::new(source, &options.defines, Some(file_id));
         let mut ctx = ParsingContext::new(lexer);
 
         match ctx.parse(self) {
@@ -229,4 +255,10 @@ impl Frontend {
     pub const fn metadata(&self) -> &ShaderMetadata {
         &self.meta
     }
+
+    /// Returns the source of the last parsed shader, which its spans and the
+    /// spans of its errors refer to.
+    pub const fn sources(&self) -> &SourceTable {
+        &self.sources
+    }
 }
@@ -10,7 +10,7 @@ use super::{
     Frontend, Result,
 };
 use crate::{arena::Handle, proc::U32EvalError, Expression, Module, Span, Type};
-use pp_rs::token::{PreprocessorError, Token as PPToken, TokenValue as PPTokenValue};
+use pp_rs::token::{Location, PreprocessorError, Token as PPToken, TokenValue as PPTokenValue};
 use std::iter::Peekable;
 
 mod declarations;
@@ -233,6 +233,8 @@ impl<'source> ParsingContext<'source> {
 impl Frontend {
     fn handle_directive(&mut self, directive: Directive, meta: Span) {
         let mut tokens = directive.tokens.into_iter();
+        // The directive's tokens are in the same file as the directive.
+        let span = |location: Location| Span::new(location.start, location.end, meta.file_id);
 
         match directive.kind {
             DirectiveKind::Version { is_first_directive } => {
@@ -253,14 +255,14 @@ impl Frontend {
                         440 | 450 | 460 => self.meta.version = int.value as u16,
                         _ => self.errors.push(Error {
                             kind: ErrorKind::InvalidVersion(int.value),
-                            meta: location.into(),
+                            meta: span(location),
                         }),
                     },
                     Some(PPToken { value, location }) => self.errors.push(Error {
                         kind: ErrorKind::PreprocessorError(PreprocessorError::UnexpectedToken(
                             value,
                         )),
-                        meta: location.into(),
+                        meta: span(location),
                     }),
                     None => self.errors.push(Error {
                         kind: ErrorKind::PreprocessorError(PreprocessorError::UnexpectedNewLine),
@@ -276,14 +278,14 @@ impl Frontend {
                         "core" => self.meta.profile = Profile::Core,
                         _ => self.errors.push(Error {
                             kind: ErrorKind::InvalidProfile(name),
-                            meta: location.into(),
+                            meta: span(location),
                         }),
                     },
                     Some(PPToken { value, location }) => self.errors.push(Error {
                         kind: ErrorKind::PreprocessorError(PreprocessorError::UnexpectedToken(
                             value,
                         )),
-                        meta: location.into(),
+                        meta: span(location),
                     }),
                     None => {}
                 };
@@ -293,7 +295,7 @@ impl Frontend {
                         kind: ErrorKind::PreprocessorError(PreprocessorError::UnexpectedToken(
                             value,
                         )),
-                        meta: location.into(),
+                        meta: span(location),
                     })
                 }
             }
@@ -312,7 +314,7 @@ impl Frontend {
                             kind: ErrorKind::PreprocessorError(PreprocessorError::UnexpectedToken(
                                 value,
                             )),
-                            meta: location.into(),
+                            meta: span(location),
                         });
 
                         None
@@ -338,7 +340,7 @@ impl Frontend {
                         kind: ErrorKind::PreprocessorError(PreprocessorError::UnexpectedToken(
                             value,
                         )),
-                        meta: location.into(),
+                        meta: span(location),
                     }),
                     None => self.errors.push(Error {
                         kind: ErrorKind::PreprocessorError(PreprocessorError::UnexpectedNewLine),
@@ -360,14 +362,14 @@ impl Frontend {
                             kind: ErrorKind::PreprocessorError(PreprocessorError::UnexpectedToken(
                                 PPTokenValue::Ident(behavior),
                             )),
-                            meta: location.into(),
+                            meta: span(location),
                         }),
                     },
                     Some(PPToken { value, location }) => self.errors.push(Error {
                         kind: ErrorKind::PreprocessorError(PreprocessorError::UnexpectedToken(
                             value,
                         )),
-                        meta: location.into(),
+                        meta: span(location),
                     }),
                     None => self.errors.push(Error {
                         kind: ErrorKind::PreprocessorError(PreprocessorError::UnexpectedNewLine),
@@ -380,7 +382,7 @@ impl Frontend {
                         kind: ErrorKind::PreprocessorError(PreprocessorError::UnexpectedToken(
                             value,
                         )),
-                        meta: location.into(),
+                        meta: span(location),
                     })
                 }
             }
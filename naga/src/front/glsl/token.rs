@@ -1,14 +1,8 @@
-pub use pp_rs::token::{Float, Integer, Location, Token as PPToken};
+pub use pp_rs::token::{Float, Integer, Token as PPToken};
 
 use super::ast::Precision;
 use crate::{Interpolation, Sampling, Span, Type};
 
-impl From<Location> for Span {
-    fn from(loc: Location) -> Self {
-        Span::new(loc.start, loc.end, None)
-    }
-}
-
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct Token {
@@ -3,6 +3,8 @@ Frontend parsers that consume binary and text shaders and load them into [`Modul
 */
 
 mod interpolator;
+#[cfg(any(feature = "glsl-in", feature = "spv-in"))]
+mod sources;
 mod type_gen;
 
 #[cfg(feature = "glsl-in")]
@@ -12,6 +14,9 @@ pub mod spv;
 #[cfg(feature = "wgsl-in")]
 pub mod wgsl;
 
+#[cfg(any(feature = "glsl-in", feature = "spv-in"))]
+pub use sources::SourceTable;
+
 use crate::{
     arena::{Arena, Handle, UniqueArena},
     proc::{ResolveContext, ResolveError, TypeResolution},
@@ -0,0 +1,142 @@
+/*!
+The source files that a frontend's [`Span`]s point into.
+*/
+
+use crate::{span::FileId, FastIndexMap, Span};
+use codespan_reporting::files::{self, Files, SimpleFile};
+use std::ops::Range;
+
+/// The source files a module was parsed from, by [`FileId`].
+///
+/// Frontends that read a single file, or binaries that carry the text of the
+/// files they were compiled from, register them here and stamp their spans'
+/// [`file_id`](Span::file_id) with the matching id. The table implements
+/// [`Files`], so it can be handed to
+/// [`WithSpan::emit_to_stderr_with_provider`](crate::WithSpan::emit_to_stderr_with_provider)
+/// like a WGSL `SourceProvider`.
+///
+/// A file may be registered with empty text, when only its name is known.
+/// No span points into such a file.
+#[derive(Clone, Debug, Default)]
+pub struct SourceTable {
+    files: FastIndexMap<FileId, SimpleFile<String, String>>,
+}
+
+impl SourceTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `source` as the file with `file_id`, replacing any file
+    /// previously registered under that id.
+    pub fn insert(&mut self, file_id: FileId, name: impl Into<String>, source: impl Into<String>) {
+        self.files
+            .insert(file_id, SimpleFile::new(name.into(), source.into()));
+    }
+
+    /// Append `text` to the source of the file with `file_id`, registering it
+    /// without a name if needed.
+    pub fn append(&mut self, file_id: FileId, text: &str) {
+        let (name, mut source) = match self.files.get(&file_id) {
+            Some(file) => (file.name().clone(), file.source().clone()),
+            None => Default::default(),
+        };
+        source.push_str(text);
+        self.insert(file_id, name, source);
+    }
+
+    pub fn get(&self, file_id: FileId) -> Option<&SimpleFile<String, String>> {
+        self.files.get(&file_id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Iterate over the files in the order they were registered.
+    pub fn iter(&self) -> impl Iterator<Item = (FileId, &SimpleFile<String, String>)> {
+        self.files.iter().map(|(&file_id, file)| (file_id, file))
+    }
+
+    pub fn clear(&mut self) {
+        self.files.clear();
+    }
+
+    /// Return the text that `span` covers.
+    pub fn source_at(&self, span: Span) -> Option<&str> {
+        let file = self.get(span.file_id?)?;
+        file.source().get(span.to_range()?)
+    }
+
+    /// Return the span from `column` to the end of `line` in the file with
+    /// `file_id`, both counting from 1.
+    ///
+    /// Columns past the end of the line, or that don't fall on a character
+    /// boundary, are treated as the start of the line.
+    pub fn line_span(&self, file_id: FileId, line: u32, column: u32) -> Option<Span> {
+        let file = self.get(file_id)?;
+        let source = file.source();
+        if source.is_empty() {
+            return None;
+        }
+        let Range { start, end } = file.line_range((), line.checked_sub(1)? as usize).ok()?;
+        let end = start + source[start..end].trim_end_matches(['\r', '\n']).len();
+        let mut column_start = start + column.saturating_sub(1) as usize;
+        if column_start > end || !source.is_char_boundary(column_start) {
+            column_start = start;
+        }
+        Some(Span::new(column_start as u32, end as u32, Some(file_id)))
+    }
+}
+
+impl<'a> Files<'a> for SourceTable {
+    type FileId = FileId;
+    type Name = &'a str;
+    type Source = &'a str;
+
+    fn name(&'a self, file_id: FileId) -> Result<&'a str, files::Error> {
+        let file = self.get(file_id).ok_or(files::Error::FileMissing)?;
+        Ok(file.name())
+    }
+
+    fn source(&'a self, file_id: FileId) -> Result<&'a str, files::Error> {
+        let file = self.get(file_id).ok_or(files::Error::FileMissing)?;
+        Ok(file.source())
+    }
+
+    fn line_index(&'a self, file_id: FileId, byte_index: usize) -> Result<usize, files::Error> {
+        let file = self.get(file_id).ok_or(files::Error::FileMissing)?;
+        file.line_index((), byte_index)
+    }
+
+    fn line_range(
+        &'a self,
+        file_id: FileId,
+        line_index: usize,
+    ) -> Result<Range<usize>, files::Error> {
+        let file = self.get(file_id).ok_or(files::Error::FileMissing)?;
+        file.line_range((), line_index)
+    }
+}
+
+#[test]
+fn line_span() {
+    let mut sources = SourceTable::new();
+    sources.insert(7, "a.hlsl", "float4 main()\r\n{\n  return 0;\n}");
+    sources.append(7, "\n");
+
+    let span = sources.line_span(7, 3, 3).unwrap();
+    assert_eq!(span, Span::new(19, 28, Some(7)));
+    assert_eq!(sources.source_at(span), Some("return 0;"));
+    assert_eq!(
+        sources.source_at(sources.line_span(7, 1, 0).unwrap()),
+        Some("float4 main()")
+    );
+    assert_eq!(
+        sources.source_at(sources.line_span(7, 2, 99).unwrap()),
+        Some("{")
+    );
+    assert_eq!(sources.line_span(7, 0, 1), None);
+    assert_eq!(sources.line_span(3, 1, 1), None);
+    assert_eq!(sources.name(7).unwrap(), "a.hlsl");
+}
@@ -145,12 +145,8 @@ impl<I: Iterator<Item = u32>> super::Frontend<I> {
             let fun_inst = self.next_inst()?;
             log::debug!("{:?}", fun_inst.op);
             match fun_inst.op {
-                spirv::Op::Line => {
-                    fun_inst.expect(4)?;
-                    let _file_id = self.next()?;
-                    let _row_id = self.next()?;
-                    let _col_id = self.next()?;
-                }
+                spirv::Op::Line => self.parse_line(fun_inst)?,
+                spirv::Op::NoLine => self.parse_no_line(fun_inst)?,
                 spirv::Op::Label => {
                     // Read the label ID
                     fun_inst.expect(2)?;
@@ -160,6 +156,7 @@ impl<I: Iterator<Item = u32>> super::Frontend<I> {
                 }
                 spirv::Op::FunctionEnd => {
                     fun_inst.expect(1)?;
+                    self.line_span = None;
                     break;
                 }
                 _ => {
@@ -24,6 +24,14 @@ Instead, we detect when such matrix is accessed in the `OpAccessChain`,
 and we generate a parallel expression that loads the value, but transposed.
 This value then gets used instead of `OpLoad` result later on.
 
+## Debug information
+
+The names from `OpString` and the source text from `OpSource` and
+`OpSourceContinued` are registered in a [`SourceTable`], with the `OpString`
+result IDs as file IDs. While an `OpLine` is in effect, spans point at the
+rest of its line in that source, if the module carries it. Otherwise spans
+are byte ranges in the SPIR-V binary, without a file ID.
+
 [spv]: https://www.khronos.org/registry/SPIR-V/
 */
 
@@ -37,6 +45,7 @@ use convert::*;
 pub use error::Error;
 use function::*;
 
+use super::SourceTable;
 use crate::{
     arena::{Arena, Handle, UniqueArena},
     proc::{Alignment, Layouter},
@@ -603,6 +612,13 @@ pub struct Frontend<I> {
     /// glslang declares those by default even though they are never written to
     /// (see <https://github.com/KhronosGroup/glslang/issues/1868>)
     gl_per_vertex_builtin_access: FastHashSet<crate::BuiltIn>,
+
+    /// The files named by `OpString`, with the text `OpSource` gave them.
+    sources: SourceTable,
+    /// The file that `OpSourceContinued` continues.
+    continued_source: Option<spirv::Word>,
+    /// The span of the `OpLine` in effect, if its file's text is known.
+    line_span: Option<crate::Span>,
 }
 
 impl<I: Iterator<Item = u32>> Frontend<I> {
@@ -635,15 +651,20 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
             options: options.clone(),
             switch_cases: FastIndexMap::default(),
             gl_per_vertex_builtin_access: FastHashSet::default(),
+            sources: SourceTable::new(),
+            continued_source: None,
+            line_span: None,
         }
     }
 
     fn span_from(&self, from: usize) -> crate::Span {
-        crate::Span::from(from..self.data_offset)
+        self.line_span
+            .unwrap_or_else(|| crate::Span::from(from..self.data_offset))
     }
 
     fn span_from_with_op(&self, from: usize) -> crate::Span {
-        crate::Span::from((from - 4)..self.data_offset)
+        self.line_span
+            .unwrap_or_else(|| crate::Span::from((from - 4)..self.data_offset))
     }
 
     fn next(&mut self) -> Result<u32, Error> {
@@ -1350,17 +1371,14 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
             use spirv::Op;
             let start = self.data_offset;
             let inst = self.next_inst()?;
-            let span = crate::Span::from(start..(start + 4 * (inst.wc as usize)));
+            let span = self
+                .line_span
+                .unwrap_or_else(|| crate::Span::from(start..(start + 4 * (inst.wc as usize))));
             log::debug!("\t\t{:?} [{}]", inst.op, inst.wc);
 
             match inst.op {
-                Op::Line => {
-                    inst.expect(4)?;
-                    let _file_id = self.next()?;
-                    let _row_id = self.next()?;
-                    let _col_id = self.next()?;
-                }
-                Op::NoLine => inst.expect(1)?,
+                Op::Line => self.parse_line(inst)?,
+                Op::NoLine => self.parse_no_line(inst)?,
                 Op::Undef => {
                     inst.expect(3)?;
                     let type_id = self.next()?;
@@ -3694,6 +3712,8 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                 _ => return Err(Error::UnsupportedInstruction(self.state, inst.op)),
             }
         };
+        // An `OpLine` ends with the block it's in.
+        self.line_span = None;
 
         block.extend(emitter.finish(ctx.expressions));
         if let Some(stmt) = terminator {
@@ -3811,7 +3831,11 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                 | S::Store { .. }
                 | S::ImageStore { .. }
                 | S::Atomic { .. }
-                | S::RayQuery { .. } => {}
+                | S::RayQuery { .. }
+                | S::SubgroupElect { .. }
+                | S::SubgroupBallot { .. }
+                | S::SubgroupCollectiveOperation { .. }
+                | S::SubgroupGather { .. } => {}
                 S::Call {
                     function: ref mut callee,
                     ref arguments,
@@ -3885,7 +3909,13 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
         Ok(())
     }
 
-    pub fn parse(mut self) -> Result<crate::Module, Error> {
+    pub fn parse(self) -> Result<crate::Module, Error> {
+        self.parse_with_sources().map(|(module, _)| module)
+    }
+
+    /// Parse the module like [`parse`](Frontend::parse), also returning the
+    /// source files its spans refer to.
+    pub fn parse_with_sources(mut self) -> Result<(crate::Module, SourceTable), Error> {
         let mut module = {
             if self.next()? != spirv::MAGIC_NUMBER {
                 return Err(Error::InvalidHeader);
@@ -3902,6 +3932,9 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
         self.dummy_functions = Arena::new();
         self.lookup_function.clear();
         self.function_call_graph.clear();
+        self.sources.clear();
+        self.continued_source = None;
+        self.line_span = None;
 
         loop {
             use spirv::Op;
@@ -3922,6 +3955,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                 Op::ExecutionMode => self.parse_execution_mode(inst),
                 Op::String => self.parse_string(inst),
                 Op::Source => self.parse_source(inst),
+                Op::SourceContinued => self.parse_source_continued(inst),
                 Op::SourceExtension => self.parse_source_extension(inst),
                 Op::Name => self.parse_name(inst),
                 Op::MemberName => self.parse_member_name(inst),
@@ -3948,6 +3982,8 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                 Op::ConstantTrue => self.parse_bool_constant(inst, true, &mut module),
                 Op::ConstantFalse => self.parse_bool_constant(inst, false, &mut module),
                 Op::Variable => self.parse_global_variable(inst, &mut module),
+                Op::Line => self.parse_line(inst),
+                Op::NoLine => self.parse_no_line(inst),
                 Op::Function => {
                     self.switch(ModuleState::Function, inst.op)?;
                     inst.expect(5)?;
@@ -4005,7 +4041,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
             self.future_member_decor.clear();
         }
 
-        Ok(module)
+        Ok((module, mem::take(&mut self.sources)))
     }
 
     fn parse_capability(&mut self, inst: Instruction) -> Result<(), Error> {
@@ -4142,16 +4178,57 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
     fn parse_string(&mut self, inst: Instruction) -> Result<(), Error> {
         self.switch(ModuleState::Source, inst.op)?;
         inst.expect_at_least(3)?;
-        let _id = self.next()?;
-        let (_name, _) = self.next_string(inst.wc - 2)?;
+        let id = self.next()?;
+        let (name, _) = self.next_string(inst.wc - 2)?;
+        self.sources.insert(id, name, String::new());
         Ok(())
     }
 
     fn parse_source(&mut self, inst: Instruction) -> Result<(), Error> {
         self.switch(ModuleState::Source, inst.op)?;
-        for _ in 1..inst.wc {
+        inst.expect_at_least(3)?;
+        let _language = self.next()?;
+        let _version = self.next()?;
+        self.continued_source = None;
+        if inst.wc > 3 {
+            let file_id = self.next()?;
+            if inst.wc > 4 {
+                let (text, left) = self.next_string(inst.wc - 4)?;
+                for _ in 0..left {
+                    let _ = self.next()?;
+                }
+                self.sources.append(file_id, &text);
+            }
+            self.continued_source = Some(file_id);
+        }
+        Ok(())
+    }
+
+    fn parse_source_continued(&mut self, inst: Instruction) -> Result<(), Error> {
+        self.switch(ModuleState::Source, inst.op)?;
+        inst.expect_at_least(2)?;
+        let (text, left) = self.next_string(inst.wc - 1)?;
+        for _ in 0..left {
             let _ = self.next()?;
         }
+        if let Some(file_id) = self.continued_source {
+            self.sources.append(file_id, &text);
+        }
+        Ok(())
+    }
+
+    fn parse_line(&mut self, inst: Instruction) -> Result<(), Error> {
+        inst.expect(4)?;
+        let file_id = self.next()?;
+        let line = self.next()?;
+        let column = self.next()?;
+        self.line_span = self.sources.line_span(file_id, line, column);
+        Ok(())
+    }
+
+    fn parse_no_line(&mut self, inst: Instruction) -> Result<(), Error> {
+        inst.expect(1)?;
+        self.line_span = None;
         Ok(())
     }
 
@@ -5326,6 +5403,22 @@ pub fn parse_u8_slice(data: &[u8], options: &Options) -> Result<crate::Module, E
     Frontend::new(words, options).parse()
 }
 
+/// Parse `data` like [`parse_u8_slice`], also returning the source files
+/// that the module's spans refer to.
+pub fn parse_u8_slice_with_sources(
+    data: &[u8],
+    options: &Options,
+) -> Result<(crate::Module, SourceTable), Error> {
+    if data.len() % 4 != 0 {
+        return Err(Error::IncompleteData);
+    }
+
+    let words = data
+        .chunks(4)
+        .map(|c| u32::from_le_bytes(c.try_into().unwrap()));
+    Frontend::new(words, options).parse_with_sources()
+}
+
 #[cfg(test)]
 mod test {
     #[test]
@@ -5341,6 +5434,54 @@ mod test {
         ];
         let _ = super::parse_u8_slice(&bin, &Default::default()).unwrap();
     }
+
+    #[test]
+    fn line_spans() {
+        use spirv::Op;
+
+        fn string(text: &str) -> Vec<u32> {
+            let mut bytes = text.as_bytes().to_vec();
+            bytes.resize(text.len() / 4 * 4 + 4, 0);
+            bytes
+                .chunks(4)
+                .map(|c| u32::from_le_bytes(c.try_into().unwrap()))
+                .collect()
+        }
+        let mut words = vec![spirv::MAGIC_NUMBER, 0x10000, 0, 6, 0];
+        let mut inst = |op: Op, operands: &[u32]| {
+            words.push((operands.len() as u32 + 1) << 16 | op as u32);
+            words.extend_from_slice(operands);
+        };
+        inst(Op::Capability, &[spirv::Capability::Shader as u32]);
+        inst(Op::MemoryModel, &[0, 1]);
+        inst(Op::String, &[[1].as_slice(), &string("a.hlsl")].concat());
+        let text = string("static float x;\n");
+        inst(Op::Source, &[[5, 600, 1].as_slice(), &text].concat());
+        inst(Op::SourceContinued, &string("static float y;\n"));
+        inst(Op::TypeFloat, &[2, 32]);
+        let private = spirv::StorageClass::Private as u32;
+        inst(Op::TypePointer, &[3, private, 2]);
+        inst(Op::Line, &[1, 2, 8]);
+        inst(Op::Variable, &[3, 4, private]);
+        inst(Op::NoLine, &[]);
+        inst(Op::Variable, &[3, 5, private]);
+
+        let (module, sources) = super::Frontend::new(words.into_iter(), &Default::default())
+            .parse_with_sources()
+            .unwrap();
+        let mut spans = module
+            .global_variables
+            .iter()
+            .map(|(handle, _)| module.global_variables.get_span(handle));
+        let span = spans.next().unwrap();
+        assert_eq!(span.file_id, Some(1));
+        assert_eq!(sources.source_at(span), Some("float y;"));
+        assert_eq!(spans.next().unwrap().file_id, None);
+        assert_eq!(
+            codespan_reporting::files::Files::name(&sources, 1).unwrap(),
+            "a.hlsl"
+        );
+    }
 }
 
 /// Helper function to check if `child` is in the scope of `parent`
@@ -12,6 +12,7 @@ use std::ops::Range;
 use termcolor::{ColorChoice, StandardStream, NoColor};
 use thiserror::Error;
 
+use super::source_provider::SingleFile;
 use super::SourceProvider;
 
 #[derive(Clone, Debug)]
@@ -81,17 +82,19 @@ impl ParseError {
     }
 
     /// Emits a summary of the error to a string.
-    pub fn emit_to_string(&self, _source: &str) -> String {
-        todo!()
+    pub fn emit_to_string(&self, source: &str) -> String {
+        self.emit_to_string_with_path(source, "wgsl")
     }
 
-  /// Emits a summary of the error to a string.
-    pub fn emit_to_string_with_path<P>(&self, _source: &str, _path: P) -> String
+    /// Emits a summary of the error to a string.
+    pub fn emit_to_string_with_path<P>(&self, source: &str, path: P) -> String
     where
         P: AsRef<std::path::Path>,
     {
-        todo!()
+        let file = SingleFile::new(path.as_ref(), source);
+        self.emit_to_string_with_provider(&file)
     }
+
     // pub fn emit_to_string_with_path<P>(&self, source: &str, path: P) -> String
     // where
     //     P: AsRef<std::path::Path>,
@@ -280,6 +283,70 @@ pub enum Error<'a> {
         source_span: Span,
         source_type: String,
     },
+    /// An entry point was declared with generic parameters.
+    GenericEntryPoint(Span),
+    /// A generic parameter does not appear in any argument type, so its type
+    /// can't be inferred at a call site.
+    GenericNotInferred {
+        /// The generic parameter's declaration.
+        param: Span,
+        /// The call that needed the parameter's type.
+        call: Span,
+    },
+    /// Two arguments at a call site infer different types for the same
+    /// generic parameter.
+    GenericConflict {
+        param: Span,
+        call: Span,
+        first: String,
+        second: String,
+    },
+    /// The type inferred for a generic parameter is not one of the types
+    /// listed in its constraints.
+    GenericConstraint {
+        param: Span,
+        call: Span,
+        ty: String,
+    },
+    /// An error occurred while lowering an instance of a generic function.
+    GenericInstantiation {
+        /// The error encountered in the body of the instance.
+        error: Box<Error<'a>>,
+        /// The name of the generic function in its declaration.
+        definition: Span,
+        /// The call that required the instance.
+        call: Span,
+        /// The instance, as written in WGSL, like `lerp<f32>`.
+        instance: String,
+    },
+    /// An enum was declared with a representation other than `u32` or `i32`.
+    InvalidEnumRepr(Span),
+    /// A qualified name like `Foo::Bar` names something other than an enum.
+    UnknownEnum(Span),
+    /// A qualified name like `Foo::Bar` names a variant the enum does not have.
+    UnknownEnumVariant {
+        /// The name of the enum in its declaration.
+        declaration: Span,
+        /// The unknown variant.
+        variant: Span,
+    },
+    /// An enum variant without an explicit value would have a value not
+    /// representable in the enum's representation type.
+    EnumValueOverflow(Span),
+    /// A method was declared with generic parameters.
+    GenericMethod(Span),
+    /// The receiver of a method call has no method with the given name.
+    UnknownMethod {
+        method: Span,
+        /// The type of the receiver, as written in WGSL.
+        ty: String,
+    },
+    /// A method taking `&mut self` was called on something other than a
+    /// function-scope variable.
+    MutatingMethodReceiver {
+        method: Span,
+        receiver: Span,
+    },
 }
 
 impl<'a> Error<'a> {
@@ -761,7 +828,150 @@ impl<'a> Error<'a> {
                     )
                 ],
                 notes: vec![],
+            },
+            Error::GenericEntryPoint(span) => ParseError {
+                message: format!(
+                    "entry point `{}` cannot be generic",
+                    &provider.source_at_unchecked(span)
+                ),
+                labels: vec![(span, "entry point declared with generic parameters".into())],
+                notes: vec![],
+            },
+            Error::GenericNotInferred { param, call } => ParseError {
+                message: format!(
+                    "unable to infer the type of generic parameter `{}`",
+                    &provider.source_at_unchecked(param)
+                ),
+                labels: vec![
+                    (call, "cannot infer generic parameters for this call".into()),
+                    (param, "generic parameter declared here".into()),
+                ],
+                notes: vec![
+                    "generic parameters must appear in the type of at least one argument".into(),
+                ],
+            },
+            Error::GenericConflict {
+                param,
+                call,
+                ref first,
+                ref second,
+            } => ParseError {
+                message: format!(
+                    "conflicting types for generic parameter `{}`: `{first}` and `{second}`",
+                    &provider.source_at_unchecked(param)
+                ),
+                labels: vec![
+                    (
+                        call,
+                        format!("arguments infer both `{first}` and `{second}`").into(),
+                    ),
+                    (param, "generic parameter declared here".into()),
+                ],
+                notes: vec![],
+            },
+            Error::GenericConstraint {
+                param,
+                call,
+                ref ty,
+            } => ParseError {
+                message: format!(
+                    "type `{ty}` does not satisfy the constraints of generic parameter `{}`",
+                    &provider.source_at_unchecked(param)
+                ),
+                labels: vec![
+                    (
+                        call,
+                        format!(
+                            "`{}` inferred as `{ty}` here",
+                            &provider.source_at_unchecked(param)
+                        )
+                        .into(),
+                    ),
+                    (param, "generic parameter declared here".into()),
+                ],
+                notes: vec![],
+            },
+            Error::GenericInstantiation {
+                ref error,
+                definition,
+                call,
+                ref instance,
+            } => {
+                let mut error = error.as_parse_error(provider);
+                error
+                    .labels
+                    .push((call, format!("`{instance}` instantiated here").into()));
+                error
+                    .labels
+                    .push((definition, "generic function declared here".into()));
+                error
+                    .notes
+                    .push(format!("while instantiating `{instance}`"));
+                error
             }
+            Error::InvalidEnumRepr(span) => ParseError {
+                message: format!(
+                    "invalid enum representation type `{}`",
+                    &provider.source_at_unchecked(span)
+                ),
+                labels: vec![(span, "invalid representation type".into())],
+                notes: vec!["enums must be represented as `u32` or `i32`".into()],
+            },
+            Error::UnknownEnum(span) => ParseError {
+                message: format!("`{}` is not an enum", &provider.source_at_unchecked(span)),
+                labels: vec![(span, "expected the name of an enum".into())],
+                notes: vec![],
+            },
+            Error::UnknownEnumVariant {
+                declaration,
+                variant,
+            } => ParseError {
+                message: format!(
+                    "enum `{}` has no variant `{}`",
+                    &provider.source_at_unchecked(declaration),
+                    &provider.source_at_unchecked(variant)
+                ),
+                labels: vec![
+                    (variant, "unknown variant".into()),
+                    (declaration, "enum declared here".into()),
+                ],
+                notes: vec![],
+            },
+            Error::EnumValueOverflow(span) => ParseError {
+                message: format!(
+                    "value of enum variant `{}` overflows its representation type",
+                    &provider.source_at_unchecked(span)
+                ),
+                labels: vec![(span, "value out of range".into())],
+                notes: vec!["consider giving this variant an explicit value".into()],
+            },
+            Error::GenericMethod(span) => ParseError {
+                message: format!(
+                    "method `{}` cannot be generic",
+                    &provider.source_at_unchecked(span)
+                ),
+                labels: vec![(span, "method declared with generic parameters".into())],
+                notes: vec![],
+            },
+            Error::UnknownMethod { method, ref ty } => ParseError {
+                message: format!(
+                    "no method named `{}` found for type `{ty}`",
+                    &provider.source_at_unchecked(method)
+                ),
+                labels: vec![(method, format!("method not found in `{ty}`").into())],
+                notes: vec![],
+            },
+            Error::MutatingMethodReceiver { method, receiver } => ParseError {
+                message: format!(
+                    "method `{}` takes `&mut self`, but its receiver is not a local variable",
+                    &provider.source_at_unchecked(method)
+                ),
+                labels: vec![
+                    (receiver, "not a `var` in function scope".into()),
+                    (method, "method takes `&mut self`".into()),
+                ],
+                notes: vec!["copy the receiver into a local `var` first".into()],
+            },
         }
     }
 }
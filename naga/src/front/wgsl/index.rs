@@ -1,6 +1,6 @@
 use super::Error;
 use crate::front::wgsl::parse::ast;
-use crate::{FastHashMap, Handle, Span};
+use crate::{FastHashMap, FastHashSet, Handle, Span};
 
 /// A `GlobalDecl` list in which each definition occurs before all its uses.
 pub struct Index<'a> {
@@ -19,7 +19,21 @@ impl<'a> Index<'a> {
         // Produce a map from global definitions' names to their `Handle<GlobalDecl>`s.
         // While doing so, reject conflicting definitions.
         let mut globals = FastHashMap::with_capacity_and_hasher(tu.decls.len(), Default::default());
+        let mut methods = FastHashMap::<_, Vec<_>>::default();
         for (handle, decl) in tu.decls.iter() {
+            // Methods aren't module-scope names. Different types may have
+            // methods with the same name, so conflicts are checked during
+            // lowering, once their types are known.
+            if let ast::GlobalDeclKind::Fn(ast::Function {
+                method: Some(_),
+                name,
+                ..
+            }) = decl.kind
+            {
+                methods.entry(name.name).or_default().push(handle);
+                continue;
+            }
+
             let ident = decl_ident(decl);
             let name = ident.name;
             if let Some(old) = globals.insert(name, handle) {
@@ -33,6 +47,7 @@ impl<'a> Index<'a> {
         let len = tu.decls.len();
         let solver = DependencySolver {
             globals: &globals,
+            methods: &methods,
             module: tu,
             visited: vec![false; len],
             temp_visited: vec![false; len],
@@ -78,6 +93,9 @@ struct DependencySolver<'source, 'temp> {
     /// A map from module-scope definitions' names to their handles.
     globals: &'temp FastHashMap<&'source str, Handle<ast::GlobalDecl<'source>>>,
 
+    /// A map from method names to the handles of all methods with that name.
+    methods: &'temp FastHashMap<&'source str, Vec<Handle<ast::GlobalDecl<'source>>>>,
+
     /// The translation unit whose declarations we're ordering.
     module: &'temp ast::TranslationUnit<'source>,
 
@@ -117,53 +135,12 @@ impl<'a> DependencySolver<'a, '_> {
         let id_usize = id.index();
 
         self.temp_visited[id_usize] = true;
+        let mut expanded = FastHashSet::default();
         for dep in decl.dependencies.iter() {
-            if let Some(&dep_id) = self.globals.get(dep.ident) {
-                self.path.push(ResolvedDependency {
-                    decl: dep_id,
-                    usage: dep.usage,
-                });
-                let dep_id_usize = dep_id.index();
-
-                if self.temp_visited[dep_id_usize] {
-                    // Found a cycle.
-                    return if dep_id == id {
-                        // A declaration refers to itself directly.
-                        Err(Error::RecursiveDeclaration {
-                            ident: decl_ident(decl).span,
-                            usage: dep.usage,
-                        })
-                    } else {
-                        // A declaration refers to itself indirectly, through
-                        // one or more other definitions. Report the entire path
-                        // of references.
-                        let start_at = self
-                            .path
-                            .iter()
-                            .rev()
-                            .enumerate()
-                            .find_map(|(i, dep)| (dep.decl == dep_id).then_some(i))
-                            .unwrap_or(0);
-
-                        Err(Error::CyclicDeclaration {
-                            ident: decl_ident(&self.module.decls[dep_id]).span,
-                            path: self.path[start_at..]
-                                .iter()
-                                .map(|curr_dep| {
-                                    let curr_id = curr_dep.decl;
-                                    let curr_decl = &self.module.decls[curr_id];
-
-                                    (decl_ident(curr_decl).span, curr_dep.usage)
-                                })
-                                .collect(),
-                        })
-                    };
-                } else if !self.visited[dep_id_usize] {
-                    self.dfs(dep_id)?;
-                }
-
-                // Remove this edge from the current path.
-                self.path.pop();
+            if dep.method {
+                self.visit_method_dependencies(id, dep.ident, &mut expanded)?;
+            } else if let Some(&dep_id) = self.globals.get(dep.ident) {
+                self.visit_dependency(id, dep_id, dep.usage)?;
             }
 
             // Ignore unresolved identifiers; they may be predeclared objects.
@@ -180,6 +157,104 @@ impl<'a> DependencySolver<'a, '_> {
 
         Ok(())
     }
+
+    /// Ensure that everything used by methods named `name`, called by `id`,
+    /// has been added to the ordering.
+    ///
+    /// Which method a call refers to depends on the type of its receiver,
+    /// which isn't known until lowering. So rather than depending on every
+    /// method named `name`, which would make `impl A { fn len(self) -> f32 {
+    /// return self.b.len(); } }` depend on itself, `id` depends on what those
+    /// methods use, and the lowerer lowers the method it actually calls when
+    /// it first needs it.
+    ///
+    /// Methods calling methods are expanded in turn, each name only once, as
+    /// recorded in `expanded`. Uses that lead back to a declaration on the
+    /// current path are skipped, since the method calls in between may not
+    /// refer to the methods that make them: the lowerer reports recursion
+    /// through methods once it knows the receivers' types.
+    fn visit_method_dependencies(
+        &mut self,
+        id: Handle<ast::GlobalDecl<'a>>,
+        name: &'a str,
+        expanded: &mut FastHashSet<&'a str>,
+    ) -> Result<(), Error<'a>> {
+        if !expanded.insert(name) {
+            return Ok(());
+        }
+
+        let (methods, module) = (self.methods, self.module);
+        for &method in methods.get(name).into_iter().flatten() {
+            for dep in module.decls[method].dependencies.iter() {
+                if dep.method {
+                    self.visit_method_dependencies(id, dep.ident, expanded)?;
+                } else if let Some(&dep_id) = self.globals.get(dep.ident) {
+                    if !self.temp_visited[dep_id.index()] {
+                        self.visit_dependency(id, dep_id, dep.usage)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Visit `dep_id`, used by `id` at `usage`, unless it has already been
+    /// visited, and check that the reference doesn't form a cycle.
+    fn visit_dependency(
+        &mut self,
+        id: Handle<ast::GlobalDecl<'a>>,
+        dep_id: Handle<ast::GlobalDecl<'a>>,
+        usage: Span,
+    ) -> Result<(), Error<'a>> {
+        self.path.push(ResolvedDependency {
+            decl: dep_id,
+            usage,
+        });
+        let dep_id_usize = dep_id.index();
+
+        if self.temp_visited[dep_id_usize] {
+            // Found a cycle.
+            return if dep_id == id {
+                // A declaration refers to itself directly.
+                Err(Error::RecursiveDeclaration {
+                    ident: decl_ident(&self.module.decls[id]).span,
+                    usage,
+                })
+            } else {
+                // A declaration refers to itself indirectly, through
+                // one or more other definitions. Report the entire path
+                // of references.
+                let start_at = self
+                    .path
+                    .iter()
+                    .rev()
+                    .enumerate()
+                    .find_map(|(i, dep)| (dep.decl == dep_id).then_some(i))
+                    .unwrap_or(0);
+
+                Err(Error::CyclicDeclaration {
+                    ident: decl_ident(&self.module.decls[dep_id]).span,
+                    path: self.path[start_at..]
+                        .iter()
+                        .map(|curr_dep| {
+                            let curr_id = curr_dep.decl;
+                            let curr_decl = &self.module.decls[curr_id];
+
+                            (decl_ident(curr_decl).span, curr_dep.usage)
+                        })
+                        .collect(),
+                })
+            };
+        } else if !self.visited[dep_id_usize] {
+            self.dfs(dep_id)?;
+        }
+
+        // Remove this edge from the current path.
+        self.path.pop();
+
+        Ok(())
+    }
 }
 
 const fn decl_ident<'a>(decl: &ast::GlobalDecl<'a>) -> ast::Ident<'a> {
@@ -189,5 +264,6 @@ const fn decl_ident<'a>(decl: &ast::GlobalDecl<'a>) -> ast::Ident<'a> {
         ast::GlobalDeclKind::Const(ref c) => c.name,
         ast::GlobalDeclKind::Struct(ref s) => s.name,
         ast::GlobalDeclKind::Type(ref t) => t.name,
+        ast::GlobalDeclKind::Enum(ref e) => e.name,
     }
 }
@@ -791,6 +791,10 @@ impl Components {
 /// An `ast::GlobalDecl` for which we have built the Naga IR equivalent.
 enum LoweredGlobalDecl {
     Function(Handle<crate::Function>),
+    /// A generic function, lowered separately for each instantiation.
+    ///
+    /// See [`Lowerer::generic_functions`].
+    GenericFunction,
     Var(Handle<crate::GlobalVariable>),
     Const(Handle<crate::Constant>),
     Type(Handle<crate::Type>),
@@ -810,6 +814,52 @@ enum Texture {
     // SampleBaseClampToEdge,
 }
 
+enum SubgroupGather {
+    BroadcastFirst,
+    Broadcast,
+    Shuffle,
+    ShuffleDown,
+    ShuffleUp,
+    ShuffleXor,
+}
+
+impl SubgroupGather {
+    pub fn map(word: &str) -> Option<Self> {
+        Some(match word {
+            "subgroupBroadcastFirst" => Self::BroadcastFirst,
+            "subgroupBroadcast" => Self::Broadcast,
+            "subgroupShuffle" => Self::Shuffle,
+            "subgroupShuffleDown" => Self::ShuffleDown,
+            "subgroupShuffleUp" => Self::ShuffleUp,
+            "subgroupShuffleXor" => Self::ShuffleXor,
+            _ => return None,
+        })
+    }
+}
+
+/// Build the name of a generic function instance from the function name and
+/// the WGSL spelling of its type arguments, like `lerp_vec3_f32`.
+fn mangle_generic(name: &str, type_args: impl Iterator<Item = String>) -> String {
+    let mut mangled = name.to_string();
+    for ty in type_args {
+        mangled.push('_');
+        let mut separated = true;
+        for c in ty.chars() {
+            if c.is_ascii_alphanumeric() {
+                mangled.push(c);
+                separated = false;
+            } else if !separated {
+                mangled.push('_');
+                separated = true;
+            }
+        }
+        if separated {
+            mangled.pop();
+        }
+    }
+    mangled
+}
+
 impl Texture {
     pub fn map(word: &str) -> Option<Self> {
         Some(match word {
@@ -846,6 +896,74 @@ impl Texture {
 pub struct Lowerer<'source, 'temp> {
     index: &'temp Index<'source>,
     layouter: Layouter,
+
+    /// Generic function declarations, along with their declaration spans.
+    ///
+    /// These have no Naga IR equivalent until they are called. See
+    /// [`Lowerer::instantiate`].
+    generic_functions: FastHashMap<&'source str, (&'temp ast::Function<'source>, Span)>,
+
+    /// The Naga functions we have built for each generic function, keyed by
+    /// the function name and the types its generic parameters were bound to.
+    generic_instances:
+        FastHashMap<(&'source str, Vec<Handle<crate::Type>>), Handle<crate::Function>>,
+
+    /// The wgslx enums declared so far, keyed by name.
+    enums: FastHashMap<&'source str, LoweredEnum<'source>>,
+
+    /// Every wgslx method declaration in the module, keyed by name.
+    ///
+    /// A method is lowered when its declaration is reached or when it is
+    /// first called, whichever comes first. See [`Lowerer::find_method`].
+    method_decls: FastHashMap<&'source str, Vec<MethodDecl<'source, 'temp>>>,
+
+    /// The wgslx methods lowered so far, keyed by the type of their
+    /// receiver and their name.
+    methods: FastHashMap<(Handle<crate::Type>, &'source str), LoweredMethod>,
+
+    /// The methods currently being lowered, innermost last.
+    ///
+    /// Each entry holds the method's receiver type and name, and the method
+    /// call that caused it to be lowered, if any. This is how recursion
+    /// through method calls is detected, since [`Index`] can't see it.
+    lowering_methods: Vec<(Handle<crate::Type>, ast::Ident<'source>, Option<Span>)>,
+}
+
+/// A wgslx method declaration, as found in the translation unit.
+#[derive(Clone, Copy)]
+struct MethodDecl<'source, 'temp> {
+    function: &'temp ast::Function<'source>,
+    method: ast::Method<'source>,
+    span: Span,
+}
+
+/// A lowered wgslx method.
+///
+/// Methods lower to ordinary Naga functions that take the receiver as
+/// their first argument.
+#[derive(Clone, Copy)]
+struct LoweredMethod {
+    function: Handle<crate::Function>,
+
+    /// Whether the method takes `&mut self`, and thus expects a pointer to
+    /// the receiver.
+    mutable: bool,
+
+    /// The method's name in its declaration.
+    span: Span,
+}
+
+/// A lowered wgslx `enum`.
+///
+/// The enum's name itself is registered in [`GlobalContext::globals`] as its
+/// representation type. This holds what qualified variant names and
+/// `switch` statements need.
+struct LoweredEnum<'source> {
+    /// The enum's name in its declaration.
+    name: ast::Ident<'source>,
+
+    /// Each variant's name, along with the constant holding its value.
+    variants: Vec<(&'source str, Handle<crate::Constant>)>,
 }
 
 impl<'source, 'temp> Lowerer<'source, 'temp> {
@@ -853,6 +971,12 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
         Self {
             index,
             layouter: Layouter::default(),
+            generic_functions: FastHashMap::default(),
+            generic_instances: FastHashMap::default(),
+            enums: FastHashMap::default(),
+            method_decls: FastHashMap::default(),
+            methods: FastHashMap::default(),
+            lowering_methods: Vec::new(),
         }
     }
 
@@ -870,11 +994,52 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
             const_typifier: &mut Typifier::new(),
         };
 
+        for (handle, decl) in tu.decls.iter() {
+            if let ast::GlobalDeclKind::Fn(
+                ref function @ ast::Function {
+                    method: Some(method),
+                    ..
+                },
+            ) = decl.kind
+            {
+                self.method_decls
+                    .entry(function.name.name)
+                    .or_default()
+                    .push(MethodDecl {
+                        function,
+                        method,
+                        span: tu.decls.get_span(handle),
+                    });
+            }
+        }
+
         for decl_handle in self.index.visit_ordered() {
             let span = tu.decls.get_span(decl_handle);
             let decl = &tu.decls[decl_handle];
 
             match decl.kind {
+                ast::GlobalDeclKind::Fn(
+                    ref f @ ast::Function {
+                        method: Some(method),
+                        ..
+                    },
+                ) => {
+                    let decl = MethodDecl {
+                        function: f,
+                        method,
+                        span,
+                    };
+                    self.method(decl, None, &mut ctx)?;
+                }
+                ast::GlobalDeclKind::Fn(ref f) if !f.generics.is_empty() => {
+                    if f.entry_point.is_some() {
+                        return Err(Error::GenericEntryPoint(f.name.span));
+                    }
+
+                    self.generic_functions.insert(f.name.name, (f, span));
+                    ctx.globals
+                        .insert(f.name.name, LoweredGlobalDecl::GenericFunction);
+                }
                 ast::GlobalDeclKind::Fn(ref f) => {
                     let lowered_decl = self.function(f, span, &mut ctx)?;
                     ctx.globals.insert(f.name.name, lowered_decl);
@@ -968,6 +1133,10 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     ctx.globals
                         .insert(alias.name.name, LoweredGlobalDecl::Type(ty));
                 }
+                ast::GlobalDeclKind::Enum(ref e) => {
+                    let ty = self.r#enum(e, span, &mut ctx)?;
+                    ctx.globals.insert(e.name.name, LoweredGlobalDecl::Type(ty));
+                }
             }
         }
 
@@ -1001,7 +1170,16 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                 let expr = expressions
                     .append(crate::Expression::FunctionArgument(i as u32), span);
 
-                local_table.insert(arg.handle, Typed::Plain(expr));
+                // The `self` parameter of a `&mut self` method is a pointer,
+                // but we treat it as a reference, so that `self.field` works.
+                match f.method {
+                    Some(ast::Method { mutable: true, .. }) if i == 0 => {
+                        local_table.insert(arg.handle, Typed::Reference(expr));
+                    }
+                    _ => {
+                        local_table.insert(arg.handle, Typed::Plain(expr));
+                    }
+                }
                 named_expressions.insert(expr, (arg.name.name.to_string(), arg.name.span, ty));
 
                 Ok(crate::FunctionArgument {
@@ -1281,7 +1459,7 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     resolve_inner!(ectx, selector).scalar_kind() == Some(crate::ScalarKind::Uint);
                 block.extend(emitter.finish(&ctx.function.expressions));
 
-                let cases = cases
+                let mut lowered_cases = cases
                     .iter()
                     .map(|case| {
                         Ok(crate::SwitchCase {
@@ -1308,9 +1486,20 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                             fall_through: case.fall_through,
                         })
                     })
-                    .collect::<Result<_, _>>()?;
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if self.enum_switch_needs_default(cases, ctx)? {
+                    lowered_cases.push(crate::SwitchCase {
+                        value: crate::SwitchValue::Default,
+                        body: crate::Block::new(),
+                        fall_through: false,
+                    });
+                }
 
-                crate::Statement::Switch { selector, cases }
+                crate::Statement::Switch {
+                    selector,
+                    cases: lowered_cases,
+                }
             }
             ast::StatementKind::Loop {
                 ref body,
@@ -1363,6 +1552,24 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                 block.extend(emitter.finish(&ctx.function.expressions));
                 return Ok(());
             }
+            ast::StatementKind::MethodCall {
+                receiver,
+                ref method,
+                ref arguments,
+            } => {
+                let mut emitter = Emitter::default();
+                emitter.start(&ctx.function.expressions);
+
+                let _ = self.method_call(
+                    stmt.span,
+                    receiver,
+                    method,
+                    arguments,
+                    &mut ctx.as_expression(block, &mut emitter),
+                )?;
+                block.extend(emitter.finish(&ctx.function.expressions));
+                return Ok(());
+            }
             ast::StatementKind::Assign {
                 target: ast_target,
                 op,
@@ -1568,6 +1775,24 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
 
                 return expr.try_map(|handle| ctx.interrupt_emitter(handle, span));
             }
+            ast::Expression::EnumVariant {
+                ref ty,
+                ref variant,
+            } => {
+                let lowered = self.enums.get(ty.name).ok_or(Error::UnknownEnum(ty.span))?;
+                let handle = lowered
+                    .variants
+                    .iter()
+                    .find(|&&(name, _)| name == variant.name)
+                    .map(|&(_, handle)| handle)
+                    .ok_or(Error::UnknownEnumVariant {
+                        declaration: lowered.name.span,
+                        variant: variant.span,
+                    })?;
+
+                return Typed::Plain(crate::Expression::Constant(handle))
+                    .try_map(|handle| ctx.interrupt_emitter(handle, span));
+            }
             ast::Expression::Construct {
                 ref ty,
                 ty_span,
@@ -1577,7 +1802,9 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                 return Ok(Typed::Plain(handle));
             }
             ast::Expression::Unary { op, expr } => {
-                let expr = self.expression(expr, ctx)?;
+                // Negate abstract values before concretizing them, so that
+                // `-2147483648` fits in an `i32`.
+                let expr = self.expression_for_abstract(expr, ctx)?;
                 Typed::Plain(crate::Expression::Unary { op, expr })
             }
             ast::Expression::AddrOf(expr) => {
@@ -1620,6 +1847,16 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     .ok_or(Error::FunctionReturnsVoid(function.span))?;
                 return Ok(Typed::Plain(handle));
             }
+            ast::Expression::MethodCall {
+                receiver,
+                ref method,
+                ref arguments,
+            } => {
+                let handle = self
+                    .method_call(span, receiver, method, arguments, ctx)?
+                    .ok_or(Error::FunctionReturnsVoid(method.span))?;
+                return Ok(Typed::Plain(handle));
+            }
             ast::Expression::Index { base, index } => {
                 let lowered_base = self.expression_for_reference(base, ctx)?;
                 let index = self.expression(index, ctx)?;
@@ -1788,27 +2025,20 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     .map(|&arg| self.expression(arg, ctx))
                     .collect::<Result<Vec<_>, _>>()?;
 
-                let has_result = ctx.module.functions[function].result.is_some();
-                let rctx = ctx.runtime_expression_ctx(span)?;
-                // we need to always do this before a fn call since all arguments need to be emitted before the fn call
-                rctx.block
-                    .extend(rctx.emitter.finish(&rctx.function.expressions));
-                let result = has_result.then(|| {
-                    rctx.function
-                        .expressions
-                        .append(crate::Expression::CallResult(function), span)
-                });
-                rctx.emitter.start(&rctx.function.expressions);
-                rctx.block.push(
-                    crate::Statement::Call {
-                        function,
-                        arguments,
-                        result,
-                    },
-                    span,
-                );
+                self.call_function(span, function, arguments, ctx)
+            }
+            Some(&LoweredGlobalDecl::GenericFunction) => {
+                let arguments = arguments
+                    .iter()
+                    .map(|&arg| self.expression(arg, ctx))
+                    .collect::<Result<Vec<_>, _>>()?;
 
-                Ok(result)
+                // Calls are not allowed in constant expressions; don't bother
+                // instantiating anything in that case.
+                ctx.runtime_expression_ctx(span)?;
+                let function = self.instantiate(span, function, &arguments, ctx)?;
+
+                self.call_function(span, function, arguments, ctx)
             }
             None => {
                 let span = function.span;
@@ -1842,6 +2072,20 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     args.finish()?;
 
                     crate::Expression::Derivative { axis, ctrl, expr }
+                } else if let Some((op, collective_op)) =
+                    conv::map_subgroup_operation(function.name)
+                {
+                    return Ok(Some(self.subgroup_operation_helper(
+                        span,
+                        op,
+                        collective_op,
+                        arguments,
+                        ctx,
+                    )?));
+                } else if let Some(mode) = SubgroupGather::map(function.name) {
+                    return Ok(Some(
+                        self.subgroup_gather_helper(span, mode, arguments, ctx)?,
+                    ));
                 } else if let Some(fun) = conv::map_standard_fun(function.name) {
                     let expected = fun.argument_count() as _;
                     let mut args = ctx.prepare_args(arguments, expected, span);
@@ -2063,6 +2307,46 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                                 .push(crate::Statement::Barrier(crate::Barrier::WORK_GROUP), span);
                             return Ok(None);
                         }
+                        "subgroupBarrier" => {
+                            ctx.prepare_args(arguments, 0, span).finish()?;
+
+                            let rctx = ctx.runtime_expression_ctx(span)?;
+                            rctx.block
+                                .push(crate::Statement::Barrier(crate::Barrier::SUB_GROUP), span);
+                            return Ok(None);
+                        }
+                        "subgroupElect" => {
+                            ctx.prepare_args(arguments, 0, span).finish()?;
+
+                            let ty = ctx.ensure_type_exists(
+                                crate::TypeInner::Scalar(crate::Scalar::BOOL),
+                                span,
+                            );
+                            let result = ctx.interrupt_emitter(
+                                crate::Expression::SubgroupOperationResult { ty },
+                                span,
+                            )?;
+                            let rctx = ctx.runtime_expression_ctx(span)?;
+                            rctx.block
+                                .push(crate::Statement::SubgroupElect { result }, span);
+                            return Ok(Some(result));
+                        }
+                        "subgroupBallot" => {
+                            let mut args = ctx.prepare_args(arguments, 0, span);
+                            let predicate = if arguments.len() == 1 {
+                                Some(self.expression(args.next()?, ctx)?)
+                            } else {
+                                None
+                            };
+                            args.finish()?;
+
+                            let result = ctx
+                                .interrupt_emitter(crate::Expression::SubgroupBallotResult, span)?;
+                            let rctx = ctx.runtime_expression_ctx(span)?;
+                            rctx.block
+                                .push(crate::Statement::SubgroupBallot { result, predicate }, span);
+                            return Ok(Some(result));
+                        }
                         "workgroupUniformLoad" => {
                             let mut args = ctx.prepare_args(arguments, 1, span);
                             let expr = args.next()?;
@@ -2280,6 +2564,417 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
         }
     }
 
+    /// Emit a [`Call`] statement invoking `function` with the already lowered
+    /// `arguments`, returning the [`CallResult`] expression, if any.
+    ///
+    /// [`Call`]: crate::Statement::Call
+    /// [`CallResult`]: crate::Expression::CallResult
+    fn call_function(
+        &mut self,
+        span: Span,
+        function: Handle<crate::Function>,
+        arguments: Vec<Handle<crate::Expression>>,
+        ctx: &mut ExpressionContext<'source, '_, '_>,
+    ) -> Result<Option<Handle<crate::Expression>>, Error<'source>> {
+        let has_result = ctx.module.functions[function].result.is_some();
+        let rctx = ctx.runtime_expression_ctx(span)?;
+        // we need to always do this before a fn call since all arguments need to be emitted before the fn call
+        rctx.block
+            .extend(rctx.emitter.finish(&rctx.function.expressions));
+        let result = has_result.then(|| {
+            rctx.function
+                .expressions
+                .append(crate::Expression::CallResult(function), span)
+        });
+        rctx.emitter.start(&rctx.function.expressions);
+        rctx.block.push(
+            crate::Statement::Call {
+                function,
+                arguments,
+                result,
+            },
+            span,
+        );
+
+        Ok(result)
+    }
+
+    /// Lower a method call like `receiver.method(arguments)`.
+    ///
+    /// The method is looked up by the type of `receiver`. Methods taking
+    /// `&mut self` are passed a pointer to the receiver, which must be a
+    /// function-scope reference; other methods are passed its value.
+    fn method_call(
+        &mut self,
+        span: Span,
+        receiver: Handle<ast::Expression<'source>>,
+        method: &ast::Ident<'source>,
+        arguments: &[Handle<ast::Expression<'source>>],
+        ctx: &mut ExpressionContext<'source, '_, '_>,
+    ) -> Result<Option<Handle<crate::Expression>>, Error<'source>> {
+        let receiver_span = ctx.ast_expressions.get_span(receiver);
+        let receiver = self.expression_for_reference(receiver, ctx)?;
+
+        let (ty, space) = match receiver {
+            Typed::Reference(pointer) => match *resolve_inner!(ctx, pointer) {
+                crate::TypeInner::Pointer { base, space } => (Some(base), Some(space)),
+                _ => (None, None),
+            },
+            Typed::Plain(value) => (Some(ctx.register_type(value)?), None),
+        };
+        let lowered = match ty {
+            Some(ty) => self.find_method(ty, method, ctx)?,
+            None => None,
+        };
+        let Some(LoweredMethod {
+            function, mutable, ..
+        }) = lowered
+        else {
+            let ty = match (ty, receiver) {
+                (Some(ty), _) => ty,
+                (None, Typed::Reference(handle) | Typed::Plain(handle)) => {
+                    ctx.register_type(handle)?
+                }
+            };
+            return Err(Error::UnknownMethod {
+                method: method.span,
+                ty: ty.to_wgsl(&ctx.module.to_ctx()),
+            });
+        };
+
+        let receiver = match receiver {
+            Typed::Reference(pointer) if mutable => {
+                if space != Some(crate::AddressSpace::Function) {
+                    return Err(Error::MutatingMethodReceiver {
+                        method: method.span,
+                        receiver: receiver_span,
+                    });
+                }
+                pointer
+            }
+            Typed::Plain(_) if mutable => {
+                return Err(Error::MutatingMethodReceiver {
+                    method: method.span,
+                    receiver: receiver_span,
+                });
+            }
+            receiver => ctx.apply_load_rule(receiver, Some(receiver_span))?,
+        };
+
+        let mut lowered_arguments = Vec::with_capacity(arguments.len() + 1);
+        lowered_arguments.push(receiver);
+        for &argument in arguments {
+            lowered_arguments.push(self.expression(argument, ctx)?);
+        }
+
+        self.call_function(span, function, lowered_arguments, ctx)
+    }
+
+    /// Return the method `method` of the type `ty`, lowering it first if
+    /// this is the first call to it.
+    ///
+    /// Return `None` if `ty` has no such method, and an error if the method
+    /// is already being lowered, meaning that it calls itself.
+    fn find_method(
+        &mut self,
+        ty: Handle<crate::Type>,
+        method: &ast::Ident<'source>,
+        ctx: &mut ExpressionContext<'source, '_, '_>,
+    ) -> Result<Option<LoweredMethod>, Error<'source>> {
+        if let Some(&lowered) = self.methods.get(&(ty, method.name)) {
+            return Ok(Some(lowered));
+        }
+
+        if let Some(start) = self
+            .lowering_methods
+            .iter()
+            .position(|&(lowering_ty, name, _)| lowering_ty == ty && name.name == method.name)
+        {
+            let cycle = &self.lowering_methods[start..];
+            return Err(match *cycle {
+                [(_, ident, _)] => Error::RecursiveDeclaration {
+                    ident: ident.span,
+                    usage: method.span,
+                },
+                _ => Error::CyclicDeclaration {
+                    ident: cycle[0].1.span,
+                    path: cycle
+                        .iter()
+                        .zip(cycle[1..].iter().map(|&(_, _, call)| call))
+                        .map(|(&(_, ident, _), call)| (ident.span, call.unwrap_or(Span::UNDEFINED)))
+                        .chain(cycle.last().map(|&(_, ident, _)| (ident.span, method.span)))
+                        .collect(),
+                },
+            });
+        }
+
+        let globals = &ctx.globals;
+        let decl = self
+            .method_decls
+            .get(method.name)
+            .into_iter()
+            .flatten()
+            .find(|decl| {
+                matches!(
+                    globals.get(decl.method.ty.name),
+                    Some(&LoweredGlobalDecl::Type(handle)) if handle == ty
+                )
+            })
+            .copied();
+        match decl {
+            Some(decl) => self
+                .method(decl, Some(method.span), &mut ctx.as_global())
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Lower a method declared in an `impl` block, and record it so that
+    /// method calls on its receiver type can find it.
+    ///
+    /// If `call` is given, the method is being lowered early, because of a
+    /// call to it at that location. If an earlier call already lowered the
+    /// method, just return it.
+    fn method(
+        &mut self,
+        decl: MethodDecl<'source, 'temp>,
+        call: Option<Span>,
+        ctx: &mut GlobalContext<'source, '_, '_>,
+    ) -> Result<LoweredMethod, Error<'source>> {
+        let MethodDecl {
+            function: f,
+            method,
+            span,
+        } = decl;
+        if !f.generics.is_empty() {
+            return Err(Error::GenericMethod(f.name.span));
+        }
+
+        let ty = match ctx.globals.get(method.ty.name) {
+            Some(&LoweredGlobalDecl::Type(handle)) => handle,
+            Some(_) => return Err(Error::Unexpected(method.ty.span, ExpectedToken::Type)),
+            None => return Err(Error::UnknownType(method.ty.span)),
+        };
+        if let Some(&previous) = self.methods.get(&(ty, f.name.name)) {
+            if previous.span == f.name.span {
+                return Ok(previous);
+            }
+            return Err(Error::Redefinition {
+                previous: previous.span,
+                current: f.name.span,
+            });
+        }
+
+        self.lowering_methods.push((ty, f.name, call));
+        let lowered = self.function(f, span, ctx);
+        self.lowering_methods.pop();
+        let function = match lowered? {
+            LoweredGlobalDecl::Function(handle) => handle,
+            _ => return Err(Error::Internal("method lowered to a non-function")),
+        };
+        ctx.module.functions[function].name = Some(format!("{}_{}", method.ty.name, f.name.name));
+
+        let lowered = LoweredMethod {
+            function,
+            mutable: method.mutable,
+            span: f.name.span,
+        };
+        self.methods.insert((ty, f.name.name), lowered);
+
+        Ok(lowered)
+    }
+
+    /// Return the Naga function for the generic function `function`, as
+    /// called with `arguments`.
+    ///
+    /// The types of the generic parameters are inferred from the types of the
+    /// arguments. The first time a given set of parameter types is seen, the
+    /// generic function is lowered with each parameter name bound to its type,
+    /// producing a new Naga function whose name is mangled with those types.
+    /// Later calls with the same types reuse that function.
+    fn instantiate(
+        &mut self,
+        span: Span,
+        function: &ast::Ident<'source>,
+        arguments: &[Handle<crate::Expression>],
+        ctx: &mut ExpressionContext<'source, '_, '_>,
+    ) -> Result<Handle<crate::Function>, Error<'source>> {
+        let (f, decl_span) = self.generic_functions[function.name];
+
+        if f.arguments.len() != arguments.len() {
+            let expected = f.arguments.len() as u32;
+            return Err(Error::WrongArgumentCount {
+                span,
+                expected: expected..expected + 1,
+                found: arguments.len() as u32,
+            });
+        }
+
+        let mut bindings = FastHashMap::default();
+        for (argument, &value) in f.arguments.iter().zip(arguments) {
+            let value_ty = ctx.register_type(value)?;
+            self.infer_generic(f, argument.ty, value_ty, span, &mut bindings, ctx)?;
+        }
+
+        let mut type_args = Vec::with_capacity(f.generics.len());
+        for generic in f.generics.iter() {
+            let ty = *bindings
+                .get(generic.name.name)
+                .ok_or(Error::GenericNotInferred {
+                    param: generic.name.span,
+                    call: span,
+                })?;
+
+            if !generic.constraints.is_empty() {
+                let mut satisfied = false;
+                for &constraint in generic.constraints.iter() {
+                    let constraint = self.resolve_ast_type(constraint, &mut ctx.as_global())?;
+                    let types = &ctx.module.types;
+                    satisfied |= types[constraint].inner.equivalent(&types[ty].inner, types);
+                }
+
+                if !satisfied {
+                    return Err(Error::GenericConstraint {
+                        param: generic.name.span,
+                        call: span,
+                        ty: ty.to_wgsl(&ctx.module.to_ctx()),
+                    });
+                }
+            }
+
+            type_args.push(ty);
+        }
+
+        let key = (function.name, type_args);
+        if let Some(&handle) = self.generic_instances.get(&key) {
+            return Ok(handle);
+        }
+
+        let gctx = ctx.module.to_ctx();
+        let instance = format!(
+            "{}<{}>",
+            function.name,
+            key.1
+                .iter()
+                .map(|ty| ty.to_wgsl(&gctx))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let mangled = mangle_generic(function.name, key.1.iter().map(|ty| ty.to_wgsl(&gctx)));
+
+        // Bind each parameter name to its type for the duration of the
+        // instantiation, restoring whatever it shadowed afterwards.
+        let mut gctx = ctx.as_global();
+        let shadowed = f
+            .generics
+            .iter()
+            .zip(key.1.iter())
+            .map(|(generic, &ty)| {
+                let previous = gctx
+                    .globals
+                    .insert(generic.name.name, LoweredGlobalDecl::Type(ty));
+                (generic.name.name, previous)
+            })
+            .collect::<Vec<_>>();
+
+        let lowered = self.function(f, decl_span, &mut gctx);
+
+        for (name, previous) in shadowed {
+            match previous {
+                Some(previous) => gctx.globals.insert(name, previous),
+                None => gctx.globals.remove(name),
+            };
+        }
+
+        let handle = match lowered {
+            Ok(LoweredGlobalDecl::Function(handle)) => handle,
+            Ok(_) => {
+                return Err(Error::Internal(
+                    "generic function lowered to a non-function",
+                ))
+            }
+            Err(error) => {
+                return Err(Error::GenericInstantiation {
+                    error: Box::new(error),
+                    definition: f.name.span,
+                    call: span,
+                    instance,
+                })
+            }
+        };
+        ctx.module.functions[handle].name = Some(mangled);
+
+        self.generic_instances.insert(key, handle);
+        Ok(handle)
+    }
+
+    /// Bind the generic parameters of `f` that appear in the argument type
+    /// `ty` by matching it against `value_ty`, the type of the value passed
+    /// for that argument.
+    fn infer_generic(
+        &mut self,
+        f: &ast::Function<'source>,
+        ty: Handle<ast::Type<'source>>,
+        value_ty: Handle<crate::Type>,
+        span: Span,
+        bindings: &mut FastHashMap<&'source str, Handle<crate::Type>>,
+        ctx: &mut ExpressionContext<'source, '_, '_>,
+    ) -> Result<(), Error<'source>> {
+        match ctx.types[ty] {
+            ast::Type::User(ref ident) => {
+                let Some(generic) = f
+                    .generics
+                    .iter()
+                    .find(|generic| generic.name.name == ident.name)
+                else {
+                    return Ok(());
+                };
+
+                match bindings.get(ident.name) {
+                    None => {
+                        bindings.insert(ident.name, value_ty);
+                    }
+                    Some(&bound) => {
+                        let types = &ctx.module.types;
+                        if !types[bound].inner.equivalent(&types[value_ty].inner, types) {
+                            let gctx = ctx.module.to_ctx();
+                            return Err(Error::GenericConflict {
+                                param: generic.name.span,
+                                call: span,
+                                first: bound.to_wgsl(&gctx),
+                                second: value_ty.to_wgsl(&gctx),
+                            });
+                        }
+                    }
+                }
+            }
+            ast::Type::Pointer { base, .. } => {
+                if let crate::TypeInner::Pointer {
+                    base: value_base, ..
+                } = ctx.module.types[value_ty].inner
+                {
+                    self.infer_generic(f, base, value_base, span, bindings, ctx)?;
+                }
+            }
+            ast::Type::Array { base, .. } | ast::Type::BindingArray { base, .. } => {
+                match ctx.module.types[value_ty].inner {
+                    crate::TypeInner::Array {
+                        base: value_base, ..
+                    }
+                    | crate::TypeInner::BindingArray {
+                        base: value_base, ..
+                    } => {
+                        self.infer_generic(f, base, value_base, span, bindings, ctx)?;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
     fn atomic_pointer(
         &mut self,
         expr: Handle<ast::Expression<'source>>,
@@ -2340,6 +3035,88 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
         Ok(result)
     }
 
+    fn subgroup_operation_helper(
+        &mut self,
+        span: Span,
+        op: crate::SubgroupOperation,
+        collective_op: crate::CollectiveOperation,
+        args: &[Handle<ast::Expression<'source>>],
+        ctx: &mut ExpressionContext<'source, '_, '_>,
+    ) -> Result<Handle<crate::Expression>, Error<'source>> {
+        let mut args = ctx.prepare_args(args, 1, span);
+
+        let argument = self.expression(args.next()?, ctx)?;
+        args.finish()?;
+
+        let ty = ctx.register_type(argument)?;
+
+        let result =
+            ctx.interrupt_emitter(crate::Expression::SubgroupOperationResult { ty }, span)?;
+        let rctx = ctx.runtime_expression_ctx(span)?;
+        rctx.block.push(
+            crate::Statement::SubgroupCollectiveOperation {
+                op,
+                collective_op,
+                argument,
+                result,
+            },
+            span,
+        );
+        Ok(result)
+    }
+
+    fn subgroup_gather_helper(
+        &mut self,
+        span: Span,
+        mode: SubgroupGather,
+        args: &[Handle<ast::Expression<'source>>],
+        ctx: &mut ExpressionContext<'source, '_, '_>,
+    ) -> Result<Handle<crate::Expression>, Error<'source>> {
+        use SubgroupGather as Sg;
+
+        let expected = if let Sg::BroadcastFirst = mode { 1 } else { 2 };
+        let mut args = ctx.prepare_args(args, expected, span);
+
+        let argument = self.expression(args.next()?, ctx)?;
+
+        let mode = if let Sg::BroadcastFirst = mode {
+            crate::GatherMode::BroadcastFirst
+        } else {
+            // The index is a `u32`, but may be written as an abstract integer.
+            let index = self.expression_for_abstract(args.next()?, ctx)?;
+            let index = ctx.try_automatic_conversions(
+                index,
+                &crate::proc::TypeResolution::Value(crate::TypeInner::Scalar(crate::Scalar::U32)),
+                span,
+            )?;
+            match mode {
+                Sg::Broadcast => crate::GatherMode::Broadcast(index),
+                Sg::Shuffle => crate::GatherMode::Shuffle(index),
+                Sg::ShuffleDown => crate::GatherMode::ShuffleDown(index),
+                Sg::ShuffleUp => crate::GatherMode::ShuffleUp(index),
+                Sg::ShuffleXor => crate::GatherMode::ShuffleXor(index),
+                Sg::BroadcastFirst => unreachable!(),
+            }
+        };
+
+        args.finish()?;
+
+        let ty = ctx.register_type(argument)?;
+
+        let result =
+            ctx.interrupt_emitter(crate::Expression::SubgroupOperationResult { ty }, span)?;
+        let rctx = ctx.runtime_expression_ctx(span)?;
+        rctx.block.push(
+            crate::Statement::SubgroupGather {
+                mode,
+                argument,
+                result,
+            },
+            span,
+        );
+        Ok(result)
+    }
+
     fn texture_sample_helper(
         &mut self,
         fun: Texture,
@@ -2461,6 +3238,145 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
         })
     }
 
+    /// Lower a wgslx enum to a [`Constant`] for each of its variants, and
+    /// return its representation type.
+    ///
+    /// [`Constant`]: crate::Constant
+    fn r#enum(
+        &mut self,
+        e: &ast::Enum<'source>,
+        span: Span,
+        ctx: &mut GlobalContext<'source, '_, '_>,
+    ) -> Result<Handle<crate::Type>, Error<'source>> {
+        let scalar = e.repr.unwrap_or(crate::Scalar::U32);
+        let ty = ctx.ensure_type_exists(None, crate::TypeInner::Scalar(scalar), span);
+
+        let mut variants = Vec::with_capacity(e.variants.len());
+        let mut next = 0i64;
+        for variant in e.variants.iter() {
+            let init = match variant.value {
+                Some(value) => {
+                    let value_span = ctx.ast_expressions.get_span(value);
+                    let mut ectx = ctx.as_const();
+                    let init = self.expression_for_abstract(value, &mut ectx)?;
+                    let init = ectx.try_automatic_conversions(
+                        init,
+                        &crate::proc::TypeResolution::Handle(ty),
+                        value_span,
+                    )?;
+                    next = match ctx.module.to_ctx().eval_expr_to_literal(init) {
+                        Some(crate::Literal::U32(value)) => i64::from(value),
+                        Some(crate::Literal::I32(value)) => i64::from(value),
+                        _ => return Err(Error::ExpectedConstExprConcreteIntegerScalar(value_span)),
+                    };
+                    init
+                }
+                None => {
+                    let literal = match scalar.kind {
+                        crate::ScalarKind::Sint => {
+                            i32::try_from(next).ok().map(crate::Literal::I32)
+                        }
+                        _ => u32::try_from(next).ok().map(crate::Literal::U32),
+                    }
+                    .ok_or(Error::EnumValueOverflow(variant.name.span))?;
+                    ctx.as_const()
+                        .append_expression(crate::Expression::Literal(literal), variant.name.span)?
+                }
+            };
+            next += 1;
+
+            let handle = ctx.module.constants.append(
+                crate::Constant {
+                    name: Some(format!("{}_{}", e.name.name, variant.name.name)),
+                    r#override: crate::Override::None,
+                    ty,
+                    init,
+                },
+                variant.name.span,
+            );
+            variants.push((variant.name.name, handle));
+        }
+
+        self.enums.insert(
+            e.name.name,
+            LoweredEnum {
+                name: e.name,
+                variants,
+            },
+        );
+
+        Ok(ty)
+    }
+
+    /// Return true if `cases` switch over a wgslx enum without a `default`
+    /// clause, warning about any of its variants left unhandled.
+    ///
+    /// A switch is over an enum if one of its selectors is a qualified
+    /// variant like `LightKind::Spot`. Such switches may omit `default`, but
+    /// Naga IR requires every switch to have one, so the caller must add an
+    /// empty default case. Cases naming variants of more than one enum are
+    /// warned about too.
+    fn enum_switch_needs_default(
+        &self,
+        cases: &[ast::SwitchCase<'source>],
+        ctx: &StatementContext<'source, '_, '_>,
+    ) -> Result<bool, Error<'source>> {
+        let mut lowered: Option<&LoweredEnum<'source>> = None;
+        let mut has_default = false;
+        let mut handled = Vec::new();
+        for case in cases {
+            match case.value {
+                ast::SwitchValue::Default => has_default = true,
+                ast::SwitchValue::Expr(expr) => {
+                    if let ast::Expression::EnumVariant {
+                        ref ty,
+                        ref variant,
+                    } = ctx.ast_expressions[expr]
+                    {
+                        let Some(current) = self.enums.get(ty.name) else {
+                            return Err(Error::UnknownEnum(ty.span));
+                        };
+                        match lowered {
+                            Some(first) if first.name.span != current.name.span => {
+                                log::warn!(
+                                    "switch cases name variants of both `{}` and `{}`",
+                                    first.name.name,
+                                    current.name.name
+                                );
+                            }
+                            Some(_) => {}
+                            None => lowered = Some(current),
+                        }
+                        handled.push(variant.name);
+                    }
+                }
+            }
+        }
+
+        let Some(lowered) = lowered else {
+            return Ok(false);
+        };
+        if has_default {
+            return Ok(false);
+        }
+
+        let missing: Vec<_> = lowered
+            .variants
+            .iter()
+            .filter(|&&(name, _)| !handled.contains(&name))
+            .map(|&(name, _)| format!("`{}::{name}`", lowered.name.name))
+            .collect();
+        if !missing.is_empty() {
+            log::warn!(
+                "switch over enum `{}` does not handle {}",
+                lowered.name.name,
+                missing.join(", ")
+            );
+        }
+
+        Ok(true)
+    }
+
     fn r#struct(
         &mut self,
         s: &ast::Struct<'source>,
@@ -23,10 +23,10 @@ use thiserror::Error;
 
 pub use crate::front::wgsl::error::ParseError;
 use crate::front::wgsl::lower::Lowerer;
-use crate::{Scalar, Span};
+use crate::Scalar;
 
 use self::parse::ast::{self};
-use self::source_provider::{File, SourceProvider};
+use self::source_provider::{File, SingleFile, SourceProvider};
 
 
 pub struct Frontend {
@@ -40,19 +40,15 @@ impl Frontend {
         }
     }
 
-    // fn inner<'a>(&mut self, source: &'a str) -> Result<crate::Module, Error<'a>> {
-    //     let mut tu = ast::TranslationUnit::default();
-
-    //     self.parser.parse(&mut tu, source, 0)?; 
-        
-    //     let index = index::Index::generate(&tu)?;
-    //     let module = Lowerer::new(&index).lower(&tu)?;
-
-    //     Ok(module)
-    // }
+    #[cfg(test)]
+    fn inner<'a>(&mut self, source: &'a str) -> Result<crate::Module, Error<'a>> {
+        let mut tu = ast::TranslationUnit::default();
+        self.parser.parse(&mut tu, source, SingleFile::ID)?;
+        lower(&tu)
+    }
 
-    pub fn parse(&mut self, _source: &str) -> Result<crate::Module, ParseError> {
-        todo!()
+    pub fn parse(&mut self, source: &str) -> Result<crate::Module, ParseError> {
+        parse_str(source)
     }
 
     pub fn parse_into<'a>(
@@ -79,8 +75,10 @@ pub fn parse_module<'a>(provider: &'a impl SourceProvider<'a>, id: FileId) -> Re
 }
 
 
-pub fn parse_str(_source: &str) -> Result<crate::Module, ParseError> {
-    todo!()
+/// Parse a module from a single file, which can't import other files.
+pub fn parse_str(source: &str) -> Result<crate::Module, ParseError> {
+    let file = SingleFile::new("wgsl", source);
+    parse_module(&file, SingleFile::ID)
 }
 
 // Returns translation units in depth-first order
@@ -89,11 +87,11 @@ fn parse_translation_unit<'a>(
     file_id: FileId,
 ) -> Result<ast::TranslationUnit<'a>, ParseError> {
     let mut handled = HashSet::new(); 
-    let mut stack = vec![(file_id, Span::new(0, 0, None))];
+    let mut stack = vec![file_id];
 
     let mut translation_unit = ast::TranslationUnit::default(); 
 
-    while let Some((file_id, span)) = stack.pop() {
+    while let Some(file_id) = stack.pop() {
         // Some temporary state specific only to the current file is added to the translation
         // unit on each parse. We only want to capture the global state.
         translation_unit.reset();
@@ -111,10 +109,10 @@ fn parse_translation_unit<'a>(
             }
 
             let file_id = provider.visit(&path)
-                .ok_or(Error::BadPath { span })
+                .ok_or(Error::BadPath { span: import.span })
                 .map_err(|x| x.as_parse_error(provider))?; 
 
-            stack.push((file_id, import.span));
+            stack.push(file_id);
             handled.insert(path); 
         }
     }
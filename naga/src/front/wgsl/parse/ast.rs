@@ -73,8 +73,8 @@ pub enum IdentExpr<'a> {
 ///
 /// Each [`GlobalDecl`] holds a set of these values, to be resolved to
 /// specific definitions later. To support de-duplication, `Eq` and
-/// `Hash` on a `Dependency` value consider only the name, not the
-/// source location at which the reference occurs.
+/// `Hash` on a `Dependency` value consider only the name and whether it
+/// names a method, not the source location at which the reference occurs.
 #[derive(Debug)]
 pub struct Dependency<'a> {
     /// The name referred to.
@@ -82,17 +82,26 @@ pub struct Dependency<'a> {
 
     /// The location at which the reference to that name occurs.
     pub usage: Span,
+
+    /// Whether this is a wgslx method call, like `ray.at(t)`, rather than a
+    /// plain reference.
+    ///
+    /// We don't know the receiver's type at parse time, so a method call
+    /// depends on whatever the methods with the given name use. See
+    /// `Index::generate`.
+    pub method: bool,
 }
 
 impl Hash for Dependency<'_> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.ident.hash(state);
+        self.method.hash(state);
     }
 }
 
 impl PartialEq for Dependency<'_> {
     fn eq(&self, other: &Self) -> bool {
-        self.ident == other.ident
+        self.ident == other.ident && self.method == other.method
     }
 }
 
@@ -115,6 +124,7 @@ pub enum GlobalDeclKind<'a> {
     Const(Const<'a>),
     Struct(Struct<'a>),
     Type(TypeAlias<'a>),
+    Enum(Enum<'a>),
 }
 
 #[derive(Debug)]
@@ -143,10 +153,50 @@ pub struct EntryPoint<'a> {
 #[cfg(doc)]
 use crate::front::wgsl::lower::{RuntimeExpressionContext, StatementContext};
 
+/// A wgslx generic type parameter, like the `T` in `fn lerp<T: f32 | vec3f>`.
+#[derive(Debug)]
+pub struct GenericParameter<'a> {
+    pub name: Ident<'a>,
+
+    /// The types this parameter may be instantiated with.
+    ///
+    /// An empty list means the parameter is unconstrained.
+    pub constraints: Vec<Handle<Type<'a>>>,
+}
+
+/// The `impl` block a wgslx method is declared in.
+#[derive(Debug, Copy, Clone)]
+pub struct Method<'a> {
+    /// The type named by the `impl` block.
+    pub ty: Ident<'a>,
+
+    /// Whether the method takes `&mut self`.
+    ///
+    /// If so, the method's first argument is a `ptr<function, T>`. Otherwise,
+    /// it takes `self` by value.
+    pub mutable: bool,
+}
+
 #[derive(Debug)]
 pub struct Function<'a> {
     pub entry_point: Option<EntryPoint<'a>>,
     pub name: Ident<'a>,
+
+    /// Wgslx extension, the `impl` block this function is a method of.
+    ///
+    /// A method's first argument is always its `self` parameter. Methods are
+    /// not module-scope names, and can only be called with method call
+    /// syntax.
+    pub method: Option<Method<'a>>,
+
+    /// Wgslx extension, generic type parameters.
+    ///
+    /// A function with generic parameters is not lowered where it is declared.
+    /// Instead, each distinct set of argument types it is called with produces
+    /// a separate, monomorphised Naga [`Function`].
+    ///
+    /// [`Function`]: crate::Function
+    pub generics: Vec<GenericParameter<'a>>,
     pub arguments: Vec<FunctionArgument<'a>>,
     pub result: Option<FunctionResult<'a>>,
 
@@ -220,6 +270,31 @@ pub struct Struct<'a> {
     pub members: Vec<StructMember<'a>>,
 }
 
+/// A wgslx `enum` declaration, like `enum LightKind: u32 { Point, Spot = 4 }`.
+///
+/// Enums have no Naga IR equivalent: the enum's name lowers to its
+/// representation type, and each variant lowers to a [`Constant`] of that
+/// type.
+///
+/// [`Constant`]: crate::Constant
+#[derive(Debug)]
+pub struct Enum<'a> {
+    pub name: Ident<'a>,
+
+    /// The integer type the enum is represented as. Defaults to `u32`.
+    pub repr: Option<Scalar>,
+    pub variants: Vec<EnumVariant<'a>>,
+}
+
+#[derive(Debug)]
+pub struct EnumVariant<'a> {
+    pub name: Ident<'a>,
+
+    /// The variant's explicit value, if any. Variants without one take
+    /// the value of the previous variant plus one, starting from zero.
+    pub value: Option<Handle<Expression<'a>>>,
+}
+
 #[derive(Debug)]
 pub struct TypeAlias<'a> {
     pub name: Ident<'a>,
@@ -325,6 +400,11 @@ pub enum StatementKind<'a> {
         function: Ident<'a>,
         arguments: Vec<Handle<Expression<'a>>>,
     },
+    MethodCall {
+        receiver: Handle<Expression<'a>>,
+        method: Ident<'a>,
+        arguments: Vec<Handle<Expression<'a>>>,
+    },
     Assign {
         target: Handle<Expression<'a>>,
         op: Option<crate::BinaryOperator>,
@@ -480,6 +560,16 @@ pub enum Expression<'a> {
         function: Ident<'a>,
         arguments: Vec<Handle<Expression<'a>>>,
     },
+
+    /// A wgslx method call, like `ray.at(t)`.
+    ///
+    /// Which function this calls depends on the type of `receiver`, so it is
+    /// resolved during lowering.
+    MethodCall {
+        receiver: Handle<Expression<'a>>,
+        method: Ident<'a>,
+        arguments: Vec<Handle<Expression<'a>>>,
+    },
     Index {
         base: Handle<Expression<'a>>,
         index: Handle<Expression<'a>>,
@@ -493,6 +583,12 @@ pub enum Expression<'a> {
         to: Handle<Type<'a>>,
         ty_span: Span,
     },
+
+    /// A qualified enum variant, like `LightKind::Spot`.
+    EnumVariant {
+        ty: Ident<'a>,
+        variant: Ident<'a>,
+    },
 }
 
 #[derive(Debug)]
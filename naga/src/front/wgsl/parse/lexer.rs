@@ -119,7 +119,6 @@ fn consume_token(input: &str, generic: bool) -> (Token<'_>, &str) {
             let og_chars = chars.as_str();
             match chars.next() {
                 Some('>') => (Token::Arrow, chars.as_str()),
-                Some('0'..='9' | '.') => consume_number(input),
                 Some('-') => (Token::DecrementOperation, chars.as_str()),
                 Some('=') => (Token::AssignmentOperation(cur), chars.as_str()),
                 _ => (Token::Operation(cur), og_chars),
@@ -364,6 +363,20 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    /// If the next two tokens form a `::` path separator, skip them and
+    /// return true.
+    pub(in crate::front::wgsl) fn skip_path_separator(&mut self) -> bool {
+        let mut lookahead = self.clone();
+        if lookahead.next().0 == Token::Separator(':')
+            && lookahead.next().0 == Token::Separator(':')
+        {
+            *self = lookahead;
+            true
+        } else {
+            false
+        }
+    }
+
     pub(in crate::front::wgsl) fn next_ident_with_span(
         &mut self,
     ) -> Result<(&'a str, Span), Error<'a>> {
@@ -526,13 +539,16 @@ fn test_numbers() {
 
     // MIN / MAX //
 
+    // A minus sign is never part of a literal, so `i32::MIN` can't be
+    // written as one.
+
     // min / max decimal signed integer
     sub_test(
-        "-2147483648i 2147483647i -2147483649i 2147483648i",
+        "-2147483648i 2147483647i 2147483648i",
         &[
-            Token::Number(Ok(Number::I32(i32::MIN))),
-            Token::Number(Ok(Number::I32(i32::MAX))),
+            Token::Operation('-'),
             Token::Number(Err(NumberError::NotRepresentable)),
+            Token::Number(Ok(Number::I32(i32::MAX))),
             Token::Number(Err(NumberError::NotRepresentable)),
         ],
     );
@@ -542,18 +558,19 @@ fn test_numbers() {
         &[
             Token::Number(Ok(Number::U32(u32::MIN))),
             Token::Number(Ok(Number::U32(u32::MAX))),
-            Token::Number(Err(NumberError::NotRepresentable)),
+            Token::Operation('-'),
+            Token::Number(Ok(Number::U32(1))),
             Token::Number(Err(NumberError::NotRepresentable)),
         ],
     );
 
     // min / max hexadecimal signed integer
     sub_test(
-        "-0x80000000i 0x7FFFFFFFi -0x80000001i 0x80000000i",
+        "-0x80000000i 0x7FFFFFFFi 0x80000000i",
         &[
-            Token::Number(Ok(Number::I32(i32::MIN))),
-            Token::Number(Ok(Number::I32(i32::MAX))),
+            Token::Operation('-'),
             Token::Number(Err(NumberError::NotRepresentable)),
+            Token::Number(Ok(Number::I32(i32::MAX))),
             Token::Number(Err(NumberError::NotRepresentable)),
         ],
     );
@@ -563,7 +580,8 @@ fn test_numbers() {
         &[
             Token::Number(Ok(Number::U32(u32::MIN))),
             Token::Number(Ok(Number::U32(u32::MAX))),
-            Token::Number(Err(NumberError::NotRepresentable)),
+            Token::Operation('-'),
+            Token::Number(Ok(Number::U32(1))),
             Token::Number(Err(NumberError::NotRepresentable)),
         ],
     );
@@ -578,8 +596,6 @@ fn test_numbers() {
     const LARGEST_F32_LESS_THAN_ONE: f32 = 0.99999994;
     /// ≈ 1 + 2^−23
     const SMALLEST_F32_LARGER_THAN_ONE: f32 = 1.0000001;
-    /// ≈ -(2^127 * (2 − 2^−23))
-    const SMALLEST_NORMAL_F32: f32 = f32::MIN;
     /// ≈ 2^127 * (2 − 2^−23)
     const LARGEST_NORMAL_F32: f32 = f32::MAX;
 
@@ -602,8 +618,9 @@ fn test_numbers() {
             Token::Number(Ok(Number::F32(
                 SMALLEST_F32_LARGER_THAN_ONE,
             ))),
+            Token::Operation('-'),
             Token::Number(Ok(Number::F32(
-                SMALLEST_NORMAL_F32,
+                LARGEST_NORMAL_F32,
             ))),
             Token::Number(Ok(Number::F32(
                 LARGEST_NORMAL_F32,
@@ -613,7 +630,8 @@ fn test_numbers() {
     sub_test(
         "-3.40282367e+38f 3.40282367e+38f",
         &[
-            Token::Number(Err(NumberError::NotRepresentable)), // ≈ -2^128
+            Token::Operation('-'),
+            Token::Number(Err(NumberError::NotRepresentable)), // ≈ 2^128
             Token::Number(Err(NumberError::NotRepresentable)), // ≈ 2^128
         ],
     );
@@ -637,8 +655,9 @@ fn test_numbers() {
             Token::Number(Ok(Number::F32(
                 SMALLEST_F32_LARGER_THAN_ONE,
             ))),
+            Token::Operation('-'),
             Token::Number(Ok(Number::F32(
-                SMALLEST_NORMAL_F32,
+                LARGEST_NORMAL_F32,
             ))),
             Token::Number(Ok(Number::F32(
                 LARGEST_NORMAL_F32,
@@ -648,7 +667,8 @@ fn test_numbers() {
     sub_test(
         "-0x1p128f 0x1p128f 0x1.000001p0f",
         &[
-            Token::Number(Err(NumberError::NotRepresentable)), // = -2^128
+            Token::Operation('-'),
+            Token::Number(Err(NumberError::NotRepresentable)), // = 2^128
             Token::Number(Err(NumberError::NotRepresentable)), // = 2^128
             Token::Number(Err(NumberError::NotRepresentable)),
         ],
@@ -744,6 +764,34 @@ fn test_tokens() {
     )
 }
 
+#[test]
+fn test_minus() {
+    // A minus sign is always an operator, whatever surrounds it.
+    sub_test(
+        "x-1 x -1 f()-1.5 return-1 x*-.5",
+        &[
+            Token::Word("x"),
+            Token::Operation('-'),
+            Token::Number(Ok(Number::AbstractInt(1))),
+            Token::Word("x"),
+            Token::Operation('-'),
+            Token::Number(Ok(Number::AbstractInt(1))),
+            Token::Word("f"),
+            Token::Paren('('),
+            Token::Paren(')'),
+            Token::Operation('-'),
+            Token::Number(Ok(Number::AbstractFloat(1.5))),
+            Token::Word("return"),
+            Token::Operation('-'),
+            Token::Number(Ok(Number::AbstractInt(1))),
+            Token::Word("x"),
+            Token::Operation('*'),
+            Token::Operation('-'),
+            Token::Number(Ok(Number::AbstractFloat(0.5))),
+        ],
+    );
+}
+
 #[test]
 fn test_variable_decl() {
     sub_test(
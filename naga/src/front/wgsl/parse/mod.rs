@@ -572,6 +572,7 @@ impl Parser {
                 ctx.unresolved.insert(ast::Dependency {
                     ident: name,
                     usage: name_span,
+                    method: false,
                 });
                 ast::Expression::Call {
                     function: ast::Ident {
@@ -600,6 +601,7 @@ impl Parser {
                 ctx.unresolved.insert(ast::Dependency {
                     ident: name,
                     usage: name_span,
+                    method: false,
                 });
                 ast::IdentExpr::Unresolved(name)
             }
@@ -650,7 +652,18 @@ impl Parser {
                 let start = lexer.start_byte_offset();
                 let _ = lexer.next();
 
-                if let Some(ty) = self.constructor_type(lexer, word, span, ctx)? {
+                if lexer.skip_path_separator() {
+                    let variant = lexer.next_ident()?;
+                    ctx.unresolved.insert(ast::Dependency {
+                        ident: word,
+                        usage: span,
+                        method: false,
+                    });
+                    ast::Expression::EnumVariant {
+                        ty: ast::Ident { name: word, span },
+                        variant,
+                    }
+                } else if let Some(ty) = self.constructor_type(lexer, word, span, ctx)? {
                     let ty_span = lexer.span_from(start);
                     let components = self.arguments(lexer, ctx)?;
                     ast::Expression::Construct {
@@ -692,7 +705,21 @@ impl Parser {
                     let _ = lexer.next();
                     let field = lexer.next_ident()?;
 
-                    ast::Expression::Member { base: expr, field }
+                    if let Token::Paren('(') = lexer.peek().0 {
+                        ctx.unresolved.insert(ast::Dependency {
+                            ident: field.name,
+                            usage: field.span,
+                            method: true,
+                        });
+                        let arguments = self.arguments(lexer, ctx)?;
+                        ast::Expression::MethodCall {
+                            receiver: expr,
+                            method: field,
+                            arguments,
+                        }
+                    } else {
+                        ast::Expression::Member { base: expr, field }
+                    }
                 }
                 Token::Paren('[') => {
                     let _ = lexer.next();
@@ -1038,6 +1065,47 @@ impl Parser {
         Ok(members)
     }
 
+    fn enum_body<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        ctx: &mut ExpressionContext<'a, '_, '_>,
+    ) -> Result<Vec<ast::EnumVariant<'a>>, Error<'a>> {
+        let mut variants: Vec<ast::EnumVariant<'a>> = Vec::new();
+
+        lexer.expect(Token::Paren('{'))?;
+        let mut ready = true;
+        while !lexer.skip(Token::Paren('}')) {
+            if !ready {
+                return Err(Error::Unexpected(
+                    lexer.next().1,
+                    ExpectedToken::Token(Token::Separator(',')),
+                ));
+            }
+
+            let name = lexer.next_ident()?;
+            if let Some(previous) = variants
+                .iter()
+                .find(|variant| variant.name.name == name.name)
+            {
+                return Err(Error::Redefinition {
+                    previous: previous.name.span,
+                    current: name.span,
+                });
+            }
+
+            let value = if lexer.skip(Token::Operation('=')) {
+                Some(self.general_expression(lexer, ctx)?)
+            } else {
+                None
+            };
+            ready = lexer.skip(Token::Separator(','));
+
+            variants.push(ast::EnumVariant { name, value });
+        }
+
+        Ok(variants)
+    }
+
     fn matrix_scalar_type<'a>(
         &mut self,
         lexer: &mut Lexer<'a>,
@@ -1472,6 +1540,7 @@ impl Parser {
                 ctx.unresolved.insert(ast::Dependency {
                     ident: name,
                     usage: span,
+                    method: false,
                 });
                 ast::Type::User(ast::Ident { name, span })
             }
@@ -1552,6 +1621,26 @@ impl Parser {
     ) -> Result<(), Error<'a>> {
         let span_start = lexer.start_byte_offset();
         let target = self.general_expression(lexer, ctx)?;
+
+        // A method call can't be assigned to, so this must be a method call
+        // statement.
+        if let ast::Expression::MethodCall {
+            receiver,
+            method,
+            ref arguments,
+        } = ctx.expressions[target]
+        {
+            block.stmts.push(ast::Statement {
+                kind: ast::StatementKind::MethodCall {
+                    receiver,
+                    method,
+                    arguments: arguments.clone(),
+                },
+                span: lexer.span_from(span_start),
+            });
+            return Ok(());
+        }
+
         self.assignment_op_and_rhs(lexer, ctx, block, target, span_start)
     }
 
@@ -1571,6 +1660,7 @@ impl Parser {
         context.unresolved.insert(ast::Dependency {
             ident,
             usage: ident_span,
+            method: false,
         });
         let arguments = self.arguments(lexer, context)?;
         let span = lexer.span_from(span_start);
@@ -2077,10 +2167,55 @@ impl Parser {
         bind_parser.finish(span)
     }
 
+    /// Parse the optional generic parameter list of a function declaration,
+    /// for example `<T, U: f32 | vec3f>`.
+    fn generic_parameters<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        ctx: &mut ExpressionContext<'a, '_, '_>,
+    ) -> Result<Vec<ast::GenericParameter<'a>>, Error<'a>> {
+        let mut generics = Vec::new();
+        if !lexer.skip(Token::Paren('<')) {
+            return Ok(generics);
+        }
+
+        loop {
+            let name = lexer.next_ident()?;
+            if let Some(previous) = generics
+                .iter()
+                .find(|generic: &&ast::GenericParameter| generic.name.name == name.name)
+            {
+                return Err(Error::Redefinition {
+                    previous: previous.name.span,
+                    current: name.span,
+                });
+            }
+
+            let mut constraints = Vec::new();
+            if lexer.skip(Token::Separator(':')) {
+                loop {
+                    constraints.push(self.type_decl(lexer, ctx)?.0);
+                    if !lexer.skip(Token::Operation('|')) {
+                        break;
+                    }
+                }
+            }
+            generics.push(ast::GenericParameter { name, constraints });
+
+            if !lexer.skip(Token::Separator(',')) || lexer.peek().0 == Token::Paren('>') {
+                break;
+            }
+        }
+        lexer.expect_generic_paren('>')?;
+
+        Ok(generics)
+    }
+
     fn function_decl<'a>(
         &mut self,
         lexer: &mut Lexer<'a>,
         out: &mut ast::TranslationUnit<'a>,
+        impl_ty: Option<ast::Ident<'a>>,
         dependencies: &mut FastIndexSet<ast::Dependency<'a>>,
     ) -> Result<ast::Function<'a>, Error<'a>> {
         self.push_rule_span(Rule::FunctionDecl, lexer);
@@ -2097,13 +2232,19 @@ impl Parser {
             unresolved: dependencies,
         };
 
+        let generics = self.generic_parameters(lexer, &mut ctx)?;
+
         // start a scope that contains arguments as well as the function body
         ctx.local_table.push_scope();
 
         // read parameter list
         let mut arguments = Vec::new();
         lexer.expect(Token::Paren('('))?;
-        let mut ready = true;
+        let method = match impl_ty {
+            Some(ty) => Some(self.method_receiver(lexer, ty, &mut ctx, &mut arguments)?),
+            None => None,
+        };
+        let mut ready = method.is_none() || lexer.skip(Token::Separator(','));
         while !lexer.skip(Token::Paren(')')) {
             if !ready {
                 return Err(Error::Unexpected(
@@ -2146,9 +2287,19 @@ impl Parser {
 
         ctx.local_table.pop_scope();
 
+        // Generic parameters are resolved per instantiation, they are not
+        // module-scope declarations.
+        ctx.unresolved.retain(|dep| {
+            !generics
+                .iter()
+                .any(|generic| generic.name.name == dep.ident)
+        });
+
         let fun = ast::Function {
             entry_point: None,
             name: fun_name,
+            method,
+            generics,
             arguments,
             result,
             body,
@@ -2161,6 +2312,56 @@ impl Parser {
         Ok(fun)
     }
 
+    /// Parse the `self` or `&mut self` parameter of a method in an `impl`
+    /// block for `ty`, and add it to `arguments`.
+    fn method_receiver<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        ty: ast::Ident<'a>,
+        ctx: &mut ExpressionContext<'a, '_, '_>,
+        arguments: &mut Vec<ast::FunctionArgument<'a>>,
+    ) -> Result<ast::Method<'a>, Error<'a>> {
+        let start = lexer.start_byte_offset();
+        let mutable = if lexer.skip(Token::Operation('&')) {
+            lexer.expect(Token::Word("mut"))?;
+            true
+        } else {
+            false
+        };
+        let name = ast::Ident {
+            name: "self",
+            span: lexer.expect_span(Token::Word("self"))?,
+        };
+        let receiver_span = lexer.span_from(start);
+
+        ctx.unresolved.insert(ast::Dependency {
+            ident: ty.name,
+            usage: ty.span,
+            method: false,
+        });
+        let mut receiver_ty = ctx.types.append(ast::Type::User(ty), Span::UNDEFINED);
+        if mutable {
+            receiver_ty = ctx.types.append(
+                ast::Type::Pointer {
+                    base: receiver_ty,
+                    space: crate::AddressSpace::Function,
+                },
+                Span::UNDEFINED,
+            );
+        }
+
+        let handle = ctx.declare_local(name)?;
+        arguments.push(ast::FunctionArgument {
+            name,
+            ty: receiver_ty,
+            ty_span: receiver_span,
+            binding: None,
+            handle,
+        });
+
+        Ok(ast::Method { ty, mutable })
+    }
+
     fn global_decl<'a>(
         &mut self,
         lexer: &mut Lexer<'a>,
@@ -2269,6 +2470,52 @@ impl Parser {
                 let members = self.struct_body(lexer, &mut ctx)?;
                 Some(ast::GlobalDeclKind::Struct(ast::Struct { name, members }))
             }
+            (Token::Word("enum"), _) => {
+                let name = lexer.next_ident()?;
+
+                let repr = if lexer.skip(Token::Separator(':')) {
+                    let (word, span) = lexer.next_ident_with_span()?;
+                    match conv::get_scalar_type(word) {
+                        Some(
+                            scalar @ Scalar {
+                                kind: crate::ScalarKind::Sint | crate::ScalarKind::Uint,
+                                width: 4,
+                            },
+                        ) => Some(scalar),
+                        _ => return Err(Error::InvalidEnumRepr(span)),
+                    }
+                } else {
+                    None
+                };
+
+                let variants = self.enum_body(lexer, &mut ctx)?;
+                Some(ast::GlobalDeclKind::Enum(ast::Enum {
+                    name,
+                    repr,
+                    variants,
+                }))
+            }
+            (Token::Word("impl"), _) => {
+                let ty = lexer.next_ident()?;
+
+                // Each method is a separate declaration, with its own
+                // dependencies.
+                lexer.expect(Token::Paren('{'))?;
+                while !lexer.skip(Token::Paren('}')) {
+                    let start = lexer.start_byte_offset();
+                    lexer.expect(Token::Word("fn"))?;
+                    let mut dependencies = FastIndexSet::default();
+                    let function = self.function_decl(lexer, out, Some(ty), &mut dependencies)?;
+                    out.decls.append(
+                        ast::GlobalDecl {
+                            kind: ast::GlobalDeclKind::Fn(function),
+                            dependencies,
+                        },
+                        lexer.span_from(start),
+                    );
+                }
+                None
+            }
             (Token::Word("alias"), _) => {
                 let name = lexer.next_ident()?;
 
@@ -2299,7 +2546,7 @@ impl Parser {
                 Some(ast::GlobalDeclKind::Var(var))
             }
             (Token::Word("fn"), _) => {
-                let function = self.function_decl(lexer, out, &mut dependencies)?;
+                let function = self.function_decl(lexer, out, None, &mut dependencies)?;
                 Some(ast::GlobalDeclKind::Fn(ast::Function {
                     entry_point: if let Some(stage) = stage.value {
                         if stage == ShaderStage::Compute && workgroup_size.value.is_none() {
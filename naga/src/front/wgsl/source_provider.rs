@@ -1,7 +1,8 @@
+use std::cell::RefCell;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 
-use crate::Span;
+use crate::{FastHashMap, Span};
 
 pub use codespan_reporting::files::{Files, self, line_starts};
 pub use codespan_reporting::files::*;
@@ -98,3 +99,142 @@ impl File {
         Ok(line_start..next_line_start)
     }
 }
+
+/// A [`SourceProvider`] holding a single in-memory file, which can't import
+/// other files.
+///
+/// This is what [`parse_str`](super::parse_str) parses with.
+#[derive(Debug, Clone)]
+pub struct SingleFile(File);
+
+impl SingleFile {
+    /// The id of the file.
+    pub const ID: FileId = 0;
+
+    pub fn new(path: impl Into<PathBuf>, source: impl Into<String>) -> Self {
+        Self(File::new(Self::ID, path.into(), source.into()))
+    }
+
+    pub const fn file(&self) -> &File {
+        &self.0
+    }
+}
+
+impl SourceProvider<'_> for SingleFile {
+    fn visit(&self, _path: impl AsRef<Path>) -> Option<FileId> {
+        None
+    }
+
+    fn get(&self, id: FileId) -> Option<&File> {
+        (id == Self::ID).then_some(&self.0)
+    }
+}
+
+impl<'a> Files<'a> for SingleFile {
+    type FileId = FileId;
+    type Name = &'a str;
+    type Source = &'a str;
+
+    fn name(&'a self, _id: FileId) -> Result<&'a str, Error> {
+        Ok(self.0.name())
+    }
+
+    fn source(&'a self, _id: FileId) -> Result<&'a str, Error> {
+        Ok(self.0.source())
+    }
+
+    fn line_index(&'a self, _id: FileId, byte_index: usize) -> Result<usize, Error> {
+        self.0.line_index((), byte_index)
+    }
+
+    fn line_range(&'a self, _id: FileId, line_index: usize) -> Result<Range<usize>, Error> {
+        self.0.line_range((), line_index)
+    }
+}
+
+/// A [`SourceProvider`] reading files from disk, with paths relative to a
+/// root directory.
+///
+/// Files are read the first time they are visited, and kept until the
+/// provider is dropped, so it never sees later changes to them.
+pub struct FileProvider {
+    root: PathBuf,
+    ids: RefCell<FastHashMap<PathBuf, FileId>>,
+    /// A `FrozenVec` keeps files in place while `visit` adds new ones, so
+    /// `get` can hand out references to them.
+    files: elsa::FrozenVec<Box<File>>,
+}
+
+impl FileProvider {
+    /// Read sources from the directory `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            ids: RefCell::default(),
+            files: elsa::FrozenVec::new(),
+        }
+    }
+
+    /// The paths of the files read so far, in the order they were visited.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.files
+            .iter()
+            .map(|file| file.path().to_path_buf())
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for FileProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileProvider")
+            .field("root", &self.root)
+            .field("paths", &self.paths())
+            .finish()
+    }
+}
+
+impl SourceProvider<'_> for FileProvider {
+    fn visit(&self, path: impl AsRef<Path>) -> Option<FileId> {
+        let path = self.root.join(path);
+        if let Some(&id) = self.ids.borrow().get(&path) {
+            return Some(id);
+        }
+
+        let source = std::fs::read_to_string(&path).ok()?;
+        let id = self.files.len() as FileId + 1;
+        self.files.push(Box::new(File::new(id, path.clone(), source)));
+        self.ids.borrow_mut().insert(path, id);
+        Some(id)
+    }
+
+    fn get(&self, id: FileId) -> Option<&File> {
+        let index = (id as usize).checked_sub(1)?;
+        self.files.get(index)
+    }
+}
+
+impl<'a> Files<'a> for FileProvider {
+    type FileId = FileId;
+    type Name = &'a str;
+    type Source = &'a str;
+
+    fn name(&'a self, id: FileId) -> Result<&'a str, Error> {
+        let file = self.get(id).ok_or(Error::FileMissing)?;
+        Ok(file.name())
+    }
+
+    fn source(&'a self, id: FileId) -> Result<&'a str, Error> {
+        let file = self.get(id).ok_or(Error::FileMissing)?;
+        Ok(file.source())
+    }
+
+    fn line_index(&'a self, id: FileId, byte_index: usize) -> Result<usize, Error> {
+        let file = self.get(id).ok_or(Error::FileMissing)?;
+        file.line_index((), byte_index)
+    }
+
+    fn line_range(&'a self, id: FileId, line_index: usize) -> Result<Range<usize>, Error> {
+        let file = self.get(id).ok_or(Error::FileMissing)?;
+        file.line_range((), line_index)
+    }
+}
@@ -611,7 +611,7 @@ fn parse_repeated_attributes() {
         let name_length = attribute.rfind('(').unwrap_or(attribute.len()) as u32;
         let span_start = shader.rfind(attribute).unwrap() as u32;
         let span_end = span_start + name_length;
-        let expected_span = Span::new(span_start, span_end, None);
+        let expected_span = Span::new(span_start, span_end, Some(0));
 
         let result = Frontend::new().inner(&shader);
         assert!(matches!(
@@ -632,6 +632,348 @@ fn parse_missing_workgroup_size() {
     let result = Frontend::new().inner(shader);
     assert!(matches!(
         result.unwrap_err(),
-        Error::MissingWorkgroupSize(span) if span == Span::new(1, 8, None)
+        Error::MissingWorkgroupSize(span) if span == Span::new(1, 8, Some(0))
     ));
 }
+
+#[test]
+fn parse_subtraction() {
+    // Spacing doesn't change what a minus sign means.
+    for source in ["x - 1", "x-1", "x -1", "x- 1"] {
+        let module = parse_str(&format!("fn f(x: i32) -> i32 {{ return {source}; }}")).unwrap();
+        let (_, f) = module.functions.iter().next().unwrap();
+        let value = match *f.body.last().unwrap() {
+            crate::Statement::Return { value: Some(value) } => value,
+            ref other => panic!("expected a return, found {other:?}"),
+        };
+        assert!(
+            matches!(
+                f.expressions[value],
+                crate::Expression::Binary {
+                    op: crate::BinaryOperator::Subtract,
+                    ..
+                }
+            ),
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn parse_negated_abstract_literals() {
+    // `2147483648` doesn't fit in an `i32`, but its negation does.
+    let module = parse_str(
+        "
+        const a: i32 = -2147483648;
+        const b = i32(-0x80000000);
+        ",
+    )
+    .unwrap();
+    for (_, constant) in module.constants.iter() {
+        assert_eq!(
+            module.const_expressions[constant.init],
+            crate::Expression::Literal(crate::Literal::I32(i32::MIN))
+        );
+    }
+}
+
+#[test]
+fn parse_generic_functions() {
+    let module = parse_str(
+        "
+        fn lerp<T: f32 | vec3<f32>>(a: T, b: T, t: f32) -> T {
+            return a + (b - a) * t;
+        }
+        fn sum<T>(values: array<T, 2>) -> T {
+            return values[0] + values[1];
+        }
+        fn main() {
+            let x = lerp(1.0, 2.0, 0.5);
+            let y = lerp(vec3(0.0), vec3(1.0), 0.5);
+            let z = lerp(3.0, 4.0, 0.25);
+            let w = sum(array(1u, 2u));
+        }
+    ",
+    )
+    .unwrap();
+
+    // Each set of types is instantiated once, before its first caller.
+    let names: Vec<_> = module
+        .functions
+        .iter()
+        .map(|(_, function)| function.name.as_deref().unwrap())
+        .collect();
+    assert_eq!(names, ["lerp_f32", "lerp_vec3_f32", "sum_u32", "main"]);
+
+    let (_, lerp_vec3) = module.functions.iter().nth(1).unwrap();
+    let result = lerp_vec3.result.as_ref().unwrap();
+    assert!(matches!(
+        module.types[result.ty].inner,
+        crate::TypeInner::Vector {
+            size: crate::VectorSize::Tri,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn parse_enum_declarations() {
+    let module = parse_str(
+        "
+        enum LightKind {
+            Point,
+            Spot = 4,
+            Area,
+        }
+        enum Offset: i32 { Low = -2, High }
+
+        fn intensity(kind: LightKind) -> f32 {
+            switch kind {
+                case LightKind::Point: { return 1.0; }
+                case LightKind::Spot, LightKind::Area: { return 0.5; }
+            }
+            return 0.0;
+        }
+    ",
+    )
+    .unwrap();
+
+    let constants: Vec<_> = module
+        .constants
+        .iter()
+        .map(|(_, constant)| {
+            let value = match module.const_expressions[constant.init] {
+                crate::Expression::Literal(crate::Literal::U32(value)) => i64::from(value),
+                crate::Expression::Literal(crate::Literal::I32(value)) => i64::from(value),
+                ref other => panic!("unexpected enum value {other:?}"),
+            };
+            (constant.name.as_deref().unwrap(), value)
+        })
+        .collect();
+    assert_eq!(
+        constants,
+        [
+            ("LightKind_Point", 0),
+            ("LightKind_Spot", 4),
+            ("LightKind_Area", 5),
+            ("Offset_Low", -2),
+            ("Offset_High", -1),
+        ]
+    );
+
+    // The switch handles every variant, so it gets an empty default case.
+    let (_, intensity) = module.functions.iter().next().unwrap();
+    let crate::Statement::Switch { ref cases, .. } = intensity.body[0] else {
+        panic!("expected a switch, found {:?}", intensity.body[0]);
+    };
+    let values: Vec<_> = cases.iter().map(|case| case.value).collect();
+    assert_eq!(
+        values,
+        [
+            crate::SwitchValue::U32(0),
+            crate::SwitchValue::U32(4),
+            crate::SwitchValue::U32(5),
+            crate::SwitchValue::Default,
+        ]
+    );
+    assert!(cases.last().unwrap().body.is_empty());
+}
+
+#[test]
+fn parse_non_exhaustive_enum_switch() {
+    // Unhandled variants, and variants of another enum, are only warned
+    // about.
+    let module = parse_str(
+        "
+        enum LightKind { Point, Spot, Area }
+        enum Shape { Square, Circle }
+
+        fn f(kind: LightKind) {
+            switch kind {
+                case LightKind::Point: {}
+                case Shape::Circle: {}
+            }
+        }
+    ",
+    )
+    .unwrap();
+
+    let (_, f) = module.functions.iter().next().unwrap();
+    let crate::Statement::Switch { ref cases, .. } = f.body[0] else {
+        panic!("expected a switch, found {:?}", f.body[0]);
+    };
+    assert_eq!(cases.last().unwrap().value, crate::SwitchValue::Default);
+}
+
+/// A source provider holding several in-memory files, which can import each
+/// other by path.
+struct MemoryFiles(Vec<super::File>);
+
+impl MemoryFiles {
+    fn new(files: &[(&str, &str)]) -> Self {
+        Self(
+            files
+                .iter()
+                .zip(1..)
+                .map(|(&(path, source), id)| super::File::new(id, path.into(), source.into()))
+                .collect(),
+        )
+    }
+
+    fn file(&self, id: crate::span::FileId) -> Result<&super::File, super::source_provider::Error> {
+        let index = (id as usize).checked_sub(1);
+        index
+            .and_then(|index| self.0.get(index))
+            .ok_or(super::source_provider::Error::FileMissing)
+    }
+}
+
+impl super::SourceProvider<'_> for MemoryFiles {
+    fn visit(&self, path: impl AsRef<std::path::Path>) -> Option<crate::span::FileId> {
+        let file = self.0.iter().find(|file| file.path() == path.as_ref())?;
+        Some(file.id())
+    }
+
+    fn get(&self, id: crate::span::FileId) -> Option<&super::File> {
+        self.file(id).ok()
+    }
+}
+
+impl<'a> super::source_provider::Files<'a> for MemoryFiles {
+    type FileId = crate::span::FileId;
+    type Name = &'a str;
+    type Source = &'a str;
+
+    fn name(&'a self, id: Self::FileId) -> Result<&'a str, super::source_provider::Error> {
+        let file = self.file(id)?;
+        Ok(file.name())
+    }
+
+    fn source(&'a self, id: Self::FileId) -> Result<&'a str, super::source_provider::Error> {
+        let file = self.file(id)?;
+        Ok(file.source())
+    }
+
+    fn line_index(
+        &'a self,
+        id: Self::FileId,
+        byte_index: usize,
+    ) -> Result<usize, super::source_provider::Error> {
+        let file = self.file(id)?;
+        file.line_index((), byte_index)
+    }
+
+    fn line_range(
+        &'a self,
+        id: Self::FileId,
+        line_index: usize,
+    ) -> Result<std::ops::Range<usize>, super::source_provider::Error> {
+        let file = self.file(id)?;
+        file.line_range((), line_index)
+    }
+}
+
+#[test]
+fn parse_imported_enum() {
+    let files = MemoryFiles::new(&[
+        (
+            "main.wgsl",
+            "
+            @import \"lights.wgsl\";
+
+            fn is_point(kind: LightKind) -> bool {
+                return kind == LightKind::Point;
+            }
+            ",
+        ),
+        ("lights.wgsl", "enum LightKind { Point, Spot }"),
+    ]);
+    let module = match super::parse_module(&files, 1) {
+        Ok(module) => module,
+        Err(error) => panic!("{}", error.emit_to_string_with_provider(&files)),
+    };
+    let names: Vec<_> = module
+        .constants
+        .iter()
+        .map(|(_, constant)| constant.name.as_deref().unwrap())
+        .collect();
+    assert_eq!(names, ["LightKind_Point", "LightKind_Spot"]);
+}
+
+#[test]
+fn parse_methods() {
+    // Every type has a `len` method, and each calls the next one's, so a
+    // method call can't simply depend on all methods of the same name.
+    let module = parse_str(
+        "
+        struct Path { segment: Segment, count: f32 }
+        struct Segment { dir: Dir, scale: f32 }
+        struct Dir { v: vec3<f32> }
+        struct Counter { value: u32 }
+
+        impl Path {
+            fn len(self) -> f32 { return self.segment.len() * self.count; }
+        }
+
+        impl Segment {
+            fn len(self) -> f32 { return self.dir.len() * self.scale; }
+        }
+
+        impl Dir {
+            fn len(self) -> f32 { return length(self.v); }
+        }
+
+        impl Counter {
+            fn bump(&mut self, by: u32) { self.value += by; }
+        }
+
+        fn main() -> f32 {
+            var counter = Counter(0u);
+            counter.bump(2u);
+            let path = Path(Segment(Dir(vec3(1.0)), 2.0), f32(counter.value));
+            return path.len();
+        }
+    ",
+    )
+    .unwrap();
+
+    // Methods are named after their type, and come before their callers.
+    let names: Vec<_> = module
+        .functions
+        .iter()
+        .map(|(_, function)| function.name.as_deref().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        ["Dir_len", "Segment_len", "Path_len", "Counter_bump", "main"]
+    );
+
+    // A method call statement passes a pointer to a `&mut self` receiver.
+    let (bump, _) = module
+        .functions
+        .iter()
+        .find(|&(_, function)| function.name.as_deref() == Some("Counter_bump"))
+        .unwrap();
+    let (_, main) = module.functions.iter().last().unwrap();
+    let (arguments, result) = main
+        .body
+        .iter()
+        .find_map(|statement| match *statement {
+            crate::Statement::Call {
+                function,
+                ref arguments,
+                result,
+            } if function == bump => Some((arguments, result)),
+            _ => None,
+        })
+        .unwrap();
+    assert!(result.is_none());
+    assert!(matches!(
+        main.expressions[arguments[0]],
+        crate::Expression::LocalVariable(_)
+    ));
+
+    crate::valid::Validator::new(Default::default(), crate::valid::Capabilities::all())
+        .validate(&module)
+        .unwrap();
+}
@@ -0,0 +1,984 @@
+//! A single invocation of an entry point, run as a resumable task.
+
+use super::{
+    ops::{self, Shape},
+    value::{self, Pointer, Region, Value},
+    Context, Error, OutOfBounds, Shared,
+};
+use crate::{
+    proc::{BoundsCheckPolicy, TypeResolution},
+    valid::FunctionInfo,
+    Block, Expression, Handle, Literal, Statement, TypeInner,
+};
+
+/// Why [`Invocation::run`] returned.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum State {
+    /// The invocation can run further.
+    Running,
+    /// The invocation waits for the rest of its workgroup at a barrier.
+    Barrier,
+    /// The invocation waits for the rest of its subgroup to reach a subgroup
+    /// operation, described by [`Invocation::subgroup_request`].
+    Subgroup,
+    /// The entry point has returned.
+    Done,
+}
+
+/// The ids of an invocation, which its built-in arguments read.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Ids {
+    pub global_invocation_id: [u32; 3],
+    pub local_invocation_id: [u32; 3],
+    pub local_invocation_index: u32,
+    pub workgroup_id: [u32; 3],
+}
+
+/// A subgroup operation that an invocation is waiting on.
+pub(super) struct SubgroupRequest<'m> {
+    /// One of the subgroup statements.
+    pub statement: &'m Statement,
+    /// The invocation's `argument`, or the `predicate` of a ballot.
+    pub argument: Option<Value>,
+    /// The invocation's index, delta or mask operand of a gather.
+    pub index: Option<u32>,
+}
+
+pub(super) struct Invocation<'m> {
+    pub ids: Ids,
+    /// The contents of each global variable in the `Private` address space,
+    /// indexed by handle.
+    private: Vec<Vec<u8>>,
+    frames: Vec<Frame<'m>>,
+    /// The pointer and result of a `WorkGroupUniformLoad` statement, whose
+    /// load happens when the invocation leaves its first barrier.
+    uniform_load: Option<(Handle<Expression>, Handle<Expression>)>,
+    pub subgroup_request: Option<SubgroupRequest<'m>>,
+}
+
+/// The state of a function call.
+struct Frame<'m> {
+    /// The function being run, or `None` for the entry point.
+    function: Option<Handle<crate::Function>>,
+    body: &'m crate::Function,
+    info: &'m FunctionInfo,
+    arguments: Vec<Value>,
+    /// The contents of each local variable, indexed by handle.
+    locals: Vec<Vec<u8>>,
+    /// The value of each expression evaluated so far, indexed by handle.
+    expressions: Vec<Option<Value>>,
+    /// The blocks being run, innermost last.
+    cursors: Vec<Cursor<'m>>,
+    /// The caller's expression that receives the return value.
+    result: Option<Handle<Expression>>,
+}
+
+/// A position in a block, and what to do when the block ends.
+struct Cursor<'m> {
+    block: &'m Block,
+    next: usize,
+    kind: CursorKind<'m>,
+}
+
+#[derive(Clone, Copy)]
+enum CursorKind<'m> {
+    Block,
+    LoopBody(Loop<'m>),
+    LoopContinuing(Loop<'m>),
+    /// The body of `cases[index]`, which may fall through to the next case.
+    SwitchCase {
+        cases: &'m [crate::SwitchCase],
+        index: usize,
+    },
+}
+
+#[derive(Clone, Copy)]
+struct Loop<'m> {
+    body: &'m Block,
+    continuing: &'m Block,
+    break_if: Option<Handle<Expression>>,
+}
+
+impl<'m> Cursor<'m> {
+    const fn new(block: &'m Block, kind: CursorKind<'m>) -> Self {
+        Cursor {
+            block,
+            next: 0,
+            kind,
+        }
+    }
+}
+
+impl<'m> Invocation<'m> {
+    pub fn new(ctx: &Context<'m>, shared: &mut Shared, ids: Ids) -> Result<Self, Error> {
+        let module = ctx.module;
+        let mut private = Vec::with_capacity(module.global_variables.len());
+        for (_, var) in module.global_variables.iter() {
+            let mut bytes = Vec::new();
+            if var.space == crate::AddressSpace::Private {
+                bytes.resize(ctx.layouter[var.ty].size as usize, 0);
+                if let Some(init) = var.init {
+                    let init = ctx.eval_const(init)?;
+                    value::write(
+                        &mut bytes,
+                        0,
+                        &module.types[var.ty].inner,
+                        &module.types,
+                        &init,
+                    )?;
+                }
+            }
+            private.push(bytes);
+        }
+
+        let mut invocation = Invocation {
+            ids,
+            private,
+            frames: Vec::new(),
+            uniform_load: None,
+            subgroup_request: None,
+        };
+
+        let entry_point = &module.entry_points[ctx.entry_point];
+        let arguments = entry_point
+            .function
+            .arguments
+            .iter()
+            .map(|argument| {
+                invocation.builtin_argument(ctx, argument.binding.as_ref(), argument.ty)
+            })
+            .collect::<Result<_, _>>()?;
+        invocation.push_frame(
+            ctx,
+            shared,
+            None,
+            &entry_point.function,
+            ctx.info.get_entry_point(ctx.entry_point),
+            arguments,
+            None,
+        )?;
+        Ok(invocation)
+    }
+
+    fn builtin_argument(
+        &self,
+        ctx: &Context,
+        binding: Option<&crate::Binding>,
+        ty: Handle<crate::Type>,
+    ) -> Result<Value, Error> {
+        use crate::BuiltIn as Bi;
+
+        let scalar = |value: u32| Value::Scalar(Literal::U32(value));
+        let vector = |values: [u32; 3]| Value::Composite(values.map(scalar).to_vec());
+        let subgroup_size = ctx.options.subgroup_size;
+        let invocations = ctx.workgroup_size.iter().product::<u32>();
+
+        Ok(match binding {
+            Some(&crate::Binding::BuiltIn(built_in)) => match built_in {
+                Bi::GlobalInvocationId => vector(self.ids.global_invocation_id),
+                Bi::LocalInvocationId => vector(self.ids.local_invocation_id),
+                Bi::LocalInvocationIndex => scalar(self.ids.local_invocation_index),
+                Bi::WorkGroupId => vector(self.ids.workgroup_id),
+                Bi::WorkGroupSize => vector(ctx.workgroup_size),
+                Bi::NumWorkGroups => vector(ctx.num_workgroups),
+                Bi::NumSubgroups => scalar((invocations + subgroup_size - 1) / subgroup_size),
+                Bi::SubgroupId => scalar(self.ids.local_invocation_index / subgroup_size),
+                Bi::SubgroupSize => scalar(subgroup_size),
+                Bi::SubgroupInvocationId => scalar(self.ids.local_invocation_index % subgroup_size),
+                _ => {
+                    return Err(Error::Invalid(
+                        "built-in is not available to compute shaders",
+                    ))
+                }
+            },
+            Some(&crate::Binding::Location { .. }) => {
+                return Err(Error::Invalid("compute shaders have no inputs"))
+            }
+            None => match ctx.module.types[ty].inner {
+                TypeInner::Struct { ref members, .. } => Value::Composite(
+                    members
+                        .iter()
+                        .map(|member| {
+                            self.builtin_argument(ctx, member.binding.as_ref(), member.ty)
+                        })
+                        .collect::<Result<_, _>>()?,
+                ),
+                _ => return Err(Error::Invalid("entry point argument has no binding")),
+            },
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push_frame(
+        &mut self,
+        ctx: &Context<'m>,
+        shared: &mut Shared,
+        function: Option<Handle<crate::Function>>,
+        body: &'m crate::Function,
+        info: &'m FunctionInfo,
+        arguments: Vec<Value>,
+        result: Option<Handle<Expression>>,
+    ) -> Result<(), Error> {
+        let locals = body
+            .local_variables
+            .iter()
+            .map(|(_, local)| vec![0; ctx.layouter[local.ty].size as usize])
+            .collect();
+        self.frames.push(Frame {
+            function,
+            body,
+            info,
+            arguments,
+            locals,
+            expressions: vec![None; body.expressions.len()],
+            cursors: vec![Cursor::new(&body.body, CursorKind::Block)],
+            result,
+        });
+
+        for (handle, local) in body.local_variables.iter() {
+            if let Some(init) = local.init {
+                let init = self.eval(ctx, shared, init)?;
+                let ty = &ctx.module.types[local.ty].inner;
+                let bytes = &mut self.frame_mut().locals[handle.index()];
+                value::write(bytes, 0, ty, &ctx.module.types, &init)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn frame(&self) -> &Frame<'m> {
+        self.frames.last().unwrap()
+    }
+
+    fn frame_mut(&mut self) -> &mut Frame<'m> {
+        self.frames.last_mut().unwrap()
+    }
+
+    /// Run until the invocation returns, or must wait for other invocations.
+    pub fn run(&mut self, ctx: &Context<'m>, shared: &mut Shared) -> Result<State, Error> {
+        if let Some((pointer, result)) = self.uniform_load.take() {
+            // Every invocation reached the first barrier, so the load sees
+            // all writes made before it. The second barrier keeps later writes
+            // from racing with the other invocations' loads.
+            let value = self.load(ctx, shared, pointer)?;
+            self.frame_mut().expressions[result.index()] = Some(value);
+            return Ok(State::Barrier);
+        }
+
+        loop {
+            let frame = match self.frames.last_mut() {
+                Some(frame) => frame,
+                None => return Ok(State::Done),
+            };
+            let cursor = match frame.cursors.last_mut() {
+                Some(cursor) => cursor,
+                None => {
+                    // The function ran off the end of its body.
+                    self.return_from_frame(None);
+                    continue;
+                }
+            };
+
+            let block: &'m Block = cursor.block;
+            match block.get(cursor.next) {
+                Some(statement) => {
+                    cursor.next += 1;
+                    let state = self.execute(ctx, shared, statement)?;
+                    if state != State::Running {
+                        return Ok(state);
+                    }
+                }
+                None => self.finish_block(ctx, shared)?,
+            }
+        }
+    }
+
+    /// Pop the innermost block, which has run to its end.
+    fn finish_block(&mut self, ctx: &Context<'m>, shared: &mut Shared) -> Result<(), Error> {
+        let cursor = self.frame_mut().cursors.pop().unwrap();
+        match cursor.kind {
+            CursorKind::Block => {}
+            CursorKind::LoopBody(lp) => {
+                let continuing = Cursor::new(lp.continuing, CursorKind::LoopContinuing(lp));
+                self.frame_mut().cursors.push(continuing);
+            }
+            CursorKind::LoopContinuing(lp) => {
+                let exit = match lp.break_if {
+                    Some(condition) => self.eval(ctx, shared, condition)?.as_bool()?,
+                    None => false,
+                };
+                if !exit {
+                    let body = Cursor::new(lp.body, CursorKind::LoopBody(lp));
+                    self.frame_mut().cursors.push(body);
+                }
+            }
+            CursorKind::SwitchCase { cases, index } => {
+                if cases[index].fall_through && index + 1 < cases.len() {
+                    let kind = CursorKind::SwitchCase {
+                        cases,
+                        index: index + 1,
+                    };
+                    let next = Cursor::new(&cases[index + 1].body, kind);
+                    self.frame_mut().cursors.push(next);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn return_from_frame(&mut self, value: Option<Value>) {
+        let frame = self.frames.pop().unwrap();
+        if let (Some(result), Some(caller)) = (frame.result, self.frames.last_mut()) {
+            caller.expressions[result.index()] = value;
+        }
+    }
+
+    fn execute(
+        &mut self,
+        ctx: &Context<'m>,
+        shared: &mut Shared,
+        statement: &'m Statement,
+    ) -> Result<State, Error> {
+        match *statement {
+            Statement::Emit(ref range) => {
+                for handle in range.clone() {
+                    // Recompute, since a loop may emit the same range again.
+                    let value = self.compute(ctx, shared, handle)?;
+                    self.frame_mut().expressions[handle.index()] = Some(value);
+                }
+            }
+            Statement::Block(ref block) => {
+                self.frame_mut()
+                    .cursors
+                    .push(Cursor::new(block, CursorKind::Block));
+            }
+            Statement::If {
+                condition,
+                ref accept,
+                ref reject,
+            } => {
+                let block = if self.eval(ctx, shared, condition)?.as_bool()? {
+                    accept
+                } else {
+                    reject
+                };
+                self.frame_mut()
+                    .cursors
+                    .push(Cursor::new(block, CursorKind::Block));
+            }
+            Statement::Switch {
+                selector,
+                ref cases,
+            } => {
+                let selector = self.eval(ctx, shared, selector)?.as_int()?;
+                let index = cases
+                    .iter()
+                    .position(|case| match case.value {
+                        crate::SwitchValue::I32(value) => i64::from(value) == selector,
+                        crate::SwitchValue::U32(value) => i64::from(value) == selector,
+                        crate::SwitchValue::Default => false,
+                    })
+                    .or_else(|| {
+                        cases
+                            .iter()
+                            .position(|case| case.value == crate::SwitchValue::Default)
+                    })
+                    .ok_or(Error::Invalid("switch has no default case"))?;
+                let kind = CursorKind::SwitchCase { cases, index };
+                self.frame_mut()
+                    .cursors
+                    .push(Cursor::new(&cases[index].body, kind));
+            }
+            Statement::Loop {
+                ref body,
+                ref continuing,
+                break_if,
+            } => {
+                let lp = Loop {
+                    body,
+                    continuing,
+                    break_if,
+                };
+                self.frame_mut()
+                    .cursors
+                    .push(Cursor::new(body, CursorKind::LoopBody(lp)));
+            }
+            Statement::Break => loop {
+                let cursor = self
+                    .frame_mut()
+                    .cursors
+                    .pop()
+                    .ok_or(Error::Invalid("break outside of a loop or switch"))?;
+                if let CursorKind::LoopBody(_)
+                | CursorKind::LoopContinuing(_)
+                | CursorKind::SwitchCase { .. } = cursor.kind
+                {
+                    break;
+                }
+            },
+            Statement::Continue => loop {
+                let cursor = self
+                    .frame_mut()
+                    .cursors
+                    .pop()
+                    .ok_or(Error::Invalid("continue outside of a loop"))?;
+                if let CursorKind::LoopBody(lp) = cursor.kind {
+                    let continuing = Cursor::new(lp.continuing, CursorKind::LoopContinuing(lp));
+                    self.frame_mut().cursors.push(continuing);
+                    break;
+                }
+            },
+            Statement::Return { value } => {
+                let value = value
+                    .map(|value| self.eval(ctx, shared, value))
+                    .transpose()?;
+                self.return_from_frame(value);
+            }
+            Statement::Kill => return Err(Error::Invalid("compute shaders cannot discard")),
+            Statement::Barrier(_) => return Ok(State::Barrier),
+            Statement::Store { pointer, value } => {
+                let value = self.eval(ctx, shared, value)?;
+                self.store(ctx, shared, pointer, &value)?;
+            }
+            Statement::ImageStore { .. } => return Err(Error::Unsupported("images")),
+            Statement::Atomic {
+                pointer,
+                ref fun,
+                value,
+                result,
+            } => {
+                let value = self.atomic(ctx, shared, pointer, fun, value)?;
+                self.frame_mut().expressions[result.index()] = Some(value);
+            }
+            Statement::WorkGroupUniformLoad { pointer, result } => {
+                self.uniform_load = Some((pointer, result));
+                return Ok(State::Barrier);
+            }
+            Statement::Call {
+                function,
+                ref arguments,
+                result,
+            } => {
+                let arguments = arguments
+                    .iter()
+                    .map(|&argument| self.eval(ctx, shared, argument))
+                    .collect::<Result<_, _>>()?;
+                self.push_frame(
+                    ctx,
+                    shared,
+                    Some(function),
+                    &ctx.module.functions[function],
+                    &ctx.info[function],
+                    arguments,
+                    result,
+                )?;
+            }
+            Statement::RayQuery { .. } => return Err(Error::Unsupported("ray queries")),
+            Statement::SubgroupElect { .. } => {
+                return Ok(self.request_subgroup(statement, None, None));
+            }
+            Statement::SubgroupBallot { predicate, .. } => {
+                let predicate = predicate
+                    .map(|predicate| self.eval(ctx, shared, predicate))
+                    .transpose()?;
+                return Ok(self.request_subgroup(statement, predicate, None));
+            }
+            Statement::SubgroupGather { mode, argument, .. } => {
+                let index = match mode {
+                    crate::GatherMode::BroadcastFirst => None,
+                    crate::GatherMode::Broadcast(index)
+                    | crate::GatherMode::Shuffle(index)
+                    | crate::GatherMode::ShuffleDown(index)
+                    | crate::GatherMode::ShuffleUp(index)
+                    | crate::GatherMode::ShuffleXor(index) => {
+                        Some(self.eval(ctx, shared, index)?.as_u32()?)
+                    }
+                };
+                let argument = self.eval(ctx, shared, argument)?;
+                return Ok(self.request_subgroup(statement, Some(argument), index));
+            }
+            Statement::SubgroupCollectiveOperation { argument, .. } => {
+                let argument = self.eval(ctx, shared, argument)?;
+                return Ok(self.request_subgroup(statement, Some(argument), None));
+            }
+        }
+        Ok(State::Running)
+    }
+
+    fn request_subgroup(
+        &mut self,
+        statement: &'m Statement,
+        argument: Option<Value>,
+        index: Option<u32>,
+    ) -> State {
+        self.subgroup_request = Some(SubgroupRequest {
+            statement,
+            argument,
+            index,
+        });
+        State::Subgroup
+    }
+
+    /// Deliver the result of the subgroup operation this invocation waits on.
+    pub fn finish_subgroup(&mut self, value: Value) -> Result<(), Error> {
+        let request = self
+            .subgroup_request
+            .take()
+            .ok_or(Error::Invalid("no subgroup operation is pending"))?;
+        let result = match *request.statement {
+            Statement::SubgroupElect { result }
+            | Statement::SubgroupBallot { result, .. }
+            | Statement::SubgroupGather { result, .. }
+            | Statement::SubgroupCollectiveOperation { result, .. } => result,
+            _ => return Err(Error::Invalid("not a subgroup operation")),
+        };
+        self.frame_mut().expressions[result.index()] = Some(value);
+        Ok(())
+    }
+
+    fn atomic(
+        &mut self,
+        ctx: &Context<'m>,
+        shared: &mut Shared,
+        pointer: Handle<Expression>,
+        fun: &crate::AtomicFunction,
+        value: Handle<Expression>,
+    ) -> Result<Value, Error> {
+        use crate::AtomicFunction as Af;
+
+        let operand = self.eval(ctx, shared, value)?;
+        let compare = match *fun {
+            Af::Exchange {
+                compare: Some(compare),
+            } => Some(self.eval(ctx, shared, compare)?),
+            _ => None,
+        };
+        let old = self.load(ctx, shared, pointer)?;
+
+        let binary = |op| ops::binary(op, &old, &operand, (Shape::Other, Shape::Other));
+        let new = match *fun {
+            Af::Add => binary(crate::BinaryOperator::Add)?,
+            Af::Subtract => binary(crate::BinaryOperator::Subtract)?,
+            Af::And => binary(crate::BinaryOperator::And)?,
+            Af::ExclusiveOr => binary(crate::BinaryOperator::ExclusiveOr)?,
+            Af::InclusiveOr => binary(crate::BinaryOperator::InclusiveOr)?,
+            Af::Min => ops::min(&old, &operand)?,
+            Af::Max => ops::max(&old, &operand)?,
+            Af::Exchange { compare: None } => operand.clone(),
+            Af::Exchange { compare: Some(_) } => {
+                if Some(&old) == compare.as_ref() {
+                    operand.clone()
+                } else {
+                    old.clone()
+                }
+            }
+        };
+        self.store(ctx, shared, pointer, &new)?;
+
+        Ok(match compare {
+            Some(compare) => {
+                let exchanged = Value::Scalar(Literal::Bool(old == compare));
+                Value::Composite(vec![old, exchanged])
+            }
+            None => old,
+        })
+    }
+
+    /// Return the type of `handle` in the current function.
+    fn ty(&self, ctx: &Context<'m>, handle: Handle<Expression>) -> &'m TypeInner {
+        let info: &'m FunctionInfo = self.frame().info;
+        info[handle].ty.inner_with(&ctx.module.types)
+    }
+
+    /// Return the type that the pointer `handle` points to.
+    fn pointee(
+        &self,
+        ctx: &Context<'m>,
+        handle: Handle<Expression>,
+    ) -> Result<TypeResolution, Error> {
+        Ok(match *self.ty(ctx, handle) {
+            TypeInner::Pointer { base, .. } => TypeResolution::Handle(base),
+            TypeInner::ValuePointer {
+                size: Some(size),
+                scalar,
+                ..
+            } => TypeResolution::Value(TypeInner::Vector { size, scalar }),
+            TypeInner::ValuePointer {
+                size: None, scalar, ..
+            } => TypeResolution::Value(TypeInner::Scalar(scalar)),
+            _ => return Err(Error::Invalid("expected a pointer")),
+        })
+    }
+
+    fn bytes_mut<'s>(
+        &'s mut self,
+        ctx: &Context,
+        shared: &'s mut Shared,
+        region: Region,
+    ) -> Result<&'s mut [u8], Error> {
+        match region {
+            Region::Global(handle) => match ctx.module.global_variables[handle].space {
+                crate::AddressSpace::Private => Ok(&mut self.private[handle.index()]),
+                crate::AddressSpace::WorkGroup => Ok(&mut shared.workgroup[handle.index()]),
+                crate::AddressSpace::Uniform | crate::AddressSpace::Storage { .. } => {
+                    match shared.buffers[handle.index()] {
+                        Some((memory, ref range)) => Ok(&mut shared.memory[memory][range.clone()]),
+                        None => Err(Error::Invalid("global is not bound")),
+                    }
+                }
+                crate::AddressSpace::PushConstant => Err(Error::Unsupported("push constants")),
+                crate::AddressSpace::Handle | crate::AddressSpace::Function => {
+                    Err(Error::Invalid("global has no memory"))
+                }
+            },
+            Region::Local { frame, local } => Ok(&mut self.frames[frame].locals[local.index()]),
+        }
+    }
+
+    fn load(
+        &mut self,
+        ctx: &Context<'m>,
+        shared: &mut Shared,
+        pointer: Handle<Expression>,
+    ) -> Result<Value, Error> {
+        let pointee = self.pointee(ctx, pointer)?;
+        let pointee = pointee.inner_with(&ctx.module.types);
+        let pointer = self.eval(ctx, shared, pointer)?.pointer()?;
+        if !pointer.in_bounds {
+            return Value::zero(pointee, &ctx.module.types);
+        }
+        let bytes = self.bytes_mut(ctx, shared, pointer.region)?;
+        value::read(bytes, pointer.offset, pointee, &ctx.module.types)
+    }
+
+    fn store(
+        &mut self,
+        ctx: &Context<'m>,
+        shared: &mut Shared,
+        pointer: Handle<Expression>,
+        value: &Value,
+    ) -> Result<(), Error> {
+        let pointee = self.pointee(ctx, pointer)?;
+        let pointee = pointee.inner_with(&ctx.module.types);
+        let pointer = self.eval(ctx, shared, pointer)?.pointer()?;
+        if !pointer.in_bounds {
+            return Ok(());
+        }
+        let bytes = self.bytes_mut(ctx, shared, pointer.region)?;
+        value::write(bytes, pointer.offset, pointee, &ctx.module.types, value)
+    }
+
+    /// Return the value of `handle`, evaluating it if it has not been yet.
+    fn eval(
+        &mut self,
+        ctx: &Context<'m>,
+        shared: &mut Shared,
+        handle: Handle<Expression>,
+    ) -> Result<Value, Error> {
+        if let Some(ref value) = self.frame().expressions[handle.index()] {
+            return Ok(value.clone());
+        }
+        let value = self.compute(ctx, shared, handle)?;
+        self.frame_mut().expressions[handle.index()] = Some(value.clone());
+        Ok(value)
+    }
+
+    fn compute(
+        &mut self,
+        ctx: &Context<'m>,
+        shared: &mut Shared,
+        handle: Handle<Expression>,
+    ) -> Result<Value, Error> {
+        let body: &'m crate::Function = self.frame().body;
+        let types = &ctx.module.types;
+
+        Ok(match body.expressions[handle] {
+            Expression::Literal(literal) => Value::Scalar(literal),
+            Expression::Constant(constant) => {
+                ctx.eval_const(ctx.module.constants[constant].init)?
+            }
+            Expression::ZeroValue(ty) => Value::zero(&types[ty].inner, types)?,
+            Expression::Compose { ty, ref components } => {
+                let components = components
+                    .iter()
+                    .map(|&component| self.eval(ctx, shared, component))
+                    .collect::<Result<_, _>>()?;
+                value::compose(&types[ty].inner, components)?
+            }
+            Expression::Access { base, index } => {
+                let index = self.eval(ctx, shared, index)?.as_int()?;
+                self.access(ctx, shared, handle, base, index)?
+            }
+            Expression::AccessIndex { base, index } => {
+                let base_value = self.eval(ctx, shared, base)?;
+                match (base_value, self.ty(ctx, base)) {
+                    (Value::Pointer(pointer), &TypeInner::Pointer { base: pointee, .. })
+                        if matches!(types[pointee].inner, TypeInner::Struct { .. }) =>
+                    {
+                        let offset = match types[pointee].inner {
+                            TypeInner::Struct { ref members, .. } => {
+                                members
+                                    .get(index as usize)
+                                    .ok_or(Error::Invalid("struct member out of range"))?
+                                    .offset
+                            }
+                            _ => unreachable!(),
+                        };
+                        Value::Pointer(Pointer {
+                            offset: pointer.offset + offset as usize,
+                            ..pointer
+                        })
+                    }
+                    (Value::Composite(mut components), _)
+                        if matches!(*self.ty(ctx, base), TypeInner::Struct { .. }) =>
+                    {
+                        if index as usize >= components.len() {
+                            return Err(Error::Invalid("struct member out of range"));
+                        }
+                        components.swap_remove(index as usize)
+                    }
+                    _ => self.access(ctx, shared, handle, base, index.into())?,
+                }
+            }
+            Expression::Splat { size, value } => {
+                Value::Composite(vec![self.eval(ctx, shared, value)?; size as usize])
+            }
+            Expression::Swizzle {
+                size,
+                vector,
+                pattern,
+            } => {
+                let vector = self.eval(ctx, shared, vector)?;
+                let components = vector.components()?;
+                pattern[..size as usize]
+                    .iter()
+                    .map(|&component| {
+                        components
+                            .get(component as usize)
+                            .cloned()
+                            .ok_or(Error::Invalid("swizzle component out of range"))
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(Value::Composite)?
+            }
+            Expression::FunctionArgument(index) => self
+                .frame()
+                .arguments
+                .get(index as usize)
+                .cloned()
+                .ok_or(Error::Invalid("function argument out of range"))?,
+            Expression::GlobalVariable(global) => {
+                if ctx.module.global_variables[global].space == crate::AddressSpace::Handle {
+                    return Err(Error::Unsupported("images and samplers"));
+                }
+                Value::Pointer(Pointer {
+                    region: Region::Global(global),
+                    offset: 0,
+                    in_bounds: true,
+                })
+            }
+            Expression::LocalVariable(local) => Value::Pointer(Pointer {
+                region: Region::Local {
+                    frame: self.frames.len() - 1,
+                    local,
+                },
+                offset: 0,
+                in_bounds: true,
+            }),
+            Expression::Load { pointer } => self.load(ctx, shared, pointer)?,
+            Expression::ImageSample { .. }
+            | Expression::ImageLoad { .. }
+            | Expression::ImageQuery { .. } => return Err(Error::Unsupported("images")),
+            Expression::Unary { op, expr } => ops::unary(op, &self.eval(ctx, shared, expr)?)?,
+            Expression::Binary { op, left, right } => {
+                let left_value = self.eval(ctx, shared, left)?;
+                let right_value = self.eval(ctx, shared, right)?;
+                let shapes = (shape(self.ty(ctx, left)), shape(self.ty(ctx, right)));
+                ops::binary(op, &left_value, &right_value, shapes)?
+            }
+            Expression::Select {
+                condition,
+                accept,
+                reject,
+            } => {
+                let condition = self.eval(ctx, shared, condition)?;
+                let accept = self.eval(ctx, shared, accept)?;
+                let reject = self.eval(ctx, shared, reject)?;
+                ops::select(&condition, &accept, &reject)?
+            }
+            Expression::Derivative { .. } => {
+                return Err(Error::Invalid("compute shaders have no derivatives"))
+            }
+            Expression::Relational { fun, argument } => {
+                ops::relational(fun, &self.eval(ctx, shared, argument)?)?
+            }
+            Expression::Math {
+                fun,
+                arg,
+                arg1,
+                arg2,
+                arg3,
+            } => {
+                let args = [Some(arg), arg1, arg2, arg3]
+                    .into_iter()
+                    .flatten()
+                    .map(|arg| self.eval(ctx, shared, arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                ops::math(fun, &args)?
+            }
+            Expression::As {
+                expr,
+                kind,
+                convert,
+            } => ops::cast(&self.eval(ctx, shared, expr)?, kind, convert)?,
+            Expression::CallResult(_)
+            | Expression::AtomicResult { .. }
+            | Expression::WorkGroupUniformLoadResult { .. }
+            | Expression::SubgroupBallotResult
+            | Expression::SubgroupOperationResult { .. } => {
+                return Err(Error::Invalid("result used before its statement ran"))
+            }
+            Expression::ArrayLength(array) => {
+                let pointer = self.eval(ctx, shared, array)?.pointer()?;
+                let (_, length) = self.element_layout(ctx, shared, array, pointer)?;
+                Value::Scalar(Literal::U32(length))
+            }
+            Expression::RayQueryProceedResult | Expression::RayQueryGetIntersection { .. } => {
+                return Err(Error::Unsupported("ray queries"))
+            }
+        })
+    }
+
+    /// Return the stride and number of elements of what `pointer`, the
+    /// value of the expression `base`, points to.
+    fn element_layout(
+        &mut self,
+        ctx: &Context<'m>,
+        shared: &mut Shared,
+        base: Handle<Expression>,
+        pointer: Pointer,
+    ) -> Result<(usize, u32), Error> {
+        Ok(match *self.ty(ctx, base) {
+            TypeInner::Pointer { base: pointee, .. } => match ctx.module.types[pointee].inner {
+                TypeInner::Array {
+                    stride,
+                    size: crate::ArraySize::Constant(count),
+                    ..
+                } => (stride as usize, count.get()),
+                TypeInner::Array {
+                    stride,
+                    size: crate::ArraySize::Dynamic,
+                    ..
+                } => {
+                    // A runtime-sized array extends to the end of its buffer.
+                    let len = self.bytes_mut(ctx, shared, pointer.region)?.len();
+                    let count = len.saturating_sub(pointer.offset) / stride as usize;
+                    (stride as usize, count as u32)
+                }
+                TypeInner::Vector { size, scalar } => (scalar.width as usize, size as u32),
+                TypeInner::Matrix {
+                    columns,
+                    rows,
+                    scalar,
+                } => (value::column_stride(rows, scalar), columns as u32),
+                TypeInner::BindingArray { .. } => return Err(Error::Unsupported("binding arrays")),
+                _ => return Err(Error::Invalid("pointee is not indexable")),
+            },
+            TypeInner::ValuePointer {
+                size: Some(size),
+                scalar,
+                ..
+            } => (scalar.width as usize, size as u32),
+            _ => return Err(Error::Invalid("expected a pointer to an indexable type")),
+        })
+    }
+
+    /// Evaluate `expression`, which indexes `base` with `index`.
+    fn access(
+        &mut self,
+        ctx: &Context<'m>,
+        shared: &mut Shared,
+        expression: Handle<Expression>,
+        base: Handle<Expression>,
+        index: i64,
+    ) -> Result<Value, Error> {
+        let policy = ctx.options.bounds_check_policies.choose_policy(
+            base,
+            &ctx.module.types,
+            self.frame().info,
+        );
+
+        match self.eval(ctx, shared, base)? {
+            Value::Pointer(pointer) => {
+                if !pointer.in_bounds {
+                    return Ok(Value::Pointer(pointer));
+                }
+                let (stride, length) = self.element_layout(ctx, shared, base, pointer)?;
+                let pointer = match self.check_index(shared, expression, index, length, policy)? {
+                    Some(index) => Pointer {
+                        offset: pointer.offset + index * stride,
+                        ..pointer
+                    },
+                    None => Pointer {
+                        in_bounds: false,
+                        ..pointer
+                    },
+                };
+                Ok(Value::Pointer(pointer))
+            }
+            Value::Composite(mut components) => {
+                let length = components.len() as u32;
+                match self.check_index(shared, expression, index, length, policy)? {
+                    Some(index) => Ok(components.swap_remove(index)),
+                    None => Value::zero(self.ty(ctx, expression), &ctx.module.types),
+                }
+            }
+            Value::Scalar(_) => Err(Error::Invalid("scalars are not indexable")),
+        }
+    }
+
+    /// Check that `index` is less than `length`, and apply `policy` if not.
+    ///
+    /// Return the index to use, or `None` if the access should read zero and
+    /// skip writes.
+    fn check_index(
+        &self,
+        shared: &mut Shared,
+        expression: Handle<Expression>,
+        index: i64,
+        length: u32,
+        policy: BoundsCheckPolicy,
+    ) -> Result<Option<usize>, Error> {
+        if (0..i64::from(length)).contains(&index) {
+            return Ok(Some(index as usize));
+        }
+
+        let out_of_bounds = OutOfBounds {
+            function: self.frame().function,
+            expression,
+            index,
+            length,
+            policy,
+            global_invocation_id: self.ids.global_invocation_id,
+        };
+        match policy {
+            BoundsCheckPolicy::Unchecked => Err(Error::OutOfBounds(out_of_bounds)),
+            BoundsCheckPolicy::Restrict if length > 0 => {
+                shared.report.out_of_bounds.push(out_of_bounds);
+                Ok(Some(length as usize - 1))
+            }
+            BoundsCheckPolicy::Restrict | BoundsCheckPolicy::ReadZeroSkipWrite => {
+                shared.report.out_of_bounds.push(out_of_bounds);
+                Ok(None)
+            }
+        }
+    }
+}
+
+const fn shape(inner: &TypeInner) -> Shape {
+    match *inner {
+        TypeInner::Matrix { .. } => Shape::Matrix,
+        TypeInner::Vector { .. } => Shape::Vector,
+        _ => Shape::Other,
+    }
+}
@@ -0,0 +1,451 @@
+/*!
+A CPU interpreter for compute shaders.
+
+[`Interpreter::dispatch`] runs a compute [`EntryPoint`] of a validated
+[`Module`] over a grid of workgroups, reading and writing the buffers given in
+[`Bindings`]. This is meant for testing shaders and the backends, not for
+speed.
+
+The invocations of a workgroup run one at a time, each until it reaches a
+barrier or a subgroup operation. Once every invocation of the workgroup has
+stopped, subgroup operations are resolved among the invocations of each
+subgroup that reached them, and then the barrier is released. Atomics are
+therefore trivially atomic, and memory is coherent at barriers.
+
+Out-of-bounds indices are handled as [`BoundsCheckPolicies`] says the
+backends would, and each one is recorded in the [`Report`]; under
+[`BoundsCheckPolicy::Unchecked`] the dispatch fails instead.
+
+Images, samplers, ray queries and push constants are not supported.
+
+Buffers hold values in the layout computed by [`Layouter`], with scalars in
+little-endian byte order and `bool`s as a single byte.
+
+[`EntryPoint`]: crate::EntryPoint
+[`Module`]: crate::Module
+*/
+
+mod invocation;
+mod ops;
+mod subgroup;
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests;
+mod value;
+
+use std::ops::Range;
+
+use crate::{
+    proc::{BoundsCheckPolicies, BoundsCheckPolicy, LayoutError, Layouter},
+    valid::ModuleInfo,
+    AddressSpace, FastHashMap, Handle, Module, ResourceBinding, ShaderStage,
+};
+use invocation::{Ids, Invocation, State};
+use value::Value;
+
+/// The largest subgroup size the interpreter supports, limited by the
+/// `vec4<u32>` mask of a ballot.
+const MAX_SUBGROUP_SIZE: u32 = 128;
+
+/// Options for [`Interpreter`].
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// How to handle out-of-bounds indices.
+    pub bounds_check_policies: BoundsCheckPolicies,
+    /// The number of invocations in a subgroup, from 1 to 128.
+    pub subgroup_size: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            bounds_check_policies: BoundsCheckPolicies::default(),
+            subgroup_size: 32,
+        }
+    }
+}
+
+/// The buffers bound to a dispatch, by group and binding.
+///
+/// Each binding views a range of some memory, and bindings may view the same
+/// memory, as bindings of the same buffer do on a GPU.
+#[derive(Debug, Default)]
+pub struct Bindings<'b> {
+    memory: Vec<&'b mut [u8]>,
+    buffers: FastHashMap<ResourceBinding, (usize, Range<usize>)>,
+}
+
+impl<'b> Bindings<'b> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind `data` as the uniform or storage buffer at `group` and `binding`.
+    pub fn buffer(&mut self, group: u32, binding: u32, data: &'b mut [u8]) -> &mut Self {
+        let range = 0..data.len();
+        let memory = self.memory(data);
+        self.view(group, binding, memory, range)
+    }
+
+    /// Add `data` as memory that bindings can [`view`](Self::view), and
+    /// return its index.
+    pub fn memory(&mut self, data: &'b mut [u8]) -> usize {
+        self.memory.push(data);
+        self.memory.len() - 1
+    }
+
+    /// Bind `range` of the memory at index `memory` as the uniform or storage
+    /// buffer at `group` and `binding`.
+    pub fn view(
+        &mut self,
+        group: u32,
+        binding: u32,
+        memory: usize,
+        range: Range<usize>,
+    ) -> &mut Self {
+        self.buffers
+            .insert(ResourceBinding { group, binding }, (memory, range));
+        self
+    }
+}
+
+/// What happened during a dispatch.
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    /// Every out-of-bounds index, in the order they were evaluated.
+    pub out_of_bounds: Vec<OutOfBounds>,
+}
+
+/// An out-of-bounds index.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutOfBounds {
+    /// The function containing the index, or `None` for the entry point.
+    pub function: Option<Handle<crate::Function>>,
+    /// The `Access` or `AccessIndex` expression.
+    pub expression: Handle<crate::Expression>,
+    pub index: i64,
+    /// The number of elements being indexed.
+    pub length: u32,
+    /// The policy that was applied.
+    pub policy: BoundsCheckPolicy,
+    pub global_invocation_id: [u32; 3],
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Layouter(#[from] LayoutError),
+    #[error("There is no compute entry point named `{0}`")]
+    MissingEntryPoint(String),
+    #[error("Nothing is bound to {0:?}")]
+    MissingBinding(ResourceBinding),
+    #[error(
+        "The buffer bound to {binding:?} is {size} bytes, but at least {required} are required"
+    )]
+    BufferTooSmall {
+        binding: ResourceBinding,
+        size: usize,
+        required: u32,
+    },
+    #[error("The range bound to {0:?} is outside of its memory")]
+    BindingOutOfRange(ResourceBinding),
+    #[error("Subgroup size {0} is not between 1 and {MAX_SUBGROUP_SIZE}")]
+    InvalidSubgroupSize(u32),
+    #[error("The interpreter does not support {0}")]
+    Unsupported(&'static str),
+    #[error("Index {} is out of bounds for length {} at {:?}, in invocation {:?}", .0.index, .0.length, .0.expression, .0.global_invocation_id)]
+    OutOfBounds(OutOfBounds),
+    #[error("Invalid module: {0}")]
+    Invalid(&'static str),
+}
+
+/// Runs the compute entry points of a module.
+pub struct Interpreter<'a> {
+    module: &'a Module,
+    info: &'a ModuleInfo,
+    layouter: Layouter,
+    options: Options,
+}
+
+impl<'a> Interpreter<'a> {
+    /// Prepare to interpret `module`, which `info` is the validation result of.
+    pub fn new(module: &'a Module, info: &'a ModuleInfo, options: Options) -> Result<Self, Error> {
+        if !(1..=MAX_SUBGROUP_SIZE).contains(&options.subgroup_size) {
+            return Err(Error::InvalidSubgroupSize(options.subgroup_size));
+        }
+        let mut layouter = Layouter::default();
+        layouter.update(module.to_ctx())?;
+        Ok(Interpreter {
+            module,
+            info,
+            layouter,
+            options,
+        })
+    }
+
+    /// Run the compute entry point named `entry_point` over
+    /// `workgroup_count` workgroups.
+    ///
+    /// Every uniform and storage buffer the entry point uses must be bound
+    /// in `bindings`, and be at least as large as its type. A runtime-sized
+    /// array takes up the rest of its buffer.
+    pub fn dispatch(
+        &self,
+        entry_point: &str,
+        workgroup_count: [u32; 3],
+        bindings: &mut Bindings,
+    ) -> Result<Report, Error> {
+        let module = self.module;
+        let (index, ep) = module
+            .entry_points
+            .iter()
+            .enumerate()
+            .find(|&(_, ep)| ep.stage == ShaderStage::Compute && ep.name == entry_point)
+            .ok_or_else(|| Error::MissingEntryPoint(entry_point.to_string()))?;
+        let ep_info = self.info.get_entry_point(index);
+
+        let mut buffers: Vec<Option<(usize, Range<usize>)>> =
+            module.global_variables.iter().map(|_| None).collect();
+        for (binding, &(memory, ref range)) in bindings.buffers.iter() {
+            let in_range = bindings.memory.get(memory).map_or(false, |data| {
+                range.start <= range.end && range.end <= data.len()
+            });
+            if !in_range {
+                return Err(Error::BindingOutOfRange(binding.clone()));
+            }
+            let global = module.global_variables.iter().find(|&(_, var)| {
+                var.binding.as_ref() == Some(binding)
+                    && matches!(
+                        var.space,
+                        AddressSpace::Uniform | AddressSpace::Storage { .. }
+                    )
+            });
+            if let Some((handle, _)) = global {
+                buffers[handle.index()] = Some((memory, range.clone()));
+            }
+        }
+
+        for (handle, var) in module.global_variables.iter() {
+            if ep_info[handle].is_empty() {
+                continue;
+            }
+            match var.space {
+                AddressSpace::Uniform | AddressSpace::Storage { .. } => {
+                    let binding = var
+                        .binding
+                        .clone()
+                        .ok_or(Error::Invalid("buffer has no binding"))?;
+                    let size = match buffers[handle.index()] {
+                        Some((_, ref range)) => range.len(),
+                        None => return Err(Error::MissingBinding(binding)),
+                    };
+                    let required = self.required_size(var.ty);
+                    if size < required as usize {
+                        return Err(Error::BufferTooSmall {
+                            binding,
+                            size,
+                            required,
+                        });
+                    }
+                }
+                AddressSpace::Handle => return Err(Error::Unsupported("images and samplers")),
+                AddressSpace::PushConstant => return Err(Error::Unsupported("push constants")),
+                AddressSpace::Function | AddressSpace::Private | AddressSpace::WorkGroup => {}
+            }
+        }
+
+        let ctx = Context {
+            module,
+            info: self.info,
+            layouter: &self.layouter,
+            options: &self.options,
+            entry_point: index,
+            workgroup_size: ep.workgroup_size,
+            num_workgroups: workgroup_count,
+        };
+        let mut shared = Shared {
+            memory: bindings.memory.iter_mut().map(|data| &mut **data).collect(),
+            buffers,
+            workgroup: Vec::new(),
+            report: Report::default(),
+        };
+
+        let [count_x, count_y, count_z] = workgroup_count;
+        for z in 0..count_z {
+            for y in 0..count_y {
+                for x in 0..count_x {
+                    shared.workgroup = module
+                        .global_variables
+                        .iter()
+                        .map(|(_, var)| match var.space {
+                            AddressSpace::WorkGroup => vec![0; self.layouter[var.ty].size as usize],
+                            _ => Vec::new(),
+                        })
+                        .collect();
+                    run_workgroup(&ctx, &mut shared, [x, y, z])?;
+                }
+            }
+        }
+
+        Ok(shared.report)
+    }
+
+    /// The smallest buffer that can hold a value of type `ty`.
+    fn required_size(&self, ty: Handle<crate::Type>) -> u32 {
+        use crate::TypeInner as Ti;
+
+        let is_runtime_array = |ty: Handle<crate::Type>| match self.module.types[ty].inner {
+            Ti::Array {
+                size: crate::ArraySize::Dynamic,
+                ..
+            } => true,
+            _ => false,
+        };
+        match self.module.types[ty].inner {
+            Ti::Array {
+                size: crate::ArraySize::Dynamic,
+                ..
+            } => 0,
+            Ti::Struct { ref members, .. } => match members.last() {
+                Some(last) if is_runtime_array(last.ty) => last.offset,
+                _ => self.layouter[ty].size,
+            },
+            _ => self.layouter[ty].size,
+        }
+    }
+}
+
+/// What every invocation of a dispatch shares.
+struct Context<'m> {
+    module: &'m Module,
+    info: &'m ModuleInfo,
+    layouter: &'m Layouter,
+    options: &'m Options,
+    /// The index of the entry point in [`Module::entry_points`].
+    entry_point: usize,
+    workgroup_size: [u32; 3],
+    num_workgroups: [u32; 3],
+}
+
+impl Context<'_> {
+    /// Evaluate a constant expression.
+    fn eval_const(&self, handle: Handle<crate::Expression>) -> Result<Value, Error> {
+        use crate::Expression as E;
+
+        let types = &self.module.types;
+        Ok(match self.module.const_expressions[handle] {
+            E::Literal(literal) => Value::Scalar(literal),
+            E::Constant(constant) => self.eval_const(self.module.constants[constant].init)?,
+            E::ZeroValue(ty) => Value::zero(&types[ty].inner, types)?,
+            E::Compose { ty, ref components } => {
+                let components = components
+                    .iter()
+                    .map(|&component| self.eval_const(component))
+                    .collect::<Result<_, _>>()?;
+                value::compose(&types[ty].inner, components)?
+            }
+            E::Splat { size, value } => {
+                Value::Composite(vec![self.eval_const(value)?; size as usize])
+            }
+            _ => return Err(Error::Invalid("constant expression was not evaluated")),
+        })
+    }
+}
+
+/// The memory that invocations of a workgroup share, and the report they
+/// add to.
+struct Shared<'b> {
+    /// The memory of the bound buffers.
+    memory: Vec<&'b mut [u8]>,
+    /// The index in `memory` and range of the buffer bound to each global
+    /// variable, indexed by handle.
+    buffers: Vec<Option<(usize, Range<usize>)>>,
+    /// The contents of each global variable in the `WorkGroup` address
+    /// space, indexed by handle.
+    workgroup: Vec<Vec<u8>>,
+    report: Report,
+}
+
+fn run_workgroup(ctx: &Context, shared: &mut Shared, workgroup_id: [u32; 3]) -> Result<(), Error> {
+    let [size_x, size_y, size_z] = ctx.workgroup_size;
+    let mut invocations = Vec::new();
+    for z in 0..size_z {
+        for y in 0..size_y {
+            for x in 0..size_x {
+                let ids = Ids {
+                    global_invocation_id: [
+                        workgroup_id[0] * size_x + x,
+                        workgroup_id[1] * size_y + y,
+                        workgroup_id[2] * size_z + z,
+                    ],
+                    local_invocation_id: [x, y, z],
+                    local_invocation_index: (z * size_y + y) * size_x + x,
+                    workgroup_id,
+                };
+                invocations.push(Invocation::new(ctx, shared, ids)?);
+            }
+        }
+    }
+
+    let mut states = vec![State::Running; invocations.len()];
+    loop {
+        for (invocation, state) in invocations.iter_mut().zip(states.iter_mut()) {
+            if *state == State::Running {
+                *state = invocation.run(ctx, shared)?;
+            }
+        }
+
+        if states.contains(&State::Subgroup) {
+            resolve_subgroups(ctx, &mut invocations, &mut states)?;
+        } else if states.contains(&State::Barrier) {
+            for state in states.iter_mut() {
+                if *state == State::Barrier {
+                    *state = State::Running;
+                }
+            }
+        } else {
+            return Ok(());
+        }
+    }
+}
+
+/// Complete the subgroup operations that invocations wait on.
+///
+/// The invocations of a subgroup waiting on the same statement are its
+/// active invocations for that operation.
+fn resolve_subgroups(
+    ctx: &Context,
+    invocations: &mut [Invocation],
+    states: &mut [State],
+) -> Result<(), Error> {
+    let size = ctx.options.subgroup_size as usize;
+    for (invocations, states) in invocations.chunks_mut(size).zip(states.chunks_mut(size)) {
+        let mut waiting = (0..invocations.len())
+            .filter(|&i| states[i] == State::Subgroup)
+            .collect::<Vec<_>>();
+
+        while let Some(&first) = waiting.first() {
+            let statement = match invocations[first].subgroup_request {
+                Some(ref request) => request.statement,
+                None => return Err(Error::Invalid("no subgroup operation is pending")),
+            };
+            let (active, rest): (Vec<usize>, Vec<usize>) = waiting.iter().partition(|&&i| {
+                invocations[i]
+                    .subgroup_request
+                    .as_ref()
+                    .map_or(false, |request| std::ptr::eq(request.statement, statement))
+            });
+
+            let requests = active
+                .iter()
+                .filter_map(|&i| Some((i as u32, invocations[i].subgroup_request.as_ref()?)))
+                .collect::<Vec<_>>();
+            let results = subgroup::resolve(statement, &requests)?;
+            for (&i, result) in active.iter().zip(results) {
+                invocations[i].finish_subgroup(result)?;
+                states[i] = State::Running;
+            }
+            waiting = rest;
+        }
+    }
+    Ok(())
+}
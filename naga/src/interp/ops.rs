@@ -0,0 +1,775 @@
+//! Operators and built-in functions on [`Value`]s.
+//!
+//! Integer arithmetic wraps, and division by zero yields the dividend, as
+//! WGSL specifies. Float built-ins are computed in `f64` and rounded to the
+//! operands' type.
+
+use super::{value::Value, Error};
+use crate::{
+    proc::{determinant, f16_bits_to_f32, f32_to_f16_bits, frexp, inverse, ldexp, round_ties_even},
+    BinaryOperator, Literal, MathFunction, RelationalFunction, ScalarKind, UnaryOperator,
+};
+
+/// Return component `i` of `value`, or `value` itself if it is a scalar
+/// that should be applied to every component.
+fn lane(value: &Value, i: usize) -> Result<&Value, Error> {
+    match *value {
+        Value::Scalar(_) => Ok(value),
+        Value::Composite(ref components) => components
+            .get(i)
+            .ok_or(Error::Invalid("operands have different sizes")),
+        Value::Pointer(_) => Err(Error::Invalid("pointers have no components")),
+    }
+}
+
+/// Apply `f` to corresponding scalars of `args`, recursing into vectors and
+/// matrices, and broadcasting scalar arguments.
+pub(super) fn map<const N: usize>(
+    args: [&Value; N],
+    f: &mut impl FnMut([Literal; N]) -> Result<Literal, Error>,
+) -> Result<Value, Error> {
+    let len = args.iter().find_map(|arg| match **arg {
+        Value::Composite(ref components) => Some(components.len()),
+        _ => None,
+    });
+    match len {
+        None => {
+            let mut scalars = [Literal::Bool(false); N];
+            for (scalar, arg) in scalars.iter_mut().zip(args) {
+                *scalar = arg.scalar()?;
+            }
+            Ok(Value::Scalar(f(scalars)?))
+        }
+        Some(len) => (0..len)
+            .map(|i| {
+                let mut lanes = args;
+                for lane_arg in lanes.iter_mut() {
+                    *lane_arg = lane(lane_arg, i)?;
+                }
+                map(lanes, f)
+            })
+            .collect::<Result<_, _>>()
+            .map(Value::Composite),
+    }
+}
+
+fn float(literal: Literal) -> Result<f64, Error> {
+    match literal {
+        Literal::F32(value) => Ok(value.into()),
+        Literal::F64(value) | Literal::AbstractFloat(value) => Ok(value),
+        _ => Err(Error::Invalid("expected a float")),
+    }
+}
+
+/// Return `value` as a float literal of the same type as `like`.
+#[allow(clippy::missing_const_for_fn)] // float casts are not const on our MSRV
+fn float_like(like: Literal, value: f64) -> Result<Literal, Error> {
+    match like {
+        Literal::F32(_) => Ok(Literal::F32(value as f32)),
+        Literal::F64(_) => Ok(Literal::F64(value)),
+        Literal::AbstractFloat(_) => Ok(Literal::AbstractFloat(value)),
+        _ => Err(Error::Invalid("expected a float")),
+    }
+}
+
+fn float_map<const N: usize>(
+    args: [&Value; N],
+    f: impl Fn([f64; N]) -> f64,
+) -> Result<Value, Error> {
+    map(args, &mut |literals| {
+        let mut values = [0.0; N];
+        for (value, &literal) in values.iter_mut().zip(literals.iter()) {
+            *value = float(literal)?;
+        }
+        float_like(literals[0], f(values))
+    })
+}
+
+/// The scalars of a float scalar or vector, with the first one to describe
+/// their type.
+fn floats(value: &Value) -> Result<(Literal, Vec<f64>), Error> {
+    let literals = match *value {
+        Value::Scalar(literal) => vec![literal],
+        Value::Composite(ref components) => components
+            .iter()
+            .map(Value::scalar)
+            .collect::<Result<_, _>>()?,
+        Value::Pointer(_) => return Err(Error::Invalid("expected a float")),
+    };
+    let like = *literals.first().ok_or(Error::Invalid("empty vector"))?;
+    let values = literals.into_iter().map(float).collect::<Result<_, _>>()?;
+    Ok((like, values))
+}
+
+fn from_floats(like: Literal, values: &[f64]) -> Result<Value, Error> {
+    values
+        .iter()
+        .map(|&value| float_like(like, value).map(Value::Scalar))
+        .collect::<Result<_, _>>()
+        .map(Value::Composite)
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+pub(super) fn unary(op: UnaryOperator, value: &Value) -> Result<Value, Error> {
+    map([value], &mut |[literal]| {
+        Ok(match (op, literal) {
+            (UnaryOperator::Negate, Literal::I32(v)) => Literal::I32(v.wrapping_neg()),
+            (UnaryOperator::Negate, Literal::I64(v)) => Literal::I64(v.wrapping_neg()),
+            (UnaryOperator::Negate, Literal::AbstractInt(v)) => {
+                Literal::AbstractInt(v.wrapping_neg())
+            }
+            (UnaryOperator::Negate, Literal::F32(v)) => Literal::F32(-v),
+            (UnaryOperator::Negate, Literal::F64(v)) => Literal::F64(-v),
+            (UnaryOperator::Negate, Literal::AbstractFloat(v)) => Literal::AbstractFloat(-v),
+            (UnaryOperator::LogicalNot, Literal::Bool(v)) => Literal::Bool(!v),
+            (UnaryOperator::BitwiseNot, Literal::I32(v)) => Literal::I32(!v),
+            (UnaryOperator::BitwiseNot, Literal::U32(v)) => Literal::U32(!v),
+            (UnaryOperator::BitwiseNot, Literal::I64(v)) => Literal::I64(!v),
+            (UnaryOperator::BitwiseNot, Literal::AbstractInt(v)) => Literal::AbstractInt(!v),
+            _ => return Err(Error::Invalid("unary operator on this type")),
+        })
+    })
+}
+
+macro_rules! int_binary {
+    ($op:expr, $a:expr, $b:expr, $variant:path) => {{
+        let (a, b) = ($a, $b);
+        match $op {
+            Bo::Add => $variant(a.wrapping_add(b)),
+            Bo::Subtract => $variant(a.wrapping_sub(b)),
+            Bo::Multiply => $variant(a.wrapping_mul(b)),
+            // Dividing by zero, or `MIN` by -1, yields the dividend.
+            Bo::Divide => $variant(a.checked_div(b).unwrap_or(a)),
+            Bo::Modulo => $variant(a.checked_rem(b).unwrap_or(0)),
+            Bo::Equal => Literal::Bool(a == b),
+            Bo::NotEqual => Literal::Bool(a != b),
+            Bo::Less => Literal::Bool(a < b),
+            Bo::LessEqual => Literal::Bool(a <= b),
+            Bo::Greater => Literal::Bool(a > b),
+            Bo::GreaterEqual => Literal::Bool(a >= b),
+            Bo::And => $variant(a & b),
+            Bo::ExclusiveOr => $variant(a ^ b),
+            Bo::InclusiveOr => $variant(a | b),
+            _ => return Err(Error::Invalid("binary operator on integers")),
+        }
+    }};
+}
+
+macro_rules! float_binary {
+    ($op:expr, $a:expr, $b:expr, $variant:path) => {{
+        let (a, b) = ($a, $b);
+        match $op {
+            Bo::Add => $variant(a + b),
+            Bo::Subtract => $variant(a - b),
+            Bo::Multiply => $variant(a * b),
+            Bo::Divide => $variant(a / b),
+            Bo::Modulo => $variant(a - b * (a / b).trunc()),
+            Bo::Equal => Literal::Bool(a == b),
+            Bo::NotEqual => Literal::Bool(a != b),
+            Bo::Less => Literal::Bool(a < b),
+            Bo::LessEqual => Literal::Bool(a <= b),
+            Bo::Greater => Literal::Bool(a > b),
+            Bo::GreaterEqual => Literal::Bool(a >= b),
+            _ => return Err(Error::Invalid("binary operator on floats")),
+        }
+    }};
+}
+
+pub(super) fn binary_scalar(op: BinaryOperator, a: Literal, b: Literal) -> Result<Literal, Error> {
+    use BinaryOperator as Bo;
+
+    if let Bo::ShiftLeft | Bo::ShiftRight = op {
+        // Shift amounts are taken modulo the bit width.
+        let amount = match b {
+            Literal::U32(amount) => amount,
+            _ => return Err(Error::Invalid("shift amount must be u32")),
+        };
+        let left = op == Bo::ShiftLeft;
+        return Ok(match a {
+            Literal::I32(a) if left => Literal::I32(a.wrapping_shl(amount)),
+            Literal::I32(a) => Literal::I32(a.wrapping_shr(amount)),
+            Literal::U32(a) if left => Literal::U32(a.wrapping_shl(amount)),
+            Literal::U32(a) => Literal::U32(a.wrapping_shr(amount)),
+            Literal::I64(a) if left => Literal::I64(a.wrapping_shl(amount)),
+            Literal::I64(a) => Literal::I64(a.wrapping_shr(amount)),
+            Literal::AbstractInt(a) if left => Literal::AbstractInt(a.wrapping_shl(amount)),
+            Literal::AbstractInt(a) => Literal::AbstractInt(a.wrapping_shr(amount)),
+            _ => return Err(Error::Invalid("shift of a non-integer")),
+        });
+    }
+
+    Ok(match (a, b) {
+        (Literal::I32(a), Literal::I32(b)) => int_binary!(op, a, b, Literal::I32),
+        (Literal::U32(a), Literal::U32(b)) => int_binary!(op, a, b, Literal::U32),
+        (Literal::I64(a), Literal::I64(b)) => int_binary!(op, a, b, Literal::I64),
+        (Literal::AbstractInt(a), Literal::AbstractInt(b)) => {
+            int_binary!(op, a, b, Literal::AbstractInt)
+        }
+        (Literal::F32(a), Literal::F32(b)) => float_binary!(op, a, b, Literal::F32),
+        (Literal::F64(a), Literal::F64(b)) => float_binary!(op, a, b, Literal::F64),
+        (Literal::AbstractFloat(a), Literal::AbstractFloat(b)) => {
+            float_binary!(op, a, b, Literal::AbstractFloat)
+        }
+        (Literal::Bool(a), Literal::Bool(b)) => match op {
+            Bo::Equal => Literal::Bool(a == b),
+            Bo::NotEqual => Literal::Bool(a != b),
+            Bo::And | Bo::LogicalAnd => Literal::Bool(a && b),
+            Bo::InclusiveOr | Bo::LogicalOr => Literal::Bool(a || b),
+            Bo::ExclusiveOr => Literal::Bool(a != b),
+            _ => return Err(Error::Invalid("binary operator on bools")),
+        },
+        _ => return Err(Error::Invalid("binary operands have different types")),
+    })
+}
+
+/// The shape of a binary operand, which decides how `Multiply` applies.
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum Shape {
+    Matrix,
+    Vector,
+    Other,
+}
+
+pub(super) fn binary(
+    op: BinaryOperator,
+    left: &Value,
+    right: &Value,
+    shapes: (Shape, Shape),
+) -> Result<Value, Error> {
+    if op == BinaryOperator::Multiply {
+        match shapes {
+            (Shape::Matrix, Shape::Vector) => return matrix_times_vector(left, right),
+            (Shape::Vector, Shape::Matrix) => {
+                let column_dots = right
+                    .components()?
+                    .iter()
+                    .map(|column| dot_values(left, column).map(Value::Scalar))
+                    .collect::<Result<_, _>>()?;
+                return Ok(Value::Composite(column_dots));
+            }
+            (Shape::Matrix, Shape::Matrix) => {
+                let columns = right
+                    .components()?
+                    .iter()
+                    .map(|column| matrix_times_vector(left, column))
+                    .collect::<Result<_, _>>()?;
+                return Ok(Value::Composite(columns));
+            }
+            _ => {}
+        }
+    }
+    map([left, right], &mut |[a, b]| binary_scalar(op, a, b))
+}
+
+fn matrix_times_vector(matrix: &Value, vector: &Value) -> Result<Value, Error> {
+    let mut sum: Option<Value> = None;
+    for (column, component) in matrix.components()?.iter().zip(vector.components()?) {
+        let product = map([column, component], &mut |[a, b]| {
+            binary_scalar(BinaryOperator::Multiply, a, b)
+        })?;
+        sum = Some(match sum {
+            Some(sum) => map([&sum, &product], &mut |[a, b]| {
+                binary_scalar(BinaryOperator::Add, a, b)
+            })?,
+            None => product,
+        });
+    }
+    sum.ok_or(Error::Invalid("empty matrix"))
+}
+
+fn dot_values(a: &Value, b: &Value) -> Result<Literal, Error> {
+    let mut sum = None;
+    for (a, b) in a.components()?.iter().zip(b.components()?) {
+        let product = binary_scalar(BinaryOperator::Multiply, a.scalar()?, b.scalar()?)?;
+        sum = Some(match sum {
+            Some(sum) => binary_scalar(BinaryOperator::Add, sum, product)?,
+            None => product,
+        });
+    }
+    sum.ok_or(Error::Invalid("empty vector"))
+}
+
+pub(super) fn select(condition: &Value, accept: &Value, reject: &Value) -> Result<Value, Error> {
+    match *condition {
+        Value::Scalar(Literal::Bool(condition)) => {
+            Ok(if condition { accept } else { reject }.clone())
+        }
+        Value::Composite(ref conditions) => conditions
+            .iter()
+            .zip(accept.components()?.iter().zip(reject.components()?))
+            .map(|(condition, (accept, reject))| select(condition, accept, reject))
+            .collect::<Result<_, _>>()
+            .map(Value::Composite),
+        _ => Err(Error::Invalid("select condition must be bool")),
+    }
+}
+
+pub(super) fn relational(fun: RelationalFunction, argument: &Value) -> Result<Value, Error> {
+    match fun {
+        RelationalFunction::All | RelationalFunction::Any => {
+            let all = fun == RelationalFunction::All;
+            let mut result = all;
+            for component in argument.components()? {
+                if component.as_bool()? != all {
+                    result = !all;
+                }
+            }
+            Ok(Value::Scalar(Literal::Bool(result)))
+        }
+        RelationalFunction::IsNan => map([argument], &mut |[literal]| {
+            Ok(Literal::Bool(float(literal)?.is_nan()))
+        }),
+        RelationalFunction::IsInf => map([argument], &mut |[literal]| {
+            Ok(Literal::Bool(float(literal)?.is_infinite()))
+        }),
+    }
+}
+
+/// Convert or bitcast `value` to `kind`.
+///
+/// With a `convert` width, this converts numerically, saturating floats that
+/// are out of range of an integer type. Otherwise, this reinterprets bits.
+pub(super) fn cast(value: &Value, kind: ScalarKind, convert: Option<u8>) -> Result<Value, Error> {
+    map([value], &mut |[literal]| match convert {
+        Some(width) => convert_scalar(literal, kind, width),
+        None => bitcast_scalar(literal, kind),
+    })
+}
+
+fn convert_scalar(literal: Literal, kind: ScalarKind, width: u8) -> Result<Literal, Error> {
+    enum Number {
+        Int(i64),
+        Float(f64),
+    }
+    let number = match literal {
+        Literal::Bool(value) => Number::Int(value as i64),
+        Literal::I32(value) => Number::Int(value.into()),
+        Literal::U32(value) => Number::Int(value.into()),
+        Literal::I64(value) | Literal::AbstractInt(value) => Number::Int(value),
+        Literal::F32(value) => Number::Float(value.into()),
+        Literal::F64(value) | Literal::AbstractFloat(value) => Number::Float(value),
+    };
+    // Float to integer `as` casts saturate, and turn NaN into zero.
+    Ok(match (kind, width, number) {
+        (ScalarKind::Bool, _, Number::Int(value)) => Literal::Bool(value != 0),
+        (ScalarKind::Bool, _, Number::Float(value)) => Literal::Bool(value != 0.0),
+        (ScalarKind::Sint, 4, Number::Int(value)) => Literal::I32(value as i32),
+        (ScalarKind::Sint, 4, Number::Float(value)) => Literal::I32(value as i32),
+        (ScalarKind::Uint, 4, Number::Int(value)) => Literal::U32(value as u32),
+        (ScalarKind::Uint, 4, Number::Float(value)) => Literal::U32(value as u32),
+        (ScalarKind::Sint, 8, Number::Int(value)) => Literal::I64(value),
+        (ScalarKind::Sint, 8, Number::Float(value)) => Literal::I64(value as i64),
+        (ScalarKind::Float, 4, Number::Int(value)) => Literal::F32(value as f32),
+        (ScalarKind::Float, 4, Number::Float(value)) => Literal::F32(value as f32),
+        (ScalarKind::Float, 8, Number::Int(value)) => Literal::F64(value as f64),
+        (ScalarKind::Float, 8, Number::Float(value)) => Literal::F64(value),
+        _ => return Err(Error::Unsupported("conversions to this type")),
+    })
+}
+
+fn bitcast_scalar(literal: Literal, kind: ScalarKind) -> Result<Literal, Error> {
+    let bits = match literal {
+        Literal::I32(value) => value as u32 as u64,
+        Literal::U32(value) => value.into(),
+        Literal::F32(value) => value.to_bits().into(),
+        Literal::I64(value) => {
+            return match kind {
+                ScalarKind::Sint => Ok(literal),
+                ScalarKind::Float => Ok(Literal::F64(f64::from_bits(value as u64))),
+                _ => Err(Error::Unsupported("bitcasts to this type")),
+            }
+        }
+        Literal::F64(value) => {
+            return match kind {
+                ScalarKind::Float => Ok(literal),
+                ScalarKind::Sint => Ok(Literal::I64(value.to_bits() as i64)),
+                _ => Err(Error::Unsupported("bitcasts to this type")),
+            }
+        }
+        _ => return Err(Error::Invalid("bitcast of a non-numeric value")),
+    };
+    let bits = bits as u32;
+    Ok(match kind {
+        ScalarKind::Sint => Literal::I32(bits as i32),
+        ScalarKind::Uint => Literal::U32(bits),
+        ScalarKind::Float => Literal::F32(f32::from_bits(bits)),
+        _ => return Err(Error::Invalid("bitcast to a non-numeric type")),
+    })
+}
+
+fn min_scalar(a: Literal, b: Literal) -> Result<Literal, Error> {
+    let less = binary_scalar(BinaryOperator::Less, b, a)?;
+    Ok(if less == Literal::Bool(true) { b } else { a })
+}
+
+fn max_scalar(a: Literal, b: Literal) -> Result<Literal, Error> {
+    let greater = binary_scalar(BinaryOperator::Greater, b, a)?;
+    Ok(if greater == Literal::Bool(true) { b } else { a })
+}
+
+pub(super) fn min(a: &Value, b: &Value) -> Result<Value, Error> {
+    map([a, b], &mut |[a, b]| min_scalar(a, b))
+}
+
+pub(super) fn max(a: &Value, b: &Value) -> Result<Value, Error> {
+    map([a, b], &mut |[a, b]| max_scalar(a, b))
+}
+
+/// Apply `f` to an `i32` or `u32`, passing its bits and whether it is signed.
+fn int_bits(literal: Literal, f: impl Fn(u32, bool) -> u32) -> Result<Literal, Error> {
+    match literal {
+        Literal::I32(value) => Ok(Literal::I32(f(value as u32, true) as i32)),
+        Literal::U32(value) => Ok(Literal::U32(f(value, false))),
+        _ => Err(Error::Unsupported("bit functions on this type")),
+    }
+}
+
+pub(super) fn math(fun: MathFunction, args: &[Value]) -> Result<Value, Error> {
+    use MathFunction as Mf;
+
+    let arg = |i: usize| -> Result<&Value, Error> {
+        args.get(i).ok_or(Error::Invalid("missing math argument"))
+    };
+
+    match fun {
+        // comparison
+        Mf::Abs => map([arg(0)?], &mut |[e]| {
+            Ok(match e {
+                Literal::I32(e) => Literal::I32(e.wrapping_abs()),
+                Literal::I64(e) => Literal::I64(e.wrapping_abs()),
+                Literal::AbstractInt(e) => Literal::AbstractInt(e.wrapping_abs()),
+                Literal::U32(_) => e,
+                e => float_like(e, float(e)?.abs())?,
+            })
+        }),
+        Mf::Min => min(arg(0)?, arg(1)?),
+        Mf::Max => max(arg(0)?, arg(1)?),
+        Mf::Clamp => map([arg(0)?, arg(1)?, arg(2)?], &mut |[e, low, high]| {
+            min_scalar(max_scalar(e, low)?, high)
+        }),
+        Mf::Saturate => float_map([arg(0)?], |[e]| e.clamp(0.0, 1.0)),
+        // trigonometry
+        Mf::Cos => float_map([arg(0)?], |[e]| e.cos()),
+        Mf::Cosh => float_map([arg(0)?], |[e]| e.cosh()),
+        Mf::Sin => float_map([arg(0)?], |[e]| e.sin()),
+        Mf::Sinh => float_map([arg(0)?], |[e]| e.sinh()),
+        Mf::Tan => float_map([arg(0)?], |[e]| e.tan()),
+        Mf::Tanh => float_map([arg(0)?], |[e]| e.tanh()),
+        Mf::Acos => float_map([arg(0)?], |[e]| e.acos()),
+        Mf::Asin => float_map([arg(0)?], |[e]| e.asin()),
+        Mf::Atan => float_map([arg(0)?], |[e]| e.atan()),
+        Mf::Atan2 => float_map([arg(0)?, arg(1)?], |[y, x]| y.atan2(x)),
+        Mf::Asinh => float_map([arg(0)?], |[e]| e.asinh()),
+        Mf::Acosh => float_map([arg(0)?], |[e]| e.acosh()),
+        Mf::Atanh => float_map([arg(0)?], |[e]| e.atanh()),
+        Mf::Radians => float_map([arg(0)?], |[e]| e.to_radians()),
+        Mf::Degrees => float_map([arg(0)?], |[e]| e.to_degrees()),
+        // decomposition
+        Mf::Ceil => float_map([arg(0)?], |[e]| e.ceil()),
+        Mf::Floor => float_map([arg(0)?], |[e]| e.floor()),
+        Mf::Round => float_map([arg(0)?], |[e]| round_ties_even(e)),
+        Mf::Fract => float_map([arg(0)?], |[e]| e - e.floor()),
+        Mf::Trunc => float_map([arg(0)?], |[e]| e.trunc()),
+        Mf::Modf => Ok(Value::Composite(vec![
+            float_map([arg(0)?], |[e]| e - e.trunc())?,
+            float_map([arg(0)?], |[e]| e.trunc())?,
+        ])),
+        Mf::Frexp => Ok(Value::Composite(vec![
+            float_map([arg(0)?], |[e]| frexp(e).0)?,
+            map([arg(0)?], &mut |[e]| Ok(Literal::I32(frexp(float(e)?).1)))?,
+        ])),
+        Mf::Ldexp => map([arg(0)?, arg(1)?], &mut |[e, exponent]| {
+            let exponent = match exponent {
+                Literal::I32(exponent) => exponent.into(),
+                Literal::I64(exponent) | Literal::AbstractInt(exponent) => exponent,
+                _ => return Err(Error::Invalid("ldexp exponent must be an integer")),
+            };
+            float_like(e, ldexp(float(e)?, exponent))
+        }),
+        // exponent
+        Mf::Exp => float_map([arg(0)?], |[e]| e.exp()),
+        Mf::Exp2 => float_map([arg(0)?], |[e]| e.exp2()),
+        Mf::Log => float_map([arg(0)?], |[e]| e.ln()),
+        Mf::Log2 => float_map([arg(0)?], |[e]| e.log2()),
+        Mf::Pow => float_map([arg(0)?, arg(1)?], |[e1, e2]| e1.powf(e2)),
+        // geometry
+        Mf::Dot => dot_values(arg(0)?, arg(1)?).map(Value::Scalar),
+        Mf::Outer => {
+            let (a, b) = (arg(0)?, arg(1)?);
+            b.components()?
+                .iter()
+                .map(|b| binary(BinaryOperator::Multiply, a, b, (Shape::Other, Shape::Other)))
+                .collect::<Result<_, _>>()
+                .map(Value::Composite)
+        }
+        Mf::Cross => {
+            let (like, a) = floats(arg(0)?)?;
+            let (_, b) = floats(arg(1)?)?;
+            if a.len() != 3 || b.len() != 3 {
+                return Err(Error::Invalid("cross of non-3-component vectors"));
+            }
+            from_floats(
+                like,
+                &[
+                    a[1] * b[2] - a[2] * b[1],
+                    a[2] * b[0] - a[0] * b[2],
+                    a[0] * b[1] - a[1] * b[0],
+                ],
+            )
+        }
+        Mf::Distance => {
+            let (like, a) = floats(arg(0)?)?;
+            let (_, b) = floats(arg(1)?)?;
+            let difference: Vec<_> = a.iter().zip(&b).map(|(a, b)| a - b).collect();
+            float_like(like, dot(&difference, &difference).sqrt()).map(Value::Scalar)
+        }
+        Mf::Length => {
+            let (like, a) = floats(arg(0)?)?;
+            float_like(like, dot(&a, &a).sqrt()).map(Value::Scalar)
+        }
+        Mf::Normalize => {
+            let (like, a) = floats(arg(0)?)?;
+            let length = dot(&a, &a).sqrt();
+            let normalized: Vec<_> = a.iter().map(|a| a / length).collect();
+            from_floats(like, &normalized)
+        }
+        Mf::FaceForward => {
+            let (_, i) = floats(arg(1)?)?;
+            let (_, n_ref) = floats(arg(2)?)?;
+            if dot(&n_ref, &i) < 0.0 {
+                Ok(arg(0)?.clone())
+            } else {
+                unary(UnaryOperator::Negate, arg(0)?)
+            }
+        }
+        Mf::Reflect => {
+            let (like, i) = floats(arg(0)?)?;
+            let (_, n) = floats(arg(1)?)?;
+            let d = dot(&n, &i);
+            let reflected: Vec<_> = i.iter().zip(&n).map(|(i, n)| i - 2.0 * d * n).collect();
+            from_floats(like, &reflected)
+        }
+        Mf::Refract => {
+            let (like, i) = floats(arg(0)?)?;
+            let (_, n) = floats(arg(1)?)?;
+            let eta = float(arg(2)?.scalar()?)?;
+            let d = dot(&n, &i);
+            let k = 1.0 - eta * eta * (1.0 - d * d);
+            let refracted: Vec<_> = if k < 0.0 {
+                vec![0.0; i.len()]
+            } else {
+                i.iter()
+                    .zip(&n)
+                    .map(|(i, n)| eta * i - (eta * d + k.sqrt()) * n)
+                    .collect()
+            };
+            from_floats(like, &refracted)
+        }
+        // computational
+        Mf::Sign => map([arg(0)?], &mut |[e]| {
+            Ok(match e {
+                Literal::I32(e) => Literal::I32(e.signum()),
+                Literal::I64(e) => Literal::I64(e.signum()),
+                Literal::AbstractInt(e) => Literal::AbstractInt(e.signum()),
+                e => {
+                    let value = float(e)?;
+                    let sign = if value > 0.0 {
+                        1.0
+                    } else if value < 0.0 {
+                        -1.0
+                    } else {
+                        value
+                    };
+                    float_like(e, sign)?
+                }
+            })
+        }),
+        Mf::Fma => float_map([arg(0)?, arg(1)?, arg(2)?], |[a, b, c]| a.mul_add(b, c)),
+        Mf::Mix => float_map([arg(0)?, arg(1)?, arg(2)?], |[a, b, t]| {
+            a * (1.0 - t) + b * t
+        }),
+        Mf::Step => float_map(
+            [arg(0)?, arg(1)?],
+            |[edge, x]| {
+                if x < edge {
+                    0.0
+                } else {
+                    1.0
+                }
+            },
+        ),
+        Mf::SmoothStep => float_map([arg(0)?, arg(1)?, arg(2)?], |[low, high, x]| {
+            let t = ((x - low) / (high - low)).clamp(0.0, 1.0);
+            t * t * (3.0 - 2.0 * t)
+        }),
+        Mf::Sqrt => float_map([arg(0)?], |[e]| e.sqrt()),
+        Mf::InverseSqrt => float_map([arg(0)?], |[e]| 1.0 / e.sqrt()),
+        Mf::Inverse => {
+            let (like, columns) = matrix_floats(arg(0)?)?;
+            let inverse = inverse(&columns)
+                .unwrap_or_else(|| vec![vec![f64::NAN; columns.len()]; columns.len()]);
+            inverse
+                .iter()
+                .map(|column| from_floats(like, column))
+                .collect::<Result<_, _>>()
+                .map(Value::Composite)
+        }
+        Mf::Transpose => {
+            let (like, columns) = matrix_floats(arg(0)?)?;
+            let rows = columns.first().map_or(0, Vec::len);
+            (0..rows)
+                .map(|row| {
+                    let column: Vec<_> = columns.iter().map(|column| column[row]).collect();
+                    from_floats(like, &column)
+                })
+                .collect::<Result<_, _>>()
+                .map(Value::Composite)
+        }
+        Mf::Determinant => {
+            let (like, columns) = matrix_floats(arg(0)?)?;
+            float_like(like, determinant(&columns)).map(Value::Scalar)
+        }
+        // bits
+        Mf::CountTrailingZeros => map([arg(0)?], &mut |[e]| {
+            int_bits(e, |bits, _| bits.trailing_zeros())
+        }),
+        Mf::CountLeadingZeros => map([arg(0)?], &mut |[e]| {
+            int_bits(e, |bits, _| bits.leading_zeros())
+        }),
+        Mf::CountOneBits => map([arg(0)?], &mut |[e]| {
+            int_bits(e, |bits, _| bits.count_ones())
+        }),
+        Mf::ReverseBits => map([arg(0)?], &mut |[e]| {
+            int_bits(e, |bits, _| bits.reverse_bits())
+        }),
+        Mf::ExtractBits => {
+            let offset = arg(1)?.as_u32()?.min(32);
+            let count = arg(2)?.as_u32()?.min(32 - offset);
+            map([arg(0)?], &mut |[e]| {
+                int_bits(e, |bits, signed| {
+                    if count == 0 {
+                        return 0;
+                    }
+                    let field = bits.wrapping_shr(offset) & mask(count);
+                    let sign_bit = 1 << (count - 1);
+                    if signed && field & sign_bit != 0 {
+                        field | !mask(count)
+                    } else {
+                        field
+                    }
+                })
+            })
+        }
+        Mf::InsertBits => {
+            let offset = arg(2)?.as_u32()?.min(32);
+            let count = arg(3)?.as_u32()?.min(32 - offset);
+            let field_mask = mask(count).wrapping_shl(offset);
+            map([arg(0)?, arg(1)?], &mut |[e, new_bits]| {
+                let new_bits = match new_bits {
+                    Literal::I32(value) => value as u32,
+                    Literal::U32(value) => value,
+                    _ => return Err(Error::Invalid("insertBits of a non-integer")),
+                };
+                int_bits(e, |bits, _| {
+                    (bits & !field_mask) | (new_bits.wrapping_shl(offset) & field_mask)
+                })
+            })
+        }
+        Mf::FindLsb => map([arg(0)?], &mut |[e]| {
+            int_bits(
+                e,
+                |bits, _| {
+                    if bits == 0 {
+                        !0
+                    } else {
+                        bits.trailing_zeros()
+                    }
+                },
+            )
+        }),
+        Mf::FindMsb => map([arg(0)?], &mut |[e]| {
+            int_bits(e, |bits, signed| {
+                // For negative signed values, find the most significant zero bit.
+                let bits = if signed && bits & (1 << 31) != 0 {
+                    !bits
+                } else {
+                    bits
+                };
+                if bits == 0 {
+                    !0
+                } else {
+                    31 - bits.leading_zeros()
+                }
+            })
+        }),
+        // data packing
+        Mf::Pack4x8snorm => pack(arg(0)?, 8, |e| (e.clamp(-1.0, 1.0) * 127.0).round() as i32),
+        Mf::Pack4x8unorm => pack(arg(0)?, 8, |e| (e.clamp(0.0, 1.0) * 255.0).round() as i32),
+        Mf::Pack2x16snorm => pack(arg(0)?, 16, |e| {
+            (e.clamp(-1.0, 1.0) * 32767.0).round() as i32
+        }),
+        Mf::Pack2x16unorm => pack(arg(0)?, 16, |e| {
+            (e.clamp(0.0, 1.0) * 65535.0).round() as i32
+        }),
+        Mf::Pack2x16float => pack(arg(0)?, 16, |e| {
+            // Values out of range of `f16` become infinity.
+            let value = e as f32;
+            let infinity = if value < 0.0 { 0xfc00 } else { 0x7c00 };
+            f32_to_f16_bits(value).unwrap_or(infinity).into()
+        }),
+        // data unpacking
+        Mf::Unpack4x8snorm => unpack(arg(0)?, 8, |bits| {
+            (f64::from(bits as u8 as i8) / 127.0).max(-1.0)
+        }),
+        Mf::Unpack4x8unorm => unpack(arg(0)?, 8, |bits| f64::from(bits as u8) / 255.0),
+        Mf::Unpack2x16snorm => unpack(arg(0)?, 16, |bits| {
+            (f64::from(bits as u16 as i16) / 32767.0).max(-1.0)
+        }),
+        Mf::Unpack2x16unorm => unpack(arg(0)?, 16, |bits| f64::from(bits as u16) / 65535.0),
+        Mf::Unpack2x16float => unpack(arg(0)?, 16, |bits| f16_bits_to_f32(bits as u16).into()),
+    }
+}
+
+/// A mask of the `count` lowest bits.
+const fn mask(count: u32) -> u32 {
+    if count >= 32 {
+        !0
+    } else {
+        (1 << count) - 1
+    }
+}
+
+fn matrix_floats(matrix: &Value) -> Result<(Literal, Vec<Vec<f64>>), Error> {
+    let mut like = None;
+    let columns = matrix
+        .components()?
+        .iter()
+        .map(|column| {
+            let (column_like, values) = floats(column)?;
+            like = Some(column_like);
+            Ok(values)
+        })
+        .collect::<Result<_, Error>>()?;
+    Ok((like.ok_or(Error::Invalid("empty matrix"))?, columns))
+}
+
+/// Pack the components of a float vector into a `u32`, `bits` bits each,
+/// the first component in the lowest bits.
+fn pack(vector: &Value, bits: u32, f: impl Fn(f64) -> i32) -> Result<Value, Error> {
+    let (_, values) = floats(vector)?;
+    let packed = values.iter().enumerate().fold(0u32, |packed, (i, &value)| {
+        packed | ((f(value) as u32 & mask(bits)) << (i as u32 * bits))
+    });
+    Ok(Value::Scalar(Literal::U32(packed)))
+}
+
+fn unpack(packed: &Value, bits: u32, f: impl Fn(u32) -> f64) -> Result<Value, Error> {
+    let packed = packed.as_u32()?;
+    let values: Vec<_> = (0..32 / bits)
+        .map(|i| f((packed >> (i * bits)) & mask(bits)))
+        .collect();
+    from_floats(Literal::F32(0.0), &values)
+}
@@ -0,0 +1,148 @@
+//! Subgroup operations, resolved once every active invocation of a subgroup
+//! has reached them.
+
+use super::{
+    invocation::SubgroupRequest,
+    ops::{self, Shape},
+    value::Value,
+    Error,
+};
+use crate::{
+    BinaryOperator, CollectiveOperation, GatherMode, Literal, Statement, SubgroupOperation,
+};
+
+/// Compute the result of `statement` for each invocation in `requests`.
+///
+/// `requests` holds the subgroup invocation id and request of each active
+/// invocation of one subgroup, sorted by id. All are waiting on
+/// `statement`.
+pub(super) fn resolve(
+    statement: &Statement,
+    requests: &[(u32, &SubgroupRequest)],
+) -> Result<Vec<Value>, Error> {
+    let argument = |request: &SubgroupRequest| {
+        request
+            .argument
+            .clone()
+            .ok_or(Error::Invalid("subgroup operation has no argument"))
+    };
+
+    match *statement {
+        Statement::SubgroupElect { .. } => Ok((0..requests.len())
+            .map(|i| Value::Scalar(Literal::Bool(i == 0)))
+            .collect()),
+        Statement::SubgroupBallot { .. } => {
+            let mut mask = [0u32; 4];
+            for &(id, request) in requests {
+                let vote = match request.argument {
+                    Some(ref predicate) => predicate.as_bool()?,
+                    None => true,
+                };
+                if vote {
+                    mask[id as usize / 32] |= 1 << (id % 32);
+                }
+            }
+            let ballot = Value::Composite(
+                mask.iter()
+                    .map(|&bits| Value::Scalar(Literal::U32(bits)))
+                    .collect(),
+            );
+            Ok(vec![ballot; requests.len()])
+        }
+        Statement::SubgroupGather { mode, .. } => requests
+            .iter()
+            .map(|&(id, request)| {
+                let index = request.index.unwrap_or(0);
+                let source = match mode {
+                    GatherMode::BroadcastFirst => Some(requests[0].0),
+                    GatherMode::Broadcast(_) | GatherMode::Shuffle(_) => Some(index),
+                    GatherMode::ShuffleDown(_) => id.checked_add(index),
+                    GatherMode::ShuffleUp(_) => id.checked_sub(index),
+                    GatherMode::ShuffleXor(_) => Some(id ^ index),
+                };
+                // Reading an inactive or nonexistent invocation is undefined;
+                // return the invocation's own value.
+                let source = source
+                    .and_then(|source| requests.iter().find(|&&(other, _)| other == source))
+                    .map_or(request, |&(_, source)| source);
+                argument(source)
+            })
+            .collect(),
+        Statement::SubgroupCollectiveOperation {
+            op, collective_op, ..
+        } => {
+            let values = requests
+                .iter()
+                .map(|&(_, request)| argument(request))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut accumulator = identity(op, &values[0])?;
+            let mut results = Vec::with_capacity(values.len());
+            for value in values.iter() {
+                let next = combine(op, &accumulator, value)?;
+                let previous = std::mem::replace(&mut accumulator, next);
+                if collective_op == CollectiveOperation::ExclusiveScan {
+                    results.push(previous);
+                } else {
+                    results.push(accumulator.clone());
+                }
+            }
+            if collective_op == CollectiveOperation::Reduce {
+                results = vec![accumulator; values.len()];
+            }
+            Ok(results)
+        }
+        _ => Err(Error::Invalid("not a subgroup operation")),
+    }
+}
+
+fn combine(op: SubgroupOperation, a: &Value, b: &Value) -> Result<Value, Error> {
+    let binary = |op| ops::binary(op, a, b, (Shape::Other, Shape::Other));
+    match op {
+        SubgroupOperation::All => binary(BinaryOperator::LogicalAnd),
+        SubgroupOperation::Any => binary(BinaryOperator::LogicalOr),
+        SubgroupOperation::Add => binary(BinaryOperator::Add),
+        SubgroupOperation::Mul => binary(BinaryOperator::Multiply),
+        SubgroupOperation::Min => ops::min(a, b),
+        SubgroupOperation::Max => ops::max(a, b),
+        SubgroupOperation::And => binary(BinaryOperator::And),
+        SubgroupOperation::Or => binary(BinaryOperator::InclusiveOr),
+        SubgroupOperation::Xor => binary(BinaryOperator::ExclusiveOr),
+    }
+}
+
+/// Return the value of `op`'s type and shape that leaves others unchanged
+/// when combined with them.
+fn identity(op: SubgroupOperation, like: &Value) -> Result<Value, Error> {
+    ops::map([like], &mut |[literal]| {
+        let scalar = literal.scalar();
+        let identity = match op {
+            SubgroupOperation::All => Some(Literal::Bool(true)),
+            SubgroupOperation::Any | SubgroupOperation::Add => Literal::zero(scalar),
+            SubgroupOperation::Or | SubgroupOperation::Xor => Literal::zero(scalar),
+            SubgroupOperation::Mul => Literal::one(scalar),
+            SubgroupOperation::And => match literal {
+                Literal::I32(_) => Some(Literal::I32(-1)),
+                Literal::U32(_) => Some(Literal::U32(u32::MAX)),
+                Literal::I64(_) => Some(Literal::I64(-1)),
+                _ => None,
+            },
+            SubgroupOperation::Min => match literal {
+                Literal::I32(_) => Some(Literal::I32(i32::MAX)),
+                Literal::U32(_) => Some(Literal::U32(u32::MAX)),
+                Literal::I64(_) => Some(Literal::I64(i64::MAX)),
+                Literal::F32(_) => Some(Literal::F32(f32::INFINITY)),
+                Literal::F64(_) => Some(Literal::F64(f64::INFINITY)),
+                _ => None,
+            },
+            SubgroupOperation::Max => match literal {
+                Literal::I32(_) => Some(Literal::I32(i32::MIN)),
+                Literal::U32(_) => Some(Literal::U32(0)),
+                Literal::I64(_) => Some(Literal::I64(i64::MIN)),
+                Literal::F32(_) => Some(Literal::F32(f32::NEG_INFINITY)),
+                Literal::F64(_) => Some(Literal::F64(f64::NEG_INFINITY)),
+                _ => None,
+            },
+        };
+        identity.ok_or(Error::Invalid("subgroup operation on this type"))
+    })
+}
@@ -0,0 +1,226 @@
+use super::{Bindings, Error, Interpreter, Options};
+use crate::{
+    front::wgsl::source_provider::SingleFile,
+    proc::{BoundsCheckPolicies, BoundsCheckPolicy},
+    valid::{Capabilities, ValidationFlags, Validator},
+};
+
+fn compile(source: &str) -> (crate::Module, crate::valid::ModuleInfo) {
+    let source = SingleFile::new("test.wgsl", source);
+    let module = match crate::front::wgsl::parse_module(&source, SingleFile::ID) {
+        Ok(module) => module,
+        Err(error) => panic!("{}", error.emit_to_string_with_provider(&source)),
+    };
+    let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+        .validate(&module)
+        .unwrap();
+    (module, info)
+}
+
+fn words(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+#[test]
+fn arithmetic_and_control_flow() {
+    let (module, info) = compile(
+        "
+        @group(0) @binding(0) var<storage, read_write> out: array<u32>;
+
+        fn triangle(n: u32) -> u32 {
+            var sum = 0u;
+            for (var i = 1u; i <= n; i++) {
+                sum += i;
+            }
+            return sum;
+        }
+
+        @compute @workgroup_size(4)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            var value: u32;
+            switch id.x {
+                case 0u: { value = triangle(10u); }
+                case 1u: { value = u32(dot(vec2(3.0, 4.0), vec2(3.0, 4.0))); }
+                case 2u: {
+                    let m = mat2x2(1.0, 2.0, 3.0, 4.0);
+                    let v = m * vec2(1.0, 1.0);
+                    value = u32(v.x * 10.0 + v.y);
+                }
+                default: { value = select(1u, 2u, id.x > 2u) << 4u; }
+            }
+            out[id.x] = value;
+        }
+        ",
+    );
+    let interpreter = Interpreter::new(&module, &info, Options::default()).unwrap();
+    let mut out = [0u8; 16];
+    let report = interpreter
+        .dispatch("main", [1, 1, 1], Bindings::new().buffer(0, 0, &mut out))
+        .unwrap();
+    assert!(report.out_of_bounds.is_empty());
+    assert_eq!(words(&out), [55, 25, 46, 32]);
+}
+
+#[test]
+fn workgroup_memory_and_barriers() {
+    let (module, info) = compile(
+        "
+        var<workgroup> scratch: array<u32, 8>;
+        @group(0) @binding(0) var<storage, read_write> out: array<u32>;
+
+        @compute @workgroup_size(8)
+        fn main(
+            @builtin(local_invocation_index) index: u32,
+            @builtin(workgroup_id) group: vec3<u32>,
+        ) {
+            scratch[index] = index + group.x * 100u;
+            workgroupBarrier();
+            out[group.x * 8u + index] = scratch[7u - index];
+        }
+        ",
+    );
+    let interpreter = Interpreter::new(&module, &info, Options::default()).unwrap();
+    let mut out = [0u8; 64];
+    interpreter
+        .dispatch("main", [2, 1, 1], Bindings::new().buffer(0, 0, &mut out))
+        .unwrap();
+    assert_eq!(
+        words(&out),
+        [7, 6, 5, 4, 3, 2, 1, 0, 107, 106, 105, 104, 103, 102, 101, 100]
+    );
+}
+
+#[test]
+fn atomics() {
+    let (module, info) = compile(
+        "
+        struct Counters {
+            sum: atomic<u32>,
+            max: atomic<u32>,
+        }
+        @group(0) @binding(0) var<storage, read_write> counters: Counters;
+
+        @compute @workgroup_size(16)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            atomicAdd(&counters.sum, id.x);
+            atomicMax(&counters.max, id.x);
+        }
+        ",
+    );
+    let interpreter = Interpreter::new(&module, &info, Options::default()).unwrap();
+    let mut counters = [0u8; 8];
+    interpreter
+        .dispatch(
+            "main",
+            [4, 1, 1],
+            Bindings::new().buffer(0, 0, &mut counters),
+        )
+        .unwrap();
+    assert_eq!(words(&counters), [(0..64).sum::<u32>(), 63]);
+}
+
+#[test]
+fn subgroups() {
+    let (module, info) = compile(
+        "
+        @group(0) @binding(0) var<storage, read_write> out: array<u32>;
+
+        @compute @workgroup_size(8)
+        fn main(@builtin(subgroup_invocation_id) lane: u32, @builtin(local_invocation_index) index: u32) {
+            out[index] = subgroupAdd(lane) * 100u + subgroupExclusiveAdd(lane);
+        }
+        ",
+    );
+    let options = Options {
+        subgroup_size: 4,
+        ..Options::default()
+    };
+    let interpreter = Interpreter::new(&module, &info, options).unwrap();
+    let mut out = [0u8; 32];
+    interpreter
+        .dispatch("main", [1, 1, 1], Bindings::new().buffer(0, 0, &mut out))
+        .unwrap();
+    assert_eq!(words(&out), [600, 600, 601, 603, 600, 600, 601, 603]);
+}
+
+#[test]
+fn out_of_bounds() {
+    let (module, info) = compile(
+        "
+        @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            data[0] = data[5];
+            data[6] = 7u;
+        }
+        ",
+    );
+    let run = |policy| {
+        let options = Options {
+            bounds_check_policies: BoundsCheckPolicies {
+                buffer: policy,
+                ..BoundsCheckPolicies::default()
+            },
+            ..Options::default()
+        };
+        let interpreter = Interpreter::new(&module, &info, options).unwrap();
+        let mut data = 1u32
+            .to_le_bytes()
+            .into_iter()
+            .chain(2u32.to_le_bytes())
+            .collect::<Vec<_>>();
+        let result =
+            interpreter.dispatch("main", [1, 1, 1], Bindings::new().buffer(0, 0, &mut data));
+        (result, words(&data))
+    };
+
+    let (report, data) = run(BoundsCheckPolicy::ReadZeroSkipWrite);
+    let report = report.unwrap();
+    assert_eq!(data, [0, 2]);
+    assert_eq!(report.out_of_bounds.len(), 2);
+    assert_eq!(report.out_of_bounds[0].index, 5);
+    assert_eq!(report.out_of_bounds[0].length, 2);
+
+    let (report, data) = run(BoundsCheckPolicy::Restrict);
+    assert_eq!(report.unwrap().out_of_bounds.len(), 2);
+    assert_eq!(data, [2, 7]);
+
+    let (report, _) = run(BoundsCheckPolicy::Unchecked);
+    assert!(matches!(report, Err(Error::OutOfBounds(ref oob)) if oob.index == 5));
+}
+
+#[test]
+fn aliased_bindings() {
+    let (module, info) = compile(
+        "
+        @group(0) @binding(0) var<storage, read_write> a: array<u32, 2>;
+        @group(0) @binding(1) var<storage, read_write> b: array<u32, 2>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            a[1] = 5u;
+            b[1] = b[0] + 1u;
+        }
+        ",
+    );
+    let interpreter = Interpreter::new(&module, &info, Options::default()).unwrap();
+    let mut data = vec![0; 12];
+    let mut bindings = Bindings::new();
+    let memory = bindings.memory(&mut data);
+    bindings.view(0, 0, memory, 0..8).view(0, 1, memory, 4..12);
+    interpreter
+        .dispatch("main", [1, 1, 1], &mut bindings)
+        .unwrap();
+    assert_eq!(words(&data), [0, 5, 6]);
+
+    let mut data = vec![0; 8];
+    let mut bindings = Bindings::new();
+    let memory = bindings.memory(&mut data);
+    bindings.view(0, 0, memory, 0..8).view(0, 1, memory, 4..12);
+    let result = interpreter.dispatch("main", [1, 1, 1], &mut bindings);
+    assert!(matches!(result, Err(Error::BindingOutOfRange(_))));
+}
@@ -0,0 +1,326 @@
+//! Values held by expressions, and their layout in memory.
+
+use super::Error;
+use crate::{Handle, Literal, Scalar, ScalarKind, TypeInner, UniqueArena};
+
+/// The value of an expression.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Value {
+    Scalar(Literal),
+    /// The components of a vector, the columns of a matrix, the elements of
+    /// an array, or the members of a struct.
+    Composite(Vec<Value>),
+    Pointer(Pointer),
+}
+
+/// A pointer into one of the interpreter's memory regions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Pointer {
+    pub region: Region,
+    /// The byte offset of the pointee in `region`.
+    pub offset: usize,
+    /// False if this pointer was produced by an out-of-bounds access under
+    /// [`BoundsCheckPolicy::ReadZeroSkipWrite`]. Loads through it read zero,
+    /// and stores are skipped.
+    ///
+    /// [`BoundsCheckPolicy::ReadZeroSkipWrite`]: crate::proc::BoundsCheckPolicy::ReadZeroSkipWrite
+    pub in_bounds: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Region {
+    Global(Handle<crate::GlobalVariable>),
+    /// A local variable of the function at `frame` in the call stack.
+    Local {
+        frame: usize,
+        local: Handle<crate::LocalVariable>,
+    },
+}
+
+impl Value {
+    pub fn zero(inner: &TypeInner, types: &UniqueArena<crate::Type>) -> Result<Self, Error> {
+        Ok(match *inner {
+            TypeInner::Scalar(scalar) | TypeInner::Atomic(scalar) => {
+                Self::Scalar(Literal::zero(scalar).ok_or(Error::Invalid("unsized scalar"))?)
+            }
+            TypeInner::Vector { size, scalar } => {
+                Self::Composite(vec![
+                    Self::zero(&TypeInner::Scalar(scalar), types)?;
+                    size as usize
+                ])
+            }
+            TypeInner::Matrix {
+                columns,
+                rows,
+                scalar,
+            } => {
+                let column = Self::zero(&TypeInner::Vector { size: rows, scalar }, types)?;
+                Self::Composite(vec![column; columns as usize])
+            }
+            TypeInner::Array {
+                base,
+                size: crate::ArraySize::Constant(count),
+                ..
+            } => Self::Composite(vec![
+                Self::zero(&types[base].inner, types)?;
+                count.get() as usize
+            ]),
+            TypeInner::Struct { ref members, .. } => Self::Composite(
+                members
+                    .iter()
+                    .map(|member| Self::zero(&types[member.ty].inner, types))
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => return Err(Error::Invalid("type has no zero value")),
+        })
+    }
+
+    pub const fn scalar(&self) -> Result<Literal, Error> {
+        match *self {
+            Self::Scalar(literal) => Ok(literal),
+            _ => Err(Error::Invalid("expected a scalar")),
+        }
+    }
+
+    pub fn components(&self) -> Result<&[Value], Error> {
+        match *self {
+            Self::Composite(ref components) => Ok(components),
+            _ => Err(Error::Invalid("expected a composite")),
+        }
+    }
+
+    pub const fn pointer(&self) -> Result<Pointer, Error> {
+        match *self {
+            Self::Pointer(pointer) => Ok(pointer),
+            _ => Err(Error::Invalid("expected a pointer")),
+        }
+    }
+
+    pub fn as_bool(&self) -> Result<bool, Error> {
+        match self.scalar()? {
+            Literal::Bool(value) => Ok(value),
+            _ => Err(Error::Invalid("expected a bool")),
+        }
+    }
+
+    /// Return the value of an integer scalar, as used for indices and
+    /// switch selectors.
+    pub fn as_int(&self) -> Result<i64, Error> {
+        match self.scalar()? {
+            Literal::I32(value) => Ok(value.into()),
+            Literal::U32(value) => Ok(value.into()),
+            Literal::I64(value) | Literal::AbstractInt(value) => Ok(value),
+            _ => Err(Error::Invalid("expected an integer")),
+        }
+    }
+
+    pub fn as_u32(&self) -> Result<u32, Error> {
+        match self.scalar()? {
+            Literal::U32(value) => Ok(value),
+            _ => Err(Error::Invalid("expected a u32")),
+        }
+    }
+}
+
+/// Build a value of type `inner` from the components of a `Compose`
+/// expression, flattening vectors used to build a vector.
+pub(super) fn compose(inner: &TypeInner, components: Vec<Value>) -> Result<Value, Error> {
+    Ok(match *inner {
+        TypeInner::Vector { .. } => {
+            let mut flat = Vec::new();
+            for component in components {
+                match component {
+                    Value::Composite(inner) => flat.extend(inner),
+                    Value::Scalar(_) => flat.push(component),
+                    Value::Pointer(_) => return Err(Error::Invalid("pointers cannot be composed")),
+                }
+            }
+            Value::Composite(flat)
+        }
+        _ => Value::Composite(components),
+    })
+}
+
+/// Read a value of type `inner` from `bytes` at `offset`.
+pub(super) fn read(
+    bytes: &[u8],
+    offset: usize,
+    inner: &TypeInner,
+    types: &UniqueArena<crate::Type>,
+) -> Result<Value, Error> {
+    Ok(match *inner {
+        TypeInner::Scalar(scalar) | TypeInner::Atomic(scalar) => {
+            Value::Scalar(read_scalar(bytes, offset, scalar)?)
+        }
+        TypeInner::Vector { size, scalar } => Value::Composite(
+            (0..size as usize)
+                .map(|i| read_scalar(bytes, offset + i * scalar.width as usize, scalar))
+                .map(|literal| literal.map(Value::Scalar))
+                .collect::<Result<_, _>>()?,
+        ),
+        TypeInner::Matrix {
+            columns,
+            rows,
+            scalar,
+        } => {
+            let column = TypeInner::Vector { size: rows, scalar };
+            let stride = column_stride(rows, scalar);
+            Value::Composite(
+                (0..columns as usize)
+                    .map(|i| read(bytes, offset + i * stride, &column, types))
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        TypeInner::Array {
+            base,
+            size: crate::ArraySize::Constant(count),
+            stride,
+        } => Value::Composite(
+            (0..count.get() as usize)
+                .map(|i| {
+                    read(
+                        bytes,
+                        offset + i * stride as usize,
+                        &types[base].inner,
+                        types,
+                    )
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        TypeInner::Struct { ref members, .. } => Value::Composite(
+            members
+                .iter()
+                .map(|member| {
+                    read(
+                        bytes,
+                        offset + member.offset as usize,
+                        &types[member.ty].inner,
+                        types,
+                    )
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        _ => return Err(Error::Invalid("type cannot be loaded")),
+    })
+}
+
+/// Write `value`, of type `inner`, to `bytes` at `offset`.
+pub(super) fn write(
+    bytes: &mut [u8],
+    offset: usize,
+    inner: &TypeInner,
+    types: &UniqueArena<crate::Type>,
+    value: &Value,
+) -> Result<(), Error> {
+    match *inner {
+        TypeInner::Scalar(_) | TypeInner::Atomic(_) => {
+            write_scalar(bytes, offset, value.scalar()?)?;
+        }
+        TypeInner::Vector { scalar, .. } => {
+            for (i, component) in value.components()?.iter().enumerate() {
+                write_scalar(
+                    bytes,
+                    offset + i * scalar.width as usize,
+                    component.scalar()?,
+                )?;
+            }
+        }
+        TypeInner::Matrix { rows, scalar, .. } => {
+            let column = TypeInner::Vector { size: rows, scalar };
+            let stride = column_stride(rows, scalar);
+            for (i, component) in value.components()?.iter().enumerate() {
+                write(bytes, offset + i * stride, &column, types, component)?;
+            }
+        }
+        TypeInner::Array { base, stride, .. } => {
+            for (i, element) in value.components()?.iter().enumerate() {
+                write(
+                    bytes,
+                    offset + i * stride as usize,
+                    &types[base].inner,
+                    types,
+                    element,
+                )?;
+            }
+        }
+        TypeInner::Struct { ref members, .. } => {
+            for (member, member_value) in members.iter().zip(value.components()?) {
+                write(
+                    bytes,
+                    offset + member.offset as usize,
+                    &types[member.ty].inner,
+                    types,
+                    member_value,
+                )?;
+            }
+        }
+        _ => return Err(Error::Invalid("type cannot be stored")),
+    }
+    Ok(())
+}
+
+/// The distance in bytes between the columns of a matrix.
+pub(super) fn column_stride(rows: crate::VectorSize, scalar: Scalar) -> usize {
+    (crate::proc::Alignment::from(rows) * scalar.width as u32) as usize
+}
+
+fn read_scalar(bytes: &[u8], offset: usize, scalar: Scalar) -> Result<Literal, Error> {
+    let bytes = bytes
+        .get(offset..offset + scalar.width as usize)
+        .ok_or(Error::Invalid("access past the end of memory"))?;
+    Ok(match (scalar.kind, bytes) {
+        (ScalarKind::Bool, &[byte]) => Literal::Bool(byte != 0),
+        (ScalarKind::Sint, &[a, b, c, d]) => Literal::I32(i32::from_le_bytes([a, b, c, d])),
+        (ScalarKind::Uint, &[a, b, c, d]) => Literal::U32(u32::from_le_bytes([a, b, c, d])),
+        (ScalarKind::Float, &[a, b, c, d]) => Literal::F32(f32::from_le_bytes([a, b, c, d])),
+        (ScalarKind::Sint, bytes) if bytes.len() == 8 => {
+            let mut array = [0; 8];
+            array.copy_from_slice(bytes);
+            Literal::I64(i64::from_le_bytes(array))
+        }
+        (ScalarKind::Float, bytes) if bytes.len() == 8 => {
+            let mut array = [0; 8];
+            array.copy_from_slice(bytes);
+            Literal::F64(f64::from_le_bytes(array))
+        }
+        _ => return Err(Error::Unsupported("scalars of this width")),
+    })
+}
+
+fn write_scalar(bytes: &mut [u8], offset: usize, literal: Literal) -> Result<(), Error> {
+    let mut buffer = [0; 8];
+    let encoded: &[u8] = match literal {
+        Literal::Bool(value) => {
+            buffer[0] = value as u8;
+            &buffer[..1]
+        }
+        Literal::I32(value) => {
+            buffer[..4].copy_from_slice(&value.to_le_bytes());
+            &buffer[..4]
+        }
+        Literal::U32(value) => {
+            buffer[..4].copy_from_slice(&value.to_le_bytes());
+            &buffer[..4]
+        }
+        Literal::F32(value) => {
+            buffer[..4].copy_from_slice(&value.to_le_bytes());
+            &buffer[..4]
+        }
+        Literal::I64(value) => {
+            buffer.copy_from_slice(&value.to_le_bytes());
+            &buffer
+        }
+        Literal::F64(value) => {
+            buffer.copy_from_slice(&value.to_le_bytes());
+            &buffer
+        }
+        Literal::AbstractInt(_) | Literal::AbstractFloat(_) => {
+            return Err(Error::Invalid("abstract values cannot be stored"))
+        }
+    };
+    bytes
+        .get_mut(offset..offset + encoded.len())
+        .ok_or(Error::Invalid("access past the end of memory"))?
+        .copy_from_slice(encoded);
+    Ok(())
+}
@@ -112,6 +112,10 @@ Naga's rules for when `Expression`s are evaluated are as follows:
     [`RayQuery`] statement whose [`Proceed::result`] points to it is
     executed.
 
+-   Likewise, [`SubgroupBallotResult`] and [`SubgroupOperationResult`]
+    expressions are evaluated when the subgroup statement whose `result`
+    points to them is executed.
+
 -   All other expressions are evaluated when the (unique) [`Statement::Emit`]
     statement that covers them is executed.
 
@@ -201,6 +205,8 @@ An override expression can be evaluated at pipeline creation time.
 
 [`AtomicResult`]: Expression::AtomicResult
 [`RayQueryProceedResult`]: Expression::RayQueryProceedResult
+[`SubgroupBallotResult`]: Expression::SubgroupBallotResult
+[`SubgroupOperationResult`]: Expression::SubgroupOperationResult
 [`CallResult`]: Expression::CallResult
 [`Constant`]: Expression::Constant
 [`ZeroValue`]: Expression::ZeroValue
@@ -284,8 +290,15 @@ mod block;
 #[cfg(feature = "compact")]
 pub mod compact;
 pub mod front;
+#[cfg(feature = "interp")]
+pub mod interp;
 pub mod keywords;
+#[cfg(feature = "link")]
+pub mod link;
+#[cfg(feature = "opt")]
+pub mod opt;
 pub mod proc;
+pub mod reflect;
 mod span;
 pub mod valid;
 
@@ -293,7 +306,7 @@ use std::collections::HashMap;
 
 pub use crate::arena::{Arena, Handle, Range, UniqueArena};
 
-pub use crate::span::{SourceLocation, Span, SpanContext, WithSpan};
+pub use crate::span::{FileId, SourceLocation, Span, SpanContext, WithSpan};
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
 #[cfg(feature = "deserialize")]
@@ -301,6 +314,9 @@ use serde::Deserialize;
 #[cfg(feature = "serialize")]
 use serde::Serialize;
 
+/// The version of this crate, for keying data derived from naga's output.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// Width of a boolean type, in bytes.
 pub const BOOL_WIDTH: Bytes = 1;
 
@@ -463,6 +479,11 @@ pub enum BuiltIn {
     WorkGroupId,
     WorkGroupSize,
     NumWorkGroups,
+    // subgroup
+    NumSubgroups,
+    SubgroupId,
+    SubgroupSize,
+    SubgroupInvocationId,
 }
 
 /// Number of bytes per scalar.
@@ -1137,6 +1158,63 @@ pub enum AtomicFunction {
     Exchange { compare: Option<Handle<Expression>> },
 }
 
+/// An operation combining the values of all active invocations in a subgroup.
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum SubgroupOperation {
+    All,
+    Any,
+    Add,
+    Mul,
+    Min,
+    Max,
+    And,
+    Or,
+    Xor,
+}
+
+/// Which invocations' values a [`SubgroupOperation`] combines.
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum CollectiveOperation {
+    /// Combine the values of all active invocations.
+    Reduce,
+    /// Combine the values of active invocations with a lower or equal index.
+    InclusiveScan,
+    /// Combine the values of active invocations with a lower index.
+    ExclusiveScan,
+}
+
+/// Which invocation a [`SubgroupGather`] statement reads its value from.
+///
+/// The invocation index or offset, in the modes that take one, must be a
+/// `u32` scalar.
+///
+/// [`SubgroupGather`]: Statement::SubgroupGather
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum GatherMode {
+    /// All invocations read the value of the lowest-indexed active invocation.
+    BroadcastFirst,
+    /// All invocations read the value of the given invocation, which must be uniform.
+    Broadcast(Handle<Expression>),
+    /// Each invocation reads the value of the given invocation.
+    Shuffle(Handle<Expression>),
+    /// Each invocation reads the value of the invocation this many above itself.
+    ShuffleDown(Handle<Expression>),
+    /// Each invocation reads the value of the invocation this many below itself.
+    ShuffleUp(Handle<Expression>),
+    /// Each invocation reads the value of the invocation whose index is its own
+    /// index XORed with the given mask.
+    ShuffleXor(Handle<Expression>),
+}
+
 /// Hint at which precision to compute a derivative.
 #[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
@@ -1321,6 +1399,8 @@ bitflags::bitflags! {
         const STORAGE = 0x1;
         /// Barrier affects all `AddressSpace::WorkGroup` accesses.
         const WORK_GROUP = 0x2;
+        /// Barrier synchronizes execution across all invocations within a subgroup.
+        const SUB_GROUP = 0x4;
     }
 }
 
@@ -1619,6 +1699,21 @@ pub enum Expression {
         query: Handle<Expression>,
         committed: bool,
     },
+
+    /// Result of a [`SubgroupBallot`] statement.
+    ///
+    /// This is a `vec4<u32>` bitmask of the invocations whose predicate was true.
+    ///
+    /// [`SubgroupBallot`]: Statement::SubgroupBallot
+    SubgroupBallotResult,
+
+    /// Result of a [`SubgroupElect`], [`SubgroupCollectiveOperation`] or
+    /// [`SubgroupGather`] statement.
+    ///
+    /// [`SubgroupElect`]: Statement::SubgroupElect
+    /// [`SubgroupCollectiveOperation`]: Statement::SubgroupCollectiveOperation
+    /// [`SubgroupGather`]: Statement::SubgroupGather
+    SubgroupOperationResult { ty: Handle<Type> },
 }
 
 pub use block::Block;
@@ -1891,6 +1986,53 @@ pub enum Statement {
         /// The specific operation we're performing on `query`.
         fun: RayQueryFunction,
     },
+    /// Select a single active invocation of the subgroup.
+    ///
+    /// The `result` is true in exactly one active invocation: the one with
+    /// the lowest subgroup invocation id.
+    SubgroupElect {
+        /// The [`SubgroupOperationResult`] expression representing this
+        /// statement's `bool` result.
+        ///
+        /// [`SubgroupOperationResult`]: Expression::SubgroupOperationResult
+        result: Handle<Expression>,
+    },
+    /// Calculate a bitmask of the active invocations whose `predicate` is true.
+    SubgroupBallot {
+        /// The [`SubgroupBallotResult`] expression representing this
+        /// statement's result.
+        ///
+        /// [`SubgroupBallotResult`]: Expression::SubgroupBallotResult
+        result: Handle<Expression>,
+        /// The `bool` value to test. If `None`, this is treated as `true`.
+        predicate: Option<Handle<Expression>>,
+    },
+    /// Read the value of `argument` from another invocation of the subgroup.
+    SubgroupGather {
+        /// Which invocation to read from.
+        mode: GatherMode,
+        /// The value to read. This must be a scalar or vector.
+        argument: Handle<Expression>,
+        /// The [`SubgroupOperationResult`] expression representing this
+        /// statement's result.
+        ///
+        /// [`SubgroupOperationResult`]: Expression::SubgroupOperationResult
+        result: Handle<Expression>,
+    },
+    /// Combine the values of `argument` across the subgroup's active invocations.
+    SubgroupCollectiveOperation {
+        /// How to combine the values.
+        op: SubgroupOperation,
+        /// Which invocations' values to combine.
+        collective_op: CollectiveOperation,
+        /// The value to combine. This must be a scalar or vector.
+        argument: Handle<Expression>,
+        /// The [`SubgroupOperationResult`] expression representing this
+        /// statement's result.
+        ///
+        /// [`SubgroupOperationResult`]: Expression::SubgroupOperationResult
+        result: Handle<Expression>,
+    },
 }
 
 /// A function argument.
@@ -0,0 +1,588 @@
+/*!
+Linking separately lowered modules.
+
+[`link`] merges two [`Module`]s into one, so that a library of shared
+functions can be lowered once and then combined with many modules that use
+it.
+
+- Types are deduplicated, so a type defined in both modules appears once in
+  the result.
+
+- Global variables that are identical in both modules, down to their names
+  and bindings, are merged. This is what happens when both modules were
+  lowered from sources that import the same bindings. Any other pair of
+  globals that use the same binding slot is reported as a
+  [`ConflictingBinding`] error.
+
+- A function in either module that is only a *declaration* is resolved to
+  the function of the same name that the other module [exports]. Calls to
+  the declaration become calls to the definition, and the declaration
+  itself is dropped. A declaration is a function whose body does nothing:
+  it is empty, or only holds a `Return` with no value. A declaration with
+  no matching export is kept, so that it can be resolved by linking with
+  yet another module.
+
+- Entry points are taken from both modules. Two entry points with the same
+  name and stage are reported as a [`DuplicateEntryPoint`] error.
+
+Everything else is simply carried over, with handles adjusted to point
+into the merged module.
+
+[`Module`]: crate::Module
+[`ConflictingBinding`]: LinkError::ConflictingBinding
+[`DuplicateEntryPoint`]: LinkError::DuplicateEntryPoint
+[exports]: crate::Module::exports
+*/
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests;
+
+use std::num::NonZeroU32;
+
+use crate::{
+    arena::{Arena, UniqueArena},
+    Constant, Export, Expression, Function, GlobalVariable, Handle, Module, Span, Statement, Type,
+};
+
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum LinkError {
+    #[error(
+        "Global variables {first:?} and {second:?} both use binding {binding} of group {group}"
+    )]
+    ConflictingBinding {
+        group: u32,
+        binding: u32,
+        first: Option<String>,
+        second: Option<String>,
+    },
+    #[error("Both modules have a {stage:?} entry point named '{name}'")]
+    DuplicateEntryPoint {
+        name: String,
+        stage: crate::ShaderStage,
+    },
+    #[error("Both modules export different definitions of '{0}'")]
+    DuplicateExport(String),
+    #[error("The declaration of function '{0}' does not match its definition")]
+    SignatureMismatch(String),
+    #[error("Resolving declarations makes function '{0}' call itself")]
+    Recursion(String),
+}
+
+/// Merge `other` into `module`, and return the result.
+///
+/// See the [module documentation](self) for details.
+///
+/// Neither module needs to have passed validation on its own, since
+/// declarations that return values don't, but the result must be validated
+/// before it is used.
+pub fn link(mut module: Module, mut other: Module) -> Result<Module, LinkError> {
+    // Bring over types, deduplicating as we go. Since a type can only refer
+    // to types that precede it, the map is always ready for the next one.
+    let mut types = Vec::with_capacity(other.types.len());
+    for (_, mut ty, span) in other.types.drain_all() {
+        adjust_type(&mut ty, &types);
+        let handle = match find_struct(&module.types, &ty) {
+            Some(existing) => existing,
+            None => module.types.insert(ty, span),
+        };
+        types.push(handle);
+    }
+
+    // Constants and constant expressions refer to each other in both
+    // directions, but since all of them are appended, we know in advance
+    // where each one will end up.
+    let mut map = ModuleMap {
+        constants: next_handles(&module.constants, other.constants.len()),
+        const_expressions: next_handles(&module.const_expressions, other.const_expressions.len()),
+        global_variables: Vec::with_capacity(other.global_variables.len()),
+        types,
+    };
+
+    let special = std::mem::take(&mut other.special_types);
+    if module.special_types.ray_desc.is_none() {
+        module.special_types.ray_desc = special.ray_desc.map(|ty| map.types[ty.index()]);
+    }
+    if module.special_types.ray_intersection.is_none() {
+        module.special_types.ray_intersection =
+            special.ray_intersection.map(|ty| map.types[ty.index()]);
+    }
+    for (predeclared, ty) in special.predeclared_types {
+        module
+            .special_types
+            .predeclared_types
+            .entry(predeclared)
+            .or_insert(map.types[ty.index()]);
+    }
+
+    for (mut expression, span) in drain(&mut other.const_expressions) {
+        map.adjust_expression(&mut expression);
+        crate::proc::expression_operands(&mut expression, |operand| {
+            *operand = map.const_expressions[operand.index()]
+        });
+        module.const_expressions.append(expression, span);
+    }
+    for (mut constant, span) in drain(&mut other.constants) {
+        constant.ty = map.types[constant.ty.index()];
+        constant.init = map.const_expressions[constant.init.index()];
+        module.constants.append(constant, span);
+    }
+
+    for (mut global, span) in drain(&mut other.global_variables) {
+        global.ty = map.types[global.ty.index()];
+        global.init = global.init.map(|init| map.const_expressions[init.index()]);
+        let handle = match module
+            .global_variables
+            .fetch_if(|existing| same_global(existing, &global))
+        {
+            Some(existing) => existing,
+            None => {
+                check_binding(&module.global_variables, &global)?;
+                module.global_variables.append(global, span)
+            }
+        };
+        map.global_variables.push(handle);
+    }
+
+    // Gather the functions from both modules in one list, `module`'s first,
+    // and bring `other`'s up to date with everything but function handles.
+    let functions_before = module.functions.len();
+    let mut functions = drain(&mut module.functions);
+    functions.extend(
+        drain(&mut other.functions)
+            .into_iter()
+            .map(|(mut function, span)| {
+                map.adjust_function(&mut function);
+                (function, span)
+            }),
+    );
+    for entry_point in other.entry_points.iter_mut() {
+        map.adjust_function(&mut entry_point.function);
+    }
+
+    // Where each function in `functions` really is, after resolving
+    // declarations: `resolved[i]` is the index in `functions` of the
+    // function that calls to `functions[i]` should call.
+    let export_target = |exports: &std::collections::HashMap<String, Export>,
+                         name: &Option<String>| {
+        match name.as_ref().and_then(|name| exports.get(name)) {
+            Some(&Export::Function(function)) => Some(function.index()),
+            _ => None,
+        }
+    };
+    let mut resolved = (0..functions.len()).collect::<Vec<_>>();
+    for (index, &(ref function, _)) in functions.iter().enumerate() {
+        if !is_declaration(function) {
+            continue;
+        }
+        let target = if index < functions_before {
+            export_target(&other.exports, &function.name).map(|i| i + functions_before)
+        } else {
+            export_target(&module.exports, &function.name)
+        };
+        if let Some(target) = target {
+            let definition = &functions[target].0;
+            if is_declaration(definition) {
+                continue;
+            }
+            if !same_signature(function, definition) {
+                return Err(LinkError::SignatureMismatch(
+                    function.name.clone().unwrap_or_default(),
+                ));
+            }
+            resolved[index] = target;
+        }
+    }
+
+    // Callees must precede their callers, so order the functions that
+    // remain by walking the call graph.
+    let mut order = Vec::with_capacity(functions.len());
+    let mut state = vec![Visit::Pending; functions.len()];
+    for index in 0..functions.len() {
+        if resolved[index] == index {
+            visit(
+                index,
+                &functions,
+                functions_before,
+                &resolved,
+                &mut state,
+                &mut order,
+            )?;
+        }
+    }
+
+    let mut new_index = vec![0; functions.len()];
+    for (new, &old) in order.iter().enumerate() {
+        new_index[old] = new;
+    }
+    let final_handles = (0..functions.len())
+        .map(|index| Handle::new(index_of(new_index[resolved[index]])))
+        .collect::<Vec<_>>();
+    let (module_handles, other_handles) = final_handles.split_at(functions_before);
+
+    let mut functions = functions.into_iter().map(Some).collect::<Vec<_>>();
+    for (position, &index) in order.iter().enumerate() {
+        let (mut function, span) = functions[index].take().unwrap();
+        let handles = if index < functions_before {
+            module_handles
+        } else {
+            other_handles
+        };
+        adjust_calls(&mut function, handles);
+        let handle = module.functions.append(function, span);
+        debug_assert_eq!(handle.index(), position);
+    }
+
+    for entry_point in module.entry_points.iter_mut() {
+        adjust_calls(&mut entry_point.function, module_handles);
+    }
+    for mut entry_point in other.entry_points {
+        if module.entry_points.iter().any(|existing| {
+            existing.name == entry_point.name && existing.stage == entry_point.stage
+        }) {
+            return Err(LinkError::DuplicateEntryPoint {
+                name: entry_point.name,
+                stage: entry_point.stage,
+            });
+        }
+        adjust_calls(&mut entry_point.function, other_handles);
+        module.entry_points.push(entry_point);
+    }
+
+    for export in module.exports.values_mut() {
+        if let Export::Function(ref mut function) = *export {
+            *function = module_handles[function.index()];
+        }
+    }
+    for (name, export) in other.exports {
+        let export = match export {
+            Export::Function(function) => Export::Function(other_handles[function.index()]),
+            Export::Var(global) => Export::Var(map.global_variables[global.index()]),
+            Export::Const(constant) => Export::Const(map.constants[constant.index()]),
+        };
+        match module.exports.get(&name) {
+            Some(&existing) if existing != export => {
+                return Err(LinkError::DuplicateExport(name));
+            }
+            _ => {
+                module.exports.insert(name, export);
+            }
+        }
+    }
+
+    Ok(module)
+}
+
+/// Where handles from the module being merged in end up.
+struct ModuleMap {
+    types: Vec<Handle<Type>>,
+    constants: Vec<Handle<Constant>>,
+    const_expressions: Vec<Handle<Expression>>,
+    global_variables: Vec<Handle<GlobalVariable>>,
+}
+
+impl ModuleMap {
+    /// Adjust the module-scope handles `expression` refers to, but not its
+    /// operands.
+    fn adjust_expression(&self, expression: &mut Expression) {
+        match *expression {
+            Expression::Constant(ref mut constant) => {
+                *constant = self.constants[constant.index()];
+            }
+            Expression::ZeroValue(ref mut ty)
+            | Expression::Compose { ref mut ty, .. }
+            | Expression::AtomicResult { ref mut ty, .. }
+            | Expression::WorkGroupUniformLoadResult { ref mut ty }
+            | Expression::SubgroupOperationResult { ref mut ty } => {
+                *ty = self.types[ty.index()];
+            }
+            Expression::GlobalVariable(ref mut global) => {
+                *global = self.global_variables[global.index()];
+            }
+            Expression::ImageSample {
+                offset: Some(ref mut offset),
+                ..
+            } => {
+                *offset = self.const_expressions[offset.index()];
+            }
+            _ => {}
+        }
+    }
+
+    /// Adjust the module-scope handles `function` refers to, except for
+    /// those of other functions.
+    fn adjust_function(&self, function: &mut Function) {
+        for argument in function.arguments.iter_mut() {
+            argument.ty = self.types[argument.ty.index()];
+        }
+        if let Some(ref mut result) = function.result {
+            result.ty = self.types[result.ty.index()];
+        }
+        for (_, local) in function.local_variables.iter_mut() {
+            local.ty = self.types[local.ty.index()];
+        }
+        for (_, expression) in function.expressions.iter_mut() {
+            self.adjust_expression(expression);
+        }
+        for named in function.named_expressions.values_mut() {
+            named.ty = named.ty.map(|ty| self.types[ty.index()]);
+        }
+    }
+}
+
+fn adjust_type(ty: &mut Type, types: &[Handle<Type>]) {
+    use crate::TypeInner as Ti;
+    match ty.inner {
+        Ti::Pointer { ref mut base, .. }
+        | Ti::Array { ref mut base, .. }
+        | Ti::BindingArray { ref mut base, .. } => *base = types[base.index()],
+        Ti::Struct {
+            ref mut members, ..
+        } => {
+            for member in members {
+                member.ty = types[member.ty.index()];
+            }
+        }
+        Ti::Scalar(_)
+        | Ti::Vector { .. }
+        | Ti::Matrix { .. }
+        | Ti::Atomic(_)
+        | Ti::ValuePointer { .. }
+        | Ti::Image { .. }
+        | Ti::Sampler { .. }
+        | Ti::AccelerationStructure
+        | Ti::RayQuery => {}
+    }
+}
+
+/// Find a struct type in `types` that is the same as `ty`, except for where
+/// its members were declared.
+///
+/// Struct members carry their spans, so the same struct lowered from two
+/// different sources is two different types as far as `UniqueArena` is
+/// concerned.
+fn find_struct(types: &UniqueArena<Type>, ty: &Type) -> Option<Handle<Type>> {
+    let (members, span) = match ty.inner {
+        crate::TypeInner::Struct { ref members, span } => (members, span),
+        _ => return None,
+    };
+    types
+        .iter()
+        .find_map(|(handle, existing)| match existing.inner {
+            crate::TypeInner::Struct {
+                members: ref existing_members,
+                span: existing_span,
+            } if existing.name == ty.name
+                && existing_span == span
+                && existing_members.len() == members.len()
+                && existing_members.iter().zip(members).all(|(a, b)| {
+                    a.name == b.name
+                        && a.ty == b.ty
+                        && a.binding == b.binding
+                        && a.offset == b.offset
+                }) =>
+            {
+                Some(handle)
+            }
+            _ => None,
+        })
+}
+
+/// Point the calls in `function` at the functions' final handles.
+fn adjust_calls(function: &mut Function, handles: &[Handle<Function>]) {
+    for (_, expression) in function.expressions.iter_mut() {
+        if let Expression::CallResult(ref mut callee) = *expression {
+            *callee = handles[callee.index()];
+        }
+    }
+    let mut blocks = vec![&mut function.body];
+    while let Some(block) = blocks.pop() {
+        for statement in block.iter_mut() {
+            match *statement {
+                Statement::Call {
+                    function: ref mut callee,
+                    ..
+                } => *callee = handles[callee.index()],
+                Statement::Block(ref mut block) => blocks.push(block),
+                Statement::If {
+                    ref mut accept,
+                    ref mut reject,
+                    ..
+                } => {
+                    blocks.push(accept);
+                    blocks.push(reject);
+                }
+                Statement::Switch { ref mut cases, .. } => {
+                    blocks.extend(cases.iter_mut().map(|case| &mut case.body));
+                }
+                Statement::Loop {
+                    ref mut body,
+                    ref mut continuing,
+                    ..
+                } => {
+                    blocks.push(body);
+                    blocks.push(continuing);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    Pending,
+    InProgress,
+    Done,
+}
+
+/// Add `index` to `order` after all the functions it calls.
+fn visit(
+    index: usize,
+    functions: &[(Function, Span)],
+    functions_before: usize,
+    resolved: &[usize],
+    state: &mut [Visit],
+    order: &mut Vec<usize>,
+) -> Result<(), LinkError> {
+    match state[index] {
+        Visit::Done => return Ok(()),
+        Visit::InProgress => {
+            let name = functions[index].0.name.clone();
+            return Err(LinkError::Recursion(name.unwrap_or_default()));
+        }
+        Visit::Pending => {}
+    }
+    state[index] = Visit::InProgress;
+
+    // Call handles still refer to the function's own module.
+    let base = if index < functions_before {
+        0
+    } else {
+        functions_before
+    };
+    let mut callees = Vec::new();
+    let mut blocks = vec![&functions[index].0.body];
+    while let Some(block) = blocks.pop() {
+        for statement in block.iter() {
+            match *statement {
+                Statement::Call { function, .. } => callees.push(function.index() + base),
+                Statement::Block(ref block) => blocks.push(block),
+                Statement::If {
+                    ref accept,
+                    ref reject,
+                    ..
+                } => {
+                    blocks.push(accept);
+                    blocks.push(reject);
+                }
+                Statement::Switch { ref cases, .. } => {
+                    blocks.extend(cases.iter().map(|case| &case.body));
+                }
+                Statement::Loop {
+                    ref body,
+                    ref continuing,
+                    ..
+                } => {
+                    blocks.push(body);
+                    blocks.push(continuing);
+                }
+                _ => {}
+            }
+        }
+    }
+    for callee in callees {
+        visit(
+            resolved[callee],
+            functions,
+            functions_before,
+            resolved,
+            state,
+            order,
+        )?;
+    }
+
+    state[index] = Visit::Done;
+    order.push(index);
+    Ok(())
+}
+
+/// Return true if `function` is only a declaration.
+fn is_declaration(function: &Function) -> bool {
+    match *function.body {
+        [] | [Statement::Return { value: None }] => true,
+        _ => false,
+    }
+}
+
+fn same_signature(declaration: &Function, definition: &Function) -> bool {
+    declaration.arguments.len() == definition.arguments.len()
+        && declaration
+            .arguments
+            .iter()
+            .zip(definition.arguments.iter())
+            .all(|(a, b)| a.ty == b.ty)
+        && declaration.result.as_ref().map(|result| result.ty)
+            == definition.result.as_ref().map(|result| result.ty)
+}
+
+/// Return true if `a` and `b` are the same global, declared in both modules.
+fn same_global(a: &GlobalVariable, b: &GlobalVariable) -> bool {
+    a.name.is_some()
+        && a.name == b.name
+        && a.space == b.space
+        && a.binding == b.binding
+        && a.ty == b.ty
+        && a.init.is_none()
+        && b.init.is_none()
+}
+
+/// Check that no global in `globals` uses the same binding as `global`.
+fn check_binding(
+    globals: &Arena<GlobalVariable>,
+    global: &GlobalVariable,
+) -> Result<(), LinkError> {
+    let binding = match global.binding {
+        Some(ref binding) => binding,
+        None => return Ok(()),
+    };
+    match globals
+        .iter()
+        .find(|&(_, existing)| existing.binding.as_ref() == Some(binding))
+    {
+        Some((_, existing)) => Err(LinkError::ConflictingBinding {
+            group: binding.group,
+            binding: binding.binding,
+            first: existing.name.clone(),
+            second: global.name.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Remove the contents of `arena`, along with their spans.
+fn drain<T>(arena: &mut Arena<T>) -> Vec<(T, Span)> {
+    let spans = arena
+        .iter()
+        .map(|(handle, _)| arena.get_span(handle))
+        .collect::<Vec<_>>();
+    std::mem::take(arena)
+        .into_inner()
+        .into_iter()
+        .zip(spans)
+        .collect()
+}
+
+/// The handles `arena` will assign to the next `count` values appended to it.
+fn next_handles<T>(arena: &Arena<T>, count: usize) -> Vec<Handle<T>> {
+    (arena.len()..arena.len() + count)
+        .map(|index| Handle::new(index_of(index)))
+        .collect()
+}
+
+/// Convert a zero-based index into the 1-based index `Handle::new` expects.
+fn index_of(index: usize) -> NonZeroU32 {
+    u32::try_from(index + 1)
+        .ok()
+        .and_then(NonZeroU32::new)
+        .expect("Handle overflows")
+}
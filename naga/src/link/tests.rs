@@ -0,0 +1,151 @@
+use super::{link, LinkError};
+use crate::{
+    front::wgsl::source_provider::SingleFile,
+    valid::{Capabilities, ValidationFlags, Validator},
+    Export, Module,
+};
+
+fn parse(source: &str) -> Module {
+    let source = SingleFile::new("test.wgsl", source);
+    match crate::front::wgsl::parse_module(&source, SingleFile::ID) {
+        Ok(module) => module,
+        Err(error) => panic!("{}", error.emit_to_string_with_provider(&source)),
+    }
+}
+
+/// Parse `source`, exporting the functions named in `exports`.
+fn library(source: &str, exports: &[&str]) -> Module {
+    let mut module = parse(source);
+    for &name in exports {
+        let (handle, _) = module
+            .functions
+            .iter()
+            .find(|&(_, f)| f.name.as_deref() == Some(name))
+            .unwrap();
+        module
+            .exports
+            .insert(name.to_string(), Export::Function(handle));
+    }
+    module
+}
+
+fn validate(module: &Module) {
+    Validator::new(ValidationFlags::all(), Capabilities::all())
+        .validate(module)
+        .unwrap();
+}
+
+const LIBRARY: &str = "
+    struct Light {
+        color: vec3<f32>,
+        intensity: f32,
+    }
+
+    @group(0) @binding(0) var<uniform> light: Light;
+
+    fn scale(x: f32) -> f32 {
+        return x * light.intensity;
+    }
+
+    fn shade(normal: vec3<f32>) -> vec4<f32> {
+        return vec4(light.color * scale(max(normal.z, 0.0)), 1.0);
+    }
+";
+
+#[test]
+fn resolve_declarations() {
+    let library = library(LIBRARY, &["shade"]);
+    let material = parse(
+        "
+        struct Light {
+            color: vec3<f32>,
+            intensity: f32,
+        }
+
+        @group(0) @binding(0) var<uniform> light: Light;
+        @group(1) @binding(0) var<uniform> tint: vec4<f32>;
+
+        fn shade(normal: vec3<f32>) -> vec4<f32> {}
+
+        @fragment
+        fn main(@location(0) normal: vec3<f32>) -> @location(0) vec4<f32> {
+            return shade(normal) * tint * light.intensity;
+        }
+        ",
+    );
+
+    let module = link(library, material).unwrap();
+    validate(&module);
+
+    // The declaration is gone, and the shared globals and types were merged.
+    let names = module
+        .functions
+        .iter()
+        .map(|(_, f)| f.name.as_deref().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["scale", "shade"]);
+    assert_eq!(module.global_variables.len(), 2);
+    let lights = module
+        .types
+        .iter()
+        .filter(|&(_, ty)| ty.name.as_deref() == Some("Light"))
+        .count();
+    assert_eq!(lights, 1);
+}
+
+#[test]
+fn declarations_in_the_first_module() {
+    // Definitions must precede their callers, even if the caller comes
+    // from the first module.
+    let material = parse(
+        "
+        fn shade(normal: vec3<f32>) -> vec4<f32> {}
+
+        fn helper(normal: vec3<f32>) -> vec4<f32> {
+            return shade(normal) * 0.5;
+        }
+
+        @fragment
+        fn main(@location(0) normal: vec3<f32>) -> @location(0) vec4<f32> {
+            return helper(normal);
+        }
+        ",
+    );
+    let library = library(LIBRARY, &["shade"]);
+
+    let module = link(material, library).unwrap();
+    validate(&module);
+}
+
+#[test]
+fn signature_mismatch() {
+    let library = library(LIBRARY, &["shade"]);
+    let material = parse("fn shade(normal: vec4<f32>) -> vec4<f32> {}");
+    assert!(matches!(
+        link(library, material),
+        Err(LinkError::SignatureMismatch(ref name)) if name == "shade"
+    ));
+}
+
+#[test]
+fn conflicting_binding() {
+    let library = library(LIBRARY, &[]);
+    let material = parse("@group(0) @binding(0) var<uniform> tint: vec4<f32>;");
+    assert!(matches!(
+        link(library, material),
+        Err(LinkError::ConflictingBinding {
+            group: 0,
+            binding: 0,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn duplicate_entry_point() {
+    let source = "@compute @workgroup_size(1) fn main() {}";
+    assert!(matches!(
+        link(parse(source), parse(source)),
+        Err(LinkError::DuplicateEntryPoint { ref name, .. }) if name == "main"
+    ));
+}
@@ -0,0 +1,214 @@
+//! Facts about functions that the optimizer relies on.
+
+use crate::proc::{expression_operands, statement_operands};
+use crate::{Block, Expression, Function, Handle, LocalVariable, Module, Statement};
+
+/// How a function uses one of its local variables.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct LocalUsage {
+    /// Something loads from the variable, or part of it.
+    pub loaded: bool,
+    /// Something stores to the variable, or part of it.
+    pub stored: bool,
+    /// A pointer to the variable is passed to a function, or used by an
+    /// atomic or ray query, so it may be read or written in ways we don't
+    /// track.
+    pub escapes: bool,
+}
+
+impl LocalUsage {
+    /// True if the variable always holds its initial value.
+    pub const fn is_constant(&self) -> bool {
+        !self.stored && !self.escapes
+    }
+
+    /// True if the variable's value is never observed, so stores to it can
+    /// be removed.
+    pub const fn is_write_only(&self) -> bool {
+        !self.loaded && !self.escapes
+    }
+}
+
+/// If `pointer` points to a local variable or part of one, return the
+/// variable.
+pub(super) fn root_local(
+    function: &Function,
+    mut pointer: Handle<Expression>,
+) -> Option<Handle<LocalVariable>> {
+    loop {
+        match function.expressions[pointer] {
+            Expression::LocalVariable(local) => return Some(local),
+            Expression::Access { base, .. } | Expression::AccessIndex { base, .. } => {
+                pointer = base
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Determine how `function` uses each of its local variables.
+pub(super) fn local_usage(function: &Function) -> Vec<LocalUsage> {
+    let mut usage = vec![LocalUsage::default(); function.local_variables.len()];
+    let escape = |usage: &mut Vec<LocalUsage>, operand: Handle<Expression>| {
+        if let Some(local) = root_local(function, operand) {
+            usage[local.index()].escapes = true;
+        }
+    };
+
+    for (_, expression) in function.expressions.iter() {
+        match *expression {
+            Expression::Load { pointer } => {
+                if let Some(local) = root_local(function, pointer) {
+                    usage[local.index()].loaded = true;
+                }
+            }
+            // Indexing a pointer produces another pointer, whose uses are
+            // what matter.
+            Expression::Access { index, .. } => escape(&mut usage, index),
+            Expression::AccessIndex { .. } => {}
+            _ => {
+                let mut expression = expression.clone();
+                expression_operands(&mut expression, |&mut operand| escape(&mut usage, operand));
+            }
+        }
+    }
+
+    let mut blocks = vec![&function.body];
+    while let Some(block) = blocks.pop() {
+        for statement in block {
+            match *statement {
+                Statement::Store { pointer, value } => {
+                    if let Some(local) = root_local(function, pointer) {
+                        usage[local.index()].stored = true;
+                    }
+                    escape(&mut usage, value);
+                }
+                Statement::Block(_) => {}
+                Statement::If { condition, .. } => escape(&mut usage, condition),
+                Statement::Switch { selector, .. } => escape(&mut usage, selector),
+                Statement::Loop { break_if, .. } => {
+                    if let Some(break_if) = break_if {
+                        escape(&mut usage, break_if);
+                    }
+                }
+                ref leaf => {
+                    let mut leaf = leaf.clone();
+                    statement_operands(&mut leaf, |&mut operand| escape(&mut usage, operand));
+                }
+            }
+            nested_blocks(statement, &mut blocks);
+        }
+    }
+
+    usage
+}
+
+/// Add the blocks nested directly in `statement` to `blocks`.
+pub(super) fn nested_blocks<'a>(statement: &'a Statement, blocks: &mut Vec<&'a Block>) {
+    match *statement {
+        Statement::Block(ref block) => blocks.push(block),
+        Statement::If {
+            ref accept,
+            ref reject,
+            ..
+        } => {
+            blocks.push(accept);
+            blocks.push(reject);
+        }
+        Statement::Switch { ref cases, .. } => {
+            blocks.extend(cases.iter().map(|case| &case.body));
+        }
+        Statement::Loop {
+            ref body,
+            ref continuing,
+            ..
+        } => {
+            blocks.push(body);
+            blocks.push(continuing);
+        }
+        _ => {}
+    }
+}
+
+/// Count the calls to each function in `module`, indexed by handle.
+pub(super) fn call_counts(module: &Module) -> Vec<usize> {
+    let mut counts = vec![0; module.functions.len()];
+    let bodies = module
+        .functions
+        .iter()
+        .map(|(_, function)| &function.body)
+        .chain(module.entry_points.iter().map(|ep| &ep.function.body));
+    for body in bodies {
+        let mut blocks = vec![body];
+        while let Some(block) = blocks.pop() {
+            for statement in block {
+                if let Statement::Call { function, .. } = *statement {
+                    counts[function.index()] += 1;
+                }
+                nested_blocks(statement, &mut blocks);
+            }
+        }
+    }
+    counts
+}
+
+/// Count the statements in `block` and the blocks nested in it, not
+/// counting `Emit`s.
+pub(super) fn statement_count(block: &Block) -> usize {
+    let mut count = 0;
+    let mut blocks = vec![block];
+    while let Some(block) = blocks.pop() {
+        for statement in block {
+            if !matches!(*statement, Statement::Emit(_)) {
+                count += 1;
+            }
+            nested_blocks(statement, &mut blocks);
+        }
+    }
+    count
+}
+
+/// Return true if `body` has no `Return` statement except, possibly, its
+/// last statement.
+///
+/// Such a function can be inlined by copying its statements in place of the
+/// call.
+pub(super) fn returns_only_at_end(body: &Block) -> bool {
+    let rest = match body.split_last() {
+        Some((&Statement::Return { .. }, rest)) => rest,
+        _ => body,
+    };
+
+    let mut blocks = Vec::new();
+    for statement in rest {
+        if let Statement::Return { .. } = *statement {
+            return false;
+        }
+        nested_blocks(statement, &mut blocks);
+    }
+    while let Some(block) = blocks.pop() {
+        for statement in block {
+            if let Statement::Return { .. } = *statement {
+                return false;
+            }
+            nested_blocks(statement, &mut blocks);
+        }
+    }
+    true
+}
+
+/// Return true if `block` contains a `Break` that would leave a `Switch`
+/// statement it is a case of.
+pub(super) fn breaks_out(block: &Block) -> bool {
+    block.iter().any(|statement| match *statement {
+        Statement::Break => true,
+        Statement::Block(ref block) => breaks_out(block),
+        Statement::If {
+            ref accept,
+            ref reject,
+            ..
+        } => breaks_out(accept) || breaks_out(reject),
+        // A `Break` in a nested loop or switch leaves that instead.
+        _ => false,
+    })
+}
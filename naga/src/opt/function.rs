@@ -0,0 +1,495 @@
+//! Rebuilding a function with constants folded, dead code removed and calls
+//! inlined.
+//!
+//! We copy the function's statements into a new [`Function`], translating
+//! each expression as its `Emit` statement is reached. Every expression goes
+//! through the [`ConstantEvaluator`] on its way into the new arena, so once
+//! loads of constant local variables are replaced by their initializers,
+//! anything that depends only on constants is folded as well. The new
+//! function only contains the expressions that were reached, in an order
+//! that respects their dependencies, and `compact` can clean up the ones
+//! that turn out to be unused.
+
+use super::analysis::{self, LocalUsage};
+use crate::{
+    arena::Arena,
+    proc::{ConstantEvaluator, Emitter, ExpressionConstnessTracker},
+    Block, Expression, Function, Handle, Literal, LocalVariable, Module, Span, Statement,
+    SwitchCase, SwitchValue,
+};
+
+/// Rebuild `function`, inlining calls to the functions marked in `inline`.
+///
+/// `functions` holds the module's functions as they were before
+/// optimization, and is where inlined bodies are copied from.
+pub(super) fn optimize(
+    module: &mut Module,
+    functions: &Arena<Function>,
+    inline: &[bool],
+    function: &Function,
+) -> Function {
+    let mut builder = Builder {
+        module,
+        functions,
+        inline,
+        function: Function {
+            name: function.name.clone(),
+            arguments: function.arguments.clone(),
+            result: function.result.clone(),
+            ..Function::default()
+        },
+        constness: ExpressionConstnessTracker::new(),
+        emitter: Emitter::default(),
+    };
+
+    let mut source = Source::new(function, None);
+    let mut body = Block::new();
+    builder.statements(&mut source, &function.body, &mut body, true);
+    builder.finish(&source);
+
+    builder.function.body = body;
+    builder.function
+}
+
+/// The function being built.
+struct Builder<'a> {
+    module: &'a mut Module,
+    functions: &'a Arena<Function>,
+    inline: &'a [bool],
+    function: Function,
+    constness: ExpressionConstnessTracker,
+    emitter: Emitter,
+}
+
+/// A function whose statements are being copied into the [`Builder`]: the
+/// function being optimized, or a function inlined into it.
+struct Source<'s> {
+    function: &'s Function,
+    usage: Vec<LocalUsage>,
+    /// The new handle of each of `function`'s expressions, once translated.
+    expressions: Vec<Option<Handle<Expression>>>,
+    /// The new handle of each of `function`'s local variables, once used.
+    local_variables: Vec<Option<Handle<LocalVariable>>>,
+    /// The values of the arguments, if `function` is being inlined.
+    arguments: Option<Vec<Handle<Expression>>>,
+}
+
+impl<'s> Source<'s> {
+    fn new(function: &'s Function, arguments: Option<Vec<Handle<Expression>>>) -> Self {
+        Source {
+            function,
+            usage: analysis::local_usage(function),
+            expressions: vec![None; function.expressions.len()],
+            local_variables: vec![None; function.local_variables.len()],
+            arguments,
+        }
+    }
+
+    /// If the expression `pointer` is a local variable that always holds its
+    /// initial value, return the variable.
+    fn constant_local(&self, pointer: Handle<Expression>) -> Option<Handle<LocalVariable>> {
+        match self.function.expressions[pointer] {
+            Expression::LocalVariable(local) if self.usage[local.index()].is_constant() => {
+                Some(local)
+            }
+            _ => None,
+        }
+    }
+
+    /// Return true if `pointer` points into a local variable whose value
+    /// is never observed.
+    fn is_dead_store(&self, pointer: Handle<Expression>) -> bool {
+        analysis::root_local(self.function, pointer)
+            .map_or(false, |local| self.usage[local.index()].is_write_only())
+    }
+}
+
+/// Return true if control never proceeds past `statement` to the next
+/// statement in its block.
+const fn is_terminator(statement: &Statement) -> bool {
+    matches!(
+        *statement,
+        Statement::Break | Statement::Continue | Statement::Return { .. } | Statement::Kill
+    )
+}
+
+impl<'a> Builder<'a> {
+    /// Record what only becomes known once all of `source` has been copied.
+    fn finish(&mut self, source: &Source) {
+        for (handle, name) in source.function.named_expressions.iter() {
+            if let Some(new) = source.expressions[handle.index()] {
+                self.function
+                    .named_expressions
+                    .entry(new)
+                    .or_insert_with(|| crate::NamedExpression {
+                        name: name.name.clone(),
+                        span: name.span,
+                        ty: name.ty,
+                    });
+            }
+        }
+
+        for (handle, named_use) in source.function.named_uses.iter() {
+            if let Some(expression) = source.expressions[named_use.expression.index()] {
+                let span = source.function.named_uses.get_span(handle);
+                self.function
+                    .named_uses
+                    .append(crate::NamedExpressionUse { expression }, span);
+            }
+        }
+    }
+
+    /// Append `expression` to the new function without evaluating it.
+    fn append(
+        &mut self,
+        block: &mut Block,
+        expression: Expression,
+        span: Span,
+    ) -> Handle<Expression> {
+        if expression.needs_pre_emit() && self.emitter.is_running() {
+            block.extend(self.emitter.finish(&self.function.expressions));
+            let handle = self.function.expressions.append(expression, span);
+            self.emitter.start(&self.function.expressions);
+            handle
+        } else {
+            self.function.expressions.append(expression, span)
+        }
+    }
+
+    /// Append `expression` to the new function, or its value if it is
+    /// constant.
+    fn evaluate(
+        &mut self,
+        block: &mut Block,
+        expression: Expression,
+        span: Span,
+    ) -> Handle<Expression> {
+        let mut evaluator = ConstantEvaluator::for_wgsl_function(
+            self.module,
+            &mut self.function.expressions,
+            &mut self.constness,
+            &mut self.emitter,
+            block,
+        );
+        match evaluator.try_eval_and_append(&expression, span) {
+            Ok(handle) => handle,
+            // Not constant, or an error that would happen at run time.
+            Err(_) => self.append(block, expression, span),
+        }
+    }
+
+    fn local(
+        &mut self,
+        source: &mut Source,
+        block: &mut Block,
+        old: Handle<LocalVariable>,
+    ) -> Handle<LocalVariable> {
+        if let Some(new) = source.local_variables[old.index()] {
+            return new;
+        }
+        let function = source.function;
+        let local = &function.local_variables[old];
+        let init = match source.arguments {
+            // `inline_call` initializes the variable at every call instead.
+            Some(_) => None,
+            None => local.init.map(|init| self.expression(source, block, init)),
+        };
+        let new = self.function.local_variables.append(
+            LocalVariable {
+                name: local.name.clone(),
+                ty: local.ty,
+                init,
+            },
+            function.local_variables.get_span(old),
+        );
+        source.local_variables[old.index()] = Some(new);
+        new
+    }
+
+    /// Return the new handle for `source`'s expression `old`, translating it
+    /// if that hasn't happened yet.
+    fn expression<'s>(
+        &mut self,
+        source: &mut Source<'s>,
+        block: &mut Block,
+        old: Handle<Expression>,
+    ) -> Handle<Expression> {
+        if let Some(new) = source.expressions[old.index()] {
+            return new;
+        }
+
+        let function = source.function;
+        let span = function.expressions.get_span(old);
+        let constant_load = match function.expressions[old] {
+            Expression::Load { pointer } => source.constant_local(pointer),
+            _ => None,
+        };
+        if let Some(local) = constant_load {
+            // The variable always holds its initial value.
+            let local = &function.local_variables[local];
+            let new = match local.init {
+                Some(init) => self.expression(source, block, init),
+                None => self.evaluate(block, Expression::ZeroValue(local.ty), span),
+            };
+            source.expressions[old.index()] = Some(new);
+            return new;
+        }
+
+        let new = match function.expressions[old] {
+            Expression::FunctionArgument(index) => match source.arguments {
+                Some(ref arguments) => arguments[index as usize],
+                None => self.append(block, Expression::FunctionArgument(index), span),
+            },
+            Expression::LocalVariable(local) => {
+                let local = self.local(source, block, local);
+                self.append(block, Expression::LocalVariable(local), span)
+            }
+            ref expression => {
+                let mut expression = expression.clone();
+                crate::proc::expression_operands(&mut expression, |operand| {
+                    *operand = self.expression(source, block, *operand);
+                });
+                self.evaluate(block, expression, span)
+            }
+        };
+
+        source.expressions[old.index()] = Some(new);
+        new
+    }
+
+    /// Copy `statements` from `source` to `block`.
+    ///
+    /// If `truncate` is set, stop after a statement that never proceeds to
+    /// the next.
+    fn statements<'s>(
+        &mut self,
+        source: &mut Source<'s>,
+        statements: &'s Block,
+        block: &mut Block,
+        truncate: bool,
+    ) {
+        for (statement, &span) in statements.span_iter() {
+            self.statement(source, statement, span, block);
+            if truncate && block.last().map_or(false, is_terminator) {
+                break;
+            }
+        }
+    }
+
+    fn block<'s>(&mut self, source: &mut Source<'s>, statements: &'s Block) -> Block {
+        let mut block = Block::new();
+        self.statements(source, statements, &mut block, true);
+        block
+    }
+
+    fn statement<'s>(
+        &mut self,
+        source: &mut Source<'s>,
+        statement: &'s Statement,
+        span: Span,
+        block: &mut Block,
+    ) {
+        match *statement {
+            Statement::Emit(ref range) => {
+                self.emitter.start(&self.function.expressions);
+                for old in range.clone() {
+                    self.expression(source, block, old);
+                }
+                block.extend(self.emitter.finish(&self.function.expressions));
+            }
+            Statement::Block(ref statements) => {
+                let new = self.block(source, statements);
+                if !new.is_empty() {
+                    block.push(Statement::Block(new), span);
+                }
+            }
+            Statement::If {
+                condition,
+                ref accept,
+                ref reject,
+            } => {
+                let condition = self.expression(source, block, condition);
+                if let Expression::Literal(Literal::Bool(value)) =
+                    self.function.expressions[condition]
+                {
+                    let taken = if value { accept } else { reject };
+                    self.statements(source, taken, block, true);
+                    return;
+                }
+
+                let accept = self.block(source, accept);
+                let reject = self.block(source, reject);
+                if !accept.is_empty() || !reject.is_empty() {
+                    block.push(
+                        Statement::If {
+                            condition,
+                            accept,
+                            reject,
+                        },
+                        span,
+                    );
+                }
+            }
+            Statement::Switch {
+                selector,
+                ref cases,
+            } => {
+                let selector = self.expression(source, block, selector);
+                let value = match self.function.expressions[selector] {
+                    Expression::Literal(Literal::I32(value)) => Some(SwitchValue::I32(value)),
+                    Expression::Literal(Literal::U32(value)) => Some(SwitchValue::U32(value)),
+                    _ => None,
+                };
+                let taken = value.and_then(|value| {
+                    cases
+                        .iter()
+                        .position(|case| case.value == value)
+                        .or_else(|| {
+                            cases
+                                .iter()
+                                .position(|case| case.value == SwitchValue::Default)
+                        })
+                });
+
+                match taken {
+                    Some(first) => {
+                        // The cases that run: the one selected, and those
+                        // it falls through to.
+                        let last = cases[first..]
+                            .iter()
+                            .position(|case| !case.fall_through)
+                            .map_or(cases.len() - 1, |offset| first + offset);
+                        let taken = &cases[first..=last];
+
+                        if taken.iter().any(|case| analysis::breaks_out(&case.body)) {
+                            // Keep a switch for the `Break`s to leave.
+                            let mut body = Block::new();
+                            for case in taken {
+                                self.statements(source, &case.body, &mut body, true);
+                                if body.last().map_or(false, is_terminator) {
+                                    break;
+                                }
+                            }
+                            block.push(
+                                Statement::Switch {
+                                    selector,
+                                    cases: vec![SwitchCase {
+                                        value: SwitchValue::Default,
+                                        body,
+                                        fall_through: false,
+                                    }],
+                                },
+                                span,
+                            );
+                        } else {
+                            for case in taken {
+                                self.statements(source, &case.body, block, true);
+                                if block.last().map_or(false, is_terminator) {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    None => {
+                        let cases = cases
+                            .iter()
+                            .map(|case| SwitchCase {
+                                value: case.value,
+                                body: self.block(source, &case.body),
+                                fall_through: case.fall_through,
+                            })
+                            .collect();
+                        block.push(Statement::Switch { selector, cases }, span);
+                    }
+                }
+            }
+            Statement::Loop {
+                ref body,
+                ref continuing,
+                break_if,
+            } => {
+                // The `continuing` block may use expressions emitted anywhere
+                // in the body, so translate all of it.
+                let mut new_body = Block::new();
+                self.statements(source, body, &mut new_body, false);
+                let mut new_continuing = self.block(source, continuing);
+                let break_if =
+                    break_if.map(|break_if| self.expression(source, &mut new_continuing, break_if));
+                block.push(
+                    Statement::Loop {
+                        body: new_body,
+                        continuing: new_continuing,
+                        break_if,
+                    },
+                    span,
+                );
+            }
+            Statement::Store { pointer, .. } if source.is_dead_store(pointer) => {}
+            Statement::Call {
+                function,
+                ref arguments,
+                result,
+            } if self.inline[function.index()] => {
+                let arguments = arguments
+                    .iter()
+                    .map(|&argument| self.expression(source, block, argument))
+                    .collect();
+                let value = self.inline_call(function, arguments, block);
+                if let (Some(result), Some(value)) = (result, value) {
+                    source.expressions[result.index()] = Some(value);
+                }
+            }
+            ref leaf => {
+                let mut leaf = leaf.clone();
+                crate::proc::statement_operands(&mut leaf, |operand| {
+                    *operand = self.expression(source, block, *operand);
+                });
+                block.push(leaf, span);
+            }
+        }
+    }
+
+    /// Copy the body of `function` to `block`, and return the value it
+    /// returns.
+    fn inline_call(
+        &mut self,
+        function: Handle<Function>,
+        arguments: Vec<Handle<Expression>>,
+        block: &mut Block,
+    ) -> Option<Handle<Expression>> {
+        let functions = self.functions;
+        let callee = &functions[function];
+        let mut source = Source::new(callee, Some(arguments));
+
+        let mut body = Block::new();
+        let mut value = None;
+        for (statement, &span) in callee.body.span_iter() {
+            if let Statement::Return { value: returned } = *statement {
+                value = returned.map(|returned| self.expression(&mut source, &mut body, returned));
+                break;
+            }
+            self.statement(&mut source, statement, span, &mut body);
+        }
+
+        // The callee's variables start over at every call, which matters
+        // when the call is in a loop, so store their initial values first.
+        let locals = source.local_variables.clone();
+        for ((old, local), new) in callee.local_variables.iter().zip(locals) {
+            let new = match new {
+                Some(new) => new,
+                None => continue,
+            };
+            let span = callee.local_variables.get_span(old);
+            let pointer = self.append(block, Expression::LocalVariable(new), span);
+            let value = match local.init {
+                Some(init) => self.expression(&mut source, block, init),
+                None => self.evaluate(block, Expression::ZeroValue(local.ty), span),
+            };
+            block.push(Statement::Store { pointer, value }, span);
+        }
+        block.append(&mut body);
+
+        self.finish(&source);
+        value
+    }
+}
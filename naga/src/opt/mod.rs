@@ -0,0 +1,189 @@
+/*!
+IR optimization.
+
+[`optimize`] rewrites a validated [`Module`] into a simpler one that behaves
+the same:
+
+- Loads from local variables that are never stored to, and whose address
+  is never taken, are replaced by the variables' initial values. Expressions
+  that become constant this way, or were already, are folded.
+
+- `If` and `Switch` statements on constant conditions are replaced by the
+  code they would run.
+
+- Stores to local variables that are never loaded from are removed, as are
+  statements that can never run because they follow a `Return`, `Break`,
+  `Continue` or `Kill`.
+
+- Calls to functions that are small, or called only once, are replaced by a
+  copy of the function's body. Functions no longer called from anywhere are
+  removed.
+
+Finally, the module is [compacted](crate::compact::compact).
+
+This is especially useful after conditional compilation or generic
+instantiation, which tend to leave constant conditions and trivial helper
+functions behind.
+
+[`Module`]: crate::Module
+*/
+
+mod analysis;
+mod function;
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests;
+
+use crate::{Block, Expression, Handle, Module, Statement};
+
+/// Options for [`optimize`].
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// Inline functions with at most this many statements, not counting
+    /// `Emit` statements. Functions that are only called once are always
+    /// inlined.
+    ///
+    /// Functions that return from anywhere but the end of their body are
+    /// never inlined.
+    pub inline_statement_limit: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            inline_statement_limit: 8,
+        }
+    }
+}
+
+/// Optimize `module`.
+///
+/// # Panics
+///
+/// If `module` has not passed validation, this may panic.
+pub fn optimize(module: &mut Module, options: &Options) {
+    let call_counts = analysis::call_counts(module);
+    let inline = module
+        .functions
+        .iter()
+        .map(|(handle, function)| {
+            let calls = call_counts[handle.index()];
+            calls > 0
+                && (calls == 1
+                    || analysis::statement_count(&function.body) <= options.inline_statement_limit)
+                && analysis::returns_only_at_end(&function.body)
+        })
+        .collect::<Vec<_>>();
+
+    // Rebuild every function from the originals, so that inlined bodies are
+    // always copied from the unoptimized function. Handles are preserved,
+    // since the functions are appended in the same order.
+    let functions = std::mem::take(&mut module.functions);
+    for (handle, function) in functions.iter() {
+        let optimized = function::optimize(module, &functions, &inline, function);
+        module
+            .functions
+            .append(optimized, functions.get_span(handle));
+    }
+    let mut entry_points = std::mem::take(&mut module.entry_points);
+    for entry_point in entry_points.iter_mut() {
+        entry_point.function =
+            function::optimize(module, &functions, &inline, &entry_point.function);
+    }
+    module.entry_points = entry_points;
+
+    remove_inlined_functions(module, &call_counts);
+    crate::compact::compact(module);
+}
+
+/// Remove the functions that were called before optimization, but no longer
+/// are, because every call to them was inlined.
+///
+/// Functions that were never called, or that are exported, are kept, since
+/// they may be used when linking with other modules.
+fn remove_inlined_functions(module: &mut Module, call_counts_before: &[usize]) {
+    let call_counts = analysis::call_counts(module);
+    let mut exported = vec![false; call_counts.len()];
+    for export in module.exports.values() {
+        if let crate::Export::Function(function) = *export {
+            exported[function.index()] = true;
+        }
+    }
+
+    let mut new_handles = Vec::with_capacity(call_counts.len());
+    // `Handle::new` takes a 1-based index.
+    let mut next_index = std::num::NonZeroU32::new(1).unwrap();
+    for (index, (&before, &after)) in call_counts_before
+        .iter()
+        .zip(call_counts.iter())
+        .enumerate()
+    {
+        if before > 0 && after == 0 && !exported[index] {
+            new_handles.push(None);
+        } else {
+            new_handles.push(Some(Handle::new(next_index)));
+            next_index = next_index.checked_add(1).unwrap();
+        }
+    }
+    if new_handles.iter().all(Option::is_some) {
+        return;
+    }
+
+    module
+        .functions
+        .retain_mut(|handle, _| new_handles[handle.index()].is_some());
+
+    let adjust = |handle: &mut Handle<crate::Function>| {
+        *handle = new_handles[handle.index()].unwrap();
+    };
+    let functions = module
+        .functions
+        .iter_mut()
+        .map(|(_, function)| function)
+        .chain(module.entry_points.iter_mut().map(|ep| &mut ep.function));
+    for function in functions {
+        for (_, expression) in function.expressions.iter_mut() {
+            if let Expression::CallResult(ref mut callee) = *expression {
+                adjust(callee);
+            }
+        }
+        adjust_calls(&mut function.body, &adjust);
+    }
+    for export in module.exports.values_mut() {
+        if let crate::Export::Function(ref mut function) = *export {
+            adjust(function);
+        }
+    }
+}
+
+fn adjust_calls(block: &mut Block, adjust: &impl Fn(&mut Handle<crate::Function>)) {
+    for statement in block.iter_mut() {
+        match *statement {
+            Statement::Call {
+                ref mut function, ..
+            } => adjust(function),
+            Statement::Block(ref mut block) => adjust_calls(block, adjust),
+            Statement::If {
+                ref mut accept,
+                ref mut reject,
+                ..
+            } => {
+                adjust_calls(accept, adjust);
+                adjust_calls(reject, adjust);
+            }
+            Statement::Switch { ref mut cases, .. } => {
+                for case in cases {
+                    adjust_calls(&mut case.body, adjust);
+                }
+            }
+            Statement::Loop {
+                ref mut body,
+                ref mut continuing,
+                ..
+            } => {
+                adjust_calls(body, adjust);
+                adjust_calls(continuing, adjust);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use super::{optimize, Options};
+use crate::{
+    front::wgsl::source_provider::SingleFile,
+    valid::{Capabilities, ValidationFlags, Validator},
+    Block, Expression, Literal, Statement,
+};
+
+/// Parse and optimize `source`, and check that the result validates.
+fn optimized(source: &str) -> crate::Module {
+    let source = SingleFile::new("test.wgsl", source);
+    let mut module = match crate::front::wgsl::parse_module(&source, SingleFile::ID) {
+        Ok(module) => module,
+        Err(error) => panic!("{}", error.emit_to_string_with_provider(&source)),
+    };
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::all());
+    validator.validate(&module).unwrap();
+    optimize(&mut module, &Options::default());
+    validator.validate(&module).unwrap();
+    module
+}
+
+/// Collect the statements in `block` and its nested blocks, except `Emit`s.
+fn statements(block: &Block) -> Vec<&Statement> {
+    let mut statements = Vec::new();
+    let mut blocks = vec![block];
+    while let Some(block) = blocks.pop() {
+        for statement in block {
+            if !matches!(*statement, Statement::Emit(_)) {
+                statements.push(statement);
+            }
+            super::analysis::nested_blocks(statement, &mut blocks);
+        }
+    }
+    statements
+}
+
+fn entry_point_body(module: &crate::Module) -> Vec<&Statement> {
+    statements(&module.entry_points[0].function.body)
+}
+
+#[test]
+fn fold_constant_conditions() {
+    let module = optimized(
+        "
+        @group(0) @binding(0) var<storage, read_write> out: array<u32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            var mode = 2;
+            if mode == 2 {
+                out[0] = 1u;
+            } else {
+                out[0] = 2u;
+            }
+            switch mode {
+                case 1: { out[1] = 1u; }
+                case 2, 3: { out[1] = 2u; }
+                default: { out[1] = 3u; }
+            }
+        }
+        ",
+    );
+
+    let body = entry_point_body(&module);
+    assert!(!body
+        .iter()
+        .any(|s| matches!(**s, Statement::If { .. } | Statement::Switch { .. })));
+    let stores = body
+        .iter()
+        .filter(|s| matches!(***s, Statement::Store { .. }))
+        .count();
+    assert_eq!(stores, 2);
+}
+
+#[test]
+fn remove_dead_stores_and_unreachable_code() {
+    let module = optimized(
+        "
+        @group(0) @binding(0) var<storage, read_write> out: array<u32>;
+
+        @compute @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            var unused = id.x;
+            unused = id.y;
+            out[0] = 1u;
+            if id.x == 0u {
+                return;
+            }
+        }
+        ",
+    );
+
+    let function = &module.entry_points[0].function;
+    assert!(function.local_variables.is_empty());
+    let stores = entry_point_body(&module)
+        .iter()
+        .filter(|s| matches!(***s, Statement::Store { .. }))
+        .count();
+    assert_eq!(stores, 1);
+}
+
+#[test]
+fn inline_functions() {
+    let module = optimized(
+        "
+        @group(0) @binding(0) var<storage, read_write> out: array<u32>;
+
+        fn small(x: u32) -> u32 {
+            return x * 2u;
+        }
+
+        fn once(x: u32) -> u32 {
+            var y = x;
+            y += small(x);
+            return y;
+        }
+
+        fn early(x: u32) -> u32 {
+            if x == 0u {
+                return 1u;
+            }
+            return x;
+        }
+
+        fn unused() {}
+
+        @compute @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            out[0] = small(id.x) + once(id.y);
+            out[1] = early(id.z) + early(id.x);
+        }
+        ",
+    );
+
+    // Only the function with an early return, and the one that was never
+    // called, are left.
+    let names = module
+        .functions
+        .iter()
+        .map(|(_, f)| f.name.as_deref().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["early", "unused"]);
+
+    let calls = entry_point_body(&module)
+        .iter()
+        .filter(|s| matches!(***s, Statement::Call { .. }))
+        .count();
+    assert_eq!(calls, 2);
+}
+
+#[test]
+fn keep_local_initializers() {
+    let module = optimized(
+        "
+        @group(0) @binding(0) var<storage, read_write> buf: array<i32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            var j = 7;
+            j = j + buf[0];
+            buf[1] = j;
+        }
+        ",
+    );
+
+    let function = &module.entry_points[0].function;
+    let (_, j) = function.local_variables.iter().next().unwrap();
+    let init = j.init.expect("the initializer was dropped");
+    assert_eq!(
+        function.expressions[init],
+        Expression::Literal(Literal::I32(7))
+    );
+}
+
+#[test]
+fn reset_inlined_locals() {
+    let module = optimized(
+        "
+        @group(0) @binding(0) var<storage, read_write> buf: array<i32>;
+
+        fn acc(x: i32) -> i32 {
+            var total: i32;
+            total += x;
+            return total;
+        }
+
+        @compute @workgroup_size(1)
+        fn main() {
+            for (var i = 0; i < 4; i++) {
+                buf[i] = acc(buf[i]);
+            }
+        }
+        ",
+    );
+
+    // Each call starts from zero, so the loop must reset `total` before
+    // adding to it.
+    let function = &module.entry_points[0].function;
+    let (total, _) = function
+        .local_variables
+        .iter()
+        .find(|&(_, local)| local.name.as_deref() == Some("total"))
+        .unwrap();
+    let body = match *entry_point_body(&module)
+        .into_iter()
+        .find(|s| matches!(**s, Statement::Loop { .. }))
+        .unwrap()
+    {
+        Statement::Loop { ref body, .. } => body,
+        _ => unreachable!(),
+    };
+    let reset = statements(body).into_iter().find_map(|s| match *s {
+        Statement::Store { pointer, value }
+            if function.expressions[pointer] == Expression::LocalVariable(total) =>
+        {
+            Some(&function.expressions[value])
+        }
+        _ => None,
+    });
+    assert!(
+        matches!(reset, Some(&Expression::ZeroValue(_))),
+        "{reset:?}"
+    );
+}
@@ -133,6 +133,8 @@ pub enum ConstantEvaluatorError {
     ImageExpression,
     #[error("Constants don't support ray query expressions")]
     RayQueryExpression,
+    #[error("Constants don't support subgroup expressions")]
+    SubgroupExpression,
     #[error("Cannot access the type")]
     InvalidAccessBase,
     #[error("Cannot access at the index")]
@@ -153,6 +155,12 @@ pub enum ConstantEvaluatorError {
     InvalidMathArgCount(crate::MathFunction, usize, usize),
     #[error("value of `low` is greater than `high` for clamp built-in function")]
     InvalidClamp,
+    #[error("Cannot invert a singular matrix")]
+    SingularMatrix,
+    #[error("Cannot apply the select built-in function to the arguments")]
+    InvalidSelectArgs,
+    #[error("Cannot apply the {0:?} built-in function to the argument")]
+    InvalidRelationalArg(crate::RelationalFunction),
     #[error("Splat is defined only on scalar values")]
     SplatScalarOnly,
     #[error("Can only swizzle vector constants")]
@@ -425,17 +433,25 @@ impl<'a> ConstantEvaluator<'a> {
 
                 match convert {
                     Some(width) => self.cast(expr, crate::Scalar { kind, width }, span),
-                    None => Err(ConstantEvaluatorError::NotImplemented(
-                        "bitcast built-in function".into(),
-                    )),
+                    None => self.bitcast(expr, kind, span),
                 }
             }
-            Expression::Select { .. } => Err(ConstantEvaluatorError::NotImplemented(
-                "select built-in function".into(),
-            )),
-            Expression::Relational { fun, .. } => Err(ConstantEvaluatorError::NotImplemented(
-                format!("{fun:?} built-in function"),
-            )),
+            Expression::Select {
+                condition,
+                accept,
+                reject,
+            } => {
+                let condition = self.check_and_get(condition)?;
+                let accept = self.check_and_get(accept)?;
+                let reject = self.check_and_get(reject)?;
+
+                self.select(reject, accept, condition, span)
+            }
+            Expression::Relational { fun, argument } => {
+                let argument = self.check_and_get(argument)?;
+
+                self.relational(fun, argument, span)
+            }
             Expression::ArrayLength(expr) => match self.behavior {
                 Behavior::Wgsl => Err(ConstantEvaluatorError::ArrayLength),
                 Behavior::Glsl => {
@@ -459,6 +475,9 @@ impl<'a> ConstantEvaluator<'a> {
             Expression::RayQueryProceedResult | Expression::RayQueryGetIntersection { .. } => {
                 Err(ConstantEvaluatorError::RayQueryExpression)
             }
+            Expression::SubgroupBallotResult | Expression::SubgroupOperationResult { .. } => {
+                Err(ConstantEvaluatorError::SubgroupExpression)
+            }
         }
     }
 
@@ -578,6 +597,8 @@ impl<'a> ConstantEvaluator<'a> {
         fun: crate::MathFunction,
         span: Span,
     ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        use crate::MathFunction as Mf;
+
         let expected = fun.argument_count();
         let given = Some(arg)
             .into_iter()
@@ -590,316 +611,940 @@ impl<'a> ConstantEvaluator<'a> {
                 fun, expected, given,
             ));
         }
+        let (arg1, arg2, arg3) = (
+            arg1.unwrap_or(arg),
+            arg2.unwrap_or(arg),
+            arg3.unwrap_or(arg),
+        );
 
         match fun {
-            crate::MathFunction::Pow => self.math_pow(arg, arg1.unwrap(), span),
-            crate::MathFunction::Clamp => self.math_clamp(arg, arg1.unwrap(), arg2.unwrap(), span),
-            fun => Err(ConstantEvaluatorError::NotImplemented(format!(
-                "{fun:?} built-in function"
-            ))),
-        }
-    }
+            // comparison
+            Mf::Abs => self.component_wise([arg], span, |[e]| {
+                Ok(match e {
+                    Literal::I32(e) => Literal::I32(e.wrapping_abs()),
+                    Literal::I64(e) => Literal::I64(e.wrapping_abs()),
+                    Literal::U32(e) => Literal::U32(e),
+                    Literal::AbstractInt(e) => Literal::AbstractInt(
+                        e.checked_abs()
+                            .ok_or_else(|| ConstantEvaluatorError::Overflow("abs".into()))?,
+                    ),
+                    e => float_op([e], |[e]| e.abs())?,
+                })
+            }),
+            Mf::Min => self.component_wise([arg, arg1], span, |[e1, e2]| {
+                check_numeric([e1, e2])?;
+                Ok(if e2 < e1 { e2 } else { e1 })
+            }),
+            Mf::Max => self.component_wise([arg, arg1], span, |[e1, e2]| {
+                check_numeric([e1, e2])?;
+                Ok(if e2 > e1 { e2 } else { e1 })
+            }),
+            Mf::Clamp => self.component_wise([arg, arg1, arg2], span, |[e, low, high]| {
+                check_numeric([e, low, high])?;
+                if low > high {
+                    Err(ConstantEvaluatorError::InvalidClamp)
+                } else if e < low {
+                    Ok(low)
+                } else if e > high {
+                    Ok(high)
+                } else {
+                    Ok(e)
+                }
+            }),
+            Mf::Saturate => self.float_component_wise([arg], span, |[e]| e.clamp(0.0, 1.0)),
+            // trigonometry
+            Mf::Cos => self.float_component_wise([arg], span, |[e]| e.cos()),
+            Mf::Cosh => self.float_component_wise([arg], span, |[e]| e.cosh()),
+            Mf::Sin => self.float_component_wise([arg], span, |[e]| e.sin()),
+            Mf::Sinh => self.float_component_wise([arg], span, |[e]| e.sinh()),
+            Mf::Tan => self.float_component_wise([arg], span, |[e]| e.tan()),
+            Mf::Tanh => self.float_component_wise([arg], span, |[e]| e.tanh()),
+            Mf::Acos => self.float_component_wise([arg], span, |[e]| e.acos()),
+            Mf::Asin => self.float_component_wise([arg], span, |[e]| e.asin()),
+            Mf::Atan => self.float_component_wise([arg], span, |[e]| e.atan()),
+            Mf::Atan2 => self.float_component_wise([arg, arg1], span, |[y, x]| y.atan2(x)),
+            Mf::Asinh => self.float_component_wise([arg], span, |[e]| e.asinh()),
+            Mf::Acosh => self.float_component_wise([arg], span, |[e]| e.acosh()),
+            Mf::Atanh => self.float_component_wise([arg], span, |[e]| e.atanh()),
+            Mf::Radians => self.float_component_wise([arg], span, |[e]| e.to_radians()),
+            Mf::Degrees => self.float_component_wise([arg], span, |[e]| e.to_degrees()),
+            // decomposition
+            Mf::Ceil => self.float_component_wise([arg], span, |[e]| e.ceil()),
+            Mf::Floor => self.float_component_wise([arg], span, |[e]| e.floor()),
+            Mf::Round => self.float_component_wise([arg], span, |[e]| round_ties_even(e)),
+            Mf::Fract => self.float_component_wise([arg], span, |[e]| e - e.floor()),
+            Mf::Trunc => self.float_component_wise([arg], span, |[e]| e.trunc()),
+            Mf::Modf => self.math_modf_frexp(arg, fun, span),
+            Mf::Frexp => self.math_modf_frexp(arg, fun, span),
+            Mf::Ldexp => self.component_wise([arg, arg1], span, |[e1, e2]| {
+                let exponent = match e2 {
+                    Literal::I32(e2) => i64::from(e2),
+                    Literal::I64(e2) | Literal::AbstractInt(e2) => e2,
+                    _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+                };
+                float_op([e1], |[e1]| ldexp(e1, exponent))
+            }),
+            // exponent
+            Mf::Exp => self.float_component_wise([arg], span, |[e]| e.exp()),
+            Mf::Exp2 => self.float_component_wise([arg], span, |[e]| e.exp2()),
+            Mf::Log => self.float_component_wise([arg], span, |[e]| e.ln()),
+            Mf::Log2 => self.float_component_wise([arg], span, |[e]| e.log2()),
+            Mf::Pow => self.float_component_wise([arg, arg1], span, |[e1, e2]| e1.powf(e2)),
+            // geometry
+            Mf::Dot => {
+                let e1 = self.components(arg, span)?;
+                let e2 = self.components(arg1, span)?;
+                if e1.size.is_none() || e1.size != e2.size {
+                    return Err(ConstantEvaluatorError::InvalidMathArg);
+                }
 
-    fn math_pow(
-        &mut self,
-        e1: Handle<Expression>,
-        e2: Handle<Expression>,
-        span: Span,
-    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
-        let e1 = self.eval_zero_value_and_splat(e1, span)?;
-        let e2 = self.eval_zero_value_and_splat(e2, span)?;
+                let literal = match e1.literals[0] {
+                    like @ (Literal::I32(_)
+                    | Literal::U32(_)
+                    | Literal::I64(_)
+                    | Literal::AbstractInt(_)) => {
+                        let (a, b) = (e1.ints()?, e2.ints()?);
+                        int_literal(like, a.iter().zip(&b).map(|(a, b)| a * b).sum(), "dot")?
+                    }
+                    like => {
+                        let dot = dot(&e1.floats()?, &e2.floats()?);
+                        float_literal(like, dot)?
+                    }
+                };
+                self.register_evaluated_expr(Expression::Literal(literal), span)
+            }
+            Mf::Outer => {
+                let e1 = self.components(arg, span)?;
+                let e2 = self.components(arg1, span)?;
+                let (like, a, b) = (e1.literals[0], e1.floats()?, e2.floats()?);
+                if e1.size.is_none() || e2.size.is_none() {
+                    return Err(ConstantEvaluatorError::InvalidMathArg);
+                }
 
-        let expr = match (&self.expressions[e1], &self.expressions[e2]) {
-            (&Expression::Literal(Literal::F32(a)), &Expression::Literal(Literal::F32(b))) => {
-                Expression::Literal(Literal::F32(a.powf(b)))
+                let columns = b
+                    .iter()
+                    .map(|&b| a.iter().map(|&a| float_literal(like, a * b)).collect())
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.matrix_from_columns(columns, span)
             }
-            (
-                &Expression::Compose {
-                    components: ref src_components0,
-                    ty: ty0,
-                },
-                &Expression::Compose {
-                    components: ref src_components1,
-                    ty: ty1,
-                },
-            ) if ty0 == ty1
-                && matches!(
-                    self.types[ty0].inner,
-                    crate::TypeInner::Vector {
-                        scalar: crate::Scalar {
-                            kind: ScalarKind::Float,
-                            ..
-                        },
-                        ..
-                    }
-                ) =>
-            {
-                let mut components: Vec<_> = crate::proc::flatten_compose(
-                    ty0,
-                    src_components0,
-                    self.expressions,
-                    self.types,
-                )
-                .chain(crate::proc::flatten_compose(
-                    ty1,
-                    src_components1,
-                    self.expressions,
-                    self.types,
-                ))
-                .collect();
+            Mf::Cross => {
+                let e1 = self.components(arg, span)?;
+                let e2 = self.components(arg1, span)?;
+                let (a, b) = (e1.floats()?, e2.floats()?);
+                if e1.size != Some(crate::VectorSize::Tri) || e2.size != e1.size {
+                    return Err(ConstantEvaluatorError::InvalidMathArg);
+                }
 
-                let mid = components.len() / 2;
-                let (first, last) = components.split_at_mut(mid);
-                for (a, b) in first.iter_mut().zip(&*last) {
-                    *a = self.math_pow(*a, *b, span)?;
+                let cross = [
+                    a[1] * b[2] - a[2] * b[1],
+                    a[2] * b[0] - a[0] * b[2],
+                    a[0] * b[1] - a[1] * b[0],
+                ];
+                self.float_components_to_expr(&e1, cross, span)
+            }
+            Mf::Distance => {
+                let e1 = self.components(arg, span)?;
+                let e2 = self.components(arg1, span)?;
+                if e1.size != e2.size {
+                    return Err(ConstantEvaluatorError::InvalidMathArg);
                 }
-                components.truncate(mid);
 
-                Expression::Compose {
-                    ty: ty0,
-                    components,
+                let (a, b) = (e1.floats()?, e2.floats()?);
+                let difference: Vec<_> = a.iter().zip(&b).map(|(a, b)| a - b).collect();
+                let literal = float_literal(e1.literals[0], dot(&difference, &difference).sqrt())?;
+                self.register_evaluated_expr(Expression::Literal(literal), span)
+            }
+            Mf::Length => {
+                let e = self.components(arg, span)?;
+                let values = e.floats()?;
+                let literal = float_literal(e.literals[0], dot(&values, &values).sqrt())?;
+                self.register_evaluated_expr(Expression::Literal(literal), span)
+            }
+            Mf::Normalize => {
+                let e = self.components(arg, span)?;
+                let values = e.floats()?;
+                let length = dot(&values, &values).sqrt();
+                self.float_components_to_expr(&e, values.iter().map(|v| v / length), span)
+            }
+            Mf::FaceForward => {
+                let n = self.components(arg, span)?;
+                let i = self.components(arg1, span)?;
+                let n_ref = self.components(arg2, span)?;
+                if n.size.is_none() || n.size != i.size || n.size != n_ref.size {
+                    return Err(ConstantEvaluatorError::InvalidMathArg);
                 }
+
+                let flip = dot(&n_ref.floats()?, &i.floats()?) >= 0.0;
+                let values = n.floats()?;
+                self.float_components_to_expr(
+                    &n,
+                    values.iter().map(|&n| if flip { -n } else { n }),
+                    span,
+                )
             }
-            _ => return Err(ConstantEvaluatorError::InvalidMathArg),
-        };
+            Mf::Reflect => {
+                let i = self.components(arg, span)?;
+                let n = self.components(arg1, span)?;
+                if i.size.is_none() || i.size != n.size {
+                    return Err(ConstantEvaluatorError::InvalidMathArg);
+                }
 
-        self.register_evaluated_expr(expr, span)
-    }
+                let (i_values, n_values) = (i.floats()?, n.floats()?);
+                let scale = 2.0 * dot(&n_values, &i_values);
+                self.float_components_to_expr(
+                    &i,
+                    i_values.iter().zip(&n_values).map(|(i, n)| i - scale * n),
+                    span,
+                )
+            }
+            Mf::Refract => {
+                let i = self.components(arg, span)?;
+                let n = self.components(arg1, span)?;
+                let eta = self.components(arg2, span)?;
+                if i.size.is_none() || i.size != n.size || eta.size.is_some() {
+                    return Err(ConstantEvaluatorError::InvalidMathArg);
+                }
 
-    fn math_clamp(
-        &mut self,
-        e: Handle<Expression>,
-        low: Handle<Expression>,
-        high: Handle<Expression>,
-        span: Span,
-    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
-        let e = self.eval_zero_value_and_splat(e, span)?;
-        let low = self.eval_zero_value_and_splat(low, span)?;
-        let high = self.eval_zero_value_and_splat(high, span)?;
-
-        let expr = match (
-            &self.expressions[e],
-            &self.expressions[low],
-            &self.expressions[high],
-        ) {
-            (&Expression::Literal(e), &Expression::Literal(low), &Expression::Literal(high)) => {
-                let literal = match (e, low, high) {
-                    (Literal::I32(e), Literal::I32(low), Literal::I32(high)) => {
-                        if low > high {
-                            return Err(ConstantEvaluatorError::InvalidClamp);
-                        } else {
-                            Literal::I32(e.clamp(low, high))
-                        }
-                    }
-                    (Literal::U32(e), Literal::U32(low), Literal::U32(high)) => {
-                        if low > high {
-                            return Err(ConstantEvaluatorError::InvalidClamp);
+                let (i_values, n_values, eta) = (i.floats()?, n.floats()?, eta.floats()?[0]);
+                let n_dot_i = dot(&n_values, &i_values);
+                let k = 1.0 - eta * eta * (1.0 - n_dot_i * n_dot_i);
+                let values: Vec<_> = if k < 0.0 {
+                    vec![0.0; i_values.len()]
+                } else {
+                    let scale = eta * n_dot_i + k.sqrt();
+                    i_values
+                        .iter()
+                        .zip(&n_values)
+                        .map(|(i, n)| eta * i - scale * n)
+                        .collect()
+                };
+                self.float_components_to_expr(&i, values, span)
+            }
+            // computational
+            Mf::Sign => self.component_wise([arg], span, |[e]| {
+                Ok(match e {
+                    Literal::I32(e) => Literal::I32(e.signum()),
+                    Literal::I64(e) => Literal::I64(e.signum()),
+                    Literal::AbstractInt(e) => Literal::AbstractInt(e.signum()),
+                    e => float_op([e], |[e]| {
+                        if e > 0.0 {
+                            1.0
+                        } else if e < 0.0 {
+                            -1.0
                         } else {
-                            Literal::U32(e.clamp(low, high))
+                            0.0
                         }
-                    }
-                    (Literal::F32(e), Literal::F32(low), Literal::F32(high)) => {
-                        if low > high {
-                            return Err(ConstantEvaluatorError::InvalidClamp);
+                    })?,
+                })
+            }),
+            Mf::Fma => {
+                self.float_component_wise([arg, arg1, arg2], span, |[a, b, c]| a.mul_add(b, c))
+            }
+            Mf::Mix => self
+                .float_component_wise([arg, arg1, arg2], span, |[x, y, a]| x * (1.0 - a) + y * a),
+            Mf::Step => {
+                self.float_component_wise(
+                    [arg, arg1],
+                    span,
+                    |[edge, x]| {
+                        if x >= edge {
+                            1.0
                         } else {
-                            Literal::F32(e.clamp(low, high))
+                            0.0
                         }
-                    }
-                    _ => return Err(ConstantEvaluatorError::InvalidMathArg),
-                };
-                Expression::Literal(literal)
-            }
-            (
-                &Expression::Compose {
-                    components: ref src_components0,
-                    ty: ty0,
-                },
-                &Expression::Compose {
-                    components: ref src_components1,
-                    ty: ty1,
-                },
-                &Expression::Compose {
-                    components: ref src_components2,
-                    ty: ty2,
-                },
-            ) if ty0 == ty1
-                && ty0 == ty2
-                && matches!(
-                    self.types[ty0].inner,
-                    crate::TypeInner::Vector {
-                        scalar: crate::Scalar {
-                            kind: ScalarKind::Float,
-                            ..
-                        },
-                        ..
-                    }
-                ) =>
-            {
-                let mut components: Vec<_> = crate::proc::flatten_compose(
-                    ty0,
-                    src_components0,
-                    self.expressions,
-                    self.types,
+                    },
                 )
-                .chain(crate::proc::flatten_compose(
-                    ty1,
-                    src_components1,
-                    self.expressions,
-                    self.types,
-                ))
-                .chain(crate::proc::flatten_compose(
-                    ty2,
-                    src_components2,
-                    self.expressions,
-                    self.types,
-                ))
-                .collect();
-
-                let chunk_size = components.len() / 3;
-                let (es, rem) = components.split_at_mut(chunk_size);
-                let (lows, highs) = rem.split_at(chunk_size);
-                for ((e, low), high) in es.iter_mut().zip(lows).zip(highs) {
-                    *e = self.math_clamp(*e, *low, *high, span)?;
+            }
+            Mf::SmoothStep => {
+                self.float_component_wise([arg, arg1, arg2], span, |[low, high, x]| {
+                    let t = ((x - low) / (high - low)).clamp(0.0, 1.0);
+                    t * t * (3.0 - 2.0 * t)
+                })
+            }
+            Mf::Sqrt => self.float_component_wise([arg], span, |[e]| e.sqrt()),
+            Mf::InverseSqrt => self.float_component_wise([arg], span, |[e]| 1.0 / e.sqrt()),
+            Mf::Inverse => {
+                let (like, columns) = self.matrix_columns(arg, span)?;
+                let inverse = inverse(&columns).ok_or(ConstantEvaluatorError::SingularMatrix)?;
+                let columns = inverse
+                    .iter()
+                    .map(|column| column.iter().map(|&v| float_literal(like, v)).collect())
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.matrix_from_columns(columns, span)
+            }
+            Mf::Transpose => {
+                let (like, columns) = self.matrix_columns(arg, span)?;
+                let rows = (0..columns[0].len())
+                    .map(|row| {
+                        columns
+                            .iter()
+                            .map(|column| float_literal(like, column[row]))
+                            .collect()
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.matrix_from_columns(rows, span)
+            }
+            Mf::Determinant => {
+                let (like, columns) = self.matrix_columns(arg, span)?;
+                if columns.len() != columns[0].len() {
+                    return Err(ConstantEvaluatorError::InvalidMathArg);
                 }
-                components.truncate(chunk_size);
 
-                Expression::Compose {
-                    ty: ty0,
-                    components,
-                }
+                let literal = float_literal(like, determinant(&columns))?;
+                self.register_evaluated_expr(Expression::Literal(literal), span)
             }
-            _ => return Err(ConstantEvaluatorError::InvalidMathArg),
-        };
-
-        self.register_evaluated_expr(expr, span)
-    }
-
-    fn array_length(
-        &mut self,
-        array: Handle<Expression>,
-        span: Span,
-    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
-        match self.expressions[array] {
-            Expression::ZeroValue(ty) | Expression::Compose { ty, .. } => {
-                match self.types[ty].inner {
-                    TypeInner::Array { size, .. } => match size {
-                        crate::ArraySize::Constant(len) => {
-                            let expr = Expression::Literal(Literal::U32(len.get()));
-                            self.register_evaluated_expr(expr, span)
+            // bits
+            Mf::CountTrailingZeros => self.component_wise([arg], span, |[e]| {
+                Ok(match e {
+                    Literal::I32(e) => Literal::I32(e.trailing_zeros() as i32),
+                    Literal::U32(e) => Literal::U32(e.trailing_zeros()),
+                    _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+                })
+            }),
+            Mf::CountLeadingZeros => self.component_wise([arg], span, |[e]| {
+                Ok(match e {
+                    Literal::I32(e) => Literal::I32(e.leading_zeros() as i32),
+                    Literal::U32(e) => Literal::U32(e.leading_zeros()),
+                    _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+                })
+            }),
+            Mf::CountOneBits => self.component_wise([arg], span, |[e]| {
+                Ok(match e {
+                    Literal::I32(e) => Literal::I32(e.count_ones() as i32),
+                    Literal::U32(e) => Literal::U32(e.count_ones()),
+                    _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+                })
+            }),
+            Mf::ReverseBits => self.component_wise([arg], span, |[e]| {
+                Ok(match e {
+                    Literal::I32(e) => Literal::I32(e.reverse_bits()),
+                    Literal::U32(e) => Literal::U32(e.reverse_bits()),
+                    _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+                })
+            }),
+            Mf::ExtractBits => self.component_wise([arg, arg1, arg2], span, |[e, offset, count]| {
+                let (Literal::U32(offset), Literal::U32(count)) = (offset, count) else {
+                    return Err(ConstantEvaluatorError::InvalidMathArg);
+                };
+                let offset = offset.min(32);
+                let count = count.min(32 - offset);
+                Ok(match e {
+                    Literal::U32(_) if count == 0 => Literal::U32(0),
+                    Literal::I32(_) if count == 0 => Literal::I32(0),
+                    Literal::U32(e) => Literal::U32((e >> offset) & (u32::MAX >> (32 - count))),
+                    // Shift the field to the top, then sign-extend it back down.
+                    Literal::I32(e) => Literal::I32((e << (32 - offset - count)) >> (32 - count)),
+                    _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+                })
+            }),
+            Mf::InsertBits => self.component_wise(
+                [arg, arg1, arg2, arg3],
+                span,
+                |[e, newbits, offset, count]| {
+                    let (Literal::U32(offset), Literal::U32(count)) = (offset, count) else {
+                        return Err(ConstantEvaluatorError::InvalidMathArg);
+                    };
+                    let offset = offset.min(32);
+                    let count = count.min(32 - offset);
+                    let mask = if count == 0 {
+                        0
+                    } else {
+                        (u32::MAX >> (32 - count)) << offset
+                    };
+                    let insert = |e: u32, newbits: u32| {
+                        (e & !mask) | (newbits.checked_shl(offset).unwrap_or(0) & mask)
+                    };
+                    Ok(match (e, newbits) {
+                        (Literal::U32(e), Literal::U32(newbits)) => {
+                            Literal::U32(insert(e, newbits))
                         }
-                        crate::ArraySize::Dynamic => {
-                            Err(ConstantEvaluatorError::ArrayLengthDynamic)
+                        (Literal::I32(e), Literal::I32(newbits)) => {
+                            Literal::I32(insert(e as u32, newbits as u32) as i32)
                         }
-                    },
-                    _ => Err(ConstantEvaluatorError::InvalidArrayLengthArg),
-                }
-            }
-            _ => Err(ConstantEvaluatorError::InvalidArrayLengthArg),
+                        _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+                    })
+                },
+            ),
+            Mf::FindLsb => self.component_wise([arg], span, |[e]| {
+                Ok(match e {
+                    Literal::I32(0) => Literal::I32(-1),
+                    Literal::U32(0) => Literal::U32(u32::MAX),
+                    Literal::I32(e) => Literal::I32(e.trailing_zeros() as i32),
+                    Literal::U32(e) => Literal::U32(e.trailing_zeros()),
+                    _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+                })
+            }),
+            Mf::FindMsb => self.component_wise([arg], span, |[e]| {
+                Ok(match e {
+                    Literal::I32(0 | -1) => Literal::I32(-1),
+                    Literal::U32(0) => Literal::U32(u32::MAX),
+                    // For negative values, find the most significant zero bit.
+                    Literal::I32(e) if e < 0 => Literal::I32(31 - e.leading_ones() as i32),
+                    Literal::I32(e) => Literal::I32(31 - e.leading_zeros() as i32),
+                    Literal::U32(e) => Literal::U32(31 - e.leading_zeros()),
+                    _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+                })
+            }),
+            // data packing
+            Mf::Pack4x8snorm => self.math_pack(arg, span, |e| {
+                let e = (0.5 + 127.0 * e.clamp(-1.0, 1.0)).floor();
+                Some(u32::from(e as i8 as u8))
+            }),
+            Mf::Pack4x8unorm => self.math_pack(arg, span, |e| {
+                let e = (0.5 + 255.0 * e.clamp(0.0, 1.0)).floor();
+                Some(u32::from(e as u8))
+            }),
+            Mf::Pack2x16snorm => self.math_pack(arg, span, |e| {
+                let e = (0.5 + 32767.0 * e.clamp(-1.0, 1.0)).floor();
+                Some(u32::from(e as i16 as u16))
+            }),
+            Mf::Pack2x16unorm => self.math_pack(arg, span, |e| {
+                let e = (0.5 + 65535.0 * e.clamp(0.0, 1.0)).floor();
+                Some(u32::from(e as u16))
+            }),
+            Mf::Pack2x16float => self.math_pack(arg, span, |e| f32_to_f16_bits(e).map(u32::from)),
+            // data unpacking
+            Mf::Unpack4x8snorm => self.math_unpack(arg, crate::VectorSize::Quad, span, |bits| {
+                (f32::from(bits as u8 as i8) / 127.0).max(-1.0)
+            }),
+            Mf::Unpack4x8unorm => self.math_unpack(arg, crate::VectorSize::Quad, span, |bits| {
+                f32::from(bits as u8) / 255.0
+            }),
+            Mf::Unpack2x16snorm => self.math_unpack(arg, crate::VectorSize::Bi, span, |bits| {
+                (f32::from(bits as u16 as i16) / 32767.0).max(-1.0)
+            }),
+            Mf::Unpack2x16unorm => self.math_unpack(arg, crate::VectorSize::Bi, span, |bits| {
+                f32::from(bits as u16) / 65535.0
+            }),
+            Mf::Unpack2x16float => self.math_unpack(arg, crate::VectorSize::Bi, span, |bits| {
+                f16_bits_to_f32(bits as u16)
+            }),
         }
     }
 
-    fn access(
+    /// Evaluate `fun` for `modf` or `frexp`, producing the predeclared result struct.
+    fn math_modf_frexp(
         &mut self,
-        base: Handle<Expression>,
-        index: usize,
+        arg: Handle<Expression>,
+        fun: crate::MathFunction,
         span: Span,
     ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
-        match self.expressions[base] {
-            Expression::ZeroValue(ty) => {
-                let ty_inner = &self.types[ty].inner;
-                let components = ty_inner
-                    .components()
-                    .ok_or(ConstantEvaluatorError::InvalidAccessBase)?;
-
-                if index >= components as usize {
-                    Err(ConstantEvaluatorError::InvalidAccessBase)
-                } else {
-                    let ty_res = ty_inner
-                        .component_type(index)
-                        .ok_or(ConstantEvaluatorError::InvalidAccessIndex)?;
-                    let ty = match ty_res {
-                        crate::proc::TypeResolution::Handle(ty) => ty,
-                        crate::proc::TypeResolution::Value(inner) => {
-                            self.types.insert(Type { name: None, inner }, span)
-                        }
-                    };
-                    self.register_evaluated_expr(Expression::ZeroValue(ty), span)
-                }
-            }
-            Expression::Splat { size, value } => {
-                if index >= size as usize {
-                    Err(ConstantEvaluatorError::InvalidAccessBase)
-                } else {
-                    Ok(value)
-                }
-            }
-            Expression::Compose { ty, ref components } => {
-                let _ = self.types[ty]
-                    .inner
-                    .components()
-                    .ok_or(ConstantEvaluatorError::InvalidAccessBase)?;
+        let e = self.components(arg, span)?;
+        let values = e.floats()?;
+        // Abstract arguments get the `f64` flavour of the result struct.
+        let like = match e.literals[0] {
+            Literal::AbstractFloat(_) => Literal::F64(0.0),
+            like => like,
+        };
+        let width = like.scalar().width;
 
-                crate::proc::flatten_compose(ty, components, self.expressions, self.types)
-                    .nth(index)
-                    .ok_or(ConstantEvaluatorError::InvalidAccessIndex)
-            }
-            _ => Err(ConstantEvaluatorError::InvalidAccessBase),
+        let mut name = if fun == crate::MathFunction::Modf {
+            "__modf_result_".to_string()
+        } else {
+            "__frexp_result_".to_string()
+        };
+        if let Some(size) = e.size {
+            name += &format!("vec{}_", size as u8);
         }
-    }
+        name += &format!("f{}", width * 8);
+        let ty = self
+            .types
+            .iter()
+            .find(|&(_, ty)| ty.name.as_deref() == Some(name.as_str()))
+            .map(|(handle, _)| handle)
+            .ok_or(ConstantEvaluatorError::TypeNotConstructible)?;
+
+        let (first, second): (Vec<_>, Vec<_>) = if fun == crate::MathFunction::Modf {
+            values
+                .iter()
+                .map(|&v| {
+                    Ok((
+                        float_literal(like, v - v.trunc())?,
+                        float_literal(like, v.trunc())?,
+                    ))
+                })
+                .collect::<Result<Vec<_>, ConstantEvaluatorError>>()?
+                .into_iter()
+                .unzip()
+        } else {
+            values
+                .iter()
+                .map(|&v| {
+                    let (fract, exp) = frexp(v);
+                    let exp = match width {
+                        8 => Literal::I64(i64::from(exp)),
+                        _ => Literal::I32(exp),
+                    };
+                    Ok((float_literal(like, fract)?, exp))
+                })
+                .collect::<Result<Vec<_>, ConstantEvaluatorError>>()?
+                .into_iter()
+                .unzip()
+        };
 
-    fn constant_index(&self, expr: Handle<Expression>) -> Result<usize, ConstantEvaluatorError> {
-        match self.expressions[expr] {
-            Expression::ZeroValue(ty)
-                if matches!(
-                    self.types[ty].inner,
-                    crate::TypeInner::Scalar(crate::Scalar {
-                        kind: ScalarKind::Uint,
-                        ..
-                    })
-                ) =>
-            {
-                Ok(0)
-            }
-            Expression::Literal(Literal::U32(index)) => Ok(index as usize),
-            _ => Err(ConstantEvaluatorError::InvalidAccessIndexTy),
-        }
+        let components = vec![
+            self.components_to_expr(e.size, first, span)?,
+            self.components_to_expr(e.size, second, span)?,
+        ];
+        self.register_evaluated_expr(Expression::Compose { ty, components }, span)
     }
 
-    /// Lower [`ZeroValue`] and [`Splat`] expressions to [`Literal`] and [`Compose`] expressions.
+    /// Pack the components of the float vector `arg` into a `u32`.
     ///
-    /// [`ZeroValue`]: Expression::ZeroValue
-    /// [`Splat`]: Expression::Splat
-    /// [`Literal`]: Expression::Literal
-    /// [`Compose`]: Expression::Compose
-    fn eval_zero_value_and_splat(
+    /// `f` converts each component to its bits, least significant
+    /// component first; `None` means the component is not representable.
+    fn math_pack(
         &mut self,
-        expr: Handle<Expression>,
+        arg: Handle<Expression>,
         span: Span,
+        f: impl Fn(f32) -> Option<u32>,
     ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
-        match self.expressions[expr] {
-            Expression::ZeroValue(ty) => self.eval_zero_value_impl(ty, span),
-            Expression::Splat { size, value } => self.splat(value, size, span),
-            _ => Ok(expr),
+        let e = self.components(arg, span)?;
+        let (Some(size), Literal::F32(_)) = (e.size, e.literals[0]) else {
+            return Err(ConstantEvaluatorError::InvalidMathArg);
+        };
+
+        let bits = 32 / size as u32;
+        let mut packed = 0;
+        for (i, value) in e.floats()?.into_iter().enumerate() {
+            let value = f(value as f32)
+                .ok_or_else(|| ConstantEvaluatorError::Overflow("pack2x16float".into()))?;
+            packed |= value << (i as u32 * bits);
         }
+        self.register_evaluated_expr(Expression::Literal(Literal::U32(packed)), span)
     }
 
-    /// Lower [`ZeroValue`] expressions to [`Literal`] and [`Compose`] expressions.
+    /// Unpack the `u32` `arg` into a float vector of `size` components.
     ///
-    /// [`ZeroValue`]: Expression::ZeroValue
-    /// [`Literal`]: Expression::Literal
-    /// [`Compose`]: Expression::Compose
-    fn eval_zero_value(
+    /// `f` receives each component's bits in the low bits of its argument.
+    fn math_unpack(
         &mut self,
-        expr: Handle<Expression>,
+        arg: Handle<Expression>,
+        size: crate::VectorSize,
         span: Span,
+        f: impl Fn(u32) -> f32,
     ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
-        match self.expressions[expr] {
-            Expression::ZeroValue(ty) => self.eval_zero_value_impl(ty, span),
-            _ => Ok(expr),
-        }
+        let e = self.components(arg, span)?;
+        let (None, Literal::U32(packed)) = (e.size, e.literals[0]) else {
+            return Err(ConstantEvaluatorError::InvalidMathArg);
+        };
+
+        let bits = 32 / size as u32;
+        let literals = (0..size as u32)
+            .map(|i| Literal::F32(f(packed >> (i * bits))))
+            .collect();
+        self.components_to_expr(Some(size), literals, span)
     }
 
-    /// Lower [`ZeroValue`] expressions to [`Literal`] and [`Compose`] expressions.
+    fn select(
+        &mut self,
+        reject: Handle<Expression>,
+        accept: Handle<Expression>,
+        condition: Handle<Expression>,
+        span: Span,
+    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        self.component_wise(
+            [reject, accept, condition],
+            span,
+            |[reject, accept, condition]| {
+                if std::mem::discriminant(&reject) != std::mem::discriminant(&accept) {
+                    return Err(ConstantEvaluatorError::InvalidSelectArgs);
+                }
+                match condition {
+                    Literal::Bool(true) => Ok(accept),
+                    Literal::Bool(false) => Ok(reject),
+                    _ => Err(ConstantEvaluatorError::InvalidSelectArgs),
+                }
+            },
+        )
+    }
+
+    fn relational(
+        &mut self,
+        fun: crate::RelationalFunction,
+        arg: Handle<Expression>,
+        span: Span,
+    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        use crate::RelationalFunction as Rf;
+
+        match fun {
+            Rf::All | Rf::Any => {
+                let e = self.components(arg, span)?;
+                let values = e
+                    .literals
+                    .iter()
+                    .map(|literal| match *literal {
+                        Literal::Bool(value) => Ok(value),
+                        _ => Err(ConstantEvaluatorError::InvalidRelationalArg(fun)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let result = if fun == Rf::All {
+                    values.iter().all(|&value| value)
+                } else {
+                    values.iter().any(|&value| value)
+                };
+                self.register_evaluated_expr(Expression::Literal(Literal::Bool(result)), span)
+            }
+            // Constant expressions can never hold a NaN or an infinity, since
+            // `register_evaluated_expr` rejects them.
+            Rf::IsNan | Rf::IsInf => self.component_wise([arg], span, |[e]| match e {
+                Literal::F32(_) | Literal::F64(_) | Literal::AbstractFloat(_) => {
+                    Ok(Literal::Bool(false))
+                }
+                _ => Err(ConstantEvaluatorError::InvalidRelationalArg(fun)),
+            }),
+        }
+    }
+
+    /// Reinterpret the bits of each component of `expr` as `kind`.
+    fn bitcast(
+        &mut self,
+        expr: Handle<Expression>,
+        kind: ScalarKind,
+        span: Span,
+    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        self.component_wise([expr], span, |[e]| {
+            let bits = match e {
+                Literal::F32(v) => u64::from(v.to_bits()),
+                Literal::U32(v) => u64::from(v),
+                Literal::I32(v) => u64::from(v as u32),
+                Literal::F64(v) => v.to_bits(),
+                Literal::I64(v) => v as u64,
+                _ => {
+                    return Err(ConstantEvaluatorError::InvalidCastArg {
+                        from: format!("{e:?}"),
+                        to: format!("{kind:?}"),
+                    })
+                }
+            };
+            Ok(match (kind, e.scalar().width) {
+                (ScalarKind::Float, 4) => Literal::F32(f32::from_bits(bits as u32)),
+                (ScalarKind::Uint, 4) => Literal::U32(bits as u32),
+                (ScalarKind::Sint, 4) => Literal::I32(bits as u32 as i32),
+                (ScalarKind::Float, 8) => Literal::F64(f64::from_bits(bits)),
+                (ScalarKind::Sint, 8) => Literal::I64(bits as i64),
+                _ => {
+                    return Err(ConstantEvaluatorError::InvalidCastArg {
+                        from: format!("{e:?}"),
+                        to: format!("{kind:?}"),
+                    })
+                }
+            })
+        })
+    }
+
+    /// Evaluate `expr` to a scalar [`Literal`] or a vector of them.
+    ///
+    /// [`Literal`]: Expression::Literal
+    fn components(
+        &mut self,
+        expr: Handle<Expression>,
+        span: Span,
+    ) -> Result<Components, ConstantEvaluatorError> {
+        let expr = self.eval_zero_value_and_splat(expr, span)?;
+        match self.expressions[expr] {
+            Expression::Literal(literal) => Ok(Components {
+                size: None,
+                literals: vec![literal],
+            }),
+            Expression::Compose { ty, ref components } => {
+                let TypeInner::Vector { size, .. } = self.types[ty].inner else {
+                    return Err(ConstantEvaluatorError::InvalidMathArg);
+                };
+                let components: Vec<_> =
+                    crate::proc::flatten_compose(ty, components, self.expressions, self.types)
+                        .collect();
+
+                let mut literals = Vec::with_capacity(components.len());
+                for component in components {
+                    let component = self.check_and_get(component)?;
+                    let component = self.eval_zero_value(component, span)?;
+                    match self.expressions[component] {
+                        Expression::Literal(literal) => literals.push(literal),
+                        _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+                    }
+                }
+                Ok(Components {
+                    size: Some(size),
+                    literals,
+                })
+            }
+            _ => Err(ConstantEvaluatorError::InvalidMathArg),
+        }
+    }
+
+    /// Register `literals` as a scalar, or as a vector of the given `size`.
+    fn components_to_expr(
+        &mut self,
+        size: Option<crate::VectorSize>,
+        literals: Vec<Literal>,
+        span: Span,
+    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        let scalar = literals[0].scalar();
+        let mut components = Vec::with_capacity(literals.len());
+        for literal in literals {
+            components.push(self.register_evaluated_expr(Expression::Literal(literal), span)?);
+        }
+
+        match size {
+            None => Ok(components[0]),
+            Some(size) => {
+                let ty = self.types.insert(
+                    Type {
+                        name: None,
+                        inner: TypeInner::Vector { size, scalar },
+                    },
+                    span,
+                );
+                self.register_evaluated_expr(Expression::Compose { ty, components }, span)
+            }
+        }
+    }
+
+    /// Register `values` with the same shape and float type as `like`.
+    fn float_components_to_expr(
+        &mut self,
+        like: &Components,
+        values: impl IntoIterator<Item = f64>,
+        span: Span,
+    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        let literals = values
+            .into_iter()
+            .map(|value| float_literal(like.literals[0], value))
+            .collect::<Result<_, _>>()?;
+        self.components_to_expr(like.size, literals, span)
+    }
+
+    /// Apply `f` to corresponding components of `args`.
+    ///
+    /// Scalar arguments are broadcast across the components of vector
+    /// arguments, as for WGSL's `mix`, `select` and `extractBits`.
+    fn component_wise<const N: usize>(
+        &mut self,
+        args: [Handle<Expression>; N],
+        span: Span,
+        mut f: impl FnMut([Literal; N]) -> Result<Literal, ConstantEvaluatorError>,
+    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        let mut components = Vec::with_capacity(N);
+        for arg in args {
+            components.push(self.components(arg, span)?);
+        }
+
+        let mut size = None;
+        for e in &components {
+            match (size, e.size) {
+                (_, None) => {}
+                (None, Some(_)) => size = e.size,
+                (Some(a), Some(b)) if a == b => {}
+                _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+            }
+        }
+
+        let count = size.map_or(1, |size| size as usize);
+        let literals = (0..count)
+            .map(|i| {
+                f(std::array::from_fn(|arg| {
+                    let e = &components[arg];
+                    e.literals[if e.size.is_some() { i } else { 0 }]
+                }))
+            })
+            .collect::<Result<_, _>>()?;
+        self.components_to_expr(size, literals, span)
+    }
+
+    /// Apply `f` to corresponding components of the float arguments `args`.
+    fn float_component_wise<const N: usize>(
+        &mut self,
+        args: [Handle<Expression>; N],
+        span: Span,
+        f: impl Fn([f64; N]) -> f64,
+    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        self.component_wise(args, span, |literals| float_op(literals, &f))
+    }
+
+    /// Evaluate the float matrix `expr`, returning its columns.
+    ///
+    /// The [`Literal`] returned alongside indicates the matrix's scalar type.
+    ///
+    /// [`Literal`]: crate::Literal
+    fn matrix_columns(
+        &mut self,
+        expr: Handle<Expression>,
+        span: Span,
+    ) -> Result<(Literal, Vec<Vec<f64>>), ConstantEvaluatorError> {
+        let expr = self.eval_zero_value(expr, span)?;
+        let Expression::Compose { ty, ref components } = self.expressions[expr] else {
+            return Err(ConstantEvaluatorError::InvalidMathArg);
+        };
+        let TypeInner::Matrix { .. } = self.types[ty].inner else {
+            return Err(ConstantEvaluatorError::InvalidMathArg);
+        };
+
+        let mut like = None;
+        let mut columns = Vec::with_capacity(components.len());
+        for column in components.clone() {
+            let column = self.components(column, span)?;
+            like = Some(column.literals[0]);
+            columns.push(column.floats()?);
+        }
+        let like = like.ok_or(ConstantEvaluatorError::InvalidMathArg)?;
+        Ok((like, columns))
+    }
+
+    /// Register a matrix whose columns are given by `columns`.
+    fn matrix_from_columns(
+        &mut self,
+        columns: Vec<Vec<Literal>>,
+        span: Span,
+    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        let scalar = columns[0][0].scalar();
+        let rows = vector_size(columns[0].len())?;
+        let column_count = vector_size(columns.len())?;
+
+        let mut components = Vec::with_capacity(columns.len());
+        for column in columns {
+            components.push(self.components_to_expr(Some(rows), column, span)?);
+        }
+        let ty = self.types.insert(
+            Type {
+                name: None,
+                inner: TypeInner::Matrix {
+                    columns: column_count,
+                    rows,
+                    scalar,
+                },
+            },
+            span,
+        );
+        self.register_evaluated_expr(Expression::Compose { ty, components }, span)
+    }
+
+    fn array_length(
+        &mut self,
+        array: Handle<Expression>,
+        span: Span,
+    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        match self.expressions[array] {
+            Expression::ZeroValue(ty) | Expression::Compose { ty, .. } => {
+                match self.types[ty].inner {
+                    TypeInner::Array { size, .. } => match size {
+                        crate::ArraySize::Constant(len) => {
+                            let expr = Expression::Literal(Literal::U32(len.get()));
+                            self.register_evaluated_expr(expr, span)
+                        }
+                        crate::ArraySize::Dynamic => {
+                            Err(ConstantEvaluatorError::ArrayLengthDynamic)
+                        }
+                    },
+                    _ => Err(ConstantEvaluatorError::InvalidArrayLengthArg),
+                }
+            }
+            _ => Err(ConstantEvaluatorError::InvalidArrayLengthArg),
+        }
+    }
+
+    fn access(
+        &mut self,
+        base: Handle<Expression>,
+        index: usize,
+        span: Span,
+    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        match self.expressions[base] {
+            Expression::ZeroValue(ty) => {
+                let ty_inner = &self.types[ty].inner;
+                let components = ty_inner
+                    .components()
+                    .ok_or(ConstantEvaluatorError::InvalidAccessBase)?;
+
+                if index >= components as usize {
+                    Err(ConstantEvaluatorError::InvalidAccessBase)
+                } else {
+                    let ty_res = ty_inner
+                        .component_type(index)
+                        .ok_or(ConstantEvaluatorError::InvalidAccessIndex)?;
+                    let ty = match ty_res {
+                        crate::proc::TypeResolution::Handle(ty) => ty,
+                        crate::proc::TypeResolution::Value(inner) => {
+                            self.types.insert(Type { name: None, inner }, span)
+                        }
+                    };
+                    self.register_evaluated_expr(Expression::ZeroValue(ty), span)
+                }
+            }
+            Expression::Splat { size, value } => {
+                if index >= size as usize {
+                    Err(ConstantEvaluatorError::InvalidAccessBase)
+                } else {
+                    Ok(value)
+                }
+            }
+            Expression::Compose { ty, ref components } => {
+                let _ = self.types[ty]
+                    .inner
+                    .components()
+                    .ok_or(ConstantEvaluatorError::InvalidAccessBase)?;
+
+                crate::proc::flatten_compose(ty, components, self.expressions, self.types)
+                    .nth(index)
+                    .ok_or(ConstantEvaluatorError::InvalidAccessIndex)
+            }
+            _ => Err(ConstantEvaluatorError::InvalidAccessBase),
+        }
+    }
+
+    fn constant_index(&self, expr: Handle<Expression>) -> Result<usize, ConstantEvaluatorError> {
+        match self.expressions[expr] {
+            Expression::ZeroValue(ty)
+                if matches!(
+                    self.types[ty].inner,
+                    crate::TypeInner::Scalar(crate::Scalar {
+                        kind: ScalarKind::Uint,
+                        ..
+                    })
+                ) =>
+            {
+                Ok(0)
+            }
+            Expression::Literal(Literal::U32(index)) => Ok(index as usize),
+            _ => Err(ConstantEvaluatorError::InvalidAccessIndexTy),
+        }
+    }
+
+    /// Lower [`ZeroValue`] and [`Splat`] expressions to [`Literal`] and [`Compose`] expressions.
+    ///
+    /// [`ZeroValue`]: Expression::ZeroValue
+    /// [`Splat`]: Expression::Splat
+    /// [`Literal`]: Expression::Literal
+    /// [`Compose`]: Expression::Compose
+    fn eval_zero_value_and_splat(
+        &mut self,
+        expr: Handle<Expression>,
+        span: Span,
+    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        match self.expressions[expr] {
+            Expression::ZeroValue(ty) => self.eval_zero_value_impl(ty, span),
+            Expression::Splat { size, value } => self.splat(value, size, span),
+            _ => Ok(expr),
+        }
+    }
+
+    /// Lower [`ZeroValue`] expressions to [`Literal`] and [`Compose`] expressions.
+    ///
+    /// [`ZeroValue`]: Expression::ZeroValue
+    /// [`Literal`]: Expression::Literal
+    /// [`Compose`]: Expression::Compose
+    fn eval_zero_value(
+        &mut self,
+        expr: Handle<Expression>,
+        span: Span,
+    ) -> Result<Handle<Expression>, ConstantEvaluatorError> {
+        match self.expressions[expr] {
+            Expression::ZeroValue(ty) => self.eval_zero_value_impl(ty, span),
+            _ => Ok(expr),
+        }
+    }
+
+    /// Lower [`ZeroValue`] expressions to [`Literal`] and [`Compose`] expressions.
     ///
     /// [`ZeroValue`]: Expression::ZeroValue
     /// [`Literal`]: Expression::Literal
@@ -1148,7 +1793,12 @@ impl<'a> ConstantEvaluator<'a> {
             return self.cast(expr, target, span);
         };
 
-        let crate::TypeInner::Array { base: _, size, stride: _ } = self.types[ty].inner else {
+        let crate::TypeInner::Array {
+            base: _,
+            size,
+            stride: _,
+        } = self.types[ty].inner
+        else {
             return self.cast(expr, target, span);
         };
 
@@ -1194,11 +1844,20 @@ impl<'a> ConstantEvaluator<'a> {
 
         let expr = match self.expressions[expr] {
             Expression::Literal(value) => Expression::Literal(match op {
-                UnaryOperator::Negate => match value {
-                    Literal::I32(v) => Literal::I32(-v),
-                    Literal::F32(v) => Literal::F32(-v),
-                    _ => return Err(ConstantEvaluatorError::InvalidUnaryOpArg),
-                },
+                UnaryOperator::Negate => {
+                    let overflow = || ConstantEvaluatorError::Overflow("negation".into());
+                    match value {
+                        Literal::I32(v) => Literal::I32(v.checked_neg().ok_or_else(overflow)?),
+                        Literal::I64(v) => Literal::I64(v.checked_neg().ok_or_else(overflow)?),
+                        Literal::AbstractInt(v) => {
+                            Literal::AbstractInt(v.checked_neg().ok_or_else(overflow)?)
+                        }
+                        Literal::F32(v) => Literal::F32(-v),
+                        Literal::F64(v) => Literal::F64(-v),
+                        Literal::AbstractFloat(v) => Literal::AbstractFloat(-v),
+                        _ => return Err(ConstantEvaluatorError::InvalidUnaryOpArg),
+                    }
+                }
                 UnaryOperator::LogicalNot => match value {
                     Literal::Bool(v) => Literal::Bool(!v),
                     _ => return Err(ConstantEvaluatorError::InvalidUnaryOpArg),
@@ -1206,6 +1865,8 @@ impl<'a> ConstantEvaluator<'a> {
                 UnaryOperator::BitwiseNot => match value {
                     Literal::I32(v) => Literal::I32(!v),
                     Literal::U32(v) => Literal::U32(!v),
+                    Literal::I64(v) => Literal::I64(!v),
+                    Literal::AbstractInt(v) => Literal::AbstractInt(!v),
                     _ => return Err(ConstantEvaluatorError::InvalidUnaryOpArg),
                 },
             }),
@@ -1462,12 +2123,255 @@ impl<'a> ConstantEvaluator<'a> {
     }
 }
 
-#[cfg(test)]
-mod tests {
+/// The scalar components of an evaluated scalar or vector expression.
+struct Components {
+    /// The vector size, or `None` for a scalar.
+    size: Option<crate::VectorSize>,
+    literals: Vec<Literal>,
+}
+
+impl Components {
+    /// Return the components as `f64`s, if they are all floats of the same type.
+    fn floats(&self) -> Result<Vec<f64>, ConstantEvaluatorError> {
+        self.literals
+            .iter()
+            .map(|&literal| match (self.literals[0], literal) {
+                (Literal::F32(_), Literal::F32(v)) => Ok(f64::from(v)),
+                (Literal::F64(_), Literal::F64(v))
+                | (Literal::AbstractFloat(_), Literal::AbstractFloat(v)) => Ok(v),
+                _ => Err(ConstantEvaluatorError::InvalidMathArg),
+            })
+            .collect()
+    }
+
+    /// Return the components as `i128`s, if they are all integers of the same type.
+    fn ints(&self) -> Result<Vec<i128>, ConstantEvaluatorError> {
+        self.literals
+            .iter()
+            .map(|&literal| match (self.literals[0], literal) {
+                (Literal::I32(_), Literal::I32(v)) => Ok(i128::from(v)),
+                (Literal::U32(_), Literal::U32(v)) => Ok(i128::from(v)),
+                (Literal::I64(_), Literal::I64(v))
+                | (Literal::AbstractInt(_), Literal::AbstractInt(v)) => Ok(i128::from(v)),
+                _ => Err(ConstantEvaluatorError::InvalidMathArg),
+            })
+            .collect()
+    }
+}
+
+/// Apply `f` to the float literals `args`, which must all have the same type.
+///
+/// The computation is carried out in `f64`, and the result converted back to
+/// the arguments' type.
+fn float_op<const N: usize>(
+    args: [Literal; N],
+    f: impl FnOnce([f64; N]) -> f64,
+) -> Result<Literal, ConstantEvaluatorError> {
+    let like = args[0];
+    let mut values = [0.0; N];
+    for (value, arg) in values.iter_mut().zip(args) {
+        *value = match (like, arg) {
+            (Literal::F32(_), Literal::F32(v)) => f64::from(v),
+            (Literal::F64(_), Literal::F64(v))
+            | (Literal::AbstractFloat(_), Literal::AbstractFloat(v)) => v,
+            _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+        };
+    }
+    float_literal(like, f(values))
+}
+
+/// Build a float literal holding `value`, of the same type as `like`.
+const fn float_literal(like: Literal, value: f64) -> Result<Literal, ConstantEvaluatorError> {
+    match like {
+        Literal::F32(_) => Ok(Literal::F32(value as f32)),
+        Literal::F64(_) => Ok(Literal::F64(value)),
+        Literal::AbstractFloat(_) => Ok(Literal::AbstractFloat(value)),
+        _ => Err(ConstantEvaluatorError::InvalidMathArg),
+    }
+}
+
+/// Build an integer literal holding `value`, of the same type as `like`.
+///
+/// Report an overflow of the `op` operation if `value` is out of range.
+fn int_literal(like: Literal, value: i128, op: &str) -> Result<Literal, ConstantEvaluatorError> {
+    let overflow = |_| ConstantEvaluatorError::Overflow(op.to_string());
+    match like {
+        Literal::I32(_) => Ok(Literal::I32(value.try_into().map_err(overflow)?)),
+        Literal::U32(_) => Ok(Literal::U32(value.try_into().map_err(overflow)?)),
+        Literal::I64(_) => Ok(Literal::I64(value.try_into().map_err(overflow)?)),
+        Literal::AbstractInt(_) => Ok(Literal::AbstractInt(value.try_into().map_err(overflow)?)),
+        _ => Err(ConstantEvaluatorError::InvalidMathArg),
+    }
+}
+
+/// Check that `args` are all numeric literals of the same type.
+fn check_numeric<const N: usize>(args: [Literal; N]) -> Result<(), ConstantEvaluatorError> {
+    let like = std::mem::discriminant(&args[0]);
+    if args
+        .iter()
+        .any(|arg| matches!(*arg, Literal::Bool(_)) || std::mem::discriminant(arg) != like)
+    {
+        return Err(ConstantEvaluatorError::InvalidMathArg);
+    }
+    Ok(())
+}
+
+const fn vector_size(len: usize) -> Result<crate::VectorSize, ConstantEvaluatorError> {
+    match len {
+        2 => Ok(crate::VectorSize::Bi),
+        3 => Ok(crate::VectorSize::Tri),
+        4 => Ok(crate::VectorSize::Quad),
+        _ => Err(ConstantEvaluatorError::InvalidMathArg),
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Round `value` to the nearest integer, with ties going to the even one.
+pub(crate) fn round_ties_even(value: f64) -> f64 {
+    if (value - value.trunc()).abs() == 0.5 {
+        2.0 * (value / 2.0).round()
+    } else {
+        value.round()
+    }
+}
+
+/// Compute `value * 2^exponent` without overflowing intermediate results.
+pub(crate) fn ldexp(value: f64, exponent: i64) -> f64 {
+    // Beyond this range every finite `f64` overflows or underflows anyway.
+    let mut exponent = exponent.clamp(-3000, 3000) as i32;
+    let mut result = value;
+    while exponent != 0 {
+        let step = exponent.clamp(-1000, 1000);
+        result *= 2.0f64.powi(step);
+        exponent -= step;
+    }
+    result
+}
+
+/// Split `value` into a fraction with magnitude in `[0.5, 1)` and a power of two.
+pub(crate) fn frexp(value: f64) -> (f64, i32) {
+    if value == 0.0 || !value.is_finite() {
+        return (value, 0);
+    }
+
+    let bits = value.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i32;
+    if exponent == 0 {
+        // Subnormal: scale into the normal range first.
+        let (fract, exponent) = frexp(value * 2.0f64.powi(64));
+        return (fract, exponent - 64);
+    }
+    let fract = f64::from_bits((bits & !(0x7ff << 52)) | (1022 << 52));
+    (fract, exponent - 1022)
+}
+
+/// Compute the determinant of the square matrix whose columns are `columns`.
+pub(crate) fn determinant(columns: &[Vec<f64>]) -> f64 {
+    if columns.len() == 1 {
+        return columns[0][0];
+    }
+
+    // Laplace expansion along the first row.
+    (0..columns.len())
+        .map(|skip| {
+            let minor: Vec<Vec<f64>> = columns
+                .iter()
+                .enumerate()
+                .filter(|&(column, _)| column != skip)
+                .map(|(_, column)| column[1..].to_vec())
+                .collect();
+            let sign = if skip % 2 == 0 { 1.0 } else { -1.0 };
+            sign * columns[skip][0] * determinant(&minor)
+        })
+        .sum()
+}
+
+/// Invert the square matrix whose columns are `columns`.
+///
+/// Return `None` if the matrix is not square or is singular.
+pub(crate) fn inverse(columns: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    // Since the inverse of the transpose is the transpose of the inverse, we
+    // can run Gauss-Jordan elimination on the columns as if they were rows.
+    let n = columns.len();
+    if columns.iter().any(|column| column.len() != n) {
+        return None;
+    }
+
+    let mut m = columns.to_vec();
+    let mut inv: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+    for i in 0..n {
+        let pivot = (i..n).max_by(|&a, &b| m[a][i].abs().total_cmp(&m[b][i].abs()))?;
+        if m[pivot][i] == 0.0 {
+            return None;
+        }
+        m.swap(i, pivot);
+        inv.swap(i, pivot);
+
+        let scale = m[i][i];
+        for j in 0..n {
+            m[i][j] /= scale;
+            inv[i][j] /= scale;
+        }
+        for k in (0..n).filter(|&k| k != i) {
+            let factor = m[k][i];
+            for j in 0..n {
+                m[k][j] -= factor * m[i][j];
+                inv[k][j] -= factor * inv[i][j];
+            }
+        }
+    }
+    Some(inv)
+}
+
+/// Convert `value` to IEEE binary16 bits, rounding to nearest even.
+///
+/// Return `None` if `value` is out of range for `f16`.
+pub(crate) fn f32_to_f16_bits(value: f32) -> Option<u16> {
+    let sign = ((value.to_bits() >> 16) & 0x8000) as u16;
+    let magnitude = f64::from(value.abs());
+    if magnitude == 0.0 {
+        return Some(sign);
+    }
+
+    let exponent = frexp(magnitude).1 - 1;
+    let bits = if exponent < -14 {
+        // Subnormal, in units of 2^-24.
+        round_ties_even(magnitude * 2.0f64.powi(24)) as u32
+    } else {
+        // A mantissa that rounds up to 1024 carries into the exponent.
+        let mantissa = round_ties_even((magnitude / 2.0f64.powi(exponent) - 1.0) * 1024.0) as u32;
+        (((exponent + 15) as u32) << 10) + mantissa
+    };
+    if bits >= 0x7c00 {
+        return None;
+    }
+    Some(sign | bits as u16)
+}
+
+/// Convert the IEEE binary16 `bits` to `f32`.
+pub(crate) fn f16_bits_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let mantissa = f32::from(bits & 0x3ff);
+    match exponent {
+        0 => sign * mantissa * 2.0f32.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2.0f32.powi(exponent - 15),
+    }
+}
+
+#[cfg(test)]
+mod tests {
     use std::vec;
 
     use crate::{
-        Arena, Constant, Expression, Literal, ScalarKind, Type, TypeInner, UnaryOperator,
+        Arena, Constant, Expression, Handle, Literal, ScalarKind, Type, TypeInner, UnaryOperator,
         UniqueArena, VectorSize,
     };
 
@@ -1968,6 +2872,544 @@ mod tests {
             panic!("unexpected evaluation result")
         }
     }
+
+    #[test]
+    fn math() {
+        let mut types = UniqueArena::new();
+        let constants = Arena::new();
+        let mut const_expressions = Arena::new();
+
+        let vec3_f32_ty = types.insert(
+            Type {
+                name: None,
+                inner: TypeInner::Vector {
+                    size: VectorSize::Tri,
+                    scalar: crate::Scalar::F32,
+                },
+            },
+            Default::default(),
+        );
+
+        let two = const_expressions.append(
+            Expression::Literal(Literal::AbstractFloat(2.0)),
+            Default::default(),
+        );
+        let ints = [7, -3].map(|value| {
+            const_expressions.append(Expression::Literal(Literal::I32(value)), Default::default())
+        });
+        let components = [3.0, 0.0, 4.0].map(|value| {
+            const_expressions.append(Expression::Literal(Literal::F32(value)), Default::default())
+        });
+        let vector = const_expressions.append(
+            Expression::Compose {
+                ty: vec3_f32_ty,
+                components: components.to_vec(),
+            },
+            Default::default(),
+        );
+
+        let mut solver = ConstantEvaluator {
+            behavior: Behavior::Wgsl,
+            types: &mut types,
+            constants: &constants,
+            expressions: &mut const_expressions,
+            function_local_data: None,
+        };
+
+        let math = |fun, arg, arg1| Expression::Math {
+            fun,
+            arg,
+            arg1,
+            arg2: None,
+            arg3: None,
+        };
+        let sqrt = solver
+            .try_eval_and_append(
+                &math(crate::MathFunction::Sqrt, two, None),
+                Default::default(),
+            )
+            .unwrap();
+        let max = solver
+            .try_eval_and_append(
+                &math(crate::MathFunction::Max, ints[0], Some(ints[1])),
+                Default::default(),
+            )
+            .unwrap();
+        let abs = solver
+            .try_eval_and_append(
+                &math(crate::MathFunction::Abs, ints[1], None),
+                Default::default(),
+            )
+            .unwrap();
+        let length = solver
+            .try_eval_and_append(
+                &math(crate::MathFunction::Length, vector, None),
+                Default::default(),
+            )
+            .unwrap();
+        let normalize = solver
+            .try_eval_and_append(
+                &math(crate::MathFunction::Normalize, vector, None),
+                Default::default(),
+            )
+            .unwrap();
+        let bad_count = solver.try_eval_and_append(
+            &math(crate::MathFunction::Max, ints[0], None),
+            Default::default(),
+        );
+
+        // `Literal`'s `PartialEq` impl never considers abstract values equal.
+        assert!(matches!(
+            const_expressions[sqrt],
+            Expression::Literal(Literal::AbstractFloat(value)) if value == 2.0f64.sqrt()
+        ));
+        assert_eq!(const_expressions[max], Expression::Literal(Literal::I32(7)));
+        assert_eq!(const_expressions[abs], Expression::Literal(Literal::I32(3)));
+        assert_eq!(
+            const_expressions[length],
+            Expression::Literal(Literal::F32(5.0))
+        );
+        match const_expressions[normalize] {
+            Expression::Compose { ty, ref components } => {
+                assert_eq!(ty, vec3_f32_ty);
+                let components: Vec<_> = components
+                    .iter()
+                    .map(|&component| const_expressions[component].clone())
+                    .collect();
+                assert_eq!(
+                    components,
+                    [0.6, 0.0, 0.8].map(|value| Expression::Literal(Literal::F32(value)))
+                );
+            }
+            _ => panic!("Expected vector"),
+        }
+        assert!(bad_count.is_err());
+    }
+
+    #[test]
+    fn select_and_relational() {
+        let mut types = UniqueArena::new();
+        let constants = Arena::new();
+        let mut const_expressions = Arena::new();
+
+        let vec2_bool_ty = types.insert(
+            Type {
+                name: None,
+                inner: TypeInner::Vector {
+                    size: VectorSize::Bi,
+                    scalar: crate::Scalar::BOOL,
+                },
+            },
+            Default::default(),
+        );
+
+        let reject =
+            const_expressions.append(Expression::Literal(Literal::U32(1)), Default::default());
+        let accept =
+            const_expressions.append(Expression::Literal(Literal::U32(2)), Default::default());
+        let components = [true, false].map(|value| {
+            const_expressions.append(
+                Expression::Literal(Literal::Bool(value)),
+                Default::default(),
+            )
+        });
+        let condition = components[0];
+        let bools = const_expressions.append(
+            Expression::Compose {
+                ty: vec2_bool_ty,
+                components: components.to_vec(),
+            },
+            Default::default(),
+        );
+
+        let mut solver = ConstantEvaluator {
+            behavior: Behavior::Wgsl,
+            types: &mut types,
+            constants: &constants,
+            expressions: &mut const_expressions,
+            function_local_data: None,
+        };
+
+        let select = solver
+            .try_eval_and_append(
+                &Expression::Select {
+                    condition,
+                    accept,
+                    reject,
+                },
+                Default::default(),
+            )
+            .unwrap();
+        let all = solver
+            .try_eval_and_append(
+                &Expression::Relational {
+                    fun: crate::RelationalFunction::All,
+                    argument: bools,
+                },
+                Default::default(),
+            )
+            .unwrap();
+        let any = solver
+            .try_eval_and_append(
+                &Expression::Relational {
+                    fun: crate::RelationalFunction::Any,
+                    argument: bools,
+                },
+                Default::default(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            const_expressions[select],
+            Expression::Literal(Literal::U32(2))
+        );
+        assert_eq!(
+            const_expressions[all],
+            Expression::Literal(Literal::Bool(false))
+        );
+        assert_eq!(
+            const_expressions[any],
+            Expression::Literal(Literal::Bool(true))
+        );
+    }
+
+    #[test]
+    fn bitcast() {
+        let mut types = UniqueArena::new();
+        let constants = Arena::new();
+        let mut const_expressions = Arena::new();
+
+        let float =
+            const_expressions.append(Expression::Literal(Literal::F32(1.0)), Default::default());
+        let abstract_int = const_expressions.append(
+            Expression::Literal(Literal::AbstractInt(1)),
+            Default::default(),
+        );
+
+        let mut solver = ConstantEvaluator {
+            behavior: Behavior::Wgsl,
+            types: &mut types,
+            constants: &constants,
+            expressions: &mut const_expressions,
+            function_local_data: None,
+        };
+
+        let bitcast = solver
+            .try_eval_and_append(
+                &Expression::As {
+                    expr: float,
+                    kind: ScalarKind::Uint,
+                    convert: None,
+                },
+                Default::default(),
+            )
+            .unwrap();
+        let bad_bitcast = solver.try_eval_and_append(
+            &Expression::As {
+                expr: abstract_int,
+                kind: ScalarKind::Uint,
+                convert: None,
+            },
+            Default::default(),
+        );
+
+        assert_eq!(
+            const_expressions[bitcast],
+            Expression::Literal(Literal::U32(0x3f80_0000))
+        );
+        assert!(bad_bitcast.is_err());
+    }
+
+    /// The literals making up the constant expression `handle`, flattening
+    /// composites.
+    fn literals(expressions: &Arena<Expression>, handle: Handle<Expression>) -> Vec<Literal> {
+        match expressions[handle] {
+            Expression::Literal(literal) => vec![literal],
+            Expression::Compose { ref components, .. } => components
+                .iter()
+                .flat_map(|&component| literals(expressions, component))
+                .collect(),
+            ref other => panic!("Expected a literal or composite, found {other:?}"),
+        }
+    }
+
+    fn math_call(fun: crate::MathFunction, args: &[Handle<Expression>]) -> Expression {
+        Expression::Math {
+            fun,
+            arg: args[0],
+            arg1: args.get(1).copied(),
+            arg2: args.get(2).copied(),
+            arg3: args.get(3).copied(),
+        }
+    }
+
+    #[test]
+    fn modf_and_frexp() {
+        let mut module = crate::Module::default();
+        for width in [4, 8] {
+            module.generate_predeclared_type(crate::PredeclaredType::ModfResult {
+                size: None,
+                width,
+            });
+            module.generate_predeclared_type(crate::PredeclaredType::FrexpResult {
+                size: None,
+                width,
+            });
+        }
+        let mut const_expressions = Arena::new();
+
+        let value =
+            const_expressions.append(Expression::Literal(Literal::F32(-2.5)), Default::default());
+        let abstract_value = const_expressions.append(
+            Expression::Literal(Literal::AbstractFloat(12.0)),
+            Default::default(),
+        );
+
+        let mut solver = ConstantEvaluator {
+            behavior: Behavior::Wgsl,
+            types: &mut module.types,
+            constants: &module.constants,
+            expressions: &mut const_expressions,
+            function_local_data: None,
+        };
+
+        let modf = solver
+            .try_eval_and_append(
+                &math_call(crate::MathFunction::Modf, &[value]),
+                Default::default(),
+            )
+            .unwrap();
+        let frexp = solver
+            .try_eval_and_append(
+                &math_call(crate::MathFunction::Frexp, &[value]),
+                Default::default(),
+            )
+            .unwrap();
+        let abstract_frexp = solver
+            .try_eval_and_append(
+                &math_call(crate::MathFunction::Frexp, &[abstract_value]),
+                Default::default(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            literals(&const_expressions, modf),
+            [Literal::F32(-0.5), Literal::F32(-2.0)]
+        );
+        assert_eq!(
+            literals(&const_expressions, frexp),
+            [Literal::F32(-0.625), Literal::I32(2)]
+        );
+        // Abstract arguments produce the `f64` result.
+        assert_eq!(
+            literals(&const_expressions, abstract_frexp),
+            [Literal::F64(0.75), Literal::I64(4)]
+        );
+    }
+
+    #[test]
+    fn pack_and_unpack() {
+        let mut types = UniqueArena::new();
+        let constants = Arena::new();
+        let mut const_expressions = Arena::new();
+
+        let vec4_f32_ty = types.insert(
+            Type {
+                name: None,
+                inner: TypeInner::Vector {
+                    size: VectorSize::Quad,
+                    scalar: crate::Scalar::F32,
+                },
+            },
+            Default::default(),
+        );
+        let components = [0.0, 1.0, 0.5, 2.0].map(|value| {
+            const_expressions.append(Expression::Literal(Literal::F32(value)), Default::default())
+        });
+        let vector = const_expressions.append(
+            Expression::Compose {
+                ty: vec4_f32_ty,
+                components: components.to_vec(),
+            },
+            Default::default(),
+        );
+        let packed = const_expressions.append(
+            Expression::Literal(Literal::U32(0xc000_3c00)),
+            Default::default(),
+        );
+
+        let mut solver = ConstantEvaluator {
+            behavior: Behavior::Wgsl,
+            types: &mut types,
+            constants: &constants,
+            expressions: &mut const_expressions,
+            function_local_data: None,
+        };
+
+        let pack = solver
+            .try_eval_and_append(
+                &math_call(crate::MathFunction::Pack4x8unorm, &[vector]),
+                Default::default(),
+            )
+            .unwrap();
+        let unpack = solver
+            .try_eval_and_append(
+                &math_call(crate::MathFunction::Unpack2x16float, &[packed]),
+                Default::default(),
+            )
+            .unwrap();
+
+        // The last component is clamped to 1.0.
+        assert_eq!(
+            const_expressions[pack],
+            Expression::Literal(Literal::U32(0xff80_ff00))
+        );
+        assert_eq!(
+            literals(&const_expressions, unpack),
+            [Literal::F32(1.0), Literal::F32(-2.0)]
+        );
+    }
+
+    #[test]
+    fn bits() {
+        let mut types = UniqueArena::new();
+        let constants = Arena::new();
+        let mut const_expressions = Arena::new();
+
+        let [value, newbits, offset, count] = [0xf0u32, 0b101, 4, 3].map(|value| {
+            const_expressions.append(Expression::Literal(Literal::U32(value)), Default::default())
+        });
+        let negative =
+            const_expressions.append(Expression::Literal(Literal::I32(-8)), Default::default());
+
+        let mut solver = ConstantEvaluator {
+            behavior: Behavior::Wgsl,
+            types: &mut types,
+            constants: &constants,
+            expressions: &mut const_expressions,
+            function_local_data: None,
+        };
+
+        let mut eval = |fun, args: &[_]| {
+            solver
+                .try_eval_and_append(&math_call(fun, args), Default::default())
+                .unwrap()
+        };
+        let count_one_bits = eval(crate::MathFunction::CountOneBits, &[value]);
+        let reverse_bits = eval(crate::MathFunction::ReverseBits, &[value]);
+        let extract_bits = eval(crate::MathFunction::ExtractBits, &[value, offset, count]);
+        let signed_extract_bits =
+            eval(crate::MathFunction::ExtractBits, &[negative, offset, count]);
+        let insert_bits = eval(
+            crate::MathFunction::InsertBits,
+            &[value, newbits, offset, count],
+        );
+        let find_msb = eval(crate::MathFunction::FindMsb, &[negative]);
+
+        let expected = [
+            (count_one_bits, Literal::U32(4)),
+            (reverse_bits, Literal::U32(0x0f00_0000)),
+            (extract_bits, Literal::U32(0b111)),
+            (signed_extract_bits, Literal::I32(-1)),
+            (insert_bits, Literal::U32(0xd0)),
+            (find_msb, Literal::I32(2)),
+        ];
+        for (handle, literal) in expected {
+            assert_eq!(const_expressions[handle], Expression::Literal(literal));
+        }
+    }
+
+    #[test]
+    fn matrix_functions() {
+        let mut types = UniqueArena::new();
+        let constants = Arena::new();
+        let mut const_expressions = Arena::new();
+
+        let vec2_f32_ty = types.insert(
+            Type {
+                name: None,
+                inner: TypeInner::Vector {
+                    size: VectorSize::Bi,
+                    scalar: crate::Scalar::F32,
+                },
+            },
+            Default::default(),
+        );
+        let mat2x2_f32_ty = types.insert(
+            Type {
+                name: None,
+                inner: TypeInner::Matrix {
+                    columns: VectorSize::Bi,
+                    rows: VectorSize::Bi,
+                    scalar: crate::Scalar::F32,
+                },
+            },
+            Default::default(),
+        );
+        let columns = [[1.0, 2.0], [3.0, 4.0]].map(|column| {
+            let components = column
+                .map(|value| {
+                    const_expressions
+                        .append(Expression::Literal(Literal::F32(value)), Default::default())
+                })
+                .to_vec();
+            const_expressions.append(
+                Expression::Compose {
+                    ty: vec2_f32_ty,
+                    components,
+                },
+                Default::default(),
+            )
+        });
+        let matrix = const_expressions.append(
+            Expression::Compose {
+                ty: mat2x2_f32_ty,
+                components: columns.to_vec(),
+            },
+            Default::default(),
+        );
+
+        let mut solver = ConstantEvaluator {
+            behavior: Behavior::Wgsl,
+            types: &mut types,
+            constants: &constants,
+            expressions: &mut const_expressions,
+            function_local_data: None,
+        };
+
+        let transpose = solver
+            .try_eval_and_append(
+                &math_call(crate::MathFunction::Transpose, &[matrix]),
+                Default::default(),
+            )
+            .unwrap();
+        let determinant = solver
+            .try_eval_and_append(
+                &math_call(crate::MathFunction::Determinant, &[matrix]),
+                Default::default(),
+            )
+            .unwrap();
+        let outer = solver
+            .try_eval_and_append(
+                &math_call(crate::MathFunction::Outer, &[columns[0], columns[1]]),
+                Default::default(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            literals(&const_expressions, transpose),
+            [1.0, 3.0, 2.0, 4.0].map(Literal::F32)
+        );
+        assert_eq!(
+            const_expressions[determinant],
+            Expression::Literal(Literal::F32(-2.0))
+        );
+        // Column `i` of `outer(a, b)` is `a * b[i]`.
+        assert_eq!(
+            literals(&const_expressions, outer),
+            [3.0, 6.0, 4.0, 8.0].map(Literal::F32)
+        );
+    }
 }
 
 /// Trait for conversions of abstract values to concrete types.
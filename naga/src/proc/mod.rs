@@ -7,9 +7,15 @@ mod emitter;
 pub mod index;
 mod layouter;
 mod namer;
+#[cfg(any(feature = "link", feature = "opt"))]
+mod operands;
 mod terminator;
 mod typifier;
 
+#[cfg(feature = "interp")]
+pub(crate) use constant_evaluator::{
+    determinant, f16_bits_to_f32, f32_to_f16_bits, frexp, inverse, ldexp, round_ties_even,
+};
 pub use constant_evaluator::{
     ConstantEvaluator, ConstantEvaluatorError, ExpressionConstnessTracker,
 };
@@ -17,6 +23,10 @@ pub use emitter::Emitter;
 pub use index::{BoundsCheckPolicies, BoundsCheckPolicy, IndexableLength, IndexableLengthError};
 pub use layouter::{Alignment, LayoutError, LayoutErrorInner, Layouter, TypeLayout};
 pub use namer::{EntryPointIndex, NameKey, Namer};
+#[cfg(any(feature = "link", feature = "opt"))]
+pub(crate) use operands::expression_operands;
+#[cfg(feature = "opt")]
+pub(crate) use operands::statement_operands;
 pub use terminator::ensure_block_returns;
 pub use typifier::{ResolveContext, ResolveError, TypeResolution};
 
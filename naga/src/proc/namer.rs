@@ -28,6 +28,10 @@ pub struct Namer {
     keywords: FastHashSet<&'static str>,
     keywords_case_insensitive: FastHashSet<AsciiUniCase<&'static str>>,
     reserved_prefixes: Vec<&'static str>,
+    /// If set, ignore labels and hand out the shortest identifiers instead.
+    minify: bool,
+    /// The index of the next short identifier to try, when minifying.
+    next_short: usize,
 }
 
 impl Namer {
@@ -91,9 +95,16 @@ impl Namer {
     ///
     /// Guarantee uniqueness by applying a numeric suffix when necessary. If `label_raw`
     /// itself ends with digits, separate them from the suffix with an underscore.
+    ///
+    /// When [minifying](Namer::set_minify), `label_raw` is ignored, and the
+    /// result is the shortest identifier meeting the above.
     pub fn call(&mut self, label_raw: &str) -> String {
         use std::fmt::Write as _; // for write!-ing to Strings
 
+        if self.minify {
+            return self.call_short();
+        }
+
         let base = self.sanitize(label_raw);
         debug_assert!(!base.is_empty() && !base.ends_with(SEPARATOR));
 
@@ -129,6 +140,49 @@ impl Namer {
         }
     }
 
+    /// Return the shortest identifier that is not a keyword and that this
+    /// `Namer` has not returned before.
+    fn call_short(&mut self) -> String {
+        const FIRST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        const REST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+        loop {
+            // Spell `next_short` in bijective numeration: a letter, followed
+            // by as many letters or digits as it takes.
+            let mut index = self.next_short;
+            self.next_short += 1;
+            let mut name = String::new();
+            name.push(FIRST[index % FIRST.len()] as char);
+            index /= FIRST.len();
+            while index > 0 {
+                index -= 1;
+                name.push(REST[index % REST.len()] as char);
+                index /= REST.len();
+            }
+
+            if self.keywords.contains(name.as_str())
+                || self
+                    .keywords_case_insensitive
+                    .contains(&AsciiUniCase(name.as_str()))
+                || self.unique.contains_key(&name)
+            {
+                continue;
+            }
+            self.unique.insert(name.clone(), 0);
+            return name;
+        }
+    }
+
+    /// Choose whether [`call`](Namer::call) should make up the shortest
+    /// names it can, rather than follow the labels it is given.
+    ///
+    /// Even when minifying, [`reset`](Namer::reset) keeps the names of
+    /// entry points and overridable constants, since pipelines refer to
+    /// those by name.
+    pub fn set_minify(&mut self, minify: bool) {
+        self.minify = minify;
+    }
+
     pub fn call_or(&mut self, label: &Option<String>, fallback: &str) -> String {
         self.call(match *label {
             Some(ref name) => name,
@@ -144,8 +198,10 @@ impl Namer {
     fn namespace(&mut self, capacity: usize, body: impl FnOnce(&mut Self)) {
         let fresh = FastHashMap::with_capacity_and_hasher(capacity, Default::default());
         let outer = std::mem::replace(&mut self.unique, fresh);
+        let outer_short = std::mem::replace(&mut self.next_short, 0);
         body(self);
         self.unique = outer;
+        self.next_short = outer_short;
     }
 
     pub fn reset(
@@ -161,6 +217,7 @@ impl Namer {
         self.reserved_prefixes.extend(reserved_prefixes.iter());
 
         self.unique.clear();
+        self.next_short = 0;
         self.keywords.clear();
         self.keywords.extend(reserved_keywords.iter());
         self.keywords.extend(extra_reserved_keywords.iter());
@@ -177,24 +234,56 @@ impl Namer {
 
         let mut temp = String::new();
 
+        // Pipelines refer to entry points and overridable constants by name,
+        // so take their names before making any up.
+        if self.minify {
+            self.minify = false;
+            for (ep_index, ep) in module.entry_points.iter().enumerate() {
+                let ep_name = self.call(&ep.name);
+                output.insert(NameKey::EntryPoint(ep_index as _), ep_name);
+            }
+            for (handle, constant) in module.constants.iter() {
+                if let crate::Override::None = constant.r#override {
+                    continue;
+                }
+                if let Some(ref name) = constant.name {
+                    let name = self.call(name);
+                    output.insert(NameKey::Constant(handle), name);
+                }
+            }
+            self.minify = true;
+        }
+
         for (ty_handle, ty) in module.types.iter() {
             let ty_name = self.call_or(&ty.name, "type");
             output.insert(NameKey::Type(ty_handle), ty_name);
 
             if let crate::TypeInner::Struct { ref members, .. } = ty.inner {
+                // The members of predeclared types, like the result of
+                // `modf`, are spelled out by the language.
+                let predeclared = module
+                    .special_types
+                    .predeclared_types
+                    .values()
+                    .any(|&t| t == ty_handle);
+                let minify = self.minify;
+                self.minify = minify && !predeclared;
                 // struct members have their own namespace, because access is always prefixed
                 self.namespace(members.len(), |namer| {
                     for (index, member) in members.iter().enumerate() {
                         let name = namer.call_or(&member.name, "member");
                         output.insert(NameKey::StructMember(ty_handle, index as u32), name);
                     }
-                })
+                });
+                self.minify = minify;
             }
         }
 
         for (ep_index, ep) in module.entry_points.iter().enumerate() {
-            let ep_name = self.call(&ep.name);
-            output.insert(NameKey::EntryPoint(ep_index as _), ep_name);
+            if !self.minify {
+                let ep_name = self.call(&ep.name);
+                output.insert(NameKey::EntryPoint(ep_index as _), ep_name);
+            }
             for (index, arg) in ep.function.arguments.iter().enumerate() {
                 let name = self.call_or(&arg.name, "param");
                 output.insert(
@@ -227,6 +316,9 @@ impl Namer {
         }
 
         for (handle, constant) in module.constants.iter() {
+            if output.contains_key(&NameKey::Constant(handle)) {
+                continue;
+            }
             let label = match constant.name {
                 Some(ref name) => name,
                 None => {
@@ -279,3 +371,19 @@ fn test() {
     assert_eq!(namer.call("__x"), "_x");
     assert_eq!(namer.call("1___x"), "_x_1");
 }
+
+#[test]
+fn test_minify() {
+    let mut namer = Namer::default();
+    namer.set_minify(true);
+    namer.keywords.insert("b");
+    assert_eq!(namer.call("x"), "a");
+    assert_eq!(namer.call("x"), "c");
+    namer.next_short = 51;
+    assert_eq!(namer.call("x"), "Z");
+    assert_eq!(namer.call("x"), "aa");
+    assert_eq!(namer.call("x"), "ba");
+    namer.next_short = 52 * 63 - 1;
+    assert_eq!(namer.call("x"), "Z9");
+    assert_eq!(namer.call("x"), "aaa");
+}
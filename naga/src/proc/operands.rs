@@ -0,0 +1,263 @@
+//! Visiting the expression handles that expressions and statements use.
+
+use crate::{Expression, Handle};
+
+/// Call `f` on each expression handle `expression` uses as an operand.
+pub fn expression_operands(
+    expression: &mut Expression,
+    mut f: impl FnMut(&mut Handle<Expression>),
+) {
+    use crate::Expression as Ex;
+
+    match *expression {
+        Ex::Literal(_)
+        | Ex::Constant(_)
+        | Ex::ZeroValue(_)
+        | Ex::FunctionArgument(_)
+        | Ex::GlobalVariable(_)
+        | Ex::LocalVariable(_)
+        | Ex::CallResult(_)
+        | Ex::AtomicResult { .. }
+        | Ex::WorkGroupUniformLoadResult { .. }
+        | Ex::SubgroupBallotResult
+        | Ex::SubgroupOperationResult { .. }
+        | Ex::RayQueryProceedResult => {}
+        Ex::Compose {
+            ref mut components, ..
+        } => components.iter_mut().for_each(f),
+        Ex::Access {
+            ref mut base,
+            ref mut index,
+        } => {
+            f(base);
+            f(index);
+        }
+        Ex::AccessIndex { ref mut base, .. } => f(base),
+        Ex::Splat { ref mut value, .. } => f(value),
+        Ex::Swizzle { ref mut vector, .. } => f(vector),
+        Ex::Load { ref mut pointer } => f(pointer),
+        Ex::ImageSample {
+            ref mut image,
+            ref mut sampler,
+            ref mut coordinate,
+            ref mut array_index,
+            ref mut level,
+            ref mut depth_ref,
+            // `offset` refers to the constant expression arena.
+            ..
+        } => {
+            f(image);
+            f(sampler);
+            f(coordinate);
+            array_index.iter_mut().for_each(&mut f);
+            match *level {
+                crate::SampleLevel::Auto | crate::SampleLevel::Zero => {}
+                crate::SampleLevel::Exact(ref mut expr)
+                | crate::SampleLevel::Bias(ref mut expr) => f(expr),
+                crate::SampleLevel::Gradient {
+                    ref mut x,
+                    ref mut y,
+                } => {
+                    f(x);
+                    f(y);
+                }
+            }
+            depth_ref.iter_mut().for_each(f);
+        }
+        Ex::ImageLoad {
+            ref mut image,
+            ref mut coordinate,
+            ref mut array_index,
+            ref mut sample,
+            ref mut level,
+        } => {
+            f(image);
+            f(coordinate);
+            array_index.iter_mut().for_each(&mut f);
+            sample.iter_mut().for_each(&mut f);
+            level.iter_mut().for_each(f);
+        }
+        Ex::ImageQuery {
+            ref mut image,
+            ref mut query,
+        } => {
+            f(image);
+            if let crate::ImageQuery::Size {
+                level: Some(ref mut level),
+            } = *query
+            {
+                f(level);
+            }
+        }
+        Ex::Unary { ref mut expr, .. }
+        | Ex::Derivative { ref mut expr, .. }
+        | Ex::As { ref mut expr, .. }
+        | Ex::ArrayLength(ref mut expr) => f(expr),
+        Ex::Binary {
+            ref mut left,
+            ref mut right,
+            ..
+        } => {
+            f(left);
+            f(right);
+        }
+        Ex::Select {
+            ref mut condition,
+            ref mut accept,
+            ref mut reject,
+        } => {
+            f(condition);
+            f(accept);
+            f(reject);
+        }
+        Ex::Relational {
+            ref mut argument, ..
+        } => f(argument),
+        Ex::Math {
+            ref mut arg,
+            ref mut arg1,
+            ref mut arg2,
+            ref mut arg3,
+            ..
+        } => {
+            f(arg);
+            arg1.iter_mut().for_each(&mut f);
+            arg2.iter_mut().for_each(&mut f);
+            arg3.iter_mut().for_each(f);
+        }
+        Ex::RayQueryGetIntersection { ref mut query, .. } => f(query),
+    }
+}
+
+/// Call `f` on each expression handle `statement` uses, not counting those
+/// in nested blocks.
+///
+/// The handles of the expressions a statement produces, like the `result`
+/// of a `Call`, come last.
+#[cfg(feature = "opt")]
+pub fn statement_operands(
+    statement: &mut crate::Statement,
+    mut f: impl FnMut(&mut Handle<Expression>),
+) {
+    use crate::Statement as St;
+
+    match *statement {
+        St::Emit(_)
+        | St::Block(_)
+        | St::Break
+        | St::Continue
+        | St::Kill
+        | St::Barrier(_)
+        | St::Return { value: None } => {}
+        St::If {
+            ref mut condition, ..
+        } => f(condition),
+        St::Switch {
+            ref mut selector, ..
+        } => f(selector),
+        St::Loop {
+            ref mut break_if, ..
+        } => break_if.iter_mut().for_each(f),
+        St::Return {
+            value: Some(ref mut value),
+        } => f(value),
+        St::Store {
+            ref mut pointer,
+            ref mut value,
+        } => {
+            f(pointer);
+            f(value);
+        }
+        St::ImageStore {
+            ref mut image,
+            ref mut coordinate,
+            ref mut array_index,
+            ref mut value,
+        } => {
+            f(image);
+            f(coordinate);
+            array_index.iter_mut().for_each(&mut f);
+            f(value);
+        }
+        St::Atomic {
+            ref mut pointer,
+            ref mut fun,
+            ref mut value,
+            ref mut result,
+        } => {
+            f(pointer);
+            if let crate::AtomicFunction::Exchange {
+                compare: Some(ref mut compare),
+            } = *fun
+            {
+                f(compare);
+            }
+            f(value);
+            f(result);
+        }
+        St::WorkGroupUniformLoad {
+            ref mut pointer,
+            ref mut result,
+        } => {
+            f(pointer);
+            f(result);
+        }
+        St::Call {
+            ref mut arguments,
+            ref mut result,
+            ..
+        } => {
+            arguments.iter_mut().for_each(&mut f);
+            result.iter_mut().for_each(f);
+        }
+        St::RayQuery {
+            ref mut query,
+            ref mut fun,
+        } => {
+            f(query);
+            match *fun {
+                crate::RayQueryFunction::Initialize {
+                    ref mut acceleration_structure,
+                    ref mut descriptor,
+                } => {
+                    f(acceleration_structure);
+                    f(descriptor);
+                }
+                crate::RayQueryFunction::Proceed { ref mut result } => f(result),
+                crate::RayQueryFunction::Terminate => {}
+            }
+        }
+        St::SubgroupElect { ref mut result } => f(result),
+        St::SubgroupBallot {
+            ref mut result,
+            ref mut predicate,
+        } => {
+            predicate.iter_mut().for_each(&mut f);
+            f(result);
+        }
+        St::SubgroupGather {
+            ref mut mode,
+            ref mut argument,
+            ref mut result,
+        } => {
+            match *mode {
+                crate::GatherMode::BroadcastFirst => {}
+                crate::GatherMode::Broadcast(ref mut index)
+                | crate::GatherMode::Shuffle(ref mut index)
+                | crate::GatherMode::ShuffleDown(ref mut index)
+                | crate::GatherMode::ShuffleUp(ref mut index)
+                | crate::GatherMode::ShuffleXor(ref mut index) => f(index),
+            }
+            f(argument);
+            f(result);
+        }
+        St::SubgroupCollectiveOperation {
+            ref mut argument,
+            ref mut result,
+            ..
+        } => {
+            f(argument);
+            f(result);
+        }
+    }
+}
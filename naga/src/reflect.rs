@@ -0,0 +1,760 @@
+/*!
+Shader reflection.
+
+This module summarizes the externally visible interface of a validated
+[`Module`]: the resources each entry point binds, its stage inputs and
+outputs, its workgroup size and push constants, and the memory layout of
+every type those refer to.
+
+Call [`reflect`] with a module and the [`ModuleInfo`] produced by validating
+it. With the `serialize` feature enabled, the result can be serialized; the
+field names and the `snake_case` spellings of enum values form a stable
+schema, independent of the IR's own serialized representation.
+
+Types are referred to by their index in [`ModuleReflection::types`], which
+matches the index of the corresponding [`Handle<Type>`] in [`Module::types`].
+
+[`Module`]: crate::Module
+[`Module::types`]: crate::Module::types
+[`ModuleInfo`]: crate::valid::ModuleInfo
+[`Handle<Type>`]: crate::Handle
+*/
+
+use crate::{
+    proc::{LayoutError, Layouter},
+    valid::{GlobalUse, ModuleInfo},
+    Module, TypeInner,
+};
+
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+/// The reflected interface of a whole module.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct ModuleReflection {
+    /// Every type in the module, indexed like [`Module::types`](crate::Module::types).
+    pub types: Vec<TypeReflection>,
+    /// Entry points, in the order they appear in the module.
+    pub entry_points: Vec<EntryPointReflection>,
+}
+
+/// The layout of a single type.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct TypeReflection {
+    pub name: Option<String>,
+    /// Size in bytes, as laid out in host-shareable memory.
+    pub size: u32,
+    /// Alignment in bytes, as laid out in host-shareable memory.
+    pub alignment: u32,
+    #[cfg_attr(feature = "serialize", serde(flatten))]
+    pub kind: TypeKind,
+}
+
+/// What sort of type a [`TypeReflection`] describes.
+///
+/// Scalar types are spelled as in WGSL: `"f32"`, `"u32"`, `"bool"`, etc.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum TypeKind {
+    Scalar {
+        scalar: &'static str,
+    },
+    Vector {
+        components: u32,
+        scalar: &'static str,
+    },
+    Matrix {
+        columns: u32,
+        rows: u32,
+        scalar: &'static str,
+    },
+    Atomic {
+        scalar: &'static str,
+    },
+    Pointer,
+    Array {
+        base: usize,
+        /// Element count, or `None` for runtime-sized arrays.
+        count: Option<u32>,
+        stride: u32,
+    },
+    Struct {
+        members: Vec<MemberReflection>,
+    },
+    Image {
+        /// One of `"1d"`, `"2d"`, `"3d"` or `"cube"`.
+        dimension: &'static str,
+        arrayed: bool,
+        /// One of `"float"`, `"sint"`, `"uint"` or `"depth"`; `None` for storage textures.
+        sample_type: Option<&'static str>,
+        multisampled: bool,
+        /// Texel format of storage textures, spelled as in WGSL.
+        storage_format: Option<&'static str>,
+        /// One of `"read"`, `"write"` or `"read_write"` for storage textures.
+        storage_access: Option<&'static str>,
+    },
+    Sampler {
+        comparison: bool,
+    },
+    AccelerationStructure,
+    RayQuery,
+    BindingArray {
+        base: usize,
+        count: Option<u32>,
+    },
+}
+
+/// A member of a struct type.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct MemberReflection {
+    pub name: Option<String>,
+    pub ty: usize,
+    /// Offset in bytes from the start of the struct.
+    pub offset: u32,
+    /// Size in bytes of the member's type.
+    pub size: u32,
+}
+
+/// The interface of a single entry point.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct EntryPointReflection {
+    pub name: String,
+    /// One of `"vertex"`, `"fragment"` or `"compute"`.
+    pub stage: &'static str,
+    /// Workgroup size of compute entry points.
+    pub workgroup_size: Option<[u32; 3]>,
+    /// Resources statically used by the entry point, sorted by group and binding.
+    pub resources: Vec<ResourceReflection>,
+    /// Push constants statically used by the entry point.
+    pub push_constants: Option<PushConstantReflection>,
+    /// Stage inputs, with struct arguments flattened into their members.
+    pub inputs: Vec<VaryingReflection>,
+    /// Stage outputs, with a struct result flattened into its members.
+    pub outputs: Vec<VaryingReflection>,
+}
+
+/// A resource bound to a group and binding.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct ResourceReflection {
+    pub name: Option<String>,
+    pub group: u32,
+    pub binding: u32,
+    /// One of `"uniform_buffer"`, `"storage_buffer"`, `"read_only_storage_buffer"`,
+    /// `"texture"`, `"storage_texture"`, `"sampler"` or `"acceleration_structure"`.
+    pub resource: &'static str,
+    pub ty: usize,
+    /// For binding arrays with a fixed size, the number of elements.
+    pub count: Option<u32>,
+    /// For buffers, the minimum size of a binding, in bytes.
+    pub min_binding_size: Option<u32>,
+    pub usage: UsageReflection,
+}
+
+/// How an entry point uses a global variable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct UsageReflection {
+    pub read: bool,
+    pub write: bool,
+    pub query: bool,
+}
+
+/// The push constant range of an entry point, starting at offset zero.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct PushConstantReflection {
+    pub name: Option<String>,
+    pub ty: usize,
+    pub size: u32,
+    /// The stages of all entry points in the module that use this push
+    /// constant variable, as named in [`EntryPointReflection::stage`].
+    ///
+    /// This is the set of stages a pipeline layout's push constant range
+    /// for the variable must be visible to.
+    pub stages: Vec<&'static str>,
+}
+
+/// A stage input or output.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct VaryingReflection {
+    pub name: Option<String>,
+    pub ty: usize,
+    /// The location, for user-defined varyings.
+    pub location: Option<u32>,
+    /// The built-in, spelled as in WGSL, for built-in varyings.
+    pub builtin: Option<&'static str>,
+    /// One of `"perspective"`, `"linear"` or `"flat"`.
+    pub interpolation: Option<&'static str>,
+    /// One of `"center"`, `"centroid"` or `"sample"`.
+    pub sampling: Option<&'static str>,
+    pub second_blend_source: bool,
+}
+
+/// Reflect the interface of `module`.
+///
+/// `info` must be the result of validating `module`.
+pub fn reflect(module: &Module, info: &ModuleInfo) -> Result<ModuleReflection, LayoutError> {
+    let mut layouter = Layouter::default();
+    layouter.update(module.to_ctx())?;
+
+    let types = module
+        .types
+        .iter()
+        .map(|(handle, ty)| TypeReflection {
+            name: ty.name.clone(),
+            size: layouter[handle].size,
+            alignment: layouter[handle].alignment.round_up(1),
+            kind: type_kind(&ty.inner, &layouter),
+        })
+        .collect();
+
+    // Push constant ranges are shared by all stages that use them.
+    let mut push_constant_stages = crate::FastHashMap::<_, Vec<_>>::default();
+    for (index, ep) in module.entry_points.iter().enumerate() {
+        let ep_info = info.get_entry_point(index);
+        for (handle, var) in module.global_variables.iter() {
+            if var.space == crate::AddressSpace::PushConstant && !ep_info[handle].is_empty() {
+                let stages = push_constant_stages.entry(handle).or_default();
+                if !stages.contains(&stage_name(ep.stage)) {
+                    stages.push(stage_name(ep.stage));
+                }
+            }
+        }
+    }
+
+    let entry_points = module
+        .entry_points
+        .iter()
+        .enumerate()
+        .map(|(index, ep)| {
+            let ep_info = info.get_entry_point(index);
+
+            let mut resources = Vec::new();
+            let mut push_constants = None;
+            for (handle, var) in module.global_variables.iter() {
+                let usage = ep_info[handle];
+                if usage.is_empty() {
+                    continue;
+                }
+                if var.space == crate::AddressSpace::PushConstant {
+                    push_constants = Some(PushConstantReflection {
+                        name: var.name.clone(),
+                        ty: var.ty.index(),
+                        size: layouter[var.ty].size,
+                        stages: push_constant_stages[&handle].clone(),
+                    });
+                    continue;
+                }
+                let Some(ref binding) = var.binding else {
+                    continue;
+                };
+
+                let (base, count) = match module.types[var.ty].inner {
+                    TypeInner::BindingArray { base, size } => (base, array_count(size)),
+                    _ => (var.ty, None),
+                };
+                let (resource, min_binding_size) = match var.space {
+                    crate::AddressSpace::Uniform => ("uniform_buffer", Some(layouter[base].size)),
+                    crate::AddressSpace::Storage { access } => (
+                        if access.contains(crate::StorageAccess::STORE) {
+                            "storage_buffer"
+                        } else {
+                            "read_only_storage_buffer"
+                        },
+                        Some(layouter[base].size),
+                    ),
+                    _ => (
+                        match module.types[base].inner {
+                            TypeInner::Image {
+                                class: crate::ImageClass::Storage { .. },
+                                ..
+                            } => "storage_texture",
+                            TypeInner::Image { .. } => "texture",
+                            TypeInner::Sampler { .. } => "sampler",
+                            _ => "acceleration_structure",
+                        },
+                        None,
+                    ),
+                };
+
+                resources.push(ResourceReflection {
+                    name: var.name.clone(),
+                    group: binding.group,
+                    binding: binding.binding,
+                    resource,
+                    ty: var.ty.index(),
+                    count,
+                    min_binding_size,
+                    usage: UsageReflection {
+                        read: usage.contains(GlobalUse::READ),
+                        write: usage.contains(GlobalUse::WRITE),
+                        query: usage.contains(GlobalUse::QUERY),
+                    },
+                });
+            }
+            resources.sort_by_key(|resource| (resource.group, resource.binding));
+
+            let mut inputs = Vec::new();
+            for argument in ep.function.arguments.iter() {
+                collect_varyings(
+                    module,
+                    argument.name.as_ref(),
+                    argument.ty,
+                    argument.binding.as_ref(),
+                    &mut inputs,
+                );
+            }
+            let mut outputs = Vec::new();
+            if let Some(ref result) = ep.function.result {
+                collect_varyings(
+                    module,
+                    None,
+                    result.ty,
+                    result.binding.as_ref(),
+                    &mut outputs,
+                );
+            }
+
+            EntryPointReflection {
+                name: ep.name.clone(),
+                stage: stage_name(ep.stage),
+                workgroup_size: match ep.stage {
+                    crate::ShaderStage::Compute => Some(ep.workgroup_size),
+                    _ => None,
+                },
+                resources,
+                push_constants,
+                inputs,
+                outputs,
+            }
+        })
+        .collect();
+
+    Ok(ModuleReflection {
+        types,
+        entry_points,
+    })
+}
+
+fn type_kind(inner: &TypeInner, layouter: &Layouter) -> TypeKind {
+    match *inner {
+        TypeInner::Scalar(scalar) => TypeKind::Scalar {
+            scalar: scalar_name(scalar),
+        },
+        TypeInner::Vector { size, scalar } => TypeKind::Vector {
+            components: size as u32,
+            scalar: scalar_name(scalar),
+        },
+        TypeInner::Matrix {
+            columns,
+            rows,
+            scalar,
+        } => TypeKind::Matrix {
+            columns: columns as u32,
+            rows: rows as u32,
+            scalar: scalar_name(scalar),
+        },
+        TypeInner::Atomic(scalar) => TypeKind::Atomic {
+            scalar: scalar_name(scalar),
+        },
+        TypeInner::Pointer { .. } | TypeInner::ValuePointer { .. } => TypeKind::Pointer,
+        TypeInner::Array { base, size, stride } => TypeKind::Array {
+            base: base.index(),
+            count: array_count(size),
+            stride,
+        },
+        TypeInner::Struct { ref members, .. } => TypeKind::Struct {
+            members: members
+                .iter()
+                .map(|member| MemberReflection {
+                    name: member.name.clone(),
+                    ty: member.ty.index(),
+                    offset: member.offset,
+                    size: layouter[member.ty].size,
+                })
+                .collect(),
+        },
+        TypeInner::Image {
+            dim,
+            arrayed,
+            class,
+        } => {
+            let (sample_type, multisampled, storage) = match class {
+                crate::ImageClass::Sampled { kind, multi } => (
+                    Some(match kind {
+                        crate::ScalarKind::Sint => "sint",
+                        crate::ScalarKind::Uint => "uint",
+                        _ => "float",
+                    }),
+                    multi,
+                    None,
+                ),
+                crate::ImageClass::Depth { multi } => (Some("depth"), multi, None),
+                crate::ImageClass::Storage { format, access } => {
+                    (None, false, Some((format, access)))
+                }
+            };
+            TypeKind::Image {
+                dimension: match dim {
+                    crate::ImageDimension::D1 => "1d",
+                    crate::ImageDimension::D2 => "2d",
+                    crate::ImageDimension::D3 => "3d",
+                    crate::ImageDimension::Cube => "cube",
+                },
+                arrayed,
+                sample_type,
+                multisampled,
+                storage_format: storage.map(|(format, _)| storage_format_name(format)),
+                storage_access: storage.map(|(_, access)| {
+                    if access.contains(crate::StorageAccess::LOAD | crate::StorageAccess::STORE) {
+                        "read_write"
+                    } else if access.contains(crate::StorageAccess::STORE) {
+                        "write"
+                    } else {
+                        "read"
+                    }
+                }),
+            }
+        }
+        TypeInner::Sampler { comparison } => TypeKind::Sampler { comparison },
+        TypeInner::AccelerationStructure => TypeKind::AccelerationStructure,
+        TypeInner::RayQuery => TypeKind::RayQuery,
+        TypeInner::BindingArray { base, size } => TypeKind::BindingArray {
+            base: base.index(),
+            count: array_count(size),
+        },
+    }
+}
+
+/// Add the varyings for a value of type `ty`, bound by `binding`, to `list`.
+///
+/// Struct values have no binding of their own; their members are added instead.
+fn collect_varyings(
+    module: &Module,
+    name: Option<&String>,
+    ty: crate::Handle<crate::Type>,
+    binding: Option<&crate::Binding>,
+    list: &mut Vec<VaryingReflection>,
+) {
+    match binding {
+        Some(binding) => list.push(varying(name.cloned(), ty, binding)),
+        None => {
+            if let TypeInner::Struct { ref members, .. } = module.types[ty].inner {
+                for member in members {
+                    if let Some(ref binding) = member.binding {
+                        list.push(varying(member.name.clone(), member.ty, binding));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn varying(
+    name: Option<String>,
+    ty: crate::Handle<crate::Type>,
+    binding: &crate::Binding,
+) -> VaryingReflection {
+    let mut varying = VaryingReflection {
+        name,
+        ty: ty.index(),
+        location: None,
+        builtin: None,
+        interpolation: None,
+        sampling: None,
+        second_blend_source: false,
+    };
+    match *binding {
+        crate::Binding::BuiltIn(builtin) => varying.builtin = Some(builtin_name(builtin)),
+        crate::Binding::Location {
+            location,
+            second_blend_source,
+            interpolation,
+            sampling,
+        } => {
+            varying.location = Some(location);
+            varying.second_blend_source = second_blend_source;
+            varying.interpolation = interpolation.map(|interpolation| match interpolation {
+                crate::Interpolation::Perspective => "perspective",
+                crate::Interpolation::Linear => "linear",
+                crate::Interpolation::Flat => "flat",
+            });
+            varying.sampling = sampling.map(|sampling| match sampling {
+                crate::Sampling::Center => "center",
+                crate::Sampling::Centroid => "centroid",
+                crate::Sampling::Sample => "sample",
+            });
+        }
+    }
+    varying
+}
+
+const fn stage_name(stage: crate::ShaderStage) -> &'static str {
+    match stage {
+        crate::ShaderStage::Vertex => "vertex",
+        crate::ShaderStage::Fragment => "fragment",
+        crate::ShaderStage::Compute => "compute",
+    }
+}
+
+const fn array_count(size: crate::ArraySize) -> Option<u32> {
+    match size {
+        crate::ArraySize::Constant(count) => Some(count.get()),
+        crate::ArraySize::Dynamic => None,
+    }
+}
+
+const fn scalar_name(scalar: crate::Scalar) -> &'static str {
+    use crate::ScalarKind as Sk;
+
+    match (scalar.kind, scalar.width) {
+        (Sk::Float, 2) => "f16",
+        (Sk::Float, 8) => "f64",
+        (Sk::Float, _) => "f32",
+        (Sk::Sint, 8) => "i64",
+        (Sk::Sint, _) => "i32",
+        (Sk::Uint, 8) => "u64",
+        (Sk::Uint, _) => "u32",
+        (Sk::Bool, _) => "bool",
+        (Sk::AbstractInt, _) => "abstract_int",
+        (Sk::AbstractFloat, _) => "abstract_float",
+    }
+}
+
+const fn builtin_name(builtin: crate::BuiltIn) -> &'static str {
+    use crate::BuiltIn as Bi;
+
+    match builtin {
+        Bi::Position { .. } => "position",
+        Bi::ViewIndex => "view_index",
+        Bi::BaseInstance => "base_instance",
+        Bi::BaseVertex => "base_vertex",
+        Bi::ClipDistance => "clip_distance",
+        Bi::CullDistance => "cull_distance",
+        Bi::InstanceIndex => "instance_index",
+        Bi::PointSize => "point_size",
+        Bi::VertexIndex => "vertex_index",
+        Bi::FragDepth => "frag_depth",
+        Bi::PointCoord => "point_coord",
+        Bi::FrontFacing => "front_facing",
+        Bi::PrimitiveIndex => "primitive_index",
+        Bi::SampleIndex => "sample_index",
+        Bi::SampleMask => "sample_mask",
+        Bi::GlobalInvocationId => "global_invocation_id",
+        Bi::LocalInvocationId => "local_invocation_id",
+        Bi::LocalInvocationIndex => "local_invocation_index",
+        Bi::WorkGroupId => "workgroup_id",
+        Bi::WorkGroupSize => "workgroup_size",
+        Bi::NumWorkGroups => "num_workgroups",
+        Bi::NumSubgroups => "num_subgroups",
+        Bi::SubgroupId => "subgroup_id",
+        Bi::SubgroupSize => "subgroup_size",
+        Bi::SubgroupInvocationId => "subgroup_invocation_id",
+    }
+}
+
+const fn storage_format_name(format: crate::StorageFormat) -> &'static str {
+    use crate::StorageFormat as Sf;
+
+    match format {
+        Sf::R8Unorm => "r8unorm",
+        Sf::R8Snorm => "r8snorm",
+        Sf::R8Uint => "r8uint",
+        Sf::R8Sint => "r8sint",
+        Sf::R16Uint => "r16uint",
+        Sf::R16Sint => "r16sint",
+        Sf::R16Float => "r16float",
+        Sf::Rg8Unorm => "rg8unorm",
+        Sf::Rg8Snorm => "rg8snorm",
+        Sf::Rg8Uint => "rg8uint",
+        Sf::Rg8Sint => "rg8sint",
+        Sf::R32Uint => "r32uint",
+        Sf::R32Sint => "r32sint",
+        Sf::R32Float => "r32float",
+        Sf::Rg16Uint => "rg16uint",
+        Sf::Rg16Sint => "rg16sint",
+        Sf::Rg16Float => "rg16float",
+        Sf::Rgba8Unorm => "rgba8unorm",
+        Sf::Rgba8Snorm => "rgba8snorm",
+        Sf::Rgba8Uint => "rgba8uint",
+        Sf::Rgba8Sint => "rgba8sint",
+        Sf::Bgra8Unorm => "bgra8unorm",
+        Sf::Rgb10a2Uint => "rgb10a2uint",
+        Sf::Rgb10a2Unorm => "rgb10a2unorm",
+        Sf::Rg11b10Float => "rg11b10float",
+        Sf::Rg32Uint => "rg32uint",
+        Sf::Rg32Sint => "rg32sint",
+        Sf::Rg32Float => "rg32float",
+        Sf::Rgba16Uint => "rgba16uint",
+        Sf::Rgba16Sint => "rgba16sint",
+        Sf::Rgba16Float => "rgba16float",
+        Sf::Rgba32Uint => "rgba32uint",
+        Sf::Rgba32Sint => "rgba32sint",
+        Sf::Rgba32Float => "rgba32float",
+        Sf::R16Unorm => "r16unorm",
+        Sf::R16Snorm => "r16snorm",
+        Sf::Rg16Unorm => "rg16unorm",
+        Sf::Rg16Snorm => "rg16snorm",
+        Sf::Rgba16Unorm => "rgba16unorm",
+        Sf::Rgba16Snorm => "rgba16snorm",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::valid::{Capabilities, ValidationFlags, Validator};
+
+    #[test]
+    fn uniform_buffer_layout() {
+        let mut module = crate::Module::default();
+        let vec3_ty = module.types.insert(
+            crate::Type {
+                name: None,
+                inner: crate::TypeInner::Vector {
+                    size: crate::VectorSize::Tri,
+                    scalar: crate::Scalar::F32,
+                },
+            },
+            Default::default(),
+        );
+        let f32_ty = module.types.insert(
+            crate::Type {
+                name: None,
+                inner: crate::TypeInner::Scalar(crate::Scalar::F32),
+            },
+            Default::default(),
+        );
+        let globals_ty = module.types.insert(
+            crate::Type {
+                name: Some("Globals".to_string()),
+                inner: crate::TypeInner::Struct {
+                    members: vec![
+                        crate::StructMember {
+                            name: Some("color".to_string()),
+                            ty: vec3_ty,
+                            binding: None,
+                            offset: 0,
+                            span: Default::default(),
+                        },
+                        crate::StructMember {
+                            name: Some("scale".to_string()),
+                            ty: f32_ty,
+                            binding: None,
+                            offset: 12,
+                            span: Default::default(),
+                        },
+                    ],
+                    span: 16,
+                },
+            },
+            Default::default(),
+        );
+        let globals = module.global_variables.append(
+            crate::GlobalVariable {
+                name: Some("globals".to_string()),
+                space: crate::AddressSpace::Uniform,
+                binding: Some(crate::ResourceBinding {
+                    group: 0,
+                    binding: 1,
+                }),
+                ty: globals_ty,
+                init: None,
+            },
+            Default::default(),
+        );
+
+        let mut function = crate::Function::default();
+        let pointer = function.expressions.append(
+            crate::Expression::GlobalVariable(globals),
+            Default::default(),
+        );
+        function
+            .expressions
+            .append(crate::Expression::Load { pointer }, Default::default());
+        function.body.push(
+            crate::Statement::Emit(function.expressions.range_from(1)),
+            Default::default(),
+        );
+        module.entry_points.push(crate::EntryPoint {
+            name: "main".to_string(),
+            stage: crate::ShaderStage::Compute,
+            early_depth_test: None,
+            workgroup_size: [8, 1, 1],
+            function,
+            span: Default::default(),
+        });
+
+        let info = Validator::new(ValidationFlags::all(), Capabilities::default())
+            .validate(&module)
+            .unwrap();
+        let reflection = super::reflect(&module, &info).unwrap();
+
+        let globals_layout = &reflection.types[globals_ty.index()];
+        assert_eq!(globals_layout.size, 16);
+        assert_eq!(globals_layout.alignment, 16);
+        match globals_layout.kind {
+            super::TypeKind::Struct { ref members } => {
+                assert_eq!(members[1].name.as_deref(), Some("scale"));
+                assert_eq!(members[1].ty, f32_ty.index());
+                assert_eq!(members[1].offset, 12);
+                assert_eq!(members[1].size, 4);
+            }
+            ref other => panic!("unexpected type kind {other:?}"),
+        }
+
+        let ep = &reflection.entry_points[0];
+        assert_eq!(ep.stage, "compute");
+        assert_eq!(ep.workgroup_size, Some([8, 1, 1]));
+        assert_eq!(ep.resources.len(), 1);
+        let resource = &ep.resources[0];
+        assert_eq!((resource.group, resource.binding), (0, 1));
+        assert_eq!(resource.resource, "uniform_buffer");
+        assert_eq!(resource.min_binding_size, Some(16));
+        assert!(resource.usage.read && !resource.usage.write);
+    }
+    #[cfg(feature = "wgsl-in")]
+    #[test]
+    fn push_constant_stages() {
+        let module = crate::front::wgsl::parse_str(
+            "
+            struct Constants { transform: mat4x4<f32>, tint: vec4<f32> }
+            var<push_constant> constants: Constants;
+
+            @vertex
+            fn vs(@location(0) position: vec4<f32>) -> @builtin(position) vec4<f32> {
+                return constants.transform * position;
+            }
+
+            @fragment
+            fn fs() -> @location(0) vec4<f32> {
+                return constants.tint;
+            }
+
+            @fragment
+            fn fs_white() -> @location(0) vec4<f32> {
+                return vec4(1.0);
+            }
+            ",
+        )
+        .unwrap();
+        let info = Validator::new(ValidationFlags::all(), Capabilities::PUSH_CONSTANT)
+            .validate(&module)
+            .unwrap();
+        let reflection = super::reflect(&module, &info).unwrap();
+
+        for ep in &reflection.entry_points[..2] {
+            let push_constants = ep.push_constants.as_ref().unwrap();
+            assert_eq!(push_constants.name.as_deref(), Some("constants"));
+            assert_eq!(push_constants.size, 80);
+            assert_eq!(push_constants.stages, ["vertex", "fragment"]);
+        }
+        assert_eq!(reflection.entry_points[2].push_constants, None);
+    }
+}
@@ -190,13 +190,30 @@ where
 impl<E: Error> WithSpan<E> {
   /// Emits a summary of the error to standard error stream.
   pub fn emit_to_stderr_with_provider<'a>(&self, provider: &'a impl files::Files<'a, FileId = FileId>) {
-    use codespan_reporting::{term};
-    use term::termcolor::{ColorChoice, StandardStream};
+    use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
 
-    let config = codespan_reporting::term::Config::default();
     let writer = StandardStream::stderr(ColorChoice::Auto);
+    self.emit_with_provider(&mut writer.lock(), provider);
+  }
+
+  /// Emits a summary of the error to a string.
+  pub fn emit_to_string_with_provider<'a>(&self, provider: &'a impl files::Files<'a, FileId = FileId>) -> String {
+    use codespan_reporting::term::termcolor::NoColor;
+
+    let mut writer = NoColor::new(Vec::new());
+    self.emit_with_provider(&mut writer, provider);
+    String::from_utf8(writer.into_inner()).unwrap()
+  }
+
+  fn emit_with_provider<'a>(
+    &self,
+    writer: &mut dyn codespan_reporting::term::termcolor::WriteColor,
+    provider: &'a impl files::Files<'a, FileId = FileId>,
+  ) {
+    use codespan_reporting::term;
 
-    term::emit(&mut writer.lock(), &config, provider, &self.diagnostic())
+    let config = term::Config::default();
+    term::emit(writer, &config, provider, &self.diagnostic())
       .expect("cannot write error");
   }
 }
@@ -289,8 +306,12 @@ impl<E> WithSpan<E> {
             .with_message(self.inner.to_string())
             .with_labels(
                 self.spans()
-                    .map(|&(span, ref desc)| {
-                        Label::primary(span.file_id.unwrap(), span.to_range().unwrap()).with_message(desc.to_owned())
+                    // Spans that don't point into a file can't be shown.
+                    .filter_map(|&(span, ref desc)| {
+                        Some(
+                            Label::primary(span.file_id?, span.to_range()?)
+                                .with_message(desc.to_owned()),
+                        )
                     })
                     .collect(),
             )
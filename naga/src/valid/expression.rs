@@ -1571,6 +1571,14 @@ impl super::Validator {
                 }
             },
             E::RayQueryProceedResult => ShaderStages::all(),
+            E::SubgroupBallotResult | E::SubgroupOperationResult { .. } => {
+                if !self.capabilities.contains(super::Capabilities::SUBGROUP) {
+                    return Err(ExpressionError::MissingCapabilities(
+                        super::Capabilities::SUBGROUP,
+                    ));
+                }
+                ShaderStages::COMPUTE | ShaderStages::FRAGMENT
+            }
             E::RayQueryGetIntersection {
                 query,
                 committed: _,
@@ -47,6 +47,17 @@ pub enum AtomicError {
     ResultTypeMismatch(Handle<crate::Expression>),
 }
 
+#[derive(Clone, Debug, thiserror::Error)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum SubgroupError {
+    #[error("Operand {0:?} has invalid type.")]
+    InvalidOperand(Handle<crate::Expression>),
+    #[error("Result type for {0:?} doesn't match the statement")]
+    ResultTypeMismatch(Handle<crate::Expression>),
+    #[error("Subgroup operation {0:?} can't be used as a {1:?}")]
+    InvalidCollectiveOperation(crate::SubgroupOperation, crate::CollectiveOperation),
+}
+
 #[derive(Clone, Debug, thiserror::Error)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum LocalVariableError {
@@ -127,6 +138,10 @@ pub enum FunctionError {
     },
     #[error("Atomic operation is invalid")]
     InvalidAtomic(#[from] AtomicError),
+    #[error("Subgroup operation is invalid")]
+    InvalidSubgroup(#[from] SubgroupError),
+    #[error("Capability {0:?} is required")]
+    MissingCapability(super::Capabilities),
     #[error("Ray Query {0:?} is not a local variable")]
     InvalidRayQueryExpression(Handle<crate::Expression>),
     #[error("Acceleration structure {0:?} is not a matching expression")]
@@ -400,6 +415,129 @@ impl super::Validator {
         Ok(())
     }
 
+    /// Check that the `result` of a subgroup statement has type `expected`.
+    fn validate_subgroup_result(
+        &mut self,
+        result: Handle<crate::Expression>,
+        expected: &crate::TypeInner,
+        context: &BlockContext,
+    ) -> Result<(), WithSpan<FunctionError>> {
+        self.emit_expression(result, context)?;
+        let okay = match context.expressions[result] {
+            crate::Expression::SubgroupBallotResult => {
+                *expected
+                    == crate::TypeInner::Vector {
+                        size: crate::VectorSize::Quad,
+                        scalar: crate::Scalar::U32,
+                    }
+            }
+            crate::Expression::SubgroupOperationResult { ty } => {
+                context.types[ty].inner.equivalent(expected, context.types)
+            }
+            _ => false,
+        };
+        if !okay {
+            log::error!("Subgroup result {:?} is not of type {:?}", result, expected);
+            return Err(SubgroupError::ResultTypeMismatch(result)
+                .with_span_handle(result, context.expressions)
+                .into_other());
+        }
+        Ok(())
+    }
+
+    fn validate_subgroup_operation(
+        &mut self,
+        op: crate::SubgroupOperation,
+        collective_op: crate::CollectiveOperation,
+        argument: Handle<crate::Expression>,
+        result: Handle<crate::Expression>,
+        context: &BlockContext,
+    ) -> Result<(), WithSpan<FunctionError>> {
+        use crate::{CollectiveOperation as co, ScalarKind as sk, SubgroupOperation as sg};
+
+        let argument_inner = context.resolve_type(argument, &self.valid_expression_set)?;
+        let kind = match *argument_inner {
+            crate::TypeInner::Scalar(scalar) | crate::TypeInner::Vector { scalar, .. } => {
+                scalar.kind
+            }
+            ref other => {
+                log::error!("Subgroup operand type {:?}", other);
+                return Err(SubgroupError::InvalidOperand(argument)
+                    .with_span_handle(argument, context.expressions)
+                    .into_other());
+            }
+        };
+
+        let operand_okay = match op {
+            sg::All | sg::Any => {
+                matches!(*argument_inner, crate::TypeInner::Scalar(_)) && kind == sk::Bool
+            }
+            sg::Add | sg::Mul | sg::Min | sg::Max => {
+                matches!(kind, sk::Sint | sk::Uint | sk::Float)
+            }
+            sg::And | sg::Or | sg::Xor => matches!(kind, sk::Sint | sk::Uint),
+        };
+        if !operand_okay {
+            log::error!("Subgroup operation {:?} on {:?}", op, argument_inner);
+            return Err(SubgroupError::InvalidOperand(argument)
+                .with_span_handle(argument, context.expressions)
+                .into_other());
+        }
+
+        match (op, collective_op) {
+            (_, co::Reduce) | (sg::Add | sg::Mul, co::InclusiveScan | co::ExclusiveScan) => {}
+            _ => {
+                return Err(SubgroupError::InvalidCollectiveOperation(op, collective_op)
+                    .with_span_handle(result, context.expressions)
+                    .into_other());
+            }
+        }
+
+        self.validate_subgroup_result(result, argument_inner, context)
+    }
+
+    fn validate_subgroup_gather(
+        &mut self,
+        mode: &crate::GatherMode,
+        argument: Handle<crate::Expression>,
+        result: Handle<crate::Expression>,
+        context: &BlockContext,
+    ) -> Result<(), WithSpan<FunctionError>> {
+        match *mode {
+            crate::GatherMode::BroadcastFirst => {}
+            crate::GatherMode::Broadcast(index)
+            | crate::GatherMode::Shuffle(index)
+            | crate::GatherMode::ShuffleDown(index)
+            | crate::GatherMode::ShuffleUp(index)
+            | crate::GatherMode::ShuffleXor(index) => {
+                let index_inner = context.resolve_type(index, &self.valid_expression_set)?;
+                match *index_inner {
+                    crate::TypeInner::Scalar(crate::Scalar::U32) => {}
+                    ref other => {
+                        log::error!("Subgroup gather index type {:?}", other);
+                        return Err(SubgroupError::InvalidOperand(index)
+                            .with_span_handle(index, context.expressions)
+                            .into_other());
+                    }
+                }
+            }
+        }
+
+        let argument_inner = context.resolve_type(argument, &self.valid_expression_set)?;
+        match *argument_inner {
+            crate::TypeInner::Scalar(scalar) | crate::TypeInner::Vector { scalar, .. }
+                if scalar.kind != crate::ScalarKind::Bool => {}
+            ref other => {
+                log::error!("Subgroup gather operand type {:?}", other);
+                return Err(SubgroupError::InvalidOperand(argument)
+                    .with_span_handle(argument, context.expressions)
+                    .into_other());
+            }
+        }
+
+        self.validate_subgroup_result(result, argument_inner, context)
+    }
+
     fn validate_block_impl(
         &mut self,
         statements: &crate::Block,
@@ -613,8 +751,16 @@ impl super::Validator {
                     stages &= super::ShaderStages::FRAGMENT;
                     finished = true;
                 }
-                S::Barrier(_) => {
+                S::Barrier(barrier) => {
                     stages &= super::ShaderStages::COMPUTE;
+                    if barrier.contains(crate::Barrier::SUB_GROUP)
+                        && !self.capabilities.contains(super::Capabilities::SUBGROUP)
+                    {
+                        return Err(FunctionError::MissingCapability(
+                            super::Capabilities::SUBGROUP,
+                        )
+                        .with_span_static(span, "subgroup barrier"));
+                    }
                 }
                 S::Store { pointer, value } => {
                     let mut current = pointer;
@@ -904,6 +1050,52 @@ impl super::Validator {
                         crate::RayQueryFunction::Terminate => {}
                     }
                 }
+                S::SubgroupElect { result } => {
+                    stages &= super::ShaderStages::COMPUTE | super::ShaderStages::FRAGMENT;
+                    self.validate_subgroup_result(
+                        result,
+                        &Ti::Scalar(crate::Scalar::BOOL),
+                        context,
+                    )?;
+                }
+                S::SubgroupBallot { result, predicate } => {
+                    stages &= super::ShaderStages::COMPUTE | super::ShaderStages::FRAGMENT;
+                    if let Some(predicate) = predicate {
+                        let predicate_inner =
+                            context.resolve_type(predicate, &self.valid_expression_set)?;
+                        if *predicate_inner != Ti::Scalar(crate::Scalar::BOOL) {
+                            log::error!("Subgroup ballot predicate type {:?}", predicate_inner);
+                            return Err(SubgroupError::InvalidOperand(predicate)
+                                .with_span_handle(predicate, context.expressions)
+                                .into_other());
+                        }
+                    }
+                    self.validate_subgroup_result(
+                        result,
+                        &Ti::Vector {
+                            size: crate::VectorSize::Quad,
+                            scalar: crate::Scalar::U32,
+                        },
+                        context,
+                    )?;
+                }
+                S::SubgroupCollectiveOperation {
+                    op,
+                    collective_op,
+                    argument,
+                    result,
+                } => {
+                    stages &= super::ShaderStages::COMPUTE | super::ShaderStages::FRAGMENT;
+                    self.validate_subgroup_operation(op, collective_op, argument, result, context)?;
+                }
+                S::SubgroupGather {
+                    ref mode,
+                    argument,
+                    result,
+                } => {
+                    stages &= super::ShaderStages::COMPUTE | super::ShaderStages::FRAGMENT;
+                    self.validate_subgroup_gather(mode, argument, result, context)?;
+                }
             }
         }
         Ok(BlockInfo { stages, finished })
@@ -0,0 +1,50 @@
+// Structs whose layouts need padding on the host: three-component vectors,
+// three-row matrices, arrays whose stride exceeds their element size, and
+// runtime-sized arrays. Padding too large for a single `Pod` array, and
+// binding names that only differ in case.
+
+struct Light {
+    position: vec3<f32>,
+    intensity: f32,
+    color: vec3<f32>,
+}
+
+struct Globals {
+    transform: mat3x3<f32>,
+    lights: array<Light, 2>,
+    corners: array<vec3<f32>, 4>,
+    ambient: vec3<f32>,
+}
+
+struct Particles {
+    count: u32,
+    origin: vec3<f32>,
+    particles: array<vec3<f32>>,
+}
+
+struct Aligned {
+    flag: u32,
+    @align(256) value: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> globals: Globals;
+
+@group(0) @binding(1)
+var<storage, read_write> particles: Particles;
+
+@group(1) @binding(0)
+var<uniform> aligned: Aligned;
+
+@group(1) @binding(1)
+var<uniform> ALIGNED: Aligned;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= particles.count {
+        return;
+    }
+    let light = globals.lights[id.x % 2u];
+    particles.particles[id.x] = globals.transform * (particles.origin + light.position)
+        + globals.ambient + globals.corners[id.x % 4u];
+}
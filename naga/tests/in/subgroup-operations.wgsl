@@ -0,0 +1,31 @@
+@compute @workgroup_size(64)
+fn main(
+    @builtin(subgroup_size) subgroup_size: u32,
+    @builtin(subgroup_invocation_id) subgroup_invocation_id: u32,
+) {
+    subgroupBarrier();
+
+    let elected = subgroupElect();
+    let ballot = subgroupBallot((subgroup_invocation_id & 1u) == 1u);
+
+    let all = subgroupAll(subgroup_invocation_id != 0u);
+    let any = subgroupAny(subgroup_invocation_id == 0u);
+    let sum = subgroupAdd(subgroup_invocation_id);
+    let product = subgroupMul(vec2(f32(subgroup_invocation_id)));
+    let minimum = subgroupMin(i32(subgroup_invocation_id));
+    let maximum = subgroupMax(subgroup_invocation_id);
+    let and = subgroupAnd(subgroup_invocation_id);
+    let or = subgroupOr(subgroup_invocation_id);
+    let xor = subgroupXor(subgroup_invocation_id);
+    let exclusive_sum = subgroupExclusiveAdd(subgroup_invocation_id);
+    let exclusive_product = subgroupExclusiveMul(subgroup_invocation_id);
+    let inclusive_sum = subgroupInclusiveAdd(subgroup_invocation_id);
+    let inclusive_product = subgroupInclusiveMul(subgroup_invocation_id);
+
+    let first = subgroupBroadcastFirst(subgroup_invocation_id);
+    let broadcast = subgroupBroadcast(subgroup_invocation_id, 4u);
+    let shuffled = subgroupShuffle(subgroup_invocation_id, subgroup_size - 1u - subgroup_invocation_id);
+    let down = subgroupShuffleDown(subgroup_invocation_id, 1);
+    let up = subgroupShuffleUp(subgroup_invocation_id, 1u);
+    let swapped = subgroupShuffleXor(subgroup_invocation_id, subgroup_size - 1u);
+}
@@ -0,0 +1,40 @@
+// Identifiers are shortened, but entry points and bindings are kept.
+
+const scale_factor: f32 = 2.0;
+const tint_amount: f32 = 0.5;
+
+struct Light {
+    position: vec3<f32>,
+    intensity: f32,
+}
+
+@group(0) @binding(0) var<uniform> light: Light;
+@group(0) @binding(1) var<storage, read_write> results: array<f32>;
+
+fn attenuate(distance_to_light: f32, intensity: f32) -> f32 {
+    let falloff = intensity / (1.0 + distance_to_light * distance_to_light);
+    return -falloff * (scale_factor - 1.0) + tint_amount;
+}
+
+@compute @workgroup_size(64)
+fn shade(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    let offset = vec3<f32>(f32(index), 0.0, 0.0) - light.position;
+    var total = 0.0;
+    if index < arrayLength(&results) && light.intensity > 0.0 {
+        total = attenuate(length(offset), light.intensity);
+    }
+    results[index] = total - (total - 1.0) + (-2.0 - total) + nested(total, offset.x, 3.0, index, 5u);
+}
+
+// Nested operators keep only the parentheses precedence needs.
+fn nested(a: f32, b: f32, c: f32, i: u32, j: u32) -> f32 {
+    let sum = a - (b - c) + (a - b) - c;
+    let product = a * (b / c) / (a * b) % c;
+    let mixed = (a + b) * (c - a) - a * b / (c + 1.0);
+    let negated = -(a + b) * -c - -1.0 - (c - 2.0);
+    let bits = (i & j & 7u) | (i ^ j);
+    let shifted = (i << 2u) + (j >> 1u);
+    let chosen = select(a - b, b * c, (a < b && b <= c) || (c > a && i != j));
+    return sum + product * mixed + negated / chosen + f32(bits + shifted);
+}
@@ -0,0 +1,40 @@
+#version 430 core
+#extension GL_ARB_compute_shader : require
+#extension GL_KHR_shader_subgroup_basic : require
+#extension GL_KHR_shader_subgroup_vote : require
+#extension GL_KHR_shader_subgroup_arithmetic : require
+#extension GL_KHR_shader_subgroup_ballot : require
+#extension GL_KHR_shader_subgroup_shuffle : require
+#extension GL_KHR_shader_subgroup_shuffle_relative : require
+layout(local_size_x = 64, local_size_y = 1, local_size_z = 1) in;
+
+
+void main() {
+    uint subgroup_size = gl_SubgroupSize;
+    uint subgroup_invocation_id = gl_SubgroupInvocationID;
+    subgroupMemoryBarrier();
+    subgroupBarrier();
+    bool _e2 = subgroupElect();
+    uvec4 _e7 = subgroupBallot(((subgroup_invocation_id & 1u) == 1u));
+    bool _e10 = subgroupAll((subgroup_invocation_id != 0u));
+    bool _e13 = subgroupAny((subgroup_invocation_id == 0u));
+    uint _e14 = subgroupAdd(subgroup_invocation_id);
+    vec2 _e17 = subgroupMul(vec2(float(subgroup_invocation_id)));
+    int _e19 = subgroupMin(int(subgroup_invocation_id));
+    uint _e20 = subgroupMax(subgroup_invocation_id);
+    uint _e21 = subgroupAnd(subgroup_invocation_id);
+    uint _e22 = subgroupOr(subgroup_invocation_id);
+    uint _e23 = subgroupXor(subgroup_invocation_id);
+    uint _e24 = subgroupExclusiveAdd(subgroup_invocation_id);
+    uint _e25 = subgroupExclusiveMul(subgroup_invocation_id);
+    uint _e26 = subgroupInclusiveAdd(subgroup_invocation_id);
+    uint _e27 = subgroupInclusiveMul(subgroup_invocation_id);
+    uint _e28 = subgroupBroadcastFirst(subgroup_invocation_id);
+    uint _e30 = subgroupBroadcast(subgroup_invocation_id, 4u);
+    uint _e34 = subgroupShuffle(subgroup_invocation_id, ((subgroup_size - 1u) - subgroup_invocation_id));
+    uint _e36 = subgroupShuffleDown(subgroup_invocation_id, 1u);
+    uint _e38 = subgroupShuffleUp(subgroup_invocation_id, 1u);
+    uint _e41 = subgroupShuffleXor(subgroup_invocation_id, (subgroup_size - 1u));
+    return;
+}
+
@@ -0,0 +1,29 @@
+[numthreads(64, 1, 1)]
+void main()
+{
+    uint subgroup_size = WaveGetLaneCount();
+    uint subgroup_invocation_id = WaveGetLaneIndex();
+    AllMemoryBarrier();
+    bool _e2 = WaveIsFirstLane();
+    uint4 _e7 = WaveActiveBallot(((subgroup_invocation_id & 1u) == 1u));
+    bool _e10 = WaveActiveAllTrue((subgroup_invocation_id != 0u));
+    bool _e13 = WaveActiveAnyTrue((subgroup_invocation_id == 0u));
+    uint _e14 = WaveActiveSum(subgroup_invocation_id);
+    float2 _e17 = WaveActiveProduct((float(subgroup_invocation_id)).xx);
+    int _e19 = WaveActiveMin(int(subgroup_invocation_id));
+    uint _e20 = WaveActiveMax(subgroup_invocation_id);
+    uint _e21 = WaveActiveBitAnd(subgroup_invocation_id);
+    uint _e22 = WaveActiveBitOr(subgroup_invocation_id);
+    uint _e23 = WaveActiveBitXor(subgroup_invocation_id);
+    uint _e24 = WavePrefixSum(subgroup_invocation_id);
+    uint _e25 = WavePrefixProduct(subgroup_invocation_id);
+    uint _e26 = subgroup_invocation_id + WavePrefixSum(subgroup_invocation_id);
+    uint _e27 = subgroup_invocation_id * WavePrefixProduct(subgroup_invocation_id);
+    uint _e28 = WaveReadLaneFirst(subgroup_invocation_id);
+    uint _e30 = WaveReadLaneAt(subgroup_invocation_id, 4u);
+    uint _e34 = WaveReadLaneAt(subgroup_invocation_id, ((subgroup_size - 1u) - subgroup_invocation_id));
+    uint _e36 = WaveReadLaneAt(subgroup_invocation_id, WaveGetLaneIndex() + 1u);
+    uint _e38 = WaveReadLaneAt(subgroup_invocation_id, WaveGetLaneIndex() - 1u);
+    uint _e41 = WaveReadLaneAt(subgroup_invocation_id, WaveGetLaneIndex() ^ (subgroup_size - 1u));
+    return;
+}
@@ -0,0 +1,125 @@
+// Generated by naga from the shader's types. Do not edit.
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Light {
+    pub position: [f32; 3],
+    pub intensity: f32,
+    pub color: [f32; 3],
+    pub _pad0: [u8; 4],
+}
+
+const _: () = assert!(std::mem::size_of::<Light>() == 32);
+const _: () = {
+    let value = std::mem::MaybeUninit::<Light>::uninit();
+    let base = value.as_ptr();
+    // SAFETY: This only computes the addresses of members, it never reads them.
+    unsafe {
+        let member = std::ptr::addr_of!((*base).position).cast::<u8>();
+        assert!(member.offset_from(base.cast()) == 0);
+        let member = std::ptr::addr_of!((*base).intensity).cast::<u8>();
+        assert!(member.offset_from(base.cast()) == 12);
+        let member = std::ptr::addr_of!((*base).color).cast::<u8>();
+        assert!(member.offset_from(base.cast()) == 16);
+    }
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Vec3F32Element {
+    pub value: [f32; 3],
+    pub _pad0: [u8; 4],
+}
+
+const _: () = assert!(std::mem::size_of::<Vec3F32Element>() == 16);
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Globals {
+    pub transform: [[f32; 4]; 3],
+    pub lights: [Light; 2],
+    pub corners: [Vec3F32Element; 4],
+    pub ambient: [f32; 3],
+    pub _pad0: [u8; 4],
+}
+
+const _: () = assert!(std::mem::size_of::<Globals>() == 192);
+const _: () = {
+    let value = std::mem::MaybeUninit::<Globals>::uninit();
+    let base = value.as_ptr();
+    // SAFETY: This only computes the addresses of members, it never reads them.
+    unsafe {
+        let member = std::ptr::addr_of!((*base).transform).cast::<u8>();
+        assert!(member.offset_from(base.cast()) == 0);
+        let member = std::ptr::addr_of!((*base).lights).cast::<u8>();
+        assert!(member.offset_from(base.cast()) == 48);
+        let member = std::ptr::addr_of!((*base).corners).cast::<u8>();
+        assert!(member.offset_from(base.cast()) == 112);
+        let member = std::ptr::addr_of!((*base).ambient).cast::<u8>();
+        assert!(member.offset_from(base.cast()) == 176);
+    }
+};
+
+/// Followed by the runtime-sized array `particles`, which is not part of this type.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Particles {
+    pub count: u32,
+    pub _pad0: [u8; 12],
+    pub origin: [f32; 3],
+    pub _pad1: [u8; 4],
+}
+
+impl Particles {
+    pub const PARTICLES_OFFSET: usize = 32;
+    pub const PARTICLES_STRIDE: usize = 16;
+}
+
+const _: () = assert!(std::mem::size_of::<Particles>() == 32);
+const _: () = {
+    let value = std::mem::MaybeUninit::<Particles>::uninit();
+    let base = value.as_ptr();
+    // SAFETY: This only computes the addresses of members, it never reads them.
+    unsafe {
+        let member = std::ptr::addr_of!((*base).count).cast::<u8>();
+        assert!(member.offset_from(base.cast()) == 0);
+        let member = std::ptr::addr_of!((*base).origin).cast::<u8>();
+        assert!(member.offset_from(base.cast()) == 16);
+    }
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Aligned {
+    pub flag: u32,
+    pub _pad0: [u8; 128],
+    pub _pad1: [u8; 96],
+    pub _pad2: [u8; 28],
+    pub value: [f32; 4],
+    pub _pad3: [u8; 128],
+    pub _pad4: [u8; 96],
+    pub _pad5: [u8; 16],
+}
+
+const _: () = assert!(std::mem::size_of::<Aligned>() == 512);
+const _: () = {
+    let value = std::mem::MaybeUninit::<Aligned>::uninit();
+    let base = value.as_ptr();
+    // SAFETY: This only computes the addresses of members, it never reads them.
+    unsafe {
+        let member = std::ptr::addr_of!((*base).flag).cast::<u8>();
+        assert!(member.offset_from(base.cast()) == 0);
+        let member = std::ptr::addr_of!((*base).value).cast::<u8>();
+        assert!(member.offset_from(base.cast()) == 256);
+    }
+};
+
+pub const GLOBALS_GROUP: u32 = 0;
+pub const GLOBALS_BINDING: u32 = 0;
+pub const PARTICLES_GROUP: u32 = 0;
+pub const PARTICLES_BINDING: u32 = 1;
+pub const ALIGNED_GROUP: u32 = 1;
+pub const ALIGNED_BINDING: u32 = 0;
+pub const ALIGNED_GROUP_1: u32 = 1;
+pub const ALIGNED_BINDING_1: u32 = 1;
+
@@ -0,0 +1,47 @@
+struct Light {
+    position: vec3<f32>,
+    intensity: f32,
+    color: vec3<f32>,
+}
+
+struct Globals {
+    transform: mat3x3<f32>,
+    lights: array<Light, 2>,
+    corners: array<vec3<f32>, 4>,
+    ambient: vec3<f32>,
+}
+
+struct Particles {
+    count: u32,
+    origin: vec3<f32>,
+    particles: array<vec3<f32>>,
+}
+
+struct Aligned {
+    flag: u32,
+    value: vec4<f32>,
+}
+
+@group(0) @binding(0) 
+var<uniform> globals: Globals;
+@group(0) @binding(1) 
+var<storage, read_write> particles: Particles;
+@group(1) @binding(0) 
+var<uniform> aligned: Aligned;
+@group(1) @binding(1) 
+var<uniform> ALIGNED: Aligned;
+
+@compute @workgroup_size(64, 1, 1) 
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let _e4 = particles.count;
+    if (id.x >= _e4) {
+        return;
+    }
+    let light = globals.lights[(id.x % 2u)];
+    let _e19 = globals.transform;
+    let _e22 = particles.origin;
+    let _e28 = globals.ambient;
+    let _e36 = globals.corners[(id.x % 4u)];
+    particles.particles[id.x] = (((_e19 * (_e22 + light.position)) + _e28) + _e36);
+    return;
+}
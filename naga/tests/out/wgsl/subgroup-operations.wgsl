@@ -0,0 +1,26 @@
+@compute @workgroup_size(64, 1, 1) 
+fn main(@builtin(subgroup_size) subgroup_size: u32, @builtin(subgroup_invocation_id) subgroup_invocation_id: u32) {
+    subgroupBarrier();
+    let _e2 = subgroupElect();
+    let _e7 = subgroupBallot(((subgroup_invocation_id & 1u) == 1u));
+    let _e10 = subgroupAll((subgroup_invocation_id != 0u));
+    let _e13 = subgroupAny((subgroup_invocation_id == 0u));
+    let _e14 = subgroupAdd(subgroup_invocation_id);
+    let _e17 = subgroupMul(vec2(f32(subgroup_invocation_id)));
+    let _e19 = subgroupMin(i32(subgroup_invocation_id));
+    let _e20 = subgroupMax(subgroup_invocation_id);
+    let _e21 = subgroupAnd(subgroup_invocation_id);
+    let _e22 = subgroupOr(subgroup_invocation_id);
+    let _e23 = subgroupXor(subgroup_invocation_id);
+    let _e24 = subgroupExclusiveAdd(subgroup_invocation_id);
+    let _e25 = subgroupExclusiveMul(subgroup_invocation_id);
+    let _e26 = subgroupInclusiveAdd(subgroup_invocation_id);
+    let _e27 = subgroupInclusiveMul(subgroup_invocation_id);
+    let _e28 = subgroupBroadcastFirst(subgroup_invocation_id);
+    let _e30 = subgroupBroadcast(subgroup_invocation_id, 4u);
+    let _e34 = subgroupShuffle(subgroup_invocation_id, ((subgroup_size - 1u) - subgroup_invocation_id));
+    let _e36 = subgroupShuffleDown(subgroup_invocation_id, 1u);
+    let _e38 = subgroupShuffleUp(subgroup_invocation_id, 1u);
+    let _e41 = subgroupShuffleXor(subgroup_invocation_id, (subgroup_size - 1u));
+    return;
+}
@@ -0,0 +1 @@
+struct c{a:vec3<f32>,b:f32,}const t:f32=2.0;const u:f32=0.5;@group(0)@binding(0)var<uniform>r:c;@group(0)@binding(1)var<storage,read_write>s:array<f32>;fn i(j:f32,k:f32)->f32{let v=k/(1.0+j*j);return-v*1.0+u;}fn l(m:f32,n:f32,o:f32,p:u32,q:u32)->f32{let w=m-(n-o)+(m-n)-o;let x=m*(n/o)/(m*n)%o;let y=(m+n)*(o-m)-m*n/(o+1.0);let z=-(m+n)*-o- -1.0-(o-2.0);let A=(p&q&7u)|(p^q);let B=(p<<2u)+(q>>1u);let C=select(m-n,n*o,(m<n&&n<=o)||(o>m&&p!=q));return w+x*y+z/C+f32(A+B);}@compute@workgroup_size(64,1,1)fn shade(@builtin(global_invocation_id)g:vec3<u32>){var h:f32=0.0;let D=g.x;let E=r.a;let F=vec3<f32>(f32(D),0.0,0.0)-E;let G=r.b;if D<arrayLength(&s)&&G>0.0{let H=r.b;let I=i(length(F),H);h=I;}let J=h;let K=h;let L=h;let M=h;let N=l(M,F.x,3.0,D,5u);s[D]=J-(K-1.0)+(-2.0-L)+N;return;}
\ No newline at end of file
@@ -22,6 +22,7 @@ bitflags::bitflags! {
         const DOT = 0x20;
         const HLSL = 0x40;
         const WGSL = 0x80;
+        const RUST = 0x100;
     }
 }
 
@@ -57,6 +58,8 @@ struct SpirvOutParameters {
 struct WgslOutParameters {
     #[serde(default)]
     explicit_types: bool,
+    #[serde(default)]
+    minify: bool,
 }
 
 #[derive(Default, serde::Deserialize)]
@@ -386,6 +389,13 @@ fn check_targets(
             write_output_wgsl(input, module, &info, &params.wgsl);
         }
     }
+    #[cfg(feature = "rust-out")]
+    {
+        if targets.contains(Targets::RUST) {
+            let string = naga::back::rust::write_string(module, &info).unwrap();
+            input.write_output_file("rust", "rs", string);
+        }
+    }
 }
 
 #[cfg(feature = "spv-out")]
@@ -592,8 +602,33 @@ fn write_output_wgsl(
 
     let mut flags = wgsl::WriterFlags::empty();
     flags.set(wgsl::WriterFlags::EXPLICIT_TYPES, params.explicit_types);
+    flags.set(wgsl::WriterFlags::MINIFY, params.minify);
+
+    let mut writer = wgsl::Writer::new(String::new(), flags);
+    writer.write(module, info).expect("WGSL write failed");
+
+    if params.minify {
+        let symbols: String = writer
+            .symbol_map()
+            .iter()
+            .map(|(name, original)| format!("{name} {original}\n"))
+            .collect();
+        input.write_output_file("wgsl", "symbols.txt", symbols);
+    }
+
+    let string = writer.finish();
 
-    let string = wgsl::write_string(module, info, flags).expect("WGSL write failed");
+    // Minified output must still be valid WGSL.
+    #[cfg(feature = "wgsl-in")]
+    if params.minify {
+        let module = naga::front::wgsl::parse_str(&string).expect("minified WGSL parse failed");
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .expect("minified WGSL validation failed");
+    }
 
     input.write_output_file("wgsl", "wgsl", string);
 }
@@ -771,6 +806,11 @@ fn convert_wgsl() {
         ("force_point_size_vertex_shader_webgl", Targets::GLSL),
         ("invariant", Targets::GLSL),
         ("ray-query", Targets::SPIRV | Targets::METAL),
+        ("host-shareable", Targets::RUST | Targets::WGSL),
+        (
+            "subgroup-operations",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+        ),
         ("hlsl-keyword", Targets::HLSL),
         (
             "constructors",
@@ -794,6 +834,7 @@ fn convert_wgsl() {
             "abstract-types",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::WGSL,
         ),
+        ("wgsl-minify", Targets::WGSL),
     ];
 
     for &(name, targets) in inputs.iter() {
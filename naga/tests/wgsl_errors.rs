@@ -2049,3 +2049,404 @@ fn compaction_preserves_spans() {
         panic!("Error message has wrong span:\n\n{err:#?}");
     }
 }
+
+#[test]
+fn generic_parameter_not_inferred() {
+    check(
+        r#"
+            fn zero<T>() -> T {
+                return T();
+            }
+
+            fn main() {
+                let x = zero();
+            }
+        "#,
+        r#"error: unable to infer the type of generic parameter `T`
+  ┌─ wgsl:2:21
+  │
+2 │             fn zero<T>() -> T {
+  │                     ^ generic parameter declared here
+  ·
+7 │                 let x = zero();
+  │                         ^^^^^ cannot infer generic parameters for this call
+  │
+  = note: generic parameters must appear in the type of at least one argument
+
+"#,
+    );
+}
+
+#[test]
+fn generic_parameter_conflict() {
+    check(
+        r#"
+            fn pick<T>(a: T, b: T) -> T {
+                return a;
+            }
+
+            fn main() {
+                let x = pick(1.0, 2u);
+            }
+        "#,
+        r#"error: conflicting types for generic parameter `T`: `f32` and `u32`
+  ┌─ wgsl:2:21
+  │
+2 │             fn pick<T>(a: T, b: T) -> T {
+  │                     ^ generic parameter declared here
+  ·
+7 │                 let x = pick(1.0, 2u);
+  │                         ^^^^^^^^^^^^^ arguments infer both `f32` and `u32`
+
+"#,
+    );
+}
+
+#[test]
+fn generic_parameter_constraint() {
+    check(
+        r#"
+            fn half<T: f32 | vec2<f32>>(a: T) -> T {
+                return a * 0.5;
+            }
+
+            fn main() {
+                let x = half(1u);
+            }
+        "#,
+        r#"error: type `u32` does not satisfy the constraints of generic parameter `T`
+  ┌─ wgsl:2:21
+  │
+2 │             fn half<T: f32 | vec2<f32>>(a: T) -> T {
+  │                     ^ generic parameter declared here
+  ·
+7 │                 let x = half(1u);
+  │                         ^^^^^^^^ `T` inferred as `u32` here
+
+"#,
+    );
+}
+
+#[test]
+fn generic_instantiation() {
+    check(
+        r#"
+            fn widen<T>(a: T) -> u32 {
+                let b: u32 = a;
+                return b;
+            }
+
+            fn main() {
+                let x = widen(1.0);
+            }
+        "#,
+        r#"error: the type of `b` is expected to be `u32`, but got `f32`
+  ┌─ wgsl:2:16
+  │
+2 │             fn widen<T>(a: T) -> u32 {
+  │                ^^^^^ generic function declared here
+3 │                 let b: u32 = a;
+  │                     ^ definition of `b`
+  ·
+8 │                 let x = widen(1.0);
+  │                         ^^^^^^^^^^ `widen<f32>` instantiated here
+  │
+  = note: while instantiating `widen<f32>`
+
+"#,
+    );
+}
+
+#[test]
+fn generic_entry_point() {
+    check(
+        r#"
+            @compute @workgroup_size(1)
+            fn main<T>() {}
+        "#,
+        r#"error: entry point `main` cannot be generic
+  ┌─ wgsl:3:16
+  │
+3 │             fn main<T>() {}
+  │                ^^^^ entry point declared with generic parameters
+
+"#,
+    );
+}
+
+#[test]
+fn unknown_enum_variant() {
+    check(
+        r#"
+            enum LightKind { Point, Spot }
+
+            fn f() -> u32 {
+                return LightKind::Area;
+            }
+        "#,
+        r#"error: enum `LightKind` has no variant `Area`
+  ┌─ wgsl:2:18
+  │
+2 │             enum LightKind { Point, Spot }
+  │                  ^^^^^^^^^ enum declared here
+  ·
+5 │                 return LightKind::Area;
+  │                                   ^^^^ unknown variant
+
+"#,
+    );
+}
+
+#[test]
+fn invalid_enum_repr() {
+    check(
+        r#"
+            enum LightKind: f32 { Point, Spot }
+        "#,
+        r#"error: invalid enum representation type `f32`
+  ┌─ wgsl:2:29
+  │
+2 │             enum LightKind: f32 { Point, Spot }
+  │                             ^^^ invalid representation type
+  │
+  = note: enums must be represented as `u32` or `i32`
+
+"#,
+    );
+}
+
+#[test]
+fn enum_value_overflow() {
+    check(
+        r#"
+            enum Level: i32 { Max = 2147483647, Overflow }
+        "#,
+        r#"error: value of enum variant `Overflow` overflows its representation type
+  ┌─ wgsl:2:49
+  │
+2 │             enum Level: i32 { Max = 2147483647, Overflow }
+  │                                                 ^^^^^^^^ value out of range
+  │
+  = note: consider giving this variant an explicit value
+
+"#,
+    );
+}
+
+#[test]
+fn unknown_method() {
+    check(
+        r#"
+            struct Ray { dir: vec3<f32> }
+
+            impl Ray {
+                fn len(self) -> f32 { return length(self.dir); }
+            }
+
+            fn f(ray: Ray) -> f32 {
+                return ray.at(1.0);
+            }
+        "#,
+        r#"error: no method named `at` found for type `Ray`
+  ┌─ wgsl:9:28
+  │
+9 │                 return ray.at(1.0);
+  │                            ^^ method not found in `Ray`
+
+"#,
+    );
+}
+
+#[test]
+fn recursive_method() {
+    check(
+        r#"
+            struct Ray { dir: vec3<f32> }
+
+            impl Ray {
+                fn len(self) -> f32 { return self.len(); }
+            }
+        "#,
+        r#"error: declaration of `len` is recursive
+  ┌─ wgsl:5:20
+  │
+5 │                 fn len(self) -> f32 { return self.len(); }
+  │                    ^^^                            ^^^ uses itself here
+
+"#,
+    );
+}
+
+#[test]
+fn cyclic_methods() {
+    check(
+        r#"
+            struct A { x: f32 }
+            struct B { y: f32 }
+
+            impl A {
+                fn f(self, b: B) -> f32 { return b.g(self); }
+            }
+
+            impl B {
+                fn g(self, a: A) -> f32 { return a.f(self); }
+            }
+        "#,
+        r#"error: declaration of `f` is cyclic
+   ┌─ wgsl:6:20
+   │
+ 6 │                 fn f(self, b: B) -> f32 { return b.g(self); }
+   │                    ^                               ^ uses `f`
+   ·
+10 │                 fn g(self, a: A) -> f32 { return a.f(self); }
+   │                    ^                               ^ ending the cycle
+
+"#,
+    );
+}
+
+#[test]
+fn method_redefinition() {
+    check(
+        r#"
+            struct Ray { dir: vec3<f32> }
+
+            impl Ray {
+                fn len(self) -> f32 { return length(self.dir); }
+            }
+
+            impl Ray {
+                fn len(self) -> f32 { return 1.0; }
+            }
+        "#,
+        r#"error: redefinition of `len`
+  ┌─ wgsl:5:20
+  │
+5 │                 fn len(self) -> f32 { return length(self.dir); }
+  │                    ^^^ previous definition of `len`
+  ·
+9 │                 fn len(self) -> f32 { return 1.0; }
+  │                    ^^^ redefinition of `len`
+
+"#,
+    );
+}
+
+#[test]
+fn mutating_method_receiver() {
+    check(
+        r#"
+            struct Counter { value: u32 }
+
+            impl Counter {
+                fn bump(&mut self) { self.value += 1u; }
+            }
+
+            fn f(counter: Counter) {
+                counter.bump();
+            }
+        "#,
+        r#"error: method `bump` takes `&mut self`, but its receiver is not a local variable
+  ┌─ wgsl:9:17
+  │
+9 │                 counter.bump();
+  │                 ^^^^^^^ ^^^^ method takes `&mut self`
+  │                 │        
+  │                 not a `var` in function scope
+  │
+  = note: copy the receiver into a local `var` first
+
+"#,
+    );
+}
+
+#[test]
+fn subgroup_capability() {
+    check_validation! {
+        "
+        fn f(x: u32) -> u32 {
+            return subgroupAdd(x);
+        }
+        ",
+        "
+        fn f(x: u32) -> u32 {
+            return subgroupShuffle(x, 1u);
+        }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            source: naga::valid::FunctionError::Expression {
+                source: naga::valid::ExpressionError::MissingCapabilities(
+                    naga::valid::Capabilities::SUBGROUP
+                ),
+                ..
+            },
+            ..
+        })
+    }
+
+    check_validation! {
+        "
+        fn f() {
+            subgroupBarrier();
+        }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            source: naga::valid::FunctionError::MissingCapability(
+                naga::valid::Capabilities::SUBGROUP
+            ),
+            ..
+        })
+    }
+}
+
+#[test]
+fn invalid_subgroup_operands() {
+    for source in [
+        "fn f(v: vec2<bool>) -> bool { return subgroupAll(v); }",
+        "fn f(x: f32) -> f32 { return subgroupAnd(x); }",
+        "fn f(b: bool) -> bool { return subgroupAdd(b); }",
+        "fn f(m: mat2x2<f32>) -> mat2x2<f32> { return subgroupMax(m); }",
+        "fn f(b: bool) -> bool { return subgroupBroadcastFirst(b); }",
+    ] {
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let error = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::SUBGROUP,
+        )
+        .validate(&module)
+        .expect_err("subgroup operand should be invalid")
+        .into_inner();
+        assert!(
+            matches!(
+                error,
+                naga::valid::ValidationError::Function {
+                    source: naga::valid::FunctionError::InvalidSubgroup(
+                        naga::valid::SubgroupError::InvalidOperand(_)
+                    ),
+                    ..
+                }
+            ),
+            "{source}: {error:?}"
+        );
+    }
+}
+
+#[test]
+fn subgroup_shuffle_signed_index() {
+    check(
+        r#"
+            fn f(x: u32, i: i32) -> u32 {
+                return subgroupShuffle(x, i);
+            }
+        "#,
+        r#"error: automatic conversions cannot convert `i32` to `u32`
+  ┌─ wgsl:2:26
+  │
+2 │             fn f(x: u32, i: i32) -> u32 {
+  │                          ^^^^^^ this expression has type i32
+3 │                 return subgroupShuffle(x, i);
+  │                        ^^^^^^^^^^^^^^^ a value of type u32 is required here
+
+"#,
+    );
+}
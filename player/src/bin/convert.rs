@@ -0,0 +1,72 @@
+/*! Converts traces between the RON and binary formats.
+
+A RON trace directory becomes a binary trace file, and a binary trace
+becomes a RON trace directory, which is easier to read and diff.
+!*/
+
+#[cfg(not(target_arch = "wasm32"))]
+const HELP: &str = "\
+Usage: convert [OPTIONS] <INPUT> <OUTPUT>
+
+Converts the RON trace directory INPUT into the binary trace file OUTPUT, or
+the binary trace INPUT into the RON trace directory OUTPUT.
+
+Options:
+  -h, --help      Print this help message.
+  -c, --compress  Compress the binary trace.
+";
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use std::path::PathBuf;
+    use wgc::device::trace;
+
+    let mut compress = false;
+    let mut paths = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{HELP}");
+                return;
+            }
+            "-c" | "--compress" => compress = true,
+            _ if arg.starts_with('-') => {
+                eprintln!("Unknown option \"{arg}\"\n\n{HELP}");
+                std::process::exit(101);
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let (input, output) = match <[PathBuf; 2]>::try_from(paths) {
+        Ok([input, output]) => (input, output),
+        Err(_) => {
+            eprintln!("Provide the input and output as parameters\n\n{HELP}");
+            std::process::exit(101);
+        }
+    };
+
+    env_logger::init();
+
+    let result = if input.join(trace::FILE_NAME).exists() {
+        log::info!("Converting {:?} to binary", input);
+        player::convert::ron_to_binary(&input, &output, compress)
+    } else {
+        log::info!("Converting {:?} to RON", input);
+        let input = if input.is_dir() {
+            input.join(trace::BINARY_FILE_NAME)
+        } else {
+            input
+        };
+        player::convert::binary_to_ron(&input, &output)
+    };
+    match result {
+        Ok(count) => println!("Converted {count} actions"),
+        Err(e) => {
+            eprintln!("Conversion failed: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -0,0 +1,55 @@
+/*! Compares two traces by what they create and submit, rather than textually.
+
+Exits with 1 if the traces differ, like `diff` does.
+!*/
+
+#[cfg(not(target_arch = "wasm32"))]
+const HELP: &str = "\
+Usage: diff <OLD_TRACE> <NEW_TRACE>
+
+Prints the resources that are created differently, and the actions and commands
+that differ. Each trace is a directory recorded by wgpu, or a binary trace file.
+";
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use player::{diff::TraceDiff, file::TraceFile};
+    use std::path::PathBuf;
+
+    let paths = std::env::args().skip(1).collect::<Vec<_>>();
+    if paths.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{HELP}");
+        return;
+    }
+    let (old, new) = match <[String; 2]>::try_from(paths) {
+        Ok([old, new]) => (PathBuf::from(old), PathBuf::from(new)),
+        Err(_) => {
+            eprintln!("Provide the two traces as parameters\n\n{HELP}");
+            std::process::exit(101);
+        }
+    };
+
+    env_logger::init();
+
+    let load = |path: &PathBuf| {
+        let mut trace = TraceFile::open(path, None)
+            .unwrap_or_else(|e| panic!("Unable to open the trace {path:?}: {e}"));
+        let actions = trace
+            .by_ref()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_or_else(|e| panic!("Unable to read the trace {path:?}: {e}"));
+        (trace, actions)
+    };
+    let (old_trace, old_actions) = load(&old);
+    let (new_trace, new_actions) = load(&new);
+
+    let diff = TraceDiff::new(&old_actions, &old_trace, &new_actions, &new_trace)
+        .unwrap_or_else(|e| panic!("Unable to compare the traces: {e}"));
+    if !diff.is_empty() {
+        print!("{diff}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -0,0 +1,96 @@
+/*! Reduces a trace that runs into an error to a smaller one with the same error.
+
+Actions are removed by delta debugging: the trace is replayed without a
+chunk of actions, and everything that depends on them, and the chunk stays
+out if the error still happens.
+!*/
+
+#[cfg(not(target_arch = "wasm32"))]
+const HELP: &str = "\
+Usage: minimize [OPTIONS] <TRACE> <OUTPUT_DIR>
+
+Writes the smallest trace found that reproduces the error as a RON trace to OUTPUT_DIR.
+
+Options:
+  -h, --help            Print this help message.
+  -b, --backend <NAME>  Replay on this backend instead of the one the trace was recorded
+                        on. Replaying on empty is quick, but only finds validation errors.
+      --action <N>      Reproduce the error of action N, instead of the first error.
+";
+
+#[cfg(not(target_arch = "wasm32"))]
+fn exit_with_help(message: &str) -> ! {
+    eprintln!("{message}\n\n{HELP}");
+    std::process::exit(101);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use player::{convert::RonWriter, file::TraceFile};
+    use std::path::PathBuf;
+
+    let mut backend = None;
+    let mut action = None;
+    let mut paths = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{HELP}");
+                return;
+            }
+            "-b" | "--backend" => {
+                let name = args.next().unwrap_or_default();
+                backend = Some(
+                    player::backend_from_name(&name)
+                        .unwrap_or_else(|| exit_with_help(&format!("Unknown backend \"{name}\""))),
+                );
+            }
+            "--action" => {
+                let value = args.next().unwrap_or_default();
+                action = Some(value.parse::<usize>().unwrap_or_else(|_| {
+                    exit_with_help(&format!("Invalid value for --action: \"{value}\""))
+                }));
+            }
+            _ if arg.starts_with('-') => exit_with_help(&format!("Unknown option \"{arg}\"")),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let (input, output) = match <[PathBuf; 2]>::try_from(paths) {
+        Ok([input, output]) => (input, output),
+        Err(_) => exit_with_help("Provide the trace and the output directory as parameters"),
+    };
+
+    env_logger::init();
+
+    let mut trace = TraceFile::open(&input, backend)
+        .unwrap_or_else(|e| panic!("Unable to open the trace: {e}"));
+    let actions = trace
+        .by_ref()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_else(|e| panic!("Unable to read the trace: {e}"));
+
+    let minimized = match player::minimize::minimize(&actions, &trace, backend, action) {
+        Ok(minimized) => minimized,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    println!(
+        "Reduced {} actions to {} in {} replays, still failing with:\n{}",
+        actions.len(),
+        minimized.actions.len(),
+        minimized.replays,
+        minimized.failure
+    );
+
+    let mut writer = RonWriter::new(&output).unwrap();
+    for action in minimized.actions {
+        writer.add(action, &trace).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
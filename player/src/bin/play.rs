@@ -1,16 +1,128 @@
 /*! This is a player for WebGPU traces.
+
+Every action that fails is printed along with its error, and the player
+exits with an error code if there were any.
 !*/
 
+#[cfg(not(target_arch = "wasm32"))]
+const HELP: &str = "\
+Usage: play [OPTIONS] <TRACE>
+
+The trace is a directory recorded by wgpu, or a binary trace file.
+
+Options:
+  -h, --help                Print this help message.
+  -b, --backend <NAME>      Replay on this backend instead of the one the trace was
+                            recorded on: vulkan, metal, dx12, dx11, gl, cpu, or empty,
+                            which only validates.
+      --stop-at-action <N>  Stop after replaying action N, counting from `Init` as 0.
+      --stop-at-frame <N>   Stop after presenting frame N.
+  -r, --report <PATH>       Write a JSON report of the created resources and failed
+                            actions to this file. (\"-\" writes to stdout)
+";
+
+#[cfg(not(target_arch = "wasm32"))]
+fn exit_with_help() -> ! {
+    eprintln!("{HELP}");
+    std::process::exit(101);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct Options {
+    path: std::path::PathBuf,
+    backend: Option<wgt::Backend>,
+    stop_at_action: Option<usize>,
+    stop_at_frame: Option<u32>,
+    report: Option<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Options {
+    fn from_args() -> Result<Self, String> {
+        fn parse<T: std::str::FromStr>(option: &str, value: Option<String>) -> Result<T, String> {
+            let value = value.ok_or_else(|| format!("Missing value for {option}"))?;
+            value
+                .parse()
+                .map_err(|_| format!("Invalid value for {option}: \"{value}\""))
+        }
+
+        let mut path = None;
+        let mut backend = None;
+        let mut stop_at_action = None;
+        let mut stop_at_frame = None;
+        let mut report = None;
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => exit_with_help(),
+                "-b" | "--backend" => {
+                    let name: String = parse(&arg, args.next())?;
+                    let backend_by_name = player::backend_from_name(&name);
+                    backend = Some(backend_by_name.ok_or(format!("Unknown backend \"{name}\""))?);
+                }
+                "--stop-at-action" => stop_at_action = Some(parse(&arg, args.next())?),
+                "--stop-at-frame" => stop_at_frame = Some(parse(&arg, args.next())?),
+                "-r" | "--report" => report = Some(parse(&arg, args.next())?),
+                _ if arg.starts_with('-') => return Err(format!("Unknown option \"{arg}\"")),
+                _ if path.is_none() => path = Some(std::path::PathBuf::from(arg)),
+                _ => return Err(format!("Unexpected argument \"{arg}\"")),
+            }
+        }
+
+        let path = path.ok_or("Provide the trace as the parameter")?;
+        if !path.exists() {
+            return Err(format!("{path:?} does not exist"));
+        }
+        Ok(Self {
+            path,
+            backend,
+            stop_at_action,
+            stop_at_frame,
+            report,
+        })
+    }
+
+    fn should_stop(&self, next_action: usize, frame_count: u32) -> bool {
+        self.stop_at_action.is_some_and(|n| next_action > n)
+            || self.stop_at_frame.is_some_and(|n| frame_count >= n)
+    }
+}
+
+/// Write out the report, and exit if any of the actions failed.
+#[cfg(not(target_arch = "wasm32"))]
+fn finish(
+    report: &mut player::report::Report,
+    trace: &mut player::file::TraceFile,
+    path: Option<&str>,
+) {
+    match trace.skip_to_end() {
+        Ok(count) => report.actions = count,
+        Err(e) => log::warn!("Unable to count the actions left: {e}"),
+    }
+    if let Some(path) = path {
+        let json = report.to_json().unwrap();
+        if path == "-" {
+            println!("{json}");
+        } else if let Err(e) = std::fs::write(path, json) {
+            panic!("Could not write the report to \"{path}\": {e}");
+        }
+    }
+    if !report.failures.is_empty() {
+        eprintln!(
+            "{} of {} actions failed",
+            report.failures.len(),
+            report.executed
+        );
+        std::process::exit(1);
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 fn main() {
-    use player::{GlobalPlay as _, IdentityPassThroughFactory};
+    use player::{file::TraceFile, report, GlobalPlay as _, IdentityPassThroughFactory};
     use wgc::{device::trace, gfx_select};
 
-    use std::{
-        fs,
-        path::{Path, PathBuf},
-    };
-
     #[cfg(feature = "winit")]
     use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
     #[cfg(feature = "winit")]
@@ -21,21 +133,27 @@ fn main() {
         window::WindowBuilder,
     };
 
-    env_logger::init();
+    let options = Options::from_args().unwrap_or_else(|e| {
+        eprintln!("{e}\n");
+        exit_with_help()
+    });
 
-    //TODO: setting for the backend bits
-    //TODO: setting for the target frame, or controls
+    env_logger::init();
 
-    let dir = match std::env::args().nth(1) {
-        Some(arg) if Path::new(&arg).is_dir() => PathBuf::from(arg),
-        _ => panic!("Provide the dir path as the parameter"),
+    log::info!("Loading trace '{:?}'", options.path);
+    let mut trace = TraceFile::open(&options.path, options.backend)
+        .unwrap_or_else(|e| panic!("Unable to open the trace: {e}"));
+    let next_action = |trace: &mut TraceFile| {
+        let index = trace.next_index();
+        trace
+            .next()
+            .map(|action| action.unwrap_or_else(|e| panic!("Unable to read action #{index}: {e}")))
     };
 
-    log::info!("Loading trace '{:?}'", dir);
-    let file = fs::File::open(dir.join(trace::FILE_NAME)).unwrap();
-    let mut actions: Vec<trace::Action> = ron::de::from_reader(file).unwrap();
-    actions.reverse(); // allows us to pop from the top
-    log::info!("Found {} actions", actions.len());
+    let (desc, backend) = match next_action(&mut trace) {
+        Some(trace::Action::Init { desc, backend }) => (desc, options.backend.unwrap_or(backend)),
+        _ => panic!("Expected Action::Init"),
+    };
 
     #[cfg(feature = "winit")]
     let event_loop = {
@@ -52,7 +170,16 @@ fn main() {
     let global = wgc::global::Global::new(
         "player",
         IdentityPassThroughFactory,
-        wgt::InstanceDescriptor::default(),
+        wgt::InstanceDescriptor {
+            backends: backend.into(),
+            // The empty backend supports exactly what the trace asks for.
+            empty_backend_options: wgt::EmptyBackendOptions {
+                enable: true,
+                features: desc.required_features,
+                limits: desc.required_limits.clone(),
+            },
+            ..Default::default()
+        },
     );
     let mut command_buffer_id_manager = wgc::identity::IdentityManager::new();
 
@@ -65,42 +192,44 @@ fn main() {
         )
     };
 
-    let device = match actions.pop() {
-        Some(trace::Action::Init { desc, backend }) => {
-            log::info!("Initializing the device for backend: {:?}", backend);
-            let adapter = global
-                .request_adapter(
-                    &wgc::instance::RequestAdapterOptions {
-                        power_preference: wgt::PowerPreference::None,
-                        force_fallback_adapter: false,
-                        #[cfg(feature = "winit")]
-                        compatible_surface: Some(surface),
-                        #[cfg(not(feature = "winit"))]
-                        compatible_surface: None,
-                    },
-                    wgc::instance::AdapterInputs::IdSet(
-                        &[wgc::id::TypedId::zip(0, 0, backend)],
-                        |id| id.backend(),
-                    ),
-                )
-                .expect("Unable to find an adapter for selected backend");
-
-            let info = gfx_select!(adapter => global.adapter_get_info(adapter)).unwrap();
-            log::info!("Picked '{}'", info.name);
-            let id = wgc::id::TypedId::zip(1, 0, backend);
-            let (_, _, error) = gfx_select!(adapter => global.adapter_request_device(
-                adapter,
-                &desc,
-                None,
-                id,
-                id
-            ));
-            if let Some(e) = error {
-                panic!("{:?}", e);
-            }
-            id
-        }
-        _ => panic!("Expected Action::Init"),
+    log::info!("Initializing the device for backend: {:?}", backend);
+    let adapter = global
+        .request_adapter(
+            &wgc::instance::RequestAdapterOptions {
+                power_preference: wgt::PowerPreference::None,
+                force_fallback_adapter: false,
+                #[cfg(feature = "winit")]
+                compatible_surface: Some(surface),
+                #[cfg(not(feature = "winit"))]
+                compatible_surface: None,
+            },
+            // `Backend::Empty` is zero, so the epoch must not be.
+            wgc::instance::AdapterInputs::IdSet(&[wgc::id::TypedId::zip(0, 1, backend)], |id| {
+                id.backend()
+            }),
+        )
+        .expect("Unable to find an adapter for selected backend");
+
+    let info = gfx_select!(adapter => global.adapter_get_info(adapter)).unwrap();
+    log::info!("Picked '{}'", info.name);
+    let device = wgc::id::TypedId::zip(1, 0, backend);
+    let (_, _, error) = gfx_select!(adapter => global.adapter_request_device(
+        adapter,
+        &desc,
+        None,
+        device,
+        device
+    ));
+    if let Some(e) = error {
+        panic!("{:?}", e);
+    }
+
+    let mut report = report::Report {
+        backend: backend.to_str().to_owned(),
+        adapter: info.name,
+        // `Init` has been replayed already.
+        executed: 1,
+        ..Default::default()
     };
 
     log::info!("Executing actions");
@@ -108,12 +237,41 @@ fn main() {
     {
         gfx_select!(device => global.device_start_capture(device));
 
-        while let Some(action) = actions.pop() {
-            gfx_select!(device => global.process(device, action, &dir, &mut command_buffer_id_manager));
+        loop {
+            let index = trace.next_index();
+            if options.should_stop(index, report.frames) {
+                eprintln!("Stopped before action {index}");
+                break;
+            }
+            let action = match next_action(&mut trace) {
+                Some(action) => action,
+                None => break,
+            };
+            let info = report::ActionInfo::new(index, &action);
+            let result = match action {
+                // There is no surface to present to, but the frames still count.
+                trace::Action::Present(_) => {
+                    report.frames += 1;
+                    Ok(())
+                }
+                trace::Action::ConfigureSurface(..)
+                | trace::Action::GetSurfaceTexture { .. }
+                | trace::Action::DiscardSurfaceTexture(_) => {
+                    log::warn!("Skipping surface action #{index}: winit feature is not enabled");
+                    Ok(())
+                }
+                action => {
+                    gfx_select!(device => global.process(device, action, &trace, &mut command_buffer_id_manager))
+                }
+            };
+            if let Some(failure) = report.record(index, info, result) {
+                eprintln!("{failure}");
+            }
         }
 
         gfx_select!(device => global.device_stop_capture(device));
         gfx_select!(device => global.device_poll(device, wgt::Maintain::Wait)).unwrap();
+        finish(&mut report, &mut trace, options.report.as_deref());
     }
     #[cfg(feature = "winit")]
     {
@@ -123,7 +281,6 @@ fn main() {
         };
 
         let mut resize_config = None;
-        let mut frame_count = 0;
         let mut done = false;
         event_loop.run(move |event, target| {
             target.set_control_flow(ControlFlow::Poll);
@@ -131,8 +288,15 @@ fn main() {
             match event {
                 Event::WindowEvent { event, .. } => match event {
                     WindowEvent::RedrawRequested if resize_config.is_none() => {
+                    let index = trace.next_index();
+                    let action = if options.should_stop(index, report.frames) {
+                        None
+                    } else {
+                        next_action(&mut trace)
+                    };
+                    let info = action.as_ref().map(|action| report::ActionInfo::new(index, action));
 
-                    match actions.pop() {
+                    let result = match action {
                         Some(trace::Action::ConfigureSurface(_device_id, config)) => {
                             log::info!("Configuring the surface");
                             let current_size: (u32, u32) = window.inner_size().into();
@@ -144,33 +308,40 @@ fn main() {
                                 ));
                                 resize_config = Some(config);
                                 target.exit();
+                                Ok(())
                             } else {
                                 let error = gfx_select!(device => global.surface_configure(surface, device, &config));
-                                if let Some(e) = error {
-                                    panic!("{:?}", e);
-                                }
+                                error.map_or(Ok(()), |e| Err(e.into()))
                             }
                         }
                         Some(trace::Action::Present(id)) => {
-                            frame_count += 1;
-                            log::debug!("Presenting frame {}", frame_count);
-                            gfx_select!(device => global.surface_present(id)).unwrap();
+                            report.frames += 1;
+                            log::debug!("Presenting frame {}", report.frames);
+                            let result = gfx_select!(device => global.surface_present(id));
                                 target.exit();
+                            result.map(|_| ()).map_err(Into::into)
                         }
                         Some(trace::Action::DiscardSurfaceTexture(id)) => {
-                            log::debug!("Discarding frame {}", frame_count);
-                            gfx_select!(device => global.surface_texture_discard(id)).unwrap();
+                            log::debug!("Discarding frame {}", report.frames);
+                            let result = gfx_select!(device => global.surface_texture_discard(id));
                                 target.exit();
+                            result.map_err(Into::into)
                         }
                         Some(action) => {
-                            gfx_select!(device => global.process(device, action, &dir, &mut command_buffer_id_manager));
+                            gfx_select!(device => global.process(device, action, &trace, &mut command_buffer_id_manager))
                         }
                         None => {
                             if !done {
-                                println!("Finished the end at frame {}", frame_count);
+                                println!("Finished the end at frame {}", report.frames);
                                 done = true;
                             }
                                 target.exit();
+                            Ok(())
+                        }
+                    };
+                    if let Some(info) = info {
+                        if let Some(failure) = report.record(index, info, result) {
+                            eprintln!("{failure}");
                         }
                     }
                     },
@@ -196,6 +367,7 @@ fn main() {
                 Event::LoopExiting => {
                     log::info!("Closing");
                     gfx_select!(device => global.device_poll(device, wgt::Maintain::Wait)).unwrap();
+                    finish(&mut report, &mut trace, options.report.as_deref());
                 }
                 _ => {}
             }
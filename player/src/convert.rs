@@ -0,0 +1,121 @@
+//! Conversion between the RON and binary trace formats.
+//!
+//! Binary traces are smaller and can be streamed, but RON traces are what
+//! you want to read or diff.
+
+use wgc::device::trace::{self, binary};
+
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+};
+
+use crate::{file::Blobs, ActionError};
+
+/// The name of the data that `action` refers to, if any.
+pub fn data_name_mut<'a>(action: &'a mut trace::Action) -> Option<&'a mut String> {
+    match *action {
+        trace::Action::CreateShaderModule { ref mut data, .. }
+        | trace::Action::WriteBuffer { ref mut data, .. }
+        | trace::Action::WriteTexture { ref mut data, .. } => Some(data),
+        _ => None,
+    }
+}
+
+/// Write `actions` to `writer`, along with the data they refer to, which is loaded from `blobs`.
+///
+/// The actions are updated to refer to the blobs in the binary trace.
+pub fn write_binary(
+    actions: &mut [trace::Action],
+    blobs: &(impl Blobs + ?Sized),
+    writer: &mut binary::Writer,
+) -> Result<(), ActionError> {
+    for action in actions {
+        if let Some(name) = data_name_mut(action) {
+            let kind = Path::new(name.as_str())
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .unwrap_or("bin");
+            let data = blobs.load(name)?;
+            *name = writer.blob(kind, &data)?;
+        }
+        writer.action(action)?;
+    }
+    Ok(writer.flush()?)
+}
+
+/// Convert the RON trace in the directory `input` into a binary trace file `output`.
+///
+/// Returns the number of actions converted.
+pub fn ron_to_binary(input: &Path, output: &Path, compress: bool) -> Result<usize, ActionError> {
+    let source = fs::read_to_string(input.join(trace::FILE_NAME))?;
+    let mut actions: Vec<trace::Action> = ron::de::from_str(&source)?;
+    let file = fs::File::create(output)?;
+    let mut writer = binary::Writer::new(io::BufWriter::new(file), compress)?;
+    write_binary(&mut actions, input, &mut writer)?;
+    Ok(actions.len())
+}
+
+/// Writes a RON trace directory, laid out like the ones `wgpu` records.
+pub struct RonWriter {
+    dir: PathBuf,
+    file: io::BufWriter<fs::File>,
+    config: ron::ser::PrettyConfig,
+    /// Names of the data files written so far.
+    written: HashSet<String>,
+    count: usize,
+}
+
+impl RonWriter {
+    pub fn new(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let mut file = io::BufWriter::new(fs::File::create(dir.join(trace::FILE_NAME))?);
+        file.write_all(b"[\n")?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file,
+            config: ron::ser::PrettyConfig::default(),
+            written: HashSet::new(),
+            count: 0,
+        })
+    }
+
+    /// Write `action`, along with the data it refers to, which is loaded from `blobs`.
+    pub fn add(
+        &mut self,
+        mut action: trace::Action,
+        blobs: &(impl Blobs + ?Sized),
+    ) -> Result<(), ActionError> {
+        if let Some(name) = data_name_mut(&mut action) {
+            // Data that is referred to more than once is written once.
+            if self.written.insert(name.clone()) {
+                fs::write(self.dir.join(name.as_str()), blobs.load(name)?)?;
+            }
+        }
+        let string = ron::ser::to_string_pretty(&action, self.config.clone())?;
+        writeln!(self.file, "{},", string)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Close the trace, returning the number of actions in it.
+    pub fn finish(mut self) -> io::Result<usize> {
+        self.file.write_all(b"]")?;
+        self.file.flush()?;
+        Ok(self.count)
+    }
+}
+
+/// Convert the binary trace file `input` into a RON trace in the directory `output`.
+///
+/// Returns the number of actions converted.
+pub fn binary_to_ron(input: &Path, output: &Path) -> Result<usize, ActionError> {
+    let mut reader = binary::Reader::open(input)?;
+    let mut writer = RonWriter::new(output)?;
+    while let Some(action) = reader.next_action()? {
+        writer.add(action, &reader)?;
+    }
+    Ok(writer.finish()?)
+}
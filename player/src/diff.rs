@@ -0,0 +1,254 @@
+//! Semantic comparison of traces.
+//!
+//! Ids depend on the order resources were created and freed in, so they are
+//! replaced by names made of the kind and label of the resource, and the
+//! number of such resources created before it. Data is replaced by its size
+//! and a hash of its contents. Resources are then matched by name, and the
+//! rest of the actions are compared as a sequence, with each command of a
+//! submission on its own line.
+
+use wgc::device::trace;
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    fmt,
+    hash::{Hash as _, Hasher as _},
+};
+
+use crate::{convert::data_name_mut, file::Blobs, replace_ids, report::ActionInfo, ActionError};
+
+/// A resource whose creation differs between the traces.
+#[derive(Debug)]
+pub struct ResourceChange {
+    pub name: String,
+    /// How the first trace creates the resource, if it does.
+    pub old: Option<String>,
+    /// How the second trace creates the resource, if it does.
+    pub new: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Line {
+    Same(String),
+    /// Only in the first trace.
+    Removed(String),
+    /// Only in the second trace.
+    Added(String),
+}
+
+#[derive(Debug)]
+pub struct TraceDiff {
+    pub resources: Vec<ResourceChange>,
+    /// The actions that don't create resources, and the commands they submit.
+    pub lines: Vec<Line>,
+}
+
+impl TraceDiff {
+    pub fn new(
+        old: &[trace::Action],
+        old_blobs: &(impl Blobs + ?Sized),
+        new: &[trace::Action],
+        new_blobs: &(impl Blobs + ?Sized),
+    ) -> Result<Self, ActionError> {
+        let old = Canonical::new(old, old_blobs)?;
+        let new = Canonical::new(new, new_blobs)?;
+
+        let new_resources = new.resources.iter().cloned().collect::<HashMap<_, _>>();
+        let old_names = old
+            .resources
+            .iter()
+            .map(|(name, _)| name)
+            .collect::<HashSet<_>>();
+        let mut resources = Vec::new();
+        for (name, source) in &old.resources {
+            match new_resources.get(name) {
+                Some(new_source) if new_source == source => {}
+                new_source => resources.push(ResourceChange {
+                    name: name.clone(),
+                    old: Some(source.clone()),
+                    new: new_source.cloned(),
+                }),
+            }
+        }
+        for (name, source) in &new.resources {
+            if !old_names.contains(name) {
+                resources.push(ResourceChange {
+                    name: name.clone(),
+                    old: None,
+                    new: Some(source.clone()),
+                });
+            }
+        }
+
+        Ok(Self {
+            resources,
+            lines: diff_lines(old.lines, new.lines),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty() && self.lines.iter().all(|line| matches!(*line, Line::Same(_)))
+    }
+}
+
+/// Only the changes are printed, like `diff` does.
+impl fmt::Display for TraceDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.resources.is_empty() {
+            writeln!(f, "Resources:")?;
+        }
+        for change in &self.resources {
+            writeln!(f, "  {}", change.name)?;
+            if let Some(ref old) = change.old {
+                writeln!(f, "-     {old}")?;
+            }
+            if let Some(ref new) = change.new {
+                writeln!(f, "+     {new}")?;
+            }
+        }
+
+        let mut position = (0, 0);
+        let mut in_hunk = false;
+        for line in &self.lines {
+            let (sign, text) = match *line {
+                Line::Same(_) => {
+                    position.0 += 1;
+                    position.1 += 1;
+                    in_hunk = false;
+                    continue;
+                }
+                Line::Removed(ref text) => ('-', text),
+                Line::Added(ref text) => ('+', text),
+            };
+            if !in_hunk {
+                writeln!(f, "Actions at line {} / {}:", position.0, position.1)?;
+                in_hunk = true;
+            }
+            match sign {
+                '-' => position.0 += 1,
+                _ => position.1 += 1,
+            }
+            writeln!(f, "{sign} {text}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A trace with the ids and data names replaced.
+struct Canonical {
+    /// The creating action of each resource, by name.
+    resources: Vec<(String, String)>,
+    lines: Vec<String>,
+}
+
+impl Canonical {
+    fn new(actions: &[trace::Action], blobs: &(impl Blobs + ?Sized)) -> Result<Self, ActionError> {
+        let mut names = HashMap::new();
+        let mut counts = HashMap::new();
+        let mut implicit_count = 0;
+        let mut resources = Vec::new();
+        let mut lines = Vec::new();
+
+        for (index, action) in actions.iter().enumerate() {
+            let info = ActionInfo::new(index, action);
+            let created = info.created().map(|resource| {
+                let label = resource.label.as_deref().unwrap_or_default();
+                let count = counts.entry((resource.kind, label.to_owned())).or_insert(0);
+                let name = match resource.label {
+                    Some(ref label) => format!("{}({:?})#{}", resource.kind, label, count),
+                    None => format!("{}#{}", resource.kind, count),
+                };
+                *count += 1;
+                names.insert((resource.index, resource.epoch), name.clone());
+                name
+            });
+
+            // Data names depend on the format and the order of the writes.
+            let mut action: trace::Action = ron::de::from_str(&ron::ser::to_string(action)?)?;
+            if let Some(name) = data_name_mut(&mut action) {
+                let data = blobs.load(name)?;
+                let mut hasher = DefaultHasher::new();
+                data.hash(&mut hasher);
+                *name = format!("<{} bytes, {:016x}>", data.len(), hasher.finish());
+            }
+
+            let mut canonical = |source: String| {
+                replace_ids(&source, |index, epoch, _| {
+                    // Ids that no action created are implicit pipeline layouts.
+                    let name = names.entry((index, epoch)).or_insert_with(|| {
+                        implicit_count += 1;
+                        format!("Implicit#{}", implicit_count - 1)
+                    });
+                    Some(name.clone())
+                })
+            };
+            match (created, action) {
+                (Some(name), action) => {
+                    resources.push((name, canonical(ron::ser::to_string(&action)?)));
+                }
+                (None, trace::Action::Submit(_, commands)) => {
+                    lines.push("Submit".to_string());
+                    for command in commands {
+                        lines.push(format!("    {}", canonical(ron::ser::to_string(&command)?)));
+                    }
+                }
+                (None, action) => lines.push(canonical(ron::ser::to_string(&action)?)),
+            }
+        }
+
+        Ok(Self { resources, lines })
+    }
+}
+
+/// Line up `old` and `new` along their longest common subsequence.
+fn diff_lines(old: Vec<String>, new: Vec<String>) -> Vec<Line> {
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_middle, new_middle) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    // `lengths[i][j]` is the length of the longest common subsequence of
+    // `old_middle[i..]` and `new_middle[j..]`.
+    let width = new_middle.len() + 1;
+    let mut lengths = vec![0u32; (old_middle.len() + 1) * width];
+    for i in (0..old_middle.len()).rev() {
+        for j in (0..new_middle.len()).rev() {
+            lengths[i * width + j] = if old_middle[i] == new_middle[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut lines = old[..prefix]
+        .iter()
+        .cloned()
+        .map(Line::Same)
+        .collect::<Vec<_>>();
+    let (mut i, mut j) = (0, 0);
+    while i < old_middle.len() || j < new_middle.len() {
+        if i < old_middle.len() && j < new_middle.len() && old_middle[i] == new_middle[j] {
+            lines.push(Line::Same(old_middle[i].clone()));
+            i += 1;
+            j += 1;
+        } else if i < old_middle.len()
+            && (j == new_middle.len() || lengths[(i + 1) * width + j] >= lengths[i * width + j + 1])
+        {
+            lines.push(Line::Removed(old_middle[i].clone()));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new_middle[j].clone()));
+            j += 1;
+        }
+    }
+    lines.extend(old[old.len() - suffix..].iter().cloned().map(Line::Same));
+    lines
+}
@@ -0,0 +1,140 @@
+//! Reading traces in either format, one action at a time.
+
+use wgc::device::trace::{self, binary};
+
+use std::{
+    borrow::Cow,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::ActionError;
+
+/// Where the data that actions refer to by name is loaded from.
+pub trait Blobs {
+    fn load(&self, name: &str) -> io::Result<Cow<'_, [u8]>>;
+}
+
+/// A trace directory, with the data in files next to the trace.
+impl Blobs for Path {
+    fn load(&self, name: &str) -> io::Result<Cow<'_, [u8]>> {
+        fs::read(self.join(name)).map(Cow::Owned)
+    }
+}
+
+impl Blobs for binary::Reader {
+    fn load(&self, name: &str) -> io::Result<Cow<'_, [u8]>> {
+        self.blob(name).map(Cow::Borrowed).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No blob named \"{name}\" precedes the action"),
+            )
+        })
+    }
+}
+
+enum Actions {
+    /// RON can only be parsed as a whole.
+    Ron {
+        dir: PathBuf,
+        actions: std::vec::IntoIter<trace::Action<'static>>,
+    },
+    Binary(binary::Reader),
+}
+
+/// A trace that is being replayed.
+pub struct TraceFile {
+    actions: Actions,
+    /// The backend to retarget the actions of a binary trace to.
+    backend: Option<wgt::Backend>,
+    next_index: usize,
+}
+
+impl TraceFile {
+    /// Open a binary trace file, or a trace directory in either format.
+    ///
+    /// If `backend` is set, the ids of the actions are rewritten to use it,
+    /// see [`retarget_trace`](crate::retarget_trace).
+    pub fn open(path: &Path, backend: Option<wgt::Backend>) -> Result<Self, ActionError> {
+        let binary_path = if path.is_dir() {
+            path.join(trace::BINARY_FILE_NAME)
+        } else {
+            path.to_path_buf()
+        };
+        let ron_path = path.join(trace::FILE_NAME);
+        let actions = if path.is_dir() && (ron_path.exists() || !binary_path.exists()) {
+            let mut source = fs::read_to_string(ron_path)?;
+            if let Some(backend) = backend {
+                source = crate::retarget_trace(&source, backend);
+            }
+            let actions: Vec<trace::Action> = ron::de::from_str(&source)?;
+            Actions::Ron {
+                dir: path.to_path_buf(),
+                actions: actions.into_iter(),
+            }
+        } else {
+            Actions::Binary(binary::Reader::open(&binary_path)?)
+        };
+        Ok(Self {
+            actions,
+            backend,
+            next_index: 0,
+        })
+    }
+
+    /// Index of the next action in the trace.
+    pub fn next_index(&self) -> usize {
+        self.next_index
+    }
+
+    /// Skip the actions that are left, and return the number of actions in the trace.
+    pub fn skip_to_end(&mut self) -> Result<usize, ActionError> {
+        let remaining = match self.actions {
+            Actions::Ron {
+                ref mut actions, ..
+            } => actions.by_ref().count(),
+            Actions::Binary(ref mut reader) => reader.skip_to_end()?,
+        };
+        self.next_index += remaining;
+        Ok(self.next_index)
+    }
+}
+
+impl Iterator for TraceFile {
+    type Item = Result<trace::Action<'static>, ActionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let action = match self.actions {
+            Actions::Ron {
+                ref mut actions, ..
+            } => Ok(actions.next()?),
+            Actions::Binary(ref mut reader) => match reader.next_action() {
+                Ok(None) => return None,
+                Ok(Some(action)) => match self.backend {
+                    Some(backend) => retarget_action(action, backend),
+                    None => Ok(action),
+                },
+                Err(e) => Err(e.into()),
+            },
+        };
+        self.next_index += 1;
+        Some(action)
+    }
+}
+
+impl Blobs for TraceFile {
+    fn load(&self, name: &str) -> io::Result<Cow<'_, [u8]>> {
+        match self.actions {
+            Actions::Ron { ref dir, .. } => dir.load(name),
+            Actions::Binary(ref reader) => reader.load(name),
+        }
+    }
+}
+
+fn retarget_action(
+    action: trace::Action,
+    backend: wgt::Backend,
+) -> Result<trace::Action<'static>, ActionError> {
+    let source = ron::ser::to_string(&action)?;
+    Ok(ron::de::from_str(&crate::retarget_trace(&source, backend))?)
+}
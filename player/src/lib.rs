@@ -10,7 +10,13 @@
 
 use wgc::device::trace;
 
-use std::{borrow::Cow, fs, path::Path};
+use std::borrow::Cow;
+
+pub mod convert;
+pub mod diff;
+pub mod file;
+pub mod minimize;
+pub mod report;
 
 pub struct IdentityPassThroughFactory;
 
@@ -27,19 +33,180 @@ impl<I: wgc::id::TypedId> wgc::identity::IdentityHandlerFactory<I> for IdentityP
 }
 impl wgc::identity::GlobalIdentityHandlerFactory for IdentityPassThroughFactory {}
 
+/// Rewrite the backend of every id in the RON `trace`, so that it can be
+/// replayed on `backend` instead of the one it was recorded on.
+pub fn retarget_trace(trace: &str, backend: wgt::Backend) -> String {
+    let backend = format!("{backend:?}");
+    replace_ids(trace, |index, epoch, _| {
+        Some(format!("Id({index}, {epoch}, {backend})"))
+    })
+}
+
+/// The backend with the name [`wgt::Backend::to_str`] returns, ignoring case.
+pub fn backend_from_name(name: &str) -> Option<wgt::Backend> {
+    [
+        wgt::Backend::Vulkan,
+        wgt::Backend::Metal,
+        wgt::Backend::Dx12,
+        wgt::Backend::Dx11,
+        wgt::Backend::Gl,
+        wgt::Backend::Cpu,
+        wgt::Backend::Empty,
+    ]
+    .into_iter()
+    .find(|backend| backend.to_str() == name.to_lowercase())
+}
+
+/// Call `replace` with the index, epoch and backend name of every id in the
+/// RON `trace`, replacing the id with the text it returns, if any.
+pub fn replace_ids(
+    trace: &str,
+    mut replace: impl FnMut(u32, u32, &str) -> Option<String>,
+) -> String {
+    let mut result = String::with_capacity(trace.len());
+    let mut rest = trace;
+    while let Some(start) = rest.find("Id(") {
+        let is_id = !rest[..start].ends_with(|c: char| c.is_alphanumeric() || c == '_');
+        let (before, after) = rest.split_at(start);
+        result.push_str(before);
+        rest = after;
+
+        // Ids are written as `Id(index, epoch, Backend)`.
+        let end = match rest.find(')') {
+            Some(end) if is_id => end,
+            _ => {
+                result.push_str("Id(");
+                rest = &rest["Id(".len()..];
+                continue;
+            }
+        };
+        let fields = rest["Id(".len()..end].split(',').collect::<Vec<_>>();
+        let replacement = match fields[..] {
+            [index, epoch, name] if !name.trim().is_empty() => {
+                match (index.trim().parse(), epoch.trim().parse()) {
+                    (Ok(index), Ok(epoch)) => replace(index, epoch, name.trim()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        match replacement {
+            Some(replacement) => {
+                result.push_str(&replacement);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push_str("Id(");
+                rest = &rest["Id(".len()..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// The error `wgc` returned while replaying an action.
+pub type ActionError = Box<dyn std::error::Error>;
+
 pub trait GlobalPlay {
     fn encode_commands<A: wgc::hal_api::HalApi>(
         &self,
         encoder: wgc::id::CommandEncoderId,
         commands: Vec<trace::Command>,
-    ) -> wgc::id::CommandBufferId;
+    ) -> Result<wgc::id::CommandBufferId, ActionError>;
     fn process<A: wgc::hal_api::HalApi>(
         &self,
         device: wgc::id::DeviceId,
         action: trace::Action,
-        dir: &Path,
+        blobs: &(impl file::Blobs + ?Sized),
         comb_manager: &mut wgc::identity::IdentityManager<wgc::id::CommandBufferId>,
-    );
+    ) -> Result<(), ActionError>;
+}
+
+fn encode_command<A: wgc::hal_api::HalApi>(
+    global: &wgc::global::Global<IdentityPassThroughFactory>,
+    encoder: wgc::id::CommandEncoderId,
+    command: trace::Command,
+) -> Result<(), ActionError> {
+    match command {
+        trace::Command::CopyBufferToBuffer {
+            src,
+            src_offset,
+            dst,
+            dst_offset,
+            size,
+        } => global.command_encoder_copy_buffer_to_buffer::<A>(
+            encoder, src, src_offset, dst, dst_offset, size,
+        )?,
+        trace::Command::CopyBufferToTexture { src, dst, size } => {
+            global.command_encoder_copy_buffer_to_texture::<A>(encoder, &src, &dst, &size)?
+        }
+        trace::Command::CopyTextureToBuffer { src, dst, size } => {
+            global.command_encoder_copy_texture_to_buffer::<A>(encoder, &src, &dst, &size)?
+        }
+        trace::Command::CopyTextureToTexture { src, dst, size } => {
+            global.command_encoder_copy_texture_to_texture::<A>(encoder, &src, &dst, &size)?
+        }
+        trace::Command::ClearBuffer { dst, offset, size } => {
+            global.command_encoder_clear_buffer::<A>(encoder, dst, offset, size)?
+        }
+        trace::Command::ClearTexture {
+            dst,
+            subresource_range,
+        } => global.command_encoder_clear_texture::<A>(encoder, dst, &subresource_range)?,
+        trace::Command::WriteTimestamp {
+            query_set_id,
+            query_index,
+        } => global.command_encoder_write_timestamp::<A>(encoder, query_set_id, query_index)?,
+        trace::Command::ResolveQuerySet {
+            query_set_id,
+            start_query,
+            query_count,
+            destination,
+            destination_offset,
+        } => global.command_encoder_resolve_query_set::<A>(
+            encoder,
+            query_set_id,
+            start_query,
+            query_count,
+            destination,
+            destination_offset,
+        )?,
+        trace::Command::PushDebugGroup(marker) => {
+            global.command_encoder_push_debug_group::<A>(encoder, &marker)?
+        }
+        trace::Command::PopDebugGroup => global.command_encoder_pop_debug_group::<A>(encoder)?,
+        trace::Command::InsertDebugMarker(marker) => {
+            global.command_encoder_insert_debug_marker::<A>(encoder, &marker)?
+        }
+        trace::Command::RunComputePass {
+            base,
+            timestamp_writes,
+        } => {
+            global.command_encoder_run_compute_pass_impl::<A>(
+                encoder,
+                base.as_ref(),
+                timestamp_writes.as_ref(),
+            )?;
+        }
+        trace::Command::RunRenderPass {
+            base,
+            target_colors,
+            target_depth_stencil,
+            timestamp_writes,
+            occlusion_query_set_id,
+        } => {
+            global.command_encoder_run_render_pass_impl::<A>(
+                encoder,
+                base.as_ref(),
+                &target_colors,
+                target_depth_stencil.as_ref(),
+                timestamp_writes.as_ref(),
+                occlusion_query_set_id,
+            )?;
+        }
+    }
+    Ok(())
 }
 
 impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
@@ -47,114 +214,31 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
         &self,
         encoder: wgc::id::CommandEncoderId,
         commands: Vec<trace::Command>,
-    ) -> wgc::id::CommandBufferId {
+    ) -> Result<wgc::id::CommandBufferId, ActionError> {
         for command in commands {
-            match command {
-                trace::Command::CopyBufferToBuffer {
-                    src,
-                    src_offset,
-                    dst,
-                    dst_offset,
-                    size,
-                } => self
-                    .command_encoder_copy_buffer_to_buffer::<A>(
-                        encoder, src, src_offset, dst, dst_offset, size,
-                    )
-                    .unwrap(),
-                trace::Command::CopyBufferToTexture { src, dst, size } => self
-                    .command_encoder_copy_buffer_to_texture::<A>(encoder, &src, &dst, &size)
-                    .unwrap(),
-                trace::Command::CopyTextureToBuffer { src, dst, size } => self
-                    .command_encoder_copy_texture_to_buffer::<A>(encoder, &src, &dst, &size)
-                    .unwrap(),
-                trace::Command::CopyTextureToTexture { src, dst, size } => self
-                    .command_encoder_copy_texture_to_texture::<A>(encoder, &src, &dst, &size)
-                    .unwrap(),
-                trace::Command::ClearBuffer { dst, offset, size } => self
-                    .command_encoder_clear_buffer::<A>(encoder, dst, offset, size)
-                    .unwrap(),
-                trace::Command::ClearTexture {
-                    dst,
-                    subresource_range,
-                } => self
-                    .command_encoder_clear_texture::<A>(encoder, dst, &subresource_range)
-                    .unwrap(),
-                trace::Command::WriteTimestamp {
-                    query_set_id,
-                    query_index,
-                } => self
-                    .command_encoder_write_timestamp::<A>(encoder, query_set_id, query_index)
-                    .unwrap(),
-                trace::Command::ResolveQuerySet {
-                    query_set_id,
-                    start_query,
-                    query_count,
-                    destination,
-                    destination_offset,
-                } => self
-                    .command_encoder_resolve_query_set::<A>(
-                        encoder,
-                        query_set_id,
-                        start_query,
-                        query_count,
-                        destination,
-                        destination_offset,
-                    )
-                    .unwrap(),
-                trace::Command::PushDebugGroup(marker) => self
-                    .command_encoder_push_debug_group::<A>(encoder, &marker)
-                    .unwrap(),
-                trace::Command::PopDebugGroup => {
-                    self.command_encoder_pop_debug_group::<A>(encoder).unwrap()
-                }
-                trace::Command::InsertDebugMarker(marker) => self
-                    .command_encoder_insert_debug_marker::<A>(encoder, &marker)
-                    .unwrap(),
-                trace::Command::RunComputePass {
-                    base,
-                    timestamp_writes,
-                } => {
-                    self.command_encoder_run_compute_pass_impl::<A>(
-                        encoder,
-                        base.as_ref(),
-                        timestamp_writes.as_ref(),
-                    )
-                    .unwrap();
-                }
-                trace::Command::RunRenderPass {
-                    base,
-                    target_colors,
-                    target_depth_stencil,
-                    timestamp_writes,
-                    occlusion_query_set_id,
-                } => {
-                    self.command_encoder_run_render_pass_impl::<A>(
-                        encoder,
-                        base.as_ref(),
-                        &target_colors,
-                        target_depth_stencil.as_ref(),
-                        timestamp_writes.as_ref(),
-                        occlusion_query_set_id,
-                    )
-                    .unwrap();
-                }
+            // Like a real command buffer, the rest of the commands are
+            // discarded once one of them fails.
+            if let Err(e) = encode_command::<A>(self, encoder, command) {
+                self.command_encoder_drop::<A>(encoder);
+                return Err(e);
             }
         }
         let (cmd_buf, error) = self
             .command_encoder_finish::<A>(encoder, &wgt::CommandBufferDescriptor { label: None });
         if let Some(e) = error {
-            panic!("{e}");
+            self.command_buffer_drop::<A>(cmd_buf);
+            return Err(e.into());
         }
-        cmd_buf
+        Ok(cmd_buf)
     }
 
     fn process<A: wgc::hal_api::HalApi>(
         &self,
         device: wgc::id::DeviceId,
         action: trace::Action,
-        dir: &Path,
+        blobs: &(impl file::Blobs + ?Sized),
         comb_manager: &mut wgc::identity::IdentityManager<wgc::id::CommandBufferId>,
-    ) {
+    ) -> Result<(), ActionError> {
         use wgc::device::trace::Action;
         log::debug!("action {:?}", action);
         //TODO: find a way to force ID perishing without excessive `maintain()` calls.
@@ -168,27 +252,27 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                 panic!("Unexpected Surface action: winit feature is not enabled")
             }
             Action::CreateBuffer(id, desc) => {
-                self.device_maintain_ids::<A>(device).unwrap();
+                self.device_maintain_ids::<A>(device)?;
                 let (_, error) = self.device_create_buffer::<A>(device, &desc, id);
                 if let Some(e) = error {
-                    panic!("{e}");
+                    return Err(e.into());
                 }
             }
             Action::FreeBuffer(id) => {
-                self.buffer_destroy::<A>(id).unwrap();
+                self.buffer_destroy::<A>(id)?;
             }
             Action::DestroyBuffer(id) => {
                 self.buffer_drop::<A>(id, true);
             }
             Action::CreateTexture(id, desc) => {
-                self.device_maintain_ids::<A>(device).unwrap();
+                self.device_maintain_ids::<A>(device)?;
                 let (_, error) = self.device_create_texture::<A>(device, &desc, id);
                 if let Some(e) = error {
-                    panic!("{e}");
+                    return Err(e.into());
                 }
             }
             Action::FreeTexture(id) => {
-                self.texture_destroy::<A>(id).unwrap();
+                self.texture_destroy::<A>(id)?;
             }
             Action::DestroyTexture(id) => {
                 self.texture_drop::<A>(id, true);
@@ -198,27 +282,27 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                 parent_id,
                 desc,
             } => {
-                self.device_maintain_ids::<A>(device).unwrap();
+                self.device_maintain_ids::<A>(device)?;
                 let (_, error) = self.texture_create_view::<A>(parent_id, &desc, id);
                 if let Some(e) = error {
-                    panic!("{e}");
+                    return Err(e.into());
                 }
             }
             Action::DestroyTextureView(id) => {
-                self.texture_view_drop::<A>(id, true).unwrap();
+                self.texture_view_drop::<A>(id, true)?;
             }
             Action::CreateSampler(id, desc) => {
-                self.device_maintain_ids::<A>(device).unwrap();
+                self.device_maintain_ids::<A>(device)?;
                 let (_, error) = self.device_create_sampler::<A>(device, &desc, id);
                 if let Some(e) = error {
-                    panic!("{e}");
+                    return Err(e.into());
                 }
             }
             Action::DestroySampler(id) => {
                 self.sampler_drop::<A>(id);
             }
             Action::GetSurfaceTexture { id, parent_id } => {
-                self.device_maintain_ids::<A>(device).unwrap();
+                self.device_maintain_ids::<A>(device)?;
                 self.surface_get_current_texture::<A>(parent_id, id)
                     .unwrap()
                     .texture_id
@@ -227,27 +311,27 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
             Action::CreateBindGroupLayout(id, desc) => {
                 let (_, error) = self.device_create_bind_group_layout::<A>(device, &desc, id);
                 if let Some(e) = error {
-                    panic!("{e}");
+                    return Err(e.into());
                 }
             }
             Action::DestroyBindGroupLayout(id) => {
                 self.bind_group_layout_drop::<A>(id);
             }
             Action::CreatePipelineLayout(id, desc) => {
-                self.device_maintain_ids::<A>(device).unwrap();
+                self.device_maintain_ids::<A>(device)?;
                 let (_, error) = self.device_create_pipeline_layout::<A>(device, &desc, id);
                 if let Some(e) = error {
-                    panic!("{e}");
+                    return Err(e.into());
                 }
             }
             Action::DestroyPipelineLayout(id) => {
                 self.pipeline_layout_drop::<A>(id);
             }
             Action::CreateBindGroup(id, desc) => {
-                self.device_maintain_ids::<A>(device).unwrap();
+                self.device_maintain_ids::<A>(device)?;
                 let (_, error) = self.device_create_bind_group::<A>(device, &desc, id);
                 if let Some(e) = error {
-                    panic!("{e}");
+                    return Err(e.into());
                 }
             }
             Action::DestroyBindGroup(id) => {
@@ -255,7 +339,7 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
             }
             Action::CreateShaderModule { id, desc, data } => {
                 log::debug!("Creating shader from {}", data);
-                let code = fs::read_to_string(dir.join(&data)).unwrap();
+                let code = String::from_utf8(blobs.load(&data)?.into_owned())?;
                 let source = if data.ends_with(".wgsl") {
                     wgc::pipeline::ShaderModuleSource::Wgsl(Cow::Owned(code.clone()))
                 } else if data.ends_with(".ron") {
@@ -266,7 +350,8 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                 };
                 let (_, error) = self.device_create_shader_module::<A>(device, &desc, source, id);
                 if let Some(e) = error {
-                    println!("shader compilation error:\n---{code}\n---\n{e}");
+                    log::error!("shader compilation error:\n---{code}\n---");
+                    return Err(e.into());
                 }
             }
             Action::DestroyShaderModule(id) => {
@@ -277,7 +362,7 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                 desc,
                 implicit_context,
             } => {
-                self.device_maintain_ids::<A>(device).unwrap();
+                self.device_maintain_ids::<A>(device)?;
                 let implicit_ids =
                     implicit_context
                         .as_ref()
@@ -288,7 +373,7 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                 let (_, error) =
                     self.device_create_compute_pipeline::<A>(device, &desc, id, implicit_ids);
                 if let Some(e) = error {
-                    panic!("{e}");
+                    return Err(e.into());
                 }
             }
             Action::DestroyComputePipeline(id) => {
@@ -299,7 +384,7 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                 desc,
                 implicit_context,
             } => {
-                self.device_maintain_ids::<A>(device).unwrap();
+                self.device_maintain_ids::<A>(device)?;
                 let implicit_ids =
                     implicit_context
                         .as_ref()
@@ -310,32 +395,31 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                 let (_, error) =
                     self.device_create_render_pipeline::<A>(device, &desc, id, implicit_ids);
                 if let Some(e) = error {
-                    panic!("{e}");
+                    return Err(e.into());
                 }
             }
             Action::DestroyRenderPipeline(id) => {
                 self.render_pipeline_drop::<A>(id);
             }
             Action::CreateRenderBundle { id, desc, base } => {
-                let bundle =
-                    wgc::command::RenderBundleEncoder::new(&desc, device, Some(base)).unwrap();
+                let bundle = wgc::command::RenderBundleEncoder::new(&desc, device, Some(base))?;
                 let (_, error) = self.render_bundle_encoder_finish::<A>(
                     bundle,
                     &wgt::RenderBundleDescriptor { label: desc.label },
                     id,
                 );
                 if let Some(e) = error {
-                    panic!("{e}");
+                    return Err(e.into());
                 }
             }
             Action::DestroyRenderBundle(id) => {
                 self.render_bundle_drop::<A>(id);
             }
             Action::CreateQuerySet { id, desc } => {
-                self.device_maintain_ids::<A>(device).unwrap();
+                self.device_maintain_ids::<A>(device)?;
                 let (_, error) = self.device_create_query_set::<A>(device, &desc, id);
                 if let Some(e) = error {
-                    panic!("{e}");
+                    return Err(e.into());
                 }
             }
             Action::DestroyQuerySet(id) => {
@@ -347,15 +431,13 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                 range,
                 queued,
             } => {
-                let bin = std::fs::read(dir.join(data)).unwrap();
+                let bin = blobs.load(&data)?;
                 let size = (range.end - range.start) as usize;
                 if queued {
-                    self.queue_write_buffer::<A>(device, id, range.start, &bin)
-                        .unwrap();
+                    self.queue_write_buffer::<A>(device, id, range.start, &bin)?;
                 } else {
-                    self.device_wait_for_buffer::<A>(device, id).unwrap();
-                    self.device_set_buffer_sub_data::<A>(device, id, range.start, &bin[..size])
-                        .unwrap();
+                    self.device_wait_for_buffer::<A>(device, id)?;
+                    self.device_set_buffer_sub_data::<A>(device, id, range.start, &bin[..size])?;
                 }
             }
             Action::WriteTexture {
@@ -364,12 +446,11 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                 layout,
                 size,
             } => {
-                let bin = std::fs::read(dir.join(data)).unwrap();
-                self.queue_write_texture::<A>(device, &to, &bin, &layout, &size)
-                    .unwrap();
+                let bin = blobs.load(&data)?;
+                self.queue_write_texture::<A>(device, &to, &bin, &layout, &size)?;
             }
             Action::Submit(_index, ref commands) if commands.is_empty() => {
-                self.queue_submit::<A>(device, &[]).unwrap();
+                self.queue_submit::<A>(device, &[])?;
             }
             Action::Submit(_index, commands) => {
                 let (encoder, error) = self.device_create_command_encoder::<A>(
@@ -378,11 +459,12 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                     comb_manager.process(device.backend()),
                 );
                 if let Some(e) = error {
-                    panic!("{e}");
+                    return Err(e.into());
                 }
-                let cmdbuf = self.encode_commands::<A>(encoder, commands);
-                self.queue_submit::<A>(device, &[cmdbuf]).unwrap();
+                let cmdbuf = self.encode_commands::<A>(encoder, commands)?;
+                self.queue_submit::<A>(device, &[cmdbuf])?;
             }
         }
+        Ok(())
     }
 }
@@ -0,0 +1,251 @@
+//! Delta debugging of traces that run into an error.
+//!
+//! Removing an action also removes every action that refers to an id it
+//! created, since replaying those would fail differently, or panic.
+
+use wgc::device::trace;
+
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{
+    file::Blobs,
+    replace_ids,
+    report::{ActionInfo, Failure, Report},
+    ActionError, GlobalPlay as _, IdentityPassThroughFactory,
+};
+
+/// Replay `actions` on a new instance without a surface, and report the outcome.
+///
+/// The first action must be `Init`. If `backend` is set, it's used instead of the
+/// one the trace was recorded on. Surface actions are skipped, and panics count
+/// as failures of the action that caused them.
+pub fn replay(
+    actions: impl IntoIterator<Item = trace::Action<'static>>,
+    blobs: &(impl Blobs + ?Sized),
+    backend: Option<wgt::Backend>,
+) -> Result<Report, ActionError> {
+    use wgc::gfx_select;
+
+    let mut actions = actions.into_iter();
+    let (desc, backend) = match actions.next() {
+        Some(trace::Action::Init {
+            desc,
+            backend: recorded,
+        }) => (desc, backend.unwrap_or(recorded)),
+        _ => return Err("Expected Action::Init".into()),
+    };
+
+    let global = wgc::global::Global::new(
+        "player",
+        IdentityPassThroughFactory,
+        wgt::InstanceDescriptor {
+            backends: backend.into(),
+            empty_backend_options: wgt::EmptyBackendOptions {
+                enable: true,
+                features: desc.required_features,
+                limits: desc.required_limits.clone(),
+            },
+            ..Default::default()
+        },
+    );
+    let adapter = global.request_adapter(
+        &wgc::instance::RequestAdapterOptions {
+            power_preference: wgt::PowerPreference::None,
+            force_fallback_adapter: false,
+            compatible_surface: None,
+        },
+        // `Backend::Empty` is zero, so the epoch must not be.
+        wgc::instance::AdapterInputs::IdSet(&[wgc::id::TypedId::zip(0, 1, backend)], |id| {
+            id.backend()
+        }),
+    )?;
+    let info = gfx_select!(adapter => global.adapter_get_info(adapter))?;
+    let device = wgc::id::TypedId::zip(1, 0, backend);
+    let (_, _, error) = gfx_select!(adapter => global.adapter_request_device(
+        adapter,
+        &desc,
+        None,
+        device,
+        device
+    ));
+    if let Some(e) = error {
+        return Err(e.into());
+    }
+
+    let mut report = Report {
+        backend: backend.to_str().to_owned(),
+        adapter: info.name,
+        executed: 1,
+        ..Default::default()
+    };
+    let mut command_buffer_id_manager = wgc::identity::IdentityManager::new();
+    for (index, action) in (1..).zip(actions) {
+        let info = ActionInfo::new(index, &action);
+        let result = match action {
+            trace::Action::ConfigureSurface(..)
+            | trace::Action::GetSurfaceTexture { .. }
+            | trace::Action::Present(_)
+            | trace::Action::DiscardSurfaceTexture(_) => Ok(()),
+            action => panic::catch_unwind(AssertUnwindSafe(|| {
+                gfx_select!(device => global.process(device, action, blobs, &mut command_buffer_id_manager))
+            }))
+            .unwrap_or_else(|payload| {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                Err(format!("Panicked: {message}").into())
+            }),
+        };
+        report.record(index, info, result);
+    }
+    let _ = gfx_select!(device => global.device_poll(device, wgt::Maintain::Wait));
+    report.actions = report.executed;
+    Ok(report)
+}
+
+/// A smaller trace that runs into the same error as the original one.
+pub struct Minimized {
+    pub actions: Vec<trace::Action<'static>>,
+    /// Indices of the actions in the original trace.
+    pub indices: Vec<usize>,
+    /// The error, as reported when replaying `actions`.
+    pub failure: Failure,
+    /// Number of times a trace was replayed to find this one.
+    pub replays: usize,
+}
+
+/// Remove as many actions from `actions` as possible, while replaying it still runs into
+/// the error of the action at index `action`, or the first error if that's `None`.
+///
+/// See [`replay`] for the meaning of `backend`.
+pub fn minimize(
+    actions: &[trace::Action],
+    blobs: &(impl Blobs + ?Sized),
+    backend: Option<wgt::Backend>,
+    action: Option<usize>,
+) -> Result<Minimized, ActionError> {
+    // The actions are replayed many times, so keep them in a form that can be parsed again.
+    let sources = actions
+        .iter()
+        .map(ron::ser::to_string)
+        .collect::<Result<Vec<_>, _>>()?;
+    let parse = |indices: &[usize]| -> Result<Vec<trace::Action<'static>>, ActionError> {
+        indices
+            .iter()
+            .map(|&index| Ok(ron::de::from_str(&sources[index])?))
+            .collect()
+    };
+
+    // Every id is mentioned first by the action that creates it, including the
+    // ids of implicit pipeline layouts. Ids of different kinds may look the same,
+    // which can only make an action depend on more than it needs to.
+    let mut creators = HashMap::new();
+    let mut dependencies = Vec::with_capacity(sources.len());
+    for (index, source) in sources.iter().enumerate() {
+        let mut depends_on = Vec::new();
+        replace_ids(source, |id_index, epoch, _| {
+            match creators.entry((id_index, epoch)) {
+                Entry::Occupied(creator) if *creator.get() != index => {
+                    depends_on.push(*creator.get())
+                }
+                Entry::Occupied(_) => {}
+                Entry::Vacant(creator) => {
+                    creator.insert(index);
+                }
+            }
+            None
+        });
+        dependencies.push(depends_on);
+    }
+
+    let all = (0..sources.len()).collect::<Vec<_>>();
+    let mut report = replay(parse(&all)?, blobs, backend)?;
+    let position = report
+        .failures
+        .iter()
+        .position(|failure| action.is_none() || action == Some(failure.action))
+        .ok_or_else(|| match action {
+            Some(action) => format!("Action #{action} does not fail"),
+            None => "The trace replays without errors".to_string(),
+        })?;
+    let target = report.failures.swap_remove(position);
+
+    let mut replays = 1;
+    let mut reproduce = |indices: &[usize]| -> Result<Option<Failure>, ActionError> {
+        replays += 1;
+        let report = replay(parse(indices)?, blobs, backend)?;
+        Ok(report
+            .failures
+            .into_iter()
+            .find(|failure| failure.is_same_error(&target)))
+    };
+
+    // Remove a chunk of the actions at a time, and halve the chunks when none of
+    // them can be removed. `Init` always stays.
+    let mut kept = all;
+    let mut failure = None;
+    let mut chunk_count = 2;
+    while kept.len() > 1 {
+        let removable = &kept[1..];
+        let chunk_count_now = chunk_count.min(removable.len());
+        let chunk_size =
+            removable.len() / chunk_count_now + usize::from(removable.len() % chunk_count_now != 0);
+        let candidates = removable
+            .chunks(chunk_size)
+            .map(|chunk| remove(&kept, chunk, &dependencies))
+            .collect::<Vec<_>>();
+
+        let mut reduced = false;
+        for candidate in candidates {
+            if let Some(found) = reproduce(&candidate)? {
+                log::info!("Reduced to {} actions", candidate.len());
+                kept = candidate;
+                failure = Some(found);
+                reduced = true;
+                break;
+            }
+        }
+        if reduced {
+            chunk_count = (chunk_count - 1).max(2);
+        } else if chunk_size == 1 {
+            break;
+        } else {
+            chunk_count *= 2;
+        }
+    }
+
+    let failure = match failure {
+        Some(failure) => failure,
+        None => target,
+    };
+    Ok(Minimized {
+        actions: parse(&kept)?,
+        indices: kept,
+        failure,
+        replays,
+    })
+}
+
+/// The actions of `kept` that are left after removing `chunk`, and what depends on it.
+fn remove(kept: &[usize], chunk: &[usize], dependencies: &[Vec<usize>]) -> Vec<usize> {
+    let mut removed = chunk.iter().copied().collect::<HashSet<_>>();
+    kept.iter()
+        .copied()
+        .filter(|&index| {
+            // Dependencies come before the actions that depend on them.
+            if !removed.contains(&index)
+                && dependencies[index]
+                    .iter()
+                    .any(|dependency| removed.contains(dependency))
+            {
+                removed.insert(index);
+            }
+            !removed.contains(&index)
+        })
+        .collect()
+}
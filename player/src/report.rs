@@ -0,0 +1,215 @@
+//! A summary of a replay, for triaging traces.
+
+use serde::Serialize;
+use wgc::{device::trace::Action, id::TypedId};
+
+use crate::ActionError;
+
+/// A resource that an action created.
+#[derive(Debug, Serialize)]
+pub struct Resource {
+    /// Index of the action in the trace.
+    pub action: usize,
+    pub kind: &'static str,
+    pub index: u32,
+    pub epoch: u32,
+    pub label: Option<String>,
+    /// Whether creating the resource failed, leaving an invalid id behind.
+    pub invalid: bool,
+}
+
+/// An action that `wgc` returned an error for.
+#[derive(Debug, Serialize)]
+pub struct Failure {
+    /// Index of the action in the trace.
+    pub action: usize,
+    pub kind: &'static str,
+    /// The error, followed by each of its sources.
+    pub messages: Vec<String>,
+}
+
+impl Failure {
+    /// Whether `other` is the same error, possibly from another action.
+    pub fn is_same_error(&self, other: &Failure) -> bool {
+        self.kind == other.kind && self.messages == other.messages
+    }
+
+    fn new(action: usize, kind: &'static str, error: &ActionError) -> Self {
+        let mut messages = vec![error.to_string()];
+        let mut source = error.source();
+        while let Some(error) = source {
+            messages.push(error.to_string());
+            source = error.source();
+        }
+        Self {
+            action,
+            kind,
+            messages,
+        }
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Action #{} ({}): {}",
+            self.action, self.kind, self.messages[0]
+        )?;
+        for message in &self.messages[1..] {
+            write!(f, "\n\tcaused by: {message}")?;
+        }
+        Ok(())
+    }
+}
+
+/// What the report needs to know about an action, taken before replaying it.
+pub struct ActionInfo {
+    kind: &'static str,
+    created: Option<Resource>,
+}
+
+impl ActionInfo {
+    pub fn new(index: usize, action: &Action) -> Self {
+        fn created<I: TypedId>(
+            action: usize,
+            kind: &'static str,
+            id: I,
+            label: Option<&str>,
+        ) -> Option<Resource> {
+            let (index, epoch, _) = id.unzip();
+            Some(Resource {
+                action,
+                kind,
+                index,
+                epoch,
+                label: label.map(str::to_owned),
+                invalid: false,
+            })
+        }
+
+        let (kind, created) = match *action {
+            Action::Init { .. } => ("Init", None),
+            Action::ConfigureSurface(..) => ("ConfigureSurface", None),
+            Action::CreateBuffer(id, ref desc) => (
+                "CreateBuffer",
+                created(index, "Buffer", id, desc.label.as_deref()),
+            ),
+            Action::FreeBuffer(_) => ("FreeBuffer", None),
+            Action::DestroyBuffer(_) => ("DestroyBuffer", None),
+            Action::CreateTexture(id, ref desc) => (
+                "CreateTexture",
+                created(index, "Texture", id, desc.label.as_deref()),
+            ),
+            Action::FreeTexture(_) => ("FreeTexture", None),
+            Action::DestroyTexture(_) => ("DestroyTexture", None),
+            Action::CreateTextureView { id, ref desc, .. } => (
+                "CreateTextureView",
+                created(index, "TextureView", id, desc.label.as_deref()),
+            ),
+            Action::DestroyTextureView(_) => ("DestroyTextureView", None),
+            Action::CreateSampler(id, ref desc) => (
+                "CreateSampler",
+                created(index, "Sampler", id, desc.label.as_deref()),
+            ),
+            Action::DestroySampler(_) => ("DestroySampler", None),
+            Action::GetSurfaceTexture { id, .. } => {
+                ("GetSurfaceTexture", created(index, "Texture", id, None))
+            }
+            Action::Present(_) => ("Present", None),
+            Action::DiscardSurfaceTexture(_) => ("DiscardSurfaceTexture", None),
+            Action::CreateBindGroupLayout(id, ref desc) => (
+                "CreateBindGroupLayout",
+                created(index, "BindGroupLayout", id, desc.label.as_deref()),
+            ),
+            Action::DestroyBindGroupLayout(_) => ("DestroyBindGroupLayout", None),
+            Action::CreatePipelineLayout(id, ref desc) => (
+                "CreatePipelineLayout",
+                created(index, "PipelineLayout", id, desc.label.as_deref()),
+            ),
+            Action::DestroyPipelineLayout(_) => ("DestroyPipelineLayout", None),
+            Action::CreateBindGroup(id, ref desc) => (
+                "CreateBindGroup",
+                created(index, "BindGroup", id, desc.label.as_deref()),
+            ),
+            Action::DestroyBindGroup(_) => ("DestroyBindGroup", None),
+            Action::CreateShaderModule { id, ref desc, .. } => (
+                "CreateShaderModule",
+                created(index, "ShaderModule", id, desc.label.as_deref()),
+            ),
+            Action::DestroyShaderModule(_) => ("DestroyShaderModule", None),
+            Action::CreateComputePipeline { id, ref desc, .. } => (
+                "CreateComputePipeline",
+                created(index, "ComputePipeline", id, desc.label.as_deref()),
+            ),
+            Action::DestroyComputePipeline(_) => ("DestroyComputePipeline", None),
+            Action::CreateRenderPipeline { id, ref desc, .. } => (
+                "CreateRenderPipeline",
+                created(index, "RenderPipeline", id, desc.label.as_deref()),
+            ),
+            Action::DestroyRenderPipeline(_) => ("DestroyRenderPipeline", None),
+            Action::CreateRenderBundle { id, ref desc, .. } => (
+                "CreateRenderBundle",
+                created(index, "RenderBundle", id, desc.label.as_deref()),
+            ),
+            Action::DestroyRenderBundle(_) => ("DestroyRenderBundle", None),
+            Action::CreateQuerySet { id, ref desc } => (
+                "CreateQuerySet",
+                created(index, "QuerySet", id, desc.label.as_deref()),
+            ),
+            Action::DestroyQuerySet(_) => ("DestroyQuerySet", None),
+            Action::WriteBuffer { .. } => ("WriteBuffer", None),
+            Action::WriteTexture { .. } => ("WriteTexture", None),
+            Action::Submit(..) => ("Submit", None),
+        };
+        Self { kind, created }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    /// The resource that the action creates, if any.
+    pub fn created(&self) -> Option<&Resource> {
+        self.created.as_ref()
+    }
+}
+
+/// The resources a replay created, and the errors it ran into.
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub backend: String,
+    pub adapter: String,
+    /// Number of actions in the trace.
+    pub actions: usize,
+    /// Number of actions that were replayed.
+    pub executed: usize,
+    /// Number of frames that were presented.
+    pub frames: u32,
+    pub resources: Vec<Resource>,
+    pub failures: Vec<Failure>,
+}
+
+impl Report {
+    /// Record the outcome of replaying an action, returning its failure, if any.
+    pub fn record(
+        &mut self,
+        index: usize,
+        info: ActionInfo,
+        result: Result<(), ActionError>,
+    ) -> Option<&Failure> {
+        self.executed += 1;
+        let failed = result.is_err();
+        if let Some(mut resource) = info.created {
+            resource.invalid = failed;
+            self.resources.push(resource);
+        }
+        let error = result.err()?;
+        self.failures.push(Failure::new(index, info.kind, &error));
+        self.failures.last()
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
@@ -1,6 +1,9 @@
 /*! Tester for WebGPU
  *  It enumerates the available backends on the system,
- *  and run the tests through them.
+ *  and run the tests through them. The empty backend only
+ *  checks that the tests pass validation, since it doesn't
+ *  execute anything. The CPU backend has its own corpus,
+ *  since it can't run the tests that draw.
  *
  *  Test requirements:
  *    - all IDs have the backend `Empty`
@@ -10,13 +13,15 @@
 !*/
 #![cfg(not(target_arch = "wasm32"))]
 
-use player::{GlobalPlay, IdentityPassThroughFactory};
+use player::{file::Blobs, report::ActionInfo, GlobalPlay, IdentityPassThroughFactory};
 use std::{
     fs::{read_to_string, File},
     io::{Read, Seek, SeekFrom},
     path::{Path, PathBuf},
+    process::Command,
     slice,
 };
+use wgc::device::trace::Action;
 
 #[derive(serde::Deserialize)]
 struct RawId {
@@ -53,7 +58,7 @@ struct Expectation {
 struct Test<'a> {
     features: wgt::Features,
     expectations: Vec<Expectation>,
-    actions: Vec<wgc::device::trace::Action<'a>>,
+    actions: Vec<Action<'a>>,
 }
 
 fn map_callback(status: Result<(), wgc::resource::BufferAccessError>) {
@@ -64,27 +69,42 @@ fn map_callback(status: Result<(), wgc::resource::BufferAccessError>) {
 
 impl Test<'_> {
     fn load(path: PathBuf, backend: wgt::Backend) -> Self {
-        let backend_name = match backend {
-            wgt::Backend::Vulkan => "Vulkan",
-            wgt::Backend::Metal => "Metal",
-            wgt::Backend::Dx12 => "Dx12",
-            wgt::Backend::Dx11 => "Dx11",
-            wgt::Backend::Gl => "Gl",
-            _ => unreachable!(),
-        };
-        let string = read_to_string(path).unwrap().replace("Empty", backend_name);
+        let string = player::retarget_trace(&read_to_string(path).unwrap(), backend);
         ron::de::from_str(&string).unwrap()
     }
 
+    /// Round-trip the actions through a compressed binary trace at `path`,
+    /// returning the reader that holds their data.
+    fn into_binary(
+        mut self,
+        dir: &Path,
+        path: &Path,
+    ) -> (Test<'static>, wgc::device::trace::binary::Reader) {
+        let file = File::create(path).unwrap();
+        let mut writer = wgc::device::trace::binary::Writer::new(file, true).unwrap();
+        player::convert::write_binary(&mut self.actions, dir, &mut writer).unwrap();
+        drop(writer);
+
+        let mut reader = wgc::device::trace::binary::Reader::open(path).unwrap();
+        let test = Test {
+            features: self.features,
+            expectations: self.expectations,
+            actions: reader.by_ref().map(Result::unwrap).collect(),
+        };
+        (test, reader)
+    }
+
     fn run(
         self,
         dir: &Path,
+        blobs: &(impl Blobs + ?Sized),
         global: &wgc::global::Global<IdentityPassThroughFactory>,
         adapter: wgc::id::AdapterId,
         test_num: u32,
     ) {
         let backend = adapter.backend();
-        let device_id = wgc::id::TypedId::zip(test_num, 0, backend);
+        // `Backend::Empty` is zero, so the epoch must not be.
+        let device_id = wgc::id::TypedId::zip(test_num, 1, backend);
         let (_, _, error) = wgc::gfx_select!(adapter => global.adapter_request_device(
             adapter,
             &wgt::DeviceDescriptor {
@@ -103,7 +123,7 @@ impl Test<'_> {
         let mut command_buffer_id_manager = wgc::identity::IdentityManager::new();
         println!("\t\t\tRunning...");
         for action in self.actions {
-            wgc::gfx_select!(device_id => global.process(device_id, action, dir, &mut command_buffer_id_manager));
+            wgc::gfx_select!(device_id => global.process(device_id, action, blobs, &mut command_buffer_id_manager)).unwrap();
         }
         println!("\t\t\tMapping...");
         for expect in &self.expectations {
@@ -124,6 +144,12 @@ impl Test<'_> {
         println!("\t\t\tWaiting...");
         wgc::gfx_select!(device_id => global.device_poll(device_id, wgt::Maintain::Wait)).unwrap();
 
+        if backend == wgt::Backend::Empty {
+            println!("\t\t\tNothing to check");
+            wgc::gfx_select!(device_id => global.clear_backend(()));
+            return;
+        }
+
         for expect in self.expectations {
             println!("\t\t\tChecking {}", expect.name);
             let buffer = wgc::id::TypedId::zip(expect.buffer.index, expect.buffer.epoch, backend);
@@ -172,10 +198,13 @@ const BACKENDS: &[wgt::Backend] = &[
     wgt::Backend::Dx12,
     wgt::Backend::Dx11,
     wgt::Backend::Gl,
+    wgt::Backend::Empty,
+    wgt::Backend::Cpu,
 ];
 
 impl Corpus {
-    fn run_from(path: PathBuf) {
+    /// Run the tests of the corpus at `path`, replaying them from a binary trace if `binary` is set.
+    fn run_from(path: PathBuf, binary: bool) {
         println!("Corpus {:?}", path);
         let dir = path.parent().unwrap();
         let corpus: Corpus = ron::de::from_reader(File::open(&path).unwrap()).unwrap();
@@ -188,6 +217,12 @@ impl Corpus {
                 flags: wgt::InstanceFlags::debugging(),
                 dx12_shader_compiler: wgt::Dx12Compiler::Fxc,
                 gles_minor_version: wgt::Gles3MinorVersion::default(),
+                empty_backend_options: wgt::EmptyBackendOptions {
+                    enable: true,
+                    features: wgt::Features::all(),
+                    limits: wgt::Limits::default(),
+                },
+                cpu_backend_options: wgt::CpuBackendOptions::default(),
             },
         );
         for &backend in BACKENDS {
@@ -201,7 +236,7 @@ impl Corpus {
                     compatible_surface: None,
                 },
                 wgc::instance::AdapterInputs::IdSet(
-                    &[wgc::id::TypedId::zip(0, 0, backend)],
+                    &[wgc::id::TypedId::zip(0, 1, backend)],
                     |id| id.backend(),
                 ),
             ) {
@@ -233,16 +268,152 @@ impl Corpus {
                     println!("\t\tSkipped due to missing compute shader capability");
                     continue;
                 }
-                test.run(dir, &global, adapter, test_num);
+                if binary {
+                    let binary_path = Path::new(env!("CARGO_TARGET_TMPDIR"))
+                        .join(test_path)
+                        .with_extension("bin");
+                    let (test, reader) = test.into_binary(dir, &binary_path);
+                    test.run(dir, &reader, &global, adapter, test_num);
+                } else {
+                    test.run(dir, dir, &global, adapter, test_num);
+                }
                 test_num += 1;
             }
         }
     }
 }
 
+/// Replay each test of the corpus at `path` with the `play` binary on the empty
+/// backend, and check its report, once stopping halfway through the test and
+/// once running into an error at the end of it.
+fn play_corpus(path: PathBuf) {
+    println!("Corpus {:?}", path);
+    let dir = path.parent().unwrap();
+    let corpus: Corpus = ron::de::from_reader(File::open(&path).unwrap()).unwrap();
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("play");
+    std::fs::create_dir_all(&out_dir).unwrap();
+
+    for test_path in &corpus.tests {
+        println!("\tTest '{:?}'", test_path);
+        let test = Test::load(dir.join(test_path), wgt::Backend::Empty);
+        let mut actions = vec![Action::Init {
+            desc: wgt::DeviceDescriptor {
+                label: None,
+                required_features: test.features,
+                required_limits: wgt::Limits::default(),
+            },
+            backend: wgt::Backend::Empty,
+        }];
+        actions.extend(test.actions);
+        // Mapping at creation needs a size that is a multiple of 4.
+        actions.push(Action::CreateBuffer(
+            wgc::id::TypedId::zip(1000, 1, wgt::Backend::Empty),
+            wgt::BufferDescriptor {
+                label: Some("unaligned".into()),
+                size: 3,
+                usage: wgt::BufferUsages::COPY_DST,
+                mapped_at_creation: true,
+            },
+        ));
+
+        let trace_path = out_dir.join(test_path).with_extension("bin");
+        let file = File::create(&trace_path).unwrap();
+        let mut writer = wgc::device::trace::binary::Writer::new(file, true).unwrap();
+        player::convert::write_binary(&mut actions, dir, &mut writer).unwrap();
+        drop(writer);
+
+        let play = |stop_at_action: Option<usize>| {
+            let mut command = Command::new(env!("CARGO_BIN_EXE_play"));
+            command.args(["--backend", "empty", "--report", "-"]);
+            if let Some(n) = stop_at_action {
+                command.args(["--stop-at-action", &n.to_string()]);
+            }
+            let output = command.arg(&trace_path).output().unwrap();
+            let report: serde_json::Value =
+                serde_json::from_slice(&output.stdout).unwrap_or_else(|e| {
+                    panic!(
+                        "Invalid report: {e}\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    )
+                });
+            assert_eq!(report["backend"], "empty");
+            assert_eq!(report["actions"], actions.len());
+            (output.status, report)
+        };
+        let created = |count: usize| {
+            actions[..count]
+                .iter()
+                .enumerate()
+                .filter(|&(index, action)| ActionInfo::new(index, action).created().is_some())
+                .map(|(index, _)| serde_json::Value::from(index))
+                .collect::<Vec<_>>()
+        };
+        let resource_actions = |report: &serde_json::Value| {
+            report["resources"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|resource| resource["action"].clone())
+                .collect::<Vec<_>>()
+        };
+
+        let stop = actions.len() / 2;
+        let (status, report) = play(Some(stop));
+        assert!(status.success(), "{report:#}");
+        assert_eq!(report["executed"], stop + 1);
+        assert_eq!(report["failures"], serde_json::json!([]));
+        assert_eq!(resource_actions(&report), created(stop + 1));
+
+        let (status, report) = play(None);
+        assert_eq!(status.code(), Some(1));
+        assert_eq!(report["executed"], actions.len());
+        assert_eq!(resource_actions(&report), created(actions.len()));
+        let failures = report["failures"].as_array().unwrap();
+        assert_eq!(failures.len(), 1, "{report:#}");
+        assert_eq!(failures[0]["action"], actions.len() - 1);
+        assert_eq!(failures[0]["kind"], "CreateBuffer");
+        let resources = report["resources"].as_array().unwrap();
+        assert_eq!(resources.last().unwrap()["invalid"], true);
+        assert_eq!(resources.last().unwrap()["label"], "unaligned");
+    }
+}
+
 #[test]
 fn test_api() {
-    env_logger::init();
+    let _ = env_logger::try_init();
+
+    Corpus::run_from(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/all.ron"),
+        false,
+    )
+}
+
+#[test]
+fn test_cpu() {
+    let _ = env_logger::try_init();
+
+    // The CPU backend doesn't rasterize, so it only runs the tests that don't draw.
+    Corpus::run_from(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/cpu.ron"),
+        false,
+    )
+}
+
+#[test]
+fn test_binary() {
+    let _ = env_logger::try_init();
+
+    // The CPU backend checks the expectations, so the data must survive the round trip.
+    Corpus::run_from(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/cpu.ron"),
+        true,
+    )
+}
+
+#[test]
+fn test_play() {
+    let _ = env_logger::try_init();
 
-    Corpus::run_from(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/all.ron"))
+    // The empty backend only validates, so the report is all there is to check.
+    play_corpus(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/all.ron"))
 }
@@ -0,0 +1,90 @@
+//! Tests for minimizing and comparing traces.
+#![cfg(not(target_arch = "wasm32"))]
+
+use player::{diff::TraceDiff, minimize::minimize};
+use std::path::Path;
+use wgc::device::trace::Action;
+
+const INIT: &str =
+    "Init(desc: (label: None, required_features: 0, required_limits: ()), backend: Empty)";
+
+fn parse(actions: &[&str]) -> Vec<Action<'static>> {
+    ron::de::from_str(&format!("[{}]", actions.join(", "))).unwrap()
+}
+
+fn buffer(id: u32, label: &str, size: u64, mapped_at_creation: bool) -> String {
+    format!(
+        "CreateBuffer(Id({id}, 1, Empty), (label: Some({label:?}), size: {size}, usage: 3, mapped_at_creation: {mapped_at_creation}))"
+    )
+}
+
+#[test]
+fn minimize_keeps_the_error() {
+    let _ = env_logger::try_init();
+
+    let unrelated = buffer(0, "unrelated", 16, false);
+    let source = buffer(1, "source", 16, false);
+    let destination = buffer(2, "destination", 16, false);
+    let bad = buffer(3, "bad", 3, true);
+    let actions = parse(&[
+        INIT,
+        &unrelated,
+        &source,
+        &destination,
+        "Submit(1, [CopyBufferToBuffer(src: Id(1, 1, Empty), src_offset: 0, dst: Id(2, 1, Empty), dst_offset: 0, size: 16)])",
+        "DestroyBuffer(Id(0, 1, Empty))",
+        &bad,
+        "Submit(2, [ClearBuffer(dst: Id(3, 1, Empty), offset: 0, size: None)])",
+        "FreeBuffer(Id(0, 1, Empty))",
+    ]);
+
+    let minimized = minimize(&actions, Path::new("."), None, None).unwrap();
+    assert_eq!(minimized.indices, [0, 6]);
+    assert_eq!(minimized.failure.kind, "CreateBuffer");
+    assert!(minimized.replays > 1);
+
+    // Only the submission that uses the bad buffer can fail the same way.
+    let minimized = minimize(&actions, Path::new("."), None, Some(7)).unwrap();
+    assert_eq!(minimized.indices, [0, 6, 7]);
+    assert_eq!(minimized.failure.kind, "Submit");
+}
+
+#[test]
+fn diff_matches_resources_by_label() {
+    let old = parse(&[
+        INIT,
+        &buffer(0, "a", 16, false),
+        &buffer(1, "b", 16, false),
+        "Submit(1, [ClearBuffer(dst: Id(0, 1, Empty), offset: 0, size: None)])",
+    ]);
+    // Creating the buffers in another order changes their ids, but not their names.
+    let new = parse(&[
+        INIT,
+        &buffer(0, "b", 32, false),
+        &buffer(1, "a", 16, false),
+        "Submit(1, [ClearBuffer(dst: Id(1, 1, Empty), offset: 0, size: None), ClearBuffer(dst: Id(0, 1, Empty), offset: 0, size: None)])",
+    ]);
+
+    let diff = TraceDiff::new(&old, Path::new("."), &new, Path::new(".")).unwrap();
+    assert_eq!(diff.resources.len(), 1);
+    assert_eq!(diff.resources[0].name, "Buffer(\"b\")#0");
+
+    let changed = diff
+        .lines
+        .iter()
+        .filter_map(|line| match *line {
+            player::diff::Line::Same(_) => None,
+            ref line => Some(line),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        changed,
+        [&player::diff::Line::Added(
+            "    ClearBuffer(dst:Buffer(\"b\")#0,offset:0,size:None)".to_string()
+        )]
+    );
+
+    assert!(TraceDiff::new(&old, Path::new("."), &old, Path::new("."))
+        .unwrap()
+        .is_empty());
+}
@@ -24,6 +24,11 @@ pub fn initialize_instance() -> Instance {
         flags: wgpu::InstanceFlags::debugging().with_env(),
         dx12_shader_compiler,
         gles_minor_version,
+        empty_backend_options: Default::default(),
+        // This must match wgpu-info, which lists the adapters that tests run on.
+        cpu_backend_options: wgpu::CpuBackendOptions {
+            enable: cfg!(feature = "cpu"),
+        },
     })
 }
 
@@ -24,6 +24,10 @@ pub struct TestParameters {
 
     /// Conditions under which this test should be run, but is expected to fail.
     pub failures: Vec<FailureCase>,
+
+    /// If this test only uses transfers and compute dispatches, which the CPU
+    /// backend can execute.
+    pub runs_on_cpu: bool,
 }
 
 impl Default for TestParameters {
@@ -34,6 +38,7 @@ impl Default for TestParameters {
             required_limits: Limits::downlevel_webgl2_defaults(),
             skips: Vec::new(),
             failures: Vec::new(),
+            runs_on_cpu: false,
         }
     }
 }
@@ -74,6 +79,13 @@ impl TestParameters {
         self.skips.push(when);
         self
     }
+
+    /// Run the test on the CPU backend as well. Only tests that don't render
+    /// should opt into this.
+    pub fn run_on_cpu(mut self) -> Self {
+        self.runs_on_cpu = true;
+        self
+    }
 }
 
 /// Information about a test, including if if it should be skipped.
@@ -87,7 +99,7 @@ pub struct TestInfo {
 impl TestInfo {
     pub(crate) fn from_configuration(test: &GpuTestConfiguration, adapter: &AdapterReport) -> Self {
         // Figure out if a test is unsupported, and why.
-        let mut unsupported_reasons: ArrayVec<_, 4> = ArrayVec::new();
+        let mut unsupported_reasons: ArrayVec<_, 5> = ArrayVec::new();
         let missing_features = test.params.required_features - adapter.features;
         if !missing_features.is_empty() {
             unsupported_reasons.push("Features");
@@ -107,6 +119,10 @@ impl TestInfo {
             unsupported_reasons.push("Shader Model");
         }
 
+        if adapter.info.backend == wgt::Backend::Cpu && !test.params.runs_on_cpu {
+            unsupported_reasons.push("CPU Backend");
+        }
+
         // Produce a lower-case version of the adapter info, for comparison against
         // `parameters.skips` and `parameters.failures`.
         let adapter_lowercase_info = wgt::AdapterInfo {
@@ -89,68 +89,157 @@ static EMPTY_BUFFER: GpuTestConfiguration = GpuTestConfiguration::new()
     });
 
 #[gpu_test]
-static MAP_OFFSET: GpuTestConfiguration = GpuTestConfiguration::new().run_sync(|ctx| {
-    // This test writes 16 bytes at the beginning of buffer mapped mapped with
-    // an offset of 32 bytes. Then the buffer is copied into another buffer that
-    // is read back and we check that the written bytes are correctly placed at
-    // offset 32..48.
-    // The goal is to check that get_mapped_range did not accidentally double-count
-    // the mapped offset.
-
-    let write_buf = ctx.device.create_buffer(&wgpu::BufferDescriptor {
-        label: None,
-        size: 256,
-        usage: wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC,
-        mapped_at_creation: false,
-    });
-    let read_buf = ctx.device.create_buffer(&wgpu::BufferDescriptor {
-        label: None,
-        size: 256,
-        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
-        mapped_at_creation: false,
-    });
-
-    write_buf
-        .slice(32..)
-        .map_async(wgpu::MapMode::Write, move |result| {
-            result.unwrap();
+static MAP_OFFSET: GpuTestConfiguration = GpuTestConfiguration::new()
+    .parameters(TestParameters::default().run_on_cpu())
+    .run_sync(|ctx| {
+        // This test writes 16 bytes at the beginning of buffer mapped mapped with
+        // an offset of 32 bytes. Then the buffer is copied into another buffer that
+        // is read back and we check that the written bytes are correctly placed at
+        // offset 32..48.
+        // The goal is to check that get_mapped_range did not accidentally double-count
+        // the mapped offset.
+
+        let write_buf = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 256,
+            usage: wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let read_buf = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 256,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
         });
 
-    ctx.device.poll(wgpu::MaintainBase::Wait);
+        write_buf
+            .slice(32..)
+            .map_async(wgpu::MapMode::Write, move |result| {
+                result.unwrap();
+            });
 
-    {
-        let slice = write_buf.slice(32..48);
-        let mut view = slice.get_mapped_range_mut();
-        for byte in &mut view[..] {
-            *byte = 2;
+        ctx.device.poll(wgpu::MaintainBase::Wait);
+
+        {
+            let slice = write_buf.slice(32..48);
+            let mut view = slice.get_mapped_range_mut();
+            for byte in &mut view[..] {
+                *byte = 2;
+            }
         }
-    }
 
-    write_buf.unmap();
+        write_buf.unmap();
 
-    let mut encoder = ctx
-        .device
-        .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
 
-    encoder.copy_buffer_to_buffer(&write_buf, 0, &read_buf, 0, 256);
+        encoder.copy_buffer_to_buffer(&write_buf, 0, &read_buf, 0, 256);
 
-    ctx.queue.submit(Some(encoder.finish()));
+        ctx.queue.submit(Some(encoder.finish()));
 
-    read_buf
-        .slice(..)
-        .map_async(wgpu::MapMode::Read, Result::unwrap);
+        read_buf
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, Result::unwrap);
 
-    ctx.device.poll(wgpu::MaintainBase::Wait);
+        ctx.device.poll(wgpu::MaintainBase::Wait);
 
-    let slice = read_buf.slice(..);
-    let view = slice.get_mapped_range();
-    for byte in &view[0..32] {
-        assert_eq!(*byte, 0);
-    }
-    for byte in &view[32..48] {
-        assert_eq!(*byte, 2);
-    }
-    for byte in &view[48..] {
-        assert_eq!(*byte, 0);
-    }
-});
+        let slice = read_buf.slice(..);
+        let view = slice.get_mapped_range();
+        for byte in &view[0..32] {
+            assert_eq!(*byte, 0);
+        }
+        for byte in &view[32..48] {
+            assert_eq!(*byte, 2);
+        }
+        for byte in &view[48..] {
+            assert_eq!(*byte, 0);
+        }
+    });
+
+#[gpu_test]
+static ALIASED_STORAGE_BINDINGS: GpuTestConfiguration = GpuTestConfiguration::new()
+    .parameters(
+        TestParameters::default()
+            .downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+            .limits(wgpu::Limits::downlevel_defaults())
+            .run_on_cpu(),
+    )
+    .run_sync(|ctx| {
+        // Both bindings view the same buffer, so each sees what the other
+        // wrote.
+        let module = ctx
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(
+                    "
+                    @group(0) @binding(0) var<storage, read_write> a: array<u32>;
+                    @group(0) @binding(1) var<storage, read_write> b: array<u32>;
+
+                    @compute @workgroup_size(1)
+                    fn main() {
+                        a[0] = 5u;
+                        b[1] = b[0] + 1u;
+                    }
+                    "
+                    .into(),
+                ),
+            });
+        let pipeline = ctx
+            .device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: None,
+                layout: None,
+                module: &module,
+                entry_point: "main",
+            });
+
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 8,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let read_buf = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 8,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            cpass.set_pipeline(&pipeline);
+            cpass.set_bind_group(0, &bind_group, &[]);
+            cpass.dispatch_workgroups(1, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&buffer, 0, &read_buf, 0, 8);
+        ctx.queue.submit(Some(encoder.finish()));
+
+        read_buf
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, Result::unwrap);
+        ctx.device.poll(wgpu::MaintainBase::Wait);
+
+        let view = read_buf.slice(..).get_mapped_range();
+        let words: &[u32] = bytemuck::cast_slice(&view);
+        assert_eq!(words, [5, 6]);
+    });
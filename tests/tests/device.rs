@@ -36,6 +36,8 @@ fn device_lifetime_check() {
         backends: wgpu::util::backend_bits_from_env().unwrap_or(wgpu::Backends::all()),
         dx12_shader_compiler: wgpu::util::dx12_shader_compiler_from_env().unwrap_or_default(),
         gles_minor_version: wgpu::util::gles_minor_version_from_env().unwrap_or_default(),
+        empty_backend_options: Default::default(),
+        cpu_backend_options: Default::default(),
         flags: wgpu::InstanceFlags::debugging().with_env(),
     });
 
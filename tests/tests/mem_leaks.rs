@@ -290,3 +290,114 @@ static SIMPLE_DRAW_CHECK_MEM_LEAKS: wgpu_test::GpuTestConfiguration =
                 cmb.draw(0..6, 0..1);
             })
         });
+
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    target_os = "emscripten",
+    feature = "webgl"
+))]
+#[wgpu_test::gpu_test]
+static LEAK_REPORT_LISTS_HOLDERS: wgpu_test::GpuTestConfiguration =
+    wgpu_test::GpuTestConfiguration::new().run_sync(|ctx| {
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("leaked"),
+            size: 1024,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        let bgl = ctx
+            .device
+            .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    count: None,
+                }],
+            });
+        let bg = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &bgl,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+
+        let find_buffer = |report: &wgpu::core::leak::LeakReport| {
+            report
+                .resources
+                .iter()
+                .find(|resource| resource.key.kind == "Buffer" && resource.size == Some(1024))
+                .cloned()
+        };
+        let report = ctx.device.leak_report();
+        let leaked = find_buffer(&report).unwrap();
+        assert!(leaked.held_by_user);
+        assert_eq!(leaked.held_by.len(), 1);
+        assert_eq!(leaked.held_by[0].kind, "BindGroup");
+
+        // The bind group keeps the buffer alive.
+        drop(buffer);
+        let leaked = find_buffer(&ctx.device.leak_report()).unwrap();
+        assert!(!leaked.held_by_user);
+        assert_eq!(leaked.held_by.len(), 1);
+
+        drop(bg);
+        ctx.device.poll(wgpu::Maintain::Wait);
+        assert!(find_buffer(&ctx.device.leak_report()).is_none());
+    });
+
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    target_os = "emscripten",
+    feature = "webgl"
+))]
+#[wgpu_test::gpu_test]
+static MEMORY_REPORT_COUNTS_BY_LABEL_PREFIX: wgpu_test::GpuTestConfiguration =
+    wgpu_test::GpuTestConfiguration::new().run_sync(|ctx| {
+        let buffers: Vec<_> = (0..2)
+            .map(|i| {
+                ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some(&format!("memory-report/buffer {i}")),
+                    size: 1024,
+                    usage: wgpu::BufferUsages::STORAGE,
+                    mapped_at_creation: false,
+                })
+            })
+            .collect();
+        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("memory-report.texture"),
+            size: wgpu::Extent3d {
+                width: 16,
+                height: 16,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+
+        let report = ctx.device.memory_report();
+        let usage = report.labels["memory-report"];
+        assert_eq!(usage.bytes, 2 * 1024 + 16 * 16 * 4);
+        assert_eq!(usage.count, 3);
+        let textures =
+            report.textures[&(wgpu::TextureFormat::Rgba8Unorm, wgpu::TextureDimension::D2)];
+        assert!(textures.bytes >= 16 * 16 * 4);
+
+        drop(buffers);
+        drop(texture);
+        ctx.device.poll(wgpu::Maintain::Wait);
+        let usage = ctx.device.memory_report().labels["memory-report"];
+        assert_eq!(usage.bytes, 0);
+        assert_eq!(usage.peak_bytes, 2 * 1024 + 16 * 16 * 4);
+    });
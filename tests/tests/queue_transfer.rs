@@ -1,10 +1,11 @@
 //! Tests for buffer copy validation.
 
-use wgpu_test::{fail, gpu_test, GpuTestConfiguration};
+use wgpu_test::{fail, gpu_test, GpuTestConfiguration, TestParameters};
 
 #[gpu_test]
-static QUEUE_WRITE_TEXTURE_OVERFLOW: GpuTestConfiguration =
-    GpuTestConfiguration::new().run_sync(|ctx| {
+static QUEUE_WRITE_TEXTURE_OVERFLOW: GpuTestConfiguration = GpuTestConfiguration::new()
+    .parameters(TestParameters::default().run_on_cpu())
+    .run_sync(|ctx| {
         let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
             label: None,
             size: wgpu::Extent3d {
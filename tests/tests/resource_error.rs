@@ -46,3 +46,83 @@ static BAD_TEXTURE: GpuTestConfiguration = GpuTestConfiguration::new().run_sync(
     valid(&ctx.device, || texture.destroy());
     valid(&ctx.device, || texture.destroy());
 });
+
+#[gpu_test]
+static ERROR_DETAIL_HAS_PASS_SCOPES: GpuTestConfiguration =
+    GpuTestConfiguration::new().run_sync(|ctx| {
+        ctx.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let _ = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 4,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::VERTEX,
+            mapped_at_creation: false,
+        });
+        let error = pollster::block_on(ctx.device.pop_error_scope()).unwrap();
+        let detail = error.detail();
+        assert_eq!(detail.kind, wgpu::ErrorKind::Validation);
+        assert_eq!(detail.operation, "Device::create_buffer");
+        assert!(matches!(detail.error, wgpu::CoreError::CreateBuffer(_)));
+        assert_eq!(detail.error.cause().variant, "UsageMismatch");
+
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("uniforms"),
+            size: 4,
+            usage: wgpu::BufferUsages::UNIFORM,
+            mapped_at_creation: false,
+        });
+        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        ctx.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("frame"),
+            });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("main pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations::default(),
+                })],
+                ..Default::default()
+            });
+            // The buffer has no `VERTEX` usage.
+            pass.set_vertex_buffer(0, buffer.slice(..));
+        }
+        drop(encoder.finish());
+        let error = pollster::block_on(ctx.device.pop_error_scope()).unwrap();
+        let detail = error.detail();
+        assert!(matches!(detail.error, wgpu::CoreError::RenderPass(_)));
+        // Labels of resources are only kept in debug builds.
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            detail.scopes,
+            [
+                wgpu::CommandScope::Encoder {
+                    label: "frame".to_string()
+                },
+                wgpu::CommandScope::RenderPass {
+                    label: "main pass".to_string()
+                },
+                wgpu::CommandScope::SetVertexBuffer {
+                    buffer: "uniforms".to_string()
+                },
+            ]
+        );
+    });
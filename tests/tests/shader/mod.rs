@@ -17,6 +17,7 @@ use wgpu_test::TestingContext;
 
 pub mod numeric_builtins;
 pub mod struct_layout;
+pub mod wgslx;
 pub mod zero_init_workgroup_mem;
 
 #[derive(Clone, Copy, PartialEq)]
@@ -41,6 +41,7 @@ fn create_numeric_builtin_test() -> Vec<ShaderTest> {
 static NUMERIC_BUILTINS: GpuTestConfiguration = GpuTestConfiguration::new()
     .parameters(
         TestParameters::default()
+            .run_on_cpu()
             .downlevel_flags(DownlevelFlags::COMPUTE_SHADERS)
             .limits(Limits::downlevel_defaults()),
     )
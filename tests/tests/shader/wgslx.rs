@@ -0,0 +1,127 @@
+//! Tests for `include_wgslx!` and `include_wgslx_types!`.
+
+use wgpu::{
+    util::{BufferInitDescriptor, DeviceExt},
+    BindGroupDescriptor, BindGroupEntry, BufferDescriptor, BufferUsages, CommandEncoderDescriptor,
+    ComputePassDescriptor, ComputePipelineDescriptor, DownlevelFlags, Limits, Maintain, MapMode,
+};
+
+use wgpu_macros::include_wgslx;
+use wgpu_test::{gpu_test, GpuTestConfiguration, TestParameters};
+
+mod particles {
+    wgpu_macros::include_wgslx_types!("tests/shader/wgslx/particles.wgsl");
+}
+
+use particles::{Params, Particle};
+
+const PARTICLE_COUNT: u32 = 128;
+
+#[gpu_test]
+static WGSLX_PARTICLES: GpuTestConfiguration = GpuTestConfiguration::new()
+    .parameters(
+        TestParameters::default()
+            .downlevel_flags(DownlevelFlags::COMPUTE_SHADERS)
+            .limits(Limits::downlevel_defaults()),
+    )
+    .run_sync(|ctx| {
+        // `Particle` is 32 bytes in WGSL, with `mass` filling the padding after `position`.
+        assert_eq!(std::mem::size_of::<Particle>(), 32);
+
+        let input: Vec<Particle> = (0..PARTICLE_COUNT)
+            .map(|i| {
+                let mut particle: Particle = bytemuck::Zeroable::zeroed();
+                particle.position = [i as f32, 0.0, 0.0];
+                particle.mass = 1.0;
+                particle.velocity = [0.0, 1.0, i as f32];
+                particle
+            })
+            .collect();
+        let params = Params {
+            dt: 2.0,
+            count: PARTICLE_COUNT,
+        };
+
+        let params_buffer = ctx.device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("params"),
+            contents: bytemuck::bytes_of(&params),
+            usage: BufferUsages::UNIFORM,
+        });
+        let particle_buffer = ctx.device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("particles"),
+            contents: bytemuck::cast_slice(&input),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+        });
+        let mapping_buffer = ctx.device.create_buffer(&BufferDescriptor {
+            label: Some("mapping buffer"),
+            size: particle_buffer.size(),
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let sm = ctx
+            .device
+            .create_shader_module(include_wgslx!("tests/shader/wgslx/particles.wgsl"));
+
+        let pipeline = ctx
+            .device
+            .create_compute_pipeline(&ComputePipelineDescriptor {
+                label: None,
+                layout: None,
+                module: &sm,
+                entry_point: "main",
+            });
+
+        assert_eq!(particles::PARAMS_GROUP, particles::PARTICLES_GROUP);
+        let bg = ctx.device.create_bind_group(&BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(particles::PARAMS_GROUP),
+            entries: &[
+                BindGroupEntry {
+                    binding: particles::PARAMS_BINDING,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: particles::PARTICLES_BINDING,
+                    resource: particle_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor::default());
+
+        let mut cpass = encoder.begin_compute_pass(&ComputePassDescriptor::default());
+        cpass.set_pipeline(&pipeline);
+        cpass.set_bind_group(particles::PARAMS_GROUP, &bg, &[]);
+        cpass.dispatch_workgroups(PARTICLE_COUNT / 64, 1, 1);
+        drop(cpass);
+
+        encoder.copy_buffer_to_buffer(
+            &particle_buffer,
+            0,
+            &mapping_buffer,
+            0,
+            particle_buffer.size(),
+        );
+
+        ctx.queue.submit(Some(encoder.finish()));
+
+        mapping_buffer.slice(..).map_async(MapMode::Read, |_| ());
+        ctx.device.poll(Maintain::Wait);
+
+        let mapped = mapping_buffer.slice(..).get_mapped_range();
+        let output: &[Particle] = bytemuck::cast_slice(&mapped);
+
+        for (i, (before, after)) in input.iter().zip(output).enumerate() {
+            let expected = [
+                before.position[0],
+                before.position[1] + 2.0,
+                before.position[2] + 2.0 * i as f32,
+            ];
+            assert_eq!(after.position, expected, "particle {i}");
+            assert_eq!(after.mass, before.mass, "particle {i}");
+            assert_eq!(after.velocity, before.velocity, "particle {i}");
+        }
+    });
@@ -0,0 +1,11 @@
+struct Particle {
+    position: vec3<f32>,
+    mass: f32,
+    velocity: vec3<f32>,
+}
+
+fn advance(particle: Particle, dt: f32) -> Particle {
+    var out = particle;
+    out.position += particle.velocity * dt;
+    return out;
+}
@@ -0,0 +1,17 @@
+@import "tests/shader/wgslx/particle.wgsl";
+
+struct Params {
+    dt: f32,
+    count: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.count {
+        return;
+    }
+    particles[id.x] = advance(particles[id.x], params.dt);
+}
@@ -6,6 +6,7 @@ use wgpu_test::{gpu_test, FailureCase, GpuTestConfiguration, TestParameters};
 static WRITE_TEXTURE_SUBSET_2D: GpuTestConfiguration = GpuTestConfiguration::new()
     .parameters(
         TestParameters::default()
+            .run_on_cpu()
             // This just totally removes the device due to invalid api call.
             //
             // https://github.com/gfx-rs/wgpu/issues/3072
@@ -103,8 +104,9 @@ static WRITE_TEXTURE_SUBSET_2D: GpuTestConfiguration = GpuTestConfiguration::new
     });
 
 #[gpu_test]
-static WRITE_TEXTURE_SUBSET_3D: GpuTestConfiguration =
-    GpuTestConfiguration::new().run_sync(|ctx| {
+static WRITE_TEXTURE_SUBSET_3D: GpuTestConfiguration = GpuTestConfiguration::new()
+    .parameters(TestParameters::default().run_on_cpu())
+    .run_sync(|ctx| {
         let size = 256;
         let depth = 4;
         let tex = ctx.device.create_texture(&wgpu::TextureDescriptor {
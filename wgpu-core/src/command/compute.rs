@@ -125,6 +125,10 @@ impl ComputePass {
         self.parent_id
     }
 
+    pub fn label(&self) -> Option<&str> {
+        self.base.label.as_deref()
+    }
+
     #[cfg(feature = "trace")]
     pub fn into_command(self) -> crate::device::trace::Command {
         crate::device::trace::Command::RunComputePass {
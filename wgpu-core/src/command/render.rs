@@ -258,6 +258,10 @@ impl RenderPass {
         self.parent_id
     }
 
+    pub fn label(&self) -> Option<&str> {
+        self.base.label.as_deref()
+    }
+
     #[cfg(feature = "trace")]
     pub fn into_command(self) -> crate::device::trace::Command {
         crate::device::trace::Command::RunRenderPass {
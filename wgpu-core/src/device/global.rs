@@ -2190,6 +2190,16 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             all_queue_empty =
                 self.poll_device::<hal::api::Gles>(force_wait, &mut closures)? && all_queue_empty;
         }
+        #[cfg(feature = "empty")]
+        {
+            all_queue_empty =
+                self.poll_device::<hal::api::Empty>(force_wait, &mut closures)? && all_queue_empty;
+        }
+        #[cfg(feature = "cpu")]
+        {
+            all_queue_empty =
+                self.poll_device::<hal::api::Cpu>(force_wait, &mut closures)? && all_queue_empty;
+        }
 
         closures.fire();
 
@@ -2232,6 +2242,14 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
 
         let hub = A::hub(self);
         if let Some(device) = hub.devices.unregister(device_id) {
+            #[cfg(debug_assertions)]
+            {
+                let report = hub.leak_report(Some(&device));
+                if !report.is_empty() {
+                    log::warn!("Dropping device {device_id:?} while resources are alive: {report}");
+                }
+            }
+
             // The things `Device::prepare_to_die` takes care are mostly
             // unnecessary here. We know our queue is empty, so we don't
             // need to wait for submissions or triage them. We know we were
@@ -2260,6 +2278,55 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         }
     }
 
+    /// Look up the shader modules this device creates from WGSL, and the code
+    /// generated from them, in `cache`, and keep new ones there. `None` stops
+    /// using a cache.
+    ///
+    /// Only shader modules created afterwards are affected.
+    #[cfg(feature = "shader-cache")]
+    pub fn device_set_shader_cache<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        cache: Option<std::sync::Arc<crate::shader_cache::ShaderCache>>,
+    ) -> Result<(), InvalidDevice> {
+        api_log!("Device::set_shader_cache {device_id:?}");
+
+        let hub = A::hub(self);
+
+        let device = hub.devices.get(device_id).map_err(|_| InvalidDevice)?;
+        *device.shader_cache.lock() = cache;
+        Ok(())
+    }
+
+    /// List the resources of the device that are still alive, not counting
+    /// the device itself.
+    ///
+    /// See the [`leak`](crate::leak) module.
+    pub fn device_leak_report<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+    ) -> Result<crate::leak::LeakReport, InvalidDevice> {
+        let hub = A::hub(self);
+
+        let device = hub.devices.get(device_id).map_err(|_| InvalidDevice)?;
+        Ok(hub.leak_report(Some(&device)))
+    }
+
+    /// Report the memory that the resources of the device take.
+    ///
+    /// See the [`memory`](crate::memory) module.
+    pub fn device_memory_report<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+    ) -> Result<crate::memory::MemoryReport, InvalidDevice> {
+        let hub = A::hub(self);
+
+        let device = hub.devices.get(device_id).map_err(|_| InvalidDevice)?;
+        let pending_destructions = device.lock_life().pending_destructions();
+        let report = device.memory.lock().report(pending_destructions);
+        Ok(report)
+    }
+
     pub fn device_destroy<A: HalApi>(&self, device_id: DeviceId) {
         api_log!("Device::destroy {device_id:?}");
 
@@ -13,6 +13,7 @@ use crate::{
         QuerySetId, RenderBundleId, RenderPipelineId, SamplerId, StagingBufferId, TextureId,
         TextureViewId,
     },
+    memory::PendingDestructions,
     pipeline::{ComputePipeline, RenderPipeline},
     resource::{
         self, Buffer, QuerySet, Resource, ResourceType, Sampler, StagingBuffer, Texture,
@@ -34,6 +35,7 @@ pub(crate) trait ResourceMap: Any + WasmNotSendSync {
     fn as_any_mut(&mut self) -> &mut dyn Any;
     fn clear_map(&mut self);
     fn extend_map(&mut self, maps: &mut ResourceMaps);
+    fn len_map(&self) -> usize;
 }
 
 impl<Id, R> ResourceMap for FastHashMap<Id, Arc<R>>
@@ -57,6 +59,9 @@ where
             }
         }
     }
+    fn len_map(&self) -> usize {
+        self.len()
+    }
 }
 
 /// A struct that keeps lists of resources that are no longer needed by the user.
@@ -112,6 +117,9 @@ impl ResourceMaps {
             map.extend_map(&mut other);
         });
     }
+    pub(crate) fn len(&self) -> usize {
+        self.maps.values().map(|map| map.len_map()).sum()
+    }
     pub(crate) fn insert<Id, R>(&mut self, id: Id, r: Arc<R>) -> &mut Self
     where
         Id: id::TypedId,
@@ -390,6 +398,17 @@ impl<A: HalApi> LifetimeTracker<A> {
         }
     }
 
+    /// Count the resources that were released, but not freed yet.
+    pub(crate) fn pending_destructions(&self) -> PendingDestructions {
+        PendingDestructions {
+            suspected: self.suspected_resources.len()
+                + self.future_suspected_buffers.len()
+                + self.future_suspected_textures.len(),
+            in_flight: self.active.iter().map(|a| a.last_resources.len()).sum(),
+            ready: self.free_resources.len(),
+        }
+    }
+
     pub fn add_work_done_closure(&mut self, closure: SubmittedWorkDoneClosure) {
         match self.active.last_mut() {
             Some(active) => {
@@ -15,6 +15,7 @@ use crate::{
     id::{self, QueueId},
     identity::{GlobalIdentityHandlerFactory, Input},
     init_tracker::{has_copy_partial_init_tracker_coverage, TextureInitRange},
+    memory::MemoryKind,
     resource::{
         Buffer, BufferAccessError, BufferMapState, Resource, ResourceInfo, ResourceType,
         StagingBuffer, Texture, TextureInner,
@@ -319,6 +320,11 @@ fn prepare_staging_buffer<A: HalApi>(
         size,
         info: ResourceInfo::new("<StagingBuffer>"),
         is_coherent: mapping.is_coherent,
+        memory: device.memory.lock().allocate(
+            MemoryKind::StagingBuffer,
+            Some("(wgpu internal) Staging"),
+            size,
+        ),
     };
 
     Ok((staging_buffer, mapping.ptr.as_ptr()))
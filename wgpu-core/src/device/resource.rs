@@ -18,6 +18,7 @@ use crate::{
         TextureInitTracker, TextureInitTrackerAction,
     },
     instance::Adapter,
+    memory::{MemoryKind, MemoryTracker},
     pipeline,
     registry::Registry,
     resource::ResourceInfo,
@@ -126,6 +127,12 @@ pub struct Device<A: HalApi> {
     pub(crate) pending_writes: Mutex<Option<PendingWrites<A>>>,
     #[cfg(feature = "trace")]
     pub(crate) trace: Mutex<Option<trace::Trace>>,
+    #[cfg(feature = "shader-cache")]
+    pub(crate) shader_cache: Mutex<Option<Arc<crate::shader_cache::ShaderCache>>>,
+    /// The memory taken by the resources of this device.
+    ///
+    /// Has to be locked temporarily only (locked last).
+    pub(crate) memory: Mutex<MemoryTracker>,
 }
 
 impl<A: HalApi> std::fmt::Debug for Device<A> {
@@ -278,6 +285,9 @@ impl<A: HalApi> Device<A> {
             downlevel,
             instance_flags,
             pending_writes: Mutex::new(Some(pending_writes)),
+            #[cfg(feature = "shader-cache")]
+            shader_cache: Mutex::new(None),
+            memory: Mutex::new(MemoryTracker::default()),
         })
     }
 
@@ -537,6 +547,12 @@ impl<A: HalApi> Device<A> {
         };
         let buffer = unsafe { self.raw().create_buffer(&hal_desc) }.map_err(DeviceError::from)?;
 
+        // Transient buffers only stage data for other buffers.
+        let memory_kind = if transient {
+            MemoryKind::StagingBuffer
+        } else {
+            MemoryKind::Buffer(desc.usage)
+        };
         Ok(Buffer {
             raw: Some(buffer),
             device: self.clone(),
@@ -546,6 +562,10 @@ impl<A: HalApi> Device<A> {
             sync_mapped_writes: Mutex::new(None),
             map_state: Mutex::new(resource::BufferMapState::Idle),
             info: ResourceInfo::new(desc.label.borrow_or_default()),
+            memory: self
+                .memory
+                .lock()
+                .allocate(memory_kind, desc.label.as_deref(), desc.size),
         })
     }
 
@@ -559,7 +579,7 @@ impl<A: HalApi> Device<A> {
     ) -> Texture<A> {
         debug_assert_eq!(self.as_info().id().backend(), A::VARIANT);
 
-        Texture {
+        let mut texture = Texture {
             inner: RwLock::new(Some(resource::TextureInner::Native {
                 raw: Some(hal_texture),
             })),
@@ -577,7 +597,14 @@ impl<A: HalApi> Device<A> {
             },
             info: ResourceInfo::new(desc.label.borrow_or_default()),
             clear_mode: RwLock::new(clear_mode),
-        }
+            memory: None,
+        };
+        texture.memory = Some(self.memory.lock().allocate(
+            MemoryKind::Texture(desc.format, desc.dimension),
+            desc.label.as_deref(),
+            texture.estimated_size(),
+        ));
+        texture
     }
 
     pub fn create_buffer_from_hal(
@@ -596,6 +623,11 @@ impl<A: HalApi> Device<A> {
             sync_mapped_writes: Mutex::new(None),
             map_state: Mutex::new(resource::BufferMapState::Idle),
             info: ResourceInfo::new(desc.label.borrow_or_default()),
+            memory: self.memory.lock().allocate(
+                MemoryKind::Buffer(desc.usage),
+                desc.label.as_deref(),
+                desc.size,
+            ),
         }
     }
 
@@ -1266,20 +1298,51 @@ impl<A: HalApi> Device<A> {
         desc: &pipeline::ShaderModuleDescriptor<'a>,
         source: pipeline::ShaderModuleSource<'a>,
     ) -> Result<pipeline::ShaderModule<A>, pipeline::CreateShaderModuleError> {
-        let (module, source) = match source {
+        // The shader cache, and the key of the module in it.
+        #[cfg(feature = "shader-cache")]
+        let shader_cache: Option<(Arc<crate::shader_cache::ShaderCache>, u128)> = match source {
+            #[cfg(feature = "wgsl")]
+            pipeline::ShaderModuleSource::Wgsl(ref code) => {
+                self.shader_cache.lock().clone().map(|cache| {
+                    let key = crate::shader_cache::ShaderCache::module_key(
+                        A::VARIANT,
+                        self.features,
+                        self.downlevel.flags,
+                        code,
+                    );
+                    (cache, key)
+                })
+            }
+            _ => None,
+        };
+
+        // Modules from the shader cache come with their validation info.
+        let (module, source, cached_info) = match source {
             #[cfg(feature = "wgsl")]
             pipeline::ShaderModuleSource::Wgsl(code) => {
-                profiling::scope!("naga::wgsl::parse_str");
-                let module = naga::front::wgsl::parse_str(&code).map_err(|inner| {
-                    pipeline::CreateShaderModuleError::Parsing(pipeline::ShaderError {
-                        source: code.to_string(),
-                        label: desc.label.as_ref().map(|l| l.to_string()),
-                        inner: Box::new(inner),
-                    })
-                })?;
-                (Cow::Owned(module), code.into_owned())
+                #[cfg(feature = "shader-cache")]
+                let cached = shader_cache
+                    .as_ref()
+                    .and_then(|&(ref cache, key)| cache.load_module(key));
+                #[cfg(not(feature = "shader-cache"))]
+                let cached = None;
+
+                match cached {
+                    Some((module, info)) => (Cow::Owned(module), code.into_owned(), Some(info)),
+                    None => {
+                        profiling::scope!("naga::wgsl::parse_str");
+                        let module = naga::front::wgsl::parse_str(&code).map_err(|inner| {
+                            pipeline::CreateShaderModuleError::Parsing(pipeline::ShaderError {
+                                source: code.to_string(),
+                                label: desc.label.as_ref().map(|l| l.to_string()),
+                                inner: Box::new(inner),
+                            })
+                        })?;
+                        (Cow::Owned(module), code.into_owned(), None)
+                    }
+                }
             }
-            pipeline::ShaderModuleSource::Naga(module) => (module, String::new()),
+            pipeline::ShaderModuleSource::Naga(module) => (module, String::new(), None),
             pipeline::ShaderModuleSource::Dummy(_) => panic!("found `ShaderModuleSource::Dummy`"),
         };
         for (_, var) in module.global_variables.iter() {
@@ -1355,6 +1418,10 @@ impl<A: HalApi> Device<A> {
             Caps::DUAL_SOURCE_BLENDING,
             self.features.contains(wgt::Features::DUAL_SOURCE_BLENDING),
         );
+        caps.set(
+            Caps::SUBGROUP,
+            self.features.contains(wgt::Features::SUBGROUP),
+        );
         caps.set(
             Caps::CUBE_ARRAY_TEXTURES,
             self.downlevel
@@ -1371,27 +1438,49 @@ impl<A: HalApi> Device<A> {
                             .map_or("shader".to_string(), |l| l.to_string()),
                     ),
                     source_code: Cow::Owned(source.clone()),
+                    // Only WGSL comes with a source, parsed by `parse_str`.
+                    #[cfg(feature = "wgsl")]
+                    file_id: Some(naga::front::wgsl::source_provider::SingleFile::ID),
+                    #[cfg(not(feature = "wgsl"))]
+                    file_id: None,
                 })
             } else {
                 None
             };
 
-        let info = naga::valid::Validator::new(naga::valid::ValidationFlags::all(), caps)
-            .validate(&module)
-            .map_err(|inner| {
-                pipeline::CreateShaderModuleError::Validation(pipeline::ShaderError {
-                    source,
-                    label: desc.label.as_ref().map(|l| l.to_string()),
-                    inner: Box::new(inner),
-                })
-            })?;
+        let info = match cached_info {
+            Some(info) => info,
+            None => {
+                let info = naga::valid::Validator::new(naga::valid::ValidationFlags::all(), caps)
+                    .validate(&module)
+                    .map_err(|inner| {
+                        pipeline::CreateShaderModuleError::Validation(pipeline::ShaderError {
+                            source,
+                            label: desc.label.as_ref().map(|l| l.to_string()),
+                            inner: Box::new(inner),
+                        })
+                    })?;
+                #[cfg(feature = "shader-cache")]
+                if let Some((ref cache, key)) = shader_cache {
+                    cache.store_module(key, &module, &info);
+                }
+                info
+            }
+        };
 
         let interface =
-            validation::Interface::new(&module, &info, self.limits.clone(), self.features);
+            validation::Interface::new(&module, &info, self.limits.clone(), self.features)?;
         let hal_shader = hal::ShaderInput::Naga(hal::NagaShader {
             module,
             info,
             debug_source,
+            #[cfg(feature = "shader-cache")]
+            code_cache: shader_cache.map(|(cache, key)| {
+                let cache: Arc<dyn hal::ShaderCodeCache> = cache;
+                (cache, key)
+            }),
+            #[cfg(not(feature = "shader-cache"))]
+            code_cache: None,
         });
         let hal_desc = hal::ShaderModuleDescriptor {
             label: desc.label.to_hal(self.instance_flags),
@@ -3282,12 +3371,19 @@ impl<A: HalApi> Device<A> {
         }
 
         let hal_desc = desc.map_label(|label| label.to_hal(self.instance_flags));
-        Ok(QuerySet {
+        let mut query_set = QuerySet {
             raw: Some(unsafe { self.raw().create_query_set(&hal_desc).unwrap() }),
             device: self.clone(),
             info: ResourceInfo::new(""),
             desc: desc.map_label(|_| ()),
-        })
+            memory: None,
+        };
+        query_set.memory = Some(self.memory.lock().allocate(
+            MemoryKind::QuerySet,
+            desc.label.as_deref(),
+            query_set.estimated_size(),
+        ));
+        Ok(query_set)
     }
 
     pub(crate) fn lose(&self, message: &str) {
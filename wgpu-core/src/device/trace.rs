@@ -5,9 +5,13 @@ use std::{borrow::Cow, io::Write as _};
 
 //TODO: consider a readable Id that doesn't include the backend
 
+pub mod binary;
+
 type FileName = String;
 
 pub const FILE_NAME: &str = "trace.ron";
+/// Name of the trace file in the [binary format](binary).
+pub const BINARY_FILE_NAME: &str = "trace.bin";
 
 #[cfg(feature = "trace")]
 pub(crate) fn new_render_bundle_encoder_descriptor<'a>(
@@ -187,43 +191,118 @@ pub enum Command {
     },
 }
 
+/// How a [`Trace`] is written.
+#[cfg(feature = "trace")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// A pretty-printed RON array in [`FILE_NAME`], with the data in separate files.
+    #[default]
+    Ron,
+    /// Length-prefixed [binary] records in [`BINARY_FILE_NAME`], with the data inline.
+    Binary { compress: bool },
+}
+
+#[cfg(feature = "trace")]
+impl Format {
+    /// Pick the format from the `WGPU_TRACE_FORMAT` environment variable,
+    /// which is one of "ron", "binary" or "binary-compressed".
+    pub fn from_env() -> Self {
+        match std::env::var("WGPU_TRACE_FORMAT").as_deref() {
+            Ok("binary") => Self::Binary { compress: false },
+            Ok("binary-compressed") => Self::Binary { compress: true },
+            Ok("ron") | Err(_) => Self::Ron,
+            Ok(other) => {
+                log::warn!("Unknown trace format '{}', using RON", other);
+                Self::Ron
+            }
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+#[derive(Debug)]
+enum Output {
+    Ron {
+        file: std::fs::File,
+        config: ron::ser::PrettyConfig,
+        binary_id: usize,
+    },
+    Binary(binary::Writer),
+}
+
 #[cfg(feature = "trace")]
 #[derive(Debug)]
 pub struct Trace {
     path: std::path::PathBuf,
-    file: std::fs::File,
-    config: ron::ser::PrettyConfig,
-    binary_id: usize,
+    output: Output,
 }
 
 #[cfg(feature = "trace")]
 impl Trace {
+    /// Start a trace in the directory `path`, in the format picked by [`Format::from_env`].
     pub fn new(path: &std::path::Path) -> Result<Self, std::io::Error> {
-        log::info!("Tracing into '{:?}'", path);
-        let mut file = std::fs::File::create(path.join(FILE_NAME))?;
-        file.write_all(b"[\n")?;
+        Self::with_format(path, Format::from_env())
+    }
+
+    pub fn with_format(path: &std::path::Path, format: Format) -> Result<Self, std::io::Error> {
+        log::info!("Tracing into '{:?}' as {:?}", path, format);
+        let output = match format {
+            Format::Ron => {
+                let mut file = std::fs::File::create(path.join(FILE_NAME))?;
+                file.write_all(b"[\n")?;
+                Output::Ron {
+                    file,
+                    config: ron::ser::PrettyConfig::default(),
+                    binary_id: 0,
+                }
+            }
+            Format::Binary { compress } => {
+                let file = std::fs::File::create(path.join(BINARY_FILE_NAME))?;
+                let writer = binary::Writer::new(std::io::BufWriter::new(file), compress)?;
+                Output::Binary(writer)
+            }
+        };
         Ok(Self {
             path: path.to_path_buf(),
-            file,
-            config: ron::ser::PrettyConfig::default(),
-            binary_id: 0,
+            output,
         })
     }
 
     pub fn make_binary(&mut self, kind: &str, data: &[u8]) -> String {
-        self.binary_id += 1;
-        let name = format!("data{}.{}", self.binary_id, kind);
-        let _ = std::fs::write(self.path.join(&name), data);
-        name
+        match self.output {
+            Output::Ron {
+                ref mut binary_id, ..
+            } => {
+                *binary_id += 1;
+                let name = format!("data{}.{}", binary_id, kind);
+                let _ = std::fs::write(self.path.join(&name), data);
+                name
+            }
+            Output::Binary(ref mut writer) => writer.blob(kind, data).unwrap_or_else(|e| {
+                log::warn!("Trace blob write failure: {:?}", e);
+                String::new()
+            }),
+        }
     }
 
     pub(crate) fn add(&mut self, action: Action) {
-        match ron::ser::to_string_pretty(&action, self.config.clone()) {
-            Ok(string) => {
-                let _ = writeln!(self.file, "{},", string);
-            }
-            Err(e) => {
-                log::warn!("RON serialization failure: {:?}", e);
+        match self.output {
+            Output::Ron {
+                ref mut file,
+                ref config,
+                ..
+            } => match ron::ser::to_string_pretty(&action, config.clone()) {
+                Ok(string) => {
+                    let _ = writeln!(file, "{},", string);
+                }
+                Err(e) => {
+                    log::warn!("RON serialization failure: {:?}", e);
+                }
+            },
+            Output::Binary(ref mut writer) => {
+                if let Err(e) = writer.action(&action) {
+                    log::warn!("Binary trace write failure: {:?}", e);
+                }
             }
         }
     }
@@ -232,6 +311,13 @@ impl Trace {
 #[cfg(feature = "trace")]
 impl Drop for Trace {
     fn drop(&mut self) {
-        let _ = self.file.write_all(b"]");
+        match self.output {
+            Output::Ron { ref mut file, .. } => {
+                let _ = file.write_all(b"]");
+            }
+            Output::Binary(ref mut writer) => {
+                let _ = writer.flush();
+            }
+        }
     }
 }
@@ -0,0 +1,267 @@
+/*! Length-prefixed binary encoding of traces.
+
+A binary trace is a single file that can be written and replayed one action
+at a time, unlike the RON array that has to be parsed as a whole.
+
+The file starts with a header:
+  - [`MAGIC`],
+  - the format [`VERSION`] as a little-endian `u32`,
+  - the format flags as a little-endian `u32`, see [`FLAG_COMPRESSED`].
+
+The rest of the file, deflate-compressed if [`FLAG_COMPRESSED`] is set, is a
+sequence of records. Each record is a tag byte, the length of the payload as
+a little-endian `u64`, and the payload:
+  - [`RECORD_ACTION`]: an [`Action`] encoded with `bincode`,
+  - [`RECORD_BLOB`]: the data that actions refer to by name. The payload is the
+    length of the name as a little-endian `u32`, the name, and the data.
+
+Blobs are named after a hash of their contents, and written only once, before
+the first action that refers to them.
+!*/
+
+use super::Action;
+use std::io::{self, Read, Write};
+
+/// The first bytes of every binary trace.
+pub const MAGIC: [u8; 8] = *b"WGTRACE\0";
+/// The version of the format, bumped on incompatible changes.
+pub const VERSION: u32 = 1;
+/// The records are deflate-compressed.
+pub const FLAG_COMPRESSED: u32 = 1;
+
+pub const RECORD_ACTION: u8 = 0;
+pub const RECORD_BLOB: u8 = 1;
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Not a binary trace")]
+    NotATrace,
+    #[error("Unsupported binary trace version {0}, expected {VERSION}")]
+    UnsupportedVersion(u32),
+    #[error("Unknown record type {0}")]
+    UnknownRecord(u8),
+    #[error("Blob record is malformed")]
+    MalformedBlob,
+    #[error("Unable to encode or decode an action")]
+    Bincode(#[from] bincode::Error),
+}
+
+/// Writes actions and blobs in the binary format.
+#[cfg(feature = "trace")]
+pub struct Writer {
+    output: Box<dyn Write + Send>,
+    /// Names of the blobs written so far.
+    blobs: std::collections::HashSet<String>,
+    buffer: Vec<u8>,
+}
+
+#[cfg(feature = "trace")]
+impl std::fmt::Debug for Writer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Writer")
+            .field("blobs", &self.blobs.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "trace")]
+impl Writer {
+    /// Write the header to `output`, compressing everything that follows if `compress` is set.
+    pub fn new(mut output: impl Write + Send + 'static, compress: bool) -> io::Result<Self> {
+        let flags = if compress { FLAG_COMPRESSED } else { 0 };
+        output.write_all(&MAGIC)?;
+        output.write_all(&VERSION.to_le_bytes())?;
+        output.write_all(&flags.to_le_bytes())?;
+        let output: Box<dyn Write + Send> = if compress {
+            Box::new(flate2::write::DeflateEncoder::new(
+                output,
+                flate2::Compression::fast(),
+            ))
+        } else {
+            Box::new(output)
+        };
+        Ok(Self {
+            output,
+            blobs: Default::default(),
+            buffer: Vec::new(),
+        })
+    }
+
+    fn record(&mut self, tag: u8, payload: &[&[u8]]) -> io::Result<()> {
+        let length = payload.iter().map(|part| part.len() as u64).sum::<u64>();
+        self.output.write_all(&[tag])?;
+        self.output.write_all(&length.to_le_bytes())?;
+        for part in payload {
+            self.output.write_all(part)?;
+        }
+        Ok(())
+    }
+
+    /// Write `data` unless an identical blob was written already, and return its name.
+    ///
+    /// `kind` becomes the extension of the name, so that the player knows how to load it.
+    pub fn blob(&mut self, kind: &str, data: &[u8]) -> io::Result<String> {
+        use std::hash::{Hash as _, Hasher as _};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        data.hash(&mut hasher);
+        // The length makes a collision even less likely.
+        let name = format!("{:016x}-{}.{}", hasher.finish(), data.len(), kind);
+        if !self.blobs.contains(&name) {
+            let name_length = (name.len() as u32).to_le_bytes();
+            self.record(RECORD_BLOB, &[&name_length, name.as_bytes(), data])?;
+            self.blobs.insert(name.clone());
+        }
+        Ok(name)
+    }
+
+    pub fn action(&mut self, action: &Action) -> Result<(), Error> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        let result = bincode::serialize_into(&mut buffer, action)
+            .map_err(Error::from)
+            .and_then(|()| Ok(self.record(RECORD_ACTION, &[&buffer])?));
+        self.buffer = buffer;
+        result
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+/// Reads the actions of a binary trace one by one, keeping the blobs they refer to.
+#[cfg(feature = "replay")]
+pub struct Reader {
+    input: Box<dyn Read + Send>,
+    blobs: std::collections::HashMap<String, Vec<u8>>,
+}
+
+#[cfg(feature = "replay")]
+impl std::fmt::Debug for Reader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Reader")
+            .field("blobs", &self.blobs.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "replay")]
+impl Reader {
+    /// Read the header from `input`.
+    pub fn new(mut input: impl Read + Send + 'static) -> Result<Self, Error> {
+        let mut header = [0; MAGIC.len() + 8];
+        input.read_exact(&mut header).map_err(|e| match e.kind() {
+            io::ErrorKind::UnexpectedEof => Error::NotATrace,
+            _ => Error::Io(e),
+        })?;
+        if header[..MAGIC.len()] != MAGIC {
+            return Err(Error::NotATrace);
+        }
+        let word = |offset: usize| {
+            let bytes = &header[MAGIC.len() + offset..MAGIC.len() + offset + 4];
+            u32::from_le_bytes(bytes.try_into().unwrap())
+        };
+        let version = word(0);
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let input: Box<dyn Read + Send> = if word(4) & FLAG_COMPRESSED != 0 {
+            Box::new(flate2::read::DeflateDecoder::new(input))
+        } else {
+            Box::new(input)
+        };
+        Ok(Self {
+            input,
+            blobs: Default::default(),
+        })
+    }
+
+    pub fn open(path: &std::path::Path) -> Result<Self, Error> {
+        let file = std::fs::File::open(path)?;
+        Self::new(io::BufReader::new(file))
+    }
+
+    /// The data of a blob that one of the actions read so far refers to.
+    pub fn blob(&self, name: &str) -> Option<&[u8]> {
+        self.blobs.get(name).map(Vec::as_slice)
+    }
+
+    /// Read the header of the next record, or `None` at the end of the trace.
+    fn record_header(&mut self) -> Result<Option<(u8, u64)>, Error> {
+        let mut tag = [0];
+        match self.input.read(&mut tag) {
+            Ok(0) => return Ok(None),
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => return self.record_header(),
+            Err(e) => return Err(e.into()),
+        }
+        let mut length = [0; 8];
+        self.input.read_exact(&mut length)?;
+        Ok(Some((tag[0], u64::from_le_bytes(length))))
+    }
+
+    fn payload(&mut self, length: u64) -> io::Result<Vec<u8>> {
+        let mut payload = Vec::new();
+        (&mut self.input).take(length).read_to_end(&mut payload)?;
+        if (payload.len() as u64) < length {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(payload)
+    }
+
+    fn add_blob(&mut self, mut payload: Vec<u8>) -> Result<(), Error> {
+        let name_length = payload
+            .get(..4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+            .ok_or(Error::MalformedBlob)?;
+        let name = payload
+            .get(4..4 + name_length)
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .ok_or(Error::MalformedBlob)?
+            .to_owned();
+        payload.drain(..4 + name_length);
+        self.blobs.insert(name, payload);
+        Ok(())
+    }
+
+    /// Read the next action, along with the blobs that precede it.
+    pub fn next_action(&mut self) -> Result<Option<Action<'static>>, Error> {
+        while let Some((tag, length)) = self.record_header()? {
+            let payload = self.payload(length)?;
+            match tag {
+                RECORD_ACTION => return Ok(Some(bincode::deserialize(&payload)?)),
+                RECORD_BLOB => self.add_blob(payload)?,
+                _ => return Err(Error::UnknownRecord(tag)),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Skip the rest of the trace without decoding it, returning the number of actions left.
+    pub fn skip_to_end(&mut self) -> Result<usize, Error> {
+        let mut count = 0;
+        while let Some((tag, length)) = self.record_header()? {
+            if tag == RECORD_ACTION {
+                count += 1;
+            }
+            let skipped = io::copy(&mut (&mut self.input).take(length), &mut io::sink())?;
+            if skipped < length {
+                return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()));
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(feature = "replay")]
+impl Iterator for Reader {
+    type Item = Result<Action<'static>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_action().transpose()
+    }
+}
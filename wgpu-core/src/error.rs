@@ -6,6 +6,7 @@ use crate::{gfx_select, global::Global, identity::IdentityManagerFactory};
 pub struct ErrorFormatter<'a> {
     writer: &'a mut dyn fmt::Write,
     global: &'a Global<IdentityManagerFactory>,
+    labels: Vec<(String, String)>,
 }
 
 impl<'a> ErrorFormatter<'a> {
@@ -20,6 +21,8 @@ impl<'a> ErrorFormatter<'a> {
     pub fn label(&mut self, label_key: &str, label_value: &String) {
         if !label_key.is_empty() && !label_value.is_empty() {
             self.note(&format!("{label_key} = `{label_value}`"));
+            self.labels
+                .push((label_key.to_string(), label_value.clone()));
         }
     }
 
@@ -107,52 +110,78 @@ pub fn format_pretty_any(
     global: &Global<IdentityManagerFactory>,
     error: &(dyn Error + 'static),
 ) {
-    let mut fmt = ErrorFormatter { writer, global };
+    let mut fmt = ErrorFormatter {
+        writer,
+        global,
+        labels: Vec::new(),
+    };
+    fmt_pretty_any(&mut fmt, error);
+}
+
+/// The labels of the resources that `error` refers to, as `(key, label)`
+/// pairs, such as `("bind group", "shadows")`.
+///
+/// These are the labels that [`format_pretty_any`] adds as notes, so
+/// resources without labels are left out.
+pub fn pretty_labels_any(
+    global: &Global<IdentityManagerFactory>,
+    error: &(dyn Error + 'static),
+) -> Vec<(String, String)> {
+    let mut writer = String::new();
+    let mut fmt = ErrorFormatter {
+        writer: &mut writer,
+        global,
+        labels: Vec::new(),
+    };
+    fmt_pretty_any(&mut fmt, error);
+    fmt.labels
+}
 
+fn fmt_pretty_any(fmt: &mut ErrorFormatter, error: &(dyn Error + 'static)) {
     if let Some(pretty_err) = error.downcast_ref::<ContextError>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
 
     if let Some(pretty_err) = error.downcast_ref::<crate::command::RenderCommandError>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
     if let Some(pretty_err) = error.downcast_ref::<crate::binding_model::CreateBindGroupError>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
     if let Some(pretty_err) =
         error.downcast_ref::<crate::binding_model::CreatePipelineLayoutError>()
     {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
     if let Some(pretty_err) = error.downcast_ref::<crate::command::ExecutionError>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
     if let Some(pretty_err) = error.downcast_ref::<crate::command::RenderPassErrorInner>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
     if let Some(pretty_err) = error.downcast_ref::<crate::command::RenderPassError>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
     if let Some(pretty_err) = error.downcast_ref::<crate::command::ComputePassErrorInner>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
     if let Some(pretty_err) = error.downcast_ref::<crate::command::ComputePassError>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
     if let Some(pretty_err) = error.downcast_ref::<crate::command::RenderBundleError>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
     if let Some(pretty_err) = error.downcast_ref::<crate::command::TransferError>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
     if let Some(pretty_err) = error.downcast_ref::<crate::command::PassErrorScope>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
     if let Some(pretty_err) = error.downcast_ref::<crate::track::UsageConflict>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
     if let Some(pretty_err) = error.downcast_ref::<crate::command::QueryError>() {
-        return pretty_err.fmt_pretty(&mut fmt);
+        return pretty_err.fmt_pretty(fmt);
     }
 
     // default
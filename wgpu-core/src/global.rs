@@ -8,6 +8,7 @@ use crate::{
     id::SurfaceId,
     identity::GlobalIdentityHandlerFactory,
     instance::{Instance, Surface},
+    leak::LeakReport,
     registry::{Registry, RegistryReport},
     resource_log,
     storage::Element,
@@ -26,6 +27,10 @@ pub struct GlobalReport {
     pub dx11: Option<HubReport>,
     #[cfg(feature = "gles")]
     pub gl: Option<HubReport>,
+    #[cfg(feature = "empty")]
+    pub empty: Option<HubReport>,
+    #[cfg(feature = "cpu")]
+    pub cpu: Option<HubReport>,
 }
 
 impl GlobalReport {
@@ -44,6 +49,10 @@ impl GlobalReport {
             Backend::Dx11 => self.dx11.as_ref().unwrap(),
             #[cfg(feature = "gles")]
             Backend::Gl => self.gl.as_ref().unwrap(),
+            #[cfg(feature = "empty")]
+            Backend::Empty => self.empty.as_ref().unwrap(),
+            #[cfg(feature = "cpu")]
+            Backend::Cpu => self.cpu.as_ref().unwrap(),
             _ => panic!("HubReport is not supported on this backend"),
         }
     }
@@ -144,14 +153,69 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             } else {
                 None
             },
+            #[cfg(feature = "empty")]
+            empty: if self.instance.empty.is_some() {
+                Some(self.hubs.empty.generate_report())
+            } else {
+                None
+            },
+            #[cfg(feature = "cpu")]
+            cpu: if self.instance.cpu.is_some() {
+                Some(self.hubs.cpu.generate_report())
+            } else {
+                None
+            },
         }
     }
+
+    /// List the resources of every backend that are still alive.
+    ///
+    /// See the [`leak`](crate::leak) module.
+    pub fn leak_report(&self) -> LeakReport {
+        let mut report = LeakReport::default();
+        #[cfg(all(feature = "vulkan", not(target_arch = "wasm32")))]
+        {
+            report.extend(self.hubs.vulkan.leak_report(None));
+        }
+        #[cfg(all(feature = "metal", any(target_os = "macos", target_os = "ios")))]
+        {
+            report.extend(self.hubs.metal.leak_report(None));
+        }
+        #[cfg(all(feature = "dx12", windows))]
+        {
+            report.extend(self.hubs.dx12.leak_report(None));
+        }
+        #[cfg(all(feature = "dx11", windows))]
+        {
+            report.extend(self.hubs.dx11.leak_report(None));
+        }
+        #[cfg(feature = "gles")]
+        {
+            report.extend(self.hubs.gl.leak_report(None));
+        }
+        #[cfg(feature = "empty")]
+        {
+            report.extend(self.hubs.empty.leak_report(None));
+        }
+        #[cfg(feature = "cpu")]
+        {
+            report.extend(self.hubs.cpu.leak_report(None));
+        }
+        report
+    }
 }
 
 impl<G: GlobalIdentityHandlerFactory> Drop for Global<G> {
     fn drop(&mut self) {
         profiling::scope!("Global::drop");
         resource_log!("Global::drop");
+        #[cfg(debug_assertions)]
+        {
+            let report = self.leak_report();
+            if !report.is_empty() {
+                log::warn!("Dropping the global while resources are alive: {report}");
+            }
+        }
         let mut surfaces_locked = self.surfaces.write();
 
         // destroy hubs before the instance gets dropped
@@ -175,6 +239,14 @@ impl<G: GlobalIdentityHandlerFactory> Drop for Global<G> {
         {
             self.hubs.gl.clear(&surfaces_locked, true);
         }
+        #[cfg(feature = "empty")]
+        {
+            self.hubs.empty.clear(&surfaces_locked, true);
+        }
+        #[cfg(feature = "cpu")]
+        {
+            self.hubs.cpu.clear(&surfaces_locked, true);
+        }
 
         // destroy surfaces
         for element in surfaces_locked.map.drain(..) {
@@ -15,6 +15,7 @@ pub trait HalApi: hal::Api + 'static + WasmNotSendSync {
     fn get_surface(surface: &Surface) -> Option<&HalSurface<Self>>;
 }
 
+#[cfg(not(feature = "empty"))]
 impl HalApi for hal::api::Empty {
     const VARIANT: Backend = Backend::Empty;
     fn create_instance_from_hal(_: &str, _: Self::Instance) -> Instance {
@@ -31,6 +32,27 @@ impl HalApi for hal::api::Empty {
     }
 }
 
+#[cfg(feature = "empty")]
+impl HalApi for hal::api::Empty {
+    const VARIANT: Backend = Backend::Empty;
+    fn create_instance_from_hal(name: &str, hal_instance: Self::Instance) -> Instance {
+        Instance {
+            name: name.to_owned(),
+            empty: Some(hal_instance),
+            ..Default::default()
+        }
+    }
+    fn instance_as_hal(instance: &Instance) -> Option<&Self::Instance> {
+        instance.empty.as_ref()
+    }
+    fn hub<G: GlobalIdentityHandlerFactory>(global: &Global<G>) -> &Hub<Self> {
+        &global.hubs.empty
+    }
+    fn get_surface(surface: &Surface) -> Option<&HalSurface<Self>> {
+        surface.raw.downcast_ref()
+    }
+}
+
 #[cfg(all(feature = "vulkan", not(target_arch = "wasm32")))]
 impl HalApi for hal::api::Vulkan {
     const VARIANT: Backend = Backend::Vulkan;
@@ -136,3 +158,24 @@ impl HalApi for hal::api::Gles {
         surface.raw.downcast_ref()
     }
 }
+
+#[cfg(feature = "cpu")]
+impl HalApi for hal::api::Cpu {
+    const VARIANT: Backend = Backend::Cpu;
+    fn create_instance_from_hal(name: &str, hal_instance: Self::Instance) -> Instance {
+        Instance {
+            name: name.to_owned(),
+            cpu: Some(hal_instance),
+            ..Default::default()
+        }
+    }
+    fn instance_as_hal(instance: &Instance) -> Option<&Self::Instance> {
+        instance.cpu.as_ref()
+    }
+    fn hub<G: GlobalIdentityHandlerFactory>(global: &Global<G>) -> &Hub<Self> {
+        &global.hubs.cpu
+    }
+    fn get_surface(surface: &Surface) -> Option<&HalSurface<Self>> {
+        surface.raw.downcast_ref()
+    }
+}
@@ -118,6 +118,7 @@ use crate::{
     id,
     identity::GlobalIdentityHandlerFactory,
     instance::{Adapter, HalSurface, Surface},
+    leak::{self, LeakReport},
     pipeline::{ComputePipeline, RenderPipeline, ShaderModule},
     registry::{Registry, RegistryReport},
     resource::{Buffer, QuerySet, Sampler, StagingBuffer, Texture, TextureView},
@@ -279,6 +280,27 @@ impl<A: HalApi> Hub<A> {
         }
     }
 
+    /// List the live resources of `device`, or of every device if it's `None`.
+    pub(crate) fn leak_report(&self, device: Option<&Device<A>>) -> LeakReport {
+        let mut collector = leak::Collector::new(device);
+        collector.collect(&self.devices);
+        collector.collect(&self.pipeline_layouts);
+        collector.collect(&self.shader_modules);
+        collector.collect(&self.bind_group_layouts);
+        collector.collect(&self.bind_groups);
+        collector.collect(&self.command_buffers);
+        collector.collect(&self.render_bundles);
+        collector.collect(&self.render_pipelines);
+        collector.collect(&self.compute_pipelines);
+        collector.collect(&self.query_sets);
+        collector.collect(&self.buffers);
+        collector.collect(&self.staging_buffers);
+        collector.collect(&self.textures);
+        collector.collect(&self.texture_views);
+        collector.collect(&self.samplers);
+        collector.finish()
+    }
+
     pub fn generate_report(&self) -> HubReport {
         HubReport {
             adapters: self.adapters.generate_report(),
@@ -312,12 +334,18 @@ pub struct Hubs {
     pub(crate) dx11: Hub<hal::api::Dx11>,
     #[cfg(feature = "gles")]
     pub(crate) gl: Hub<hal::api::Gles>,
-    #[cfg(all(
-        not(all(feature = "vulkan", not(target_arch = "wasm32"))),
-        not(all(feature = "metal", any(target_os = "macos", target_os = "ios"))),
-        not(all(feature = "dx12", windows)),
-        not(all(feature = "dx11", windows)),
-        not(feature = "gles"),
+    #[cfg(feature = "cpu")]
+    pub(crate) cpu: Hub<hal::api::Cpu>,
+    #[cfg(any(
+        feature = "empty",
+        all(
+            not(all(feature = "vulkan", not(target_arch = "wasm32"))),
+            not(all(feature = "metal", any(target_os = "macos", target_os = "ios"))),
+            not(all(feature = "dx12", windows)),
+            not(all(feature = "dx11", windows)),
+            not(feature = "gles"),
+            not(feature = "cpu"),
+        )
     ))]
     pub(crate) empty: Hub<hal::api::Empty>,
 }
@@ -335,12 +363,18 @@ impl Hubs {
             dx11: Hub::new(factory),
             #[cfg(feature = "gles")]
             gl: Hub::new(factory),
-            #[cfg(all(
-                not(all(feature = "vulkan", not(target_arch = "wasm32"))),
-                not(all(feature = "metal", any(target_os = "macos", target_os = "ios"))),
-                not(all(feature = "dx12", windows)),
-                not(all(feature = "dx11", windows)),
-                not(feature = "gles"),
+            #[cfg(feature = "cpu")]
+            cpu: Hub::new(factory),
+            #[cfg(any(
+                feature = "empty",
+                all(
+                    not(all(feature = "vulkan", not(target_arch = "wasm32"))),
+                    not(all(feature = "metal", any(target_os = "macos", target_os = "ios"))),
+                    not(all(feature = "dx12", windows)),
+                    not(all(feature = "dx11", windows)),
+                    not(feature = "gles"),
+                    not(feature = "cpu"),
+                )
             ))]
             empty: Hub::new(factory),
         }
@@ -133,6 +133,7 @@ where
             3 => Backend::Dx12,
             4 => Backend::Dx11,
             5 => Backend::Gl,
+            7 => Backend::Cpu,
             _ => unreachable!(),
         }
     }
@@ -163,6 +164,7 @@ where
             Backend::Gl => "gl",
             Backend::BrowserWebGpu => "webgpu",
             Backend::Empty => "_",
+            Backend::Cpu => "cpu",
         };
         write!(formatter, "Id({index},{epoch},{backend})")?;
         Ok(())
@@ -282,6 +284,7 @@ fn test_id_backend() {
         Backend::Dx12,
         Backend::Dx11,
         Backend::Gl,
+        Backend::Cpu,
     ] {
         let id: Id<()> = Id::zip(1, 0, b);
         let (_id, _epoch, backend) = id.unzip();
@@ -302,6 +305,7 @@ fn test_id() {
         Backend::Dx12,
         Backend::Dx11,
         Backend::Gl,
+        Backend::Cpu,
     ];
     for &i in &indexes {
         for &e in &epochs {
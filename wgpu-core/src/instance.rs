@@ -72,6 +72,10 @@ pub struct Instance {
     pub dx11: Option<HalInstance<hal::api::Dx11>>,
     #[cfg(feature = "gles")]
     pub gl: Option<HalInstance<hal::api::Gles>>,
+    #[cfg(feature = "empty")]
+    pub empty: Option<HalInstance<hal::api::Empty>>,
+    #[cfg(feature = "cpu")]
+    pub cpu: Option<HalInstance<hal::api::Cpu>>,
     pub flags: wgt::InstanceFlags,
 }
 
@@ -84,6 +88,8 @@ impl Instance {
                     flags: instance_desc.flags,
                     dx12_shader_compiler: instance_desc.dx12_shader_compiler.clone(),
                     gles_minor_version: instance_desc.gles_minor_version,
+                    empty_backend_options: instance_desc.empty_backend_options.clone(),
+                    cpu_backend_options: instance_desc.cpu_backend_options.clone(),
                 };
                 match unsafe { hal::Instance::init(&hal_desc) } {
                     Ok(instance) => {
@@ -117,6 +123,10 @@ impl Instance {
             dx11: init(hal::api::Dx11, &instance_desc),
             #[cfg(feature = "gles")]
             gl: init(hal::api::Gles, &instance_desc),
+            #[cfg(feature = "empty")]
+            empty: init(hal::api::Empty, &instance_desc),
+            #[cfg(feature = "cpu")]
+            cpu: init(hal::api::Cpu, &instance_desc),
             flags: instance_desc.flags,
         }
     }
@@ -853,6 +863,15 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         self.enumerate(hal::api::Dx11, &self.instance.dx11, &inputs, &mut adapters);
         #[cfg(feature = "gles")]
         self.enumerate(hal::api::Gles, &self.instance.gl, &inputs, &mut adapters);
+        #[cfg(feature = "empty")]
+        self.enumerate(
+            hal::api::Empty,
+            &self.instance.empty,
+            &inputs,
+            &mut adapters,
+        );
+        #[cfg(feature = "cpu")]
+        self.enumerate(hal::api::Cpu, &self.instance.cpu, &inputs, &mut adapters);
 
         adapters
     }
@@ -978,6 +997,24 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             desc.force_fallback_adapter,
             &mut device_types,
         );
+        #[cfg(feature = "empty")]
+        let (id_empty, adapters_empty) = gather(
+            hal::api::Empty,
+            self.instance.empty.as_ref(),
+            &inputs,
+            compatible_surface,
+            desc.force_fallback_adapter,
+            &mut device_types,
+        );
+        #[cfg(feature = "cpu")]
+        let (id_cpu, adapters_cpu) = gather(
+            hal::api::Cpu,
+            self.instance.cpu.as_ref(),
+            &inputs,
+            compatible_surface,
+            desc.force_fallback_adapter,
+            &mut device_types,
+        );
 
         if device_types.is_empty() {
             return Err(RequestAdapterError::NotFound);
@@ -1050,6 +1087,14 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         if let Some(id) = self.select(&mut selected, id_gl, adapters_gl) {
             return Ok(id);
         }
+        #[cfg(feature = "empty")]
+        if let Some(id) = self.select(&mut selected, id_empty, adapters_empty) {
+            return Ok(id);
+        }
+        #[cfg(feature = "cpu")]
+        if let Some(id) = self.select(&mut selected, id_cpu, adapters_cpu) {
+            return Ok(id);
+        }
         let _ = selected;
 
         log::warn!("Some adapters are present, but enumerating them failed!");
@@ -1080,6 +1125,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Backend::Dx11 => fid.assign(Adapter::new(hal_adapter)),
                 #[cfg(feature = "gles")]
                 Backend::Gl => fid.assign(Adapter::new(hal_adapter)),
+                #[cfg(feature = "empty")]
+                Backend::Empty => fid.assign(Adapter::new(hal_adapter)),
+                #[cfg(feature = "cpu")]
+                Backend::Cpu => fid.assign(Adapter::new(hal_adapter)),
                 _ => unreachable!(),
             };
         resource_log!("Created Adapter {:?}", id);
@@ -1287,6 +1336,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
 /// - metal  = "metal" or "mtl"
 /// - gles   = "opengl" or "gles" or "gl"
 /// - webgpu = "webgpu"
+/// - empty  = "empty"
+/// - cpu    = "cpu"
 pub fn parse_backends_from_comma_list(string: &str) -> Backends {
     let mut backends = Backends::empty();
     for backend in string.to_lowercase().split(',') {
@@ -1297,6 +1348,8 @@ pub fn parse_backends_from_comma_list(string: &str) -> Backends {
             "metal" | "mtl" => Backends::METAL,
             "opengl" | "gles" | "gl" => Backends::GL,
             "webgpu" => Backends::BROWSER_WEBGPU,
+            "empty" => Backends::EMPTY,
+            "cpu" => Backends::CPU,
             b => {
                 log::warn!("unknown backend string '{}'", b);
                 continue;
@@ -0,0 +1,402 @@
+/*! Reports of the resources that are still alive.
+
+Unlike the counts of a [`GlobalReport`], a [`LeakReport`] lists every
+resource, including the ones the user released but that something else
+still refers to, along with what refers to them. Reports are logged in debug
+builds when a device or the global is dropped while resources are alive.
+
+Creation backtraces are only kept when backtraces are enabled with the
+`RUST_LIB_BACKTRACE` or `RUST_BACKTRACE` environment variables, as described
+in [`std::backtrace`]. Labels are only kept in debug builds.
+
+[`GlobalReport`]: crate::global::GlobalReport
+!*/
+
+use std::{collections::HashMap, fmt, ptr, sync::Arc};
+
+use crate::{
+    binding_model::{BindGroup, BindGroupLayout, PipelineLayout},
+    command::{CommandBuffer, RenderBundle},
+    device::Device,
+    hal_api::HalApi,
+    id::TypedId,
+    pipeline::{ComputePipeline, RenderPipeline, ShaderModule},
+    registry::Registry,
+    resource::{Buffer, QuerySet, Resource, Sampler, StagingBuffer, Texture, TextureView},
+    Epoch, Index,
+};
+
+/// Identifies a resource in a [`LeakReport`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ResourceKey {
+    /// The kind of resource, such as `"Buffer"`.
+    pub kind: &'static str,
+    pub index: Index,
+    pub epoch: Epoch,
+    pub backend: wgt::Backend,
+}
+
+impl ResourceKey {
+    fn new(kind: &'static str, id: impl TypedId) -> Self {
+        let (index, epoch, backend) = id.unzip();
+        Self {
+            kind,
+            index,
+            epoch,
+            backend,
+        }
+    }
+
+    fn of<I: TypedId, T: Resource<I>>(resource: &T) -> Self {
+        Self::new(T::TYPE, resource.as_info().id())
+    }
+}
+
+impl fmt::Display for ResourceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}({},{},{})",
+            self.kind,
+            self.index,
+            self.epoch,
+            self.backend.to_str()
+        )
+    }
+}
+
+/// A resource that is still alive.
+#[derive(Clone, Debug)]
+pub struct LiveResource {
+    pub key: ResourceKey,
+    /// Empty unless this is a debug build.
+    pub label: String,
+    /// The number of bytes of buffers, textures and query sets, estimated
+    /// from their descriptors.
+    pub size: Option<wgt::BufferAddress>,
+    /// Where the resource was created, if backtraces are enabled.
+    pub backtrace: Option<String>,
+    /// The number of references to the resource.
+    pub ref_count: usize,
+    /// Whether the user still has the id of the resource.
+    pub held_by_user: bool,
+    /// The live resources that refer to this one.
+    ///
+    /// Resources aren't listed as holders of their device.
+    pub held_by: Vec<ResourceKey>,
+}
+
+impl LiveResource {
+    /// The number of references that are neither the user's nor another
+    /// resource's, such as those of the device's trackers, and of
+    /// submissions that haven't completed.
+    pub fn internal_refs(&self) -> usize {
+        self.ref_count
+            .saturating_sub(usize::from(self.held_by_user) + self.held_by.len())
+    }
+}
+
+/// The resources that are still alive, grouped by kind.
+#[derive(Clone, Debug, Default)]
+pub struct LeakReport {
+    pub resources: Vec<LiveResource>,
+}
+
+impl LeakReport {
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    /// The sum of the known sizes of the resources.
+    pub fn total_size(&self) -> wgt::BufferAddress {
+        self.resources.iter().filter_map(|r| r.size).sum()
+    }
+
+    pub(crate) fn extend(&mut self, other: Self) {
+        self.resources.extend(other.resources);
+    }
+}
+
+impl fmt::Display for LeakReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Live resources: {}, taking {} bytes",
+            self.resources.len(),
+            self.total_size()
+        )?;
+        for resource in self.resources.iter() {
+            write!(f, "  {}", resource.key)?;
+            if !resource.label.is_empty() {
+                write!(f, " {:?}", resource.label)?;
+            }
+            if let Some(size) = resource.size {
+                write!(f, ", {size} bytes")?;
+            }
+            write!(f, ", {} references", resource.ref_count)?;
+            let mut holders = Vec::new();
+            if resource.held_by_user {
+                holders.push("the user".to_string());
+            }
+            holders.extend(resource.held_by.iter().map(ToString::to_string));
+            match resource.internal_refs() {
+                0 => {}
+                1 => holders.push("1 internal".to_string()),
+                count => holders.push(format!("{count} internal")),
+            }
+            if !holders.is_empty() {
+                write!(f, ": {}", holders.join(", "))?;
+            }
+            writeln!(f)?;
+            if let Some(ref backtrace) = resource.backtrace {
+                writeln!(f, "    created at:")?;
+                for line in backtrace.lines() {
+                    writeln!(f, "    {line}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a leak report needs to know about a kind of resource.
+pub(crate) trait Inspect<A: HalApi> {
+    /// The device the resource belongs to, unless it's a device.
+    fn device(&self) -> Option<&Arc<Device<A>>>;
+
+    fn size(&self) -> Option<wgt::BufferAddress> {
+        None
+    }
+
+    /// Call `refer` with every resource this one refers to, apart from its
+    /// device. Resources may be repeated.
+    fn references(&self, _refer: &mut dyn FnMut(ResourceKey)) {}
+}
+
+macro_rules! impl_inspect_device {
+    ($($ty:ident),*) => {
+        $(
+            impl<A: HalApi> Inspect<A> for $ty<A> {
+                fn device(&self) -> Option<&Arc<Device<A>>> {
+                    Some(&self.device)
+                }
+            }
+        )*
+    };
+}
+
+impl_inspect_device!(BindGroupLayout, CommandBuffer, Sampler, ShaderModule);
+
+impl<A: HalApi> Inspect<A> for Device<A> {
+    fn device(&self) -> Option<&Arc<Device<A>>> {
+        None
+    }
+}
+
+impl<A: HalApi> Inspect<A> for Buffer<A> {
+    fn device(&self) -> Option<&Arc<Device<A>>> {
+        Some(&self.device)
+    }
+
+    fn size(&self) -> Option<wgt::BufferAddress> {
+        Some(self.size)
+    }
+}
+
+impl<A: HalApi> Inspect<A> for StagingBuffer<A> {
+    fn device(&self) -> Option<&Arc<Device<A>>> {
+        Some(&self.device)
+    }
+
+    fn size(&self) -> Option<wgt::BufferAddress> {
+        Some(self.size)
+    }
+}
+
+impl<A: HalApi> Inspect<A> for Texture<A> {
+    fn device(&self) -> Option<&Arc<Device<A>>> {
+        Some(&self.device)
+    }
+
+    fn size(&self) -> Option<wgt::BufferAddress> {
+        Some(self.estimated_size())
+    }
+}
+
+impl<A: HalApi> Inspect<A> for QuerySet<A> {
+    fn device(&self) -> Option<&Arc<Device<A>>> {
+        Some(&self.device)
+    }
+
+    fn size(&self) -> Option<wgt::BufferAddress> {
+        Some(self.estimated_size())
+    }
+}
+
+impl<A: HalApi> Inspect<A> for TextureView<A> {
+    fn device(&self) -> Option<&Arc<Device<A>>> {
+        Some(&self.device)
+    }
+
+    fn references(&self, refer: &mut dyn FnMut(ResourceKey)) {
+        if let Some(ref parent) = *self.parent.read() {
+            refer(ResourceKey::of(&**parent));
+        }
+    }
+}
+
+impl<A: HalApi> Inspect<A> for BindGroup<A> {
+    fn device(&self) -> Option<&Arc<Device<A>>> {
+        Some(&self.device)
+    }
+
+    fn references(&self, refer: &mut dyn FnMut(ResourceKey)) {
+        refer(ResourceKey::of(&*self.layout));
+        for id in self.used.buffers.used_ids() {
+            refer(ResourceKey::new(Buffer::<A>::TYPE, id));
+        }
+        for id in self.used.textures.used_ids() {
+            refer(ResourceKey::new(Texture::<A>::TYPE, id));
+        }
+        for view in self.used.views.used_resources() {
+            refer(ResourceKey::of(&*view));
+        }
+        for sampler in self.used.samplers.used_resources() {
+            refer(ResourceKey::of(&*sampler));
+        }
+    }
+}
+
+impl<A: HalApi> Inspect<A> for PipelineLayout<A> {
+    fn device(&self) -> Option<&Arc<Device<A>>> {
+        Some(&self.device)
+    }
+
+    fn references(&self, refer: &mut dyn FnMut(ResourceKey)) {
+        for layout in self.bind_group_layouts.iter() {
+            refer(ResourceKey::of(&**layout));
+        }
+    }
+}
+
+impl<A: HalApi> Inspect<A> for ComputePipeline<A> {
+    fn device(&self) -> Option<&Arc<Device<A>>> {
+        Some(&self.device)
+    }
+
+    fn references(&self, refer: &mut dyn FnMut(ResourceKey)) {
+        refer(ResourceKey::of(&*self.layout));
+        refer(ResourceKey::of(&*self._shader_module));
+    }
+}
+
+impl<A: HalApi> Inspect<A> for RenderPipeline<A> {
+    fn device(&self) -> Option<&Arc<Device<A>>> {
+        Some(&self.device)
+    }
+
+    fn references(&self, refer: &mut dyn FnMut(ResourceKey)) {
+        refer(ResourceKey::of(&*self.layout));
+        for module in self._shader_modules.iter() {
+            refer(ResourceKey::of(&**module));
+        }
+    }
+}
+
+impl<A: HalApi> Inspect<A> for RenderBundle<A> {
+    fn device(&self) -> Option<&Arc<Device<A>>> {
+        Some(&self.device)
+    }
+
+    fn references(&self, refer: &mut dyn FnMut(ResourceKey)) {
+        for buffer in self.used.buffers.read().used_resources() {
+            refer(ResourceKey::of(&*buffer));
+        }
+        for texture in self.used.textures.read().used_resources() {
+            refer(ResourceKey::of(&*texture));
+        }
+        for bind_group in self.used.bind_groups.read().used_resources() {
+            refer(ResourceKey::of(&*bind_group));
+        }
+        for pipeline in self.used.render_pipelines.read().used_resources() {
+            refer(ResourceKey::of(&*pipeline));
+        }
+        for query_set in self.used.query_sets.read().used_resources() {
+            refer(ResourceKey::of(&*query_set));
+        }
+    }
+}
+
+/// Collects live resources, along with what they refer to, and works out
+/// the holders of each once every resource is found.
+pub(crate) struct Collector<'a, A: HalApi> {
+    device: Option<&'a Device<A>>,
+    found: Vec<(LiveResource, Vec<ResourceKey>)>,
+}
+
+impl<'a, A: HalApi> Collector<'a, A> {
+    /// Collect the resources of `device`, or of every device if it's `None`.
+    ///
+    /// Devices themselves are only collected in the latter case.
+    pub(crate) fn new(device: Option<&'a Device<A>>) -> Self {
+        Self {
+            device,
+            found: Vec::new(),
+        }
+    }
+
+    pub(crate) fn collect<I, T>(&mut self, registry: &Registry<I, T>)
+    where
+        I: TypedId,
+        T: Resource<I> + Inspect<A>,
+    {
+        for resource in registry.live_resources() {
+            if let Some(device) = self.device {
+                match resource.device() {
+                    Some(owner) if ptr::eq(&**owner, device) => {}
+                    _ => continue,
+                }
+            }
+            let mut references = Vec::new();
+            resource.references(&mut |key| references.push(key));
+            let live = LiveResource {
+                key: ResourceKey::of(&*resource),
+                label: resource.label(),
+                size: resource.size(),
+                backtrace: resource.as_info().backtrace().map(ToString::to_string),
+                // Not counting the reference `live_resources` returned.
+                ref_count: Arc::strong_count(&resource) - 1,
+                held_by_user: registry.is_registered(&resource),
+                held_by: Vec::new(),
+            };
+            self.found.push((live, references));
+        }
+    }
+
+    pub(crate) fn finish(self) -> LeakReport {
+        let positions = self
+            .found
+            .iter()
+            .enumerate()
+            .map(|(position, &(ref live, _))| (live.key, position))
+            .collect::<HashMap<_, _>>();
+        let mut resources = Vec::with_capacity(self.found.len());
+        let mut all_references = Vec::with_capacity(self.found.len());
+        for (live, references) in self.found {
+            resources.push(live);
+            all_references.push(references);
+        }
+        for (holder, references) in all_references.into_iter().enumerate() {
+            let holder_key = resources[holder].key;
+            for key in references {
+                if let Some(&position) = positions.get(&key) {
+                    let held_by = &mut resources[position].held_by;
+                    if !held_by.contains(&holder_key) {
+                        held_by.push(holder_key);
+                    }
+                }
+            }
+        }
+        LeakReport { resources }
+    }
+}
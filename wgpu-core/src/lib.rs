@@ -11,6 +11,7 @@
         not(all(feature = "dx12", windows)),
         not(all(feature = "dx11", windows)),
         not(feature = "gles"),
+        not(feature = "cpu"),
     ),
     allow(unused, clippy::let_and_return)
 )]
@@ -61,10 +62,14 @@ pub mod id;
 pub mod identity;
 mod init_tracker;
 pub mod instance;
+pub mod leak;
+pub mod memory;
 pub mod pipeline;
 pub mod present;
 pub mod registry;
 pub mod resource;
+#[cfg(feature = "shader-cache")]
+pub mod shader_cache;
 pub mod storage;
 mod track;
 mod validation;
@@ -219,6 +224,8 @@ define_backend_caller! { gfx_if_metal, gfx_if_metal_hidden, "metal" if all(featu
 define_backend_caller! { gfx_if_dx12, gfx_if_dx12_hidden, "dx12" if all(feature = "dx12", windows) }
 define_backend_caller! { gfx_if_dx11, gfx_if_dx11_hidden, "dx11" if all(feature = "dx11", windows) }
 define_backend_caller! { gfx_if_gles, gfx_if_gles_hidden, "gles" if feature = "gles" }
+define_backend_caller! { gfx_if_empty, gfx_if_empty_hidden, "empty" if feature = "empty" }
+define_backend_caller! { gfx_if_cpu, gfx_if_cpu_hidden, "cpu" if feature = "cpu" }
 
 /// Dispatch on an [`Id`]'s backend to a backend-generic method.
 ///
@@ -274,6 +281,8 @@ macro_rules! gfx_select {
             wgt::Backend::Dx12 => $crate::gfx_if_dx12!($global.$method::<$crate::api::Dx12>( $($param),* )),
             wgt::Backend::Dx11 => $crate::gfx_if_dx11!($global.$method::<$crate::api::Dx11>( $($param),* )),
             wgt::Backend::Gl => $crate::gfx_if_gles!($global.$method::<$crate::api::Gles>( $($param),+ )),
+            wgt::Backend::Empty => $crate::gfx_if_empty!($global.$method::<$crate::api::Empty>( $($param),* )),
+            wgt::Backend::Cpu => $crate::gfx_if_cpu!($global.$method::<$crate::api::Cpu>( $($param),* )),
             other => panic!("Unexpected backend {:?}", other),
         }
     };
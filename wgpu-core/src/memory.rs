@@ -0,0 +1,205 @@
+/*! Accounting of the memory that a device's resources take.
+
+Every device counts the bytes of the buffers, textures, staging buffers and
+query sets it creates, from their descriptors, and takes them off again when
+their raw resources are freed. So the counts need no support from the
+backends, but they are estimates: backends may add padding, or pack
+some formats more tightly.
+
+Bytes are counted by kind of resource, and by label prefix: the part of the
+label before its first `/`, `:` or `.`. Labelling resources like
+`"shadows/cascade 0"` thus lets a [`MemoryReport`] tell how much memory each
+subsystem of an application takes.
+!*/
+
+use crate::FastHashMap;
+
+/// The bytes taken by a set of resources.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// The bytes taken by the live resources.
+    pub bytes: wgt::BufferAddress,
+    /// The most bytes ever taken at once.
+    pub peak_bytes: wgt::BufferAddress,
+    /// The number of live resources.
+    pub count: usize,
+}
+
+impl MemoryUsage {
+    fn add(&mut self, size: wgt::BufferAddress) {
+        self.bytes += size;
+        self.peak_bytes = self.peak_bytes.max(self.bytes);
+        self.count += 1;
+    }
+
+    fn remove(&mut self, size: wgt::BufferAddress) {
+        self.bytes -= size;
+        self.count -= 1;
+    }
+}
+
+/// The numbers of resources that were released, but whose raw resources
+/// are not freed yet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PendingDestructions {
+    /// Resources the user released, which may still be used by commands
+    /// that are recorded or submitted.
+    pub suspected: usize,
+    /// Resources waiting for the submissions that use them to complete.
+    pub in_flight: usize,
+    /// Resources that will be freed the next time the device is maintained.
+    pub ready: usize,
+}
+
+/// The memory taken by the resources of a device. See the
+/// [module documentation](self).
+///
+/// Kinds and labels that have no live resources left stay in the report, so
+/// that their peaks can be seen.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryReport {
+    /// All resources of the device.
+    pub total: MemoryUsage,
+    /// Buffers, by their usages.
+    pub buffers: FastHashMap<wgt::BufferUsages, MemoryUsage>,
+    /// Textures, by their format and dimension.
+    pub textures: FastHashMap<(wgt::TextureFormat, wgt::TextureDimension), MemoryUsage>,
+    /// Buffers that `wgpu-core` creates to upload data, such as for
+    /// `Queue::write_buffer`, or for buffers mapped at creation.
+    pub staging_buffers: MemoryUsage,
+    pub query_sets: MemoryUsage,
+    /// Resources by label prefix. Unlabelled resources are counted under
+    /// the empty prefix.
+    pub labels: FastHashMap<String, MemoryUsage>,
+    pub pending_destructions: PendingDestructions,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum MemoryKind {
+    Buffer(wgt::BufferUsages),
+    Texture(wgt::TextureFormat, wgt::TextureDimension),
+    StagingBuffer,
+    QuerySet,
+}
+
+/// Memory counted by a [`MemoryTracker`], to be given back to it when the
+/// raw resource is freed.
+#[derive(Debug)]
+pub(crate) struct Allocation {
+    kind: MemoryKind,
+    label_prefix: String,
+    size: wgt::BufferAddress,
+}
+
+fn label_prefix(label: &str) -> &str {
+    match label.find(['/', ':', '.']) {
+        Some(end) => &label[..end],
+        None => label,
+    }
+}
+
+/// The memory taken by the resources of a device.
+#[derive(Debug, Default)]
+pub(crate) struct MemoryTracker {
+    total: MemoryUsage,
+    kinds: FastHashMap<MemoryKind, MemoryUsage>,
+    labels: FastHashMap<String, MemoryUsage>,
+}
+
+impl MemoryTracker {
+    pub(crate) fn allocate(
+        &mut self,
+        kind: MemoryKind,
+        label: Option<&str>,
+        size: wgt::BufferAddress,
+    ) -> Allocation {
+        let label_prefix = label_prefix(label.unwrap_or_default());
+        self.total.add(size);
+        self.kinds.entry(kind).or_default().add(size);
+        match self.labels.get_mut(label_prefix) {
+            Some(usage) => usage.add(size),
+            None => self
+                .labels
+                .entry(label_prefix.to_string())
+                .or_default()
+                .add(size),
+        }
+        Allocation {
+            kind,
+            label_prefix: label_prefix.to_string(),
+            size,
+        }
+    }
+
+    pub(crate) fn free(&mut self, allocation: &Allocation) {
+        self.total.remove(allocation.size);
+        if let Some(usage) = self.kinds.get_mut(&allocation.kind) {
+            usage.remove(allocation.size);
+        }
+        if let Some(usage) = self.labels.get_mut(&allocation.label_prefix) {
+            usage.remove(allocation.size);
+        }
+    }
+
+    pub(crate) fn report(&self, pending_destructions: PendingDestructions) -> MemoryReport {
+        let mut report = MemoryReport {
+            total: self.total,
+            labels: self.labels.clone(),
+            pending_destructions,
+            ..Default::default()
+        };
+        for (&kind, &usage) in self.kinds.iter() {
+            match kind {
+                MemoryKind::Buffer(usages) => {
+                    report.buffers.insert(usages, usage);
+                }
+                MemoryKind::Texture(format, dimension) => {
+                    report.textures.insert((format, dimension), usage);
+                }
+                MemoryKind::StagingBuffer => report.staging_buffers = usage,
+                MemoryKind::QuerySet => report.query_sets = usage,
+            }
+        }
+        report
+    }
+}
+
+#[test]
+fn memory_is_counted_with_peaks() {
+    let mut tracker = MemoryTracker::default();
+    let vertices = MemoryKind::Buffer(wgt::BufferUsages::VERTEX);
+    let a = tracker.allocate(vertices, Some("terrain/chunk 0"), 100);
+    let b = tracker.allocate(vertices, Some("terrain.chunk 1"), 50);
+    let c = tracker.allocate(MemoryKind::QuerySet, None, 8);
+    tracker.free(&a);
+    let d = tracker.allocate(MemoryKind::StagingBuffer, Some("ui"), 10);
+
+    let report = tracker.report(PendingDestructions::default());
+    assert_eq!(
+        report.total,
+        MemoryUsage {
+            bytes: 68,
+            peak_bytes: 158,
+            count: 3,
+        }
+    );
+    assert_eq!(
+        report.buffers[&wgt::BufferUsages::VERTEX],
+        MemoryUsage {
+            bytes: 50,
+            peak_bytes: 150,
+            count: 1,
+        }
+    );
+    assert_eq!(report.labels["terrain"].peak_bytes, 150);
+    assert_eq!(report.labels[""].bytes, 8);
+    assert_eq!(report.staging_buffers.bytes, 10);
+    assert_eq!(report.query_sets.count, 1);
+
+    for allocation in [b, c, d] {
+        tracker.free(&allocation);
+    }
+    let report = tracker.report(PendingDestructions::default());
+    assert_eq!(report.total.bytes, 0);
+    assert_eq!(report.labels["ui"].peak_bytes, 10);
+}
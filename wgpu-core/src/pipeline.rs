@@ -163,6 +163,8 @@ pub enum CreateShaderModuleError {
     Validation(#[from] ShaderError<naga::WithSpan<naga::valid::ValidationError>>),
     #[error(transparent)]
     MissingFeatures(#[from] MissingFeatures),
+    #[error("Unable to lay out the shader's types")]
+    Layout(#[from] naga::proc::LayoutError),
     #[error(
         "Shader global {bind:?} uses a group index {group} that exceeds the max_bind_groups limit of {limit}."
     )]
@@ -212,6 +214,59 @@ pub enum ImplicitLayoutError {
     Pipeline(#[from] CreatePipelineLayoutError),
 }
 
+#[derive(Clone, Debug, Error)]
+pub enum DeriveLayoutError {
+    #[error("Unable to lay out the shader's types")]
+    Layout(#[from] naga::proc::LayoutError),
+    #[error("Unable to derive bind group layouts from the {stage:?} entry point '{entry_point}'")]
+    EntryPoint {
+        stage: naga::ShaderStage,
+        entry_point: String,
+        #[source]
+        error: validation::StageError,
+    },
+}
+
+/// Derive the bind group layout entries used by `entry_points` of `module`.
+///
+/// This is the derivation that pipelines created without an explicit layout
+/// perform, exposed so that several pipelines can share the same layouts.
+/// Each resource is visible to the stages of the entry points that use it,
+/// and buffers get the `min_binding_size` of their type.
+///
+/// The result has one list of entries per bind group, up to the last group
+/// that is used, with entries sorted by binding. `module` must have been
+/// validated, producing `info`.
+pub fn derive_bind_group_layouts(
+    module: &naga::Module,
+    info: &naga::valid::ModuleInfo,
+    entry_points: &[(naga::ShaderStage, &str)],
+) -> Result<Vec<Vec<wgt::BindGroupLayoutEntry>>, DeriveLayoutError> {
+    // Limits and features only matter for the checks that derivation skips.
+    let interface =
+        validation::Interface::new(module, info, wgt::Limits::default(), wgt::Features::empty())?;
+
+    let mut derived_layouts = Vec::new();
+    for &(stage, entry_point) in entry_points {
+        interface
+            .derive_layouts(&mut derived_layouts, stage, entry_point)
+            .map_err(|error| DeriveLayoutError::EntryPoint {
+                stage,
+                entry_point: entry_point.to_string(),
+                error,
+            })?;
+    }
+
+    Ok(derived_layouts
+        .into_iter()
+        .map(|map| {
+            let mut entries = map.into_values().collect::<Vec<_>>();
+            entries.sort_by_key(|entry| entry.binding);
+            entries
+        })
+        .collect())
+}
+
 /// Describes a compute pipeline.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "trace", derive(serde::Serialize))]
@@ -521,3 +576,156 @@ impl<A: HalApi> RenderPipeline<A> {
         self.raw.as_ref().unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+    /// Append an entry point that loads each of `globals`.
+    fn add_entry_point(
+        module: &mut naga::Module,
+        name: &str,
+        stage: naga::ShaderStage,
+        globals: &[naga::Handle<naga::GlobalVariable>],
+    ) {
+        let mut function = naga::Function::default();
+        for &global in globals {
+            let pointer = function
+                .expressions
+                .append(naga::Expression::GlobalVariable(global), Default::default());
+            let start = function.expressions.len();
+            function
+                .expressions
+                .append(naga::Expression::Load { pointer }, Default::default());
+            function.body.push(
+                naga::Statement::Emit(function.expressions.range_from(start)),
+                Default::default(),
+            );
+        }
+        module.entry_points.push(naga::EntryPoint {
+            name: name.to_string(),
+            stage,
+            early_depth_test: None,
+            workgroup_size: match stage {
+                naga::ShaderStage::Compute => [1, 1, 1],
+                _ => [0, 0, 0],
+            },
+            function,
+            span: Default::default(),
+        });
+    }
+
+    #[test]
+    fn derive_shared_layouts() {
+        let mut module = naga::Module::default();
+        let vec4_ty = module.types.insert(
+            naga::Type {
+                name: None,
+                inner: naga::TypeInner::Vector {
+                    size: naga::VectorSize::Quad,
+                    scalar: naga::Scalar::F32,
+                },
+            },
+            Default::default(),
+        );
+        let u32_ty = module.types.insert(
+            naga::Type {
+                name: None,
+                inner: naga::TypeInner::Scalar(naga::Scalar::U32),
+            },
+            Default::default(),
+        );
+        let globals = module.global_variables.append(
+            naga::GlobalVariable {
+                name: Some("globals".to_string()),
+                space: naga::AddressSpace::Uniform,
+                binding: Some(naga::ResourceBinding {
+                    group: 0,
+                    binding: 1,
+                }),
+                ty: vec4_ty,
+                init: None,
+            },
+            Default::default(),
+        );
+        let counter = module.global_variables.append(
+            naga::GlobalVariable {
+                name: Some("counter".to_string()),
+                space: naga::AddressSpace::Storage {
+                    access: naga::StorageAccess::LOAD,
+                },
+                binding: Some(naga::ResourceBinding {
+                    group: 1,
+                    binding: 0,
+                }),
+                ty: u32_ty,
+                init: None,
+            },
+            Default::default(),
+        );
+        add_entry_point(
+            &mut module,
+            "cs_main",
+            naga::ShaderStage::Compute,
+            &[globals, counter],
+        );
+        add_entry_point(
+            &mut module,
+            "fs_main",
+            naga::ShaderStage::Fragment,
+            &[globals],
+        );
+
+        let info = Validator::new(ValidationFlags::all(), Capabilities::default())
+            .validate(&module)
+            .unwrap();
+        let layouts = super::derive_bind_group_layouts(
+            &module,
+            &info,
+            &[
+                (naga::ShaderStage::Compute, "cs_main"),
+                (naga::ShaderStage::Fragment, "fs_main"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            layouts,
+            [
+                vec![wgt::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgt::ShaderStages::COMPUTE | wgt::ShaderStages::FRAGMENT,
+                    ty: wgt::BindingType::Buffer {
+                        ty: wgt::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgt::BufferSize::new(16),
+                    },
+                    count: None,
+                }],
+                vec![wgt::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgt::ShaderStages::COMPUTE,
+                    ty: wgt::BindingType::Buffer {
+                        ty: wgt::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: wgt::BufferSize::new(4),
+                    },
+                    count: None,
+                }],
+            ]
+        );
+
+        let error = super::derive_bind_group_layouts(
+            &module,
+            &info,
+            &[(naga::ShaderStage::Vertex, "cs_main")],
+        )
+        .unwrap_err();
+        match error {
+            super::DeriveLayoutError::EntryPoint { entry_point, .. } => {
+                assert_eq!(entry_point, "cs_main")
+            }
+            error => panic!("{error:?}"),
+        }
+    }
+}
@@ -234,6 +234,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     clear_mode: RwLock::new(resource::TextureClearMode::Surface {
                         clear_view: Some(clear_view),
                     }),
+                    memory: None,
                 };
 
                 let (id, resource) = fid.assign(texture);
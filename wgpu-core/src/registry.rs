@@ -1,6 +1,6 @@
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
-use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use wgt::Backend;
 
 use crate::{
@@ -41,14 +41,40 @@ impl RegistryReport {
 pub struct Registry<I: id::TypedId, T: Resource<I>> {
     identity: Arc<IdentityManager<I>>,
     storage: RwLock<Storage<T, I>>,
+    live: Mutex<LiveResources<T>>,
     backend: Backend,
 }
 
+/// Weak references to every resource a registry created, so that resources
+/// the user released, but that are still referenced, can be found.
+#[derive(Debug)]
+struct LiveResources<T> {
+    resources: Vec<Weak<T>>,
+    /// The length of `resources` after dead references were last removed.
+    pruned_len: usize,
+}
+
+impl<T> LiveResources<T> {
+    fn push(&mut self, resource: &Arc<T>) {
+        // Removing dead references once the list doubles keeps it
+        // proportional to the number of live resources.
+        if self.resources.len() >= 2 * self.pruned_len.max(32) {
+            self.resources.retain(|weak| weak.strong_count() != 0);
+            self.pruned_len = self.resources.len();
+        }
+        self.resources.push(Arc::downgrade(resource));
+    }
+}
+
 impl<I: id::TypedId, T: Resource<I>> Registry<I, T> {
     pub(crate) fn new<F: IdentityHandlerFactory<I>>(backend: Backend, factory: &F) -> Self {
         Self {
             identity: factory.spawn(),
             storage: RwLock::new(Storage::new()),
+            live: Mutex::new(LiveResources {
+                resources: Vec::new(),
+                pruned_len: 0,
+            }),
             backend,
         }
     }
@@ -63,6 +89,7 @@ pub(crate) struct FutureId<'a, I: id::TypedId, T: Resource<I>> {
     id: I,
     identity: Arc<IdentityManager<I>>,
     data: &'a RwLock<Storage<T, I>>,
+    live: &'a Mutex<LiveResources<T>>,
 }
 
 impl<I: id::TypedId + Copy, T: Resource<I>> FutureId<'_, I, T> {
@@ -77,7 +104,9 @@ impl<I: id::TypedId + Copy, T: Resource<I>> FutureId<'_, I, T> {
 
     pub fn init(&self, mut value: T) -> Arc<T> {
         value.as_info_mut().set_id(self.id, &self.identity);
-        Arc::new(value)
+        let value = Arc::new(value);
+        self.live.lock().push(&value);
+        value
     }
 
     pub fn assign(self, value: T) -> (I, Arc<T>) {
@@ -113,6 +142,7 @@ impl<I: id::TypedId, T: Resource<I>> Registry<I, T> {
             },
             identity: self.identity.clone(),
             data: &self.storage,
+            live: &self.live,
         }
     }
     pub(crate) fn request(&self) -> FutureId<I, T> {
@@ -120,6 +150,7 @@ impl<I: id::TypedId, T: Resource<I>> Registry<I, T> {
             id: self.identity.process(self.backend),
             identity: self.identity.clone(),
             data: &self.storage,
+            live: &self.live,
         }
     }
     pub(crate) fn try_get(&self, id: I) -> Result<Option<Arc<T>>, InvalidId> {
@@ -140,7 +171,8 @@ impl<I: id::TypedId, T: Resource<I>> Registry<I, T> {
     pub fn force_replace(&self, id: I, mut value: T) {
         let mut storage = self.storage.write();
         value.as_info_mut().set_id(id, &self.identity);
-        storage.force_replace(id, value)
+        storage.force_replace(id, value);
+        self.live.lock().push(&storage[id]);
     }
     pub fn force_replace_with_error(&self, id: I, label: &str) {
         let mut storage = self.storage.write();
@@ -174,6 +206,25 @@ impl<I: id::TypedId, T: Resource<I>> Registry<I, T> {
         }
     }
 
+    /// Every resource this registry created that is still alive, whether or
+    /// not the user still has its id.
+    pub(crate) fn live_resources(&self) -> Vec<Arc<T>> {
+        let live = self.live.lock();
+        live.resources.iter().filter_map(Weak::upgrade).collect()
+    }
+
+    /// Whether `resource` is still registered under its id, which means the
+    /// user hasn't released it.
+    pub(crate) fn is_registered(&self, resource: &Arc<T>) -> bool {
+        let (index, epoch, _) = resource.as_info().id().unzip();
+        match self.storage.read().map.get(index as usize) {
+            Some(&Element::Occupied(ref registered, registered_epoch)) => {
+                registered_epoch == epoch && Arc::ptr_eq(registered, resource)
+            }
+            _ => false,
+        }
+    }
+
     pub(crate) fn generate_report(&self) -> RegistryReport {
         let storage = self.storage.read();
         let mut report = RegistryReport {
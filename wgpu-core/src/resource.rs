@@ -13,6 +13,7 @@ use crate::{
     },
     identity::{GlobalIdentityHandlerFactory, IdentityManager},
     init_tracker::{BufferInitTracker, TextureInitTracker},
+    memory::Allocation,
     resource, resource_log,
     track::TextureSelector,
     validation::MissingBufferUsageError,
@@ -26,6 +27,7 @@ use thiserror::Error;
 use wgt::WasmNotSendSync;
 
 use std::{
+    backtrace::{Backtrace, BacktraceStatus},
     borrow::Borrow,
     fmt::Debug,
     iter, mem,
@@ -72,6 +74,10 @@ pub struct ResourceInfo<Id: TypedId> {
     /// The `label` from the descriptor used to create the resource.
     #[cfg(debug_assertions)]
     pub(crate) label: String,
+
+    /// Where the resource was created, if backtraces are enabled with
+    /// `RUST_LIB_BACKTRACE` or `RUST_BACKTRACE`.
+    backtrace: Option<Box<Backtrace>>,
 }
 
 impl<Id: TypedId> Drop for ResourceInfo<Id> {
@@ -92,6 +98,8 @@ impl<Id: TypedId> ResourceInfo<Id> {
             submission_index: AtomicUsize::new(0),
             #[cfg(debug_assertions)]
             label: label.to_string(),
+            backtrace: Some(Box::new(Backtrace::capture()))
+                .filter(|backtrace| backtrace.status() == BacktraceStatus::Captured),
         }
     }
 
@@ -115,6 +123,10 @@ impl<Id: TypedId> ResourceInfo<Id> {
         self.id.unwrap()
     }
 
+    pub(crate) fn backtrace(&self) -> Option<&Backtrace> {
+        self.backtrace.as_deref()
+    }
+
     pub(crate) fn set_id(&mut self, id: Id, identity: &Arc<IdentityManager<Id>>) {
         self.id = Some(id);
         self.identity = Some(identity.clone());
@@ -413,6 +425,7 @@ pub struct Buffer<A: HalApi> {
     pub(crate) sync_mapped_writes: Mutex<Option<hal::MemoryRange>>,
     pub(crate) info: ResourceInfo<BufferId>,
     pub(crate) map_state: Mutex<BufferMapState<A>>,
+    pub(crate) memory: Allocation,
 }
 
 impl<A: HalApi> Drop for Buffer<A> {
@@ -424,6 +437,7 @@ impl<A: HalApi> Drop for Buffer<A> {
                 self.device.raw().destroy_buffer(raw);
             }
         }
+        self.device.memory.lock().free(&self.memory);
     }
 }
 
@@ -634,6 +648,7 @@ pub struct StagingBuffer<A: HalApi> {
     pub(crate) size: wgt::BufferAddress,
     pub(crate) is_coherent: bool,
     pub(crate) info: ResourceInfo<StagingBufferId>,
+    pub(crate) memory: Allocation,
 }
 
 impl<A: HalApi> Drop for StagingBuffer<A> {
@@ -645,6 +660,7 @@ impl<A: HalApi> Drop for StagingBuffer<A> {
                 self.device.raw().destroy_buffer(raw);
             }
         }
+        self.device.memory.lock().free(&self.memory);
     }
 }
 
@@ -717,11 +733,16 @@ pub struct Texture<A: HalApi> {
     pub(crate) full_range: TextureSelector,
     pub(crate) info: ResourceInfo<TextureId>,
     pub(crate) clear_mode: RwLock<TextureClearMode<A>>,
+    /// `None` for surface textures, which the device doesn't allocate.
+    pub(crate) memory: Option<Allocation>,
 }
 
 impl<A: HalApi> Drop for Texture<A> {
     fn drop(&mut self) {
         resource_log!("Destroy raw Texture {}", self.info.label());
+        if let Some(ref memory) = self.memory {
+            self.device.memory.lock().free(memory);
+        }
         use hal::Device;
         let mut clear_mode = self.clear_mode.write();
         let clear_mode = &mut *clear_mode;
@@ -762,6 +783,34 @@ impl<A: HalApi> Drop for Texture<A> {
 }
 
 impl<A: HalApi> Texture<A> {
+    /// The number of bytes the texture needs, estimated from its descriptor.
+    ///
+    /// Backends may need more, for alignment, or less, for formats they pack
+    /// more tightly.
+    pub(crate) fn estimated_size(&self) -> wgt::BufferAddress {
+        let format = self.desc.format;
+        let block_size = format.block_copy_size(None).unwrap_or_else(|| {
+            // Combined depth-stencil and multi-planar formats have no block
+            // size of their own, so take 4 bytes, and a byte for stencil.
+            4 + u32::from(format.has_stencil_aspect())
+        });
+        let (block_width, block_height) = format.block_dimensions();
+        let bytes_per_sample = (0..self.desc.mip_level_count)
+            .map(|mip| {
+                let size = self
+                    .desc
+                    .size
+                    .mip_level_size(mip, self.desc.dimension)
+                    .physical_size(format);
+                u64::from(size.width / block_width)
+                    * u64::from(size.height / block_height)
+                    * u64::from(size.depth_or_array_layers)
+                    * u64::from(block_size)
+            })
+            .sum::<u64>();
+        bytes_per_sample * u64::from(self.desc.sample_count)
+    }
+
     pub(crate) fn inner<'a>(&'a self) -> RwLockReadGuard<'a, Option<TextureInner<A>>> {
         self.inner.read()
     }
@@ -1264,6 +1313,8 @@ pub struct QuerySet<A: HalApi> {
     pub(crate) device: Arc<Device<A>>,
     pub(crate) info: ResourceInfo<QuerySetId>,
     pub(crate) desc: wgt::QuerySetDescriptor<()>,
+    /// Always `Some` once the query set is created.
+    pub(crate) memory: Option<Allocation>,
 }
 
 impl<A: HalApi> Drop for QuerySet<A> {
@@ -1275,6 +1326,9 @@ impl<A: HalApi> Drop for QuerySet<A> {
                 self.device.raw().destroy_query_set(raw);
             }
         }
+        if let Some(ref memory) = self.memory {
+            self.device.memory.lock().free(memory);
+        }
     }
 }
 
@@ -1294,6 +1348,15 @@ impl<A: HalApi> QuerySet<A> {
     pub(crate) fn raw(&self) -> &A::QuerySet {
         self.raw.as_ref().unwrap()
     }
+
+    /// The number of bytes the results of the queries take.
+    pub(crate) fn estimated_size(&self) -> wgt::BufferAddress {
+        let values = match self.desc.ty {
+            wgt::QueryType::PipelineStatistics(types) => types.bits().count_ones(),
+            wgt::QueryType::Occlusion | wgt::QueryType::Timestamp => 1,
+        };
+        u64::from(self.desc.count) * u64::from(values) * u64::from(wgt::QUERY_SIZE)
+    }
 }
 
 #[derive(Clone, Debug, Error)]
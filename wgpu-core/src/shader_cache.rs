@@ -0,0 +1,375 @@
+/*! Persistent cache of shader modules, and of the code generated from them.
+
+Parsing, validating and translating shaders is a large part of the start-up
+time of many applications, and its results only change along with the
+shaders. A [`ShaderCache`] keeps them in a directory, so that later runs can
+skip that work.
+
+Two kinds of entries are kept, both named after a 128-bit key:
+
+- `module-<key>.bin`: a validated `naga::Module` and its `ModuleInfo`. The key
+  covers the WGSL source, the naga and wgpu-core versions, the backend, and
+  the device features and downlevel flags, which decide the capabilities the
+  module is validated with.
+
+- `code-<key>.bin`: code a backend generated for a pipeline stage, such as
+  SPIR-V words, or HLSL, MSL or GLSL text. The key covers the module key, and
+  every backend and pipeline option the code depends on.
+
+Only modules created from WGSL are cached, since modules given as naga IR
+would have to be serialized to find their key. Entries that can't be read,
+for example because they are left from an older version, count as misses
+and are replaced.
+
+Cached modules aren't validated again, and cached code goes straight to the
+driver, so every entry starts with a checksum of its key and contents.
+Entries that were damaged, or copied under another key, fail the check, and
+count as misses too.
+!*/
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use hal::auxil::code_cache::KeyHasher;
+
+/// Numbers of lookups in a [`ShaderCache`], since it was created.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShaderCacheStats {
+    pub module_hits: u64,
+    pub module_misses: u64,
+    pub code_hits: u64,
+    pub code_misses: u64,
+}
+
+/// Keeps validated shader modules, and the code generated from them, in a
+/// directory. See the [module documentation](self).
+///
+/// Several devices, and processes, may share a directory.
+#[derive(Debug)]
+pub struct ShaderCache {
+    dir: PathBuf,
+    module_hits: AtomicU64,
+    module_misses: AtomicU64,
+    code_hits: AtomicU64,
+    code_misses: AtomicU64,
+}
+
+impl ShaderCache {
+    /// Use the directory `dir`, creating it if it doesn't exist.
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            module_hits: AtomicU64::new(0),
+            module_misses: AtomicU64::new(0),
+            code_hits: AtomicU64::new(0),
+            code_misses: AtomicU64::new(0),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn stats(&self) -> ShaderCacheStats {
+        ShaderCacheStats {
+            module_hits: self.module_hits.load(Ordering::Relaxed),
+            module_misses: self.module_misses.load(Ordering::Relaxed),
+            code_hits: self.code_hits.load(Ordering::Relaxed),
+            code_misses: self.code_misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The key of the module made from the WGSL `source`, on a device with
+    /// these features and downlevel flags.
+    pub(crate) fn module_key(
+        backend: wgt::Backend,
+        features: wgt::Features,
+        downlevel_flags: wgt::DownlevelFlags,
+        source: &str,
+    ) -> u128 {
+        KeyHasher::new()
+            .write(naga::VERSION.as_bytes())
+            .write(env!("CARGO_PKG_VERSION").as_bytes())
+            .write(backend.to_str().as_bytes())
+            .write(&features.bits().to_le_bytes())
+            .write(&downlevel_flags.bits().to_le_bytes())
+            .write(source.as_bytes())
+            .finish()
+    }
+
+    fn path(&self, kind: &str, key: u128) -> PathBuf {
+        self.dir.join(format!("{kind}-{key:032x}.bin"))
+    }
+
+    const CHECKSUM_LEN: usize = 16;
+
+    fn checksum(key: u128, data: &[u8]) -> [u8; Self::CHECKSUM_LEN] {
+        KeyHasher::new()
+            .write_u128(key)
+            .write(data)
+            .finish()
+            .to_le_bytes()
+    }
+
+    fn read(&self, kind: &str, key: u128) -> Option<Vec<u8>> {
+        let mut data = match fs::read(self.path(kind, key)) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                log::warn!("Unable to read the shader cache entry {kind}-{key:032x}: {e}");
+                return None;
+            }
+        };
+        if data.len() < Self::CHECKSUM_LEN
+            || data[..Self::CHECKSUM_LEN] != Self::checksum(key, &data[Self::CHECKSUM_LEN..])
+        {
+            log::warn!("Ignoring the shader cache entry {kind}-{key:032x}: bad checksum");
+            return None;
+        }
+        data.drain(..Self::CHECKSUM_LEN);
+        Some(data)
+    }
+
+    /// Write the entry to a temporary file first, so that other processes
+    /// never read a partial entry.
+    fn write(&self, kind: &str, key: u128, data: &[u8]) {
+        let path = self.path(kind, key);
+        let temp_path = path.with_extension(format!("tmp{}", std::process::id()));
+        let entry = [&Self::checksum(key, data)[..], data].concat();
+        if let Err(e) = fs::write(&temp_path, entry).and_then(|()| fs::rename(&temp_path, &path)) {
+            log::warn!("Unable to write the shader cache entry {path:?}: {e}");
+            let _ = fs::remove_file(&temp_path);
+        }
+    }
+
+    pub(crate) fn load_module(&self, key: u128) -> Option<(naga::Module, naga::valid::ModuleInfo)> {
+        let module = self
+            .read("module", key)
+            .and_then(|data| match bincode::deserialize(&data) {
+                Ok(module) => Some(module),
+                Err(e) => {
+                    log::warn!("Ignoring the shader cache entry module-{key:032x}: {e}");
+                    None
+                }
+            });
+        let counter = match module {
+            Some(_) => &self.module_hits,
+            None => &self.module_misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        module
+    }
+
+    pub(crate) fn store_module(
+        &self,
+        key: u128,
+        module: &naga::Module,
+        info: &naga::valid::ModuleInfo,
+    ) {
+        match bincode::serialize(&(module, info)) {
+            Ok(data) => self.write("module", key, &data),
+            Err(e) => log::warn!("Unable to serialize a shader module for the cache: {e}"),
+        }
+    }
+}
+
+impl hal::ShaderCodeCache for ShaderCache {
+    fn load(&self, key: u128) -> Option<Vec<u8>> {
+        let code = self.read("code", key);
+        let counter = match code {
+            Some(_) => &self.code_hits,
+            None => &self.code_misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        code
+    }
+
+    fn store(&self, key: u128, code: &[u8]) {
+        self.write("code", key, code);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ShaderCache, ShaderCacheStats};
+    use hal::ShaderCodeCache as _;
+    use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "wgpu-core-shader-cache-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn compute_module() -> naga::Module {
+        let mut module = naga::Module::default();
+        let u32_ty = module.types.insert(
+            naga::Type {
+                name: None,
+                inner: naga::TypeInner::Scalar(naga::Scalar::U32),
+            },
+            Default::default(),
+        );
+        let counter = module.global_variables.append(
+            naga::GlobalVariable {
+                name: Some("counter".to_string()),
+                space: naga::AddressSpace::Storage {
+                    access: naga::StorageAccess::LOAD,
+                },
+                binding: Some(naga::ResourceBinding {
+                    group: 0,
+                    binding: 0,
+                }),
+                ty: u32_ty,
+                init: None,
+            },
+            Default::default(),
+        );
+        let mut function = naga::Function::default();
+        let pointer = function.expressions.append(
+            naga::Expression::GlobalVariable(counter),
+            Default::default(),
+        );
+        let start = function.expressions.len();
+        function
+            .expressions
+            .append(naga::Expression::Load { pointer }, Default::default());
+        function.body.push(
+            naga::Statement::Emit(function.expressions.range_from(start)),
+            Default::default(),
+        );
+        module.entry_points.push(naga::EntryPoint {
+            name: "main".to_string(),
+            stage: naga::ShaderStage::Compute,
+            early_depth_test: None,
+            workgroup_size: [1, 1, 1],
+            function,
+            span: Default::default(),
+        });
+        module
+    }
+
+    #[test]
+    fn modules_persist() {
+        let dir = temp_dir("modules");
+        let source = "@compute @workgroup_size(1) fn main() {}";
+        let key = ShaderCache::module_key(
+            wgt::Backend::Vulkan,
+            wgt::Features::empty(),
+            wgt::DownlevelFlags::all(),
+            source,
+        );
+        let module = compute_module();
+        let info = Validator::new(ValidationFlags::all(), Capabilities::default())
+            .validate(&module)
+            .unwrap();
+
+        let cache = ShaderCache::new(&dir).unwrap();
+        assert!(cache.load_module(key).is_none());
+        cache.store_module(key, &module, &info);
+
+        // A cache of the same directory stands for a later run.
+        let cache = ShaderCache::new(&dir).unwrap();
+        let (loaded, loaded_info) = cache.load_module(key).unwrap();
+        assert_eq!(loaded.entry_points[0].name, "main");
+        assert_eq!(
+            loaded_info.get_entry_point(0)[loaded.global_variables.iter().next().unwrap().0],
+            info.get_entry_point(0)[module.global_variables.iter().next().unwrap().0],
+        );
+
+        // Other features may change how the module validates.
+        let other_key = ShaderCache::module_key(
+            wgt::Backend::Vulkan,
+            wgt::Features::SHADER_F64,
+            wgt::DownlevelFlags::all(),
+            source,
+        );
+        assert_ne!(key, other_key);
+        assert!(cache.load_module(other_key).is_none());
+        assert_eq!(
+            cache.stats(),
+            ShaderCacheStats {
+                module_hits: 1,
+                module_misses: 1,
+                ..Default::default()
+            }
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unreadable_entries_are_misses() {
+        let dir = temp_dir("unreadable");
+        let cache = ShaderCache::new(&dir).unwrap();
+        std::fs::write(cache.path("module", 1), b"not a module").unwrap();
+        assert!(cache.load_module(1).is_none());
+        assert_eq!(cache.stats().module_misses, 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn damaged_entries_are_misses() {
+        let dir = temp_dir("damaged");
+        let cache = ShaderCache::new(&dir).unwrap();
+        let module = compute_module();
+        let info = Validator::new(ValidationFlags::all(), Capabilities::default())
+            .validate(&module)
+            .unwrap();
+        cache.store_module(1, &module, &info);
+        cache.store(2, b"#version 450");
+
+        // Flip a bit of the module's contents.
+        let mut entry = std::fs::read(cache.path("module", 1)).unwrap();
+        *entry.last_mut().unwrap() ^= 1;
+        std::fs::write(cache.path("module", 1), entry).unwrap();
+        assert!(cache.load_module(1).is_none());
+
+        // Intact entries are only valid under their own key.
+        std::fs::copy(cache.path("code", 2), cache.path("code", 3)).unwrap();
+        assert_eq!(cache.load(3), None);
+        assert_eq!(cache.load(2).as_deref(), Some(&b"#version 450"[..]));
+        assert_eq!(
+            cache.stats(),
+            ShaderCacheStats {
+                module_misses: 1,
+                code_hits: 1,
+                code_misses: 1,
+                ..Default::default()
+            }
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn code_persists() {
+        let dir = temp_dir("code");
+        let cache = ShaderCache::new(&dir).unwrap();
+        assert_eq!(cache.load(7), None);
+        cache.store(7, b"#version 450");
+
+        let cache = ShaderCache::new(&dir).unwrap();
+        assert_eq!(cache.load(7).as_deref(), Some(&b"#version 450"[..]));
+        assert_eq!(cache.load(8), None);
+        assert_eq!(
+            cache.stats(),
+            ShaderCacheStats {
+                code_hits: 1,
+                code_misses: 1,
+                ..Default::default()
+            }
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -142,6 +142,11 @@ impl<A: HalApi> BufferUsageScope<A> {
         }
     }
 
+    /// Returns a list of all buffers tracked.
+    pub fn used_resources(&self) -> impl Iterator<Item = Arc<Buffer<A>>> + '_ {
+        self.metadata.owned_resources()
+    }
+
     /// Drains all buffers tracked.
     pub fn drain_resources(&mut self) -> impl Iterator<Item = Arc<Buffer<A>>> + '_ {
         let resources = self.metadata.drain_resources();
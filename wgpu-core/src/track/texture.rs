@@ -176,6 +176,16 @@ impl<A: HalApi> TextureBindGroupState<A> {
         textures.sort_unstable_by_key(|v| v.texture.as_info().id().unzip().0);
     }
 
+    /// Returns a list of all textures tracked. May contain duplicates.
+    pub fn used_ids(&self) -> impl Iterator<Item = TextureId> + '_ {
+        let textures = self.textures.lock();
+        textures
+            .iter()
+            .map(|v| v.texture.as_info().id())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
     /// Returns a list of all textures tracked. May contain duplicates.
     pub fn drain_resources(&self) -> impl Iterator<Item = Arc<Texture<A>>> + '_ {
         let mut textures = self.textures.lock();
@@ -266,6 +276,11 @@ impl<A: HalApi> TextureUsageScope<A> {
         self.metadata.set_size(size);
     }
 
+    /// Returns a list of all textures tracked.
+    pub(crate) fn used_resources(&self) -> impl Iterator<Item = Arc<Texture<A>>> + '_ {
+        self.metadata.owned_resources()
+    }
+
     /// Drains all textures tracked.
     pub(crate) fn drain_resources(&mut self) -> impl Iterator<Item = Arc<Texture<A>>> + '_ {
         let resources = self.metadata.drain_resources();
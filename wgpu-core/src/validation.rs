@@ -25,6 +25,8 @@ struct Resource {
     bind: naga::ResourceBinding,
     ty: ResourceType,
     class: naga::AddressSpace,
+    /// The number of elements, if this is a binding array of known size.
+    count: Option<std::num::NonZeroU32>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -595,6 +597,33 @@ impl Resource {
             }
         })
     }
+
+    /// Add this resource's entry to the derived bind group layout `set`,
+    /// or make an existing entry visible to `stage_bit` as well.
+    fn derive_layout_entry(
+        &self,
+        set: &mut BindEntryMap,
+        stage_bit: wgt::ShaderStages,
+    ) -> Result<(), BindingError> {
+        let ty = self.derive_binding_type()?;
+        match set.entry(self.bind.binding) {
+            Entry::Occupied(e) if e.get().ty != ty || e.get().count != self.count => {
+                return Err(BindingError::InconsistentlyDerivedType)
+            }
+            Entry::Occupied(e) => {
+                e.into_mut().visibility |= stage_bit;
+            }
+            Entry::Vacant(e) => {
+                e.insert(BindGroupLayoutEntry {
+                    binding: self.bind.binding,
+                    ty,
+                    visibility: stage_bit,
+                    count: self.count,
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 impl NumericType {
@@ -844,7 +873,10 @@ impl Interface {
         info: &naga::valid::ModuleInfo,
         limits: wgt::Limits,
         features: wgt::Features,
-    ) -> Self {
+    ) -> Result<Self, naga::proc::LayoutError> {
+        let mut layouter = naga::proc::Layouter::default();
+        layouter.update(module.to_ctx())?;
+
         let mut resources = naga::Arena::new();
         let mut resource_mapping = FastHashMap::default();
         for (var_handle, var) in module.global_variables.iter() {
@@ -852,12 +884,17 @@ impl Interface {
                 Some(ref br) => br.clone(),
                 _ => continue,
             };
-            let naga_ty = &module.types[var.ty].inner;
-
-            let inner_ty = match *naga_ty {
-                naga::TypeInner::BindingArray { base, .. } => &module.types[base].inner,
-                ref ty => ty,
+            let (inner, count) = match module.types[var.ty].inner {
+                naga::TypeInner::BindingArray { base, size } => (
+                    base,
+                    match size {
+                        naga::ArraySize::Constant(count) => Some(count),
+                        naga::ArraySize::Dynamic => None,
+                    },
+                ),
+                _ => (var.ty, None),
             };
+            let inner_ty = &module.types[inner].inner;
 
             let ty = match *inner_ty {
                 naga::TypeInner::Image {
@@ -873,8 +910,8 @@ impl Interface {
                 naga::TypeInner::Array { stride, .. } => ResourceType::Buffer {
                     size: wgt::BufferSize::new(stride as u64).unwrap(),
                 },
-                ref other => ResourceType::Buffer {
-                    size: wgt::BufferSize::new(other.size(module.to_ctx()) as u64).unwrap(),
+                _ => ResourceType::Buffer {
+                    size: wgt::BufferSize::new(layouter[inner].size as u64).unwrap(),
                 },
             };
             let handle = resources.append(
@@ -883,6 +920,7 @@ impl Interface {
                     bind,
                     ty,
                     class: var.space,
+                    count,
                 },
                 Default::default(),
             );
@@ -923,12 +961,12 @@ impl Interface {
             entry_points.insert((entry_point.stage, entry_point.name.clone()), ep);
         }
 
-        Self {
+        Ok(Self {
             limits,
             features,
             resources,
             entry_points,
-        }
+        })
     }
 
     pub fn check_stage(
@@ -987,26 +1025,7 @@ impl Interface {
                 None => derived_layouts
                     .get_mut(res.bind.group as usize)
                     .ok_or(BindingError::Missing)
-                    .and_then(|set| {
-                        let ty = res.derive_binding_type()?;
-                        match set.entry(res.bind.binding) {
-                            Entry::Occupied(e) if e.get().ty != ty => {
-                                return Err(BindingError::InconsistentlyDerivedType)
-                            }
-                            Entry::Occupied(e) => {
-                                e.into_mut().visibility |= stage_bit;
-                            }
-                            Entry::Vacant(e) => {
-                                e.insert(BindGroupLayoutEntry {
-                                    binding: res.bind.binding,
-                                    ty,
-                                    visibility: stage_bit,
-                                    count: None,
-                                });
-                            }
-                        }
-                        Ok(())
-                    }),
+                    .and_then(|set| res.derive_layout_entry(set, stage_bit)),
             };
             if let Err(error) = result {
                 return Err(StageError::Binding(res.bind.clone(), error));
@@ -1198,6 +1217,40 @@ impl Interface {
         Ok(outputs)
     }
 
+    /// Add the resources used by an entry point to `derived_layouts`, the
+    /// same way `check_stage` does for pipelines without a given layout.
+    ///
+    /// Unlike `check_stage`, this grows `derived_layouts` to fit every
+    /// group the entry point uses, and checks nothing else.
+    pub fn derive_layouts(
+        &self,
+        derived_layouts: &mut Vec<BindEntryMap>,
+        shader_stage: naga::ShaderStage,
+        entry_point_name: &str,
+    ) -> Result<(), StageError> {
+        let stage_bit = match shader_stage {
+            naga::ShaderStage::Vertex => wgt::ShaderStages::VERTEX,
+            naga::ShaderStage::Fragment => wgt::ShaderStages::FRAGMENT,
+            naga::ShaderStage::Compute => wgt::ShaderStages::COMPUTE,
+        };
+        let pair = (shader_stage, entry_point_name.to_string());
+        let entry_point = self
+            .entry_points
+            .get(&pair)
+            .ok_or(StageError::MissingEntryPoint(pair.1))?;
+
+        for &handle in entry_point.resources.iter() {
+            let res = &self.resources[handle];
+            let group = res.bind.group as usize;
+            if derived_layouts.len() <= group {
+                derived_layouts.resize_with(group + 1, BindEntryMap::default);
+            }
+            res.derive_layout_entry(&mut derived_layouts[group], stage_bit)
+                .map_err(|error| StageError::Binding(res.bind.clone(), error))?;
+        }
+        Ok(())
+    }
+
     pub fn fragment_uses_dual_source_blending(
         &self,
         entry_point_name: &str,
@@ -98,6 +98,8 @@ impl<A: hal::Api> Example<A> {
             // Can't rely on having DXC available, so use FXC instead
             dx12_shader_compiler: wgt::Dx12Compiler::Fxc,
             gles_minor_version: wgt::Gles3MinorVersion::default(),
+            empty_backend_options: wgt::EmptyBackendOptions::default(),
+            cpu_backend_options: wgt::CpuBackendOptions::default(),
         };
         let instance = unsafe { A::Instance::init(&instance_desc)? };
         let surface = {
@@ -168,6 +170,7 @@ impl<A: hal::Api> Example<A> {
                 module: Cow::Owned(module),
                 info,
                 debug_source: None,
+                code_cache: None,
             }
         };
         let shader_desc = hal::ShaderModuleDescriptor {
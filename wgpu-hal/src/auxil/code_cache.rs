@@ -0,0 +1,198 @@
+//! Looking up the code that backends generate in a [`ShaderCodeCache`].
+//!
+//! [`ShaderCodeCache`]: crate::ShaderCodeCache
+
+use std::fmt;
+
+/// Hashes the options that generated code depends on into a key.
+///
+/// This is 128-bit FNV-1a, so unlike the hashers in `std`, it gives the same
+/// key in every run.
+#[derive(Clone, Debug)]
+pub struct KeyHasher(u128);
+
+impl Default for KeyHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyHasher {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    pub fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    /// Hash `bytes`, along with their length, so that consecutive parts
+    /// can't be confused with each other.
+    pub fn write(&mut self, bytes: &[u8]) -> &mut Self {
+        for &byte in (bytes.len() as u64).to_le_bytes().iter().chain(bytes) {
+            self.0 ^= u128::from(byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+        self
+    }
+
+    pub fn write_u128(&mut self, value: u128) -> &mut Self {
+        self.write(&value.to_le_bytes())
+    }
+
+    /// Hash the `Debug` form of `value`.
+    ///
+    /// Only use this for values whose `Debug` form doesn't depend on the
+    /// iteration order of a hash map.
+    pub fn write_debug(&mut self, value: &impl fmt::Debug) -> &mut Self {
+        self.write(format!("{value:?}").as_bytes())
+    }
+
+    pub fn finish(&self) -> u128 {
+        self.0
+    }
+}
+
+/// Builds an entry out of strings and numbers.
+#[allow(dead_code)]
+#[derive(Default)]
+pub(crate) struct EntryWriter(Vec<u8>);
+
+#[allow(dead_code)]
+impl EntryWriter {
+    pub fn u32(&mut self, value: u32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub fn str(&mut self, value: &str) -> &mut Self {
+        self.u32(value.len() as u32);
+        self.0.extend_from_slice(value.as_bytes());
+        self
+    }
+
+    /// Write the mappings of `line_map`.
+    #[cfg(any(
+        feature = "dx11",
+        feature = "dx12",
+        feature = "gles",
+        feature = "metal"
+    ))]
+    pub fn line_map(&mut self, line_map: &naga::back::LineMap) -> &mut Self {
+        self.u32(line_map.mappings.len() as u32);
+        for mapping in line_map.mappings.iter() {
+            // Zero stands for no file id.
+            let file_id = mapping.file_id.map_or(0, |id| id + 1);
+            self.u32(mapping.output_line)
+                .u32(file_id)
+                .u32(mapping.source_line);
+        }
+        self
+    }
+
+    pub fn finish(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.0)
+    }
+}
+
+/// Reads the parts of an entry written by [`EntryWriter`], in the same order.
+#[allow(dead_code)]
+pub(crate) struct EntryReader<'a>(&'a [u8]);
+
+#[allow(dead_code)]
+impl<'a> EntryReader<'a> {
+    pub fn new(entry: &'a [u8]) -> Self {
+        Self(entry)
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(bytes)
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        let bytes = self.take(4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn str(&mut self) -> Option<&'a str> {
+        let len = self.u32()? as usize;
+        std::str::from_utf8(self.take(len)?).ok()
+    }
+
+    /// Read a line map written by [`EntryWriter::line_map`].
+    #[cfg(any(
+        feature = "dx11",
+        feature = "dx12",
+        feature = "gles",
+        feature = "metal"
+    ))]
+    pub fn line_map(&mut self) -> Option<naga::back::LineMap> {
+        let mut line_map = naga::back::LineMap::default();
+        for _ in 0..self.u32()? {
+            let output_line = self.u32()?;
+            let file_id = self.u32()?.checked_sub(1);
+            line_map.mappings.push(naga::back::LineMapping {
+                output_line,
+                file_id,
+                source_line: self.u32()?,
+            });
+        }
+        Some(line_map)
+    }
+
+    /// Whether everything was read.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Return the code that `generate` makes for `shader`, looking it up in the
+/// code cache of the shader first, if it has one.
+///
+/// `key` must hash everything the code depends on, apart from the module.
+/// Entries that `decode` rejects are treated as missing, and replaced.
+#[allow(dead_code)]
+pub(crate) fn load_or_generate<T, E>(
+    shader: &crate::NagaShader,
+    key: impl FnOnce(&mut KeyHasher),
+    encode: impl FnOnce(&T) -> Vec<u8>,
+    decode: impl FnOnce(&[u8]) -> Option<T>,
+    generate: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    let (cache, module_key) = match shader.code_cache {
+        Some((ref cache, module_key)) => (cache, module_key),
+        None => return generate(),
+    };
+    let mut hasher = KeyHasher::new();
+    hasher.write_u128(module_key);
+    key(&mut hasher);
+    let key = hasher.finish();
+
+    if let Some(code) = cache.load(key).and_then(|entry| decode(&entry)) {
+        return Ok(code);
+    }
+    let code = generate()?;
+    cache.store(key, &encode(&code));
+    Ok(code)
+}
+
+#[test]
+fn entries_round_trip() {
+    let entry = EntryWriter::default().str("main").u32(7).str("").finish();
+    let mut reader = EntryReader::new(&entry);
+    assert_eq!(reader.str(), Some("main"));
+    assert_eq!(reader.u32(), Some(7));
+    assert_eq!(reader.str(), Some(""));
+    assert!(reader.is_empty());
+    assert_eq!(reader.u32(), None);
+
+    // Parts are hashed with their lengths.
+    assert_ne!(
+        KeyHasher::new().write(b"ab").write(b"c").finish(),
+        KeyHasher::new().write(b"a").write(b"bc").finish()
+    );
+}
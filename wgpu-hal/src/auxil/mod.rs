@@ -1,3 +1,4 @@
+pub mod code_cache;
 #[cfg(all(any(feature = "dx11", feature = "dx12"), windows))]
 pub(super) mod dxgi;
 
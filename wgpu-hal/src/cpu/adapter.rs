@@ -0,0 +1,148 @@
+use std::time::Instant;
+
+use super::{Api, DeviceResult};
+
+impl crate::Instance<Api> for super::Instance {
+    unsafe fn init(desc: &crate::InstanceDescriptor) -> Result<Self, crate::InstanceError> {
+        if !desc.cpu_backend_options.enable {
+            return Err(crate::InstanceError::new(String::from(
+                "the CPU backend was not enabled",
+            )));
+        }
+        Ok(super::Instance)
+    }
+
+    unsafe fn create_surface(
+        &self,
+        _display_handle: raw_window_handle::RawDisplayHandle,
+        _window_handle: raw_window_handle::RawWindowHandle,
+    ) -> Result<super::Surface, crate::InstanceError> {
+        Err(crate::InstanceError::new(String::from(
+            "the CPU backend doesn't support surfaces",
+        )))
+    }
+
+    unsafe fn destroy_surface(&self, _surface: super::Surface) {}
+
+    unsafe fn enumerate_adapters(&self) -> Vec<crate::ExposedAdapter<Api>> {
+        vec![crate::ExposedAdapter {
+            adapter: super::Adapter,
+            info: wgt::AdapterInfo {
+                name: String::from("CPU"),
+                vendor: 0,
+                device: 0,
+                device_type: wgt::DeviceType::Cpu,
+                driver: String::from("wgpu-hal"),
+                driver_info: String::new(),
+                backend: wgt::Backend::Cpu,
+            },
+            features: super::Adapter::FEATURES,
+            capabilities: crate::Capabilities {
+                limits: wgt::Limits::default(),
+                alignments: crate::Alignments {
+                    buffer_copy_offset: wgt::BufferSize::new(4).unwrap(),
+                    buffer_copy_pitch: wgt::BufferSize::new(
+                        wgt::COPY_BYTES_PER_ROW_ALIGNMENT as wgt::BufferAddress,
+                    )
+                    .unwrap(),
+                },
+                downlevel: wgt::DownlevelCapabilities::default(),
+            },
+        }]
+    }
+}
+
+impl super::Adapter {
+    const FEATURES: wgt::Features = wgt::Features::DEPTH32FLOAT_STENCIL8
+        .union(wgt::Features::TIMESTAMP_QUERY)
+        .union(wgt::Features::TIMESTAMP_QUERY_INSIDE_PASSES)
+        .union(wgt::Features::MAPPABLE_PRIMARY_BUFFERS)
+        .union(wgt::Features::CLEAR_TEXTURE)
+        .union(wgt::Features::SUBGROUP);
+}
+
+impl crate::Adapter<Api> for super::Adapter {
+    unsafe fn open(
+        &self,
+        _features: wgt::Features,
+        _limits: &wgt::Limits,
+    ) -> DeviceResult<crate::OpenDevice<Api>> {
+        Ok(crate::OpenDevice {
+            device: super::Device,
+            queue: super::Queue {
+                start: Instant::now(),
+            },
+        })
+    }
+
+    unsafe fn texture_format_capabilities(
+        &self,
+        format: wgt::TextureFormat,
+    ) -> crate::TextureFormatCapabilities {
+        use crate::TextureFormatCapabilities as Tfc;
+        use wgt::{TextureFormatFeatureFlags as Tfff, TextureUsages as Tu};
+
+        // Nothing is ever sampled or rendered, so there is no reason to
+        // advertise more than WebGPU guarantees. Storage textures can't be
+        // bound to a dispatch, though.
+        let wgt::TextureFormatFeatures {
+            allowed_usages,
+            flags,
+        } = format.guaranteed_format_features(Self::FEATURES);
+
+        let mut caps = Tfc::empty();
+        caps.set(Tfc::COPY_SRC, allowed_usages.contains(Tu::COPY_SRC));
+        caps.set(Tfc::COPY_DST, allowed_usages.contains(Tu::COPY_DST));
+        caps.set(Tfc::SAMPLED, allowed_usages.contains(Tu::TEXTURE_BINDING));
+        caps.set(Tfc::SAMPLED_LINEAR, flags.contains(Tfff::FILTERABLE));
+        if allowed_usages.contains(Tu::RENDER_ATTACHMENT) {
+            caps |= if format.is_depth_stencil_format() {
+                Tfc::DEPTH_STENCIL_ATTACHMENT
+            } else {
+                Tfc::COLOR_ATTACHMENT
+            };
+        }
+        caps.set(Tfc::COLOR_ATTACHMENT_BLEND, flags.contains(Tfff::BLENDABLE));
+        caps.set(Tfc::MULTISAMPLE_X2, flags.contains(Tfff::MULTISAMPLE_X2));
+        caps.set(Tfc::MULTISAMPLE_X4, flags.contains(Tfff::MULTISAMPLE_X4));
+        caps.set(Tfc::MULTISAMPLE_X8, flags.contains(Tfff::MULTISAMPLE_X8));
+        caps.set(Tfc::MULTISAMPLE_X16, flags.contains(Tfff::MULTISAMPLE_X16));
+        caps.set(
+            Tfc::MULTISAMPLE_RESOLVE,
+            flags.contains(Tfff::MULTISAMPLE_RESOLVE),
+        );
+        caps
+    }
+
+    unsafe fn surface_capabilities(
+        &self,
+        _surface: &super::Surface,
+    ) -> Option<crate::SurfaceCapabilities> {
+        None
+    }
+
+    unsafe fn get_presentation_timestamp(&self) -> wgt::PresentationTimestamp {
+        wgt::PresentationTimestamp::INVALID_TIMESTAMP
+    }
+}
+
+impl crate::Surface<Api> for super::Surface {
+    unsafe fn configure(
+        &self,
+        _device: &super::Device,
+        _config: &crate::SurfaceConfiguration,
+    ) -> Result<(), crate::SurfaceError> {
+        Err(crate::SurfaceError::Lost)
+    }
+
+    unsafe fn unconfigure(&self, _device: &super::Device) {}
+
+    unsafe fn acquire_texture(
+        &self,
+        _timeout: Option<std::time::Duration>,
+    ) -> Result<Option<crate::AcquiredSurfaceTexture<Api>>, crate::SurfaceError> {
+        Err(crate::SurfaceError::Lost)
+    }
+
+    unsafe fn discard_texture(&self, _texture: super::Texture) {}
+}
@@ -0,0 +1,419 @@
+use std::{mem, ops::Range, sync::Arc};
+
+use super::{Api, Command as C, DeviceResult};
+
+#[derive(Debug, Default)]
+pub(super) struct State {
+    compute_pipeline: Option<super::ComputePipeline>,
+    bind_groups: Vec<Option<BoundGroup>>,
+    /// The multisampled attachments of the current render pass, and the
+    /// views they resolve to.
+    resolves: Vec<(super::TextureView, super::TextureView)>,
+    end_of_pass_timestamp: Option<(super::QuerySet, u32)>,
+}
+
+#[derive(Debug)]
+struct BoundGroup {
+    buffers: Arc<[super::BufferEntry]>,
+    dynamic_offsets: Vec<wgt::DynamicOffset>,
+}
+
+impl super::CommandEncoder {
+    fn begin_pass(
+        &mut self,
+        timestamp_writes: Option<(&super::QuerySet, Option<u32>, Option<u32>)>,
+    ) {
+        self.state = State::default();
+        if let Some((set, beginning, end)) = timestamp_writes {
+            if let Some(index) = beginning {
+                self.cmd_buffer.commands.push(C::WriteTimestamp {
+                    set: set.clone(),
+                    index,
+                });
+            }
+            self.state.end_of_pass_timestamp = end.map(|index| (set.clone(), index));
+        }
+    }
+
+    fn end_pass(&mut self) {
+        if let Some((set, index)) = self.state.end_of_pass_timestamp.take() {
+            self.cmd_buffer
+                .commands
+                .push(C::WriteTimestamp { set, index });
+        }
+        self.state = State::default();
+    }
+
+    /// The buffers that the bound groups bind, with their dynamic offsets
+    /// applied.
+    fn bound_buffers(&self) -> Vec<super::BoundBuffer> {
+        let mut buffers = Vec::new();
+        for (group, bound) in self.state.bind_groups.iter().enumerate() {
+            let bound = match *bound {
+                Some(ref bound) => bound,
+                None => continue,
+            };
+            let mut dynamic_offsets = bound.dynamic_offsets.iter();
+            for entry in bound.buffers.iter() {
+                let mut offset = entry.offset;
+                if entry.has_dynamic_offset {
+                    offset += dynamic_offsets.next().copied().unwrap_or(0) as wgt::BufferAddress;
+                }
+                buffers.push(super::BoundBuffer {
+                    group: group as u32,
+                    binding: entry.binding,
+                    buffer: entry.buffer.clone(),
+                    offset,
+                    size: entry.size,
+                });
+            }
+        }
+        buffers
+    }
+
+    fn push_dispatch(&mut self, count: super::DispatchCount) {
+        let pipeline = match self.state.compute_pipeline {
+            Some(ref pipeline) => pipeline.clone(),
+            None => return,
+        };
+        let buffers = self.bound_buffers();
+        self.cmd_buffer.commands.push(C::Dispatch {
+            pipeline,
+            buffers,
+            count,
+        });
+    }
+}
+
+impl crate::CommandEncoder<Api> for super::CommandEncoder {
+    unsafe fn begin_encoding(&mut self, label: crate::Label) -> DeviceResult<()> {
+        self.state = State::default();
+        self.cmd_buffer.label = label.map(str::to_string);
+        Ok(())
+    }
+    unsafe fn discard_encoding(&mut self) {
+        self.cmd_buffer.commands.clear();
+    }
+    unsafe fn end_encoding(&mut self) -> DeviceResult<super::CommandBuffer> {
+        Ok(mem::take(&mut self.cmd_buffer))
+    }
+    unsafe fn reset_all<I>(&mut self, _command_buffers: I) {}
+
+    unsafe fn transition_buffers<'a, T>(&mut self, _barriers: T)
+    where
+        T: Iterator<Item = crate::BufferBarrier<'a, Api>>,
+    {
+    }
+
+    unsafe fn transition_textures<'a, T>(&mut self, _barriers: T)
+    where
+        T: Iterator<Item = crate::TextureBarrier<'a, Api>>,
+    {
+    }
+
+    unsafe fn clear_buffer(&mut self, buffer: &super::Buffer, range: crate::MemoryRange) {
+        self.cmd_buffer.commands.push(C::ClearBuffer {
+            dst: buffer.clone(),
+            range,
+        });
+    }
+
+    unsafe fn copy_buffer_to_buffer<T>(
+        &mut self,
+        src: &super::Buffer,
+        dst: &super::Buffer,
+        regions: T,
+    ) where
+        T: Iterator<Item = crate::BufferCopy>,
+    {
+        for copy in regions {
+            self.cmd_buffer.commands.push(C::CopyBufferToBuffer {
+                src: src.clone(),
+                dst: dst.clone(),
+                copy,
+            });
+        }
+    }
+
+    #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+    unsafe fn copy_external_image_to_texture<T>(
+        &mut self,
+        _src: &wgt::ImageCopyExternalImage,
+        _dst: &super::Texture,
+        _dst_premultiplication: bool,
+        _regions: T,
+    ) where
+        T: Iterator<Item = crate::TextureCopy>,
+    {
+        log::warn!("The CPU backend can't copy external images");
+    }
+
+    unsafe fn copy_texture_to_texture<T>(
+        &mut self,
+        src: &super::Texture,
+        _src_usage: crate::TextureUses,
+        dst: &super::Texture,
+        regions: T,
+    ) where
+        T: Iterator<Item = crate::TextureCopy>,
+    {
+        for copy in regions {
+            self.cmd_buffer.commands.push(C::CopyTextureToTexture {
+                src: Arc::clone(&src.inner),
+                dst: Arc::clone(&dst.inner),
+                copy,
+            });
+        }
+    }
+
+    unsafe fn copy_buffer_to_texture<T>(
+        &mut self,
+        src: &super::Buffer,
+        dst: &super::Texture,
+        regions: T,
+    ) where
+        T: Iterator<Item = crate::BufferTextureCopy>,
+    {
+        for copy in regions {
+            self.cmd_buffer.commands.push(C::CopyBufferToTexture {
+                src: src.clone(),
+                dst: Arc::clone(&dst.inner),
+                copy,
+            });
+        }
+    }
+
+    unsafe fn copy_texture_to_buffer<T>(
+        &mut self,
+        src: &super::Texture,
+        _src_usage: crate::TextureUses,
+        dst: &super::Buffer,
+        regions: T,
+    ) where
+        T: Iterator<Item = crate::BufferTextureCopy>,
+    {
+        for copy in regions {
+            self.cmd_buffer.commands.push(C::CopyTextureToBuffer {
+                src: Arc::clone(&src.inner),
+                dst: dst.clone(),
+                copy,
+            });
+        }
+    }
+
+    unsafe fn begin_query(&mut self, _set: &super::QuerySet, _index: u32) {}
+    unsafe fn end_query(&mut self, set: &super::QuerySet, index: u32) {
+        // Nothing is rasterized, so no samples ever pass.
+        self.cmd_buffer.commands.push(C::ResetQueries {
+            set: set.clone(),
+            range: index..index + 1,
+        });
+    }
+    unsafe fn write_timestamp(&mut self, set: &super::QuerySet, index: u32) {
+        self.cmd_buffer.commands.push(C::WriteTimestamp {
+            set: set.clone(),
+            index,
+        });
+    }
+    unsafe fn reset_queries(&mut self, set: &super::QuerySet, range: Range<u32>) {
+        self.cmd_buffer.commands.push(C::ResetQueries {
+            set: set.clone(),
+            range,
+        });
+    }
+    unsafe fn copy_query_results(
+        &mut self,
+        set: &super::QuerySet,
+        range: Range<u32>,
+        buffer: &super::Buffer,
+        offset: wgt::BufferAddress,
+        stride: wgt::BufferSize,
+    ) {
+        self.cmd_buffer.commands.push(C::CopyQueryResults {
+            set: set.clone(),
+            range,
+            dst: buffer.clone(),
+            offset,
+            stride,
+        });
+    }
+
+    // render
+
+    unsafe fn begin_render_pass(&mut self, desc: &crate::RenderPassDescriptor<Api>) {
+        self.begin_pass(desc.timestamp_writes.as_ref().map(|tw| {
+            (
+                tw.query_set,
+                tw.beginning_of_pass_write_index,
+                tw.end_of_pass_write_index,
+            )
+        }));
+
+        for cat in desc.color_attachments.iter().flatten() {
+            if !cat.ops.contains(crate::AttachmentOps::LOAD) {
+                self.cmd_buffer.commands.push(C::ClearAttachment {
+                    view: cat.target.view.clone(),
+                    aspects: crate::FormatAspects::COLOR,
+                    value: super::ClearValue::Color(cat.clear_value),
+                });
+            }
+            if let Some(ref resolve) = cat.resolve_target {
+                self.state
+                    .resolves
+                    .push((cat.target.view.clone(), resolve.view.clone()));
+            }
+        }
+        if let Some(ref dsat) = desc.depth_stencil_attachment {
+            let (depth, stencil) = dsat.clear_value;
+            if !dsat.depth_ops.contains(crate::AttachmentOps::LOAD) {
+                self.cmd_buffer.commands.push(C::ClearAttachment {
+                    view: dsat.target.view.clone(),
+                    aspects: crate::FormatAspects::DEPTH,
+                    value: super::ClearValue::Depth(depth),
+                });
+            }
+            if !dsat.stencil_ops.contains(crate::AttachmentOps::LOAD) {
+                self.cmd_buffer.commands.push(C::ClearAttachment {
+                    view: dsat.target.view.clone(),
+                    aspects: crate::FormatAspects::STENCIL,
+                    value: super::ClearValue::Stencil(stencil),
+                });
+            }
+        }
+    }
+    unsafe fn end_render_pass(&mut self) {
+        for (src, dst) in mem::take(&mut self.state.resolves) {
+            self.cmd_buffer
+                .commands
+                .push(C::ResolveAttachment { src, dst });
+        }
+        self.end_pass();
+    }
+
+    unsafe fn set_bind_group(
+        &mut self,
+        _layout: &super::PipelineLayout,
+        index: u32,
+        group: &super::BindGroup,
+        dynamic_offsets: &[wgt::DynamicOffset],
+    ) {
+        let index = index as usize;
+        if self.state.bind_groups.len() <= index {
+            self.state.bind_groups.resize_with(index + 1, || None);
+        }
+        self.state.bind_groups[index] = Some(BoundGroup {
+            buffers: Arc::clone(&group.buffers),
+            dynamic_offsets: dynamic_offsets.to_vec(),
+        });
+    }
+    unsafe fn set_push_constants(
+        &mut self,
+        _layout: &super::PipelineLayout,
+        _stages: wgt::ShaderStages,
+        _offset_bytes: u32,
+        _data: &[u32],
+    ) {
+    }
+
+    unsafe fn insert_debug_marker(&mut self, _label: &str) {}
+    unsafe fn begin_debug_marker(&mut self, _group_label: &str) {}
+    unsafe fn end_debug_marker(&mut self) {}
+
+    unsafe fn set_render_pipeline(&mut self, _pipeline: &super::RenderPipeline) {}
+
+    unsafe fn set_index_buffer<'a>(
+        &mut self,
+        _binding: crate::BufferBinding<'a, Api>,
+        _format: wgt::IndexFormat,
+    ) {
+    }
+    unsafe fn set_vertex_buffer<'a>(
+        &mut self,
+        _index: u32,
+        _binding: crate::BufferBinding<'a, Api>,
+    ) {
+    }
+    unsafe fn set_viewport(&mut self, _rect: &crate::Rect<f32>, _depth_range: Range<f32>) {}
+    unsafe fn set_scissor_rect(&mut self, _rect: &crate::Rect<u32>) {}
+    unsafe fn set_stencil_reference(&mut self, _value: u32) {}
+    unsafe fn set_blend_constants(&mut self, _color: &[f32; 4]) {}
+
+    unsafe fn draw(
+        &mut self,
+        _first_vertex: u32,
+        _vertex_count: u32,
+        _first_instance: u32,
+        _instance_count: u32,
+    ) {
+    }
+    unsafe fn draw_indexed(
+        &mut self,
+        _first_index: u32,
+        _index_count: u32,
+        _base_vertex: i32,
+        _first_instance: u32,
+        _instance_count: u32,
+    ) {
+    }
+    unsafe fn draw_indirect(
+        &mut self,
+        _buffer: &super::Buffer,
+        _offset: wgt::BufferAddress,
+        _draw_count: u32,
+    ) {
+    }
+    unsafe fn draw_indexed_indirect(
+        &mut self,
+        _buffer: &super::Buffer,
+        _offset: wgt::BufferAddress,
+        _draw_count: u32,
+    ) {
+    }
+    unsafe fn draw_indirect_count(
+        &mut self,
+        _buffer: &super::Buffer,
+        _offset: wgt::BufferAddress,
+        _count_buffer: &super::Buffer,
+        _count_offset: wgt::BufferAddress,
+        _max_count: u32,
+    ) {
+    }
+    unsafe fn draw_indexed_indirect_count(
+        &mut self,
+        _buffer: &super::Buffer,
+        _offset: wgt::BufferAddress,
+        _count_buffer: &super::Buffer,
+        _count_offset: wgt::BufferAddress,
+        _max_count: u32,
+    ) {
+    }
+
+    // compute
+
+    unsafe fn begin_compute_pass(&mut self, desc: &crate::ComputePassDescriptor<Api>) {
+        self.begin_pass(desc.timestamp_writes.as_ref().map(|tw| {
+            (
+                tw.query_set,
+                tw.beginning_of_pass_write_index,
+                tw.end_of_pass_write_index,
+            )
+        }));
+    }
+    unsafe fn end_compute_pass(&mut self) {
+        self.end_pass();
+    }
+
+    unsafe fn set_compute_pipeline(&mut self, pipeline: &super::ComputePipeline) {
+        self.state.compute_pipeline = Some(pipeline.clone());
+    }
+
+    unsafe fn dispatch(&mut self, count: [u32; 3]) {
+        self.push_dispatch(super::DispatchCount::Direct(count));
+    }
+    unsafe fn dispatch_indirect(&mut self, buffer: &super::Buffer, offset: wgt::BufferAddress) {
+        self.push_dispatch(super::DispatchCount::Indirect {
+            buffer: buffer.clone(),
+            offset,
+        });
+    }
+}
@@ -0,0 +1,147 @@
+pub fn map_aspect(aspect: crate::FormatAspects) -> wgt::TextureAspect {
+    match aspect {
+        crate::FormatAspects::DEPTH => wgt::TextureAspect::DepthOnly,
+        crate::FormatAspects::STENCIL => wgt::TextureAspect::StencilOnly,
+        _ => wgt::TextureAspect::All,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Channel {
+    Unorm8,
+    Snorm8,
+    Uint8,
+    Sint8,
+    Unorm16,
+    Snorm16,
+    Uint16,
+    Sint16,
+    Float16,
+    Uint32,
+    Sint32,
+    Float32,
+}
+
+/// Encode `color` as a texel of `format`, if it is a format with one block
+/// per texel and a whole number of bytes per channel.
+pub fn encode_color(format: wgt::TextureFormat, color: wgt::Color) -> Option<Vec<u8>> {
+    use wgt::TextureFormat as Tf;
+    use Channel as C;
+
+    let (channel, count) = match format {
+        Tf::R8Unorm => (C::Unorm8, 1),
+        Tf::R8Snorm => (C::Snorm8, 1),
+        Tf::R8Uint => (C::Uint8, 1),
+        Tf::R8Sint => (C::Sint8, 1),
+        Tf::Rg8Unorm => (C::Unorm8, 2),
+        Tf::Rg8Snorm => (C::Snorm8, 2),
+        Tf::Rg8Uint => (C::Uint8, 2),
+        Tf::Rg8Sint => (C::Sint8, 2),
+        Tf::Rgba8Unorm | Tf::Rgba8UnormSrgb | Tf::Bgra8Unorm | Tf::Bgra8UnormSrgb => (C::Unorm8, 4),
+        Tf::Rgba8Snorm => (C::Snorm8, 4),
+        Tf::Rgba8Uint => (C::Uint8, 4),
+        Tf::Rgba8Sint => (C::Sint8, 4),
+        Tf::R16Unorm => (C::Unorm16, 1),
+        Tf::R16Snorm => (C::Snorm16, 1),
+        Tf::R16Uint => (C::Uint16, 1),
+        Tf::R16Sint => (C::Sint16, 1),
+        Tf::R16Float => (C::Float16, 1),
+        Tf::Rg16Unorm => (C::Unorm16, 2),
+        Tf::Rg16Snorm => (C::Snorm16, 2),
+        Tf::Rg16Uint => (C::Uint16, 2),
+        Tf::Rg16Sint => (C::Sint16, 2),
+        Tf::Rg16Float => (C::Float16, 2),
+        Tf::Rgba16Unorm => (C::Unorm16, 4),
+        Tf::Rgba16Snorm => (C::Snorm16, 4),
+        Tf::Rgba16Uint => (C::Uint16, 4),
+        Tf::Rgba16Sint => (C::Sint16, 4),
+        Tf::Rgba16Float => (C::Float16, 4),
+        Tf::R32Uint => (C::Uint32, 1),
+        Tf::R32Sint => (C::Sint32, 1),
+        Tf::R32Float => (C::Float32, 1),
+        Tf::Rg32Uint => (C::Uint32, 2),
+        Tf::Rg32Sint => (C::Sint32, 2),
+        Tf::Rg32Float => (C::Float32, 2),
+        Tf::Rgba32Uint => (C::Uint32, 4),
+        Tf::Rgba32Sint => (C::Sint32, 4),
+        Tf::Rgba32Float => (C::Float32, 4),
+        _ => return None,
+    };
+
+    let mut values = [color.r, color.g, color.b, color.a];
+    if format.is_srgb() {
+        for value in values[..3].iter_mut() {
+            *value = linear_to_srgb(*value);
+        }
+    }
+    if let Tf::Bgra8Unorm | Tf::Bgra8UnormSrgb = format {
+        values.swap(0, 2);
+    }
+
+    let mut texel = Vec::new();
+    for &value in values[..count].iter() {
+        match channel {
+            C::Unorm8 => texel.push((value.clamp(0.0, 1.0) * 255.0).round() as u8),
+            C::Snorm8 => texel.push((value.clamp(-1.0, 1.0) * 127.0).round() as i8 as u8),
+            C::Uint8 => texel.push(value as u8),
+            C::Sint8 => texel.push(value as i8 as u8),
+            C::Unorm16 => {
+                texel.extend(((value.clamp(0.0, 1.0) * 65535.0).round() as u16).to_le_bytes())
+            }
+            C::Snorm16 => {
+                texel.extend(((value.clamp(-1.0, 1.0) * 32767.0).round() as i16).to_le_bytes())
+            }
+            C::Uint16 => texel.extend((value as u16).to_le_bytes()),
+            C::Sint16 => texel.extend((value as i16).to_le_bytes()),
+            C::Float16 => texel.extend(f16_bits(value as f32).to_le_bytes()),
+            C::Uint32 => texel.extend((value as u32).to_le_bytes()),
+            C::Sint32 => texel.extend((value as i32).to_le_bytes()),
+            C::Float32 => texel.extend((value as f32).to_le_bytes()),
+        }
+    }
+    Some(texel)
+}
+
+/// Encode `depth` as a texel of the depth aspect of `format`.
+pub fn encode_depth(format: wgt::TextureFormat, depth: f32) -> Vec<u8> {
+    match format {
+        wgt::TextureFormat::Depth16Unorm => ((depth.clamp(0.0, 1.0) * 65535.0).round() as u16)
+            .to_le_bytes()
+            .to_vec(),
+        // `Depth24Plus` is stored as `f32`, like `Depth32Float`.
+        _ => depth.to_le_bytes().to_vec(),
+    }
+}
+
+fn linear_to_srgb(value: f64) -> f64 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Convert `value` to a half-precision float, rounding towards zero.
+fn f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if exponent <= 0 {
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        return sign | (mantissa >> (14 - exponent)) as u16;
+    }
+    sign | ((exponent as u16) << 10) | (mantissa >> 13) as u16
+}
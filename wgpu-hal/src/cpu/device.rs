@@ -0,0 +1,298 @@
+use std::{ptr::NonNull, sync::Arc};
+
+use parking_lot::Mutex;
+
+use super::{conv, Api, DeviceResult};
+
+/// Allocate `size` zeroed bytes, failing gracefully if we can't.
+fn allocate(size: usize) -> DeviceResult<Vec<u8>> {
+    let mut data = Vec::new();
+    data.try_reserve_exact(size)
+        .map_err(|_| crate::DeviceError::OutOfMemory)?;
+    data.resize(size, 0);
+    Ok(data)
+}
+
+impl crate::Device<Api> for super::Device {
+    unsafe fn exit(self, _queue: super::Queue) {}
+
+    unsafe fn create_buffer(&self, desc: &crate::BufferDescriptor) -> DeviceResult<super::Buffer> {
+        let size = usize::try_from(desc.size).map_err(|_| crate::DeviceError::OutOfMemory)?;
+        Ok(super::Buffer {
+            data: Arc::new(Mutex::new(allocate(size)?)),
+        })
+    }
+    unsafe fn destroy_buffer(&self, _buffer: super::Buffer) {}
+
+    unsafe fn map_buffer(
+        &self,
+        buffer: &super::Buffer,
+        range: crate::MemoryRange,
+    ) -> DeviceResult<crate::BufferMapping> {
+        // The vector is never resized, so the pointer stays valid for as
+        // long as the buffer lives.
+        let mut data = buffer.data.lock();
+        let ptr = data[range.start as usize..range.end as usize].as_mut_ptr();
+        Ok(crate::BufferMapping {
+            ptr: NonNull::new(ptr).unwrap(),
+            is_coherent: true,
+        })
+    }
+    unsafe fn unmap_buffer(&self, _buffer: &super::Buffer) -> DeviceResult<()> {
+        Ok(())
+    }
+    unsafe fn flush_mapped_ranges<I>(&self, _buffer: &super::Buffer, _ranges: I) {}
+    unsafe fn invalidate_mapped_ranges<I>(&self, _buffer: &super::Buffer, _ranges: I) {}
+
+    unsafe fn create_texture(
+        &self,
+        desc: &crate::TextureDescriptor,
+    ) -> DeviceResult<super::Texture> {
+        let (block_width, block_height) = desc.format.block_dimensions();
+        let planes = crate::FormatAspects::from(desc.format)
+            .iter()
+            .map(|aspect| {
+                // Depth formats that can't be copied are stored as `f32`.
+                let block_size = desc
+                    .format
+                    .block_copy_size(Some(conv::map_aspect(aspect)))
+                    .unwrap_or(4) as usize;
+                let texel_size = block_size * desc.sample_count as usize;
+
+                let mut size = 0;
+                let mips = (0..desc.mip_level_count)
+                    .map(|level| {
+                        let extent = desc.size.mip_level_size(level, desc.dimension);
+                        let blocks_x = (extent.width + block_width - 1) / block_width;
+                        let blocks_y = (extent.height + block_height - 1) / block_height;
+                        let bytes_per_row = blocks_x as usize * texel_size;
+                        let bytes_per_slice = bytes_per_row * blocks_y as usize;
+                        let layout = super::MipLayout {
+                            offset: size,
+                            bytes_per_row,
+                            bytes_per_slice,
+                        };
+                        size += bytes_per_slice * extent.depth_or_array_layers as usize;
+                        layout
+                    })
+                    .collect();
+
+                Ok(super::Plane {
+                    aspect,
+                    block_size,
+                    texel_size,
+                    mips,
+                    data: Mutex::new(allocate(size)?),
+                })
+            })
+            .collect::<DeviceResult<_>>()?;
+
+        Ok(super::Texture {
+            inner: Arc::new(super::TextureInner {
+                format: desc.format,
+                array_layer_count: desc.array_layer_count(),
+                planes,
+            }),
+        })
+    }
+    unsafe fn destroy_texture(&self, _texture: super::Texture) {}
+
+    unsafe fn create_texture_view(
+        &self,
+        texture: &super::Texture,
+        desc: &crate::TextureViewDescriptor,
+    ) -> DeviceResult<super::TextureView> {
+        let range = &desc.range;
+        let end_layer = match range.array_layer_count {
+            Some(count) => range.base_array_layer + count,
+            None => texture.inner.array_layer_count,
+        };
+        Ok(super::TextureView {
+            texture: Arc::clone(&texture.inner),
+            format: desc.format,
+            aspects: crate::FormatAspects::new(texture.inner.format, range.aspect),
+            mip_level: range.base_mip_level,
+            array_layers: range.base_array_layer..end_layer,
+        })
+    }
+    unsafe fn destroy_texture_view(&self, _view: super::TextureView) {}
+
+    unsafe fn create_sampler(
+        &self,
+        _desc: &crate::SamplerDescriptor,
+    ) -> DeviceResult<super::Sampler> {
+        Ok(super::Sampler)
+    }
+    unsafe fn destroy_sampler(&self, _sampler: super::Sampler) {}
+
+    unsafe fn create_command_encoder(
+        &self,
+        _desc: &crate::CommandEncoderDescriptor<Api>,
+    ) -> DeviceResult<super::CommandEncoder> {
+        Ok(super::CommandEncoder {
+            cmd_buffer: super::CommandBuffer::default(),
+            state: Default::default(),
+        })
+    }
+    unsafe fn destroy_command_encoder(&self, _encoder: super::CommandEncoder) {}
+
+    unsafe fn create_bind_group_layout(
+        &self,
+        desc: &crate::BindGroupLayoutDescriptor,
+    ) -> DeviceResult<super::BindGroupLayout> {
+        Ok(super::BindGroupLayout {
+            entries: desc.entries.into(),
+        })
+    }
+    unsafe fn destroy_bind_group_layout(&self, _bg_layout: super::BindGroupLayout) {}
+
+    unsafe fn create_pipeline_layout(
+        &self,
+        _desc: &crate::PipelineLayoutDescriptor<Api>,
+    ) -> DeviceResult<super::PipelineLayout> {
+        Ok(super::PipelineLayout)
+    }
+    unsafe fn destroy_pipeline_layout(&self, _pipeline_layout: super::PipelineLayout) {}
+
+    unsafe fn create_bind_group(
+        &self,
+        desc: &crate::BindGroupDescriptor<Api>,
+    ) -> DeviceResult<super::BindGroup> {
+        let mut buffers = Vec::new();
+        for entry in desc.entries {
+            let layout = desc
+                .layout
+                .entries
+                .iter()
+                .find(|layout| layout.binding == entry.binding)
+                .expect("bind group entry has no layout");
+            let has_dynamic_offset = match layout.ty {
+                wgt::BindingType::Buffer {
+                    has_dynamic_offset, ..
+                } => has_dynamic_offset,
+                // Only buffers can be bound to a dispatch.
+                _ => continue,
+            };
+            let binding = &desc.buffers[entry.resource_index as usize];
+            let size = match binding.size {
+                Some(size) => size.get(),
+                None => binding.buffer.data.lock().len() as wgt::BufferAddress - binding.offset,
+            };
+            buffers.push(super::BufferEntry {
+                binding: entry.binding,
+                buffer: binding.buffer.clone(),
+                offset: binding.offset,
+                size,
+                has_dynamic_offset,
+            });
+        }
+        // Dynamic offsets are given in binding order.
+        buffers.sort_by_key(|entry| entry.binding);
+
+        Ok(super::BindGroup {
+            buffers: buffers.into(),
+        })
+    }
+    unsafe fn destroy_bind_group(&self, _group: super::BindGroup) {}
+
+    unsafe fn create_shader_module(
+        &self,
+        desc: &crate::ShaderModuleDescriptor,
+        shader: crate::ShaderInput,
+    ) -> Result<super::ShaderModule, crate::ShaderError> {
+        let naga = match shader {
+            crate::ShaderInput::SpirV(_) => {
+                panic!("`Features::SPIRV_SHADER_PASSTHROUGH` is not enabled")
+            }
+            crate::ShaderInput::Naga(naga) => naga,
+        };
+        Ok(super::ShaderModule {
+            shader: Arc::new(super::Shader {
+                module: naga.module.into_owned(),
+                info: naga.info,
+            }),
+            runtime_checks: desc.runtime_checks,
+        })
+    }
+    unsafe fn destroy_shader_module(&self, _module: super::ShaderModule) {}
+
+    unsafe fn create_render_pipeline(
+        &self,
+        _desc: &crate::RenderPipelineDescriptor<Api>,
+    ) -> Result<super::RenderPipeline, crate::PipelineError> {
+        Ok(super::RenderPipeline)
+    }
+    unsafe fn destroy_render_pipeline(&self, _pipeline: super::RenderPipeline) {}
+
+    unsafe fn create_compute_pipeline(
+        &self,
+        desc: &crate::ComputePipelineDescriptor<Api>,
+    ) -> Result<super::ComputePipeline, crate::PipelineError> {
+        let module = desc.stage.module;
+        let has_entry_point =
+            module.shader.module.entry_points.iter().any(|ep| {
+                ep.stage == naga::ShaderStage::Compute && ep.name == desc.stage.entry_point
+            });
+        if !has_entry_point {
+            return Err(crate::PipelineError::EntryPoint(naga::ShaderStage::Compute));
+        }
+
+        let bounds_check_policies = if module.runtime_checks {
+            naga::proc::BoundsCheckPolicies {
+                index: naga::proc::BoundsCheckPolicy::Restrict,
+                buffer: naga::proc::BoundsCheckPolicy::Restrict,
+                image_load: naga::proc::BoundsCheckPolicy::Restrict,
+                image_store: naga::proc::BoundsCheckPolicy::Unchecked,
+                binding_array: naga::proc::BoundsCheckPolicy::Unchecked,
+            }
+        } else {
+            // Out-of-bounds accesses fail the dispatch, rather than touching
+            // memory they shouldn't.
+            naga::proc::BoundsCheckPolicies::default()
+        };
+
+        Ok(super::ComputePipeline {
+            shader: Arc::clone(&module.shader),
+            entry_point: desc.stage.entry_point.into(),
+            bounds_check_policies,
+        })
+    }
+    unsafe fn destroy_compute_pipeline(&self, _pipeline: super::ComputePipeline) {}
+
+    unsafe fn create_query_set(
+        &self,
+        desc: &wgt::QuerySetDescriptor<crate::Label>,
+    ) -> DeviceResult<super::QuerySet> {
+        let values_per_query = match desc.ty {
+            wgt::QueryType::PipelineStatistics(types) => types.bits().count_ones() as usize,
+            wgt::QueryType::Occlusion | wgt::QueryType::Timestamp => 1,
+        };
+        Ok(super::QuerySet {
+            data: Arc::new(Mutex::new(vec![0; desc.count as usize * values_per_query])),
+            values_per_query,
+        })
+    }
+    unsafe fn destroy_query_set(&self, _set: super::QuerySet) {}
+
+    unsafe fn create_fence(&self) -> DeviceResult<super::Fence> {
+        Ok(super::Fence { value: 0 })
+    }
+    unsafe fn destroy_fence(&self, _fence: super::Fence) {}
+    unsafe fn get_fence_value(&self, fence: &super::Fence) -> DeviceResult<crate::FenceValue> {
+        Ok(fence.value)
+    }
+    unsafe fn wait(
+        &self,
+        fence: &super::Fence,
+        value: crate::FenceValue,
+        _timeout_ms: u32,
+    ) -> DeviceResult<bool> {
+        // Submissions are executed before `submit` returns.
+        Ok(fence.value >= value)
+    }
+
+    unsafe fn start_capture(&self) -> bool {
+        false
+    }
+    unsafe fn stop_capture(&self) {}
+}
@@ -0,0 +1,330 @@
+/*!
+# CPU API internals.
+
+A software backend that needs no GPU at all. It is only instantiated when
+[`wgt::CpuBackendOptions::enable`] is set, and then exposes a single adapter
+of type [`wgt::DeviceType::Cpu`].
+
+## Memory
+
+Buffers, textures and query sets live in host memory. Buffers are plain byte
+vectors, and mapping one hands out a pointer into it. Textures keep one
+plane per aspect of their format, holding every mip level in turn; a mip
+level holds its array layers, or depth slices, one after the other, each as
+rows of texel blocks. The samples of a multisampled texel are adjacent.
+
+## Commands
+
+Command encoders record commands that keep the resources they use alive,
+and [`Queue::submit`](crate::Queue::submit) executes them one after the
+other, before it returns. Fences are therefore signaled as soon as they are
+submitted.
+
+Transfers, buffer and texture clears, and query writes all work as they do
+on a GPU. Compute dispatches run the compute entry point through
+[`naga::interp`], with the uniform and storage buffers of the bound groups.
+Bindings of the same buffer alias, as they do on a GPU. Textures and samplers
+can't be bound to a dispatch, and an error from the interpreter loses the
+device.
+
+Nothing is rasterized: a render pass loads, clears, and resolves its
+attachments, but its draws are ignored.
+!*/
+
+mod adapter;
+mod command;
+mod conv;
+mod device;
+mod queue;
+
+use std::{ops::Range, sync::Arc, time::Instant};
+
+use parking_lot::Mutex;
+
+#[derive(Clone, Debug)]
+pub struct Api;
+
+impl crate::Api for Api {
+    type Instance = Instance;
+    type Surface = Surface;
+    type Adapter = Adapter;
+    type Device = Device;
+
+    type Queue = Queue;
+    type CommandEncoder = CommandEncoder;
+    type CommandBuffer = CommandBuffer;
+
+    type Buffer = Buffer;
+    type Texture = Texture;
+    type SurfaceTexture = Texture;
+    type TextureView = TextureView;
+    type Sampler = Sampler;
+    type QuerySet = QuerySet;
+    type Fence = Fence;
+
+    type BindGroupLayout = BindGroupLayout;
+    type BindGroup = BindGroup;
+    type PipelineLayout = PipelineLayout;
+    type ShaderModule = ShaderModule;
+    type RenderPipeline = RenderPipeline;
+    type ComputePipeline = ComputePipeline;
+}
+
+type DeviceResult<T> = Result<T, crate::DeviceError>;
+
+#[derive(Debug)]
+pub struct Instance;
+
+/// Surfaces are never created, since the CPU backend can't present.
+#[derive(Debug)]
+pub struct Surface;
+
+#[derive(Debug)]
+pub struct Adapter;
+
+#[derive(Debug)]
+pub struct Device;
+
+#[derive(Debug)]
+pub struct Queue {
+    /// The time that timestamp queries count from.
+    start: Instant,
+}
+
+#[derive(Clone, Debug)]
+pub struct Buffer {
+    data: Arc<Mutex<Vec<u8>>>,
+}
+
+#[derive(Debug)]
+pub struct Texture {
+    inner: Arc<TextureInner>,
+}
+
+#[derive(Debug)]
+struct TextureInner {
+    format: wgt::TextureFormat,
+    array_layer_count: u32,
+    planes: Vec<Plane>,
+}
+
+/// The texels of one aspect of a texture.
+#[derive(Debug)]
+struct Plane {
+    aspect: crate::FormatAspects,
+    /// The size of a texel block, for a single sample.
+    block_size: usize,
+    /// The size of a texel block, with all its samples.
+    texel_size: usize,
+    mips: Vec<MipLayout>,
+    data: Mutex<Vec<u8>>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct MipLayout {
+    offset: usize,
+    bytes_per_row: usize,
+    bytes_per_slice: usize,
+}
+
+impl TextureInner {
+    fn plane(&self, aspect: crate::FormatAspects) -> &Plane {
+        self.planes(aspect).next().unwrap()
+    }
+
+    fn planes(&self, aspects: crate::FormatAspects) -> impl Iterator<Item = &Plane> {
+        self.planes
+            .iter()
+            .filter(move |plane| plane.aspect.intersects(aspects))
+    }
+}
+
+impl Plane {
+    /// The byte offset of the block at `block_x` and `block_y` in `slice` of
+    /// `mip_level`.
+    fn offset(&self, mip_level: u32, slice: u32, block_x: u32, block_y: u32) -> usize {
+        let mip = &self.mips[mip_level as usize];
+        mip.offset
+            + slice as usize * mip.bytes_per_slice
+            + block_y as usize * mip.bytes_per_row
+            + block_x as usize * self.texel_size
+    }
+
+    /// The bytes of `slices` of `mip_level`.
+    fn slice_range(&self, mip_level: u32, slices: Range<u32>) -> Range<usize> {
+        let mip = &self.mips[mip_level as usize];
+        mip.offset + slices.start as usize * mip.bytes_per_slice
+            ..mip.offset + slices.end as usize * mip.bytes_per_slice
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TextureView {
+    texture: Arc<TextureInner>,
+    format: wgt::TextureFormat,
+    aspects: crate::FormatAspects,
+    mip_level: u32,
+    array_layers: Range<u32>,
+}
+
+#[derive(Debug)]
+pub struct Sampler;
+
+#[derive(Clone, Debug)]
+pub struct QuerySet {
+    /// The values of each query, one after the other.
+    data: Arc<Mutex<Vec<u64>>>,
+    values_per_query: usize,
+}
+
+#[derive(Debug)]
+pub struct Fence {
+    value: crate::FenceValue,
+}
+
+#[derive(Debug)]
+pub struct BindGroupLayout {
+    entries: Box<[wgt::BindGroupLayoutEntry]>,
+}
+
+#[derive(Debug)]
+pub struct BindGroup {
+    /// The buffers in the group, sorted by binding.
+    buffers: Arc<[BufferEntry]>,
+}
+
+#[derive(Debug)]
+struct BufferEntry {
+    binding: u32,
+    buffer: Buffer,
+    offset: wgt::BufferAddress,
+    size: wgt::BufferAddress,
+    has_dynamic_offset: bool,
+}
+
+#[derive(Debug)]
+pub struct PipelineLayout;
+
+#[derive(Debug)]
+pub struct ShaderModule {
+    shader: Arc<Shader>,
+    runtime_checks: bool,
+}
+
+struct Shader {
+    module: naga::Module,
+    info: naga::valid::ModuleInfo,
+}
+
+// Custom implementation avoids the need to generate Debug impl code
+// for the whole Naga module and info.
+impl std::fmt::Debug for Shader {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "Naga shader")
+    }
+}
+
+#[derive(Debug)]
+pub struct RenderPipeline;
+
+#[derive(Clone, Debug)]
+pub struct ComputePipeline {
+    shader: Arc<Shader>,
+    entry_point: Arc<str>,
+    bounds_check_policies: naga::proc::BoundsCheckPolicies,
+}
+
+#[derive(Debug)]
+pub struct CommandEncoder {
+    cmd_buffer: CommandBuffer,
+    state: command::State,
+}
+
+#[derive(Debug, Default)]
+pub struct CommandBuffer {
+    label: Option<String>,
+    commands: Vec<Command>,
+}
+
+/// A uniform or storage buffer bound to a dispatch.
+#[derive(Debug)]
+struct BoundBuffer {
+    group: u32,
+    binding: u32,
+    buffer: Buffer,
+    offset: wgt::BufferAddress,
+    size: wgt::BufferAddress,
+}
+
+#[derive(Debug)]
+enum DispatchCount {
+    Direct([u32; 3]),
+    Indirect {
+        buffer: Buffer,
+        offset: wgt::BufferAddress,
+    },
+}
+
+#[derive(Debug)]
+enum ClearValue {
+    Color(wgt::Color),
+    Depth(f32),
+    Stencil(u32),
+}
+
+#[derive(Debug)]
+enum Command {
+    ClearBuffer {
+        dst: Buffer,
+        range: crate::MemoryRange,
+    },
+    CopyBufferToBuffer {
+        src: Buffer,
+        dst: Buffer,
+        copy: crate::BufferCopy,
+    },
+    CopyBufferToTexture {
+        src: Buffer,
+        dst: Arc<TextureInner>,
+        copy: crate::BufferTextureCopy,
+    },
+    CopyTextureToBuffer {
+        src: Arc<TextureInner>,
+        dst: Buffer,
+        copy: crate::BufferTextureCopy,
+    },
+    CopyTextureToTexture {
+        src: Arc<TextureInner>,
+        dst: Arc<TextureInner>,
+        copy: crate::TextureCopy,
+    },
+    ClearAttachment {
+        view: TextureView,
+        aspects: crate::FormatAspects,
+        value: ClearValue,
+    },
+    ResolveAttachment {
+        src: TextureView,
+        dst: TextureView,
+    },
+    Dispatch {
+        pipeline: ComputePipeline,
+        buffers: Vec<BoundBuffer>,
+        count: DispatchCount,
+    },
+    ResetQueries {
+        set: QuerySet,
+        range: Range<u32>,
+    },
+    WriteTimestamp {
+        set: QuerySet,
+        index: u32,
+    },
+    CopyQueryResults {
+        set: QuerySet,
+        range: Range<u32>,
+        dst: Buffer,
+        offset: wgt::BufferAddress,
+        stride: wgt::BufferSize,
+    },
+}
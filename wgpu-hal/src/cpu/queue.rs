@@ -0,0 +1,298 @@
+use std::{ops::Range, sync::Arc};
+
+use super::{conv, Api, Command as C, DeviceResult};
+
+/// The texture and buffer bytes of each row of blocks that `copy` covers.
+fn buffer_texture_rows(
+    texture: &super::TextureInner,
+    copy: &crate::BufferTextureCopy,
+) -> Vec<(Range<usize>, Range<usize>)> {
+    let plane = texture.plane(copy.texture_base.aspect);
+    let (block_width, block_height) = texture.format.block_dimensions();
+    let blocks_x = (copy.size.width + block_width - 1) / block_width;
+    let blocks_y = (copy.size.height + block_height - 1) / block_height;
+    let row_size = blocks_x as usize * plane.block_size;
+    let layout = &copy.buffer_layout;
+    let bytes_per_row = layout.bytes_per_row.map_or(row_size, |bpr| bpr as usize);
+    let rows_per_image = layout.rows_per_image.unwrap_or(blocks_y) as usize;
+
+    let base = &copy.texture_base;
+    let mut rows = Vec::new();
+    for z in 0..copy.size.depth {
+        for y in 0..blocks_y {
+            let texture_offset = plane.offset(
+                base.mip_level,
+                base.array_layer + base.origin.z + z,
+                base.origin.x / block_width,
+                base.origin.y / block_height + y,
+            );
+            let buffer_offset =
+                layout.offset as usize + (z as usize * rows_per_image + y as usize) * bytes_per_row;
+            rows.push((
+                texture_offset..texture_offset + row_size,
+                buffer_offset..buffer_offset + row_size,
+            ));
+        }
+    }
+    rows
+}
+
+impl super::Queue {
+    fn execute(&self, command: &C) -> DeviceResult<()> {
+        match *command {
+            C::ClearBuffer { ref dst, ref range } => {
+                dst.data.lock()[range.start as usize..range.end as usize].fill(0);
+            }
+            C::CopyBufferToBuffer {
+                ref src,
+                ref dst,
+                copy,
+            } => {
+                let size = copy.size.get() as usize;
+                let src_offset = copy.src_offset as usize;
+                let dst_offset = copy.dst_offset as usize;
+                // `src` and `dst` may be the same buffer.
+                let data = src.data.lock()[src_offset..src_offset + size].to_vec();
+                dst.data.lock()[dst_offset..dst_offset + size].copy_from_slice(&data);
+            }
+            C::CopyBufferToTexture {
+                ref src,
+                ref dst,
+                ref copy,
+            } => {
+                let src = src.data.lock();
+                let mut texels = dst.plane(copy.texture_base.aspect).data.lock();
+                for (texture_range, buffer_range) in buffer_texture_rows(dst, copy) {
+                    texels[texture_range].copy_from_slice(&src[buffer_range]);
+                }
+            }
+            C::CopyTextureToBuffer {
+                ref src,
+                ref dst,
+                ref copy,
+            } => {
+                let texels = src.plane(copy.texture_base.aspect).data.lock();
+                let mut dst = dst.data.lock();
+                for (texture_range, buffer_range) in buffer_texture_rows(src, copy) {
+                    dst[buffer_range].copy_from_slice(&texels[texture_range]);
+                }
+            }
+            C::CopyTextureToTexture {
+                ref src,
+                ref dst,
+                ref copy,
+            } => {
+                let src_plane = src.plane(copy.src_base.aspect);
+                let dst_plane = dst.plane(copy.dst_base.aspect);
+                let (block_width, block_height) = src.format.block_dimensions();
+                let blocks_x = (copy.size.width + block_width - 1) / block_width;
+                let blocks_y = (copy.size.height + block_height - 1) / block_height;
+                let row_size = blocks_x as usize * src_plane.texel_size;
+
+                let block_offset = |plane: &super::Plane, base: &crate::TextureCopyBase, z, y| {
+                    plane.offset(
+                        base.mip_level,
+                        base.array_layer + base.origin.z + z,
+                        base.origin.x / block_width,
+                        base.origin.y / block_height + y,
+                    )
+                };
+
+                // `src` and `dst` may be the same texture.
+                let mut rows = Vec::new();
+                {
+                    let texels = src_plane.data.lock();
+                    for z in 0..copy.size.depth {
+                        for y in 0..blocks_y {
+                            let offset = block_offset(src_plane, &copy.src_base, z, y);
+                            rows.push(texels[offset..offset + row_size].to_vec());
+                        }
+                    }
+                }
+                let mut texels = dst_plane.data.lock();
+                let mut rows = rows.into_iter();
+                for z in 0..copy.size.depth {
+                    for y in 0..blocks_y {
+                        let offset = block_offset(dst_plane, &copy.dst_base, z, y);
+                        texels[offset..offset + row_size].copy_from_slice(&rows.next().unwrap());
+                    }
+                }
+            }
+            C::ClearAttachment {
+                ref view,
+                aspects,
+                ref value,
+            } => {
+                for plane in view.texture.planes(view.aspects & aspects) {
+                    let block = match *value {
+                        super::ClearValue::Color(color) => conv::encode_color(view.format, color)
+                            .filter(|block| block.len() == plane.block_size)
+                            .unwrap_or_else(|| {
+                                if color != wgt::Color::TRANSPARENT {
+                                    log::warn!(
+                                        "Clearing {:?} to {:?} is not supported, clearing to zero",
+                                        view.format,
+                                        color
+                                    );
+                                }
+                                vec![0; plane.block_size]
+                            }),
+                        super::ClearValue::Depth(depth) => {
+                            conv::encode_depth(view.texture.format, depth)
+                        }
+                        super::ClearValue::Stencil(stencil) => vec![stencil as u8],
+                    };
+                    let range = plane.slice_range(view.mip_level, view.array_layers.clone());
+                    let mut texels = plane.data.lock();
+                    for texel in texels[range].chunks_exact_mut(block.len()) {
+                        texel.copy_from_slice(&block);
+                    }
+                }
+            }
+            C::ResolveAttachment { ref src, ref dst } => {
+                let src_plane = src.texture.plane(crate::FormatAspects::COLOR);
+                let dst_plane = dst.texture.plane(crate::FormatAspects::COLOR);
+                let src_range = src_plane.slice_range(src.mip_level, src.array_layers.clone());
+                let dst_range = dst_plane.slice_range(dst.mip_level, dst.array_layers.clone());
+                // Every sample has the same value, since nothing is drawn.
+                let samples = src_plane.data.lock();
+                let mut texels = dst_plane.data.lock();
+                for (texel, sample) in texels[dst_range]
+                    .chunks_exact_mut(dst_plane.texel_size)
+                    .zip(samples[src_range].chunks_exact(src_plane.texel_size))
+                {
+                    texel.copy_from_slice(&sample[..dst_plane.texel_size]);
+                }
+            }
+            C::Dispatch {
+                ref pipeline,
+                ref buffers,
+                ref count,
+            } => {
+                if let Err(err) = Self::dispatch(pipeline, buffers, count) {
+                    // A GPU would most likely hang or reset, so the device
+                    // is lost.
+                    log::error!("Dispatch of `{}` failed: {}", pipeline.entry_point, err);
+                    return Err(crate::DeviceError::Lost);
+                }
+            }
+            C::ResetQueries { ref set, ref range } => {
+                let per_query = set.values_per_query;
+                set.data.lock()[range.start as usize * per_query..range.end as usize * per_query]
+                    .fill(0);
+            }
+            C::WriteTimestamp { ref set, index } => {
+                let nanoseconds = self.start.elapsed().as_nanos() as u64;
+                set.data.lock()[index as usize * set.values_per_query] = nanoseconds;
+            }
+            C::CopyQueryResults {
+                ref set,
+                ref range,
+                ref dst,
+                offset,
+                stride,
+            } => {
+                let per_query = set.values_per_query;
+                let values = set.data.lock();
+                let mut dst = dst.data.lock();
+                for (i, query) in range.clone().enumerate() {
+                    let start = query as usize * per_query;
+                    let mut offset = (offset + i as wgt::BufferAddress * stride.get()) as usize;
+                    for value in values[start..start + per_query].iter() {
+                        dst[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+                        offset += 8;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn dispatch(
+        pipeline: &super::ComputePipeline,
+        buffers: &[super::BoundBuffer],
+        count: &super::DispatchCount,
+    ) -> Result<(), naga::interp::Error> {
+        let count = match *count {
+            super::DispatchCount::Direct(count) => count,
+            super::DispatchCount::Indirect { ref buffer, offset } => {
+                let data = buffer.data.lock();
+                let mut count = [0; 3];
+                for (i, value) in count.iter_mut().enumerate() {
+                    let start = offset as usize + i * 4;
+                    let bytes = &data[start..start + 4];
+                    *value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                }
+                count
+            }
+        };
+
+        // Bindings of the same buffer view the same memory, so each buffer
+        // is locked only once.
+        let mut locked: Vec<(&super::Buffer, _)> = Vec::new();
+        let mut views = Vec::with_capacity(buffers.len());
+        for bound in buffers {
+            let memory = match locked
+                .iter()
+                .position(|&(buffer, _)| Arc::ptr_eq(&buffer.data, &bound.buffer.data))
+            {
+                Some(memory) => memory,
+                None => {
+                    locked.push((&bound.buffer, bound.buffer.data.lock()));
+                    locked.len() - 1
+                }
+            };
+            views.push((
+                memory,
+                bound.offset as usize..(bound.offset + bound.size) as usize,
+            ));
+        }
+
+        let shader = &pipeline.shader;
+        let options = naga::interp::Options {
+            bounds_check_policies: pipeline.bounds_check_policies,
+            ..Default::default()
+        };
+        let interpreter = naga::interp::Interpreter::new(&shader.module, &shader.info, options)?;
+        let mut bindings = naga::interp::Bindings::new();
+        for &mut (_, ref mut data) in locked.iter_mut() {
+            bindings.memory(&mut data[..]);
+        }
+        for (bound, (memory, range)) in buffers.iter().zip(views) {
+            bindings.view(bound.group, bound.binding, memory, range);
+        }
+        interpreter.dispatch(&pipeline.entry_point, count, &mut bindings)?;
+        Ok(())
+    }
+}
+
+impl crate::Queue<Api> for super::Queue {
+    unsafe fn submit(
+        &self,
+        command_buffers: &[&super::CommandBuffer],
+        signal_fence: Option<(&mut super::Fence, crate::FenceValue)>,
+    ) -> DeviceResult<()> {
+        for cmd_buffer in command_buffers {
+            profiling::scope!("execute", cmd_buffer.label.as_deref().unwrap_or_default());
+            for command in cmd_buffer.commands.iter() {
+                self.execute(command)?;
+            }
+        }
+        if let Some((fence, value)) = signal_fence {
+            fence.value = value;
+        }
+        Ok(())
+    }
+
+    unsafe fn present(
+        &self,
+        _surface: &super::Surface,
+        _texture: super::Texture,
+    ) -> Result<(), crate::SurfaceError> {
+        Err(crate::SurfaceError::Lost)
+    }
+
+    unsafe fn get_timestamp_period(&self) -> f32 {
+        1.0
+    }
+}
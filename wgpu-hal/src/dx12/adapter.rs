@@ -168,6 +168,19 @@ impl super::Adapter {
             hr == 0 && features2.DepthBoundsTestSupported != 0
         };
 
+        let wave_ops_supported = {
+            let mut features1: d3d12_ty::D3D12_FEATURE_DATA_D3D12_OPTIONS1 =
+                unsafe { mem::zeroed() };
+            let hr = unsafe {
+                device.CheckFeatureSupport(
+                    d3d12_ty::D3D12_FEATURE_D3D12_OPTIONS1,
+                    &mut features1 as *mut _ as *mut _,
+                    mem::size_of::<d3d12_ty::D3D12_FEATURE_DATA_D3D12_OPTIONS1>() as _,
+                )
+            };
+            hr == 0 && features1.WaveOps != 0
+        };
+
         let casting_fully_typed_format_supported = {
             let mut features3: crate::dx12::types::D3D12_FEATURE_DATA_D3D12_OPTIONS3 =
                 unsafe { mem::zeroed() };
@@ -294,6 +307,14 @@ impl super::Adapter {
             bgra8unorm_storage_supported,
         );
 
+        // Wave intrinsics need shader model 6.0, which only DXC can compile.
+        features.set(
+            wgt::Features::SUBGROUP,
+            wave_ops_supported
+                && dxc_container.is_some()
+                && shader_model_support.HighestShaderModel >= d3d12_ty::D3D_SHADER_MODEL_6_0,
+        );
+
         // TODO: Determine if IPresentationManager is supported
         let presentation_timer = auxil::dxgi::time::PresentationTimer::new_dxgi();
 
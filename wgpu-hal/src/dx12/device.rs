@@ -207,31 +207,69 @@ impl super::Device {
 
         let stage_bit = crate::auxil::map_naga_stage(naga_stage);
         let module = &stage.module.naga.module;
-        //TODO: reuse the writer
-        let mut source = String::new();
-        let mut writer = hlsl::Writer::new(&mut source, &layout.naga_options);
-        let reflection_info = {
-            profiling::scope!("naga::back::hlsl::write");
-            writer
-                .write(module, &stage.module.naga.info)
-                .map_err(|e| crate::PipelineError::Linkage(stage_bit, format!("HLSL: {e:?}")))?
-        };
-
-        let full_stage = format!(
-            "{}_{}\0",
-            naga_stage.to_hlsl_str(),
-            layout.naga_options.shader_model.to_str()
-        );
-
         let ep_index = module
             .entry_points
             .iter()
             .position(|ep| ep.stage == naga_stage && ep.name == stage.entry_point)
             .ok_or(crate::PipelineError::EntryPoint(naga_stage))?;
 
-        let raw_ep = reflection_info.entry_point_names[ep_index]
-            .as_ref()
-            .map_err(|e| crate::PipelineError::Linkage(stage_bit, format!("{e}")))?;
+        let (source, raw_ep, line_map) = crate::auxil::code_cache::load_or_generate(
+            &stage.module.naga,
+            |key| {
+                key.write(b"hlsl")
+                    .write_debug(&layout.naga_options)
+                    .write(stage.entry_point.as_bytes())
+                    .write_debug(&naga_stage)
+                    .write_debug(
+                        &stage
+                            .module
+                            .naga
+                            .debug_source
+                            .as_ref()
+                            .map(|debug| &debug.file_name),
+                    );
+            },
+            |&(ref source, ref raw_ep, ref line_map): &(String, String, naga::back::LineMap)| {
+                crate::auxil::code_cache::EntryWriter::default()
+                    .str(source)
+                    .str(raw_ep)
+                    .line_map(line_map)
+                    .finish()
+            },
+            |entry| {
+                let mut reader = crate::auxil::code_cache::EntryReader::new(entry);
+                Some((
+                    reader.str()?.to_string(),
+                    reader.str()?.to_string(),
+                    reader.line_map()?,
+                ))
+            },
+            || -> Result<_, crate::PipelineError> {
+                //TODO: reuse the writer
+                let mut source = String::new();
+                let mut writer = hlsl::Writer::new(&mut source, &layout.naga_options);
+                if let Some(ref debug) = stage.module.naga.debug_source {
+                    writer.set_source_files(debug.source_files());
+                }
+                let reflection_info = {
+                    profiling::scope!("naga::back::hlsl::write");
+                    writer.write(module, &stage.module.naga.info).map_err(|e| {
+                        crate::PipelineError::Linkage(stage_bit, format!("HLSL: {e:?}"))
+                    })?
+                };
+                let raw_ep = reflection_info.entry_point_names[ep_index]
+                    .as_ref()
+                    .map_err(|e| crate::PipelineError::Linkage(stage_bit, format!("{e}")))?
+                    .clone();
+                Ok((source, raw_ep, reflection_info.line_map))
+            },
+        )?;
+
+        let full_stage = format!(
+            "{}_{}\0",
+            naga_stage.to_hlsl_str(),
+            layout.naga_options.shader_model.to_str()
+        );
 
         let source_name = stage
             .module
@@ -246,7 +284,7 @@ impl super::Device {
                 self,
                 &source,
                 source_name,
-                raw_ep,
+                &raw_ep,
                 stage_bit,
                 full_stage,
                 dxc_container,
@@ -269,7 +307,15 @@ impl super::Device {
             naga_stage,
             source
         );
-        result
+        result.map_err(
+            |error| match (error, stage.module.naga.debug_source.as_ref()) {
+                (crate::PipelineError::Linkage(stages, msg), Some(debug)) => {
+                    let msg = debug.map_compiler_message(&msg, source_name, &line_map);
+                    crate::PipelineError::Linkage(stages, msg)
+                }
+                (error, _) => error,
+            },
+        )
     }
 
     pub fn raw_device(&self) -> &d3d12::Device {
@@ -1246,7 +1292,11 @@ impl crate::Device<super::Api> for super::Device {
         desc: &crate::ShaderModuleDescriptor,
         shader: crate::ShaderInput,
     ) -> Result<super::ShaderModule, crate::ShaderError> {
-        let raw_name = desc.label.and_then(|label| ffi::CString::new(label).ok());
+        // Name the generated HLSL apart from the source that its `#line`
+        // directives point at, so compiler messages can tell them apart.
+        let raw_name = desc
+            .label
+            .and_then(|label| ffi::CString::new(format!("{label}.hlsl")).ok());
         match shader {
             crate::ShaderInput::Naga(naga) => Ok(super::ShaderModule { naga, raw_name }),
             crate::ShaderInput::SpirV(_) => {
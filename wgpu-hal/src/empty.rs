@@ -1,6 +1,18 @@
+/*!
+# Empty API internals.
+
+A backend that validates and tracks, but never executes anything. It is only
+instantiated when [`wgt::EmptyBackendOptions::enable`] is set, and then
+exposes a single adapter with the features and limits given there.
+
+Buffers that can be mapped are backed by host memory, so that their contents
+read back as whatever was last written through a mapping. Submitted work
+completes immediately, and doesn't touch any resource.
+!*/
+
 #![allow(unused_variables)]
 
-use std::ops::Range;
+use std::{cell::UnsafeCell, ops::Range, ptr::NonNull};
 
 #[derive(Clone, Debug)]
 pub struct Api;
@@ -10,25 +22,53 @@ pub struct Encoder;
 #[derive(Debug)]
 pub struct Resource;
 
+#[derive(Debug)]
+pub struct Instance {
+    options: wgt::EmptyBackendOptions,
+}
+
+#[derive(Debug)]
+pub struct Adapter {
+    features: wgt::Features,
+}
+
+#[derive(Debug)]
+pub struct Buffer {
+    /// Host memory for buffers that can be mapped.
+    ///
+    /// Mappings point into this until the buffer is destroyed, so it is never
+    /// reallocated, and is only accessed through those pointers.
+    data: Option<Box<[UnsafeCell<u8>]>>,
+}
+
+// SAFETY: `data` is only accessed through mappings, and wgpu-core doesn't let
+// mapped ranges be accessed from several threads at once.
+unsafe impl Sync for Buffer {}
+
+#[derive(Debug)]
+pub struct Fence {
+    value: crate::FenceValue,
+}
+
 type DeviceResult<T> = Result<T, crate::DeviceError>;
 
 impl crate::Api for Api {
-    type Instance = Context;
+    type Instance = Instance;
     type Surface = Context;
-    type Adapter = Context;
+    type Adapter = Adapter;
     type Device = Context;
 
     type Queue = Context;
     type CommandEncoder = Encoder;
     type CommandBuffer = Resource;
 
-    type Buffer = Resource;
+    type Buffer = Buffer;
     type Texture = Resource;
     type SurfaceTexture = Resource;
     type TextureView = Resource;
     type Sampler = Resource;
     type QuerySet = Resource;
-    type Fence = Resource;
+    type Fence = Fence;
 
     type BindGroupLayout = Resource;
     type BindGroup = Resource;
@@ -38,20 +78,54 @@ impl crate::Api for Api {
     type ComputePipeline = Resource;
 }
 
-impl crate::Instance<Api> for Context {
+impl crate::Instance<Api> for Instance {
     unsafe fn init(desc: &crate::InstanceDescriptor) -> Result<Self, crate::InstanceError> {
-        Ok(Context)
+        if !desc.empty_backend_options.enable {
+            return Err(crate::InstanceError::new(String::from(
+                "the empty backend was not enabled",
+            )));
+        }
+        Ok(Instance {
+            options: desc.empty_backend_options.clone(),
+        })
     }
     unsafe fn create_surface(
         &self,
         _display_handle: raw_window_handle::RawDisplayHandle,
         _window_handle: raw_window_handle::RawWindowHandle,
     ) -> Result<Context, crate::InstanceError> {
-        Ok(Context)
+        Err(crate::InstanceError::new(String::from(
+            "the empty backend doesn't support surfaces",
+        )))
     }
     unsafe fn destroy_surface(&self, surface: Context) {}
     unsafe fn enumerate_adapters(&self) -> Vec<crate::ExposedAdapter<Api>> {
-        Vec::new()
+        vec![crate::ExposedAdapter {
+            adapter: Adapter {
+                features: self.options.features,
+            },
+            info: wgt::AdapterInfo {
+                name: String::from("Empty"),
+                vendor: 0,
+                device: 0,
+                device_type: wgt::DeviceType::Cpu,
+                driver: String::new(),
+                driver_info: String::new(),
+                backend: wgt::Backend::Empty,
+            },
+            features: self.options.features,
+            capabilities: crate::Capabilities {
+                limits: self.options.limits.clone(),
+                alignments: crate::Alignments {
+                    buffer_copy_offset: wgt::BufferSize::new(4).unwrap(),
+                    buffer_copy_pitch: wgt::BufferSize::new(
+                        wgt::COPY_BYTES_PER_ROW_ALIGNMENT as wgt::BufferAddress,
+                    )
+                    .unwrap(),
+                },
+                downlevel: wgt::DownlevelCapabilities::default(),
+            },
+        }]
     }
 }
 
@@ -75,19 +149,57 @@ impl crate::Surface<Api> for Context {
     unsafe fn discard_texture(&self, texture: Resource) {}
 }
 
-impl crate::Adapter<Api> for Context {
+impl crate::Adapter<Api> for Adapter {
     unsafe fn open(
         &self,
         features: wgt::Features,
         _limits: &wgt::Limits,
     ) -> DeviceResult<crate::OpenDevice<Api>> {
-        Err(crate::DeviceError::Lost)
+        Ok(crate::OpenDevice {
+            device: Context,
+            queue: Context,
+        })
     }
     unsafe fn texture_format_capabilities(
         &self,
         format: wgt::TextureFormat,
     ) -> crate::TextureFormatCapabilities {
-        crate::TextureFormatCapabilities::empty()
+        use crate::TextureFormatCapabilities as Tfc;
+        use wgt::{TextureFormatFeatureFlags as Tfff, TextureUsages as Tu};
+
+        // Advertise exactly what WebGPU guarantees.
+        let wgt::TextureFormatFeatures {
+            allowed_usages,
+            flags,
+        } = format.guaranteed_format_features(self.features);
+
+        let mut caps = Tfc::empty();
+        caps.set(Tfc::COPY_SRC, allowed_usages.contains(Tu::COPY_SRC));
+        caps.set(Tfc::COPY_DST, allowed_usages.contains(Tu::COPY_DST));
+        caps.set(Tfc::SAMPLED, allowed_usages.contains(Tu::TEXTURE_BINDING));
+        caps.set(Tfc::SAMPLED_LINEAR, flags.contains(Tfff::FILTERABLE));
+        caps.set(Tfc::STORAGE, allowed_usages.contains(Tu::STORAGE_BINDING));
+        caps.set(
+            Tfc::STORAGE_READ_WRITE,
+            flags.contains(Tfff::STORAGE_READ_WRITE),
+        );
+        if allowed_usages.contains(Tu::RENDER_ATTACHMENT) {
+            caps |= if format.is_depth_stencil_format() {
+                Tfc::DEPTH_STENCIL_ATTACHMENT
+            } else {
+                Tfc::COLOR_ATTACHMENT
+            };
+        }
+        caps.set(Tfc::COLOR_ATTACHMENT_BLEND, flags.contains(Tfff::BLENDABLE));
+        caps.set(Tfc::MULTISAMPLE_X2, flags.contains(Tfff::MULTISAMPLE_X2));
+        caps.set(Tfc::MULTISAMPLE_X4, flags.contains(Tfff::MULTISAMPLE_X4));
+        caps.set(Tfc::MULTISAMPLE_X8, flags.contains(Tfff::MULTISAMPLE_X8));
+        caps.set(Tfc::MULTISAMPLE_X16, flags.contains(Tfff::MULTISAMPLE_X16));
+        caps.set(
+            Tfc::MULTISAMPLE_RESOLVE,
+            flags.contains(Tfff::MULTISAMPLE_RESOLVE),
+        );
+        caps
     }
 
     unsafe fn surface_capabilities(&self, surface: &Context) -> Option<crate::SurfaceCapabilities> {
@@ -103,8 +215,11 @@ impl crate::Queue<Api> for Context {
     unsafe fn submit(
         &self,
         command_buffers: &[&Resource],
-        signal_fence: Option<(&mut Resource, crate::FenceValue)>,
+        signal_fence: Option<(&mut Fence, crate::FenceValue)>,
     ) -> DeviceResult<()> {
+        if let Some((fence, value)) = signal_fence {
+            fence.value = value;
+        }
         Ok(())
     }
     unsafe fn present(
@@ -122,22 +237,40 @@ impl crate::Queue<Api> for Context {
 
 impl crate::Device<Api> for Context {
     unsafe fn exit(self, queue: Context) {}
-    unsafe fn create_buffer(&self, desc: &crate::BufferDescriptor) -> DeviceResult<Resource> {
-        Ok(Resource)
-    }
-    unsafe fn destroy_buffer(&self, buffer: Resource) {}
+    unsafe fn create_buffer(&self, desc: &crate::BufferDescriptor) -> DeviceResult<Buffer> {
+        let data = if desc
+            .usage
+            .intersects(crate::BufferUses::MAP_READ | crate::BufferUses::MAP_WRITE)
+        {
+            let size = usize::try_from(desc.size).map_err(|_| crate::DeviceError::OutOfMemory)?;
+            Some(
+                std::iter::repeat_with(|| UnsafeCell::new(0))
+                    .take(size)
+                    .collect(),
+            )
+        } else {
+            None
+        };
+        Ok(Buffer { data })
+    }
+    unsafe fn destroy_buffer(&self, buffer: Buffer) {}
     unsafe fn map_buffer(
         &self,
-        buffer: &Resource,
+        buffer: &Buffer,
         range: crate::MemoryRange,
     ) -> DeviceResult<crate::BufferMapping> {
-        Err(crate::DeviceError::Lost)
-    }
-    unsafe fn unmap_buffer(&self, buffer: &Resource) -> DeviceResult<()> {
+        let data = buffer.data.as_ref().ok_or(crate::DeviceError::Lost)?;
+        let slice = &data[range.start as usize..range.end as usize];
+        Ok(crate::BufferMapping {
+            ptr: NonNull::new(UnsafeCell::raw_get(slice.as_ptr())).unwrap_or(NonNull::dangling()),
+            is_coherent: true,
+        })
+    }
+    unsafe fn unmap_buffer(&self, buffer: &Buffer) -> DeviceResult<()> {
         Ok(())
     }
-    unsafe fn flush_mapped_ranges<I>(&self, buffer: &Resource, ranges: I) {}
-    unsafe fn invalidate_mapped_ranges<I>(&self, buffer: &Resource, ranges: I) {}
+    unsafe fn flush_mapped_ranges<I>(&self, buffer: &Buffer, ranges: I) {}
+    unsafe fn invalidate_mapped_ranges<I>(&self, buffer: &Buffer, ranges: I) {}
 
     unsafe fn create_texture(&self, desc: &crate::TextureDescriptor) -> DeviceResult<Resource> {
         Ok(Resource)
@@ -216,16 +349,16 @@ impl crate::Device<Api> for Context {
         Ok(Resource)
     }
     unsafe fn destroy_query_set(&self, set: Resource) {}
-    unsafe fn create_fence(&self) -> DeviceResult<Resource> {
-        Ok(Resource)
+    unsafe fn create_fence(&self) -> DeviceResult<Fence> {
+        Ok(Fence { value: 0 })
     }
-    unsafe fn destroy_fence(&self, fence: Resource) {}
-    unsafe fn get_fence_value(&self, fence: &Resource) -> DeviceResult<crate::FenceValue> {
-        Ok(0)
+    unsafe fn destroy_fence(&self, fence: Fence) {}
+    unsafe fn get_fence_value(&self, fence: &Fence) -> DeviceResult<crate::FenceValue> {
+        Ok(fence.value)
     }
     unsafe fn wait(
         &self,
-        fence: &Resource,
+        fence: &Fence,
         value: crate::FenceValue,
         timeout_ms: u32,
     ) -> DeviceResult<bool> {
@@ -260,9 +393,9 @@ impl crate::CommandEncoder<Api> for Encoder {
     {
     }
 
-    unsafe fn clear_buffer(&mut self, buffer: &Resource, range: crate::MemoryRange) {}
+    unsafe fn clear_buffer(&mut self, buffer: &Buffer, range: crate::MemoryRange) {}
 
-    unsafe fn copy_buffer_to_buffer<T>(&mut self, src: &Resource, dst: &Resource, regions: T) {}
+    unsafe fn copy_buffer_to_buffer<T>(&mut self, src: &Buffer, dst: &Buffer, regions: T) {}
 
     #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
     unsafe fn copy_external_image_to_texture<T>(
@@ -285,13 +418,13 @@ impl crate::CommandEncoder<Api> for Encoder {
     ) {
     }
 
-    unsafe fn copy_buffer_to_texture<T>(&mut self, src: &Resource, dst: &Resource, regions: T) {}
+    unsafe fn copy_buffer_to_texture<T>(&mut self, src: &Buffer, dst: &Resource, regions: T) {}
 
     unsafe fn copy_texture_to_buffer<T>(
         &mut self,
         src: &Resource,
         src_usage: crate::TextureUses,
-        dst: &Resource,
+        dst: &Buffer,
         regions: T,
     ) {
     }
@@ -304,7 +437,7 @@ impl crate::CommandEncoder<Api> for Encoder {
         &mut self,
         set: &Resource,
         range: Range<u32>,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
         stride: wgt::BufferSize,
     ) {
@@ -370,32 +503,32 @@ impl crate::CommandEncoder<Api> for Encoder {
     }
     unsafe fn draw_indirect(
         &mut self,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
         draw_count: u32,
     ) {
     }
     unsafe fn draw_indexed_indirect(
         &mut self,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
         draw_count: u32,
     ) {
     }
     unsafe fn draw_indirect_count(
         &mut self,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
-        count_buffer: &Resource,
+        count_buffer: &Buffer,
         count_offset: wgt::BufferAddress,
         max_count: u32,
     ) {
     }
     unsafe fn draw_indexed_indirect_count(
         &mut self,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
-        count_buffer: &Resource,
+        count_buffer: &Buffer,
         count_offset: wgt::BufferAddress,
         max_count: u32,
     ) {
@@ -409,5 +542,5 @@ impl crate::CommandEncoder<Api> for Encoder {
     unsafe fn set_compute_pipeline(&mut self, pipeline: &Resource) {}
 
     unsafe fn dispatch(&mut self, count: [u32; 3]) {}
-    unsafe fn dispatch_indirect(&mut self, buffer: &Resource, offset: wgt::BufferAddress) {}
+    unsafe fn dispatch_indirect(&mut self, buffer: &Buffer, offset: wgt::BufferAddress) {}
 }
@@ -474,6 +474,10 @@ impl super::Adapter {
             supported((3, 1), (4, 2)) || extensions.contains("GL_ARB_shader_image_load_store"),
         );
         features.set(wgt::Features::SHADER_UNUSED_VERTEX_OUTPUT, true);
+        features.set(
+            wgt::Features::SUBGROUP,
+            supported((3, 1), (4, 3)) && extensions.contains("GL_KHR_shader_subgroup"),
+        );
         if extensions.contains("GL_ARB_timer_query") {
             features.set(wgt::Features::TIMESTAMP_QUERY, true);
             features.set(wgt::Features::TIMESTAMP_QUERY_INSIDE_PASSES, true);
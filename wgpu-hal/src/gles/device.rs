@@ -9,7 +9,6 @@ use std::{
 };
 
 use arrayvec::ArrayVec;
-#[cfg(not(target_arch = "wasm32"))]
 use std::mem;
 use std::sync::atomic::Ordering;
 
@@ -107,6 +106,113 @@ impl CompilationContext<'_> {
     }
 }
 
+/// Encode generated GLSL, and what it reflects, as an entry of a [`crate::ShaderCodeCache`].
+fn encode_glsl(
+    &(ref output, ref reflection_info): &(String, naga::back::glsl::ReflectionInfo),
+) -> Vec<u8> {
+    let mut writer = crate::auxil::code_cache::EntryWriter::default();
+    writer.str(output);
+    writer.u32(reflection_info.texture_mapping.len() as u32);
+    for (name, mapping) in reflection_info.texture_mapping.iter() {
+        // Zero stands for no sampler.
+        let sampler = mapping
+            .sampler
+            .map_or(0, |sampler| sampler.index() as u32 + 1);
+        writer
+            .str(name)
+            .u32(mapping.texture.index() as u32)
+            .u32(sampler);
+    }
+    writer.u32(reflection_info.uniforms.len() as u32);
+    for (handle, name) in reflection_info.uniforms.iter() {
+        writer.u32(handle.index() as u32).str(name);
+    }
+    writer.u32(reflection_info.varying.len() as u32);
+    for (name, location) in reflection_info.varying.iter() {
+        writer.str(name).u32(location.location).u32(location.index);
+    }
+    writer.u32(reflection_info.push_constant_items.len() as u32);
+    for item in reflection_info.push_constant_items.iter() {
+        writer
+            .str(&item.access_path)
+            .u32(item.ty.index() as u32)
+            .u32(item.offset);
+    }
+    writer.line_map(&reflection_info.line_map);
+    writer.finish()
+}
+
+/// Decode an entry written by [`encode_glsl`], for a pipeline using `module`.
+fn decode_glsl(
+    module: &naga::Module,
+    entry: &[u8],
+) -> Option<(String, naga::back::glsl::ReflectionInfo)> {
+    use naga::back::glsl;
+
+    let globals = module
+        .global_variables
+        .iter()
+        .map(|(handle, _)| handle)
+        .collect::<Vec<_>>();
+    let types = module
+        .types
+        .iter()
+        .map(|(handle, _)| handle)
+        .collect::<Vec<_>>();
+
+    let mut reader = crate::auxil::code_cache::EntryReader::new(entry);
+    let output = reader.str()?.to_string();
+    let mut reflection_info = glsl::ReflectionInfo {
+        texture_mapping: Default::default(),
+        uniforms: Default::default(),
+        varying: Default::default(),
+        push_constant_items: Vec::new(),
+        line_map: naga::back::LineMap::default(),
+    };
+    for _ in 0..reader.u32()? {
+        let name = reader.str()?.to_string();
+        let texture = *globals.get(reader.u32()? as usize)?;
+        let sampler = match reader.u32()? {
+            0 => None,
+            index => Some(*globals.get(index as usize - 1)?),
+        };
+        reflection_info
+            .texture_mapping
+            .insert(name, glsl::TextureMapping { texture, sampler });
+    }
+    for _ in 0..reader.u32()? {
+        let handle = *globals.get(reader.u32()? as usize)?;
+        reflection_info
+            .uniforms
+            .insert(handle, reader.str()?.to_string());
+    }
+    for _ in 0..reader.u32()? {
+        let name = reader.str()?.to_string();
+        let location = glsl::VaryingLocation {
+            location: reader.u32()?,
+            index: reader.u32()?,
+        };
+        reflection_info.varying.insert(name, location);
+    }
+    for _ in 0..reader.u32()? {
+        let access_path = reader.str()?.to_string();
+        let ty = *types.get(reader.u32()? as usize)?;
+        let offset = reader.u32()?;
+        reflection_info
+            .push_constant_items
+            .push(glsl::PushConstantItem {
+                access_path,
+                ty,
+                offset,
+            });
+    }
+    reflection_info.line_map = reader.line_map()?;
+    if !reader.is_empty() {
+        return None;
+    }
+    Some((output, reflection_info))
+}
+
 impl super::Device {
     /// # Safety
     ///
@@ -246,27 +352,46 @@ impl super::Device {
             binding_array: BoundsCheckPolicy::Unchecked,
         };
 
-        let mut output = String::new();
-        let mut writer = glsl::Writer::new(
-            &mut output,
-            &shader.module,
-            &shader.info,
-            &context.layout.naga_options,
-            &pipeline_options,
-            policies,
-        )
-        .map_err(|e| {
-            let msg = format!("{e}");
-            crate::PipelineError::Linkage(map_naga_stage(naga_stage), msg)
-        })?;
-
-        let reflection_info = writer.write().map_err(|e| {
-            let msg = format!("{e}");
-            crate::PipelineError::Linkage(map_naga_stage(naga_stage), msg)
-        })?;
+        let (output, mut reflection_info) = crate::auxil::code_cache::load_or_generate(
+            shader,
+            |key| {
+                key.write(b"glsl")
+                    .write_debug(&context.layout.naga_options)
+                    .write_debug(&pipeline_options)
+                    .write_debug(&policies)
+                    .write_debug(&shader.debug_source.as_ref().map(|debug| &debug.file_name));
+            },
+            encode_glsl,
+            |entry| decode_glsl(&shader.module, entry),
+            || -> Result<_, crate::PipelineError> {
+                let mut output = String::new();
+                let mut writer = glsl::Writer::new(
+                    &mut output,
+                    &shader.module,
+                    &shader.info,
+                    &context.layout.naga_options,
+                    &pipeline_options,
+                    policies,
+                )
+                .map_err(|e| {
+                    let msg = format!("{e}");
+                    crate::PipelineError::Linkage(map_naga_stage(naga_stage), msg)
+                })?;
+                if let Some(ref debug) = shader.debug_source {
+                    writer.set_source_files(debug.source_files());
+                }
+
+                let reflection_info = writer.write().map_err(|e| {
+                    let msg = format!("{e}");
+                    crate::PipelineError::Linkage(map_naga_stage(naga_stage), msg)
+                })?;
+                Ok((output, reflection_info))
+            },
+        )?;
 
         log::debug!("Naga generated shader:\n{}", output);
 
+        let line_map = mem::take(&mut reflection_info.line_map);
         context.consume_reflection(
             gl,
             &shader.module,
@@ -277,6 +402,14 @@ impl super::Device {
         );
 
         unsafe { Self::compile_shader(gl, &output, naga_stage, stage.module.label.as_deref()) }
+            .map_err(|error| match (error, shader.debug_source.as_ref()) {
+                // GLSL calls the generated code source string 0.
+                (crate::PipelineError::Linkage(stages, msg), Some(debug)) => {
+                    let msg = debug.map_compiler_message(&msg, "0", &line_map);
+                    crate::PipelineError::Linkage(stages, msg)
+                }
+                (error, _) => error,
+            })
     }
 
     unsafe fn create_pipeline<'a>(
@@ -51,6 +51,9 @@
     clippy::pattern_type_mismatch,
 )]
 
+/// CPU API internals.
+#[cfg(feature = "cpu")]
+pub mod cpu;
 /// DirectX11 API internals.
 #[cfg(all(feature = "dx11", windows))]
 pub mod dx11;
@@ -71,6 +74,8 @@ pub mod vulkan;
 
 pub mod auxil;
 pub mod api {
+    #[cfg(feature = "cpu")]
+    pub use super::cpu::Api as Cpu;
     #[cfg(all(feature = "dx11", windows))]
     pub use super::dx11::Api as Dx11;
     #[cfg(all(feature = "dx12", windows))]
@@ -838,6 +843,8 @@ pub struct InstanceDescriptor<'a> {
     pub flags: wgt::InstanceFlags,
     pub dx12_shader_compiler: wgt::Dx12Compiler,
     pub gles_minor_version: wgt::Gles3MinorVersion,
+    pub empty_backend_options: wgt::EmptyBackendOptions,
+    pub cpu_backend_options: wgt::CpuBackendOptions,
 }
 
 #[derive(Clone, Debug)]
@@ -1107,6 +1114,21 @@ pub struct NagaShader {
     pub info: naga::valid::ModuleInfo,
     /// Source codes for debug
     pub debug_source: Option<DebugSource>,
+    /// Where to keep the code generated from this module, and the key of the
+    /// module in it. The key must identify the module, and the options it
+    /// was validated with.
+    pub code_cache: Option<(Arc<dyn ShaderCodeCache>, u128)>,
+}
+
+/// Storage for the code that backends generate from [`NagaShader`]s, which can
+/// outlive the process.
+///
+/// Backends make keys with [`auxil::code_cache::KeyHasher`], out of the module
+/// key and every option the code depends on, so entries are never checked
+/// against the options they were generated with.
+pub trait ShaderCodeCache: WasmNotSendSync + fmt::Debug {
+    fn load(&self, key: u128) -> Option<Vec<u8>>;
+    fn store(&self, key: u128, code: &[u8]);
 }
 
 // Custom implementation avoids the need to generate Debug impl code
@@ -1133,6 +1155,84 @@ pub struct ShaderModuleDescriptor<'a> {
 pub struct DebugSource {
     pub file_name: Cow<'static, str>,
     pub source_code: Cow<'static, str>,
+    /// The file id that the spans of the module use for this source.
+    pub file_id: Option<naga::FileId>,
+}
+
+impl DebugSource {
+    /// Describe this source to naga's text backends, so that they write
+    /// `#line` directives pointing back to it.
+    #[cfg(any(
+        feature = "dx11",
+        feature = "dx12",
+        feature = "gles",
+        feature = "metal"
+    ))]
+    pub fn source_files(&self) -> naga::back::SourceFiles {
+        let mut files = naga::back::SourceFiles::new();
+        files.add(self.file_id, self.file_name.as_ref(), &self.source_code);
+        files
+    }
+
+    /// Point the lines that a shader compiler's `message` reports against
+    /// the generated code, which it calls `output_name`, at this source.
+    ///
+    /// Lines are looked up in the `line_map` that the backend wrote along
+    /// with the code. Compilers that honor its `#line` directives already
+    /// report those lines against [`file_name`](Self::file_name), so
+    /// `output_name` must be a different name.
+    #[cfg(any(
+        feature = "dx11",
+        feature = "dx12",
+        feature = "gles",
+        feature = "metal"
+    ))]
+    pub fn map_compiler_message(
+        &self,
+        message: &str,
+        output_name: &str,
+        line_map: &naga::back::LineMap,
+    ) -> String {
+        if output_name.is_empty() {
+            return message.to_string();
+        }
+        let mut mapped = String::with_capacity(message.len());
+        let mut rest = message;
+        while let Some(index) = rest.find(output_name) {
+            mapped.push_str(&rest[..index]);
+            let starts_word = !mapped.ends_with(|c: char| c.is_alphanumeric() || c == '_');
+            let after = &rest[index + output_name.len()..];
+            // References look like `name:line` or `name(line`.
+            let digits = match after.strip_prefix(|c| c == ':' || c == '(') {
+                Some(tail) => {
+                    &tail[..tail
+                        .find(|c: char| !c.is_ascii_digit())
+                        .unwrap_or(tail.len())]
+                }
+                None => "",
+            };
+            let source_line = digits
+                .parse()
+                .ok()
+                .filter(|_| starts_word)
+                .and_then(|line| line_map.source_line(line))
+                .filter(|&(file_id, _)| file_id == self.file_id);
+            match source_line {
+                Some((_, line)) => {
+                    mapped.push_str(&self.file_name);
+                    mapped.push_str(&after[..1]);
+                    mapped.push_str(&line.to_string());
+                    rest = &after[1 + digits.len()..];
+                }
+                None => {
+                    mapped.push_str(output_name);
+                    rest = after;
+                }
+            }
+        }
+        mapped.push_str(rest);
+        mapped
+    }
 }
 
 /// Describes a programmable pipeline stage.
@@ -1422,3 +1522,37 @@ fn test_default_limits() {
     let limits = wgt::Limits::default();
     assert!(limits.max_bind_groups <= MAX_BIND_GROUPS as u32);
 }
+
+#[cfg(any(
+    feature = "dx11",
+    feature = "dx12",
+    feature = "gles",
+    feature = "metal"
+))]
+#[test]
+fn map_compiler_message() {
+    let debug = DebugSource {
+        file_name: Cow::Borrowed("main.wgsl"),
+        source_code: Cow::Borrowed(""),
+        file_id: Some(0),
+    };
+    let line_map = naga::back::LineMap {
+        mappings: vec![naga::back::LineMapping {
+            output_line: 5,
+            file_id: Some(0),
+            source_line: 2,
+        }],
+    };
+    assert_eq!(
+        debug.map_compiler_message(
+            "ERROR: 0:6: 'x' : undeclared\nERROR: 0:3: header\nERROR: 10:6: 0(7) : 1:6",
+            "0",
+            &line_map
+        ),
+        "ERROR: main.wgsl:3: 'x' : undeclared\nERROR: 0:3: header\nERROR: 10:6: main.wgsl(4) : 1:6"
+    );
+    assert_eq!(
+        debug.map_compiler_message("program_source:9:5: error", "program_source", &line_map),
+        "main.wgsl:6:5: error"
+    );
+}
@@ -789,6 +789,10 @@ impl super::PrivateCapabilities {
             supports_preserve_invariance: version.at_least((11, 0), (13, 0), os_is_mac),
             // Metal 2.2 on mac, 2.3 on iOS.
             supports_shader_primitive_index: version.at_least((10, 15), (14, 0), os_is_mac),
+            // SIMD-scoped reduction and permute functions.
+            supports_simd_scoped_operations: family_check
+                && (device.supports_family(MTLGPUFamily::Apple7)
+                    || device.supports_family(MTLGPUFamily::Mac2)),
             has_unified_memory: if version.at_least((10, 15), (13, 0), os_is_mac) {
                 Some(device.has_unified_memory())
             } else {
@@ -874,6 +878,10 @@ impl super::PrivateCapabilities {
 
         features.set(F::RG11B10UFLOAT_RENDERABLE, self.format_rg11b10_all);
         features.set(F::SHADER_UNUSED_VERTEX_OUTPUT, true);
+        features.set(
+            F::SUBGROUP,
+            self.msl_version >= MTLLanguageVersion::V2_1 && self.supports_simd_scoped_operations,
+        );
 
         features
     }
@@ -114,13 +114,60 @@ impl super::Device {
             },
         };
 
-        let (source, info) = naga::back::msl::write_string(
-            module,
-            &stage.module.naga.info,
-            &options,
-            &pipeline_options,
-        )
-        .map_err(|e| crate::PipelineError::Linkage(stage_bit, format!("MSL: {:?}", e)))?;
+        let ep_index = module
+            .entry_points
+            .iter()
+            .position(|ep| ep.stage == naga_stage && ep.name == stage.entry_point)
+            .ok_or(crate::PipelineError::EntryPoint(naga_stage))?;
+
+        let (source, ep_name, line_map) = crate::auxil::code_cache::load_or_generate(
+            &stage.module.naga,
+            |key| {
+                key.write(b"msl")
+                    .write_debug(&options)
+                    .write_debug(&pipeline_options)
+                    .write_debug(&naga_stage)
+                    .write_debug(
+                        &stage
+                            .module
+                            .naga
+                            .debug_source
+                            .as_ref()
+                            .map(|debug| &debug.file_name),
+                    );
+            },
+            |&(ref source, ref ep_name, ref line_map): &(String, String, naga::back::LineMap)| {
+                crate::auxil::code_cache::EntryWriter::default()
+                    .str(source)
+                    .str(ep_name)
+                    .line_map(line_map)
+                    .finish()
+            },
+            |entry| {
+                let mut reader = crate::auxil::code_cache::EntryReader::new(entry);
+                Some((
+                    reader.str()?.to_string(),
+                    reader.str()?.to_string(),
+                    reader.line_map()?,
+                ))
+            },
+            || -> Result<_, crate::PipelineError> {
+                let mut writer = naga::back::msl::Writer::new(String::new());
+                if let Some(ref debug) = stage.module.naga.debug_source {
+                    writer.set_source_files(debug.source_files());
+                }
+                let info = writer
+                    .write(module, &stage.module.naga.info, &options, &pipeline_options)
+                    .map_err(|e| {
+                        crate::PipelineError::Linkage(stage_bit, format!("MSL: {:?}", e))
+                    })?;
+                let ep_name = info.entry_point_names[ep_index]
+                    .as_ref()
+                    .map_err(|e| crate::PipelineError::Linkage(stage_bit, format!("{}", e)))?
+                    .clone();
+                Ok((writer.finish(), ep_name, info.line_map))
+            },
+        )?;
 
         log::debug!(
             "Naga generated shader for entry point '{}' and stage {:?}\n{}",
@@ -143,18 +190,17 @@ impl super::Device {
             .new_library_with_source(source.as_ref(), &options)
             .map_err(|err| {
                 log::warn!("Naga generated shader:\n{}", source);
+                // Metal calls source strings `program_source`.
+                let err = match stage.module.naga.debug_source {
+                    Some(ref debug) => {
+                        debug.map_compiler_message(&err, "program_source", &line_map)
+                    }
+                    None => err,
+                };
                 crate::PipelineError::Linkage(stage_bit, format!("Metal: {}", err))
             })?;
 
-        let ep_index = module
-            .entry_points
-            .iter()
-            .position(|ep| ep.stage == naga_stage && ep.name == stage.entry_point)
-            .ok_or(crate::PipelineError::EntryPoint(naga_stage))?;
         let ep = &module.entry_points[ep_index];
-        let ep_name = info.entry_point_names[ep_index]
-            .as_ref()
-            .map_err(|e| crate::PipelineError::Linkage(stage_bit, format!("{}", e)))?;
 
         let wg_size = metal::MTLSize {
             width: ep.workgroup_size[0] as _,
@@ -162,7 +208,7 @@ impl super::Device {
             depth: ep.workgroup_size[2] as _,
         };
 
-        let function = library.get_function(ep_name, None).map_err(|e| {
+        let function = library.get_function(&ep_name, None).map_err(|e| {
             log::error!("get_function: {:?}", e);
             crate::PipelineError::EntryPoint(naga_stage)
         })?;
@@ -261,6 +261,7 @@ struct PrivateCapabilities {
     supports_depth_clip_control: bool,
     supports_preserve_invariance: bool,
     supports_shader_primitive_index: bool,
+    supports_simd_scoped_operations: bool,
     has_unified_memory: Option<bool>,
     timestamp_query_support: TimestampQuerySupport,
 }
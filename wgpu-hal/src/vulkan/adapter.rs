@@ -552,6 +552,22 @@ impl PhysicalDeviceFeatures {
                 && !adapter_info.driver.contains("MoltenVK"),
         );
 
+        if let Some(ref subgroup) = caps.subgroup {
+            features.set(
+                F::SUBGROUP,
+                subgroup.supported_operations.contains(
+                    vk::SubgroupFeatureFlags::BASIC
+                        | vk::SubgroupFeatureFlags::VOTE
+                        | vk::SubgroupFeatureFlags::ARITHMETIC
+                        | vk::SubgroupFeatureFlags::BALLOT
+                        | vk::SubgroupFeatureFlags::SHUFFLE
+                        | vk::SubgroupFeatureFlags::SHUFFLE_RELATIVE,
+                ) && subgroup
+                    .supported_stages
+                    .contains(vk::ShaderStageFlags::COMPUTE | vk::ShaderStageFlags::FRAGMENT),
+            );
+        }
+
         (features, dl_flags)
     }
 
@@ -573,6 +589,7 @@ pub struct PhysicalDeviceCapabilities {
     maintenance_3: Option<vk::PhysicalDeviceMaintenance3Properties>,
     descriptor_indexing: Option<vk::PhysicalDeviceDescriptorIndexingPropertiesEXT>,
     driver: Option<vk::PhysicalDeviceDriverPropertiesKHR>,
+    subgroup: Option<vk::PhysicalDeviceSubgroupProperties>,
     /// The device API version.
     ///
     /// Which is the version of Vulkan supported for device-level functionality.
@@ -838,6 +855,14 @@ impl super::InstanceShared {
                     builder = builder.push_next(next);
                 }
 
+                // Subgroup properties are core in 1.1, and have no extension.
+                if capabilities.device_api_version >= vk::API_VERSION_1_1 {
+                    let next = capabilities
+                        .subgroup
+                        .insert(vk::PhysicalDeviceSubgroupProperties::default());
+                    builder = builder.push_next(next);
+                }
+
                 let mut properties2 = builder.build();
                 unsafe {
                     get_device_properties.get_physical_device_properties2(phd, &mut properties2);
@@ -1302,6 +1327,16 @@ impl super::Adapter {
             if features.contains(wgt::Features::BGRA8UNORM_STORAGE) {
                 capabilities.push(spv::Capability::StorageImageWriteWithoutFormat);
             }
+            if features.contains(wgt::Features::SUBGROUP) {
+                capabilities.extend([
+                    spv::Capability::GroupNonUniform,
+                    spv::Capability::GroupNonUniformVote,
+                    spv::Capability::GroupNonUniformArithmetic,
+                    spv::Capability::GroupNonUniformBallot,
+                    spv::Capability::GroupNonUniformShuffle,
+                    spv::Capability::GroupNonUniformShuffleRelative,
+                ]);
+            }
 
             let mut flags = spv::WriterFlags::empty();
             flags.set(
@@ -1320,7 +1355,13 @@ impl super::Adapter {
                 true, // could check `super::Workarounds::SEPARATE_ENTRY_POINTS`
             );
             spv::Options {
-                lang_version: (1, 0),
+                // Subgroup operations need SPIR-V 1.3, which `SUBGROUP`
+                // guarantees through its Vulkan 1.1 requirement.
+                lang_version: if features.contains(wgt::Features::SUBGROUP) {
+                    (1, 3)
+                } else {
+                    (1, 0)
+                },
                 flags,
                 capabilities: Some(capabilities.iter().cloned().collect()),
                 bounds_check_policies: naga::proc::BoundsCheckPolicies {
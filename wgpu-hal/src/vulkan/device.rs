@@ -753,16 +753,51 @@ impl super::Device {
                 } else {
                     &self.naga_options
                 };
-                let spv = {
-                    profiling::scope!("naga::spv::write_vec");
-                    naga::back::spv::write_vec(
-                        &naga_shader.module,
-                        &naga_shader.info,
-                        options,
-                        Some(&pipeline_options),
-                    )
-                }
-                .map_err(|e| crate::PipelineError::Linkage(stage_flags, format!("{e}")))?;
+                let spv = crate::auxil::code_cache::load_or_generate(
+                    naga_shader,
+                    |key| {
+                        // The capabilities are a hash set, so they are sorted first.
+                        let capabilities = options.capabilities.as_ref().map(|set| {
+                            let mut capabilities =
+                                set.iter().map(|&c| c as u32).collect::<Vec<_>>();
+                            capabilities.sort_unstable();
+                            capabilities
+                        });
+                        key.write(b"spv")
+                            .write_debug(&options.lang_version)
+                            .write_debug(&options.flags)
+                            .write_debug(&options.binding_map)
+                            .write_debug(&capabilities)
+                            .write_debug(&options.bounds_check_policies)
+                            .write_debug(&options.zero_initialize_workgroup_memory)
+                            .write_debug(&options.debug_info.as_ref().map(|debug| debug.file_name))
+                            .write_debug(&pipeline_options);
+                    },
+                    |spv: &Vec<u32>| spv.iter().flat_map(|word| word.to_le_bytes()).collect(),
+                    |bytes| {
+                        if bytes.len() % 4 != 0 {
+                            return None;
+                        }
+                        Some(
+                            bytes
+                                .chunks_exact(4)
+                                .map(|word| {
+                                    u32::from_le_bytes([word[0], word[1], word[2], word[3]])
+                                })
+                                .collect(),
+                        )
+                    },
+                    || {
+                        profiling::scope!("naga::spv::write_vec");
+                        naga::back::spv::write_vec(
+                            &naga_shader.module,
+                            &naga_shader.info,
+                            options,
+                            Some(&pipeline_options),
+                        )
+                        .map_err(|e| crate::PipelineError::Linkage(stage_flags, format!("{e}")))
+                    },
+                )?;
                 self.create_shader_module_impl(&spv)?
             }
         };
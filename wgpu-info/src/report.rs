@@ -22,6 +22,10 @@ impl GpuReport {
             flags: wgpu::InstanceFlags::debugging().with_env(),
             dx12_shader_compiler: wgpu::util::dx12_shader_compiler_from_env().unwrap_or_default(),
             gles_minor_version: wgpu::util::gles_minor_version_from_env().unwrap_or_default(),
+            empty_backend_options: Default::default(),
+            cpu_backend_options: wgpu::CpuBackendOptions {
+                enable: cfg!(feature = "cpu"),
+            },
         });
         let adapters = instance.enumerate_adapters(wgpu::Backends::all());
 
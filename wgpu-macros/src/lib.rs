@@ -3,6 +3,8 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::Ident;
 
+mod wgslx;
+
 /// Creates a test that will run on all gpus on a given system.
 ///
 /// Apply this macro to a static variable with a type that can be converted to a `GpuTestConfiguration`.
@@ -42,3 +44,75 @@ pub fn gpu_test(_attr: TokenStream, item: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// Loads a wgslx shader at compile time, as a `wgpu::ShaderModuleDescriptor`.
+///
+/// The path, and the paths of any `@import`s, are relative to the crate root. The shader is
+/// parsed and validated when the crate is built, and any error is reported as a compile error
+/// pointing at the offending file and line. The descriptor holds the shader as plain WGSL, with
+/// its imports resolved.
+///
+/// The shader is validated with only the capabilities every device has. List any others the
+/// target device supports after the path, by their `naga::valid::Capabilities` names:
+/// `include_wgslx!("shaders/sky.wgsl", capabilities = [FLOAT64, SUBGROUP])`.
+#[proc_macro]
+pub fn include_wgslx(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as wgslx::Input);
+    let path = &input.path;
+    let shader = match wgslx::load(&input) {
+        Ok(shader) => shader,
+        Err(error) => return error.into(),
+    };
+
+    let wgsl = match naga::back::wgsl::write_string(
+        &shader.module,
+        &shader.info,
+        naga::back::wgsl::WriterFlags::empty(),
+    ) {
+        Ok(wgsl) => wgsl,
+        Err(error) => return wgslx::compile_error(path, &error.to_string()).into(),
+    };
+    let track_files = shader.track_files();
+
+    quote! {
+        {
+            #track_files
+            ::wgpu::ShaderModuleDescriptor {
+                label: Some(#path),
+                source: ::wgpu::ShaderSource::Wgsl(::std::borrow::Cow::Borrowed(#wgsl)),
+            }
+        }
+    }
+    .into()
+}
+
+/// Generates Rust definitions for the host-shareable types and bindings of a wgslx shader.
+///
+/// The shader is loaded as by [`include_wgslx!`], and takes the same arguments. This expands to
+/// items: a `#[repr(C)]` struct deriving `bytemuck::Pod` for every host-shareable struct, and
+/// `_GROUP` / `_BINDING` constants for every resource, as written by `naga::back::rust`.
+#[proc_macro]
+pub fn include_wgslx_types(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as wgslx::Input);
+    let path = &input.path;
+    let shader = match wgslx::load(&input) {
+        Ok(shader) => shader,
+        Err(error) => return error.into(),
+    };
+
+    let items = match naga::back::rust::write_string(&shader.module, &shader.info) {
+        Ok(items) => items,
+        Err(error) => return wgslx::compile_error(path, &error.to_string()).into(),
+    };
+    let items: proc_macro2::TokenStream = match items.parse() {
+        Ok(items) => items,
+        Err(error) => return wgslx::compile_error(path, &error.to_string()).into(),
+    };
+    let track_files = shader.track_files();
+
+    quote! {
+        #track_files
+        #items
+    }
+    .into()
+}
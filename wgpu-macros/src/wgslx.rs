@@ -0,0 +1,164 @@
+//! Compile-time loading of wgslx shaders, for `include_wgslx!` and `include_wgslx_types!`.
+
+use std::path::PathBuf;
+
+use naga::front::wgsl::source_provider::{FileProvider, SourceProvider};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Ident, LitStr, Token,
+};
+
+/// The arguments of `include_wgslx!` and `include_wgslx_types!`: a path,
+/// optionally followed by `capabilities = [...]`.
+pub struct Input {
+    pub path: LitStr,
+    /// The capabilities the shader is validated with.
+    pub capabilities: naga::valid::Capabilities,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        // Validate against what every device supports, unless the caller
+        // names the capabilities their device has.
+        let mut capabilities = naga::valid::Capabilities::default();
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let keyword: Ident = input.parse()?;
+            if keyword != "capabilities" {
+                return Err(syn::Error::new(keyword.span(), "expected `capabilities`"));
+            }
+            input.parse::<Token![=]>()?;
+            let content;
+            syn::bracketed!(content in input);
+            let names = content.parse_terminated(Ident::parse, Token![,])?;
+            for name in names {
+                capabilities |= naga::valid::Capabilities::from_name(&name.to_string())
+                    .ok_or_else(|| {
+                        syn::Error::new(name.span(), format!("unknown capability `{name}`"))
+                    })?;
+            }
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Self { path, capabilities })
+    }
+}
+
+/// A shader that parsed and validated successfully.
+pub struct Shader {
+    pub module: naga::Module,
+    pub info: naga::valid::ModuleInfo,
+    /// Absolute paths of the root file and every file it imports.
+    pub paths: Vec<PathBuf>,
+}
+
+impl Shader {
+    /// `include_str!` every source file, so that cargo rebuilds the crate
+    /// when one of them changes.
+    pub fn track_files(&self) -> TokenStream {
+        let paths = self.paths.iter().map(|path| path.to_string_lossy());
+        quote! {
+            #(const _: &str = include_str!(#paths);)*
+        }
+    }
+}
+
+/// Parse and validate the shader at `input.path`, relative to the crate root.
+///
+/// On failure, return a `compile_error!` invocation describing the problem.
+pub fn load(input: &Input) -> Result<Shader, TokenStream> {
+    let path = &input.path;
+    let root = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .ok_or_else(|| compile_error(path, "CARGO_MANIFEST_DIR is not set"))?;
+    let provider = FileProvider::new(root);
+
+    let id = provider
+        .visit(path.value())
+        .ok_or_else(|| compile_error(path, &format!("Unable to read `{}`", path.value())))?;
+
+    let module = naga::front::wgsl::parse_module(&provider, id)
+        .map_err(|error| compile_error(path, &error.emit_to_string_with_provider(&provider)))?;
+
+    let info = naga::valid::Validator::new(naga::valid::ValidationFlags::all(), input.capabilities)
+        .validate(&module)
+        .map_err(|error| compile_error(path, &error.emit_to_string_with_provider(&provider)))?;
+
+    Ok(Shader {
+        module,
+        info,
+        paths: provider.paths(),
+    })
+}
+
+pub fn compile_error(path: &LitStr, message: &str) -> TokenStream {
+    // Rendered diagnostics start with their own `error: `, which rustc adds as well.
+    let message = message.trim_start_matches("error: ").trim_end();
+    quote::quote_spanned! {path.span()=>
+        compile_error!(#message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /// Load a shader, given the macro's arguments.
+    fn load(args: &str) -> Result<super::Shader, String> {
+        let input: super::Input = syn::parse_str(args).unwrap();
+        super::load(&input).map_err(|error| error.to_string())
+    }
+
+    #[test]
+    fn load_with_import() {
+        let shader = match load(r#""tests/shaders/lit.wgsl""#) {
+            Ok(shader) => shader,
+            Err(error) => panic!("{error}"),
+        };
+        let names: Vec<_> = shader
+            .paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, ["lit.wgsl", "light.wgsl"]);
+        assert!(shader.track_files().to_string().contains("include_str ! ("));
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = load(r#""tests/shaders/parse_error.wgsl""#).err().unwrap();
+        assert!(error.starts_with("compile_error ! ("), "{error}");
+        assert!(error.contains("parse_error.wgsl:5:"), "{error}");
+    }
+
+    #[test]
+    fn missing_file() {
+        let error = load(r#""tests/shaders/no_such_file.wgsl""#).err().unwrap();
+        assert!(error.starts_with("compile_error ! ("), "{error}");
+        assert!(error.contains("Unable to read"), "{error}");
+    }
+
+    #[test]
+    fn missing_import() {
+        let error = load(r#""tests/shaders/missing_import.wgsl""#)
+            .err()
+            .unwrap();
+        assert!(error.starts_with("compile_error ! ("), "{error}");
+        assert!(error.contains("no_such_file.wgsl"), "{error}");
+    }
+
+    #[test]
+    fn capabilities() {
+        let error = load(r#""tests/shaders/float64.wgsl""#).err().unwrap();
+        assert!(error.starts_with("compile_error ! ("), "{error}");
+        assert!(error.contains("FLOAT64"), "{error}");
+
+        if let Err(error) = load(r#""tests/shaders/float64.wgsl", capabilities = [FLOAT64]"#) {
+            panic!("{error}");
+        }
+
+        let error = syn::parse_str::<super::Input>(r#""a.wgsl", capabilities = [FLOAT128]"#)
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "unknown capability `FLOAT128`");
+    }
+}
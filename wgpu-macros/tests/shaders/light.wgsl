@@ -0,0 +1,9 @@
+struct Light {
+    position: vec3<f32>,
+    intensity: f32,
+}
+
+fn attenuation(light: Light, point: vec3<f32>) -> f32 {
+    let d = distance(light.position, point);
+    return light.intensity / (1.0 + d * d);
+}
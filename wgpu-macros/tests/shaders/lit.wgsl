@@ -0,0 +1,12 @@
+@import "tests/shaders/light.wgsl";
+
+@group(0) @binding(0) var<storage, read> lights: array<Light>;
+
+@fragment
+fn main(@location(0) position: vec3<f32>) -> @location(0) vec4<f32> {
+    var total = 0.0;
+    for (var i = 0u; i < arrayLength(&lights); i++) {
+        total += attenuation(lights[i], position);
+    }
+    return vec4(vec3(total), 1.0);
+}
@@ -0,0 +1 @@
+@import "tests/shaders/no_such_file.wgsl";
@@ -0,0 +1,5 @@
+@import "tests/shaders/light.wgsl";
+
+fn brightest(a: Light, b: Light) -> Light {
+    return select(a, b, b.intensity > a.intensity)
+}
@@ -108,6 +108,8 @@ pub enum Backend {
     Gl = 5,
     /// WebGPU in the browser
     BrowserWebGpu = 6,
+    /// Software implementation that runs on the CPU
+    Cpu = 7,
 }
 
 impl Backend {
@@ -121,6 +123,7 @@ impl Backend {
             Backend::Dx11 => "dx11",
             Backend::Gl => "gl",
             Backend::BrowserWebGpu => "webgpu",
+            Backend::Cpu => "cpu",
         }
     }
 }
@@ -162,6 +165,14 @@ bitflags::bitflags! {
         const DX11 = 1 << Backend::Dx11 as u32;
         /// Supported when targeting the web through webassembly
         const BROWSER_WEBGPU = 1 << Backend::BrowserWebGpu as u32;
+        /// Supported everywhere, with the `empty` feature, when enabled by
+        /// [`InstanceDescriptor::empty_backend_options`]. Validates and tracks
+        /// everything, but doesn't execute any commands.
+        const EMPTY = 1 << Backend::Empty as u32;
+        /// Supported everywhere, with the `cpu` feature, when enabled by
+        /// [`InstanceDescriptor::cpu_backend_options`]. Executes transfers and
+        /// compute dispatches on the CPU, but doesn't rasterize.
+        const CPU = 1 << Backend::Cpu as u32;
         /// All the apis that wgpu offers first tier of support for.
         ///
         /// Vulkan + Metal + DX12 + Browser WebGPU
@@ -780,7 +791,19 @@ bitflags::bitflags! {
         /// This is a native only feature.
         const TEXTURE_FORMAT_NV12 = 1 << 55;
 
-        // 55..59 available
+        /// Allows compute and fragment shaders to use subgroup operations,
+        /// built-ins and barriers.
+        ///
+        /// Supported platforms:
+        /// - Vulkan (1.1+)
+        /// - DX12 (shader model 6.0+)
+        /// - Metal (MSL 2.1+ on Apple7+/Mac2)
+        /// - OpenGL (with GL_KHR_shader_subgroup)
+        ///
+        /// This is a native only feature.
+        const SUBGROUP = 1 << 56;
+
+        // 57..59 available
 
         // Shader:
 
@@ -6780,6 +6803,40 @@ pub enum Gles3MinorVersion {
     Version2,
 }
 
+/// Options for the [`Backend::Empty`] backend.
+///
+/// The backend exposes a single adapter of type [`DeviceType::Cpu`], which
+/// runs all of the validation, resource tracking and lifetime management, but
+/// never executes any commands. Mapped buffers are backed by host memory, but
+/// their contents are not touched by submitted work.
+#[derive(Clone, Debug, Default)]
+pub struct EmptyBackendOptions {
+    /// Whether to expose the adapter.
+    ///
+    /// Since [`Backends::all`] includes [`Backends::EMPTY`], the adapter is
+    /// never exposed unless this is set, so that it isn't picked instead of
+    /// a real one.
+    pub enable: bool,
+    /// The features the adapter supports.
+    pub features: Features,
+    /// The limits the adapter supports.
+    pub limits: Limits,
+}
+
+/// Options for the [`Backend::Cpu`] backend.
+///
+/// The backend exposes a single adapter of type [`DeviceType::Cpu`], which
+/// executes transfers and compute dispatches on the CPU.
+#[derive(Clone, Debug, Default)]
+pub struct CpuBackendOptions {
+    /// Whether to expose the adapter.
+    ///
+    /// Since [`Backends::all`] includes [`Backends::CPU`], the adapter is
+    /// never exposed unless this is set, so that it isn't picked instead of
+    /// a real one.
+    pub enable: bool,
+}
+
 /// Options for creating an instance.
 #[derive(Debug)]
 pub struct InstanceDescriptor {
@@ -6791,6 +6848,10 @@ pub struct InstanceDescriptor {
     pub dx12_shader_compiler: Dx12Compiler,
     /// Which OpenGL ES 3 minor version to request.
     pub gles_minor_version: Gles3MinorVersion,
+    /// Options for the [`Backend::Empty`] backend.
+    pub empty_backend_options: EmptyBackendOptions,
+    /// Options for the [`Backend::Cpu`] backend.
+    pub cpu_backend_options: CpuBackendOptions,
 }
 
 impl Default for InstanceDescriptor {
@@ -6800,6 +6861,8 @@ impl Default for InstanceDescriptor {
             flags: InstanceFlags::default(),
             dx12_shader_compiler: Dx12Compiler::default(),
             gles_minor_version: Gles3MinorVersion::default(),
+            empty_backend_options: EmptyBackendOptions::default(),
+            cpu_backend_options: CpuBackendOptions::default(),
         }
     }
 }
@@ -1,10 +1,11 @@
 use crate::{
     context::{ObjectId, Unused},
     AdapterInfo, BindGroupDescriptor, BindGroupLayoutDescriptor, BindingResource, BufferBinding,
-    BufferDescriptor, CommandEncoderDescriptor, ComputePassDescriptor, ComputePipelineDescriptor,
-    DownlevelCapabilities, Features, Label, Limits, LoadOp, MapMode, Operations,
-    PipelineLayoutDescriptor, RenderBundleEncoderDescriptor, RenderPipelineDescriptor,
-    SamplerDescriptor, ShaderModuleDescriptor, ShaderModuleDescriptorSpirV, ShaderSource, StoreOp,
+    BufferDescriptor, CommandEncoderDescriptor, CommandScope, ComputePassDescriptor,
+    ComputePipelineDescriptor, CoreError, DownlevelCapabilities, ErrorCause, ErrorDetail,
+    ErrorKind, Features, Label, Limits, LoadOp, MapMode, Operations, PipelineLayoutDescriptor,
+    RenderBundleEncoderDescriptor, RenderPipelineDescriptor, ResourceLabel, SamplerDescriptor,
+    ShaderModuleDescriptor, ShaderModuleDescriptorSpirV, ShaderSource, ShaderSpan, StoreOp,
     SurfaceStatus, TextureDescriptor, TextureViewDescriptor, UncapturedErrorHandler,
 };
 
@@ -229,6 +230,22 @@ impl Context {
         self.0.generate_report()
     }
 
+    pub fn device_leak_report(&self, device: &Device) -> wgc::leak::LeakReport {
+        let global = &self.0;
+        match wgc::gfx_select!(device.id => global.device_leak_report(device.id)) {
+            Ok(report) => report,
+            Err(err) => self.handle_error_fatal(err, "Device::leak_report"),
+        }
+    }
+
+    pub fn device_memory_report(&self, device: &Device) -> wgc::memory::MemoryReport {
+        let global = &self.0;
+        match wgc::gfx_select!(device.id => global.device_memory_report(device.id)) {
+            Ok(report) => report,
+            Err(err) => self.handle_error_fatal(err, "Device::memory_report"),
+        }
+    }
+
     #[cfg(any(target_os = "ios", target_os = "macos"))]
     pub unsafe fn create_surface_from_core_animation_layer(
         &self,
@@ -311,6 +328,20 @@ impl Context {
         label_key: &'static str,
         label: Label<'_>,
         string: &'static str,
+    ) {
+        self.handle_error_in_scopes(sink_mutex, cause, label_key, label, string, Vec::new())
+    }
+
+    /// Like [`handle_error`](Self::handle_error), for errors of commands that
+    /// were recorded in `scopes`, outermost first.
+    fn handle_error_in_scopes(
+        &self,
+        sink_mutex: &Mutex<ErrorSinkRaw>,
+        cause: impl Error + WasmNotSendSync + 'static,
+        label_key: &'static str,
+        label: Label<'_>,
+        string: &'static str,
+        scopes: Vec<CommandScope>,
     ) {
         let error = wgc::error::ContextError {
             string,
@@ -325,6 +356,7 @@ impl Context {
                 source.downcast_ref::<wgc::device::DeviceError>()
             {
                 return sink.handle_error(crate::Error::OutOfMemory {
+                    detail: Box::new(self.error_detail(ErrorKind::OutOfMemory, &error, scopes)),
                     source: Box::new(error),
                 });
             }
@@ -334,6 +366,7 @@ impl Context {
         // Otherwise, it is a validation error
         sink.handle_error(crate::Error::Validation {
             description: self.format_error(&error),
+            detail: Box::new(self.error_detail(ErrorKind::Validation, &error, scopes)),
             source: Box::new(error),
         });
     }
@@ -374,6 +407,165 @@ impl Context {
 
         format!("Validation Error\n\nCaused by:\n{}", err_descs.join(""))
     }
+
+    fn error_detail(
+        &self,
+        kind: ErrorKind,
+        error: &wgc::error::ContextError,
+        mut scopes: Vec<CommandScope>,
+    ) -> ErrorDetail {
+        let error_kind = self.core_error(error.cause.as_ref(), &mut scopes);
+        ErrorDetail {
+            kind,
+            operation: error.string.to_string(),
+            label: (!error.label_key.is_empty()).then(|| ResourceLabel {
+                kind: error.label_key.to_string(),
+                label: error.label.clone(),
+            }),
+            scopes,
+            error: error_kind,
+        }
+    }
+
+    /// Classify `error` by its type in `wgpu-core`, adding the scope of the
+    /// command that failed to `scopes` for errors of passes and bundles.
+    fn core_error(
+        &self,
+        error: &(dyn Error + 'static),
+        scopes: &mut Vec<CommandScope>,
+    ) -> CoreError {
+        use wgc::{binding_model as bm, command as cmd, device, pipeline as pl, present, resource};
+
+        // Errors of passes and bundles only wrap the error of the command.
+        let inner_cause = |error: &(dyn Error + 'static)| match error.source() {
+            Some(inner) => self.error_cause(inner),
+            None => self.error_cause(error),
+        };
+        if let Some(e) = error.downcast_ref::<cmd::RenderPassError>() {
+            scopes.extend(self.pass_scope(e.scope));
+            return CoreError::RenderPass(inner_cause(error));
+        }
+        if let Some(e) = error.downcast_ref::<cmd::ComputePassError>() {
+            scopes.extend(self.pass_scope(e.scope));
+            return CoreError::ComputePass(inner_cause(error));
+        }
+        if let Some(e) = error.downcast_ref::<cmd::RenderBundleError>() {
+            scopes.push(CommandScope::RenderBundle);
+            scopes.extend(self.pass_scope(e.scope));
+            return CoreError::RenderBundle(inner_cause(error));
+        }
+        if let Some(e) = error.downcast_ref::<pl::CreateShaderModuleError>() {
+            return CoreError::CreateShaderModule {
+                cause: self.error_cause(error),
+                spans: shader_spans(e),
+            };
+        }
+
+        macro_rules! classify {
+            ($($variant:ident: $ty:ty,)*) => {
+                $(
+                    if error.is::<$ty>() {
+                        return CoreError::$variant(self.error_cause(error));
+                    }
+                )*
+            };
+        }
+        classify! {
+            Device: device::DeviceError,
+            CreateBuffer: resource::CreateBufferError,
+            BufferAccess: resource::BufferAccessError,
+            CreateTexture: resource::CreateTextureError,
+            CreateTextureView: resource::CreateTextureViewError,
+            CreateSampler: resource::CreateSamplerError,
+            CreateQuerySet: resource::CreateQuerySetError,
+            CreateBindGroupLayout: bm::CreateBindGroupLayoutError,
+            CreatePipelineLayout: bm::CreatePipelineLayoutError,
+            CreateBindGroup: bm::CreateBindGroupError,
+            GetBindGroupLayout: bm::GetBindGroupLayoutError,
+            CreateComputePipeline: pl::CreateComputePipelineError,
+            CreateRenderPipeline: pl::CreateRenderPipelineError,
+            CreateRenderBundle: cmd::CreateRenderBundleError,
+            CommandEncoder: cmd::CommandEncoderError,
+            Transfer: cmd::TransferError,
+            Clear: cmd::ClearError,
+            Query: cmd::QueryError,
+            QueueWrite: device::queue::QueueWriteError,
+            QueueSubmit: device::queue::QueueSubmitError,
+            ConfigureSurface: present::ConfigureSurfaceError,
+            Surface: present::SurfaceError,
+        }
+        CoreError::Other(self.error_cause(error))
+    }
+
+    fn error_cause(&self, error: &(dyn Error + 'static)) -> ErrorCause {
+        // The `Debug` output of the errors is derived, so it starts with the
+        // name of the variant, or of the type.
+        let debug = format!("{error:?}");
+        let variant_len = debug
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(debug.len());
+        ErrorCause {
+            variant: debug[..variant_len].to_string(),
+            message: error.to_string(),
+            labels: wgc::error::pretty_labels_any(self.global(), error)
+                .into_iter()
+                .map(|(kind, label)| ResourceLabel { kind, label })
+                .collect(),
+            source: error
+                .source()
+                .map(|source| Box::new(self.error_cause(source))),
+        }
+    }
+
+    fn pass_scope(&self, scope: wgc::command::PassErrorScope) -> Option<CommandScope> {
+        use wgc::command::PassErrorScope as Pes;
+
+        let global = &self.0;
+        Some(match scope {
+            Pes::Bundle | Pes::Pass(_) => return None,
+            Pes::SetBindGroup(id) => CommandScope::SetBindGroup {
+                bind_group: wgc::gfx_select!(id => global.bind_group_label(id)),
+            },
+            Pes::SetPipelineRender(id) => CommandScope::SetRenderPipeline {
+                pipeline: wgc::gfx_select!(id => global.render_pipeline_label(id)),
+            },
+            Pes::SetPipelineCompute(id) => CommandScope::SetComputePipeline {
+                pipeline: wgc::gfx_select!(id => global.compute_pipeline_label(id)),
+            },
+            Pes::SetPushConstant => CommandScope::SetPushConstant,
+            Pes::SetVertexBuffer(id) => CommandScope::SetVertexBuffer {
+                buffer: wgc::gfx_select!(id => global.buffer_label(id)),
+            },
+            Pes::SetIndexBuffer(id) => CommandScope::SetIndexBuffer {
+                buffer: wgc::gfx_select!(id => global.buffer_label(id)),
+            },
+            Pes::SetViewport => CommandScope::SetViewport,
+            Pes::SetScissorRect => CommandScope::SetScissorRect,
+            Pes::Draw {
+                indexed,
+                indirect,
+                pipeline,
+            } => CommandScope::Draw {
+                indexed,
+                indirect,
+                pipeline: pipeline
+                    .map(|id| wgc::gfx_select!(id => global.render_pipeline_label(id))),
+            },
+            Pes::Dispatch { indirect, pipeline } => CommandScope::Dispatch {
+                indirect,
+                pipeline: pipeline
+                    .map(|id| wgc::gfx_select!(id => global.compute_pipeline_label(id))),
+            },
+            Pes::QueryReset => CommandScope::QueryReset,
+            Pes::WriteTimestamp => CommandScope::WriteTimestamp,
+            Pes::BeginOcclusionQuery => CommandScope::BeginOcclusionQuery,
+            Pes::EndOcclusionQuery => CommandScope::EndOcclusionQuery,
+            Pes::BeginPipelineStatisticsQuery => CommandScope::BeginPipelineStatisticsQuery,
+            Pes::EndPipelineStatisticsQuery => CommandScope::EndPipelineStatisticsQuery,
+            Pes::ExecuteBundle => CommandScope::ExecuteBundle,
+            Pes::PopDebugGroup => CommandScope::PopDebugGroup,
+        })
+    }
 }
 
 fn map_buffer_copy_view(view: crate::ImageCopyBuffer<'_>) -> wgc::command::ImageCopyBuffer {
@@ -517,6 +709,36 @@ pub struct CommandEncoder {
     open: bool,
 }
 
+fn shader_span(source: &str, range: Option<Range<usize>>, message: &str) -> Option<ShaderSpan> {
+    let range = range.filter(|range| range.end <= source.len())?;
+    let before = source.get(..range.start)?;
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    Some(ShaderSpan {
+        start: range.start as u32,
+        end: range.end as u32,
+        line_number: before.matches('\n').count() as u32 + 1,
+        line_position: before[line_start..].chars().count() as u32 + 1,
+        message: message.to_string(),
+    })
+}
+
+fn shader_spans(error: &wgc::pipeline::CreateShaderModuleError) -> Vec<ShaderSpan> {
+    match *error {
+        #[cfg(feature = "wgsl")]
+        wgc::pipeline::CreateShaderModuleError::Parsing(ref e) => e
+            .inner
+            .labels()
+            .filter_map(|(span, message)| shader_span(&e.source, span.to_range(), message))
+            .collect(),
+        wgc::pipeline::CreateShaderModuleError::Validation(ref e) => e
+            .inner
+            .spans()
+            .filter_map(|&(span, ref message)| shader_span(&e.source, span.to_range(), message))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 impl crate::Context for Context {
     type AdapterId = wgc::id::AdapterId;
     type AdapterData = ();
@@ -1923,12 +2145,21 @@ impl crate::Context for Context {
             encoder => global.command_encoder_run_compute_pass(*encoder, pass_data)
         ) {
             let name = wgc::gfx_select!(encoder => global.command_buffer_label(*encoder));
-            self.handle_error(
+            let scopes = vec![
+                CommandScope::Encoder {
+                    label: name.clone(),
+                },
+                CommandScope::ComputePass {
+                    label: pass_data.label().unwrap_or_default().to_string(),
+                },
+            ];
+            self.handle_error_in_scopes(
                 &encoder_data.error_sink,
                 cause,
                 "encoder",
                 Some(&name),
                 "a ComputePass",
+                scopes,
             );
         }
     }
@@ -2007,12 +2238,21 @@ impl crate::Context for Context {
             wgc::gfx_select!(encoder => global.command_encoder_run_render_pass(*encoder, pass_data))
         {
             let name = wgc::gfx_select!(encoder => global.command_buffer_label(*encoder));
-            self.handle_error(
+            let scopes = vec![
+                CommandScope::Encoder {
+                    label: name.clone(),
+                },
+                CommandScope::RenderPass {
+                    label: pass_data.label().unwrap_or_default().to_string(),
+                },
+            ];
+            self.handle_error_in_scopes(
                 &encoder_data.error_sink,
                 cause,
                 "encoder",
                 Some(&name),
                 "a RenderPass",
+                scopes,
             );
         }
     }
@@ -113,13 +113,33 @@ impl fmt::Debug for Context {
 impl crate::Error {
     fn from_js(js_error: js_sys::Object) -> Self {
         let source = Box::<dyn std::error::Error + Send + Sync>::from("<WebGPU Error>");
+        // Browsers only give a message.
+        let detail = |kind, message| {
+            Box::new(crate::ErrorDetail {
+                kind,
+                operation: String::new(),
+                label: None,
+                scopes: Vec::new(),
+                error: crate::CoreError::Other(crate::ErrorCause {
+                    variant: String::new(),
+                    message,
+                    labels: Vec::new(),
+                    source: None,
+                }),
+            })
+        };
         if let Some(js_error) = js_error.dyn_ref::<web_sys::GpuValidationError>() {
+            let description = js_error.message();
             crate::Error::Validation {
                 source,
-                description: js_error.message(),
+                detail: detail(crate::ErrorKind::Validation, description.clone()),
+                description,
             }
         } else if js_error.has_type::<web_sys::GpuOutOfMemoryError>() {
-            crate::Error::OutOfMemory { source }
+            crate::Error::OutOfMemory {
+                source,
+                detail: detail(crate::ErrorKind::OutOfMemory, "Out of Memory".to_string()),
+            }
         } else {
             panic!("Unexpected error");
         }
@@ -0,0 +1,293 @@
+//! Structured descriptions of [`Error`]s.
+//!
+//! The message of an [`Error`] is meant for people. Tools that sort, count or
+//! report errors can use its [`ErrorDetail`] instead, which tells the call
+//! that failed, the resources involved, and what went wrong, following the
+//! errors of `wgpu-core`. With the `serde` feature, details can be serialized,
+//! for example to be sent along with telemetry.
+//!
+//! On the web, browsers only give a message, so details only have that.
+//!
+//! [`Error`]: crate::Error
+
+/// What kind of error an [`ErrorDetail`] describes.
+///
+/// These match the [`ErrorFilter`](crate::ErrorFilter)s that catch the
+/// errors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ErrorKind {
+    /// Out of memory.
+    OutOfMemory,
+    /// Invalid use of the API.
+    Validation,
+}
+
+/// A structured description of an [`Error`](crate::Error).
+///
+/// See the [`Error::detail`](crate::Error::detail) method.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorDetail {
+    /// The kind of error.
+    pub kind: ErrorKind,
+    /// The call that failed, such as `"Device::create_buffer"`, or empty if
+    /// it isn't known.
+    pub operation: String,
+    /// The resource that the call was made on, or was creating.
+    pub label: Option<ResourceLabel>,
+    /// The scopes of a command encoder that the error happened in, outermost
+    /// first. For example, a render pass and then the draw call in it.
+    pub scopes: Vec<CommandScope>,
+    /// What went wrong.
+    pub error: CoreError,
+}
+
+/// The label of a resource that an error refers to.
+///
+/// Labels are only kept in debug builds, so they are empty in release
+/// builds, and for resources without labels.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResourceLabel {
+    /// What the resource is to the error, such as `"buffer"` or
+    /// `"bind group"`.
+    pub kind: String,
+    /// The label.
+    pub label: String,
+}
+
+/// A scope of a command encoder that an error happened in.
+///
+/// The scopes of commands in passes follow the `PassErrorScope` of
+/// `wgpu-core`, along with the labels of the resources they name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum CommandScope {
+    /// A command encoder.
+    Encoder {
+        /// The label of the encoder.
+        label: String,
+    },
+    /// A render pass.
+    RenderPass {
+        /// The label of the pass.
+        label: String,
+    },
+    /// A compute pass.
+    ComputePass {
+        /// The label of the pass.
+        label: String,
+    },
+    /// A render bundle that is being encoded.
+    RenderBundle,
+    /// A `set_bind_group` command.
+    SetBindGroup {
+        /// The label of the bind group.
+        bind_group: String,
+    },
+    /// A `set_pipeline` command of a render pass or bundle.
+    SetRenderPipeline {
+        /// The label of the pipeline.
+        pipeline: String,
+    },
+    /// A `set_pipeline` command of a compute pass.
+    SetComputePipeline {
+        /// The label of the pipeline.
+        pipeline: String,
+    },
+    /// A `set_push_constants` command.
+    SetPushConstant,
+    /// A `set_vertex_buffer` command.
+    SetVertexBuffer {
+        /// The label of the buffer.
+        buffer: String,
+    },
+    /// A `set_index_buffer` command.
+    SetIndexBuffer {
+        /// The label of the buffer.
+        buffer: String,
+    },
+    /// A `set_viewport` command.
+    SetViewport,
+    /// A `set_scissor_rect` command.
+    SetScissorRect,
+    /// A draw command.
+    Draw {
+        /// Whether it draws indexed vertices.
+        indexed: bool,
+        /// Whether it takes its arguments from a buffer.
+        indirect: bool,
+        /// The label of the pipeline, if one was set.
+        pipeline: Option<String>,
+    },
+    /// A dispatch command.
+    Dispatch {
+        /// Whether it takes its arguments from a buffer.
+        indirect: bool,
+        /// The label of the pipeline, if one was set.
+        pipeline: Option<String>,
+    },
+    /// Resetting the queries of a render pass, after it ran.
+    QueryReset,
+    /// A `write_timestamp` command.
+    WriteTimestamp,
+    /// A `begin_occlusion_query` command.
+    BeginOcclusionQuery,
+    /// An `end_occlusion_query` command.
+    EndOcclusionQuery,
+    /// A `begin_pipeline_statistics_query` command.
+    BeginPipelineStatisticsQuery,
+    /// An `end_pipeline_statistics_query` command.
+    EndPipelineStatisticsQuery,
+    /// An `execute_bundles` command.
+    ExecuteBundle,
+    /// A `pop_debug_group` command.
+    PopDebugGroup,
+}
+
+/// What went wrong, by the type of the error in `wgpu-core`.
+///
+/// Most variants are named after the top-level error types of `wgpu-core`,
+/// such as `CreateBufferError`. Errors of passes and render bundles hold the
+/// error of the command that failed; the command itself is the last of the
+/// [`scopes`](ErrorDetail::scopes).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum CoreError {
+    /// A `DeviceError`, such as a lost device.
+    Device(ErrorCause),
+    /// A `CreateBufferError`.
+    CreateBuffer(ErrorCause),
+    /// A `BufferAccessError`, from mapping or unmapping a buffer.
+    BufferAccess(ErrorCause),
+    /// A `CreateTextureError`.
+    CreateTexture(ErrorCause),
+    /// A `CreateTextureViewError`.
+    CreateTextureView(ErrorCause),
+    /// A `CreateSamplerError`.
+    CreateSampler(ErrorCause),
+    /// A `CreateQuerySetError`.
+    CreateQuerySet(ErrorCause),
+    /// A `CreateBindGroupLayoutError`.
+    CreateBindGroupLayout(ErrorCause),
+    /// A `CreatePipelineLayoutError`.
+    CreatePipelineLayout(ErrorCause),
+    /// A `CreateBindGroupError`.
+    CreateBindGroup(ErrorCause),
+    /// A `GetBindGroupLayoutError`.
+    GetBindGroupLayout(ErrorCause),
+    /// A `CreateShaderModuleError`.
+    CreateShaderModule {
+        /// The error.
+        cause: ErrorCause,
+        /// The places in the source of the shader that the error points at.
+        spans: Vec<ShaderSpan>,
+    },
+    /// A `CreateComputePipelineError`.
+    CreateComputePipeline(ErrorCause),
+    /// A `CreateRenderPipelineError`.
+    CreateRenderPipeline(ErrorCause),
+    /// A `CreateRenderBundleError`.
+    CreateRenderBundle(ErrorCause),
+    /// A `CommandEncoderError`.
+    CommandEncoder(ErrorCause),
+    /// A `TransferError`, from a copy command.
+    Transfer(ErrorCause),
+    /// A `ClearError`.
+    Clear(ErrorCause),
+    /// A `QueryError`.
+    Query(ErrorCause),
+    /// The error of a command of a render pass.
+    RenderPass(ErrorCause),
+    /// The error of a command of a compute pass.
+    ComputePass(ErrorCause),
+    /// The error of a command of a render bundle.
+    RenderBundle(ErrorCause),
+    /// A `QueueWriteError`.
+    QueueWrite(ErrorCause),
+    /// A `QueueSubmitError`.
+    QueueSubmit(ErrorCause),
+    /// A `ConfigureSurfaceError`.
+    ConfigureSurface(ErrorCause),
+    /// A `SurfaceError`.
+    Surface(ErrorCause),
+    /// Any other error, including the errors of browsers.
+    Other(ErrorCause),
+}
+
+impl CoreError {
+    /// The cause of the error.
+    pub fn cause(&self) -> &ErrorCause {
+        match *self {
+            Self::Device(ref cause)
+            | Self::CreateBuffer(ref cause)
+            | Self::BufferAccess(ref cause)
+            | Self::CreateTexture(ref cause)
+            | Self::CreateTextureView(ref cause)
+            | Self::CreateSampler(ref cause)
+            | Self::CreateQuerySet(ref cause)
+            | Self::CreateBindGroupLayout(ref cause)
+            | Self::CreatePipelineLayout(ref cause)
+            | Self::CreateBindGroup(ref cause)
+            | Self::GetBindGroupLayout(ref cause)
+            | Self::CreateShaderModule { ref cause, .. }
+            | Self::CreateComputePipeline(ref cause)
+            | Self::CreateRenderPipeline(ref cause)
+            | Self::CreateRenderBundle(ref cause)
+            | Self::CommandEncoder(ref cause)
+            | Self::Transfer(ref cause)
+            | Self::Clear(ref cause)
+            | Self::Query(ref cause)
+            | Self::RenderPass(ref cause)
+            | Self::ComputePass(ref cause)
+            | Self::RenderBundle(ref cause)
+            | Self::QueueWrite(ref cause)
+            | Self::QueueSubmit(ref cause)
+            | Self::ConfigureSurface(ref cause)
+            | Self::Surface(ref cause)
+            | Self::Other(ref cause) => cause,
+        }
+    }
+}
+
+/// An error in a chain of errors, and the error that caused it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorCause {
+    /// The name of the variant of the `wgpu-core` error, such as
+    /// `"MissingFeatures"`, or of the type of errors that aren't enums.
+    /// Empty if it isn't known.
+    pub variant: String,
+    /// The message of this error, without those of its causes.
+    pub message: String,
+    /// The resources that this error refers to.
+    pub labels: Vec<ResourceLabel>,
+    /// The error that caused this one.
+    pub source: Option<Box<ErrorCause>>,
+}
+
+impl ErrorCause {
+    /// Iterate over this error and the errors that caused it.
+    pub fn chain(&self) -> impl Iterator<Item = &ErrorCause> {
+        std::iter::successors(Some(self), |cause| cause.source.as_deref())
+    }
+}
+
+/// A place in the source of a shader that an error points at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShaderSpan {
+    /// The byte offset of the start of the span in the source.
+    pub start: u32,
+    /// The byte offset of the end of the span in the source.
+    pub end: u32,
+    /// The line of the start of the span, starting at 1.
+    pub line_number: u32,
+    /// The column of the start of the span, in characters, starting at 1.
+    pub line_position: u32,
+    /// What the error says about this place.
+    pub message: String,
+}
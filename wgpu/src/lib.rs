@@ -8,6 +8,7 @@
 
 mod backend;
 mod context;
+mod error_detail;
 pub mod util;
 #[macro_use]
 mod macros;
@@ -25,6 +26,9 @@ use std::{
 };
 
 use context::{Context, DeviceRequest, DynContext, ObjectId};
+pub use error_detail::{
+    CommandScope, CoreError, ErrorCause, ErrorDetail, ErrorKind, ResourceLabel, ShaderSpan,
+};
 use parking_lot::Mutex;
 
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
@@ -32,20 +36,21 @@ pub use wgt::{
     AdapterInfo, AddressMode, AstcBlock, AstcChannel, Backend, Backends, BindGroupLayoutEntry,
     BindingType, BlendComponent, BlendFactor, BlendOperation, BlendState, BufferAddress,
     BufferBindingType, BufferSize, BufferUsages, Color, ColorTargetState, ColorWrites,
-    CommandBufferDescriptor, CompareFunction, CompositeAlphaMode, DepthBiasState,
-    DepthStencilState, DeviceLostReason, DeviceType, DownlevelCapabilities, DownlevelFlags,
-    Dx12Compiler, DynamicOffset, Extent3d, Face, Features, FilterMode, FrontFace,
-    Gles3MinorVersion, ImageDataLayout, ImageSubresourceRange, IndexFormat, InstanceDescriptor,
-    InstanceFlags, Limits, MultisampleState, Origin2d, Origin3d, PipelineStatisticsTypes,
-    PolygonMode, PowerPreference, PredefinedColorSpace, PresentMode, PresentationTimestamp,
-    PrimitiveState, PrimitiveTopology, PushConstantRange, QueryType, RenderBundleDepthStencil,
-    SamplerBindingType, SamplerBorderColor, ShaderLocation, ShaderModel, ShaderStages,
-    StencilFaceState, StencilOperation, StencilState, StorageTextureAccess, SurfaceCapabilities,
-    SurfaceStatus, TextureAspect, TextureDimension, TextureFormat, TextureFormatFeatureFlags,
-    TextureFormatFeatures, TextureSampleType, TextureUsages, TextureViewDimension, VertexAttribute,
-    VertexFormat, VertexStepMode, WasmNotSend, WasmNotSendSync, WasmNotSync, COPY_BUFFER_ALIGNMENT,
-    COPY_BYTES_PER_ROW_ALIGNMENT, MAP_ALIGNMENT, PUSH_CONSTANT_ALIGNMENT,
-    QUERY_RESOLVE_BUFFER_ALIGNMENT, QUERY_SET_MAX_QUERIES, QUERY_SIZE, VERTEX_STRIDE_ALIGNMENT,
+    CommandBufferDescriptor, CompareFunction, CompositeAlphaMode, CpuBackendOptions,
+    DepthBiasState, DepthStencilState, DeviceLostReason, DeviceType, DownlevelCapabilities,
+    DownlevelFlags, Dx12Compiler, DynamicOffset, EmptyBackendOptions, Extent3d, Face, Features,
+    FilterMode, FrontFace, Gles3MinorVersion, ImageDataLayout, ImageSubresourceRange, IndexFormat,
+    InstanceDescriptor, InstanceFlags, Limits, MultisampleState, Origin2d, Origin3d,
+    PipelineStatisticsTypes, PolygonMode, PowerPreference, PredefinedColorSpace, PresentMode,
+    PresentationTimestamp, PrimitiveState, PrimitiveTopology, PushConstantRange, QueryType,
+    RenderBundleDepthStencil, SamplerBindingType, SamplerBorderColor, ShaderLocation, ShaderModel,
+    ShaderStages, StencilFaceState, StencilOperation, StencilState, StorageTextureAccess,
+    SurfaceCapabilities, SurfaceStatus, TextureAspect, TextureDimension, TextureFormat,
+    TextureFormatFeatureFlags, TextureFormatFeatures, TextureSampleType, TextureUsages,
+    TextureViewDimension, VertexAttribute, VertexFormat, VertexStepMode, WasmNotSend,
+    WasmNotSendSync, WasmNotSync, COPY_BUFFER_ALIGNMENT, COPY_BYTES_PER_ROW_ALIGNMENT,
+    MAP_ALIGNMENT, PUSH_CONSTANT_ALIGNMENT, QUERY_RESOLVE_BUFFER_ALIGNMENT, QUERY_SET_MAX_QUERIES,
+    QUERY_SIZE, VERTEX_STRIDE_ALIGNMENT,
 };
 
 #[cfg(any(
@@ -2248,7 +2253,8 @@ impl Adapter {
     ///
     /// - `desc` - Description of the features and limits requested from the given device.
     /// - `trace_path` - Can be used for API call tracing, if that feature is
-    ///   enabled in `wgpu-core`.
+    ///   enabled in `wgpu-core`. The trace is written as RON, or in a binary format if
+    ///   the `WGPU_TRACE_FORMAT` environment variable is "binary" or "binary-compressed".
     ///
     /// # Panics
     ///
@@ -2870,6 +2876,45 @@ impl Device {
         }
     }
 
+    /// Lists the resources of this device that are still alive, with their
+    /// labels, sizes, and what keeps them alive.
+    ///
+    /// This includes resources that were dropped, but are still used by
+    /// other resources or by work on the GPU. In debug builds, the report is
+    /// logged when the device is dropped while resources are alive.
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub fn leak_report(&self) -> wgc::leak::LeakReport {
+        self.context
+            .as_any()
+            .downcast_ref::<crate::backend::Context>()
+            .unwrap()
+            .device_leak_report(self.data.as_ref().downcast_ref().unwrap())
+    }
+
+    /// Reports the memory that the buffers, textures and query sets of this
+    /// device take, by kind and by label prefix, along with their peaks.
+    ///
+    /// Sizes are estimated from the descriptors of the resources, so they
+    /// don't depend on the backend. The label prefix is the part of a label
+    /// before its first `/`, `:` or `.`, which lets you budget memory per
+    /// subsystem by labelling resources like `"shadows/cascade 0"`.
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub fn memory_report(&self) -> wgc::memory::MemoryReport {
+        self.context
+            .as_any()
+            .downcast_ref::<crate::backend::Context>()
+            .unwrap()
+            .device_memory_report(self.data.as_ref().downcast_ref().unwrap())
+    }
+
     /// Destroy this device.
     pub fn destroy(&self) {
         DynContext::device_destroy(&*self.context, &self.id, self.data.as_ref())
@@ -5391,6 +5436,8 @@ pub enum Error {
             )
         )))]
         source: Box<dyn error::Error + 'static>,
+        /// Structured description of the error.
+        detail: Box<ErrorDetail>,
     },
     /// Validation error, signifying a bug in code or data
     Validation {
@@ -5414,6 +5461,8 @@ pub enum Error {
         source: Box<dyn error::Error + 'static>,
         /// Description of the validation error.
         description: String,
+        /// Structured description of the error.
+        detail: Box<ErrorDetail>,
     },
 }
 #[cfg(any(
@@ -5425,10 +5474,21 @@ pub enum Error {
 ))]
 static_assertions::assert_impl_all!(Error: Send);
 
+impl Error {
+    /// A structured description of the error, for tools that would
+    /// otherwise have to parse its message.
+    pub fn detail(&self) -> &ErrorDetail {
+        match self {
+            Error::OutOfMemory { detail, .. } => detail,
+            Error::Validation { detail, .. } => detail,
+        }
+    }
+}
+
 impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
-            Error::OutOfMemory { source } => Some(source.as_ref()),
+            Error::OutOfMemory { source, .. } => Some(source.as_ref()),
             Error::Validation { source, .. } => Some(source.as_ref()),
         }
     }
@@ -0,0 +1,780 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::HashSet,
+    error, fmt,
+    future::Future,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+    task,
+};
+
+use naga::front::wgsl::{
+    source_provider::{self as sp, File, FileId, SourceProvider},
+    ParseError,
+};
+
+use crate::{ComputePipeline, Device, RenderPipeline, ShaderModule};
+
+/// Where a [`HotReload`] reads wgslx sources from, and learns that they changed.
+///
+/// Paths are the ones of the [`File`]s that providers return, apart from the
+/// path of a shader whose file couldn't be found, which is passed to
+/// [`watch`](Self::watch) as it was given to [`HotReload::add_shader`].
+pub trait ShaderWatcher {
+    /// Provides the sources, and renders errors in them.
+    type Provider: for<'a> SourceProvider<'a>;
+
+    /// A provider that reads the current sources. Each build gets a new one.
+    fn provider(&mut self) -> Self::Provider;
+
+    /// Report changes to `paths` from now on, instead of the paths given before.
+    fn watch(&mut self, paths: &[PathBuf]);
+
+    /// The watched paths that changed since the last call.
+    fn changes(&mut self) -> Vec<PathBuf>;
+}
+
+/// Identifies a shader in a [`HotReload`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShaderId(usize);
+
+/// Identifies a render pipeline in a [`HotReload`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RenderPipelineId(usize);
+
+/// Identifies a compute pipeline in a [`HotReload`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ComputePipelineId(usize);
+
+/// Something a [`HotReload`] builds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Reloadable {
+    /// A shader module.
+    Shader(ShaderId),
+    /// A render pipeline.
+    RenderPipeline(RenderPipelineId),
+    /// A compute pipeline.
+    ComputePipeline(ComputePipelineId),
+}
+
+/// What [`HotReload::update`] built again.
+#[derive(Clone, Debug, Default)]
+pub struct UpdateReport {
+    /// What built successfully.
+    pub rebuilt: Vec<Reloadable>,
+    /// What failed to build. These keep what they built last, and their
+    /// errors are available from [`HotReload::error`].
+    pub failed: Vec<Reloadable>,
+}
+
+impl UpdateReport {
+    /// Whether nothing was built.
+    pub fn is_empty(&self) -> bool {
+        self.rebuilt.is_empty() && self.failed.is_empty()
+    }
+}
+
+/// Why a [`HotReload`] couldn't build a shader module or pipeline.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum HotReloadError {
+    /// The file of the shader wasn't found.
+    Missing(PathBuf),
+    /// The shader, or a file it imports, failed to parse.
+    Parse {
+        /// The error the parser reported.
+        error: ParseError,
+        /// The error, rendered along with the source it points at.
+        message: String,
+    },
+    /// The device rejected the shader module or pipeline.
+    Device(crate::Error),
+}
+
+impl fmt::Display for HotReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(path) => write!(f, "Shader file {path:?} not found"),
+            Self::Parse { message, .. } => f.write_str(message),
+            Self::Device(error) => error.fmt(f),
+        }
+    }
+}
+
+impl error::Error for HotReloadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Missing(_) => None,
+            Self::Parse { error, .. } => Some(error),
+            Self::Device(error) => Some(error),
+        }
+    }
+}
+
+struct Shader {
+    path: PathBuf,
+    module: Option<ShaderModule>,
+    /// The files the last build read, and those of the last successful one.
+    files: Vec<PathBuf>,
+    error: Option<HotReloadError>,
+}
+
+type BuildPipeline<T> = Box<dyn FnMut(&Device, &[&ShaderModule]) -> T>;
+
+struct Pipeline<T> {
+    shaders: Vec<ShaderId>,
+    build: BuildPipeline<T>,
+    pipeline: Option<T>,
+    error: Option<HotReloadError>,
+}
+
+impl<T> Pipeline<T> {
+    /// Build the pipeline again, unless one of its shaders never built.
+    ///
+    /// Returns whether it was built, if it was attempted.
+    fn rebuild(&mut self, device: &Device, shaders: &[Shader]) -> Option<bool> {
+        let modules = self
+            .shaders
+            .iter()
+            .map(|id| shaders[id.0].module.as_ref())
+            .collect::<Option<Vec<_>>>()?;
+        device.push_error_scope(crate::ErrorFilter::Validation);
+        let pipeline = (self.build)(device, &modules);
+        Some(match pop_error_scope_now(device) {
+            Some(error) => {
+                self.error = Some(HotReloadError::Device(error));
+                false
+            }
+            None => {
+                self.pipeline = Some(pipeline);
+                self.error = None;
+                true
+            }
+        })
+    }
+}
+
+/// Rebuilds wgslx shader modules when any file they import changes, along with
+/// the render and compute pipelines that use them.
+///
+/// When a shader fails to build, its last good module, and the pipelines
+/// built from it, are kept, and the error is available from
+/// [`error`](Self::error). Call [`update`](Self::update) regularly, for example
+/// once per frame, to pick up changes.
+///
+/// Modules are validated by the device, so their errors are caught with an
+/// error scope. On the web, errors of error scopes aren't known right away,
+/// so they are left to the device's error handler.
+pub struct HotReload<W: ShaderWatcher> {
+    watcher: W,
+    shaders: Vec<Shader>,
+    render_pipelines: Vec<Pipeline<RenderPipeline>>,
+    compute_pipelines: Vec<Pipeline<ComputePipeline>>,
+}
+
+impl<W: ShaderWatcher> HotReload<W> {
+    /// Create an empty service, which reads sources from `watcher`.
+    pub fn new(watcher: W) -> Self {
+        Self {
+            watcher,
+            shaders: Vec::new(),
+            render_pipelines: Vec::new(),
+            compute_pipelines: Vec::new(),
+        }
+    }
+
+    /// The watcher the sources are read from.
+    pub fn watcher(&self) -> &W {
+        &self.watcher
+    }
+
+    /// The watcher the sources are read from.
+    pub fn watcher_mut(&mut self) -> &mut W {
+        &mut self.watcher
+    }
+
+    /// Build the shader at `path`, and watch the files it imports.
+    ///
+    /// The shader is added even if it fails to build, so that it builds once
+    /// its files are fixed.
+    pub fn add_shader(&mut self, device: &Device, path: impl Into<PathBuf>) -> ShaderId {
+        let id = ShaderId(self.shaders.len());
+        self.shaders.push(Shader {
+            path: path.into(),
+            module: None,
+            files: Vec::new(),
+            error: None,
+        });
+        self.rebuild_shader(device, id);
+        self.watch_files();
+        id
+    }
+
+    /// Build a render pipeline with `build`, which is passed the modules of
+    /// `shaders`, in that order, and build it again whenever one of them is
+    /// rebuilt.
+    ///
+    /// The pipeline is first built once all the shaders have built.
+    pub fn add_render_pipeline(
+        &mut self,
+        device: &Device,
+        shaders: &[ShaderId],
+        build: impl FnMut(&Device, &[&ShaderModule]) -> RenderPipeline + 'static,
+    ) -> RenderPipelineId {
+        let mut pipeline = Pipeline {
+            shaders: shaders.to_vec(),
+            build: Box::new(build),
+            pipeline: None,
+            error: None,
+        };
+        pipeline.rebuild(device, &self.shaders);
+        self.render_pipelines.push(pipeline);
+        RenderPipelineId(self.render_pipelines.len() - 1)
+    }
+
+    /// Like [`add_render_pipeline`](Self::add_render_pipeline), for a compute pipeline.
+    pub fn add_compute_pipeline(
+        &mut self,
+        device: &Device,
+        shaders: &[ShaderId],
+        build: impl FnMut(&Device, &[&ShaderModule]) -> ComputePipeline + 'static,
+    ) -> ComputePipelineId {
+        let mut pipeline = Pipeline {
+            shaders: shaders.to_vec(),
+            build: Box::new(build),
+            pipeline: None,
+            error: None,
+        };
+        pipeline.rebuild(device, &self.shaders);
+        self.compute_pipelines.push(pipeline);
+        ComputePipelineId(self.compute_pipelines.len() - 1)
+    }
+
+    /// The last module of the shader that built, if any did.
+    pub fn shader_module(&self, id: ShaderId) -> Option<&ShaderModule> {
+        self.shaders[id.0].module.as_ref()
+    }
+
+    /// The last render pipeline that built, if any did.
+    pub fn render_pipeline(&self, id: RenderPipelineId) -> Option<&RenderPipeline> {
+        self.render_pipelines[id.0].pipeline.as_ref()
+    }
+
+    /// The last compute pipeline that built, if any did.
+    pub fn compute_pipeline(&self, id: ComputePipelineId) -> Option<&ComputePipeline> {
+        self.compute_pipelines[id.0].pipeline.as_ref()
+    }
+
+    /// Why the last build of `item` failed, if it did.
+    pub fn error(&self, item: Reloadable) -> Option<&HotReloadError> {
+        match item {
+            Reloadable::Shader(id) => self.shaders[id.0].error.as_ref(),
+            Reloadable::RenderPipeline(id) => self.render_pipelines[id.0].error.as_ref(),
+            Reloadable::ComputePipeline(id) => self.compute_pipelines[id.0].error.as_ref(),
+        }
+    }
+
+    /// Rebuild the shaders whose files changed, and the pipelines using the
+    /// shaders that built.
+    pub fn update(&mut self, device: &Device) -> UpdateReport {
+        let mut report = UpdateReport::default();
+        let changes = self.watcher.changes().into_iter().collect::<HashSet<_>>();
+        if changes.is_empty() {
+            return report;
+        }
+
+        let mut rebuilt = HashSet::new();
+        for index in 0..self.shaders.len() {
+            let id = ShaderId(index);
+            if !self.shaders[index]
+                .files
+                .iter()
+                .any(|path| changes.contains(path))
+            {
+                continue;
+            }
+            if self.rebuild_shader(device, id) {
+                rebuilt.insert(id);
+                report.rebuilt.push(Reloadable::Shader(id));
+            } else {
+                report.failed.push(Reloadable::Shader(id));
+            }
+        }
+
+        let uses_rebuilt = |shaders: &[ShaderId]| shaders.iter().any(|id| rebuilt.contains(id));
+        for (index, pipeline) in self.render_pipelines.iter_mut().enumerate() {
+            if uses_rebuilt(&pipeline.shaders) {
+                let item = Reloadable::RenderPipeline(RenderPipelineId(index));
+                match pipeline.rebuild(device, &self.shaders) {
+                    Some(true) => report.rebuilt.push(item),
+                    Some(false) => report.failed.push(item),
+                    None => {}
+                }
+            }
+        }
+        for (index, pipeline) in self.compute_pipelines.iter_mut().enumerate() {
+            if uses_rebuilt(&pipeline.shaders) {
+                let item = Reloadable::ComputePipeline(ComputePipelineId(index));
+                match pipeline.rebuild(device, &self.shaders) {
+                    Some(true) => report.rebuilt.push(item),
+                    Some(false) => report.failed.push(item),
+                    None => {}
+                }
+            }
+        }
+
+        self.watch_files();
+        report
+    }
+
+    /// Build the shader again, returning whether it built.
+    fn rebuild_shader(&mut self, device: &Device, id: ShaderId) -> bool {
+        let shader = &mut self.shaders[id.0];
+        let provider = self.watcher.provider();
+        let tracked = Tracked::new(&provider);
+        let parsed = match tracked.visit(&shader.path) {
+            Some(file_id) => naga::front::wgsl::parse_module(&tracked, file_id).map_err(|error| {
+                HotReloadError::Parse {
+                    message: error.emit_to_string_with_provider(&tracked),
+                    error,
+                }
+            }),
+            None => Err(HotReloadError::Missing(shader.path.clone())),
+        };
+        let mut files = tracked.paths();
+        if files.is_empty() {
+            files.push(shader.path.clone());
+        }
+
+        let result = parsed.and_then(|module| {
+            device.push_error_scope(crate::ErrorFilter::Validation);
+            let module = device.create_shader_module(crate::ShaderModuleDescriptor {
+                label: Some(&shader.path.to_string_lossy()),
+                source: crate::ShaderSource::Naga(Cow::Owned(module)),
+            });
+            match pop_error_scope_now(device) {
+                Some(error) => Err(HotReloadError::Device(error)),
+                None => Ok(module),
+            }
+        });
+        match result {
+            Ok(module) => {
+                shader.module = Some(module);
+                shader.files = files;
+                shader.error = None;
+                true
+            }
+            Err(error) => {
+                // Keep watching the files of the last good build too, since
+                // fixing the failing file may bring their imports back.
+                for path in files {
+                    if !shader.files.contains(&path) {
+                        shader.files.push(path);
+                    }
+                }
+                shader.error = Some(error);
+                false
+            }
+        }
+    }
+
+    fn watch_files(&mut self) {
+        let mut paths = Vec::new();
+        let mut seen = HashSet::new();
+        for shader in self.shaders.iter() {
+            for path in shader.files.iter() {
+                if seen.insert(path) {
+                    paths.push(path.clone());
+                }
+            }
+        }
+        self.watcher.watch(&paths);
+    }
+}
+
+/// Pop an error scope, returning its error if the backend reports it right
+/// away, which native backends do.
+fn pop_error_scope_now(device: &Device) -> Option<crate::Error> {
+    struct NoopWake;
+    impl task::Wake for NoopWake {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let waker = task::Waker::from(Arc::new(NoopWake));
+    let mut future = Box::pin(device.pop_error_scope());
+    match future.as_mut().poll(&mut task::Context::from_waker(&waker)) {
+        task::Poll::Ready(error) => error,
+        task::Poll::Pending => None,
+    }
+}
+
+/// A source provider that records the files it hands out.
+struct Tracked<'a, P> {
+    inner: &'a P,
+    visited: RefCell<Vec<FileId>>,
+}
+
+impl<'a, P: SourceProvider<'a>> Tracked<'a, P> {
+    fn new(inner: &'a P) -> Self {
+        Self {
+            inner,
+            visited: RefCell::default(),
+        }
+    }
+
+    /// The paths of the files that were visited, without duplicates.
+    fn paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        for &id in self.visited.borrow().iter() {
+            if let Some(file) = self.inner.get(id) {
+                if !paths.iter().any(|path| path == file.path()) {
+                    paths.push(file.path().to_path_buf());
+                }
+            }
+        }
+        paths
+    }
+}
+
+impl<'a, P: SourceProvider<'a>> SourceProvider<'a> for Tracked<'a, P> {
+    fn visit(&self, path: impl AsRef<Path>) -> Option<FileId> {
+        let id = self.inner.visit(path)?;
+        self.visited.borrow_mut().push(id);
+        Some(id)
+    }
+
+    fn get(&self, id: FileId) -> Option<&File> {
+        self.inner.get(id)
+    }
+}
+
+impl<'a, P: SourceProvider<'a>> sp::Files<'a> for Tracked<'a, P> {
+    type FileId = FileId;
+    type Name = &'a str;
+    type Source = &'a str;
+
+    fn name(&'a self, id: FileId) -> Result<&'a str, sp::Error> {
+        let file = self.get(id).ok_or(sp::Error::FileMissing)?;
+        Ok(file.name())
+    }
+
+    fn source(&'a self, id: FileId) -> Result<&'a str, sp::Error> {
+        let file = self.get(id).ok_or(sp::Error::FileMissing)?;
+        Ok(file.source())
+    }
+
+    fn line_index(&'a self, id: FileId, byte_index: usize) -> Result<usize, sp::Error> {
+        let file = self.get(id).ok_or(sp::Error::FileMissing)?;
+        file.line_index((), byte_index)
+    }
+
+    fn line_range(&'a self, id: FileId, line_index: usize) -> Result<Range<usize>, sp::Error> {
+        let file = self.get(id).ok_or(sp::Error::FileMissing)?;
+        file.line_range((), line_index)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use files::FileWatcher;
+#[cfg(not(target_arch = "wasm32"))]
+pub use naga::front::wgsl::source_provider::FileProvider;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod files {
+    use std::{
+        collections::HashMap,
+        fs,
+        path::{Path, PathBuf},
+        time::SystemTime,
+    };
+
+    use naga::front::wgsl::source_provider::FileProvider;
+
+    /// Reads sources from disk, and notices changes by their modification times.
+    ///
+    /// Paths, including those of imports, are relative to a root directory.
+    #[derive(Debug)]
+    pub struct FileWatcher {
+        root: PathBuf,
+        /// The modification times of the watched files, if they exist.
+        modified: HashMap<PathBuf, Option<SystemTime>>,
+    }
+
+    impl FileWatcher {
+        /// Read sources from the directory `root`.
+        pub fn new(root: impl Into<PathBuf>) -> Self {
+            Self {
+                root: root.into(),
+                modified: HashMap::new(),
+            }
+        }
+    }
+
+    fn modified(root: &Path, path: &Path) -> Option<SystemTime> {
+        fs::metadata(root.join(path))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    impl super::ShaderWatcher for FileWatcher {
+        type Provider = FileProvider;
+
+        fn provider(&mut self) -> FileProvider {
+            FileProvider::new(self.root.clone())
+        }
+
+        fn watch(&mut self, paths: &[PathBuf]) {
+            let mut watched = HashMap::with_capacity(paths.len());
+            for path in paths {
+                let time = match self.modified.remove(path) {
+                    Some(time) => time,
+                    None => modified(&self.root, path),
+                };
+                watched.insert(path.clone(), time);
+            }
+            self.modified = watched;
+        }
+
+        fn changes(&mut self) -> Vec<PathBuf> {
+            let mut changes = Vec::new();
+            for (path, time) in self.modified.iter_mut() {
+                let now = modified(&self.root, path);
+                if now != *time {
+                    *time = now;
+                    changes.push(path.clone());
+                }
+            }
+            changes
+        }
+    }
+}
+
+#[cfg(all(test, feature = "empty", not(target_arch = "wasm32")))]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        collections::BTreeMap,
+        ops::Range,
+        path::{Path, PathBuf},
+        rc::Rc,
+    };
+
+    use naga::front::wgsl::source_provider::{self as sp, File, FileId, SourceProvider};
+
+    use super::{HotReload, HotReloadError, Reloadable, ShaderWatcher};
+
+    /// Sources kept in memory, shared by a [`Memory`] watcher and the test.
+    #[derive(Clone, Default)]
+    struct Sources {
+        files: Rc<RefCell<BTreeMap<PathBuf, String>>>,
+        changed: Rc<RefCell<Vec<PathBuf>>>,
+    }
+
+    impl Sources {
+        fn new(files: &[(&str, &str)]) -> Self {
+            let sources = Self::default();
+            for &(path, source) in files {
+                sources
+                    .files
+                    .borrow_mut()
+                    .insert(path.into(), source.to_string());
+            }
+            sources
+        }
+
+        fn write(&self, path: &str, source: &str) {
+            self.files
+                .borrow_mut()
+                .insert(path.into(), source.to_string());
+            self.changed.borrow_mut().push(path.into());
+        }
+    }
+
+    struct Memory {
+        sources: Sources,
+        watched: Vec<PathBuf>,
+    }
+
+    struct MemoryProvider(Vec<File>);
+
+    impl ShaderWatcher for Memory {
+        type Provider = MemoryProvider;
+
+        fn provider(&mut self) -> MemoryProvider {
+            MemoryProvider(
+                (1..)
+                    .zip(self.sources.files.borrow().iter())
+                    .map(|(id, (path, source))| File::new(id, path.clone(), source.clone()))
+                    .collect(),
+            )
+        }
+
+        fn watch(&mut self, paths: &[PathBuf]) {
+            self.watched = paths.to_vec();
+        }
+
+        fn changes(&mut self) -> Vec<PathBuf> {
+            let mut changed = self.sources.changed.borrow_mut();
+            changed.retain(|path| self.watched.contains(path));
+            changed.drain(..).collect()
+        }
+    }
+
+    impl SourceProvider<'_> for MemoryProvider {
+        fn visit(&self, path: impl AsRef<Path>) -> Option<FileId> {
+            let file = self.0.iter().find(|file| file.path() == path.as_ref())?;
+            Some(file.id())
+        }
+
+        fn get(&self, id: FileId) -> Option<&File> {
+            self.0.get((id as usize).checked_sub(1)?)
+        }
+    }
+
+    impl<'a> sp::Files<'a> for MemoryProvider {
+        type FileId = FileId;
+        type Name = &'a str;
+        type Source = &'a str;
+
+        fn name(&'a self, id: FileId) -> Result<&'a str, sp::Error> {
+            Ok(self.get(id).ok_or(sp::Error::FileMissing)?.name())
+        }
+
+        fn source(&'a self, id: FileId) -> Result<&'a str, sp::Error> {
+            Ok(self.get(id).ok_or(sp::Error::FileMissing)?.source())
+        }
+
+        fn line_index(&'a self, id: FileId, byte_index: usize) -> Result<usize, sp::Error> {
+            let file = self.get(id).ok_or(sp::Error::FileMissing)?;
+            file.line_index((), byte_index)
+        }
+
+        fn line_range(&'a self, id: FileId, line_index: usize) -> Result<Range<usize>, sp::Error> {
+            let file = self.get(id).ok_or(sp::Error::FileMissing)?;
+            file.line_range((), line_index)
+        }
+    }
+
+    fn empty_device() -> crate::Device {
+        let instance = crate::Instance::new(crate::InstanceDescriptor {
+            backends: crate::Backends::EMPTY,
+            empty_backend_options: wgt::EmptyBackendOptions {
+                enable: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let adapter = pollster::block_on(instance.request_adapter(&Default::default())).unwrap();
+        let (device, _queue) =
+            pollster::block_on(adapter.request_device(&Default::default(), None)).unwrap();
+        device
+    }
+
+    const MAIN: &str = "
+        @import \"common.wgsl\";
+
+        @compute @workgroup_size(1)
+        fn main() {
+            let x = helper();
+        }
+    ";
+
+    #[test]
+    fn rebuilds_importers_and_keeps_last_good() {
+        let device = empty_device();
+        let sources = Sources::new(&[
+            ("main.wgsl", MAIN),
+            ("common.wgsl", "fn helper() -> u32 { return 1u; }"),
+            ("unrelated.wgsl", "fn unused() {}"),
+        ]);
+        let mut hot_reload = HotReload::new(Memory {
+            sources: sources.clone(),
+            watched: Vec::new(),
+        });
+
+        let shader = hot_reload.add_shader(&device, "main.wgsl");
+        assert!(hot_reload.error(Reloadable::Shader(shader)).is_none());
+        let builds = Rc::new(Cell::new(0));
+        let pipeline = hot_reload.add_compute_pipeline(&device, &[shader], {
+            let builds = builds.clone();
+            move |device, modules| {
+                builds.set(builds.get() + 1);
+                device.create_compute_pipeline(&crate::ComputePipelineDescriptor {
+                    label: None,
+                    layout: None,
+                    module: modules[0],
+                    entry_point: "main",
+                })
+            }
+        });
+        assert_eq!(builds.get(), 1);
+        assert!(hot_reload.compute_pipeline(pipeline).is_some());
+        assert_eq!(
+            hot_reload.watcher().watched,
+            [PathBuf::from("main.wgsl"), PathBuf::from("common.wgsl")]
+        );
+
+        // Nothing was changed since the shader was added.
+        assert!(hot_reload.update(&device).is_empty());
+
+        // Breaking an import keeps the last module and pipeline.
+        sources.write("common.wgsl", "fn helper() -> u32 { return 1u }");
+        let report = hot_reload.update(&device);
+        assert!(report.rebuilt.is_empty());
+        assert_eq!(report.failed, [Reloadable::Shader(shader)]);
+        match hot_reload.error(Reloadable::Shader(shader)) {
+            Some(HotReloadError::Parse { message, .. }) => {
+                assert!(message.contains("common.wgsl"), "{message}")
+            }
+            other => panic!("Expected a parse error, got {other:?}"),
+        }
+        assert!(hot_reload.shader_module(shader).is_some());
+        assert!(hot_reload.compute_pipeline(pipeline).is_some());
+        assert_eq!(builds.get(), 1);
+
+        // Fixing it rebuilds both.
+        sources.write("common.wgsl", "fn helper() -> u32 { return 2u; }");
+        let report = hot_reload.update(&device);
+        assert_eq!(
+            report.rebuilt,
+            [
+                Reloadable::Shader(shader),
+                Reloadable::ComputePipeline(pipeline)
+            ]
+        );
+        assert!(hot_reload.error(Reloadable::Shader(shader)).is_none());
+        assert_eq!(builds.get(), 2);
+
+        // Files that no shader imports are ignored.
+        sources.write("unrelated.wgsl", "fn unused() -> u32 { return 0u; }");
+        assert!(hot_reload.update(&device).is_empty());
+        assert_eq!(builds.get(), 2);
+    }
+
+    #[test]
+    fn missing_shaders_build_once_written() {
+        let device = empty_device();
+        let sources = Sources::default();
+        let mut hot_reload = HotReload::new(Memory {
+            sources: sources.clone(),
+            watched: Vec::new(),
+        });
+
+        let shader = hot_reload.add_shader(&device, "main.wgsl");
+        assert!(matches!(
+            hot_reload.error(Reloadable::Shader(shader)),
+            Some(HotReloadError::Missing(_))
+        ));
+        assert!(hot_reload.shader_module(shader).is_none());
+
+        sources.write("common.wgsl", "fn helper() -> u32 { return 1u; }");
+        sources.write("main.wgsl", MAIN);
+        assert_eq!(
+            hot_reload.update(&device).rebuilt,
+            [Reloadable::Shader(shader)]
+        );
+        assert!(hot_reload.shader_module(shader).is_some());
+    }
+}
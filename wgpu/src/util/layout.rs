@@ -0,0 +1,65 @@
+use std::{error, fmt};
+
+use crate::BindGroupLayoutEntry;
+
+/// Derive the bind group layout entries used by the given entry points of a shader.
+///
+/// This is the layout a pipeline created with `layout: None` would derive for
+/// itself, but shared layouts can be built from it, so that bind groups work
+/// with every pipeline using these entry points:
+///
+/// - Each entry is visible to the stages of the entry points that use it.
+/// - Its binding type follows from the global's type and address space.
+///   Float textures are assumed to be filterable, and samplers to be
+///   filtering.
+/// - Buffers get a `min_binding_size` of their type's size, or of one element
+///   if it is a runtime-sized array.
+///
+/// The result has one list of entries per bind group, up to the last group
+/// that is used, with entries sorted by binding. Pass each list to
+/// [`Device::create_bind_group_layout`](crate::Device::create_bind_group_layout).
+///
+/// The module is validated first, with all capabilities enabled.
+pub fn derive_bind_group_layout_entries(
+    module: &naga::Module,
+    entry_points: &[(naga::ShaderStage, &str)],
+) -> Result<Vec<Vec<BindGroupLayoutEntry>>, DeriveLayoutError> {
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(module)
+    .map_err(DeriveLayoutError::Validation)?;
+
+    wgc::pipeline::derive_bind_group_layouts(module, &info, entry_points)
+        .map_err(DeriveLayoutError::Derive)
+}
+
+/// [`derive_bind_group_layout_entries`] failed.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum DeriveLayoutError {
+    /// The module is invalid.
+    Validation(naga::WithSpan<naga::valid::ValidationError>),
+    /// An entry point is missing, one of its resources has no binding type, or
+    /// the module's types can't be laid out.
+    Derive(wgc::pipeline::DeriveLayoutError),
+}
+
+impl fmt::Display for DeriveLayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation(error) => error.fmt(f),
+            Self::Derive(error) => error.fmt(f),
+        }
+    }
+}
+
+impl error::Error for DeriveLayoutError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Validation(error) => error.source(),
+            Self::Derive(error) => error.source(),
+        }
+    }
+}
@@ -6,7 +6,18 @@
 mod belt;
 mod device;
 mod encoder;
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
 mod init;
+#[cfg(all(
+    feature = "naga",
+    any(
+        not(target_arch = "wasm32"),
+        feature = "webgl",
+        target_os = "emscripten"
+    )
+))]
+mod layout;
 
 use std::sync::Arc;
 use std::{
@@ -18,7 +29,18 @@ use std::{
 pub use belt::StagingBelt;
 pub use device::{BufferInitDescriptor, DeviceExt, TextureDataOrder};
 pub use encoder::RenderEncoder;
+#[cfg(feature = "hot-reload")]
+pub use hot_reload::*;
 pub use init::*;
+#[cfg(all(
+    feature = "naga",
+    any(
+        not(target_arch = "wasm32"),
+        feature = "webgl",
+        target_os = "emscripten"
+    )
+))]
+pub use layout::{derive_bind_group_layout_entries, DeriveLayoutError};
 pub use wgt::{math::*, DispatchIndirectArgs, DrawIndexedIndirectArgs, DrawIndirectArgs};
 
 /// Treat the given byte slice as a SPIR-V module.
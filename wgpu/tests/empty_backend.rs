@@ -0,0 +1,89 @@
+//! Tests for the empty backend, which validates and tracks, but never
+//! executes anything.
+
+#![cfg(feature = "empty")]
+
+fn empty_device() -> (wgpu::Device, wgpu::Queue) {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::EMPTY,
+        empty_backend_options: wgpu::EmptyBackendOptions {
+            enable: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    let adapter = pollster::block_on(instance.request_adapter(&Default::default()))
+        .expect("the empty backend exposes no adapter");
+    assert_eq!(adapter.get_info().backend, wgpu::Backend::Empty);
+    pollster::block_on(adapter.request_device(&Default::default(), None)).unwrap()
+}
+
+const SHADER: &str = "
+    @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+    @compute @workgroup_size(64)
+    fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+        data[id.x] *= 2u;
+    }
+";
+
+#[test]
+fn validates_pipelines() {
+    let (device, _queue) = empty_device();
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+    });
+
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let _ = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: None,
+        layout: None,
+        module: &module,
+        entry_point: "main",
+    });
+    let error = pollster::block_on(device.pop_error_scope());
+    assert!(error.is_none(), "{error:?}");
+
+    // The layout is missing the shader's binding.
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &[],
+        push_constant_ranges: &[],
+    });
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let _ = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: None,
+        layout: Some(&layout),
+        module: &module,
+        entry_point: "main",
+    });
+    let error = pollster::block_on(device.pop_error_scope());
+    assert!(
+        matches!(error, Some(wgpu::Error::Validation { .. })),
+        "{error:?}"
+    );
+}
+
+#[test]
+fn mapped_buffers_keep_their_contents() {
+    let (device, _queue) = empty_device();
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 16,
+        usage: wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: true,
+    });
+    buffer
+        .slice(..)
+        .get_mapped_range_mut()
+        .copy_from_slice(&[7; 16]);
+    buffer.unmap();
+
+    buffer
+        .slice(8..16)
+        .map_async(wgpu::MapMode::Write, |result| result.unwrap());
+    device.poll(wgpu::Maintain::Wait);
+    assert_eq!(*buffer.slice(8..16).get_mapped_range_mut(), [7; 8]);
+    buffer.unmap();
+}
@@ -23,7 +23,7 @@ pub fn run_tests(mut args: Arguments) -> anyhow::Result<()> {
 
     xshell::cmd!(
         shell,
-        "cargo {llvm_cov_flags...} run --bin wgpu-info -- --json -o .gpuconfig"
+        "cargo {llvm_cov_flags...} run --bin wgpu-info --features cpu -- --json -o .gpuconfig"
     )
     .quiet()
     .run()